@@ -0,0 +1,297 @@
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "integrations")]
+use serde_json::json;
+use crate::{Result, SKMError};
+use crate::meta::config::NotificationConfig;
+#[cfg(feature = "integrations")]
+use crate::http::http_json;
+use crate::reporter::ProjectChange;
+use crate::reporter::style::Color;
+#[cfg(feature = "integrations")]
+use crate::retry;
+use crate::retry::RetryPolicy;
+
+/// How urgent a change is; critical changes skip batching
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    #[default]
+    Info,
+    Warning,
+    Critical,
+}
+
+impl Severity {
+    pub fn of(change: &ProjectChange) -> Self {
+        match change {
+            ProjectChange::Critical { .. } | ProjectChange::SlaBreached { .. } => Severity::Critical,
+            ProjectChange::NewlyBlocked { .. }
+            | ProjectChange::Removed { .. }
+            | ProjectChange::Attention { needs_attention: true, .. } => Severity::Warning,
+            ProjectChange::Added { .. }
+            | ProjectChange::StageMoved { .. }
+            | ProjectChange::TasksChanged { .. }
+            | ProjectChange::RequirementAddressed { .. }
+            | ProjectChange::Attention { needs_attention: false, .. } => Severity::Info,
+        }
+    }
+
+    /// Color of the change lines `skm watch` prints
+    pub fn color(&self) -> Color {
+        match self {
+            Severity::Info => Color::Green,
+            Severity::Warning => Color::Yellow,
+            Severity::Critical => Color::Red,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Critical => "critical",
+        }
+    }
+}
+
+/// One message to deliver, covering one or more changes
+#[derive(Debug, Clone, PartialEq)]
+pub struct Notification {
+    pub title: String,
+    /// Ordered by severity, most severe first, then by project
+    pub changes: Vec<ProjectChange>,
+}
+
+impl Notification {
+    pub fn new(title: String, mut changes: Vec<ProjectChange>) -> Self {
+        changes.sort_by(|a, b| Severity::of(b).cmp(&Severity::of(a)).then_with(|| a.project().cmp(b.project())));
+        Notification { title, changes }
+    }
+
+    /// Highest severity among the changes
+    pub fn severity(&self) -> Severity {
+        self.changes.iter().map(Severity::of).max().unwrap_or(Severity::Info)
+    }
+
+    /// Plain-text body: the title, then one section per severity
+    pub fn text(&self) -> String {
+        let mut text = self.title.clone();
+        let mut section = None;
+        for change in &self.changes {
+            let severity = Severity::of(change);
+            if section != Some(severity) {
+                let count = self.changes.iter().filter(|c| Severity::of(c) == severity).count();
+                text.push_str(&format!("\n\n{} ({})", severity.as_str(), count));
+                section = Some(severity);
+            }
+            text.push_str(&format!("\n- {}", change));
+        }
+        text
+    }
+}
+
+/// A destination for notifications
+pub trait Notifier {
+    fn name(&self) -> String;
+    fn send(&self, notification: &Notification) -> Result<()>;
+}
+
+/// Longest `content` a Discord webhook accepts
+#[cfg(feature = "integrations")]
+const DISCORD_MAX_CONTENT: usize = 2000;
+
+/// JSON body of a webhook notification
+///
+/// Slack reads `text` and Discord reads `content` (cut to its 2000
+/// characters); other receivers get `title`, `severity` and the individual
+/// `changes`.
+#[cfg(feature = "integrations")]
+pub fn webhook_payload(notification: &Notification) -> serde_json::Value {
+    let changes: Vec<_> = notification.changes.iter()
+        .map(|change| json!({
+            "project": change.project(),
+            "severity": Severity::of(change).as_str(),
+            "text": change.to_string(),
+        }))
+        .collect();
+    let text = notification.text();
+    let content = match text.chars().count() > DISCORD_MAX_CONTENT {
+        true => format!("{}…", text.chars().take(DISCORD_MAX_CONTENT - 1).collect::<String>()),
+        false => text.clone(),
+    };
+    json!({
+        "text": text,
+        "content": content,
+        "title": notification.title,
+        "severity": notification.severity().as_str(),
+        "changes": changes,
+    })
+}
+
+/// POSTs notifications as JSON, see [`webhook_payload`]
+#[cfg(feature = "integrations")]
+pub struct WebhookNotifier {
+    url: String,
+    policy: RetryPolicy,
+}
+
+#[cfg(feature = "integrations")]
+impl WebhookNotifier {
+    pub fn new(url: &str, policy: RetryPolicy) -> Self {
+        WebhookNotifier { url: url.to_string(), policy }
+    }
+}
+
+#[cfg(feature = "integrations")]
+impl Notifier for WebhookNotifier {
+    fn name(&self) -> String {
+        format!("webhook {}", self.url)
+    }
+
+    fn send(&self, notification: &Notification) -> Result<()> {
+        let body = webhook_payload(notification);
+        retry::retry(&self.policy, &format!("POST {}", self.url), || http_json("POST", &self.url, Some(&body)))
+            .map(|_| ())
+            .map_err(|e| SKMError::NotificationError { message: e.message })
+    }
+}
+
+/// Shows notifications on the desktop: `osascript` on macOS, `notify-send` elsewhere
+pub struct DesktopNotifier;
+
+impl Notifier for DesktopNotifier {
+    fn name(&self) -> String {
+        "the desktop".to_string()
+    }
+
+    fn send(&self, notification: &Notification) -> Result<()> {
+        let body: Vec<String> = notification.changes.iter().map(ToString::to_string).collect();
+        let body = body.join("\n");
+        let mut command = if cfg!(target_os = "macos") {
+            let quote = |s: &str| format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""));
+            let mut command = std::process::Command::new("osascript");
+            command.arg("-e").arg(format!("display notification {} with title {}", quote(&body), quote(&notification.title)));
+            command
+        } else {
+            let mut command = std::process::Command::new("notify-send");
+            let urgency = if notification.severity() == Severity::Critical { "critical" } else { "normal" };
+            command.args(["--app-name", "skm", "--urgency", urgency, &notification.title, &body]);
+            command
+        };
+        let output = command.output().map_err(|e| SKMError::NotificationError {
+            message: format!("{}: {}", command.get_program().to_string_lossy(), e),
+        })?;
+        if !output.status.success() {
+            return Err(SKMError::NotificationError {
+                message: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Notifiers configured under `[notifications]`
+pub fn notifiers(config: &NotificationConfig, policy: &RetryPolicy) -> Vec<Box<dyn Notifier>> {
+    let mut notifiers = webhook_notifiers(config, policy);
+    if config.desktop {
+        notifiers.push(Box::new(DesktopNotifier));
+    }
+    notifiers
+}
+
+#[cfg(feature = "integrations")]
+fn webhook_notifiers(config: &NotificationConfig, policy: &RetryPolicy) -> Vec<Box<dyn Notifier>> {
+    let mut notifiers: Vec<Box<dyn Notifier>> = Vec::new();
+    if let Some(url) = config.webhook_url.as_deref().filter(|url| !url.is_empty()) {
+        notifiers.push(Box::new(WebhookNotifier::new(url, policy.clone())));
+    }
+    notifiers
+}
+
+/// Without the `integrations` feature there are no webhooks to deliver to
+/// (see [`webhook_unavailable`])
+#[cfg(not(feature = "integrations"))]
+fn webhook_notifiers(_config: &NotificationConfig, _policy: &RetryPolicy) -> Vec<Box<dyn Notifier>> {
+    Vec::new()
+}
+
+/// Why the configured `webhook_url` gets no notifications, if it is set in
+/// a build without the `integrations` feature
+pub fn webhook_unavailable(config: &NotificationConfig) -> Option<SKMError> {
+    let configured = config.webhook_url.as_deref().is_some_and(|url| !url.is_empty());
+    (configured && !cfg!(feature = "integrations")).then_some(SKMError::FeatureDisabled { feature: "integrations" })
+}
+
+/// Collects changes into one notification per interval
+///
+/// The interval starts with the first change queued after a flush.
+/// Critical changes are never held back: they go out at once in a
+/// message of their own.
+pub struct Batcher {
+    interval: Duration,
+    pending: Vec<ProjectChange>,
+    opened: Option<Instant>,
+}
+
+impl Batcher {
+    /// A zero interval delivers each scan's changes as they come
+    pub fn new(interval: Duration) -> Self {
+        Batcher { interval, pending: Vec::new(), opened: None }
+    }
+
+    /// Queue changes, returning what has to be delivered right away
+    pub fn push(&mut self, changes: Vec<ProjectChange>) -> Option<Notification> {
+        let (critical, rest): (Vec<_>, Vec<_>) = changes.into_iter()
+            .partition(|change| Severity::of(change) == Severity::Critical);
+        if !rest.is_empty() {
+            self.opened.get_or_insert_with(Instant::now);
+            self.pending.extend(rest);
+        }
+        if self.interval.is_zero() {
+            let mut now = critical;
+            now.extend(self.take_all());
+            return (!now.is_empty()).then(|| Notification::new(title(now.len()), now));
+        }
+        (!critical.is_empty()).then(|| Notification::new(title(critical.len()), critical))
+    }
+
+    /// Time left until the pending changes are due; `None` when nothing is pending
+    pub fn time_to_flush(&self) -> Option<Duration> {
+        self.opened.map(|opened| self.interval.saturating_sub(opened.elapsed()))
+    }
+
+    /// The batched notification once its interval has passed
+    pub fn take_due(&mut self) -> Option<Notification> {
+        if self.time_to_flush()? > Duration::ZERO {
+            return None;
+        }
+        self.take()
+    }
+
+    /// The batched notification regardless of the interval, e.g. on shutdown
+    pub fn take(&mut self) -> Option<Notification> {
+        let changes = self.take_all();
+        (!changes.is_empty()).then(|| {
+            let minutes = self.interval.as_secs() / 60;
+            Notification::new(format!("{} in the last {} minute(s)", title(changes.len()), minutes.max(1)), changes)
+        })
+    }
+
+    fn take_all(&mut self) -> Vec<ProjectChange> {
+        self.opened = None;
+        std::mem::take(&mut self.pending)
+    }
+}
+
+fn title(count: usize) -> String {
+    format!("skm: {} project change(s)", count)
+}
+
+/// Deliver a notification to every notifier; failures are reported and
+/// do not stop the others
+pub fn deliver(notifiers: &[Box<dyn Notifier>], notification: &Notification) -> Vec<(String, SKMError)> {
+    notifiers.iter()
+        .filter_map(|notifier| notifier.send(notification).err().map(|e| (notifier.name(), e)))
+        .collect()
+}
@@ -0,0 +1,78 @@
+use std::path::Path;
+use std::fs;
+use chrono::{DateTime, Utc};
+use walkdir::WalkDir;
+use crate::{hash, ArtifactKind, Project};
+use crate::meta::snapshots::{SnapshotStore, SNAPSHOT_KINDS, artifact_file};
+use crate::scanner::finder::should_ignore;
+
+/// How deep the directory mtime fallback looks below the project root
+const DIR_SCAN_DEPTH: usize = 3;
+
+/// Upper bound on entries inspected by the directory mtime fallback
+const DIR_SCAN_LIMIT: usize = 2_000;
+
+/// Most recent sign of work on a project
+///
+/// Combines the last commit, task file and artifact modification times.
+/// Artifact times are checked against retained snapshots: when the content
+/// hash is unchanged since the last snapshot, a newer mtime is treated as a
+/// touch (copy, checkout, sync) rather than an edit. Projects outside git
+/// additionally fall back to the newest source file mtime in the project tree.
+pub fn last_activity(project: &Project, snapshots: &SnapshotStore) -> Option<DateTime<Utc>> {
+    let mut candidates = Vec::new();
+
+    if project.git.is_repo {
+        candidates.extend(project.git.last_commit);
+    }
+    candidates.extend(project.tasks.last_activity);
+    // An inherited constitution is shared by the portfolio and says nothing about this project
+    if let Some(constitution) = &project.artifacts.constitution
+        && !project.constitution_inherited {
+        candidates.push(constitution.modified);
+    }
+    for kind in SNAPSHOT_KINDS {
+        candidates.extend(artifact_activity(project, kind, snapshots));
+    }
+    if !project.git.is_repo {
+        candidates.extend(newest_mtime(&project.path));
+    }
+
+    candidates.into_iter().max()
+}
+
+/// Modification time of an artifact, corrected by its snapshot history
+fn artifact_activity(project: &Project, kind: ArtifactKind, snapshots: &SnapshotStore) -> Option<DateTime<Utc>> {
+    let file = artifact_file(project, kind)?;
+    let Some(last) = snapshots.history(&project.id, kind).last() else {
+        return Some(file.modified);
+    };
+
+    let unchanged = fs::read(&file.path).ok()
+        .is_some_and(|content| hash::blob_id(&content) == last.oid);
+    if unchanged {
+        Some(file.modified.min(last.taken_at))
+    } else {
+        Some(file.modified)
+    }
+}
+
+/// Newest file modification time in a project tree
+///
+/// Skips build and VCS directories, and the artifact directories, whose
+/// activity is judged by [`artifact_activity`] instead.
+pub fn newest_mtime(root: &Path) -> Option<DateTime<Utc>> {
+    WalkDir::new(root)
+        .max_depth(DIR_SCAN_DEPTH)
+        .into_iter()
+        .filter_entry(|e| {
+            let name = e.file_name();
+            !should_ignore(e.path()) && name != ".skm" && name != ".specify" && name != "specs"
+        })
+        .filter_map(|e| e.ok())
+        .take(DIR_SCAN_LIMIT)
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| e.metadata().ok()?.modified().ok())
+        .map(DateTime::<Utc>::from)
+        .max()
+}
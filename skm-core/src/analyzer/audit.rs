@@ -0,0 +1,115 @@
+use std::collections::BTreeSet;
+use std::path::Path;
+use chrono::Utc;
+use serde_json::Value;
+use crate::{AuditResult, Capability};
+use crate::{exec, hash};
+use crate::meta::state::ExecProfile;
+
+/// Dependency audit tool for one kind of lockfile
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AuditTool {
+    pub name: &'static str,
+    pub command: &'static str,
+    pub lockfile: &'static str,
+}
+
+const TOOLS: &[AuditTool] = &[
+    AuditTool { name: "cargo audit", command: "cargo audit --json", lockfile: "Cargo.lock" },
+    AuditTool { name: "npm audit", command: "npm audit --json", lockfile: "package-lock.json" },
+    AuditTool { name: "pip-audit", command: "pip-audit -r requirements.txt -f json", lockfile: "requirements.txt" },
+];
+
+/// Audit tool for the first lockfile found in a project
+pub fn audit_tool(path: &Path) -> Option<AuditTool> {
+    TOOLS.iter().copied().find(|tool| path.join(tool.lockfile).is_file())
+}
+
+/// Blob hash of a lockfile, the cache key for its audit
+pub fn lockfile_hash(lockfile: &Path) -> Option<String> {
+    hash::file_blob_id(lockfile).ok()
+}
+
+/// Audit a project's dependencies, reusing `cached` while the lockfile is unchanged
+///
+/// Returns `None` for projects without a supported lockfile. A tool that is
+/// missing or prints unrecognized output yields a result with `error` set.
+pub fn audit_project(
+    path: &Path,
+    profile: &ExecProfile,
+    capabilities: &BTreeSet<Capability>,
+    cached: Option<&AuditResult>,
+) -> Option<AuditResult> {
+    let tool = audit_tool(path)?;
+    let hash = lockfile_hash(&path.join(tool.lockfile))?;
+    if let Some(cached) = cached.filter(|c| c.lockfile_hash == hash && c.tool == tool.name) {
+        return Some(cached.clone());
+    }
+
+    let mut result = AuditResult {
+        at: Utc::now(),
+        tool: tool.name.to_string(),
+        lockfile: tool.lockfile.to_string(),
+        lockfile_hash: hash,
+        vulnerabilities: 0,
+        advisories: Vec::new(),
+        error: None,
+    };
+    // The tools exit non-zero when they find vulnerabilities, so only the output counts
+    match exec::run(path, profile, capabilities, tool.command) {
+        Ok(output) => match parse_audit_output(tool.name, &String::from_utf8_lossy(&output.stdout)) {
+            Some((count, advisories)) => {
+                result.vulnerabilities = count;
+                result.advisories = advisories;
+            }
+            None => {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                let reason = stderr.lines().find(|l| !l.trim().is_empty()).unwrap_or("unrecognized output");
+                result.error = Some(format!("{} failed: {}", tool.name, reason.trim()));
+            }
+        },
+        Err(e) => result.error = Some(e.to_string()),
+    }
+    Some(result)
+}
+
+/// Vulnerability count and advisory ids from the JSON output of an audit tool
+///
+/// Understands `cargo audit --json`, `npm audit --json` (npm 7+) and
+/// `pip-audit -f json` (both the bare list and the `dependencies` object).
+pub fn parse_audit_output(tool: &str, output: &str) -> Option<(u32, Vec<String>)> {
+    let doc: Value = serde_json::from_str(output.trim()).ok()?;
+    let mut advisories = Vec::new();
+    let count = match tool {
+        "cargo audit" => {
+            let vulnerabilities = doc.get("vulnerabilities")?;
+            for entry in vulnerabilities.get("list").and_then(Value::as_array).into_iter().flatten() {
+                if let Some(id) = entry.pointer("/advisory/id").and_then(Value::as_str) {
+                    advisories.push(id.to_string());
+                }
+            }
+            vulnerabilities.get("count").and_then(Value::as_u64).unwrap_or(advisories.len() as u64) as u32
+        }
+        "npm audit" => {
+            if let Some(packages) = doc.get("vulnerabilities").and_then(Value::as_object) {
+                advisories.extend(packages.keys().cloned());
+            }
+            doc.pointer("/metadata/vulnerabilities/total").and_then(Value::as_u64)? as u32
+        }
+        "pip-audit" => {
+            let dependencies = doc.get("dependencies").unwrap_or(&doc).as_array()?;
+            for dependency in dependencies {
+                for vuln in dependency.get("vulns").and_then(Value::as_array).into_iter().flatten() {
+                    if let Some(id) = vuln.get("id").and_then(Value::as_str) {
+                        advisories.push(id.to_string());
+                    }
+                }
+            }
+            advisories.len() as u32
+        }
+        _ => return None,
+    };
+    advisories.sort();
+    advisories.dedup();
+    Some((count, advisories))
+}
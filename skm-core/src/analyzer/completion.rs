@@ -0,0 +1,92 @@
+use std::path::Path;
+use crate::{CompletionSummary, Project, RootCompletion, Stage};
+
+/// Progress of a single project in the 0-1 range
+///
+/// Projects with tasks use their task completion ratio; projects without
+/// tasks use their position on the built-in stage ladder, where a custom
+/// stage counts as far along as review. `Done` is always 1.
+pub fn project_progress(project: &Project) -> f64 {
+    if project.stage == Stage::Done {
+        return 1.0;
+    }
+
+    if project.tasks.total > 0 {
+        project.tasks.completed as f64 / project.tasks.total as f64
+    } else {
+        let ladder = Stage::ALL;
+        let position = ladder.iter().position(|s| *s == project.stage)
+            .or_else(|| ladder.iter().position(|s| *s == Stage::Review))
+            .unwrap_or(0);
+        position as f64 / (ladder.len() - 1) as f64
+    }
+}
+
+/// Effort weight of a project: its task count, with a floor of one
+fn effort_weight(project: &Project) -> f64 {
+    project.tasks.total.max(1) as f64
+}
+
+/// Completion metrics for a set of projects, without a per-root breakdown
+pub fn completion<'a>(projects: impl IntoIterator<Item = &'a Project>) -> CompletionSummary {
+    let mut summary = CompletionSummary::default();
+    let mut weighted_progress = 0.0;
+    let mut total_weight = 0.0;
+    let mut total_tasks = 0u64;
+    let mut completed_tasks = 0u64;
+
+    for project in projects {
+        summary.projects_total += 1;
+        if project.stage == Stage::Done {
+            summary.projects_done += 1;
+        }
+
+        let weight = effort_weight(project);
+        weighted_progress += project_progress(project) * weight;
+        total_weight += weight;
+        total_tasks += project.tasks.total as u64;
+        completed_tasks += project.tasks.completed as u64;
+    }
+
+    summary.percent_done = percent(summary.projects_done as f64, summary.projects_total as f64);
+    summary.task_percent = percent(completed_tasks as f64, total_tasks as f64);
+    summary.effort_weighted_percent = percent(weighted_progress, total_weight);
+    summary
+}
+
+/// Completion metrics with a breakdown for each labeled root
+///
+/// Projects tagged with a root name belong to that root; untagged projects
+/// are assigned to the first root whose path contains them.
+pub fn completion_by_root(projects: &[Project], roots: &[(String, &Path)]) -> CompletionSummary {
+    let mut summary = completion(projects);
+
+    summary.by_root = roots.iter()
+        .map(|(label, root)| {
+            let in_root: Vec<&Project> = projects.iter()
+                .filter(|p| match &p.root {
+                    Some(name) => name == label,
+                    None => roots.iter()
+                        .find(|(_, r)| p.path.starts_with(r))
+                        .is_some_and(|(l, _)| l == label),
+                })
+                .collect();
+            RootCompletion {
+                root: label.clone(),
+                path: root.to_path_buf(),
+                needs_attention: in_root.iter().filter(|p| p.needs_attention).count() as u32,
+                completion: completion(in_root),
+            }
+        })
+        .collect();
+
+    summary
+}
+
+fn percent(part: f64, whole: f64) -> f64 {
+    if whole > 0.0 {
+        part / whole * 100.0
+    } else {
+        0.0
+    }
+}
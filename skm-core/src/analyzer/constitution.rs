@@ -0,0 +1,13 @@
+use crate::Principle;
+
+/// Principles that apply to a project: its own, then the portfolio's
+///
+/// A portfolio principle with the same name as one of the project's
+/// (ignoring case) is overridden by the project's.
+pub fn merge_principles(portfolio: &[String], project: &[String]) -> Vec<Principle> {
+    let own = project.iter().map(|name| Principle { name: name.clone(), inherited: false });
+    let inherited = portfolio.iter()
+        .filter(|name| !project.iter().any(|p| p.eq_ignore_ascii_case(name)))
+        .map(|name| Principle { name: name.clone(), inherited: true });
+    own.chain(inherited).collect()
+}
@@ -0,0 +1,348 @@
+use chrono::{DateTime, Utc};
+use crate::{Project, SKMError, Stage};
+use crate::view::FieldFilter;
+use super::completion::project_progress;
+
+/// A parsed `--only` expression, e.g. `stage:implement AND priority>40 AND git:dirty`
+///
+/// Predicates are combined with `AND`, `OR` and `NOT` (any case) and
+/// parentheses; `AND` binds tighter than `OR`, and predicates written next
+/// to each other are joined with `AND`. See [`Predicate`] for what can be
+/// tested.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Filter {
+    And(Box<Filter>, Box<Filter>),
+    Or(Box<Filter>, Box<Filter>),
+    Not(Box<Filter>),
+    Test(Predicate),
+}
+
+/// A single test on a project
+#[derive(Debug, Clone, PartialEq)]
+pub enum Predicate {
+    /// `needs-attention` (or `attention`), `incomplete`, `outdated-deps`,
+    /// `human` (requires a person) or `done`
+    Flag(Flag),
+    /// `stage:plan`, `stage>=implement`; stages compare by their order in the workflow
+    Stage(Op, Stage),
+    /// `priority>40`, `open>=3`, `age>14`, ...
+    Metric(Metric, Op, f64),
+    /// `git:dirty`, `git:clean` or `git:none` (not a repository)
+    Git(GitState),
+    /// `id:`, `root:` or `custom.<field>:` a value; `!=` negates
+    Field(FieldFilter, bool),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Flag {
+    NeedsAttention,
+    Incomplete,
+    OutdatedDeps,
+    Human,
+    Done,
+}
+
+/// Numeric project attributes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Metric {
+    Priority,
+    /// Total tasks
+    Tasks,
+    /// Tasks not completed yet
+    Open,
+    Completed,
+    Blocked,
+    /// Progress in percent (see `analyzer::completion`)
+    Progress,
+    Questions,
+    Vulnerabilities,
+    /// Dependencies a major version behind
+    Outdated,
+    /// TODO/FIXME comments
+    Debt,
+    /// Days since the project was last active
+    Age,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GitState {
+    Dirty,
+    Clean,
+    None,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Eq,
+    Ne,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+}
+
+impl Op {
+    fn compare<T: PartialOrd>(self, left: T, right: T) -> bool {
+        match self {
+            Op::Eq => left == right,
+            Op::Ne => left != right,
+            Op::Gt => left > right,
+            Op::Ge => left >= right,
+            Op::Lt => left < right,
+            Op::Le => left <= right,
+        }
+    }
+}
+
+const METRICS: &[(&str, Metric)] = &[
+    ("priority", Metric::Priority),
+    ("tasks", Metric::Tasks),
+    ("open", Metric::Open),
+    ("completed", Metric::Completed),
+    ("blocked", Metric::Blocked),
+    ("progress", Metric::Progress),
+    ("questions", Metric::Questions),
+    ("vulns", Metric::Vulnerabilities),
+    ("outdated", Metric::Outdated),
+    ("debt", Metric::Debt),
+    ("age", Metric::Age),
+];
+
+const FLAGS: &[(&str, Flag)] = &[
+    ("needs-attention", Flag::NeedsAttention),
+    ("attention", Flag::NeedsAttention),
+    ("incomplete", Flag::Incomplete),
+    ("outdated-deps", Flag::OutdatedDeps),
+    ("human", Flag::Human),
+    ("done", Flag::Done),
+];
+
+/// Conditions that are complete as a single word, for shell completion:
+/// the flags and every `stage:` value, with the given custom stages, and `git:` value
+pub fn completion_words(custom_stages: &[String]) -> Vec<String> {
+    let mut words: Vec<String> = FLAGS.iter().map(|(name, _)| name.to_string()).collect();
+    words.extend(Stage::ladder(custom_stages.iter().map(String::as_str)).iter().map(|stage| format!("stage:{}", stage.as_str())));
+    words.extend(["dirty", "clean", "none"].iter().map(|state| format!("git:{}", state)));
+    words
+}
+
+impl Filter {
+    /// Whether the project passes, with ages measured from now
+    pub fn matches(&self, project: &Project) -> bool {
+        self.matches_at(project, Utc::now())
+    }
+
+    pub fn matches_at(&self, project: &Project, now: DateTime<Utc>) -> bool {
+        match self {
+            Filter::And(left, right) => left.matches_at(project, now) && right.matches_at(project, now),
+            Filter::Or(left, right) => left.matches_at(project, now) || right.matches_at(project, now),
+            Filter::Not(inner) => !inner.matches_at(project, now),
+            Filter::Test(predicate) => predicate.matches(project, now),
+        }
+    }
+}
+
+impl Predicate {
+    fn matches(&self, project: &Project, now: DateTime<Utc>) -> bool {
+        match self {
+            Predicate::Flag(flag) => match flag {
+                Flag::NeedsAttention => project.needs_attention,
+                Flag::Incomplete => project.tasks.completed < project.tasks.total,
+                Flag::OutdatedDeps => project.freshness.as_ref().is_some_and(|f| f.major_behind > 0),
+                Flag::Human => !project.requires_human.is_empty(),
+                Flag::Done => project.stage == Stage::Done,
+            },
+            Predicate::Stage(op, stage) => op.compare(&project.stage, stage),
+            Predicate::Metric(metric, op, value) => op.compare(metric_value(*metric, project, now), *value),
+            Predicate::Git(state) => match state {
+                GitState::Dirty => project.git.is_repo && !project.git.clean,
+                GitState::Clean => project.git.is_repo && project.git.clean,
+                GitState::None => !project.git.is_repo,
+            },
+            Predicate::Field(filter, negate) => filter.matches(project) != *negate,
+        }
+    }
+}
+
+fn metric_value(metric: Metric, project: &Project, now: DateTime<Utc>) -> f64 {
+    match metric {
+        Metric::Priority => project.priority,
+        Metric::Tasks => project.tasks.total as f64,
+        Metric::Open => project.tasks.total.saturating_sub(project.tasks.completed) as f64,
+        Metric::Completed => project.tasks.completed as f64,
+        Metric::Blocked => project.tasks.blocked as f64,
+        Metric::Progress => project_progress(project) * 100.0,
+        Metric::Questions => project.open_questions.len() as f64,
+        Metric::Vulnerabilities => project.audit.as_ref().map_or(0.0, |a| a.vulnerabilities as f64),
+        Metric::Outdated => project.freshness.as_ref().map_or(0.0, |f| f.major_behind as f64),
+        Metric::Debt => project.code_debt.unwrap_or(0) as f64,
+        Metric::Age => (now - project.updated).num_seconds() as f64 / 86_400.0,
+    }
+}
+
+impl std::str::FromStr for Filter {
+    type Err = SKMError;
+
+    fn from_str(s: &str) -> crate::Result<Self> {
+        let tokens = tokenize(s);
+        if tokens.is_empty() {
+            return Err(invalid(s, "the expression is empty"));
+        }
+        let mut parser = Parser { source: s, tokens, pos: 0 };
+        let filter = parser.or()?;
+        match parser.tokens.get(parser.pos) {
+            Some(token) => Err(invalid(s, &format!("unexpected {}", token))),
+            None => Ok(filter),
+        }
+    }
+}
+
+fn invalid(source: &str, reason: &str) -> SKMError {
+    SKMError::ConfigError {
+        message: format!("Invalid filter {}: {}", source, reason),
+    }
+}
+
+/// Words and parentheses; operators stay inside their predicate
+fn tokenize(s: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut word = String::new();
+    for c in s.chars() {
+        if c.is_whitespace() || c == '(' || c == ')' {
+            if !word.is_empty() {
+                tokens.push(std::mem::take(&mut word));
+            }
+            if !c.is_whitespace() {
+                tokens.push(c.to_string());
+            }
+        } else {
+            word.push(c);
+        }
+    }
+    if !word.is_empty() {
+        tokens.push(word);
+    }
+    tokens
+}
+
+struct Parser<'a> {
+    source: &'a str,
+    tokens: Vec<String>,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        self.tokens.get(self.pos).is_some_and(|t| t.eq_ignore_ascii_case(keyword))
+    }
+
+    fn or(&mut self) -> crate::Result<Filter> {
+        let mut left = self.and()?;
+        while self.peek_keyword("or") {
+            self.pos += 1;
+            left = Filter::Or(Box::new(left), Box::new(self.and()?));
+        }
+        Ok(left)
+    }
+
+    fn and(&mut self) -> crate::Result<Filter> {
+        let mut left = self.not()?;
+        loop {
+            if self.peek_keyword("and") {
+                self.pos += 1;
+            } else if self.pos >= self.tokens.len() || self.peek_keyword("or") || self.peek_keyword(")") {
+                return Ok(left);
+            }
+            left = Filter::And(Box::new(left), Box::new(self.not()?));
+        }
+    }
+
+    fn not(&mut self) -> crate::Result<Filter> {
+        if self.peek_keyword("not") {
+            self.pos += 1;
+            return Ok(Filter::Not(Box::new(self.not()?)));
+        }
+        self.atom()
+    }
+
+    fn atom(&mut self) -> crate::Result<Filter> {
+        let Some(token) = self.tokens.get(self.pos).cloned() else {
+            return Err(invalid(self.source, "it ends where a condition was expected"));
+        };
+        self.pos += 1;
+        match token.as_str() {
+            "(" => {
+                let inner = self.or()?;
+                if !self.peek_keyword(")") {
+                    return Err(invalid(self.source, "missing )"));
+                }
+                self.pos += 1;
+                Ok(inner)
+            }
+            ")" => Err(invalid(self.source, "unexpected )")),
+            _ if ["and", "or"].iter().any(|k| token.eq_ignore_ascii_case(k)) =>
+                Err(invalid(self.source, &format!("{} needs a condition before it", token.to_uppercase()))),
+            _ => Ok(Filter::Test(parse_predicate(&token).map_err(|reason| invalid(self.source, &reason))?)),
+        }
+    }
+}
+
+fn parse_predicate(token: &str) -> Result<Predicate, String> {
+    let key_end = token.find(['<', '>', '=', '!', ':']).unwrap_or(token.len());
+    let (key, rest) = token.split_at(key_end);
+    let key = key.to_ascii_lowercase();
+    if rest.is_empty() {
+        return FLAGS.iter()
+            .find(|(name, _)| *name == key)
+            .map(|(_, flag)| Predicate::Flag(*flag))
+            .ok_or_else(|| format!("unknown condition {} (flags: {})",
+                token, FLAGS.iter().map(|(name, _)| *name).collect::<Vec<_>>().join(", ")));
+    }
+
+    let (op, value) = [(">=", Op::Ge), ("<=", Op::Le), ("!=", Op::Ne), (">", Op::Gt), ("<", Op::Lt), ("=", Op::Eq), (":", Op::Eq)]
+        .iter()
+        .find_map(|(symbol, op)| rest.strip_prefix(symbol).map(|value| (*op, value)))
+        .ok_or_else(|| format!("no comparison in {}", token))?;
+    if value.is_empty() {
+        return Err(format!("{} has no value", token));
+    }
+
+    match key.as_str() {
+        "stage" => value.parse::<Stage>()
+            .map(|stage| Predicate::Stage(op, stage))
+            .map_err(|_| format!("unknown stage {}", value)),
+        "git" => {
+            let state = match value.to_ascii_lowercase().as_str() {
+                "dirty" => GitState::Dirty,
+                "clean" => GitState::Clean,
+                "none" => GitState::None,
+                _ => return Err(format!("git: takes dirty, clean or none, not {}", value)),
+            };
+            match op {
+                Op::Eq => Ok(Predicate::Git(state)),
+                _ => Err(format!("git only compares with : or =, in {}", token)),
+            }
+        }
+        "id" | "root" | "tag" => field(&key, op, value, token),
+        _ if key.starts_with("custom.") => field(&key, op, value, token),
+        _ => {
+            let metric = METRICS.iter()
+                .find(|(name, _)| *name == key)
+                .map(|(_, metric)| *metric)
+                .ok_or_else(|| format!("unknown field {} (fields: stage, git, id, root, tag, custom.<field>, {})",
+                    key, METRICS.iter().map(|(name, _)| *name).collect::<Vec<_>>().join(", ")))?;
+            let number = value.parse::<f64>().map_err(|_| format!("{} needs a number, not {}", key, value))?;
+            Ok(Predicate::Metric(metric, op, number))
+        }
+    }
+}
+
+fn field(key: &str, op: Op, value: &str, token: &str) -> Result<Predicate, String> {
+    let negate = match op {
+        Op::Eq => false,
+        Op::Ne => true,
+        _ => return Err(format!("{} only compares with :, = or !=, in {}", key, token)),
+    };
+    let filter = format!("{}={}", key, value).parse::<FieldFilter>().map_err(|e| e.to_string())?;
+    Ok(Predicate::Field(filter, negate))
+}
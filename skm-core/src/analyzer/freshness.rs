@@ -0,0 +1,242 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+use std::process::Command;
+use chrono::{Duration, Utc};
+use serde_json::Value;
+use crate::{DependencyFreshness, OutdatedDependency};
+use crate::retry::{self, RetryPolicy, Transience};
+use super::audit::lockfile_hash;
+
+/// Package registry a locked dependency comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Registry {
+    Crates,
+    Npm,
+    PyPi,
+}
+
+/// Direct dependency with the version its lockfile pins
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LockedDependency {
+    pub name: String,
+    pub version: String,
+}
+
+/// Lockfiles in the order they are looked for, with the registry they resolve against
+const LOCKFILES: &[(&str, Registry)] = &[
+    ("Cargo.lock", Registry::Crates),
+    ("package-lock.json", Registry::Npm),
+    ("requirements.txt", Registry::PyPi),
+];
+
+/// Direct dependencies pinned by the first supported lockfile of a project
+pub fn locked_dependencies(path: &Path) -> Option<(&'static str, Registry, Vec<LockedDependency>)> {
+    let (lockfile, registry) = LOCKFILES.iter().copied().find(|(name, _)| path.join(name).is_file())?;
+    let content = std::fs::read_to_string(path.join(lockfile)).ok()?;
+    let dependencies = match registry {
+        Registry::Crates => cargo_lock_dependencies(&content)?,
+        Registry::Npm => {
+            let manifest = std::fs::read_to_string(path.join("package.json")).unwrap_or_default();
+            npm_lock_dependencies(&content, &manifest)?
+        }
+        Registry::PyPi => requirements_dependencies(&content),
+    };
+    Some((lockfile, registry, dependencies))
+}
+
+/// Registry packages that the workspace members of a Cargo.lock depend on directly
+fn cargo_lock_dependencies(content: &str) -> Option<Vec<LockedDependency>> {
+    let lock: toml::Value = toml::from_str(content).ok()?;
+    let packages = lock.get("package")?.as_array()?;
+    let field = |p: &toml::Value, key: &str| p.get(key).and_then(|v| v.as_str()).map(str::to_string);
+
+    let mut direct = BTreeMap::new();
+    // Workspace members are the packages without a source
+    for member in packages.iter().filter(|p| p.get("source").is_none()) {
+        for dependency in member.get("dependencies").and_then(|d| d.as_array()).into_iter().flatten() {
+            // "name", "name version" or "name version (source)"
+            let mut parts = dependency.as_str().unwrap_or_default().split_whitespace();
+            let (Some(name), version) = (parts.next(), parts.next()) else { continue };
+            let locked = packages.iter().find(|p| {
+                field(p, "name").as_deref() == Some(name)
+                    && version.is_none_or(|v| field(p, "version").as_deref() == Some(v))
+                    && field(p, "source").is_some_and(|s| s.starts_with("registry+"))
+            });
+            if let Some(version) = locked.and_then(|p| field(p, "version")) {
+                direct.insert(name.to_string(), version);
+            }
+        }
+    }
+    Some(direct.into_iter().map(|(name, version)| LockedDependency { name, version }).collect())
+}
+
+/// Dependencies listed in package.json with the versions package-lock.json resolved
+fn npm_lock_dependencies(content: &str, manifest: &str) -> Option<Vec<LockedDependency>> {
+    let lock: Value = serde_json::from_str(content).ok()?;
+    let manifest: Value = serde_json::from_str(manifest).unwrap_or_default();
+    // lockfileVersion 2+ records the root manifest itself under `packages[""]`
+    let root = lock.pointer("/packages/").filter(|r| r.is_object()).unwrap_or(&manifest);
+
+    let mut dependencies = Vec::new();
+    for section in ["dependencies", "devDependencies"] {
+        for name in root.get(section).and_then(Value::as_object).into_iter().flat_map(|m| m.keys()) {
+            let version = lock.get("packages")
+                .and_then(|p| p.get(format!("node_modules/{}", name)))
+                .or_else(|| lock.get("dependencies").and_then(|d| d.get(name)))
+                .and_then(|p| p.get("version"))
+                .and_then(Value::as_str);
+            if let Some(version) = version {
+                dependencies.push(LockedDependency { name: name.clone(), version: version.to_string() });
+            }
+        }
+    }
+    dependencies.sort_by(|a, b| a.name.cmp(&b.name));
+    dependencies.dedup_by(|a, b| a.name == b.name);
+    Some(dependencies)
+}
+
+/// `name==version` pins of a requirements file; ranges and URLs are skipped
+fn requirements_dependencies(content: &str) -> Vec<LockedDependency> {
+    content.lines()
+        .map(|line| line.split('#').next().unwrap_or_default().split(';').next().unwrap_or_default().trim())
+        .filter_map(|line| line.split_once("=="))
+        .map(|(name, version)| LockedDependency {
+            name: name.split('[').next().unwrap_or(name).trim().to_lowercase(),
+            version: version.trim().to_string(),
+        })
+        .filter(|d| !d.name.is_empty() && !d.version.is_empty())
+        .collect()
+}
+
+/// Numeric release components of a version: `1.2.3-beta.1` is `[1, 2, 3]`
+fn release(version: &str) -> Vec<u64> {
+    version.trim_start_matches(['v', '=', '^', '~'])
+        .split(['-', '+'])
+        .next()
+        .unwrap_or_default()
+        .split('.')
+        .map_while(|part| part.parse().ok())
+        .collect()
+}
+
+/// Whether `latest` is a breaking release ahead of `current`
+///
+/// Below 1.0 the minor version is the breaking one, as in semver and Cargo.
+pub fn major_behind(current: &str, latest: &str) -> bool {
+    let (current, latest) = (release(current), release(latest));
+    let part = |v: &[u64], i: usize| v.get(i).copied().unwrap_or(0);
+    match (part(&current, 0), part(&latest, 0)) {
+        (0, 0) => part(&latest, 1) > part(&current, 1),
+        (current_major, latest_major) => latest_major > current_major,
+    }
+}
+
+#[derive(Debug)]
+struct FetchError(String);
+
+impl fmt::Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Transience for FetchError {
+    fn is_transient(&self) -> bool {
+        retry::is_transient_message(&self.0)
+    }
+}
+
+fn fetch_json(url: &str) -> Result<Value, FetchError> {
+    let output = Command::new("curl")
+        .args(["-sSfL", "--max-time", "10", "-A", "skm (dependency freshness)", url])
+        .output()
+        .map_err(|e| FetchError(format!("cannot run curl: {}", e)))?;
+    if !output.status.success() {
+        return Err(FetchError(String::from_utf8_lossy(&output.stderr).trim().to_string()));
+    }
+    serde_json::from_slice(&output.stdout).map_err(|e| FetchError(format!("{}: {}", url, e)))
+}
+
+/// Latest stable version of a package, from its registry's JSON API
+pub fn latest_version(registry: Registry, name: &str, policy: &RetryPolicy) -> Option<String> {
+    let (url, pointer) = match registry {
+        Registry::Crates => (format!("https://crates.io/api/v1/crates/{}", name), "/crate/max_stable_version"),
+        Registry::Npm => (format!("https://registry.npmjs.org/{}/latest", name.replace('/', "%2F")), "/version"),
+        Registry::PyPi => (format!("https://pypi.org/pypi/{}/json", name), "/info/version"),
+    };
+    let doc = retry::retry(policy, &format!("fetching {}", url), || fetch_json(&url)).ok()?;
+    doc.pointer(pointer).and_then(Value::as_str).map(str::to_string)
+}
+
+/// Compare a project's direct dependencies with their registries' latest versions
+///
+/// A cached result is reused while the lockfile is unchanged and younger
+/// than `max_age_days`; only the dependencies whose lookup failed are
+/// looked up again. Returns `None` for projects without a supported
+/// lockfile or with no direct dependencies.
+pub fn check_freshness(
+    path: &Path,
+    cached: Option<&DependencyFreshness>,
+    max_age_days: u32,
+    policy: &RetryPolicy,
+) -> Option<DependencyFreshness> {
+    check_freshness_with(path, cached, max_age_days, |registry, name| latest_version(registry, name, policy))
+}
+
+/// [`check_freshness`] with the latest versions from `latest`, `None` when a lookup fails
+pub fn check_freshness_with(
+    path: &Path,
+    cached: Option<&DependencyFreshness>,
+    max_age_days: u32,
+    latest: impl Fn(Registry, &str) -> Option<String>,
+) -> Option<DependencyFreshness> {
+    let (lockfile, registry, dependencies) = locked_dependencies(path)?;
+    if dependencies.is_empty() {
+        return None;
+    }
+    let hash = lockfile_hash(&path.join(lockfile))?;
+    // Results cached before failed lookups were named are checked in full
+    let fresh = |c: &&DependencyFreshness| {
+        c.lockfile_hash == hash && Utc::now() - c.at < Duration::days(max_age_days as i64)
+            && c.failed.len() == c.unchecked as usize
+    };
+
+    let (mut result, pending) = match cached.filter(fresh) {
+        Some(cached) => {
+            let pending = dependencies.into_iter().filter(|d| cached.failed.contains(&d.name)).collect();
+            let mut result = cached.clone();
+            result.failed.clear();
+            (result, pending)
+        }
+        None => {
+            let result = DependencyFreshness {
+                at: Utc::now(),
+                lockfile: lockfile.to_string(),
+                lockfile_hash: hash,
+                direct: dependencies.len() as u32,
+                unchecked: 0,
+                failed: Vec::new(),
+                major_behind: 0,
+                outdated: Vec::new(),
+            };
+            (result, dependencies)
+        }
+    };
+    for dependency in pending {
+        let Some(latest) = latest(registry, &dependency.name) else {
+            result.failed.push(dependency.name);
+            continue;
+        };
+        if major_behind(&dependency.version, &latest) {
+            result.major_behind += 1;
+            result.outdated.push(OutdatedDependency {
+                name: dependency.name,
+                current: dependency.version,
+                latest,
+            });
+        }
+    }
+    result.unchecked = result.failed.len() as u32;
+    Some(result)
+}
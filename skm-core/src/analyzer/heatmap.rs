@@ -0,0 +1,129 @@
+use crate::{Project, TestSource};
+
+/// Share of the projects at which a signal counts as a systemic problem
+pub const SYSTEMIC_SHARE: f64 = 0.5;
+
+/// A risk signal shown as a column of the heat map
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RiskSignal {
+    /// A failing `skm test` or `skm check` run, or a failing local test report
+    Errors,
+    /// Tasks marked blocked
+    Blocked,
+    /// Uncommitted changes
+    Dirty,
+    /// Commits on the upstream branch not pulled yet
+    Behind,
+    /// The spec changed after the plan was written, or grew substantially since
+    StalePlan,
+    /// Failing checks on HEAD or on open pull requests, or a failing CI note
+    FailingCi,
+}
+
+impl RiskSignal {
+    pub const ALL: [RiskSignal; 6] = [
+        RiskSignal::Errors,
+        RiskSignal::Blocked,
+        RiskSignal::Dirty,
+        RiskSignal::Behind,
+        RiskSignal::StalePlan,
+        RiskSignal::FailingCi,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            RiskSignal::Errors => "Errors",
+            RiskSignal::Blocked => "Blocked",
+            RiskSignal::Dirty => "Dirty",
+            RiskSignal::Behind => "Behind",
+            RiskSignal::StalePlan => "Stale plan",
+            RiskSignal::FailingCi => "Failing CI",
+        }
+    }
+
+    /// How a systemic problem reads, e.g. "behind upstream"
+    pub fn description(&self) -> &'static str {
+        match self {
+            RiskSignal::Errors => "failing tests or checks",
+            RiskSignal::Blocked => "blocked tasks",
+            RiskSignal::Dirty => "uncommitted changes",
+            RiskSignal::Behind => "behind upstream",
+            RiskSignal::StalePlan => "a plan older than its spec",
+            RiskSignal::FailingCi => "failing CI",
+        }
+    }
+
+    pub fn detect(&self, project: &Project) -> bool {
+        let failing_evidence = |ci: bool| project.test_evidence.as_ref().is_some_and(|evidence| {
+            !evidence.passed && ci == matches!(evidence.source, TestSource::CiNote | TestSource::GitHubChecks)
+        });
+        match self {
+            RiskSignal::Errors => {
+                project.last_test.as_ref().is_some_and(|run| !run.success)
+                    || project.last_check.as_ref().is_some_and(|run| !run.success)
+                    || failing_evidence(false)
+            }
+            RiskSignal::Blocked => project.tasks.blocked > 0,
+            RiskSignal::Dirty => project.git.is_repo && !project.git.clean,
+            RiskSignal::Behind => project.git.behind > 0,
+            RiskSignal::StalePlan => {
+                let spec_newer = match (&project.artifacts.spec, &project.artifacts.plan) {
+                    (Some(spec), Some(plan)) => spec.modified > plan.modified,
+                    _ => false,
+                };
+                spec_newer || project.spec_trend.as_ref().is_some_and(|trend| trend.scope_creep)
+            }
+            RiskSignal::FailingCi => {
+                project.git.github.as_ref().is_some_and(|g| g.failing_checks > 0 || g.head_checks == Some(false))
+                    || project.git.ci_note.as_ref().is_some_and(|note| !note.passed)
+                    || failing_evidence(true)
+            }
+        }
+    }
+}
+
+/// Projects × risk signals, for spotting problems shared across the portfolio
+#[derive(Debug, Clone)]
+pub struct RiskHeatMap<'a> {
+    /// Projects with at least one signal, most signals first, in the order given otherwise
+    pub rows: Vec<(&'a Project, [bool; RiskSignal::ALL.len()])>,
+    /// Projects with each signal, in the order of [`RiskSignal::ALL`]
+    pub totals: [usize; RiskSignal::ALL.len()],
+    /// All projects looked at, with or without signals
+    pub projects: usize,
+}
+
+impl<'a> RiskHeatMap<'a> {
+    pub fn new(projects: impl IntoIterator<Item = &'a Project>) -> Self {
+        let mut rows = Vec::new();
+        let mut totals = [0; RiskSignal::ALL.len()];
+        let mut count = 0;
+        for project in projects {
+            count += 1;
+            let cells = RiskSignal::ALL.map(|signal| signal.detect(project));
+            for (total, hit) in totals.iter_mut().zip(cells) {
+                *total += hit as usize;
+            }
+            if cells.contains(&true) {
+                rows.push((project, cells));
+            }
+        }
+        // Stable, so projects with as many signals keep the caller's order
+        rows.sort_by_key(|(_, cells)| std::cmp::Reverse(cells.iter().filter(|hit| **hit).count()));
+        RiskHeatMap { rows, totals, projects: count }
+    }
+
+    /// Fraction of all projects with a signal
+    pub fn share(&self, signal: RiskSignal) -> f64 {
+        let index = RiskSignal::ALL.iter().position(|s| *s == signal).unwrap_or(0);
+        if self.projects == 0 { 0.0 } else { self.totals[index] as f64 / self.projects as f64 }
+    }
+
+    /// Signals found in at least [`SYSTEMIC_SHARE`] of the projects, with their count
+    pub fn systemic(&self) -> Vec<(RiskSignal, usize)> {
+        RiskSignal::ALL.iter().zip(self.totals)
+            .filter(|(signal, total)| *total > 0 && self.share(**signal) >= SYSTEMIC_SHARE)
+            .map(|(signal, total)| (*signal, total))
+            .collect()
+    }
+}
@@ -0,0 +1,17 @@
+pub mod stage;
+pub mod priority;
+pub mod requirements;
+pub mod completion;
+pub mod activity;
+pub mod constitution;
+pub mod sprint;
+pub mod testrun;
+pub mod audit;
+pub mod freshness;
+pub mod filter;
+pub mod season;
+pub mod heatmap;
+pub mod sla;
+
+pub use stage::*;
+pub use priority::*;
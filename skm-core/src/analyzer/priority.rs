@@ -0,0 +1,647 @@
+use chrono::{Utc, DateTime};
+use serde::{Deserialize, Serialize};
+use crate::{Stage, HumanRequirement, GitStatus, TaskSummary, SKMError};
+
+/// Coarse priority band used in reports and the shell prompt
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PriorityTier {
+    High,
+    Medium,
+    Low,
+}
+
+impl PriorityTier {
+    pub fn from_priority(priority: f64) -> Self {
+        if priority > 70.0 {
+            PriorityTier::High
+        } else if priority > 40.0 {
+            PriorityTier::Medium
+        } else {
+            PriorityTier::Low
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PriorityTier::High => "high",
+            PriorityTier::Medium => "medium",
+            PriorityTier::Low => "low",
+        }
+    }
+
+    pub fn emoji(&self) -> &'static str {
+        match self {
+            PriorityTier::High => "🔴",
+            PriorityTier::Medium => "🟡",
+            PriorityTier::Low => "🟢",
+        }
+    }
+
+    /// The emoji, or the tier name in accessible mode
+    pub fn symbol(&self) -> &'static str {
+        crate::reporter::style::icon(self.emoji(), self.as_str())
+    }
+}
+
+/// Weights of the default [`WeightedSum`] strategy
+#[derive(Debug, Clone)]
+pub struct PriorityWeights {
+    pub needs_human: f64,
+    pub risk: f64,
+    pub staleness: f64,
+    pub impact: f64,
+    pub confidence: f64,
+}
+
+impl Default for PriorityWeights {
+    fn default() -> Self {
+        Self {
+            needs_human: 40.0,
+            risk: 25.0,
+            staleness: 15.0,
+            impact: 15.0,
+            confidence: 10.0,
+        }
+    }
+}
+
+/// Open tasks at which `effort` reaches 1
+const FULL_EFFORT_TASKS: f64 = 20.0;
+
+/// What a priority score is computed from, each normalized to 0-1
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct PriorityFactors {
+    /// 1 when the project needs a person, else 0
+    pub needs_human: f64,
+    /// Risk level 0-3
+    pub risk: f64,
+    /// Days since the last activity, full at 7
+    pub staleness: f64,
+    /// Impact 1-3 from the project meta
+    pub impact: f64,
+    /// 1 when the approach was approved by a person, else 0.5
+    pub confidence: f64,
+    /// Open tasks, full at 20
+    pub effort: f64,
+}
+
+impl PriorityFactors {
+    /// Factor names usable in a [`PriorityExpression`]
+    pub const NAMES: [&'static str; 6] = ["needs_human", "risk", "staleness", "impact", "confidence", "effort"];
+
+    /// Factors with staleness measured at `now` and no open tasks
+    pub fn new(
+        requires_human: &[HumanRequirement],
+        risk_level: u8,
+        last_updated: DateTime<Utc>,
+        impact: u8,
+        confidence: u8,
+        now: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            needs_human: if requires_human.is_empty() { 0.0 } else { 1.0 },
+            risk: normalize_risk(risk_level),
+            staleness: calculate_staleness(last_updated, now),
+            impact: normalize_impact(impact),
+            confidence: normalize_confidence(confidence),
+            effort: 0.0,
+        }
+    }
+
+    pub fn with_open_tasks(self, open: u32) -> Self {
+        Self { effort: (open as f64 / FULL_EFFORT_TASKS).min(1.0), ..self }
+    }
+
+    pub fn get(&self, name: &str) -> Option<f64> {
+        match name {
+            "needs_human" => Some(self.needs_human),
+            "risk" => Some(self.risk),
+            "staleness" => Some(self.staleness),
+            "impact" => Some(self.impact),
+            "confidence" => Some(self.confidence),
+            "effort" => Some(self.effort),
+            _ => None,
+        }
+    }
+}
+
+/// A factor's share of an additive score: `weight * value` points
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PriorityTerm {
+    pub factor: String,
+    pub value: f64,
+    /// Negative for factors that lower the score
+    pub weight: f64,
+}
+
+impl PriorityTerm {
+    pub fn new(factor: &str, value: f64, weight: f64) -> Self {
+        Self { factor: factor.to_string(), value, weight }
+    }
+
+    pub fn points(&self) -> f64 {
+        self.weight * self.value
+    }
+}
+
+/// The raw project data behind the factors
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PriorityInputs {
+    /// Risk level 0-3, including vulnerabilities and stale dependencies
+    pub risk_level: u8,
+    /// Impact 1-3, 2 unless set in the project meta
+    pub impact: u8,
+    pub approved_by_human: bool,
+    pub last_activity: DateTime<Utc>,
+    /// Whole days from the last activity to the staleness clock (which stops out of season)
+    pub idle_days: i64,
+    pub open_tasks: u32,
+}
+
+/// Why a project got its priority: the strategy, the factors with their raw
+/// inputs and, for additive strategies, each factor's points
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PriorityBreakdown {
+    pub strategy: String,
+    pub inputs: PriorityInputs,
+    pub factors: PriorityFactors,
+    /// Empty for strategies that don't add up factors (WSJF, expressions)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub terms: Vec<PriorityTerm>,
+    /// The strategy's score, before done projects are set to 0
+    pub score: f64,
+    /// Points added to the score from the project's `priority_boost`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub boost: Option<f64>,
+    /// The project's `priority_override`, used instead of the score
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority_override: Option<f64>,
+}
+
+/// A prioritization scheme: turns a project's factors into a score, highest first
+pub trait PriorityCalculator: Send + Sync {
+    fn score(&self, factors: &PriorityFactors) -> f64;
+
+    /// The score as a sum of per-factor points, for schemes that are one
+    fn terms(&self, _factors: &PriorityFactors) -> Vec<PriorityTerm> {
+        Vec::new()
+    }
+
+    /// Calculate priority score for a project
+    fn calculate(
+        &self,
+        requires_human: &[HumanRequirement],
+        risk_level: u8,
+        last_updated: DateTime<Utc>,
+        impact: u8,
+        confidence: u8,
+    ) -> f64 {
+        self.calculate_at(requires_human, risk_level, last_updated, impact, confidence, Utc::now())
+    }
+
+    /// Like [`PriorityCalculator::calculate`], with staleness measured at `now`
+    fn calculate_at(
+        &self,
+        requires_human: &[HumanRequirement],
+        risk_level: u8,
+        last_updated: DateTime<Utc>,
+        impact: u8,
+        confidence: u8,
+        now: DateTime<Utc>,
+    ) -> f64 {
+        self.score(&PriorityFactors::new(requires_human, risk_level, last_updated, impact, confidence, now))
+    }
+}
+
+/// The default scheme
+///
+/// Formula: w1*NeedsHuman + w2*Risk + w3*Staleness + w4*Impact - w5*Confidence
+pub struct WeightedSum {
+    pub weights: PriorityWeights,
+}
+
+impl WeightedSum {
+    pub fn new(weights: PriorityWeights) -> Self {
+        Self { weights }
+    }
+}
+
+impl PriorityCalculator for WeightedSum {
+    fn score(&self, f: &PriorityFactors) -> f64 {
+        self.weights.needs_human * f.needs_human
+            + self.weights.risk * f.risk
+            + self.weights.staleness * f.staleness
+            + self.weights.impact * f.impact
+            - self.weights.confidence * f.confidence
+    }
+
+    fn terms(&self, f: &PriorityFactors) -> Vec<PriorityTerm> {
+        vec![
+            PriorityTerm::new("needs_human", f.needs_human, self.weights.needs_human),
+            PriorityTerm::new("risk", f.risk, self.weights.risk),
+            PriorityTerm::new("staleness", f.staleness, self.weights.staleness),
+            PriorityTerm::new("impact", f.impact, self.weights.impact),
+            PriorityTerm::new("confidence", f.confidence, -self.weights.confidence),
+        ]
+    }
+}
+
+/// Weighted shortest job first: cost of delay over job size
+///
+/// Cost of delay adds impact (business value), staleness (time
+/// criticality) and the larger of risk and needs-human (risk reduction);
+/// job size grows from 1 to 4 with the open tasks. Scores run 0-100.
+pub struct Wsjf;
+
+impl PriorityCalculator for Wsjf {
+    fn score(&self, f: &PriorityFactors) -> f64 {
+        let cost_of_delay = f.impact + f.staleness + f.risk.max(f.needs_human);
+        let job_size = 1.0 + 3.0 * f.effort;
+        100.0 / 3.0 * cost_of_delay / job_size
+    }
+}
+
+/// Neglected projects first: 70*Staleness + 20*NeedsHuman + 10*Risk
+pub struct StalenessFirst;
+
+impl PriorityCalculator for StalenessFirst {
+    fn score(&self, f: &PriorityFactors) -> f64 {
+        70.0 * f.staleness + 20.0 * f.needs_human + 10.0 * f.risk
+    }
+
+    fn terms(&self, f: &PriorityFactors) -> Vec<PriorityTerm> {
+        vec![
+            PriorityTerm::new("staleness", f.staleness, 70.0),
+            PriorityTerm::new("needs_human", f.needs_human, 20.0),
+            PriorityTerm::new("risk", f.risk, 10.0),
+        ]
+    }
+}
+
+/// A user-defined score over the factors, e.g. `50*staleness + 30*risk - 10*confidence`
+///
+/// Numbers and factor names (see [`PriorityFactors::NAMES`]) combine with
+/// `+`, `-`, `*`, `/` and parentheses. A result that is not a finite number
+/// (a division by zero) scores 0.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PriorityExpression {
+    source: String,
+    root: Expr,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Number(f64),
+    Factor(&'static str),
+    Neg(Box<Expr>),
+    Binary(char, Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+    fn eval(&self, factors: &PriorityFactors) -> f64 {
+        match self {
+            Expr::Number(n) => *n,
+            Expr::Factor(name) => factors.get(name).unwrap_or(0.0),
+            Expr::Neg(inner) => -inner.eval(factors),
+            Expr::Binary(op, left, right) => {
+                let (left, right) = (left.eval(factors), right.eval(factors));
+                match op {
+                    '+' => left + right,
+                    '-' => left - right,
+                    '*' => left * right,
+                    _ => left / right,
+                }
+            }
+        }
+    }
+}
+
+impl PriorityCalculator for PriorityExpression {
+    fn score(&self, factors: &PriorityFactors) -> f64 {
+        let score = self.root.eval(factors);
+        if score.is_finite() { score } else { 0.0 }
+    }
+}
+
+impl std::fmt::Display for PriorityExpression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+impl std::str::FromStr for PriorityExpression {
+    type Err = SKMError;
+
+    fn from_str(s: &str) -> crate::Result<Self> {
+        let invalid = |reason: String| SKMError::ConfigError {
+            message: format!("Invalid priority expression: {} ({})", s, reason),
+        };
+        let mut tokens = Vec::new();
+        let mut chars = s.char_indices().peekable();
+        while let Some(&(start, c)) = chars.peek() {
+            if c.is_whitespace() {
+                chars.next();
+            } else if "+-*/()".contains(c) {
+                tokens.push(Token::Symbol(c));
+                chars.next();
+            } else if c.is_ascii_digit() || c == '.' || c.is_ascii_alphabetic() || c == '_' {
+                let mut end = start;
+                while let Some(&(i, c)) = chars.peek() {
+                    if !(c.is_ascii_alphanumeric() || c == '.' || c == '_') {
+                        break;
+                    }
+                    end = i + c.len_utf8();
+                    chars.next();
+                }
+                let word = &s[start..end];
+                tokens.push(match word.parse::<f64>() {
+                    Ok(number) => Token::Number(number),
+                    Err(_) => Token::Factor(PriorityFactors::NAMES.iter().find(|name| **name == word)
+                        .ok_or_else(|| invalid(format!("unknown factor {}; expected one of {}", word, PriorityFactors::NAMES.join(", "))))?),
+                });
+            } else {
+                return Err(invalid(format!("unexpected {}", c)));
+            }
+        }
+        let mut parser = ExprParser { tokens, pos: 0 };
+        let root = parser.sum().map_err(invalid)?;
+        if parser.pos < parser.tokens.len() {
+            return Err(invalid("unexpected input after the expression".to_string()));
+        }
+        Ok(PriorityExpression { source: s.trim().to_string(), root })
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Token {
+    Number(f64),
+    Factor(&'static str),
+    Symbol(char),
+}
+
+struct ExprParser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl ExprParser {
+    fn symbol(&mut self, symbols: &str) -> Option<char> {
+        match self.tokens.get(self.pos) {
+            Some(Token::Symbol(c)) if symbols.contains(*c) => {
+                self.pos += 1;
+                Some(*c)
+            }
+            _ => None,
+        }
+    }
+
+    fn sum(&mut self) -> Result<Expr, String> {
+        let mut left = self.product()?;
+        while let Some(op) = self.symbol("+-") {
+            left = Expr::Binary(op, Box::new(left), Box::new(self.product()?));
+        }
+        Ok(left)
+    }
+
+    fn product(&mut self) -> Result<Expr, String> {
+        let mut left = self.unary()?;
+        while let Some(op) = self.symbol("*/") {
+            left = Expr::Binary(op, Box::new(left), Box::new(self.unary()?));
+        }
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<Expr, String> {
+        if self.symbol("-").is_some() {
+            return Ok(Expr::Neg(Box::new(self.unary()?)));
+        }
+        if self.symbol("(").is_some() {
+            let inner = self.sum()?;
+            return match self.symbol(")") {
+                Some(_) => Ok(inner),
+                None => Err("missing )".to_string()),
+            };
+        }
+        let token = self.tokens.get(self.pos).copied();
+        self.pos += 1;
+        match token {
+            Some(Token::Number(n)) => Ok(Expr::Number(n)),
+            Some(Token::Factor(name)) => Ok(Expr::Factor(name)),
+            Some(Token::Symbol(c)) => Err(format!("unexpected {}", c)),
+            None => Err("the expression ends early".to_string()),
+        }
+    }
+}
+
+/// Which [`PriorityCalculator`] scans use, from `priority_strategy` in the config
+///
+/// Written as `weighted` (the default, using `[weights]`), `wsjf`,
+/// `staleness-first`, or an expression over the factors.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum PriorityStrategy {
+    #[default]
+    Weighted,
+    Wsjf,
+    StalenessFirst,
+    Expression(PriorityExpression),
+}
+
+impl PriorityStrategy {
+    pub fn calculator(&self, weights: &PriorityWeights) -> Box<dyn PriorityCalculator> {
+        match self {
+            PriorityStrategy::Weighted => Box::new(WeightedSum::new(weights.clone())),
+            PriorityStrategy::Wsjf => Box::new(Wsjf),
+            PriorityStrategy::StalenessFirst => Box::new(StalenessFirst),
+            PriorityStrategy::Expression(expression) => Box::new(expression.clone()),
+        }
+    }
+}
+
+impl std::fmt::Display for PriorityStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PriorityStrategy::Weighted => f.write_str("weighted"),
+            PriorityStrategy::Wsjf => f.write_str("wsjf"),
+            PriorityStrategy::StalenessFirst => f.write_str("staleness-first"),
+            PriorityStrategy::Expression(expression) => expression.fmt(f),
+        }
+    }
+}
+
+impl std::str::FromStr for PriorityStrategy {
+    type Err = SKMError;
+
+    fn from_str(s: &str) -> crate::Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "weighted" => Ok(PriorityStrategy::Weighted),
+            "wsjf" => Ok(PriorityStrategy::Wsjf),
+            "staleness-first" => Ok(PriorityStrategy::StalenessFirst),
+            _ => s.parse().map(PriorityStrategy::Expression).map_err(|e: SKMError| SKMError::ConfigError {
+                message: format!("Unknown priority strategy: {} (expected weighted, wsjf, staleness-first or an expression; {})",
+                    s, e.to_string().trim_start_matches("Configuration error: ")),
+            }),
+        }
+    }
+}
+
+impl TryFrom<String> for PriorityStrategy {
+    type Error = SKMError;
+
+    fn try_from(s: String) -> crate::Result<Self> {
+        s.parse()
+    }
+}
+
+impl From<PriorityStrategy> for String {
+    fn from(strategy: PriorityStrategy) -> Self {
+        strategy.to_string()
+    }
+}
+
+/// Calculate risk level (0-3) based on various factors
+pub fn calculate_risk(
+    _stage: &Stage,
+    git_status: &GitStatus,
+    tasks: &TaskSummary,
+    has_errors: bool,
+    scope_creep: bool,
+) -> u8 {
+    let mut risk = 0;
+    
+    // Add risk when the spec grew substantially after planning
+    if scope_creep {
+        risk += 1;
+    }
+    
+    // Add risk for build/test errors
+    if has_errors {
+        risk += 1;
+    }
+    
+    // Add risk for many parallel branches
+    if tasks.parallel_marked > 3 {
+        risk += 1;
+    }
+    
+    // Add risk for blocked tasks
+    if tasks.blocked > 0 {
+        risk += 1;
+    }
+    
+    // Add risk for uncommitted changes (never for projects outside git)
+    if git_status.is_repo && !git_status.clean {
+        risk += 1;
+    }
+    
+    // Add risk for pull requests with failing checks
+    if git_status.github.as_ref().is_some_and(|g| g.failing_checks > 0) {
+        risk += 1;
+    }
+    
+    // Cap at 3
+    risk.min(3)
+}
+
+/// Extra risk from known dependency vulnerabilities: one level for any,
+/// two for five or more
+pub fn vulnerability_risk(vulnerabilities: u32) -> u8 {
+    match vulnerabilities {
+        0 => 0,
+        1..=4 => 1,
+        _ => 2,
+    }
+}
+
+/// Extra risk from stale dependencies: one level when at least five, or a
+/// quarter, of the direct dependencies are a major version behind
+pub fn freshness_risk(major_behind: u32, direct: u32) -> u8 {
+    (major_behind > 0 && (major_behind >= 5 || major_behind * 4 >= direct)) as u8
+}
+
+/// Normalize risk to 0-1 range
+fn normalize_risk(risk: u8) -> f64 {
+    (risk as f64) / 3.0
+}
+
+/// Calculate staleness based on days since last update (normalized to 0-1, max 7 days)
+fn calculate_staleness(last_updated: DateTime<Utc>, now: DateTime<Utc>) -> f64 {
+    let duration = now.signed_duration_since(last_updated);
+    let days = duration.num_days() as f64;
+
+    // Normalize to 0-1 range, max at 7 days
+    (days / 7.0).clamp(0.0, 1.0)
+}
+
+/// Normalize impact (1-3) to 0-1 range
+fn normalize_impact(impact: u8) -> f64 {
+    match impact {
+        1 => 0.33,
+        2 => 0.66,
+        3 => 1.0,
+        _ => 0.5, // Default middle value
+    }
+}
+
+/// Normalize confidence (0-2) to 0-1 range
+fn normalize_confidence(confidence: u8) -> f64 {
+    (confidence as f64) / 2.0
+}
+
+/// Determine which human requirements are needed based on stage
+pub fn detect_human_requirements(
+    stage: &Stage,
+    git_status: &GitStatus,
+    tasks: &TaskSummary,
+    open_questions: usize,
+) -> Vec<HumanRequirement> {
+    let mut requirements = Vec::new();
+    
+    match stage {
+        Stage::Bootstrap | Stage::Specify | Stage::Clarify | Stage::Plan => {
+            requirements.push(HumanRequirement::Input);
+        }
+        Stage::Review => {
+            requirements.push(HumanRequirement::Review);
+        }
+        Stage::Test if tasks.completed < tasks.total => {
+            requirements.push(HumanRequirement::Test);
+        }
+        _ => {}
+    }
+    
+    // Unanswered clarification questions need human input at any stage
+    if open_questions > 0 && !requirements.contains(&HumanRequirement::Input) {
+        requirements.push(HumanRequirement::Input);
+    }
+    
+    // Add Fix requirement if there are uncommitted changes (never for projects outside git)
+    if git_status.is_repo && !git_status.clean {
+        requirements.push(HumanRequirement::Fix);
+    }
+    
+    // Pull requests waiting for the user's review, or with failing checks
+    if let Some(github) = &git_status.github {
+        if github.review_requests > 0 && !requirements.contains(&HumanRequirement::Review) {
+            requirements.push(HumanRequirement::Review);
+        }
+        if github.failing_checks > 0 && !requirements.contains(&HumanRequirement::Fix) {
+            requirements.push(HumanRequirement::Fix);
+        }
+    }
+    
+    // Add Decision requirement if there are blocked tasks
+    if tasks.blocked > 0 {
+        requirements.push(HumanRequirement::Decision);
+    }
+    
+    requirements
+}
+
+/// Check if there are error markers in build/test output files
+pub fn has_error_markers(_project_path: &std::path::Path) -> bool {
+    // This is a simplified check - in reality would scan actual files
+    // For now, return false to avoid false positives
+    // TODO: Implement actual file scanning for error patterns
+    false
+}
\ No newline at end of file
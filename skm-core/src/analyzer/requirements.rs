@@ -0,0 +1,85 @@
+use std::collections::BTreeMap;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use crate::HumanRequirement;
+
+/// A human requirement from the scan that first raised it to the scan that no longer did
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RequirementEpisode {
+    pub requirement: HumanRequirement,
+    pub raised: DateTime<Utc>,
+    /// When a scan found it addressed; `None` while it is still open
+    #[serde(default)]
+    pub resolved: Option<DateTime<Utc>>,
+}
+
+impl RequirementEpisode {
+    /// Hours from raised to resolved, or to `now` while open
+    pub fn hours(&self, now: DateTime<Utc>) -> f64 {
+        (self.resolved.unwrap_or(now) - self.raised).num_seconds().max(0) as f64 / 3600.0
+    }
+}
+
+/// Open an episode for each newly raised requirement and resolve those a
+/// scan at `at` no longer raises
+///
+/// Requirements are derived from the project's state on every scan, so one
+/// disappears as soon as a person addresses it: a commit cleans the tree,
+/// open questions get answered, a review moves the stage on.
+pub fn reconcile(episodes: &mut Vec<RequirementEpisode>, current: &[HumanRequirement], at: DateTime<Utc>) {
+    for episode in episodes.iter_mut().filter(|e| e.resolved.is_none()) {
+        if !current.contains(&episode.requirement) {
+            episode.resolved = Some(at);
+        }
+    }
+    for requirement in current {
+        if !episodes.iter().any(|e| e.resolved.is_none() && e.requirement == *requirement) {
+            episodes.push(RequirementEpisode { requirement: requirement.clone(), raised: at, resolved: None });
+        }
+    }
+}
+
+/// How quickly one kind of requirement gets addressed
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct TimeToAddress {
+    pub requirement: HumanRequirement,
+    /// Episodes resolved since the start of the window
+    pub resolved: u32,
+    /// Mean hours from raised to resolved, over the resolved episodes
+    pub mean_hours: Option<f64>,
+    /// Episodes still open
+    pub open: u32,
+    /// Hours the oldest open episode has waited so far
+    pub oldest_open_hours: Option<f64>,
+}
+
+/// Mean time to address per requirement type, for episodes resolved after
+/// `since` and those still open at `now`
+///
+/// Types without any such episode are left out; the result follows the
+/// declaration order of [`HumanRequirement`].
+pub fn time_to_address<'a>(
+    episodes: impl IntoIterator<Item = &'a RequirementEpisode>,
+    since: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> Vec<TimeToAddress> {
+    let mut by_type: BTreeMap<HumanRequirement, (Vec<f64>, Vec<f64>)> = BTreeMap::new();
+    for episode in episodes {
+        match episode.resolved {
+            Some(resolved) if resolved > since => {
+                by_type.entry(episode.requirement.clone()).or_default().0.push(episode.hours(now));
+            }
+            Some(_) => {}
+            None => by_type.entry(episode.requirement.clone()).or_default().1.push(episode.hours(now)),
+        }
+    }
+    by_type.into_iter()
+        .map(|(requirement, (resolved, open))| TimeToAddress {
+            requirement,
+            resolved: resolved.len() as u32,
+            mean_hours: (!resolved.is_empty()).then(|| resolved.iter().sum::<f64>() / resolved.len() as f64),
+            open: open.len() as u32,
+            oldest_open_hours: open.into_iter().reduce(f64::max),
+        })
+        .collect()
+}
@@ -0,0 +1,131 @@
+use std::fmt;
+use std::str::FromStr;
+use chrono::{DateTime, Datelike, Months, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use crate::{Result, SKMError};
+
+const MONTHS: [&str; 12] = ["jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec"];
+
+/// Months of the year a seasonal project is worked on, e.g. `Q4`,
+/// `Q1,Q3`, `oct-dec`, `11-2` (wrapping over the new year) or `jun`
+///
+/// Kept as written, so `meta get` shows what was set.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct ActiveWindow {
+    spec: String,
+    /// Bit `n` is set when month `n + 1` is in the window
+    months: u16,
+}
+
+/// Where a project with an active window stands at scan time
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Season {
+    pub window: ActiveWindow,
+    pub active: bool,
+    /// The day the window last opened or closed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub since: Option<NaiveDate>,
+}
+
+impl ActiveWindow {
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        self.months & (1 << date.month0()) != 0
+    }
+
+    /// The first day of the month the window last opened or closed, on or
+    /// before `date`; `None` for a window covering every month
+    pub fn last_change(&self, date: NaiveDate) -> Option<NaiveDate> {
+        let inside = self.contains(date);
+        let mut month = date.with_day(1)?;
+        for _ in 0..12 {
+            let previous = month.checked_sub_months(Months::new(1))?;
+            if self.contains(previous) != inside {
+                return Some(month);
+            }
+            month = previous;
+        }
+        None
+    }
+
+    /// Whether the project is in season at `now`, and since when
+    pub fn season(&self, now: DateTime<Utc>) -> Season {
+        let today = now.date_naive();
+        Season { window: self.clone(), active: self.contains(today), since: self.last_change(today) }
+    }
+
+    /// The moment staleness is measured at: `now` in season, else the
+    /// start of the day the window closed, so a paused project does not
+    /// grow staler
+    pub fn staleness_clock(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        let today = now.date_naive();
+        match self.last_change(today) {
+            Some(closed) if !self.contains(today) => closed.and_hms_opt(0, 0, 0).map_or(now, |at| at.and_utc()),
+            _ => now,
+        }
+    }
+}
+
+/// A month by number (`1`-`12`) or three-letter name
+fn parse_month(s: &str) -> Option<u32> {
+    let s = s.trim().to_ascii_lowercase();
+    match s.parse::<u32>() {
+        Ok(month) => (1..=12).contains(&month).then_some(month),
+        Err(_) => MONTHS.iter().position(|name| s.starts_with(name) && s.len() >= 3).map(|i| i as u32 + 1),
+    }
+}
+
+impl FromStr for ActiveWindow {
+    type Err = SKMError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || SKMError::ConfigError {
+            message: format!("Invalid active window: {} (expected quarters like Q4 or months like oct-dec)", s),
+        };
+        let mut months = 0u16;
+        for part in s.split(',').map(str::trim) {
+            let (first, last) = match part.to_ascii_lowercase().strip_prefix('q') {
+                Some(quarter) => {
+                    let quarter: u32 = quarter.parse().ok().filter(|q| (1..=4).contains(q)).ok_or_else(invalid)?;
+                    (quarter * 3 - 2, quarter * 3)
+                }
+                None => match part.split_once('-') {
+                    Some((first, last)) => (parse_month(first).ok_or_else(invalid)?, parse_month(last).ok_or_else(invalid)?),
+                    None => {
+                        let month = parse_month(part).ok_or_else(invalid)?;
+                        (month, month)
+                    }
+                },
+            };
+            let mut month = first;
+            loop {
+                months |= 1 << (month - 1);
+                if month == last {
+                    break;
+                }
+                month = month % 12 + 1;
+            }
+        }
+        Ok(ActiveWindow { spec: s.trim().to_string(), months })
+    }
+}
+
+impl TryFrom<String> for ActiveWindow {
+    type Error = SKMError;
+
+    fn try_from(s: String) -> Result<Self> {
+        s.parse()
+    }
+}
+
+impl From<ActiveWindow> for String {
+    fn from(window: ActiveWindow) -> Self {
+        window.spec
+    }
+}
+
+impl fmt::Display for ActiveWindow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.spec)
+    }
+}
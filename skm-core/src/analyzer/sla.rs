@@ -0,0 +1,73 @@
+use chrono::{DateTime, Datelike, Duration, NaiveTime, Utc, Weekday};
+use serde::{Deserialize, Serialize};
+
+/// How long a project has waited in Review against the `[sla]` limit
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ReviewSla {
+    /// When the project entered Review
+    pub since: DateTime<Utc>,
+    /// `since` plus the limit in business days
+    pub due: DateTime<Utc>,
+    pub limit_days: u32,
+    /// Whole business days waited so far
+    pub waited_days: u32,
+    pub breached: bool,
+}
+
+impl ReviewSla {
+    pub fn new(since: DateTime<Utc>, limit_days: u32, now: DateTime<Utc>) -> Self {
+        let due = add_business_days(since, limit_days);
+        ReviewSla {
+            since,
+            due,
+            limit_days,
+            waited_days: business_days_between(since, now),
+            breached: now > due,
+        }
+    }
+
+    /// E.g. "4 business days in Review, over the 3-day SLA"
+    pub fn summary(&self) -> String {
+        format!("{} business day(s) in Review, {} the {}-day SLA",
+            self.waited_days, if self.breached { "over" } else { "within" }, self.limit_days)
+    }
+}
+
+fn is_weekend(at: DateTime<Utc>) -> bool {
+    matches!(at.weekday(), Weekday::Sat | Weekday::Sun)
+}
+
+/// `start` moved on by `days` business days (Monday to Friday, UTC)
+///
+/// A start on a weekend counts from the following Monday at midnight, so
+/// Friday 17:00 plus 3 is Wednesday 17:00 and Saturday plus 1 is Tuesday
+/// 00:00.
+pub fn add_business_days(start: DateTime<Utc>, days: u32) -> DateTime<Utc> {
+    let mut at = start;
+    if is_weekend(at) {
+        while is_weekend(at) {
+            at += Duration::days(1);
+        }
+        at = at.date_naive().and_time(NaiveTime::MIN).and_utc();
+    }
+    for _ in 0..days {
+        at += Duration::days(1);
+        while is_weekend(at) {
+            at += Duration::days(1);
+        }
+    }
+    at
+}
+
+/// Whole business days from `start` to `end`; 0 when `end` comes first
+pub fn business_days_between(start: DateTime<Utc>, end: DateTime<Utc>) -> u32 {
+    let mut at = add_business_days(start, 0);
+    let mut days = 0;
+    loop {
+        at = add_business_days(at, 1);
+        if at > end {
+            return days;
+        }
+        days += 1;
+    }
+}
@@ -0,0 +1,88 @@
+use std::collections::HashMap;
+use chrono::{DateTime, Utc};
+use crate::{PortfolioStatus, Project};
+use crate::meta::config::SprintConfig;
+
+/// Portfolio change within one sprint, derived from the history log
+#[derive(Debug, Clone, PartialEq)]
+pub struct SprintRollup {
+    pub number: u32,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    /// Stage changes observed across all projects
+    pub stage_transitions: u32,
+    pub tasks_completed: u32,
+    pub new_projects: u32,
+    /// Projects in the last state logged during the sprint
+    pub projects: u32,
+}
+
+/// Roll logged portfolio states (oldest first) up into sprints
+///
+/// Each sprint is measured against the last state logged before it began,
+/// or its own first state when there is none. Sprints without any logged
+/// state are skipped.
+pub fn sprint_rollups(log: &[PortfolioStatus], sprint: &SprintConfig) -> Vec<SprintRollup> {
+    let mut rollups = Vec::new();
+    let mut previous: Option<&PortfolioStatus> = None;
+    let mut i = 0;
+
+    while i < log.len() {
+        let Some(number) = sprint.number_at(log[i].generated_at) else {
+            previous = Some(&log[i]);
+            i += 1;
+            continue;
+        };
+        let len = log[i..].iter()
+            .take_while(|state| sprint.number_at(state.generated_at) == Some(number))
+            .count();
+        let states = &log[i..i + len];
+        rollups.push(rollup(number, sprint, previous, states));
+        previous = states.last();
+        i += len;
+    }
+
+    rollups
+}
+
+fn rollup(
+    number: u32,
+    sprint: &SprintConfig,
+    previous: Option<&PortfolioStatus>,
+    states: &[PortfolioStatus],
+) -> SprintRollup {
+    let (start, end) = sprint.bounds(number);
+    let sequence: Vec<HashMap<String, &Project>> = previous.into_iter()
+        .chain(states)
+        .map(by_name)
+        .collect();
+    let baseline = &sequence[0];
+    let last = &sequence[sequence.len() - 1];
+
+    let stage_transitions = sequence.windows(2)
+        .map(|pair| pair[1].iter()
+            .filter(|(name, project)| pair[0].get(*name).is_some_and(|before| before.stage != project.stage))
+            .count() as u32)
+        .sum();
+    let tasks_completed = last.iter()
+        .map(|(name, project)| {
+            let before = baseline.get(name).map(|p| p.tasks.completed).unwrap_or(0);
+            project.tasks.completed.saturating_sub(before)
+        })
+        .sum();
+    let new_projects = last.keys().filter(|name| !baseline.contains_key(*name)).count() as u32;
+
+    SprintRollup {
+        number,
+        start,
+        end,
+        stage_transitions,
+        tasks_completed,
+        new_projects,
+        projects: last.len() as u32,
+    }
+}
+
+fn by_name(status: &PortfolioStatus) -> HashMap<String, &Project> {
+    status.projects.iter().map(|p| (p.display_name(), p)).collect()
+}
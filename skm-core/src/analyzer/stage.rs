@@ -0,0 +1,312 @@
+use std::fs;
+use std::path::Path;
+use walkdir::WalkDir;
+use crate::{Stage, NextAction, AutomationLevel, ArtifactStatus, ProjectType, TaskSummary, TestEvidence};
+use crate::meta::config::WorkflowConfig;
+use crate::scanner::{finder::should_ignore, parser, todos::SOURCE_EXTENSIONS};
+
+/// Upper bound on directory entries visited when looking for source code
+const SOURCE_ENTRY_LIMIT: usize = 2_000;
+
+/// Non-blank source lines that count as an implementation rather than a
+/// scaffold such as `cargo new`'s hello world
+const MIN_SOURCE_LINES: usize = 20;
+
+/// Directories holding tests, which do not count as implementation
+const TEST_DIRS: &[&str] = &["tests", "test", "__tests__", "spec", "benches", "examples", "fixtures"];
+
+/// Evidence from artifacts and test runs that moves a project between stages
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StageSignals {
+    /// The spec has a `Clarifications` section, written by `/speckit.clarify`
+    pub clarified: bool,
+    /// An `analysis.md` sits next to the tasks, or the tasks or plan have an `Analysis` section
+    pub analyzed: bool,
+    /// At least one task has been completed
+    pub tasks_started: bool,
+    /// Every task has been completed
+    pub tasks_done: bool,
+    /// Outcome of the newest test run since the tasks last changed, from
+    /// `skm test`, result files or CI (see [`crate::analyzer::testrun::latest_outcome`])
+    pub tests_passed: Option<bool>,
+    /// The project has source code beyond a scaffold (see [`has_implementation_artifacts`])
+    pub implemented: bool,
+    /// The tasks file has `status: done` in its front matter
+    pub marked_done: bool,
+    /// Committed and pushed or merged (see [`crate::GitStatus::is_shipped`])
+    pub shipped: bool,
+}
+
+impl StageSignals {
+    /// Collect signals from the artifacts on disk; `implemented` and
+    /// `shipped` are left to the caller, since they concern the whole project
+    pub fn collect(artifacts: &ArtifactStatus, tasks: &TaskSummary, tests: Option<&TestEvidence>) -> Self {
+        let section_in = |file: &Option<crate::FileInfo>, title: &str| {
+            file.as_ref()
+                .and_then(|f| fs::read_to_string(&f.path).ok())
+                .is_some_and(|content| parser::has_heading(&content, title))
+        };
+
+        let marked_done = artifacts.tasks.as_ref()
+            .and_then(|f| fs::read_to_string(&f.path).ok())
+            .is_some_and(|content| parser::is_marked_done(&content));
+
+        let analysis_file = artifacts.tasks.as_ref()
+            .and_then(|f| f.path.parent())
+            .is_some_and(|dir| dir.join("analysis.md").exists());
+
+        Self {
+            clarified: section_in(&artifacts.spec, "Clarifications"),
+            analyzed: analysis_file
+                || section_in(&artifacts.tasks, "Analysis")
+                || section_in(&artifacts.plan, "Analysis"),
+            tasks_started: tasks.completed > 0,
+            tasks_done: tasks.total > 0 && tasks.completed == tasks.total,
+            tests_passed: tests.map(|evidence| evidence.passed),
+            implemented: false,
+            marked_done,
+            shipped: false,
+        }
+    }
+}
+
+/// Detect the current stage of a project based on artifacts
+///
+/// `Clarify` and `Analyze` are only reported when enabled in the workflow
+/// config, and only until there is evidence the step was performed; a
+/// project that already has a plan is never sent back to clarify. With
+/// every task done, source code behind them moves a project on to `Test`,
+/// and a passing test run to `Review`, while a failing one keeps it in
+/// `Test`. Done projects are those whose tasks
+/// file says so, and those with every task done, code or a passing test run
+/// behind them, no failing test run and the work committed and pushed or
+/// merged.
+pub fn detect_stage(
+    artifacts: &ArtifactStatus,
+    signals: &StageSignals,
+    workflow: &WorkflowConfig,
+) -> Stage {
+    if artifacts.constitution.is_none() {
+        return Stage::Bootstrap;
+    }
+    
+    if artifacts.spec.is_none() {
+        return Stage::Specify;
+    }
+    
+    if artifacts.plan.is_none() {
+        if workflow.clarify && !signals.clarified {
+            return Stage::Clarify;
+        }
+        return Stage::Plan;
+    }
+    
+    if artifacts.tasks.is_none() {
+        return Stage::Tasks;
+    }
+    
+    if signals.marked_done {
+        return Stage::Done;
+    }
+    
+    if workflow.analyze && !signals.analyzed && !signals.tasks_started {
+        return Stage::Analyze;
+    }
+    
+    if signals.tasks_done && signals.shipped && signals.tests_passed != Some(false)
+        && (signals.implemented || signals.tests_passed == Some(true))
+    {
+        return Stage::Done;
+    }
+    
+    // Once all tasks are done, the latest test outcome decides between test and review
+    if signals.tasks_done {
+        match signals.tests_passed {
+            Some(true) => return Stage::Review,
+            Some(false) => return Stage::Test,
+            None => {}
+        }
+    }
+    
+    // Checked-off tasks without code behind them are not ready for testing
+    if signals.tasks_done && signals.implemented {
+        return Stage::Test;
+    }
+    Stage::Implement
+}
+
+/// Determine the next action based on the current stage
+pub fn get_next_action(stage: &Stage) -> NextAction {
+    match stage {
+        Stage::Bootstrap => NextAction {
+            command: "/speckit.constitution".to_string(),
+            description: "Create project constitution to establish core values and principles".to_string(),
+            automated: false,
+            risk_level: AutomationLevel::L2,
+            launch: Vec::new(),
+        },
+        Stage::Specify => NextAction {
+            command: "/speckit.specify".to_string(),
+            description: "Create specification with user stories and requirements".to_string(),
+            automated: false,
+            risk_level: AutomationLevel::L2,
+            launch: Vec::new(),
+        },
+        Stage::Clarify => NextAction {
+            command: "/speckit.clarify".to_string(),
+            description: "Resolve underspecified areas of the spec before planning".to_string(),
+            automated: false,
+            risk_level: AutomationLevel::L2,
+            launch: Vec::new(),
+        },
+        Stage::Plan => NextAction {
+            command: "/speckit.plan".to_string(),
+            description: "Create implementation plan with technical design".to_string(),
+            automated: false,
+            risk_level: AutomationLevel::L2,
+            launch: Vec::new(),
+        },
+        Stage::Tasks => NextAction {
+            command: "/speckit.tasks".to_string(),
+            description: "Generate task breakdown for implementation".to_string(),
+            automated: true,
+            risk_level: AutomationLevel::L1,
+            launch: Vec::new(),
+        },
+        Stage::Analyze => NextAction {
+            command: "/speckit.analyze".to_string(),
+            description: "Check spec, plan and tasks for consistency before implementing".to_string(),
+            automated: true,
+            risk_level: AutomationLevel::L1,
+            launch: Vec::new(),
+        },
+        Stage::Implement => NextAction {
+            command: "/speckit.implement".to_string(),
+            description: "Begin implementation of tasks".to_string(),
+            automated: false,
+            risk_level: AutomationLevel::L3,
+            launch: Vec::new(),
+        },
+        Stage::Test => NextAction {
+            command: "Run tests and verify implementation".to_string(),
+            description: "Execute test suite and validate functionality".to_string(),
+            automated: true,
+            risk_level: AutomationLevel::L1,
+            launch: Vec::new(),
+        },
+        Stage::Review => NextAction {
+            command: "Review code and documentation".to_string(),
+            description: "Perform code review and quality checks".to_string(),
+            automated: false,
+            risk_level: AutomationLevel::L1,
+            launch: Vec::new(),
+        },
+        // The configured template replaces this (see `CustomStage::next_action`)
+        Stage::Custom(name) => NextAction {
+            command: format!("Complete {:?}", stage),
+            description: format!("Finish the {} stage of the workflow", name),
+            automated: false,
+            risk_level: AutomationLevel::L1,
+            launch: Vec::new(),
+        },
+        Stage::Done => NextAction {
+            command: "Project complete".to_string(),
+            description: "All stages completed successfully".to_string(),
+            automated: false,
+            risk_level: AutomationLevel::L0,
+            launch: Vec::new(),
+        },
+    }
+}
+
+/// Whether a project has source code beyond a scaffold
+///
+/// Counts the non-blank lines of the files that make up an implementation
+/// for the project type: `.rs` files under a `src` directory for Rust,
+/// JavaScript and TypeScript other than tool config files (`*.config.js`,
+/// dotfiles, gulp and grunt files) for Node, modules other than `setup.py` for Python (packages, `src`
+/// layouts and single-file apps alike), non-test `.go` files for Go, and
+/// any source file otherwise. Tests, dependency,
+/// build, hidden and Spec-Kit directories are skipped, and the walk stops
+/// after a fixed number of entries.
+pub fn has_implementation_artifacts(project: &Path, project_type: &ProjectType) -> bool {
+    let mut lines = 0;
+    let entries = WalkDir::new(project)
+        .into_iter()
+        .filter_entry(|e| {
+            let name = e.file_name().to_string_lossy();
+            e.depth() == 0
+                || !e.file_type().is_dir()
+                || !(should_ignore(e.path()) || name.starts_with('.') || name == "specs" || name == "vendor"
+                    || TEST_DIRS.contains(&name.as_ref()))
+        })
+        .take(SOURCE_ENTRY_LIMIT)
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file());
+
+    for entry in entries {
+        let Ok(relative) = entry.path().strip_prefix(project) else { continue };
+        if !is_implementation_file(relative, project_type) {
+            continue;
+        }
+        let Ok(content) = fs::read_to_string(entry.path()) else { continue };
+        lines += content.lines().filter(|line| !line.trim().is_empty()).count();
+        if lines >= MIN_SOURCE_LINES {
+            return true;
+        }
+    }
+    false
+}
+
+/// Whether a file (relative to the project root) is implementation code for the project type
+fn is_implementation_file(relative: &Path, project_type: &ProjectType) -> bool {
+    let extension = relative.extension().and_then(|ext| ext.to_str()).unwrap_or_default();
+    let name = relative.file_name().and_then(|name| name.to_str()).unwrap_or_default();
+    let in_dir = |dir: &str| relative.parent().is_some_and(|parent| parent.iter().any(|c| c == dir));
+    let nested = relative.components().count() > 1;
+    match project_type {
+        ProjectType::Rust => extension == "rs" && in_dir("src"),
+        ProjectType::Node => {
+            let config_file = !nested
+                && (name.starts_with('.') || name.contains(".config.") || matches!(name, "gulpfile.js" | "Gruntfile.js"));
+            matches!(extension, "js" | "jsx" | "ts" | "tsx" | "mjs" | "cjs" | "vue" | "svelte")
+                && !config_file
+                && !name.contains(".test.") && !name.contains(".spec.") && !name.ends_with(".d.ts")
+        }
+        ProjectType::Python => {
+            extension == "py"
+                && !name.starts_with("test_") && !name.ends_with("_test.py")
+                && !matches!(name, "setup.py" | "conftest.py")
+        }
+        ProjectType::Go => extension == "go" && !name.ends_with("_test.go"),
+        ProjectType::Generic | ProjectType::Unknown => SOURCE_EXTENSIONS.contains(&extension) && !name.contains("test"),
+    }
+}
+
+/// Check if a project needs immediate human attention
+pub fn needs_human_attention(stage: &Stage) -> bool {
+    matches!(stage, 
+        Stage::Bootstrap | 
+        Stage::Specify | 
+        Stage::Clarify | 
+        Stage::Plan | 
+        Stage::Review
+    )
+}
+
+/// Get a human-readable description of the stage
+pub fn stage_description(stage: &Stage) -> &'static str {
+    match stage {
+        Stage::Bootstrap => "Needs constitution - establish project identity",
+        Stage::Specify => "Needs specification - define requirements",
+        Stage::Clarify => "Needs clarification - resolve spec ambiguities",
+        Stage::Plan => "Needs plan - design technical approach",
+        Stage::Tasks => "Needs tasks - break down work items",
+        Stage::Analyze => "Needs analysis - check artifact consistency",
+        Stage::Implement => "In implementation - coding in progress",
+        Stage::Test => "In testing - validating functionality",
+        Stage::Review => "In review - awaiting approval",
+        Stage::Custom(_) => "In a custom workflow stage - see [[workflow.stages]]",
+        Stage::Done => "Complete - all stages finished",
+    }
+}
\ No newline at end of file
@@ -0,0 +1,236 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use chrono::{DateTime, SubsecRound, Utc};
+use regex::Regex;
+use crate::{ProjectType, TestEvidence, TestSource};
+
+/// Go test output a project keeps in its root, plain or `go test -json`
+const GO_TEST_LOGS: &[&str] = &["go-test.log", "gotest.log", "go-test.json"];
+
+static JUNIT_SUITE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"<testsuites?\b[^>]*>").unwrap());
+static JUNIT_FAILURES: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"\b(?:failures|errors)="(\d+)""#).unwrap());
+static GO_JSON_ACTION: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#""Action":\s*"(pass|fail)""#).unwrap());
+
+/// Failure counts in a CI note: "3 failed", "failures: 0", "errors=2", "no errors"
+static NOTE_FAILURES: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\b(\d+|no)\s+(?:failed|failures?|failing|errors?)\b|\b(?:failed|failures?|failing|errors?)\s*[:=]\s*(\d+)").unwrap()
+});
+static NOTE_FAILED: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\b(?:fail|failed|failure|failures|failing|error|errors|errored|broken)\b").unwrap()
+});
+static NOTE_PASSED: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\b(?:pass|passed|passing|success|successful|succeeded|green)\b").unwrap()
+});
+
+/// Test command for a project type, when it has a conventional one
+pub fn default_test_command(project_type: &ProjectType) -> Option<&'static str> {
+    match project_type {
+        ProjectType::Rust => Some("cargo test"),
+        ProjectType::Node => Some("npm test"),
+        ProjectType::Python => Some("pytest"),
+        ProjectType::Go => Some("go test ./..."),
+        ProjectType::Generic | ProjectType::Unknown => None,
+    }
+}
+
+/// Cheap build or typecheck command for a project type
+///
+/// Node projects are only checked when they have a `tsconfig.json`; Python
+/// projects use mypy when it is configured and ruff otherwise.
+pub fn default_check_command(project_type: &ProjectType, path: &Path) -> Option<&'static str> {
+    match project_type {
+        ProjectType::Rust => Some("cargo check --all-targets"),
+        ProjectType::Node => path.join("tsconfig.json").exists().then_some("npx tsc --noEmit"),
+        ProjectType::Python => {
+            let mypy = path.join("mypy.ini").exists()
+                || std::fs::read_to_string(path.join("pyproject.toml"))
+                    .is_ok_and(|content| content.contains("[tool.mypy]"));
+            Some(if mypy { "mypy ." } else { "ruff check ." })
+        }
+        ProjectType::Go => Some("go vet ./..."),
+        ProjectType::Generic | ProjectType::Unknown => None,
+    }
+}
+
+/// Passed and failed test counts found in a runner's output
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TestCounts {
+    pub passed: u32,
+    pub failed: u32,
+}
+
+/// Parse pass/fail counts from cargo, pytest, jest, mocha or `go test -v` output
+///
+/// Returns `None` when no known summary format is found.
+pub fn parse_test_counts(output: &str) -> Option<TestCounts> {
+    // cargo prints one summary per test binary
+    let cargo = Regex::new(r"test result: \w+\. (\d+) passed; (\d+) failed").unwrap();
+    let mut counts: Option<TestCounts> = None;
+    for caps in cargo.captures_iter(output) {
+        let total = counts.get_or_insert_with(TestCounts::default);
+        total.passed += caps[1].parse::<u32>().unwrap_or(0);
+        total.failed += caps[2].parse::<u32>().unwrap_or(0);
+    }
+    if counts.is_some() {
+        return counts;
+    }
+
+    // pytest: "=== 3 passed, 1 failed in 0.12s ===", jest: "Tests: 1 failed, 5 passed, 6 total"
+    let summary = output.lines().rev().find(|line| {
+        let line = line.trim();
+        (line.starts_with('=') && line.contains(" in ")) || line.starts_with("Tests:")
+    });
+    if let Some(line) = summary {
+        let count = |word: &str| {
+            Regex::new(&format!(r"(\d+) {}", word)).unwrap()
+                .captures(line)
+                .and_then(|caps| caps[1].parse::<u32>().ok())
+        };
+        let (passed, failed) = (count("passed"), count("failed"));
+        if passed.is_some() || failed.is_some() {
+            return Some(TestCounts {
+                passed: passed.unwrap_or(0),
+                failed: failed.unwrap_or(0),
+            });
+        }
+    }
+
+    // mocha: "5 passing" / "1 failing"
+    let passing = Regex::new(r"(?m)^\s*(\d+) passing").unwrap().captures(output);
+    let failing = Regex::new(r"(?m)^\s*(\d+) failing").unwrap().captures(output);
+    if passing.is_some() || failing.is_some() {
+        return Some(TestCounts {
+            passed: passing.and_then(|c| c[1].parse().ok()).unwrap_or(0),
+            failed: failing.and_then(|c| c[1].parse().ok()).unwrap_or(0),
+        });
+    }
+
+    // go test -v
+    let passed = output.lines().filter(|l| l.trim_start().starts_with("--- PASS")).count() as u32;
+    let failed = output.lines().filter(|l| l.trim_start().starts_with("--- FAIL")).count() as u32;
+    (passed + failed > 0).then_some(TestCounts { passed, failed })
+}
+
+/// The test result files a project may have: JUnit reports (`junit.xml`,
+/// `test-results/*.xml`, `target/nextest/<profile>/junit.xml`), pytest's
+/// cache and saved `go test` logs, whether or not they exist
+pub fn result_files(project: &Path) -> Vec<PathBuf> {
+    let mut files = vec![
+        project.join("junit.xml"),
+        project.join(".pytest_cache/v/cache/nodeids"),
+        project.join(".pytest_cache/v/cache/lastfailed"),
+    ];
+    for dir in ["test-results", "target/nextest"] {
+        let mut found: Vec<PathBuf> = fs::read_dir(project.join(dir)).into_iter().flatten()
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .map(|path| if path.is_dir() { path.join("junit.xml") } else { path })
+            .filter(|path| path.extension().is_some_and(|ext| ext == "xml"))
+            .collect();
+        found.sort();
+        files.extend(found);
+    }
+    files.extend(GO_TEST_LOGS.iter().map(|name| project.join(name)));
+    files
+}
+
+/// Test outcomes left behind by the project's own tooling, one per report
+pub fn find_test_results(project: &Path) -> Vec<TestEvidence> {
+    let modified = |path: &Path| fs::metadata(path).and_then(|m| m.modified()).ok().map(DateTime::<Utc>::from);
+    let mut results = Vec::new();
+    for path in result_files(project) {
+        let Some(at) = modified(&path) else { continue };
+        let name = path.file_name().and_then(|name| name.to_str()).unwrap_or_default();
+        let (source, passed) = match name {
+            // pytest always writes `nodeids`; `lastfailed` lists the failures, if any
+            "nodeids" => {
+                let failed = fs::read_to_string(path.with_file_name("lastfailed")).ok()
+                    .and_then(|content| serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(&content).ok())
+                    .is_some_and(|failures| !failures.is_empty());
+                let at = modified(&path.with_file_name("lastfailed")).map_or(at, |failed_at| failed_at.max(at));
+                results.push(TestEvidence { source: TestSource::Pytest, at, passed: !failed, path: Some(path) });
+                continue;
+            }
+            "lastfailed" => continue,
+            _ if GO_TEST_LOGS.contains(&name) => {
+                let Some(passed) = fs::read_to_string(&path).ok().and_then(|log| parse_go_test_log(&log)) else { continue };
+                (TestSource::GoTest, passed)
+            }
+            _ => {
+                let Some(passed) = fs::read_to_string(&path).ok().and_then(|xml| parse_junit(&xml)) else { continue };
+                (TestSource::Junit, passed)
+            }
+        };
+        results.push(TestEvidence { source, at, passed, path: Some(path) });
+    }
+    results
+}
+
+/// Whether a JUnit report passed: no `<testsuite>` or `<testsuites>` with
+/// failures or errors; `None` when it has neither
+pub fn parse_junit(xml: &str) -> Option<bool> {
+    let mut found = false;
+    for tag in JUNIT_SUITE.find_iter(xml) {
+        found = true;
+        if JUNIT_FAILURES.captures_iter(tag.as_str()).any(|caps| caps[1] != *"0") {
+            return Some(false);
+        }
+    }
+    found.then_some(true)
+}
+
+/// Whether a `go test` log, plain or `-json`, passed; `None` when it has
+/// no results
+pub fn parse_go_test_log(log: &str) -> Option<bool> {
+    let actions: Vec<bool> = GO_JSON_ACTION.captures_iter(log).map(|caps| &caps[1] == "pass").collect();
+    if !actions.is_empty() {
+        return Some(actions.iter().all(|passed| *passed));
+    }
+    if let Some(counts) = parse_test_counts(log) {
+        return Some(counts.failed == 0);
+    }
+    // Package summaries without -v: "ok  \texample.com/pkg" and "FAIL\texample.com/pkg"
+    let failed = log.lines().any(|line| line.starts_with("FAIL"));
+    let passed = log.lines().any(|line| line.starts_with("ok ") || line.starts_with("ok\t"));
+    (failed || passed).then_some(!failed)
+}
+
+/// Whether a CI note passed; `None` when it says neither
+///
+/// Failure counts decide first ("0 failed", "failures: 0" and "no errors"
+/// passed, "2 failed" did not). Otherwise a whole status word does: "fail",
+/// "failed", "error" and the like failed, "pass", "success" and the like
+/// passed. URLs are left out, so a link to a build log named `errors` does
+/// not count.
+pub fn parse_ci_note(text: &str) -> Option<bool> {
+    let text: Vec<&str> = text.split_whitespace().filter(|word| !word.contains("://")).collect();
+    let text = text.join(" ");
+    let counts: Vec<u32> = NOTE_FAILURES.captures_iter(&text)
+        .filter_map(|caps| match caps.get(1).or(caps.get(2))?.as_str() {
+            count if count.eq_ignore_ascii_case("no") => Some(0),
+            count => count.parse().ok(),
+        })
+        .collect();
+    if !counts.is_empty() {
+        return Some(counts.iter().all(|count| *count == 0));
+    }
+    if NOTE_FAILED.is_match(&text) {
+        Some(false)
+    } else if NOTE_PASSED.is_match(&text) {
+        Some(true)
+    } else {
+        None
+    }
+}
+
+/// The most recent of the outcomes, skipping those from before `since`
+/// (the last task change), which say nothing about the finished work
+///
+/// Compared to the second, the precision of git dates.
+pub fn latest_outcome(evidence: Vec<TestEvidence>, since: Option<DateTime<Utc>>) -> Option<TestEvidence> {
+    let since = since.map(|since| since.trunc_subsecs(0));
+    evidence.into_iter()
+        .filter(|e| since.is_none_or(|since| e.at >= since))
+        .max_by_key(|e| e.at)
+}
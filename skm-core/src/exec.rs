@@ -0,0 +1,171 @@
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus, Output};
+use crate::{Capability, Result, SKMError};
+use crate::meta::state::ExecProfile;
+use crate::session::launch::shell_quote;
+
+/// Build the process that runs a shell command line for a project
+///
+/// The command runs inside the profile's docker image or devcontainer, in
+/// the project's own Nix flake, shell.nix or devcontainer when it has one,
+/// or on the host. Either way it starts in the profile's working
+/// directory, with PATH prepends, fixed variables and secrets applied.
+pub fn command(
+    project_path: &Path,
+    profile: &ExecProfile,
+    capabilities: &BTreeSet<Capability>,
+    command_line: &str,
+) -> Result<Command> {
+    let project = project_path.canonicalize()?;
+    let workdir = match &profile.workdir {
+        Some(dir) => project.join(dir),
+        None => project.clone(),
+    };
+    if !workdir.is_dir() {
+        return Err(SKMError::ConfigError {
+            message: format!("exec.workdir {} does not exist", workdir.display()),
+        });
+    }
+
+    let mut env: Vec<(String, String)> = Vec::new();
+    for name in &profile.secrets {
+        let value = std::env::var(name).map_err(|_| SKMError::ConfigError {
+            message: format!("{} needs the environment variable {} (exec.secrets)", project.display(), name),
+        })?;
+        env.push((name.clone(), value));
+    }
+    let mut fixed: Vec<_> = profile.env.iter().collect();
+    fixed.sort();
+    env.extend(fixed.into_iter().map(|(k, v)| (k.clone(), v.clone())));
+
+    let prepend: Vec<PathBuf> = profile.path_prepend.iter().map(|dir| workdir.join(dir)).collect();
+    let script = if prepend.is_empty() {
+        command_line.to_string()
+    } else {
+        let dirs: Vec<String> = prepend.iter().map(|d| shell_quote(&d.to_string_lossy())).collect();
+        format!("PATH={}:\"$PATH\"; {}", dirs.join(":"), command_line)
+    };
+
+    let cmd = match runner(profile, capabilities, &project)? {
+        Runner::Docker(image) => {
+            // The project is mounted at the same path, so paths mean the same inside
+            let mount = format!("{}:{}", project.display(), project.display());
+            let mut cmd = Command::new("docker");
+            cmd.args(["run", "--rm", "-v", &mount, "-w"]).arg(&workdir);
+            for (name, value) in &env {
+                cmd.arg("-e").arg(format!("{}={}", name, value));
+            }
+            cmd.args([image, "sh", "-c", &script]);
+            cmd
+        }
+        Runner::Devcontainer => {
+            let mut cmd = Command::new("devcontainer");
+            cmd.args(["exec", "--workspace-folder"]).arg(&project);
+            for (name, value) in &env {
+                cmd.arg("--remote-env").arg(format!("{}={}", name, value));
+            }
+            let script = format!("cd {} && {}", shell_quote(&workdir.to_string_lossy()), script);
+            cmd.args(["sh", "-c", &script]);
+            cmd
+        }
+        Runner::NixFlake => {
+            let mut cmd = Command::new("nix");
+            cmd.arg("develop").arg(&project).args(["-c", "sh", "-c", &script]);
+            cmd.current_dir(&workdir).envs(env);
+            cmd
+        }
+        Runner::NixShell => {
+            let mut cmd = Command::new("nix-shell");
+            cmd.arg(project.join("shell.nix")).args(["--run", &script]);
+            cmd.current_dir(&workdir).envs(env);
+            cmd
+        }
+        Runner::Host => {
+            let mut cmd = Command::new("sh");
+            cmd.args(["-c", &script]).current_dir(&workdir).envs(env);
+            cmd
+        }
+    };
+
+    Ok(cmd)
+}
+
+/// Where a project's commands run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Runner<'a> {
+    Host,
+    Docker(&'a str),
+    Devcontainer,
+    NixFlake,
+    NixShell,
+}
+
+/// An explicit image or devcontainer wins; otherwise the project's own Nix
+/// or devcontainer environment is used unless the profile asks for `native`
+fn runner<'a>(profile: &'a ExecProfile, capabilities: &BTreeSet<Capability>, project: &Path) -> Result<Runner<'a>> {
+    match (&profile.image, profile.devcontainer) {
+        (Some(_), true) => Err(SKMError::ConfigError {
+            message: format!("{}: exec.image and exec.devcontainer cannot both be set", project.display()),
+        }),
+        (Some(image), false) => Ok(Runner::Docker(image)),
+        (None, true) => Ok(Runner::Devcontainer),
+        (None, false) if profile.native => Ok(Runner::Host),
+        (None, false) => Ok(
+            if capabilities.contains(&Capability::NixFlake) {
+                Runner::NixFlake
+            } else if capabilities.contains(&Capability::NixShell) {
+                Runner::NixShell
+            } else if capabilities.contains(&Capability::Devcontainer) {
+                Runner::Devcontainer
+            } else {
+                Runner::Host
+            }
+        ),
+    }
+}
+
+/// Wrap a shell command so it runs in the project's Nix environment, if any
+///
+/// Used for interactive launches, where a devcontainer would need the
+/// agent installed inside it and is therefore not used.
+pub fn wrap_in_environment(capabilities: &BTreeSet<Capability>, command_line: &str) -> String {
+    if capabilities.contains(&Capability::NixFlake) {
+        format!("nix develop -c {}", command_line)
+    } else if capabilities.contains(&Capability::NixShell) {
+        format!("nix-shell --run {}", shell_quote(command_line))
+    } else {
+        command_line.to_string()
+    }
+}
+
+/// Run a command line for a project and capture its output
+pub fn run(
+    project_path: &Path,
+    profile: &ExecProfile,
+    capabilities: &BTreeSet<Capability>,
+    command_line: &str,
+) -> Result<Output> {
+    let mut cmd = command(project_path, profile, capabilities, command_line)?;
+    cmd.output().map_err(|e| spawn_error(&cmd, e))
+}
+
+/// Run a command line for a project with inherited stdio
+pub fn run_streaming(
+    project_path: &Path,
+    profile: &ExecProfile,
+    capabilities: &BTreeSet<Capability>,
+    command_line: &str,
+) -> Result<ExitStatus> {
+    let mut cmd = command(project_path, profile, capabilities, command_line)?;
+    cmd.status().map_err(|e| spawn_error(&cmd, e))
+}
+
+fn spawn_error(cmd: &Command, e: std::io::Error) -> SKMError {
+    match e.kind() {
+        std::io::ErrorKind::NotFound => SKMError::ConfigError {
+            message: format!("cannot run {:?}: program not found", cmd.get_program()),
+        },
+        _ => e.into(),
+    }
+}
@@ -0,0 +1,85 @@
+use std::fmt;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use serde_json::Value;
+use crate::retry::{self, Transience};
+
+/// A failed HTTP request: curl could not connect, or the server answered with an error
+#[derive(Debug)]
+pub struct HttpError {
+    /// HTTP status, `None` when no response arrived
+    pub status: Option<u16>,
+    pub message: String,
+}
+
+impl fmt::Display for HttpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.status {
+            Some(status) => write!(f, "HTTP {}: {}", status, self.message),
+            None => f.write_str(&self.message),
+        }
+    }
+}
+
+impl Transience for HttpError {
+    fn is_transient(&self) -> bool {
+        matches!(self.status, Some(429 | 502 | 503 | 504))
+            || (self.status.is_none() && retry::is_transient_message(&self.message))
+    }
+}
+
+/// Send a JSON request with curl and parse the JSON response
+#[doc(hidden)]
+pub fn http_json(method: &str, url: &str, body: Option<&Value>) -> std::result::Result<Value, HttpError> {
+    http_json_with(method, url, &[], body)
+}
+
+/// Like [`http_json`], with extra request headers such as credentials
+///
+/// Headers and body reach curl as a config file on stdin, so tokens do not
+/// show up in the process list.
+#[doc(hidden)]
+pub fn http_json_with(method: &str, url: &str, headers: &[String], body: Option<&Value>) -> std::result::Result<Value, HttpError> {
+    let failed = |message: String| HttpError { status: None, message };
+    // Quoted curl config values take backslash escapes
+    let quote = |value: &str| format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""));
+    let mut config = String::new();
+    for header in headers {
+        config.push_str(&format!("header = {}\n", quote(header)));
+    }
+    if let Some(body) = body {
+        config.push_str("header = \"Content-Type: application/json\"\n");
+        config.push_str(&format!("data-binary = {}\n", quote(&body.to_string())));
+    }
+
+    let mut child = Command::new("curl")
+        .args(["-sS", "--max-time", "60", "-X", method, "-w", "\n%{http_code}", "-K", "-"])
+        .arg(url)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| failed(format!("cannot run curl: {}", e)))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(config.as_bytes()).map_err(|e| failed(e.to_string()))?;
+    }
+    let output = child.wait_with_output().map_err(|e| failed(e.to_string()))?;
+    if !output.status.success() {
+        return Err(failed(String::from_utf8_lossy(&output.stderr).trim().to_string()));
+    }
+
+    // `-w` appends the status code on a line of its own
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let (response, code) = stdout.trim_end().rsplit_once('\n').unwrap_or(("", stdout.trim()));
+    let status = code.trim().parse::<u16>().map_err(|_| failed(format!("no HTTP status from {}", url)))?;
+    let doc: Value = serde_json::from_str(response).unwrap_or(Value::Null);
+    if !(200..300).contains(&status) {
+        let message = doc.pointer("/status/error")
+            .or_else(|| doc.get("error"))
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .unwrap_or_else(|| response.trim().to_string());
+        return Err(HttpError { status: Some(status), message: format!("{} {}: {}", method, url, message) });
+    }
+    Ok(doc)
+}
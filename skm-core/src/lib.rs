@@ -0,0 +1,1276 @@
+//! Data model, scanner and analyzer of skm, without its command line
+//!
+//! Editor plugins, bots and other tools can discover Spec-Kit projects,
+//! scan them into a [`PortfolioStatus`] and analyze it the way `skm` does,
+//! without depending on the CLI's argument parsing, async runtime or
+//! logging. The `skm` crate re-exports everything here, so `skm::Project`
+//! and `skm_core::Project` are the same type.
+//!
+//! # Stability
+//!
+//! The public API follows semver: anything reachable from this crate and
+//! shown in its documentation only changes incompatibly in a new major
+//! version (a new minor version while below 1.0). The modules the CLI
+//! needs for running commands, sessions, notifications and HTTP are
+//! `#[doc(hidden)]`; they are shared with the `skm` crate and are not
+//! covered. Fields added to the serialized types are optional, so
+//! `.skm/status.json` files written by older versions keep loading.
+//!
+//! # Features
+//!
+//! - `git`: git status, gitignores and remote clones through libgit2
+//! - `integrations`: webhook notifications, used by `skm watch`
+//! - `async`: `retry::retry_async` on the tokio timer
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+pub mod scanner;
+pub mod analyzer;
+pub mod meta;
+pub mod reporter;
+#[doc(hidden)]
+pub mod session;
+pub mod retry;
+pub mod view;
+#[doc(hidden)]
+pub mod exec;
+pub mod template;
+pub mod timefmt;
+pub mod hash;
+#[doc(hidden)]
+pub mod http;
+#[doc(hidden)]
+pub mod alerts;
+
+// Types are already publicly accessible through their definitions below
+
+/// Whether `SKM_DEBUG` is set, for diagnostics on stderr
+#[inline]
+pub(crate) fn is_debug() -> bool {
+    std::env::var("SKM_DEBUG").is_ok()
+}
+
+/// Write a path as a string, replacing bytes that are not valid UTF-8 rather
+/// than failing, so one odd directory name cannot break a whole status file
+fn lossy_path<S: serde::Serializer>(path: &std::path::Path, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+    serializer.serialize_str(&path.to_string_lossy())
+}
+
+fn lossy_option_path<S: serde::Serializer>(path: &Option<PathBuf>, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+    match path {
+        Some(path) => lossy_path(path, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
+// Error types
+#[derive(Debug, Error)]
+pub enum SKMError {
+    #[error("Project not found: {path}")]
+    ProjectNotFound { path: PathBuf },
+    
+    #[error("Configuration error: {message}")]
+    ConfigError { message: String },
+    
+    /// A file skm keeps under `.skm` that it cannot read or use
+    #[error("Cache error: {message}")]
+    CacheError { message: String },
+    
+    /// An external tool or service skm drives (specify, agents, git workflows)
+    #[error("Integration error: {message}")]
+    IntegrationError { message: String },
+    
+    #[error("Lock error: {message}")]
+    LockError { message: String },
+    
+    #[error("Qdrant connection failed: {message}")]
+    QdrantError { message: String },
+    
+    #[error("tmux command failed: {command}")]
+    TmuxError { command: String },
+    
+    #[error("Notification delivery failed: {message}")]
+    NotificationError { message: String },
+    
+    #[error("Publishing failed: {message}")]
+    PublishError { message: String },
+    
+    #[error("File system error: {source}")]
+    FsError { #[from] source: std::io::Error },
+    
+    #[cfg(feature = "git")]
+    #[error("Git operation failed: {source}")]
+    GitError { #[from] source: git2::Error },
+    
+    #[error("Serialization error: {source}")]
+    SerdeError { #[from] source: serde_json::Error },
+    
+    #[error("TOML parsing error: {source}")]
+    TomlError { #[from] source: toml::de::Error },
+    
+    #[error("TOML serialization error: {source}")]
+    TomlSerError { #[from] source: toml::ser::Error },
+    
+    /// Something this build of skm left out; see the features in Cargo.toml
+    #[error("skm was built without the `{feature}` feature")]
+    FeatureDisabled { feature: &'static str },
+}
+
+// Result type alias
+pub type Result<T> = std::result::Result<T, SKMError>;
+
+// Core data structures from data-model.md
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Project {
+    pub id: String,
+    #[serde(serialize_with = "lossy_path")]
+    pub path: PathBuf,
+    pub stage: Stage,
+    pub next: NextAction,
+    pub requires_human: Vec<HumanRequirement>,
+    pub priority: f64,
+    pub tasks: TaskSummary,
+    pub updated: DateTime<Utc>,
+    pub git: GitStatus,
+    pub project_type: ProjectType,
+    pub artifacts: ArtifactStatus,
+    /// Numbered feature directories (001-*, 002-*) with their own artifacts and tasks
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub features: Vec<FeatureStatus>,
+    /// Spec growth since planning, when history is available
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spec_trend: Option<SpecTrend>,
+    /// Unresolved clarification markers in spec and plan
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub open_questions: Vec<OpenQuestion>,
+    /// Name of the configured root the project was scanned under
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub root: Option<String>,
+    /// Priority exceeds the attention threshold of the project's root
+    #[serde(default)]
+    pub needs_attention: bool,
+    /// Pinned with `skm pin`: listed first and always needing attention until done
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub pinned: bool,
+    /// URL of a remote project analyzed from a read-only clone
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remote: Option<String>,
+    /// Result of the last `skm test` run
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_test: Option<TestRun>,
+    /// Result of the last `skm check` run
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_check: Option<TestRun>,
+    /// The newest test outcome since the tasks last changed: an `skm test`
+    /// run, a result file or CI, which tells `Test` from `Review`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub test_evidence: Option<TestEvidence>,
+    /// Fields that differed between consecutive scans of unchanged inputs
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unstable: Vec<UnstableField>,
+    /// TODO/FIXME comments in the source, when code debt harvesting is enabled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code_debt: Option<u32>,
+    /// The project has no constitution of its own and uses the portfolio's `.skm/constitution.md`
+    #[serde(default)]
+    pub constitution_inherited: bool,
+    /// Principles from the portfolio and project constitutions, project ones first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub principles: Vec<Principle>,
+    /// Dependency audit result, when audits are enabled and the project has a lockfile
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub audit: Option<AuditResult>,
+    /// Direct dependencies a major version behind, when freshness checks are enabled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub freshness: Option<DependencyFreshness>,
+    /// License files and manifest declarations, with any incompatible combinations
+    #[serde(default)]
+    pub license: LicenseStatus,
+    /// When scans first saw the project in its current stage
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stage_since: Option<DateTime<Utc>>,
+    /// Fingerprint of everything the analysis of this scan read
+    #[serde(skip)]
+    pub inputs: Option<String>,
+    /// Development environments detected in the project root
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub capabilities: BTreeSet<Capability>,
+    /// Team-defined fields from `.skm/meta.json` (cost center, environment, ...)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub custom: BTreeMap<String, serde_json::Value>,
+    /// In or out of season, for projects with an `active_window` in their metadata
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub season: Option<analyzer::season::Season>,
+    /// How the priority was computed, for `skm explain`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority_breakdown: Option<analyzer::priority::PriorityBreakdown>,
+    /// Time waited in Review against `[sla] review_days`, while in Review
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub review_sla: Option<analyzer::sla::ReviewSla>,
+}
+
+impl Project {
+    /// Project id, prefixed with its root name when scanned under a configured root
+    pub fn display_name(&self) -> String {
+        match &self.root {
+            Some(root) => format!("{}/{}", root, self.id),
+            None => self.id.clone(),
+        }
+    }
+
+    /// The order projects are listed in everywhere: pinned ones first, then
+    /// highest priority first, ties broken by display name and then path, so
+    /// output is the same on every run
+    pub fn priority_order(&self, other: &Project) -> std::cmp::Ordering {
+        other.pinned.cmp(&self.pinned)
+            .then_with(|| other.priority.total_cmp(&self.priority))
+            .then_with(|| self.display_name().cmp(&other.display_name()))
+            .then_with(|| self.path.cmp(&other.path))
+    }
+}
+
+/// Which Spec-Kit artifact something was found in
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "kebab-case")]
+pub enum ArtifactKind {
+    Constitution,
+    Spec,
+    Plan,
+    Tasks,
+}
+
+impl ArtifactKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ArtifactKind::Constitution => "constitution",
+            ArtifactKind::Spec => "spec",
+            ArtifactKind::Plan => "plan",
+            ArtifactKind::Tasks => "tasks",
+        }
+    }
+}
+
+impl std::fmt::Display for ArtifactKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for ArtifactKind {
+    type Err = SKMError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        [ArtifactKind::Constitution, ArtifactKind::Spec, ArtifactKind::Plan, ArtifactKind::Tasks]
+            .into_iter()
+            .find(|kind| kind.as_str().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| SKMError::ConfigError {
+                message: format!("Unknown artifact: {} (expected constitution, spec, plan or tasks)", s),
+            })
+    }
+}
+
+/// A question raised in an artifact that still needs an answer
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct OpenQuestion {
+    pub source: ArtifactKind,
+    /// 1-based line number in the artifact
+    pub line: u32,
+    pub text: String,
+}
+
+/// Artifacts and task progress of a single numbered feature directory
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FeatureStatus {
+    /// Directory name, e.g. `001-user-auth`
+    pub id: String,
+    pub number: Option<u32>,
+    pub name: String,
+    #[serde(serialize_with = "lossy_path")]
+    pub path: PathBuf,
+    pub artifacts: ArtifactStatus,
+    pub tasks: TaskSummary,
+    /// Stage of this feature alone, detected from its own artifacts and tasks
+    #[serde(default)]
+    pub stage: Stage,
+}
+
+/// Project lifecycle stage
+///
+/// Serialized as kebab-case (`"implement"`); the capitalized names written by
+/// older versions (`"Implement"`) are still accepted when reading. Any other
+/// name is a stage defined under `[[workflow.stages]]` and is kept as
+/// written, so files from a setup with other custom stages still load.
+#[derive(Clone, PartialEq, Eq, Hash, Default)]
+pub enum Stage {
+    #[default]
+    Bootstrap,
+    Specify,
+    /// Optional: resolve ambiguities in the spec before planning
+    Clarify,
+    Plan,
+    Tasks,
+    /// Optional: cross-artifact consistency check before implementing
+    Analyze,
+    Implement,
+    Test,
+    Review,
+    /// A stage from the config, between review and done (see [`Stage::ladder`])
+    Custom(String),
+    Done,
+}
+
+impl Stage {
+    /// All built-in stages in lifecycle order
+    pub const ALL: [Stage; 10] = [
+        Stage::Bootstrap,
+        Stage::Specify,
+        Stage::Clarify,
+        Stage::Plan,
+        Stage::Tasks,
+        Stage::Analyze,
+        Stage::Implement,
+        Stage::Test,
+        Stage::Review,
+        Stage::Done,
+    ];
+
+    /// Stable kebab-case name used in JSON, CLI filters and config
+    pub fn as_str(&self) -> &str {
+        match self {
+            Stage::Bootstrap => "bootstrap",
+            Stage::Specify => "specify",
+            Stage::Clarify => "clarify",
+            Stage::Plan => "plan",
+            Stage::Tasks => "tasks",
+            Stage::Analyze => "analyze",
+            Stage::Implement => "implement",
+            Stage::Test => "test",
+            Stage::Review => "review",
+            Stage::Custom(name) => name,
+            Stage::Done => "done",
+        }
+    }
+
+    /// The built-in stage with this name, in any case
+    fn builtin(name: &str) -> Option<Stage> {
+        Stage::ALL.iter().find(|stage| stage.as_str().eq_ignore_ascii_case(name.trim())).cloned()
+    }
+
+    /// The built-in stages with `custom` stages, in workflow order, before done
+    pub fn ladder<'a>(custom: impl IntoIterator<Item = &'a str>) -> Vec<Stage> {
+        let custom = custom.into_iter().map(|name| Stage::Custom(name.to_string()));
+        let mut ladder: Vec<Stage> = Stage::ALL[..Stage::ALL.len() - 1].iter().cloned().chain(custom).collect();
+        ladder.push(Stage::Done);
+        ladder
+    }
+
+    /// Position in the lifecycle: built-in stages by index, custom ones
+    /// between review and done by name
+    ///
+    /// Their configured order is not known here; lists that follow it use
+    /// [`Stage::ladder`] with the workflow of the scan.
+    fn rank(&self) -> (usize, &str) {
+        match self {
+            Stage::Custom(name) => (Stage::ALL.len() - 2, name),
+            Stage::Done => (Stage::ALL.len() - 1, ""),
+            builtin => (Stage::ALL.iter().position(|s| s == builtin).unwrap_or(0), ""),
+        }
+    }
+}
+
+impl Ord for Stage {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.rank().cmp(&other.rank())
+    }
+}
+
+impl PartialOrd for Stage {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// The variant name for built-in stages (`Implement`), and the camel-cased
+/// name for custom ones (`SecurityReview`), as reports show them
+impl std::fmt::Debug for Stage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Stage::Custom(name) => {
+                for word in name.split('-') {
+                    let mut chars = word.chars();
+                    if let Some(first) = chars.next() {
+                        write!(f, "{}{}", first.to_uppercase(), chars.as_str())?;
+                    }
+                }
+                Ok(())
+            }
+            builtin => {
+                let name = builtin.as_str();
+                write!(f, "{}{}", name[..1].to_uppercase(), &name[1..])
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for Stage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for Stage {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Stage {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Ok(Stage::builtin(&name).unwrap_or(Stage::Custom(name)))
+    }
+}
+
+/// Kebab-case form of a stage name: `SecurityReview` and `security_review`
+/// both become `security-review`
+pub fn kebab_stage_name(name: &str) -> String {
+    let mut kebab = String::new();
+    let mut previous: Option<char> = None;
+    for c in name.trim().chars() {
+        if c == '_' || c == ' ' || c == '-' {
+            if !kebab.is_empty() && !kebab.ends_with('-') {
+                kebab.push('-');
+            }
+        } else {
+            if c.is_uppercase() && previous.is_some_and(|p| p.is_lowercase() || p.is_ascii_digit()) && !kebab.ends_with('-') {
+                kebab.push('-');
+            }
+            kebab.extend(c.to_lowercase());
+        }
+        previous = Some(c);
+    }
+    kebab
+}
+
+/// Parses stage names case-insensitively, so both `implement` and `Implement`
+/// work; other names are custom stages, written in kebab-case or camel case
+impl std::str::FromStr for Stage {
+    type Err = SKMError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if let Some(stage) = Stage::builtin(s) {
+            return Ok(stage);
+        }
+        let name = kebab_stage_name(s);
+        let valid = name.starts_with(|c: char| c.is_ascii_lowercase())
+            && !name.ends_with('-')
+            && name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+        if !valid {
+            return Err(SKMError::ConfigError {
+                message: format!("Invalid stage: {} (expected one of: {}, or a custom stage name like security-review)", s,
+                    Stage::ALL.iter().map(Stage::as_str).collect::<Vec<_>>().join(", ")),
+            });
+        }
+        Ok(Stage::builtin(&name).unwrap_or(Stage::Custom(name)))
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NextAction {
+    pub command: String,
+    pub description: String,
+    pub automated: bool,
+    pub risk_level: AutomationLevel,
+    /// Agent-specific ways to start the step; only set for slash commands
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub launch: Vec<AgentLaunch>,
+}
+
+/// Coding agents skm can hand a next action to
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Agent {
+    #[serde(rename = "claude")]
+    ClaudeCode,
+    #[serde(rename = "cursor")]
+    Cursor,
+    #[serde(rename = "vscode")]
+    VsCode,
+}
+
+impl Agent {
+    pub const ALL: [Agent; 3] = [Agent::ClaudeCode, Agent::Cursor, Agent::VsCode];
+
+    /// Name used in config (`agent_priority`) and JSON
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Agent::ClaudeCode => "claude",
+            Agent::Cursor => "cursor",
+            Agent::VsCode => "vscode",
+        }
+    }
+
+    /// Human-readable name for reports
+    pub fn label(&self) -> &'static str {
+        match self {
+            Agent::ClaudeCode => "Claude Code",
+            Agent::Cursor => "Cursor",
+            Agent::VsCode => "VS Code",
+        }
+    }
+}
+
+impl std::fmt::Display for Agent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A launch string for one agent: a shell command or a URI
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AgentLaunch {
+    pub agent: Agent,
+    pub target: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum HumanRequirement {
+    Review,
+    Input,
+    Fix,
+    Test,
+    Deploy,
+    Decision,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct TaskSummary {
+    pub total: u32,
+    pub completed: u32,
+    pub parallel_marked: u32,
+    pub blocked: u32,
+    pub last_activity: Option<DateTime<Utc>>,
+}
+
+/// A single task line from a tasks file
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TaskItem {
+    /// 1-based line number in the tasks file
+    pub line: usize,
+    pub text: String,
+    pub done: bool,
+    pub parallel: bool,
+    pub blocked: bool,
+    /// Which of the supported notations the line uses
+    pub format: TaskFormat,
+}
+
+/// Notation a task line is written in
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum TaskFormat {
+    /// `- [ ] task`, `* [x] task`
+    Checkbox,
+    /// `T001: task`, done when marked ✅, DONE, [COMPLETE] or [x]
+    TaskId,
+    /// `✅ task`, `⬜ task`
+    Emoji,
+    /// `TODO: task`, `DONE: task`
+    Keyword,
+}
+
+impl TaskFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TaskFormat::Checkbox => "checkbox",
+            TaskFormat::TaskId => "task-id",
+            TaskFormat::Emoji => "emoji",
+            TaskFormat::Keyword => "keyword",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GitStatus {
+    pub is_repo: bool,
+    pub branch: Option<String>,
+    pub clean: bool,
+    pub last_commit: Option<DateTime<Utc>>,
+    pub ahead: u32,
+    pub behind: u32,
+    /// The branch tracks an upstream branch, which `ahead` and `behind` count against
+    #[serde(default)]
+    pub upstream: bool,
+    /// HEAD is on a branch other than the default one and already merged into it
+    #[serde(default)]
+    pub merged: bool,
+    /// Git was not queried (`--skip-git` or disabled in project meta)
+    #[serde(default)]
+    pub skipped: bool,
+    /// Git data is still being collected in the background
+    #[serde(default)]
+    pub pending: bool,
+    /// Pull requests and issues of the GitHub repository, when enabled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub github: Option<GitHubStatus>,
+    /// CI result recorded for HEAD in `refs/notes/ci`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ci_note: Option<TestEvidence>,
+}
+
+/// Open pull requests and issues of a project's GitHub repository
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GitHubStatus {
+    pub at: DateTime<Utc>,
+    /// `owner/name`, from the `origin` remote
+    pub repo: String,
+    pub open_prs: u32,
+    pub open_issues: u32,
+    /// Open pull requests whose latest commit has failing checks
+    pub failing_checks: u32,
+    /// Open pull requests waiting for a review from the token's user
+    pub review_requests: u32,
+    /// Commit the checks below belong to, the local HEAD at query time
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub head: Option<String>,
+    /// Whether the checks of `head` passed; `None` while pending or without checks
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub head_checks: Option<bool>,
+    /// Why the counts could not be fetched, e.g. a missing token
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl GitStatus {
+    /// Status for a directory that is not a git repository
+    pub fn not_a_repo() -> Self {
+        Self {
+            is_repo: false,
+            branch: None,
+            clean: true,
+            last_commit: None,
+            ahead: 0,
+            behind: 0,
+            upstream: false,
+            merged: false,
+            skipped: false,
+            pending: false,
+            github: None,
+            ci_note: None,
+        }
+    }
+
+    /// Everything is committed and either pushed to the upstream or merged
+    /// into the default branch
+    pub fn is_shipped(&self) -> bool {
+        self.is_repo && self.clean && ((self.upstream && self.ahead == 0) || self.merged)
+    }
+
+    /// Placeholder for a project whose git status was intentionally not queried
+    pub fn skipped() -> Self {
+        Self {
+            skipped: true,
+            ..Self::not_a_repo()
+        }
+    }
+
+    /// Placeholder for a project whose git status will be filled in later
+    pub fn pending() -> Self {
+        Self {
+            pending: true,
+            ..Self::not_a_repo()
+        }
+    }
+}
+
+/// Development environment a project ships, usable for running its commands
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "kebab-case")]
+pub enum Capability {
+    /// `.devcontainer/` or `.devcontainer.json`
+    Devcontainer,
+    /// `flake.nix`
+    NixFlake,
+    /// `shell.nix`
+    NixShell,
+    /// `Dockerfile`
+    Dockerfile,
+}
+
+impl Capability {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Capability::Devcontainer => "devcontainer",
+            Capability::NixFlake => "nix-flake",
+            Capability::NixShell => "nix-shell",
+            Capability::Dockerfile => "dockerfile",
+        }
+    }
+}
+
+impl std::fmt::Display for Capability {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum ProjectType {
+    Rust,
+    Node,
+    Python,
+    Go,
+    Generic,
+    Unknown,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ArtifactStatus {
+    pub constitution: Option<FileInfo>,
+    pub spec: Option<FileInfo>,
+    pub plan: Option<FileInfo>,
+    pub tasks: Option<FileInfo>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FileInfo {
+    #[serde(serialize_with = "lossy_path")]
+    pub path: PathBuf,
+    pub size: u64,
+    pub modified: DateTime<Utc>,
+    pub valid: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metrics: Option<ArtifactMetrics>,
+}
+
+/// Size and complexity of a markdown artifact
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct ArtifactMetrics {
+    pub words: u32,
+    /// Number of `##` sections
+    pub sections: u32,
+    /// Lines stating a requirement (FR-001 style IDs or MUST/SHALL)
+    pub requirements: u32,
+    /// TODO/TBD/FIXME markers
+    pub todos: u32,
+    /// TODO markers per 1000 words
+    pub todo_density: f64,
+}
+
+/// A principle (`###` heading) of a constitution
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Principle {
+    pub name: String,
+    /// Comes from the portfolio constitution rather than the project's own
+    pub inherited: bool,
+}
+
+/// Where a project declares its license
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LicenseDeclaration {
+    /// License file name or manifest path relative to the project
+    pub source: String,
+    /// SPDX id or expression, as declared or identified from the license text
+    pub license: String,
+}
+
+/// License files and declarations found in a project and its workspace members
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct LicenseStatus {
+    /// LICENSE, COPYING and similar files in the project root
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub files: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub declared: Vec<LicenseDeclaration>,
+    /// Declarations that cannot be combined, as `a (source) vs b (source)`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub conflicts: Vec<String>,
+}
+
+impl LicenseStatus {
+    /// Neither a license file nor a manifest declaration was found
+    pub fn is_missing(&self) -> bool {
+        self.files.is_empty() && self.declared.is_empty()
+    }
+
+    /// Distinct declared licenses in discovery order
+    pub fn licenses(&self) -> Vec<&str> {
+        let mut licenses: Vec<&str> = Vec::new();
+        for declaration in &self.declared {
+            if !licenses.contains(&declaration.license.as_str()) {
+                licenses.push(&declaration.license);
+            }
+        }
+        licenses
+    }
+
+    /// One-cell compliance summary: `✅ MIT`, `⚠️ missing`, `❌ conflict`
+    ///
+    /// Accessible mode leaves out the emoji; the words carry the meaning.
+    pub fn compliance(&self) -> String {
+        use crate::reporter::style::decorate;
+        if !self.conflicts.is_empty() {
+            decorate("❌", "conflict")
+        } else if self.is_missing() {
+            decorate("⚠️", "missing")
+        } else if self.declared.is_empty() {
+            decorate("❓", "unrecognized")
+        } else {
+            decorate("✅", &self.licenses().join(", "))
+        }
+    }
+}
+
+/// A result that changed between scans although the project did not,
+/// which usually points at ambiguous parsing
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct UnstableField {
+    /// `stage` or `priority`
+    pub field: String,
+    pub previous: String,
+    pub current: String,
+}
+
+/// Outcome of the last `skm test` or `skm check` run of a project
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TestRun {
+    pub at: DateTime<Utc>,
+    pub command: String,
+    pub success: bool,
+    /// Counts parsed from the test output, when its format is recognized
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub passed: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failed: Option<u32>,
+    pub duration_ms: u64,
+    /// Captured output in `.skm/logs`
+    #[serde(serialize_with = "lossy_path")]
+    pub log: PathBuf,
+}
+
+/// Where a test outcome was found
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum TestSource {
+    /// The last `skm test` run
+    SkmTest,
+    /// A JUnit XML report, e.g. from cargo-nextest
+    Junit,
+    /// pytest's cache of failed tests
+    Pytest,
+    /// A saved `go test` log
+    GoTest,
+    /// A CI note on HEAD in `refs/notes/ci`
+    CiNote,
+    /// The GitHub checks of the HEAD commit
+    GitHubChecks,
+}
+
+/// The outcome of a test run, from `skm test`, result files or CI
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TestEvidence {
+    pub source: TestSource,
+    pub at: DateTime<Utc>,
+    pub passed: bool,
+    /// The result file, for evidence found on disk
+    #[serde(default, skip_serializing_if = "Option::is_none", serialize_with = "lossy_option_path")]
+    pub path: Option<PathBuf>,
+}
+
+impl TestEvidence {
+    pub fn from_run(run: &TestRun) -> Self {
+        TestEvidence { source: TestSource::SkmTest, at: run.at, passed: run.success, path: None }
+    }
+}
+
+/// Known vulnerabilities reported by a project's dependency audit tool
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AuditResult {
+    pub at: DateTime<Utc>,
+    /// `cargo audit`, `npm audit` or `pip-audit`
+    pub tool: String,
+    /// Lockfile the audit covered, relative to the project
+    pub lockfile: String,
+    /// Blob hash of the lockfile; a cached result is reused while it matches
+    pub lockfile_hash: String,
+    pub vulnerabilities: u32,
+    /// Advisory ids (RUSTSEC-..., GHSA-..., PYSEC-...) or affected packages
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub advisories: Vec<String>,
+    /// Why the audit produced no result, e.g. the tool is not installed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// A direct dependency whose latest release is a major version ahead
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct OutdatedDependency {
+    pub name: String,
+    /// Version pinned by the lockfile
+    pub current: String,
+    pub latest: String,
+}
+
+/// How far a project's direct dependencies lag behind their registries
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DependencyFreshness {
+    pub at: DateTime<Utc>,
+    /// Lockfile the versions were read from, relative to the project
+    pub lockfile: String,
+    /// Blob hash of the lockfile; a cached result is reused while it matches
+    pub lockfile_hash: String,
+    /// Direct dependencies pinned by the lockfile
+    pub direct: u32,
+    /// Dependencies whose latest version could not be fetched
+    #[serde(default)]
+    pub unchecked: u32,
+    /// Names of the unchecked dependencies, looked up again on the next scan
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub failed: Vec<String>,
+    pub major_behind: u32,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub outdated: Vec<OutdatedDependency>,
+}
+
+/// How much the spec changed since planning started
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SpecTrend {
+    /// Spec word count recorded when a plan first appeared
+    pub baseline_words: u32,
+    pub current_words: u32,
+    /// current / baseline
+    pub growth: f64,
+    /// The spec grew past the scope creep ratio since planning
+    pub scope_creep: bool,
+}
+
+/// Ordered by risk, so `risk <= level` tells whether a level permits an action
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AutomationLevel {
+    L0,  // Read-only
+    L1,  // Low-risk
+    L2,  // Medium-risk
+    L3,  // High-risk
+}
+
+impl AutomationLevel {
+    /// Whether an action of this risk may run unattended at `level`
+    pub fn permitted_at(&self, level: &AutomationLevel) -> bool {
+        self <= level
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PortfolioStatus {
+    pub generated_at: DateTime<Utc>,
+    pub scan_stats: ScanStats,
+    pub projects: Vec<Project>,
+    pub summary: StatusSummary,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ScanStats {
+    pub directories_scanned: u32,
+    pub projects_found: u32,
+    pub scan_time_ms: u64,
+    pub errors: Vec<ScanError>,
+    /// Time spent walking the root to discover projects
+    #[serde(default)]
+    pub discover_ms: u64,
+    /// Slowest projects of the scan, slowest first
+    #[serde(default)]
+    pub slowest: Vec<ProjectTiming>,
+}
+
+/// Number of projects kept in `ScanStats.slowest`
+pub const SLOWEST_PROJECTS: usize = 10;
+
+/// Per-phase processing time for a single project, in milliseconds
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ProjectTiming {
+    pub id: String,
+    #[serde(serialize_with = "lossy_path")]
+    pub path: PathBuf,
+    pub total_ms: u64,
+    pub artifacts_ms: u64,
+    pub tasks_ms: u64,
+    pub git_ms: u64,
+    pub analyze_ms: u64,
+}
+
+impl ProjectTiming {
+    /// The phase that took the longest, with its duration
+    pub fn dominant_phase(&self) -> (ScanPhase, u64) {
+        [
+            (ScanPhase::Artifacts, self.artifacts_ms),
+            (ScanPhase::Tasks, self.tasks_ms),
+            (ScanPhase::Git, self.git_ms),
+            (ScanPhase::Analyze, self.analyze_ms),
+        ]
+        .into_iter()
+        .max_by_key(|(_, ms)| *ms)
+        .unwrap_or((ScanPhase::Unknown, 0))
+    }
+}
+
+impl ScanStats {
+    /// Errors raised during a specific scan phase
+    pub fn errors_in(&self, phase: ScanPhase) -> impl Iterator<Item = &ScanError> {
+        self.errors.iter().filter(move |e| e.phase == phase)
+    }
+
+    /// Number of errors per phase, in phase order
+    pub fn error_counts(&self) -> Vec<(ScanPhase, usize)> {
+        ScanPhase::ALL.iter()
+            .map(|phase| (*phase, self.errors_in(*phase).count()))
+            .filter(|(_, count)| *count > 0)
+            .collect()
+    }
+
+    /// Keep only the slowest projects from a full set of timings
+    pub fn set_slowest(&mut self, mut timings: Vec<ProjectTiming>) {
+        timings.sort_by(|a, b| b.total_ms.cmp(&a.total_ms).then_with(|| a.id.cmp(&b.id)));
+        timings.truncate(SLOWEST_PROJECTS);
+        self.slowest = timings;
+    }
+}
+
+/// A single failure encountered while scanning a project
+///
+/// Serialized as an object with `path`, `phase`, `kind` and `message` fields.
+/// Older caches stored plain strings; those still deserialize, with the
+/// phase and kind set to `Unknown`/`Other`.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct ScanError {
+    #[serde(serialize_with = "lossy_path")]
+    pub path: PathBuf,
+    pub phase: ScanPhase,
+    pub kind: ScanErrorKind,
+    pub message: String,
+}
+
+/// Pipeline phase in which a scan error occurred
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "kebab-case")]
+pub enum ScanPhase {
+    Discover,
+    Artifacts,
+    Tasks,
+    Git,
+    Analyze,
+    Unknown,
+}
+
+/// Machine-readable classification of a scan error
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "kebab-case")]
+pub enum ScanErrorKind {
+    NotFound,
+    PermissionDenied,
+    Io,
+    Parse,
+    Git,
+    Config,
+    Other,
+}
+
+impl ScanPhase {
+    pub const ALL: [ScanPhase; 6] = [
+        ScanPhase::Discover,
+        ScanPhase::Artifacts,
+        ScanPhase::Tasks,
+        ScanPhase::Git,
+        ScanPhase::Analyze,
+        ScanPhase::Unknown,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ScanPhase::Discover => "discover",
+            ScanPhase::Artifacts => "artifacts",
+            ScanPhase::Tasks => "tasks",
+            ScanPhase::Git => "git",
+            ScanPhase::Analyze => "analyze",
+            ScanPhase::Unknown => "unknown",
+        }
+    }
+}
+
+impl std::str::FromStr for ScanPhase {
+    type Err = SKMError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        ScanPhase::ALL.iter()
+            .find(|phase| phase.as_str().eq_ignore_ascii_case(s))
+            .copied()
+            .ok_or_else(|| SKMError::ConfigError {
+                message: format!("Unknown scan phase: {}", s),
+            })
+    }
+}
+
+impl std::fmt::Display for ScanPhase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl ScanErrorKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ScanErrorKind::NotFound => "not-found",
+            ScanErrorKind::PermissionDenied => "permission-denied",
+            ScanErrorKind::Io => "io",
+            ScanErrorKind::Parse => "parse",
+            ScanErrorKind::Git => "git",
+            ScanErrorKind::Config => "config",
+            ScanErrorKind::Other => "other",
+        }
+    }
+}
+
+impl std::fmt::Display for ScanErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl ScanError {
+    pub fn new(path: impl Into<PathBuf>, phase: ScanPhase, kind: ScanErrorKind, message: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            phase,
+            kind,
+            message: message.into(),
+        }
+    }
+
+    /// Build a scan error from a library error, deriving the kind from its variant
+    pub fn from_skm(path: impl Into<PathBuf>, phase: ScanPhase, error: &SKMError) -> Self {
+        Self::new(path, phase, ScanErrorKind::from(error), error.to_string())
+    }
+}
+
+impl From<&SKMError> for ScanErrorKind {
+    fn from(error: &SKMError) -> Self {
+        match error {
+            SKMError::ProjectNotFound { .. } => ScanErrorKind::NotFound,
+            SKMError::ConfigError { .. } => ScanErrorKind::Config,
+            SKMError::CacheError { .. } => ScanErrorKind::Parse,
+            SKMError::FsError { source } => match source.kind() {
+                std::io::ErrorKind::NotFound => ScanErrorKind::NotFound,
+                std::io::ErrorKind::PermissionDenied => ScanErrorKind::PermissionDenied,
+                std::io::ErrorKind::InvalidData => ScanErrorKind::Parse,
+                _ => ScanErrorKind::Io,
+            },
+            #[cfg(feature = "git")]
+            SKMError::GitError { .. } => ScanErrorKind::Git,
+            SKMError::SerdeError { .. } | SKMError::TomlError { .. } | SKMError::TomlSerError { .. } => ScanErrorKind::Parse,
+            SKMError::QdrantError { .. } | SKMError::TmuxError { .. } | SKMError::NotificationError { .. }
+            | SKMError::PublishError { .. } | SKMError::IntegrationError { .. }
+            | SKMError::LockError { .. } | SKMError::FeatureDisabled { .. } => ScanErrorKind::Other,
+        }
+    }
+}
+
+/// Renders the same text older versions stored in `ScanStats.errors`
+impl std::fmt::Display for ScanError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Error processing {}: {}", self.path.display(), self.message)
+    }
+}
+
+impl std::error::Error for ScanError {}
+
+impl<'de> Deserialize<'de> for ScanError {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        struct Structured {
+            path: PathBuf,
+            phase: ScanPhase,
+            kind: ScanErrorKind,
+            message: String,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Structured(Structured),
+            Legacy(String),
+        }
+
+        Ok(match Repr::deserialize(deserializer)? {
+            Repr::Structured(s) => ScanError::new(s.path, s.phase, s.kind, s.message),
+            Repr::Legacy(text) => {
+                // Legacy format: "Error processing <path>: <message>"
+                let (path, message) = text.strip_prefix("Error processing ")
+                    .and_then(|rest| rest.split_once(": "))
+                    .map(|(path, message)| (PathBuf::from(path), message.to_string()))
+                    .unwrap_or_else(|| (PathBuf::new(), text.clone()));
+                ScanError::new(path, ScanPhase::Unknown, ScanErrorKind::Other, message)
+            }
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StatusSummary {
+    pub needs_attention: u32,
+    pub total_projects: u32,
+    pub by_stage: BTreeMap<Stage, u32>,
+    /// Custom stages of the scan's workflow, in workflow order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub custom_stages: Vec<String>,
+    pub total_tasks: u32,
+    pub completed_tasks: u32,
+    pub avg_priority: f64,
+    #[serde(default)]
+    pub completion: CompletionSummary,
+    /// Open questions across all projects
+    #[serde(default)]
+    pub open_questions: u32,
+}
+
+impl StatusSummary {
+    /// Every stage in workflow order: the built-in ones with the workflow's
+    /// custom stages before done, and after those any other custom stage
+    /// a project is in (from a project's own workflow), by name
+    pub fn ladder(&self) -> Vec<Stage> {
+        let others = self.by_stage.keys()
+            .filter_map(|stage| match stage {
+                Stage::Custom(name) if !self.custom_stages.contains(name) => Some(name.as_str()),
+                _ => None,
+            });
+        Stage::ladder(self.custom_stages.iter().map(String::as_str).chain(others))
+    }
+}
+
+/// How far along a set of projects is
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct CompletionSummary {
+    pub projects_done: u32,
+    pub projects_total: u32,
+    /// Share of projects in the `Done` stage
+    pub percent_done: f64,
+    /// Share of all tasks that are completed
+    pub task_percent: f64,
+    /// Mean project progress weighted by task count (see `analyzer::completion`)
+    pub effort_weighted_percent: f64,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub by_root: Vec<RootCompletion>,
+}
+
+/// Completion metrics for one scan root
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RootCompletion {
+    pub root: String,
+    #[serde(serialize_with = "lossy_path")]
+    pub path: PathBuf,
+    pub completion: CompletionSummary,
+    /// Projects above this root's attention threshold
+    #[serde(default)]
+    pub needs_attention: u32,
+}
\ No newline at end of file
@@ -0,0 +1,85 @@
+use std::path::Path;
+use std::fs;
+use std::collections::BTreeMap;
+use serde::{Deserialize, Serialize};
+use crate::Result;
+use crate::{AuditResult, DependencyFreshness, GitHubStatus, Project};
+use super::schema::{self, AUDITS_SCHEMA_VERSION};
+
+/// Last successful dependency audit, freshness check and GitHub query per
+/// project, stored in .skm/audits.json
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AuditStore {
+    pub schema_version: u32,
+    pub projects: BTreeMap<String, AuditResult>,
+    /// Dependency freshness checks
+    #[serde(default)]
+    pub freshness: BTreeMap<String, DependencyFreshness>,
+    /// Pull request and issue counts from GitHub
+    #[serde(default)]
+    pub github: BTreeMap<String, GitHubStatus>,
+}
+
+impl Default for AuditStore {
+    fn default() -> Self {
+        Self {
+            schema_version: AUDITS_SCHEMA_VERSION,
+            projects: BTreeMap::new(),
+            freshness: BTreeMap::new(),
+            github: BTreeMap::new(),
+        }
+    }
+}
+
+impl AuditStore {
+    /// Load audit results from .skm/audits.json
+    pub fn load(root: &Path) -> Result<Self> {
+        let path = root.join(".skm/audits.json");
+        
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        
+        let content = fs::read_to_string(&path)?;
+        let mut doc: serde_json::Value = serde_json::from_str(&content)?;
+        schema::ensure_supported(&mut doc, "audits.json", AUDITS_SCHEMA_VERSION)?;
+        Ok(serde_json::from_value(doc)?)
+    }
+    
+    /// Save audit results to .skm/audits.json
+    pub fn save(&self, root: &Path) -> Result<()> {
+        let skm_dir = root.join(".skm");
+        fs::create_dir_all(&skm_dir)?;
+        
+        let content = serde_json::to_string_pretty(self)?;
+        fs::write(skm_dir.join("audits.json"), content)?;
+        
+        Ok(())
+    }
+    
+    /// Cached result for a project, if it was taken from the same lockfile
+    pub fn get(&self, id: &str, lockfile_hash: &str) -> Option<&AuditResult> {
+        self.projects.get(id).filter(|result| result.lockfile_hash == lockfile_hash)
+    }
+    
+    /// Remember the successful audits, freshness checks and GitHub queries
+    /// of freshly scanned projects
+    ///
+    /// Failed audits, checks that reached no registry and failed queries are
+    /// not cached, so they are retried on the next scan. Freshness checks
+    /// that reached only some registries keep the names of the failed
+    /// lookups, which the next scan retries.
+    pub fn record(&mut self, projects: &[Project]) {
+        for project in projects {
+            if let Some(result) = project.audit.as_ref().filter(|r| r.error.is_none()) {
+                self.projects.insert(project.id.clone(), result.clone());
+            }
+            if let Some(result) = project.freshness.as_ref().filter(|r| r.unchecked < r.direct) {
+                self.freshness.insert(project.id.clone(), result.clone());
+            }
+            if let Some(result) = project.git.github.as_ref().filter(|r| r.error.is_none()) {
+                self.github.insert(project.id.clone(), result.clone());
+            }
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use std::path::Path;
+use std::fs;
+use std::collections::BTreeMap;
+use serde::Deserialize;
+use crate::{Result, SKMError};
+
+/// Metadata changes for many projects at once, read by `skm meta apply`
+///
+/// ```toml
+/// [[change]]
+/// filter = ["stage=bootstrap"]
+/// set = { impact = 3, "custom.tier" = 2 }
+/// ```
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct ChangeSet {
+    #[serde(default, rename = "change")]
+    pub changes: Vec<MetaChange>,
+}
+
+/// Keys to set on every project matching all conditions
+#[derive(Deserialize, Debug, Clone)]
+pub struct MetaChange {
+    /// Conditions as for `--filter`; an empty list matches every project
+    #[serde(default)]
+    pub filter: Vec<String>,
+    /// Values keyed like `skm meta set` (impact, custom.tier, ...)
+    pub set: BTreeMap<String, toml::Value>,
+}
+
+impl ChangeSet {
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| SKMError::ConfigError { message: format!("reading {}: {}", path.display(), e) })?;
+        Ok(toml::from_str(&content)?)
+    }
+}
+
+impl MetaChange {
+    /// Values as the strings `ProjectMetaStore::set_value` expects
+    pub fn assignments(&self) -> Vec<(String, String)> {
+        self.set.iter()
+            .map(|(key, value)| {
+                let value = match value {
+                    toml::Value::String(s) => s.clone(),
+                    other => other.to_string(),
+                };
+                (key.clone(), value)
+            })
+            .collect()
+    }
+}
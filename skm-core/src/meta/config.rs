@@ -0,0 +1,730 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+use crate::Result;
+use crate::{AutomationLevel, NextAction, Project, SKMError, Stage};
+pub use crate::alerts::Severity;
+use crate::analyzer::filter::Filter;
+use crate::analyzer::priority::PriorityStrategy;
+use crate::reporter::ProjectChange;
+use crate::retry::RetryPolicy;
+use crate::timefmt::DisplayTimeZone;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GlobalConfig {
+    pub weights: PriorityWeights,
+    /// How priority is computed: `weighted` (with `weights`), `wsjf`,
+    /// `staleness-first` or an expression over the factors
+    #[serde(default)]
+    pub priority_strategy: PriorityStrategy,
+    pub attention_threshold: f64,
+    pub agent_priority: Vec<String>,
+    pub default_editor: String,
+    pub qdrant_url: String,
+    pub automation_level: AutomationLevel,
+    /// Highest level per stage, e.g. `implement = "L0"`; stages not listed are not capped
+    #[serde(default)]
+    pub stage_automation: BTreeMap<Stage, AutomationLevel>,
+    pub dry_run_default: bool,
+    pub scan_depth: u8,
+    pub watch_interval_secs: u64,
+    pub max_projects: Option<u32>,
+    /// Zone for timestamps in status, reports and digests: utc, local or an offset like +02:00
+    #[serde(default)]
+    pub timezone: DisplayTimeZone,
+    /// Text labels instead of emoji and color-coded symbols, lists instead of tables
+    #[serde(default)]
+    pub accessible: bool,
+    /// Projects processed at once during a scan; 0 uses one per CPU
+    #[serde(default)]
+    pub scan_concurrency: usize,
+    #[serde(default)]
+    pub retry: RetryPolicy,
+    #[serde(default)]
+    pub workflow: WorkflowConfig,
+    /// Named scan roots with their own weights and thresholds (e.g. work and hobby)
+    #[serde(default)]
+    pub roots: Vec<RootConfig>,
+    #[serde(default)]
+    pub snapshots: SnapshotConfig,
+    /// Sprint cadence for per-sprint rollups in digests and `skm sprints`
+    #[serde(default)]
+    pub sprint: Option<SprintConfig>,
+    #[serde(default)]
+    pub init: InitConfig,
+    #[serde(default)]
+    pub discovery: DiscoveryConfig,
+    #[serde(default)]
+    pub code_debt: CodeDebtConfig,
+    #[serde(default)]
+    pub audit: AuditConfig,
+    #[serde(default)]
+    pub freshness: FreshnessConfig,
+    #[serde(default)]
+    pub github: GitHubConfig,
+    #[serde(default)]
+    pub rag: RagConfig,
+    /// Where `skm watch` sends project changes, and how they are batched
+    #[serde(default)]
+    pub notifications: NotificationConfig,
+    #[serde(default)]
+    pub publish: PublishConfig,
+    #[serde(default)]
+    pub report_commit: ReportCommitConfig,
+    /// Service levels for projects waiting on people
+    #[serde(default)]
+    pub sla: SlaConfig,
+    /// Values of `{{portfolio.<name>}}` in spec artifacts, e.g. `deadline = "2025-06-30"`
+    #[serde(default)]
+    pub variables: BTreeMap<String, String>,
+}
+
+/// Fixed-length sprints counted from a start date
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SprintConfig {
+    /// First day of sprint 1
+    pub start: NaiveDate,
+    pub length_days: u32,
+}
+
+impl SprintConfig {
+    /// 1-based number of the sprint containing `at`; `None` before the first sprint
+    pub fn number_at(&self, at: DateTime<Utc>) -> Option<u32> {
+        let days = (at.date_naive() - self.start).num_days();
+        if days < 0 || self.length_days == 0 {
+            return None;
+        }
+        Some((days / self.length_days as i64) as u32 + 1)
+    }
+    
+    /// Start (inclusive) and end (exclusive) of a 1-based sprint, at midnight UTC
+    pub fn bounds(&self, number: u32) -> (DateTime<Utc>, DateTime<Utc>) {
+        let offset = chrono::Days::new((number.saturating_sub(1) * self.length_days) as u64);
+        let start = (self.start + offset).and_time(NaiveTime::MIN).and_utc();
+        (start, start + chrono::Duration::days(self.length_days as i64))
+    }
+}
+
+/// Service levels, in business days (Monday to Friday, UTC)
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct SlaConfig {
+    /// Longest a project may wait in Review; unset disables tracking
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub review_days: Option<u32>,
+}
+
+/// Retention of artifact content snapshots used by `skm spec-diff`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct SnapshotConfig {
+    /// Versions kept per artifact; 0 disables snapshots
+    pub keep: usize,
+}
+
+impl Default for SnapshotConfig {
+    fn default() -> Self {
+        Self { keep: 10 }
+    }
+}
+
+/// How scans find project directories
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct DiscoveryConfig {
+    /// Skip directories ignored by the git repository they are in
+    pub gitignore: bool,
+}
+
+/// Opt-in harvesting of TODO/FIXME comments from project sources
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct CodeDebtConfig {
+    /// Count TODO/FIXME comments during scans
+    pub enabled: bool,
+    /// Also rewrite a `## Code debt` section of tasks.md listing them
+    pub sync_tasks: bool,
+}
+
+/// Dependency vulnerability audits during scans
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct AuditConfig {
+    /// Run `cargo audit`, `npm audit` or `pip-audit` when a project's lockfile changed
+    pub enabled: bool,
+}
+
+/// Comparison of locked dependency versions with their registries during scans
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct FreshnessConfig {
+    /// Query crates.io, npm or PyPI for the latest version of each direct dependency
+    pub enabled: bool,
+    /// Days a result is reused for an unchanged lockfile
+    pub max_age_days: u32,
+}
+
+impl Default for FreshnessConfig {
+    fn default() -> Self {
+        Self { enabled: false, max_age_days: 7 }
+    }
+}
+
+/// Pull request and issue counts from GitHub during scans
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct GitHubConfig {
+    /// Query the GitHub API for projects whose `origin` remote is on `host`
+    pub enabled: bool,
+    /// Environment variable holding the API token
+    pub token_env: String,
+    /// Host of the remotes to query; set with `api_url` for GitHub Enterprise
+    pub host: String,
+    /// `https://<host>/api` for GitHub Enterprise
+    pub api_url: String,
+    /// Minutes a result is reused before the API is queried again
+    pub max_age_minutes: u32,
+}
+
+impl Default for GitHubConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            token_env: "GITHUB_TOKEN".to_string(),
+            host: "github.com".to_string(),
+            api_url: "https://api.github.com".to_string(),
+            max_age_minutes: 15,
+        }
+    }
+}
+
+/// Semantic search over spec artifacts with `skm index` and `skm search`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct RagConfig {
+    /// Qdrant collection holding the artifact chunks
+    pub collection: String,
+    pub embedding: EmbeddingProvider,
+    /// Embedding model, for providers that serve several
+    pub model: String,
+    /// Base URL of the embedding service
+    pub embedding_url: String,
+    /// Vector size of the built-in hashing embedder
+    pub dimensions: usize,
+    /// Chunks longer than this many characters are split at paragraph boundaries
+    pub chunk_chars: usize,
+}
+
+impl Default for RagConfig {
+    fn default() -> Self {
+        Self {
+            collection: "skm_artifacts".to_string(),
+            embedding: EmbeddingProvider::Hash,
+            model: "nomic-embed-text".to_string(),
+            embedding_url: "http://localhost:11434".to_string(),
+            dimensions: 512,
+            chunk_chars: 1500,
+        }
+    }
+}
+
+/// Notifications about project changes seen by `skm watch`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct NotificationConfig {
+    /// Receives each notification as a JSON POST; unset disables webhooks
+    pub webhook_url: Option<String>,
+    /// Changes are collected into one message per this many minutes; 0 sends them as they come.
+    /// Projects rising into the high priority tier are always sent at once.
+    pub batch_minutes: u64,
+    /// Also show notifications on the desktop, with notify-send or osascript
+    pub desktop: bool,
+    /// Least severe change that is notified: `info` (every change, including
+    /// task progress), `warning` (the default: attention threshold crossings,
+    /// newly blocked tasks and removed projects, plus critical ones) or
+    /// `critical`
+    pub min_severity: Severity,
+}
+
+impl Default for NotificationConfig {
+    fn default() -> Self {
+        Self {
+            webhook_url: None,
+            batch_minutes: 30,
+            desktop: false,
+            min_severity: Severity::Warning,
+        }
+    }
+}
+
+impl NotificationConfig {
+    pub fn batch_interval(&self) -> Duration {
+        Duration::from_secs(self.batch_minutes * 60)
+    }
+
+    /// Whether a change is severe enough to be notified
+    pub fn notifies(&self, change: &ProjectChange) -> bool {
+        Severity::of(change) >= self.min_severity
+    }
+}
+
+/// Pages `skm publish` pushes the status report to
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct PublishConfig {
+    pub notion: Option<NotionConfig>,
+    pub confluence: Option<ConfluenceConfig>,
+}
+
+/// Commit STATUS.md after scans and digests after `skm digest` to a git
+/// repository, so the report history lives in git
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct ReportCommitConfig {
+    pub enabled: bool,
+    /// Working tree of the repository; unset means the repository containing the root
+    pub repo: Option<PathBuf>,
+    /// Directory in the repository the reports are copied to
+    pub path: String,
+    /// Commit message; `{files}` becomes the changed report names and `{date}` the day
+    pub message: String,
+}
+
+impl Default for ReportCommitConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            repo: None,
+            path: "reports".to_string(),
+            message: "Update {files} ({date})".to_string(),
+        }
+    }
+}
+
+impl ReportCommitConfig {
+    /// Repository path with a leading `~/` expanded to the home directory
+    pub fn resolved_repo(&self) -> Option<PathBuf> {
+        let repo = self.repo.as_ref()?;
+        Some(match (repo.strip_prefix("~"), std::env::var("HOME")) {
+            (Ok(rest), Ok(home)) => PathBuf::from(home).join(rest),
+            _ => repo.clone(),
+        })
+    }
+}
+
+/// A Notion page whose content is replaced by the report
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NotionConfig {
+    /// The page id, from the end of the page's URL
+    pub page_id: String,
+    /// Environment variable holding the integration token; the page must be shared with the integration
+    #[serde(default = "default_notion_token_env")]
+    pub token_env: String,
+}
+
+fn default_notion_token_env() -> String {
+    "NOTION_TOKEN".to_string()
+}
+
+/// A Confluence page whose body is replaced by the report
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ConfluenceConfig {
+    /// Site base URL including the context path, e.g. `https://example.atlassian.net/wiki`
+    pub base_url: String,
+    pub page_id: String,
+    /// Account email for Confluence Cloud API tokens; unset sends the token as a bearer token
+    /// (Data Center personal access tokens)
+    #[serde(default)]
+    pub email: Option<String>,
+    /// Environment variable holding the API token
+    #[serde(default = "default_confluence_token_env")]
+    pub token_env: String,
+}
+
+fn default_confluence_token_env() -> String {
+    "CONFLUENCE_TOKEN".to_string()
+}
+
+/// Where artifact chunks get their vectors from
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum EmbeddingProvider {
+    /// Built-in feature hashing of words and word pairs; needs no service
+    #[default]
+    Hash,
+    /// An Ollama server's `/api/embed` endpoint
+    Ollama,
+}
+
+/// How `skm init` and `skm new-feature` create Spec-Kit files
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct InitConfig {
+    pub backend: InitBackend,
+    /// Extra arguments for `specify init`, e.g. `["--ai", "claude"]`
+    pub specify_args: Vec<String>,
+}
+
+/// Which scaffolding `skm init` and `skm new-feature` use
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum InitBackend {
+    /// The official `specify` CLI when it is on PATH, otherwise skm's templates
+    #[default]
+    Auto,
+    /// skm's built-in templates
+    Internal,
+    /// Always the `specify` CLI; fails when it is not installed
+    Specify,
+}
+
+impl std::str::FromStr for InitBackend {
+    type Err = SKMError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "auto" => Ok(InitBackend::Auto),
+            "internal" => Ok(InitBackend::Internal),
+            "specify" => Ok(InitBackend::Specify),
+            _ => Err(SKMError::ConfigError {
+                message: format!("Unknown init backend: {} (expected auto, internal or specify)", s),
+            }),
+        }
+    }
+}
+
+/// Attention threshold used when none is configured
+pub const DEFAULT_ATTENTION_THRESHOLD: f64 = 50.0;
+
+/// A named scan root; unset fields fall back to the global settings
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RootConfig {
+    /// Label projects are shown and filtered by, e.g. `clients/api` and `root:clients`
+    #[serde(alias = "label")]
+    pub name: String,
+    pub path: PathBuf,
+    #[serde(default)]
+    pub weights: Option<PriorityWeights>,
+    #[serde(default)]
+    pub attention_threshold: Option<f64>,
+    /// Maximum directory depth of discovery under this root
+    #[serde(default)]
+    pub scan_depth: Option<u8>,
+    /// Minimum seconds between `skm watch` rescans of this root
+    #[serde(default)]
+    pub watch_interval_secs: Option<u64>,
+}
+
+impl RootConfig {
+    /// Root path with a leading `~/` expanded to the home directory
+    pub fn resolved_path(&self) -> PathBuf {
+        match (self.path.strip_prefix("~"), std::env::var("HOME")) {
+            (Ok(rest), Ok(home)) => PathBuf::from(home).join(rest),
+            _ => self.path.clone(),
+        }
+    }
+}
+
+/// Optional Spec-Kit stages that skm should expect before moving on
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct WorkflowConfig {
+    /// Recommend `/speckit.clarify` until the spec has a Clarifications section
+    pub clarify: bool,
+    /// Recommend `/speckit.analyze` before the first task is completed
+    pub analyze: bool,
+    /// Stages of the team's own between review and done, in workflow order
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub stages: Vec<CustomStage>,
+}
+
+impl Default for WorkflowConfig {
+    fn default() -> Self {
+        Self {
+            clarify: true,
+            analyze: true,
+            stages: Vec::new(),
+        }
+    }
+}
+
+impl WorkflowConfig {
+    /// Check that custom stage names are new, unique kebab-case names and
+    /// their rules parse
+    pub fn validate(&self) -> Result<()> {
+        let invalid = |message: String| SKMError::ConfigError { message };
+        for (i, stage) in self.stages.iter().enumerate() {
+            match stage.name.parse::<Stage>() {
+                Ok(Stage::Custom(name)) if name == stage.name => {}
+                Ok(Stage::Custom(name)) => {
+                    return Err(invalid(format!("Custom stage {} must be written in kebab-case: {}", stage.name, name)));
+                }
+                Ok(_) => return Err(invalid(format!("Custom stage {} is already a built-in stage", stage.name))),
+                Err(e) => return Err(e),
+            }
+            if self.stages[..i].iter().any(|other| other.name == stage.name) {
+                return Err(invalid(format!("Custom stage {} is defined twice", stage.name)));
+            }
+            stage.rule()?;
+        }
+        Ok(())
+    }
+
+    /// The custom stage with this name
+    pub fn custom_stage(&self, name: &str) -> Option<&CustomStage> {
+        self.stages.iter().find(|stage| stage.name == name)
+    }
+}
+
+/// A stage of the team's own, such as a security review or a rollout
+///
+/// Once the built-in stages reach review or done, a project is in the first
+/// custom stage whose `when` rule it matches. Rules use the `--only` syntax
+/// and see the project as analyzed with its built-in stage.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CustomStage {
+    /// Kebab-case name, e.g. `security-review`
+    pub name: String,
+    /// Filter expression, e.g. `custom.security!=approved`
+    pub when: String,
+    /// Next action; `{id}`, `{stage}` and `{path}` are replaced
+    pub command: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub automated: bool,
+    #[serde(default = "default_custom_risk")]
+    pub risk_level: AutomationLevel,
+    /// The stage waits for a person, like review
+    #[serde(default)]
+    pub human: bool,
+}
+
+fn default_custom_risk() -> AutomationLevel {
+    AutomationLevel::L1
+}
+
+impl CustomStage {
+    pub fn rule(&self) -> Result<Filter> {
+        self.when.parse().map_err(|e: SKMError| SKMError::ConfigError {
+            message: format!("Custom stage {}: {}", self.name, e),
+        })
+    }
+
+    /// The next action for a project in this stage
+    pub fn next_action(&self, project: &Project) -> NextAction {
+        let render = |template: &str| template
+            .replace("{id}", &project.id)
+            .replace("{stage}", &self.name)
+            .replace("{path}", &project.path.to_string_lossy());
+        NextAction {
+            command: render(&self.command),
+            description: render(&self.description),
+            automated: self.automated,
+            risk_level: self.risk_level.clone(),
+            launch: Vec::new(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PriorityWeights {
+    pub needs_human: f64,
+    pub risk: f64,
+    pub staleness: f64,
+    pub impact: f64,
+    pub confidence: f64,
+}
+
+
+impl Default for GlobalConfig {
+    fn default() -> Self {
+        Self {
+            weights: PriorityWeights::default(),
+            priority_strategy: PriorityStrategy::default(),
+            attention_threshold: DEFAULT_ATTENTION_THRESHOLD,
+            agent_priority: vec!["claude".to_string(), "cursor".to_string(), "nvim".to_string(), "bash".to_string()],
+            default_editor: "nvim".to_string(),
+            qdrant_url: "http://localhost:6333".to_string(),
+            automation_level: AutomationLevel::L1,
+            stage_automation: BTreeMap::new(),
+            dry_run_default: true,
+            scan_depth: 5,
+            watch_interval_secs: 5,
+            max_projects: None,
+            timezone: DisplayTimeZone::Utc,
+            accessible: false,
+            scan_concurrency: 0,
+            retry: RetryPolicy::default(),
+            workflow: WorkflowConfig::default(),
+            roots: Vec::new(),
+            snapshots: SnapshotConfig::default(),
+            sprint: None,
+            init: InitConfig::default(),
+            discovery: DiscoveryConfig::default(),
+            code_debt: CodeDebtConfig::default(),
+            audit: AuditConfig::default(),
+            freshness: FreshnessConfig::default(),
+            github: GitHubConfig::default(),
+            rag: RagConfig::default(),
+            notifications: NotificationConfig::default(),
+            publish: PublishConfig::default(),
+            report_commit: ReportCommitConfig::default(),
+            sla: SlaConfig::default(),
+            variables: BTreeMap::new(),
+        }
+    }
+}
+
+impl Default for PriorityWeights {
+    fn default() -> Self {
+        Self {
+            needs_human: 40.0,
+            risk: 25.0,
+            staleness: 15.0,
+            impact: 15.0,
+            confidence: 10.0,
+        }
+    }
+}
+
+
+/// Merge `overrides` into `base`: tables key by key, anything else replaced
+fn merge_toml(base: &mut toml::Value, overrides: toml::Value) {
+    match (base, overrides) {
+        (toml::Value::Table(base), toml::Value::Table(overrides)) => {
+            for (key, value) in overrides {
+                match base.get_mut(&key) {
+                    Some(existing) => merge_toml(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, value) => *base = value,
+    }
+}
+
+/// Top-level settings a project may override in its own `.skm/config.toml`
+pub const PROJECT_SETTINGS: &[&str] = &[
+    "attention_threshold",
+    "weights",
+    "priority_strategy",
+    "automation_level",
+    "stage_automation",
+    "workflow",
+    "agent_priority",
+    "code_debt",
+    "audit",
+    "freshness",
+    "sla",
+    "variables",
+];
+
+impl GlobalConfig {
+    pub fn load() -> Result<Self> {
+        let config_path = Self::config_path()?;
+        
+        if config_path.exists() {
+            let content = std::fs::read_to_string(&config_path)?;
+            let config: GlobalConfig = toml::from_str(&content)?;
+            config.workflow.validate()?;
+            Ok(config)
+        } else {
+            Ok(Self::default())
+        }
+    }
+    
+    pub fn save(&self) -> Result<()> {
+        let config_path = Self::config_path()?;
+        
+        if let Some(parent) = config_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        
+        let content = toml::to_string_pretty(self)?;
+        std::fs::write(&config_path, content)?;
+        
+        Ok(())
+    }
+    
+    pub fn config_path() -> Result<PathBuf> {
+        Ok(Self::config_dir()?.join("config.toml"))
+    }
+    
+    /// Settings for one root: its weights, threshold and depth override the global ones
+    pub fn for_root(&self, root: &RootConfig) -> GlobalConfig {
+        let mut config = self.clone();
+        if let Some(weights) = &root.weights {
+            config.weights = weights.clone();
+        }
+        if let Some(threshold) = root.attention_threshold {
+            config.attention_threshold = threshold;
+        }
+        if let Some(depth) = root.scan_depth {
+            config.scan_depth = depth;
+        }
+        if let Some(secs) = root.watch_interval_secs {
+            config.watch_interval_secs = secs;
+        }
+        config
+    }
+    
+    /// Settings for one project, with its `.skm/config.toml` merged over these
+    ///
+    /// Tables merge key by key, so `[weights] risk = 40` keeps the other
+    /// weights; other values replace the inherited ones. Only
+    /// [`PROJECT_SETTINGS`] may appear in the file. Returns `None` when the
+    /// project has no config file.
+    pub fn for_project(&self, project_path: &Path) -> Result<Option<GlobalConfig>> {
+        let path = project_path.join(".skm/config.toml");
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let invalid = |message: String| SKMError::ConfigError { message: format!("{}: {}", path.display(), message) };
+        let overrides: toml::Table = toml::from_str(&content).map_err(|e| invalid(e.message().to_string()))?;
+        if let Some(key) = overrides.keys().find(|key| !PROJECT_SETTINGS.contains(&key.as_str())) {
+            return Err(invalid(format!("{} cannot be set per project (allowed: {})", key, PROJECT_SETTINGS.join(", "))));
+        }
+
+        let mut merged = toml::Value::try_from(self)?;
+        merge_toml(&mut merged, toml::Value::Table(overrides));
+        let config: GlobalConfig = merged.try_into().map_err(|e: toml::de::Error| invalid(e.message().to_string()))?;
+        config.workflow.validate().map_err(|e| match e {
+            SKMError::ConfigError { message } => invalid(message),
+            e => e,
+        })?;
+        Ok(Some(config))
+    }
+    
+    /// Where the combined portfolio of all configured roots is cached
+    ///
+    /// The cache lives in `.skm` under this directory, like any scan root.
+    pub fn portfolio_home() -> Result<PathBuf> {
+        Self::config_dir()
+    }
+    
+    /// Directory holding user-level skm files (`~/.config/skm`)
+    pub fn config_dir() -> Result<PathBuf> {
+        let home = std::env::var("HOME")
+            .map_err(|_| SKMError::ConfigError { message: "HOME is not set".to_string() })?;
+        Ok(PathBuf::from(home).join(".config/skm"))
+    }
+    
+    pub fn watch_interval(&self) -> Duration {
+        Duration::from_secs(self.watch_interval_secs)
+    }
+    
+    /// Number of scan workers: `scan_concurrency`, or one per CPU when unset
+    pub fn scan_workers(&self) -> usize {
+        match self.scan_concurrency {
+            0 => std::thread::available_parallelism().map_or(1, |n| n.get()),
+            n => n,
+        }
+    }
+}
\ No newline at end of file
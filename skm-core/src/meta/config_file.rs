@@ -0,0 +1,217 @@
+use crate::{Result, SKMError};
+use super::config::GlobalConfig;
+
+/// Comments written above keys and sections by `skm config init`, by dotted path
+const FIELD_DOCS: &[(&str, &str)] = &[
+    ("attention_threshold", "Priority threshold for \"needs attention\""),
+    ("agent_priority", "Agents offered to start a next action, most preferred first"),
+    ("default_editor", "Editor for manual edits"),
+    ("qdrant_url", "Vector store for skm index / search"),
+    ("automation_level", "Highest risk autopilot runs unattended (L0-L3)"),
+    ("dry_run_default", "autopilot only reports unless --execute"),
+    ("scan_depth", "Maximum directory depth"),
+    ("watch_interval_secs", "Minimum seconds between `skm watch` rescans"),
+    ("timezone", "Timestamps in output: \"utc\", \"local\" or an offset like \"+02:00\""),
+    ("accessible", "Text labels instead of emoji, lists instead of tables"),
+    ("scan_concurrency", "Projects processed at once; 0 = one per CPU"),
+    ("roots", "Named roots, each with optional weights, threshold, depth and watch interval overrides, e.g.\n[[roots]]\nname = \"work\"\npath = \"~/work\"\nattention_threshold = 40.0\nscan_depth = 3\nwatch_interval_secs = 60"),
+    ("priority_strategy", "weighted (uses [weights]), wsjf, staleness-first, or an expression over\nneeds_human, risk, staleness, impact, confidence and effort, e.g. \"50*staleness + 50*risk\""),
+    ("weights", "Priority calculation weights"),
+    ("weights.needs_human", "Human attention required"),
+    ("weights.risk", "Risk assessment"),
+    ("weights.staleness", "Days since update"),
+    ("weights.impact", "Project importance"),
+    ("weights.confidence", "Solution certainty"),
+    ("stage_automation", "Caps automation_level per stage, e.g. implement = \"L0\""),
+    ("retry", "Retry policy for git and network operations (transient errors only)"),
+    ("retry.max_attempts", "1 disables retrying"),
+    ("retry.base_delay_ms", "Doubles on each retry"),
+    ("retry.jitter", "Fraction of each delay that is randomized"),
+    ("workflow", "Optional Spec-Kit stages"),
+    ("workflow.clarify", "Suggest /speckit.clarify until spec.md has a \"Clarifications\" section"),
+    ("workflow.analyze", "Suggest /speckit.analyze before the first task is completed"),
+    ("snapshots", "Artifact versions kept for skm spec-diff"),
+    ("snapshots.keep", "0 disables snapshots"),
+    ("init", "Scaffolding for skm init / new-feature: auto (specify when installed), internal or specify"),
+    ("init.specify_args", "Passed to specify init"),
+    ("discovery", "Project discovery; directories listed in .skmignore files are always skipped"),
+    ("discovery.gitignore", "Also skip directories ignored by the git repository they are in"),
+    ("code_debt", "Count TODO/FIXME comments in project sources"),
+    ("code_debt.sync_tasks", "Also rewrite a \"## Code debt\" section of tasks.md"),
+    ("audit", "Audit dependencies for known vulnerabilities during scans"),
+    ("freshness", "Compare locked dependency versions with crates.io, npm and PyPI"),
+    ("freshness.max_age_days", "Reuse a result this long while the lockfile is unchanged"),
+    ("github", "Open pull requests, failing checks, review requests and issues from GitHub"),
+    ("github.token_env", "Environment variable with the API token"),
+    ("github.host", "Host of the origin remotes to query"),
+    ("github.api_url", "https://<host>/api for GitHub Enterprise"),
+    ("github.max_age_minutes", "Reuse a result this long before querying again"),
+    ("rag", "Semantic search over spec artifacts (skm index / skm search)"),
+    ("rag.embedding", "Built-in \"hash\", or \"ollama\""),
+    ("rag.model", "Ollama model"),
+    ("rag.embedding_url", "Ollama server"),
+    ("rag.dimensions", "Vector size of the hash embedder"),
+    ("rag.chunk_chars", "Longer sections are split at blank lines"),
+    ("notifications", "Notifications from skm watch; set webhook_url to enable, e.g.\nwebhook_url = \"https://hooks.slack.com/services/...\""),
+    ("notifications.batch_minutes", "One message per interval; 0 = immediately"),
+    ("notifications.desktop", "Also show notifications on the desktop (notify-send or osascript)"),
+    ("notifications.min_severity", "info (every change), warning (the default: attention crossings, newly blocked tasks) or critical"),
+    ("publish", "Pages skm publish --target notion / confluence replace with the status report, e.g.\n[publish.notion]\npage_id = \"...\"          # token in $NOTION_TOKEN\n[publish.confluence]\nbase_url = \"https://example.atlassian.net/wiki\"\npage_id = \"123456\"\nemail = \"me@example.com\"  # token in $CONFLUENCE_TOKEN"),
+    ("sla", "Service levels in business days (Monday to Friday, UTC), e.g.\nreview_days = 3   # Reviews must start within 3 business days"),
+    ("variables", "Values of {{portfolio.<name>}} in spec artifacts, e.g.\ndeadline = \"2025-06-30\""),
+    ("report_commit", "Commit STATUS.md after scans and digests after skm digest, when more than their timestamps changed"),
+    ("report_commit.repo", "Working tree of the repository; unset means the one containing the root, e.g.\nrepo = \"~/portfolio\""),
+    ("report_commit.path", "Directory in the repository the reports are copied to"),
+    ("report_commit.message", "{files} = changed reports, {date} = the day"),
+];
+
+/// The default configuration as TOML, with a comment on each setting
+///
+/// Optional settings that are unset by default are shown as commented-out examples.
+pub fn commented_default() -> Result<String> {
+    let plain = toml::to_string_pretty(&GlobalConfig::default())?;
+    let mut out = String::from("# skm configuration; every setting shown has its default value\n\n");
+    out.push_str("# Stop scanning after this many projects\n# max_projects = 100\n\n");
+    out.push_str("# Sprint cadence for per-sprint rollups in digests and `skm sprints`, e.g.\n");
+    out.push_str("# [sprint]\n# start = \"2025-01-06\"\n# length_days = 14\n\n");
+
+    let mut section = String::new();
+    for line in plain.lines() {
+        let path = if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            section = name.to_string();
+            Some(name.to_string())
+        } else {
+            line.split_once(" = ").map(|(key, _)| match section.as_str() {
+                "" => key.to_string(),
+                section => format!("{}.{}", section, key),
+            })
+        };
+        if let Some((_, doc)) = path.and_then(|path| FIELD_DOCS.iter().find(|(key, _)| *key == path)) {
+            for doc_line in doc.lines() {
+                out.push_str(&format!("# {}\n", doc_line));
+            }
+        }
+        out.push_str(line);
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+/// Set a dotted key like `weights.staleness` in a config file's content,
+/// keeping its comments and layout
+///
+/// The value is read as a TOML value (`20`, `true`, `["a"]`) when it parses
+/// as one and fits the setting, and as a string otherwise. Missing tables
+/// are created. The result must
+/// load as a valid configuration that knows the key.
+pub fn set_value(content: &str, key: &str, value: &str) -> Result<String> {
+    let parts: Vec<&str> = key.split('.').collect();
+    if parts.iter().any(|part| part.is_empty()) {
+        return Err(config_error(format!("Invalid config key: {}", key)));
+    }
+
+    let mut candidates = Vec::new();
+    // Dates stay strings: settings hold them as text, not TOML datetimes
+    if let Ok(typed) = value.parse::<toml_edit::Value>() && !typed.is_datetime() {
+        candidates.push(typed);
+    }
+    candidates.push(toml_edit::Value::from(value));
+
+    let mut first_error = None;
+    for candidate in candidates {
+        match with_value(content, &parts, candidate) {
+            Ok(text) => return Ok(text),
+            Err(e) => {
+                first_error.get_or_insert(e);
+            }
+        }
+    }
+    Err(first_error.unwrap_or_else(|| config_error(format!("Cannot set {}", key))))
+}
+
+fn with_value(content: &str, parts: &[&str], value: toml_edit::Value) -> Result<String> {
+    let mut doc: toml_edit::DocumentMut = content.parse()
+        .map_err(|e: toml_edit::TomlError| config_error(format!("The config is not valid TOML: {}", e.message())))?;
+    let (last, tables) = parts.split_last().ok_or_else(|| config_error("Empty config key".to_string()))?;
+    let mut table: &mut dyn toml_edit::TableLike = doc.as_table_mut();
+    for (depth, part) in tables.iter().enumerate() {
+        if table.get(part).is_none() {
+            table.insert(part, toml_edit::table());
+        }
+        table = table.get_mut(part)
+            .and_then(|item| item.as_table_like_mut())
+            .ok_or_else(|| config_error(format!("{} is not a table", parts[..=depth].join("."))))?;
+    }
+    table.insert(last, toml_edit::value(value));
+
+    let text = doc.to_string();
+    let config: GlobalConfig = toml::from_str(&text)
+        .map_err(|e| config_error(format!("Cannot set {}: the config would not load: {}", parts.join("."), e.message())))?;
+    if !has_path(&toml::Value::try_from(&config)?, parts) {
+        return Err(config_error(format!("Unknown config key: {}", parts.join("."))));
+    }
+    Ok(text)
+}
+
+/// Dotted paths of the keys in a config file's content that no setting
+/// reads, such as misspelled ones; they are ignored when loading
+///
+/// Keys inside maps like `stage_automation` and arrays of tables like
+/// `[[roots]]` are checked too.
+pub fn unknown_keys(content: &str) -> Result<Vec<String>> {
+    let raw: toml::Value = toml::from_str(content)
+        .map_err(|e: toml::de::Error| config_error(format!("The config is not valid TOML: {}", e.message())))?;
+    let config: GlobalConfig = raw.clone().try_into()
+        .map_err(|e: toml::de::Error| config_error(e.message().to_string()))?;
+    let mut unknown = Vec::new();
+    collect_unknown(&raw, &toml::Value::try_from(&config)?, "", &mut unknown);
+    Ok(unknown)
+}
+
+/// Keys read under another name in the entries of an array of tables,
+/// such as `label` for a `[[roots]]` entry's `name`
+const KEY_ALIASES: &[(&str, &str, &str)] = &[("roots", "label", "name")];
+
+/// The name `key` is read under at `path`
+fn canonical_key<'a>(path: &str, key: &'a str) -> &'a str {
+    let array = path.strip_suffix(']').and_then(|path| path.rsplit_once('[')).map(|(array, _)| array);
+    KEY_ALIASES.iter()
+        .find(|(table, alias, _)| Some(*table) == array && alias == &key)
+        .map_or(key, |(_, _, name)| name)
+}
+
+fn collect_unknown(raw: &toml::Value, effective: &toml::Value, path: &str, unknown: &mut Vec<String>) {
+    match (raw, effective) {
+        (toml::Value::Table(raw), toml::Value::Table(effective)) => {
+            for (key, value) in raw {
+                let child = if path.is_empty() { key.clone() } else { format!("{}.{}", path, key) };
+                let name = canonical_key(path, key);
+                match effective.iter().find(|(known, _)| known.eq_ignore_ascii_case(name)) {
+                    Some((_, known)) => collect_unknown(value, known, &child, unknown),
+                    None => unknown.push(child),
+                }
+            }
+        }
+        (toml::Value::Array(raw), toml::Value::Array(effective)) => {
+            for (i, (value, known)) in raw.iter().zip(effective).enumerate() {
+                collect_unknown(value, known, &format!("{}[{}]", path, i), unknown);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Whether the effective config has a value at `parts`; names are compared
+/// case-insensitively since stage keys are written back lowercased
+fn has_path(value: &toml::Value, parts: &[&str]) -> bool {
+    let Some((first, rest)) = parts.split_first() else {
+        return true;
+    };
+    value.as_table()
+        .and_then(|table| table.iter().find(|(key, _)| key.eq_ignore_ascii_case(first)))
+        .is_some_and(|(_, child)| has_path(child, rest))
+}
+
+fn config_error(message: String) -> SKMError {
+    SKMError::ConfigError { message }
+}
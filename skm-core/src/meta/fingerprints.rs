@@ -0,0 +1,59 @@
+use std::path::Path;
+use std::fs;
+use std::collections::BTreeMap;
+use serde::{Deserialize, Serialize};
+use crate::Result;
+use super::schema::{self, FINGERPRINTS_SCHEMA_VERSION};
+
+/// Fingerprint of each project's scan inputs as of its last processing,
+/// keyed by project path and stored in .skm/fingerprints.json
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FingerprintStore {
+    pub schema_version: u32,
+    pub projects: BTreeMap<String, String>,
+}
+
+impl Default for FingerprintStore {
+    fn default() -> Self {
+        Self {
+            schema_version: FINGERPRINTS_SCHEMA_VERSION,
+            projects: BTreeMap::new(),
+        }
+    }
+}
+
+impl FingerprintStore {
+    /// Load fingerprints from .skm/fingerprints.json
+    pub fn load(root: &Path) -> Result<Self> {
+        let path = root.join(".skm/fingerprints.json");
+        
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        
+        let content = fs::read_to_string(&path)?;
+        let mut doc: serde_json::Value = serde_json::from_str(&content)?;
+        schema::ensure_supported(&mut doc, "fingerprints.json", FINGERPRINTS_SCHEMA_VERSION)?;
+        Ok(serde_json::from_value(doc)?)
+    }
+    
+    /// Save fingerprints to .skm/fingerprints.json
+    pub fn save(&self, root: &Path) -> Result<()> {
+        let skm_dir = root.join(".skm");
+        fs::create_dir_all(&skm_dir)?;
+        
+        let content = serde_json::to_string_pretty(self)?;
+        fs::write(skm_dir.join("fingerprints.json"), content)?;
+        
+        Ok(())
+    }
+    
+    /// Whether a project was last processed with the same fingerprint
+    pub fn unchanged(&self, project: &Path, fingerprint: &str) -> bool {
+        self.projects.get(&project.to_string_lossy().into_owned()).is_some_and(|f| f == fingerprint)
+    }
+    
+    pub fn insert(&mut self, project: &Path, fingerprint: String) {
+        self.projects.insert(project.to_string_lossy().into_owned(), fingerprint);
+    }
+}
@@ -0,0 +1,273 @@
+use std::path::Path;
+use std::fs;
+use std::collections::BTreeMap;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use crate::Result;
+use crate::{Project, SpecTrend, Stage, UnstableField};
+use crate::analyzer::requirements::{self, RequirementEpisode};
+use super::schema::{self, METRICS_SCHEMA_VERSION};
+
+/// Number of metric samples kept per project
+const MAX_SAMPLES: usize = 30;
+
+/// Number of stage transitions kept per project
+const MAX_TRANSITIONS: usize = 100;
+
+/// Number of human requirement episodes kept per project
+const MAX_EPISODES: usize = 100;
+
+/// Spec growth ratio since planning that counts as scope creep
+pub const SCOPE_CREEP_RATIO: f64 = 2.0;
+
+/// Priority change between scans of unchanged inputs that counts as unstable
+pub const PRIORITY_JITTER: f64 = 20.0;
+
+/// Artifact size history per project, stored in .skm/metrics.json
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MetricsHistory {
+    pub schema_version: u32,
+    pub projects: BTreeMap<String, ProjectMetricsHistory>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ProjectMetricsHistory {
+    /// Spec word count when a plan was first seen
+    #[serde(default)]
+    pub spec_words_at_plan: Option<u32>,
+    #[serde(default)]
+    pub samples: Vec<MetricsSample>,
+    /// Inputs and results of the most recent scan
+    #[serde(default)]
+    pub last_scan: Option<ScanOutcome>,
+    /// Fields seen to differ while the inputs stayed the same; cleared when they change
+    #[serde(default)]
+    pub unstable: Vec<UnstableField>,
+    /// Stage at the last scan and when the project was first seen in it
+    #[serde(default)]
+    pub stage: Option<Stage>,
+    #[serde(default)]
+    pub stage_since: Option<DateTime<Utc>>,
+    /// Every stage the project entered, oldest first
+    #[serde(default)]
+    pub stages: Vec<StageTransition>,
+    /// Human requirements raised by scans and when they were addressed, oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub requirements: Vec<RequirementEpisode>,
+}
+
+/// A project entering a stage, as first seen by a scan
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct StageTransition {
+    pub at: DateTime<Utc>,
+    pub stage: Stage,
+}
+
+/// Stage changes of a set of projects since a point in time
+#[derive(Debug, Clone, PartialEq)]
+pub struct StageVelocity {
+    pub since: DateTime<Utc>,
+    /// Stage changes in the period
+    pub transitions: u32,
+    /// Projects that changed stage at least once
+    pub projects: u32,
+    /// Mean days spent in each stage, over the stays that ended in the period
+    pub mean_days: BTreeMap<Stage, f64>,
+}
+
+/// What a scan read and concluded for a project
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ScanOutcome {
+    /// Fingerprint of the analysis inputs
+    pub inputs: String,
+    pub stage: Stage,
+    pub priority: f64,
+}
+
+/// Word counts of the main artifacts at one scan
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MetricsSample {
+    pub at: DateTime<Utc>,
+    pub spec_words: Option<u32>,
+    pub plan_words: Option<u32>,
+    pub tasks_words: Option<u32>,
+}
+
+impl Default for MetricsHistory {
+    fn default() -> Self {
+        Self {
+            schema_version: METRICS_SCHEMA_VERSION,
+            projects: BTreeMap::new(),
+        }
+    }
+}
+
+impl MetricsHistory {
+    /// Load metrics history from .skm/metrics.json
+    pub fn load(root: &Path) -> Result<Self> {
+        let path = root.join(".skm/metrics.json");
+        
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        
+        let content = fs::read_to_string(&path)?;
+        let mut doc: serde_json::Value = serde_json::from_str(&content)?;
+        schema::ensure_supported(&mut doc, "metrics.json", METRICS_SCHEMA_VERSION)?;
+        Ok(serde_json::from_value(doc)?)
+    }
+    
+    /// Save metrics history to .skm/metrics.json
+    pub fn save(&self, root: &Path) -> Result<()> {
+        let skm_dir = root.join(".skm");
+        fs::create_dir_all(&skm_dir)?;
+        
+        let content = serde_json::to_string_pretty(self)?;
+        fs::write(skm_dir.join("metrics.json"), content)?;
+        
+        Ok(())
+    }
+    
+    /// Spec growth of a project relative to the size recorded when planning started
+    pub fn spec_trend(&self, project_id: &str, current_words: Option<u32>) -> Option<SpecTrend> {
+        let baseline = self.projects.get(project_id)?.spec_words_at_plan?;
+        let current = current_words?;
+        if baseline == 0 {
+            return None;
+        }
+        
+        let growth = current as f64 / baseline as f64;
+        Some(SpecTrend {
+            baseline_words: baseline,
+            current_words: current,
+            growth,
+            scope_creep: growth >= SCOPE_CREEP_RATIO,
+        })
+    }
+    
+    /// When the project was first seen in `stage`, if that is the stage it was last recorded in
+    pub fn stage_since(&self, project_id: &str, stage: &Stage) -> Option<DateTime<Utc>> {
+        let entry = self.projects.get(project_id)?;
+        entry.stage_since.filter(|_| entry.stage.as_ref() == Some(stage))
+    }
+    
+    /// The stages a project entered, oldest first
+    pub fn timeline(&self, project_id: &str) -> &[StageTransition] {
+        self.projects.get(project_id).map(|entry| entry.stages.as_slice()).unwrap_or_default()
+    }
+    
+    /// Stage changes of the given projects after `since`
+    pub fn velocity<'a>(&self, project_ids: impl IntoIterator<Item = &'a str>, since: DateTime<Utc>) -> StageVelocity {
+        let mut velocity = StageVelocity { since, transitions: 0, projects: 0, mean_days: BTreeMap::new() };
+        let mut stays: BTreeMap<Stage, Vec<f64>> = BTreeMap::new();
+        for id in project_ids {
+            let changes: Vec<_> = self.timeline(id).windows(2).filter(|pair| pair[1].at > since).collect();
+            if changes.is_empty() {
+                continue;
+            }
+            velocity.projects += 1;
+            velocity.transitions += changes.len() as u32;
+            for pair in changes {
+                let days = (pair[1].at - pair[0].at).num_seconds().max(0) as f64 / 86_400.0;
+                stays.entry(pair[0].stage.clone()).or_default().push(days);
+            }
+        }
+        velocity.mean_days = stays.into_iter()
+            .map(|(stage, days)| (stage, days.iter().sum::<f64>() / days.len() as f64))
+            .collect();
+        velocity
+    }
+    
+    /// Fields whose result differs from the last scan of the same inputs
+    ///
+    /// Flags stay until the project's inputs change, so a project that
+    /// flips back and forth is reported even on scans that happen to agree.
+    pub fn unstable_fields(&self, project: &Project) -> Vec<UnstableField> {
+        let Some(entry) = self.projects.get(&project.id) else {
+            return Vec::new();
+        };
+        let Some(last) = entry.last_scan.as_ref().filter(|last| Some(&last.inputs) == project.inputs.as_ref()) else {
+            return Vec::new();
+        };
+        
+        let mut fields = entry.unstable.clone();
+        let mut flag = |field: &str, previous: String, current: String| {
+            fields.retain(|f| f.field != field);
+            fields.push(UnstableField { field: field.to_string(), previous, current });
+        };
+        if last.stage != project.stage {
+            flag("stage", last.stage.as_str().to_string(), project.stage.as_str().to_string());
+        }
+        if (last.priority - project.priority).abs() >= PRIORITY_JITTER {
+            flag("priority", format!("{:.1}", last.priority), format!("{:.1}", project.priority));
+        }
+        fields
+    }
+    
+    /// Human requirement episodes of a project, oldest first
+    pub fn requirements(&self, project_id: &str) -> &[RequirementEpisode] {
+        self.projects.get(project_id).map(|entry| entry.requirements.as_slice()).unwrap_or_default()
+    }
+    
+    /// Append a sample for each project, capture planning baselines and
+    /// open or resolve human requirement episodes
+    pub fn record(&mut self, projects: &[Project], at: DateTime<Utc>) {
+        for project in projects {
+            let words = |f: &Option<crate::FileInfo>| {
+                f.as_ref().and_then(|f| f.metrics.as_ref()).map(|m| m.words)
+            };
+            let sample = MetricsSample {
+                at,
+                spec_words: words(&project.artifacts.spec),
+                plan_words: words(&project.artifacts.plan),
+                tasks_words: words(&project.artifacts.tasks),
+            };
+            
+            let entry = self.projects.entry(project.id.clone()).or_default();
+            if let Some(inputs) = &project.inputs {
+                entry.last_scan = Some(ScanOutcome {
+                    inputs: inputs.clone(),
+                    stage: project.stage.clone(),
+                    priority: project.priority,
+                });
+                entry.unstable = project.unstable.clone();
+            }
+            // Entries saved before transitions were kept start at their recorded stage
+            if entry.stages.is_empty()
+                && let (Some(stage), Some(since)) = (&entry.stage, entry.stage_since) {
+                entry.stages.push(StageTransition { at: since, stage: stage.clone() });
+            }
+            if entry.stage.as_ref() != Some(&project.stage) {
+                let since = project.stage_since.unwrap_or(at);
+                entry.stage = Some(project.stage.clone());
+                entry.stage_since = Some(since);
+                entry.stages.push(StageTransition { at: since, stage: project.stage.clone() });
+                if entry.stages.len() > MAX_TRANSITIONS {
+                    let excess = entry.stages.len() - MAX_TRANSITIONS;
+                    entry.stages.drain(..excess);
+                }
+            }
+            requirements::reconcile(&mut entry.requirements, &project.requires_human, at);
+            if entry.requirements.len() > MAX_EPISODES {
+                let excess = entry.requirements.len() - MAX_EPISODES;
+                entry.requirements.drain(..excess);
+            }
+            if entry.spec_words_at_plan.is_none() && sample.plan_words.is_some() {
+                entry.spec_words_at_plan = sample.spec_words;
+            }
+            
+            // Only store a new sample when sizes actually changed
+            let changed = entry.samples.last()
+                .map(|last| (last.spec_words, last.plan_words, last.tasks_words)
+                    != (sample.spec_words, sample.plan_words, sample.tasks_words))
+                .unwrap_or(true);
+            if changed {
+                entry.samples.push(sample);
+                if entry.samples.len() > MAX_SAMPLES {
+                    let excess = entry.samples.len() - MAX_SAMPLES;
+                    entry.samples.drain(..excess);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,14 @@
+pub mod config;
+pub mod config_file;
+pub mod state;
+pub mod history;
+pub mod schema;
+pub mod registry;
+pub mod snapshots;
+pub mod bulk;
+pub mod testruns;
+pub mod audits;
+pub mod fingerprints;
+
+pub use config::*;
+pub use state::*;
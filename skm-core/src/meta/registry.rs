@@ -0,0 +1,149 @@
+use std::path::{Path, PathBuf};
+use std::fs;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use crate::Result;
+use super::config::GlobalConfig;
+use super::schema::{self, REGISTRY_SCHEMA_VERSION};
+
+/// Manifest of projects registered outside of a scan, stored in `~/.config/skm/projects.json`
+///
+/// Registered projects are included in every scan whose root contains
+/// them, even beyond `scan_depth`. Ignored projects are never offered for
+/// registration again by the shell hook.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Registry {
+    pub schema_version: u32,
+    #[serde(default)]
+    pub projects: Vec<RegisteredProject>,
+    #[serde(default)]
+    pub ignored: Vec<PathBuf>,
+    /// Projects tracked by URL and analyzed from a shallow clone
+    #[serde(default)]
+    pub remotes: Vec<RemoteProject>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RegisteredProject {
+    pub path: PathBuf,
+    pub registered_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RemoteProject {
+    /// Project id, also the name of its clone in the remote cache
+    pub name: String,
+    pub url: String,
+    pub added_at: DateTime<Utc>,
+    #[serde(default)]
+    pub synced_at: Option<DateTime<Utc>>,
+}
+
+impl Default for Registry {
+    fn default() -> Self {
+        Self {
+            schema_version: REGISTRY_SCHEMA_VERSION,
+            projects: Vec::new(),
+            ignored: Vec::new(),
+            remotes: Vec::new(),
+        }
+    }
+}
+
+impl Registry {
+    pub fn load() -> Result<Self> {
+        let path = Self::path()?;
+        
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        
+        let content = fs::read_to_string(&path)?;
+        let mut doc: serde_json::Value = serde_json::from_str(&content)?;
+        schema::ensure_supported(&mut doc, "projects.json", REGISTRY_SCHEMA_VERSION)?;
+        Ok(serde_json::from_value(doc)?)
+    }
+    
+    pub fn save(&self) -> Result<()> {
+        let path = Self::path()?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        
+        fs::write(&path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+    
+    fn path() -> Result<PathBuf> {
+        Ok(GlobalConfig::config_dir()?.join("projects.json"))
+    }
+    
+    pub fn is_registered(&self, path: &Path) -> bool {
+        self.projects.iter().any(|p| p.path == path)
+    }
+    
+    pub fn is_ignored(&self, path: &Path) -> bool {
+        self.ignored.iter().any(|p| p == path)
+    }
+    
+    /// Register a project; returns false if it was already registered
+    pub fn register(&mut self, path: &Path) -> bool {
+        self.ignored.retain(|p| p != path);
+        if self.is_registered(path) {
+            return false;
+        }
+        
+        self.projects.push(RegisteredProject {
+            path: path.to_path_buf(),
+            registered_at: Utc::now(),
+        });
+        true
+    }
+    
+    /// Stop offering a project for registration
+    pub fn ignore(&mut self, path: &Path) {
+        self.projects.retain(|p| p.path != path);
+        if !self.is_ignored(path) {
+            self.ignored.push(path.to_path_buf());
+        }
+    }
+    
+    pub fn remote(&self, name: &str) -> Option<&RemoteProject> {
+        self.remotes.iter().find(|r| r.name == name)
+    }
+    
+    /// Track a remote project; returns false if the name is already taken
+    pub fn add_remote(&mut self, name: &str, url: &str) -> bool {
+        if self.remote(name).is_some() {
+            return false;
+        }
+        
+        self.remotes.push(RemoteProject {
+            name: name.to_string(),
+            url: url.to_string(),
+            added_at: Utc::now(),
+            synced_at: None,
+        });
+        true
+    }
+    
+    /// Stop tracking a remote project; returns false if it was not tracked
+    pub fn remove_remote(&mut self, name: &str) -> bool {
+        let before = self.remotes.len();
+        self.remotes.retain(|r| r.name != name);
+        self.remotes.len() != before
+    }
+    
+    pub fn mark_synced(&mut self, name: &str) {
+        if let Some(remote) = self.remotes.iter_mut().find(|r| r.name == name) {
+            remote.synced_at = Some(Utc::now());
+        }
+    }
+    
+    /// Registered projects inside `root` that still exist on disk
+    pub fn projects_under<'a>(&'a self, root: &'a Path) -> impl Iterator<Item = &'a Path> + 'a {
+        self.projects.iter()
+            .map(|p| p.path.as_path())
+            .filter(move |path| path.starts_with(root) && path.is_dir())
+    }
+}
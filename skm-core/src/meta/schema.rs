@@ -0,0 +1,166 @@
+use serde_json::Value;
+use crate::{Result, SKMError};
+use super::config::DEFAULT_ATTENTION_THRESHOLD;
+
+/// Current schema version of `.skm/status.json`
+pub const STATUS_SCHEMA_VERSION: u32 = 3;
+
+/// Current schema version of `.skm/meta.json`
+pub const META_SCHEMA_VERSION: u32 = 1;
+
+/// Current schema version of `.skm/metrics.json`
+pub const METRICS_SCHEMA_VERSION: u32 = 1;
+
+/// Current schema version of `.skm/snapshots.json`
+pub const SNAPSHOTS_SCHEMA_VERSION: u32 = 1;
+
+/// Current schema version of `.skm/tests.json`
+pub const TESTS_SCHEMA_VERSION: u32 = 1;
+
+/// Current schema version of `.skm/audits.json`
+pub const AUDITS_SCHEMA_VERSION: u32 = 1;
+
+/// Current schema version of `.skm/fingerprints.json`
+pub const FINGERPRINTS_SCHEMA_VERSION: u32 = 1;
+
+/// Current schema version of `~/.config/skm/projects.json`
+pub const REGISTRY_SCHEMA_VERSION: u32 = 1;
+
+/// Current schema version of `.skm/published.json`
+pub const PUBLISHED_SCHEMA_VERSION: u32 = 1;
+
+/// Outcome of bringing a persisted document up to the current schema
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Migration {
+    pub from: u32,
+    pub to: u32,
+}
+
+impl Migration {
+    pub fn changed(&self) -> bool {
+        self.from != self.to
+    }
+}
+
+/// Read the `schema_version` of a document; files written before versioning report 0
+pub fn schema_version(doc: &Value) -> u32 {
+    doc.get("schema_version")
+        .and_then(Value::as_u64)
+        .unwrap_or(0) as u32
+}
+
+/// Migrate a status cache document in place
+pub fn migrate_status(doc: &mut Value) -> Result<Migration> {
+    migrate(doc, "status.json", STATUS_SCHEMA_VERSION, |version, doc| match version {
+        0 => {
+            // v0 -> v1: stages switched from variant names to kebab-case
+            if let Some(data) = doc.get_mut("data") {
+                normalize_stages(data);
+            }
+        }
+        // v1 -> v2: clarify and analyze stages added; existing data is unchanged
+        1 => {}
+        // v2 -> v3: projects carry `needs_attention`, derived with the default threshold
+        2 => {
+            if let Some(data) = doc.get_mut("data") {
+                derive_needs_attention(data, DEFAULT_ATTENTION_THRESHOLD);
+            }
+        }
+        _ => unreachable!("no migration from status schema {}", version),
+    })
+}
+
+/// Migrate a project metadata document in place
+pub fn migrate_meta(doc: &mut Value) -> Result<Migration> {
+    migrate(doc, "meta.json", META_SCHEMA_VERSION, |version, _doc| match version {
+        // v0 -> v1: only the version field was introduced
+        0 => {}
+        _ => unreachable!("no migration from meta schema {}", version),
+    })
+}
+
+/// Migrate a publish state document in place
+pub fn migrate_published(doc: &mut Value) -> Result<Migration> {
+    migrate(doc, "published.json", PUBLISHED_SCHEMA_VERSION, |version, doc| match version {
+        // v0 -> v1: hashes leave out timestamp lines, so older hashes never match; drop them
+        0 => {
+            if let Some(pages) = doc.get_mut("pages") {
+                *pages = Value::Object(Default::default());
+            }
+        }
+        _ => unreachable!("no migration from published schema {}", version),
+    })
+}
+
+/// Version check for files introduced after versioning, which have no migrations yet
+pub fn ensure_supported(doc: &mut Value, name: &str, current: u32) -> Result<Migration> {
+    let from = schema_version(doc).max(1);
+    if let Some(obj) = doc.as_object_mut() {
+        obj.insert("schema_version".to_string(), Value::from(from));
+    }
+    migrate(doc, name, current, |version, _doc| {
+        unreachable!("no migration from {} schema {}", name, version)
+    })
+}
+
+/// Apply step-wise migrations until the document reaches `current`
+///
+/// Documents from a newer skm are refused rather than silently misread.
+fn migrate(
+    doc: &mut Value,
+    name: &str,
+    current: u32,
+    step: impl Fn(u32, &mut Value),
+) -> Result<Migration> {
+    let from = schema_version(doc);
+    if from > current {
+        return Err(SKMError::CacheError {
+            message: format!("{} has schema version {} but this skm only understands up to {}; upgrade skm",
+                name, from, current),
+        });
+    }
+
+    for version in from..current {
+        step(version, doc);
+    }
+
+    if let Some(obj) = doc.as_object_mut() {
+        obj.insert("schema_version".to_string(), Value::from(current));
+    }
+
+    Ok(Migration { from, to: current })
+}
+
+fn normalize_stages(portfolio: &mut Value) {
+    if let Some(projects) = portfolio.get_mut("projects").and_then(Value::as_array_mut) {
+        for project in projects {
+            if let Some(stage) = project.get_mut("stage") {
+                lowercase_string(stage);
+            }
+        }
+    }
+
+    if let Some(by_stage) = portfolio.pointer_mut("/summary/by_stage").and_then(Value::as_object_mut) {
+        let entries = std::mem::take(by_stage);
+        for (stage, count) in entries {
+            by_stage.insert(stage.to_lowercase(), count);
+        }
+    }
+}
+
+fn derive_needs_attention(portfolio: &mut Value, threshold: f64) {
+    if let Some(projects) = portfolio.get_mut("projects").and_then(Value::as_array_mut) {
+        for project in projects {
+            let priority = project.get("priority").and_then(Value::as_f64).unwrap_or(0.0);
+            if let Some(obj) = project.as_object_mut() {
+                obj.insert("needs_attention".to_string(), Value::from(priority > threshold));
+            }
+        }
+    }
+}
+
+fn lowercase_string(value: &mut Value) {
+    if let Value::String(s) = value {
+        *s = s.to_lowercase();
+    }
+}
@@ -0,0 +1,145 @@
+use std::path::{Path, PathBuf};
+use std::fs;
+use std::collections::{BTreeMap, HashSet};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use crate::{hash, Result, SKMError};
+use crate::{ArtifactKind, Project};
+use super::schema::{self, SNAPSHOTS_SCHEMA_VERSION};
+
+/// Artifacts whose content is snapshotted
+pub const SNAPSHOT_KINDS: [ArtifactKind; 3] = [ArtifactKind::Spec, ArtifactKind::Plan, ArtifactKind::Tasks];
+
+/// Retained artifact versions per project, stored in .skm/snapshots.json
+///
+/// Content is stored once per version under `.skm/snapshots/<oid>`, keyed
+/// by its git blob OID, so diffs work for projects outside git or with
+/// gitignored artifacts.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SnapshotStore {
+    pub schema_version: u32,
+    pub projects: BTreeMap<String, BTreeMap<ArtifactKind, Vec<Snapshot>>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Snapshot {
+    /// Git blob OID of the content
+    pub oid: String,
+    pub taken_at: DateTime<Utc>,
+}
+
+impl Default for SnapshotStore {
+    fn default() -> Self {
+        Self {
+            schema_version: SNAPSHOTS_SCHEMA_VERSION,
+            projects: BTreeMap::new(),
+        }
+    }
+}
+
+impl SnapshotStore {
+    /// Load snapshots from .skm/snapshots.json
+    pub fn load(root: &Path) -> Result<Self> {
+        let path = root.join(".skm/snapshots.json");
+        
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        
+        let content = fs::read_to_string(&path)?;
+        let mut doc: serde_json::Value = serde_json::from_str(&content)?;
+        schema::ensure_supported(&mut doc, "snapshots.json", SNAPSHOTS_SCHEMA_VERSION)?;
+        Ok(serde_json::from_value(doc)?)
+    }
+    
+    /// Save the index and delete content no snapshot refers to anymore
+    pub fn save(&self, root: &Path) -> Result<()> {
+        let skm_dir = root.join(".skm");
+        fs::create_dir_all(&skm_dir)?;
+        
+        let content = serde_json::to_string_pretty(self)?;
+        fs::write(skm_dir.join("snapshots.json"), content)?;
+        
+        let referenced: HashSet<&str> = self.projects.values()
+            .flat_map(|kinds| kinds.values())
+            .flatten()
+            .map(|s| s.oid.as_str())
+            .collect();
+        if let Ok(entries) = fs::read_dir(objects_dir(root)) {
+            for entry in entries.filter_map(|e| e.ok()) {
+                if !referenced.contains(entry.file_name().to_string_lossy().as_ref()) {
+                    let _ = fs::remove_file(entry.path());
+                }
+            }
+        }
+        
+        Ok(())
+    }
+    
+    /// Snapshot each project's spec, plan and tasks, keeping the last `keep` versions
+    ///
+    /// A new snapshot is only taken when the content changed.
+    pub fn record(&mut self, root: &Path, projects: &[Project], at: DateTime<Utc>, keep: usize) -> Result<()> {
+        if keep == 0 {
+            return Ok(());
+        }
+        fs::create_dir_all(objects_dir(root))?;
+        
+        for project in projects {
+            for kind in SNAPSHOT_KINDS {
+                let Some(file) = artifact_file(project, kind) else { continue };
+                let Ok(content) = fs::read(&file.path) else { continue };
+                let oid = hash::blob_id(&content);
+                
+                let history = self.projects.entry(project.id.clone())
+                    .or_default()
+                    .entry(kind)
+                    .or_default();
+                if history.last().is_some_and(|last| last.oid == oid) {
+                    continue;
+                }
+                
+                let object = objects_dir(root).join(&oid);
+                if !object.exists() {
+                    fs::write(&object, &content)?;
+                }
+                history.push(Snapshot { oid, taken_at: at });
+                if history.len() > keep {
+                    let excess = history.len() - keep;
+                    history.drain(..excess);
+                }
+            }
+        }
+        
+        Ok(())
+    }
+    
+    /// Snapshots of one artifact, oldest first
+    pub fn history(&self, project_id: &str, kind: ArtifactKind) -> &[Snapshot] {
+        self.projects.get(project_id)
+            .and_then(|kinds| kinds.get(&kind))
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+    
+    /// Content of a snapshot
+    pub fn read(&self, root: &Path, snapshot: &Snapshot) -> Result<String> {
+        let path = objects_dir(root).join(&snapshot.oid);
+        fs::read_to_string(&path)
+            .map_err(|e| SKMError::CacheError { message: format!("Snapshot {} is missing: {}", snapshot.oid, e) })
+    }
+}
+
+/// The file backing an artifact of a project, if present
+pub fn artifact_file(project: &Project, kind: ArtifactKind) -> Option<&crate::FileInfo> {
+    match kind {
+        ArtifactKind::Constitution => project.artifacts.constitution.as_ref(),
+        ArtifactKind::Spec => project.artifacts.spec.as_ref(),
+        ArtifactKind::Plan => project.artifacts.plan.as_ref(),
+        ArtifactKind::Tasks => project.artifacts.tasks.as_ref(),
+    }
+}
+
+fn objects_dir(root: &Path) -> PathBuf {
+    root.join(".skm/snapshots")
+}
@@ -0,0 +1,603 @@
+use std::path::{Path, PathBuf};
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::collections::BTreeMap;
+use serde::{Deserialize, Serialize};
+use crate::{Result, SKMError};
+use crate::{AutomationLevel, Stage};
+use crate::analyzer::season::ActiveWindow;
+use super::schema::{self, Migration, META_SCHEMA_VERSION, STATUS_SCHEMA_VERSION};
+
+/// Days of portfolio states kept in .skm/history.jsonl
+const LOG_RETENTION_DAYS: i64 = 180;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProjectMetaStore {
+    #[serde(default)]
+    pub schema_version: u32,
+    pub version: String,
+    pub projects: BTreeMap<String, ProjectMeta>,
+    /// Optional schema for `custom` project fields
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub custom_schema: BTreeMap<String, CustomFieldSpec>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ProjectMeta {
+    #[serde(default)]
+    pub impact: Option<u8>,
+    #[serde(default)]
+    pub approved_by_human: bool,
+    #[serde(default)]
+    pub custom_commands: BTreeMap<String, String>,
+    #[serde(default)]
+    pub agent_command: Option<String>,
+    #[serde(default)]
+    pub automation_level: Option<AutomationLevel>,
+    #[serde(default)]
+    pub auto_approve: Vec<String>,
+    /// Per-stage caps replacing the global `stage_automation` entries
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub stage_automation: BTreeMap<Stage, AutomationLevel>,
+    #[serde(default)]
+    pub git: GitMeta,
+    /// Manual override of the done stage: `true` marks the project done,
+    /// `false` keeps it out of the done stage whatever its artifacts and git say
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub done: Option<bool>,
+    /// Listed first and always needing attention until done
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub pinned: bool,
+    /// Priority used instead of the computed one; 0 parks the project
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority_override: Option<f64>,
+    /// Points added to the computed priority; negative values lower it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority_boost: Option<f64>,
+    /// Months the project is worked on; outside them it is paused
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub active_window: Option<ActiveWindow>,
+    /// How automated commands are run for this project
+    #[serde(default, skip_serializing_if = "ExecProfile::is_default")]
+    pub exec: ExecProfile,
+    /// Team-defined fields, checked against `custom_schema` when one is declared
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub custom: BTreeMap<String, serde_json::Value>,
+}
+
+impl ProjectMeta {
+    /// Current value of a key `set_value` accepts; `None` when it is unset
+    pub fn get_value(&self, key: &str) -> Result<Option<String>> {
+        let value = match key {
+            "impact" => self.impact.map(|impact| impact.to_string()),
+            "approved_by_human" => Some(self.approved_by_human.to_string()),
+            "agent_command" => self.agent_command.clone(),
+            "automation_level" => self.automation_level.as_ref().map(|level| format!("{:?}", level)),
+            "auto_approve" => Some(self.auto_approve.join(",")).filter(|list| !list.is_empty()),
+            "git.enabled" => Some(self.git.enabled.to_string()),
+            "done" => self.done.map(|done| done.to_string()),
+            "pinned" => Some(self.pinned.to_string()),
+            "priority_override" => self.priority_override.map(|priority| priority.to_string()),
+            "priority_boost" => self.priority_boost.map(|boost| boost.to_string()),
+            "active_window" => self.active_window.as_ref().map(ActiveWindow::to_string),
+            "exec.image" => self.exec.image.clone(),
+            "exec.devcontainer" => Some(self.exec.devcontainer.to_string()),
+            "exec.native" => Some(self.exec.native.to_string()),
+            "exec.workdir" => self.exec.workdir.as_ref().map(|dir| dir.display().to_string()),
+            _ if key.starts_with("stage_automation.") => {
+                let stage: Stage = key["stage_automation.".len()..].parse()?;
+                self.stage_automation.get(&stage).map(|level| format!("{:?}", level))
+            }
+            _ if key.starts_with("exec.env.") => self.exec.env.get(&key["exec.env.".len()..]).cloned(),
+            _ if key.starts_with("custom.") => self.custom.get(&key["custom.".len()..]).map(|value| match value {
+                serde_json::Value::String(s) => s.clone(),
+                other => other.to_string(),
+            }),
+            _ if key.starts_with("command.") => self.custom_commands.get(&key["command.".len()..]).cloned(),
+            _ => return Err(SKMError::ConfigError { message: format!("Unknown key: {}", key) }),
+        };
+        Ok(value)
+    }
+    
+    /// Every key that differs from its default, with its value, sorted by key
+    pub fn entries(&self) -> Vec<(String, String)> {
+        let defaults = ProjectMeta::default();
+        let mut keys: Vec<String> = [
+            "impact", "approved_by_human", "agent_command", "automation_level", "auto_approve",
+            "git.enabled", "done", "pinned", "priority_override", "priority_boost", "active_window", "exec.image", "exec.devcontainer", "exec.native", "exec.workdir",
+        ].iter().map(|key| key.to_string()).collect();
+        keys.extend(self.stage_automation.keys().map(|stage| format!("stage_automation.{}", stage)));
+        keys.extend(self.exec.env.keys().map(|name| format!("exec.env.{}", name)));
+        keys.extend(self.custom.keys().map(|field| format!("custom.{}", field)));
+        keys.extend(self.custom_commands.keys().map(|name| format!("command.{}", name)));
+        
+        let mut entries: Vec<(String, String)> = keys.into_iter()
+            .filter_map(|key| {
+                let value = self.get_value(&key).ok()??;
+                let default = defaults.get_value(&key).ok().flatten();
+                (default.as_ref() != Some(&value)).then_some((key, value))
+            })
+            .collect();
+        entries.sort();
+        entries
+    }
+}
+
+/// Environment for commands skm runs in a project (tests, checks, autopilot)
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct ExecProfile {
+    /// Variables that must be set in skm's environment (e.g. secrets) and are passed through
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub secrets: Vec<String>,
+    /// Fixed variables set for every command
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+    /// Directories put in front of PATH, relative to the working directory unless absolute
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub path_prepend: Vec<PathBuf>,
+    /// Subdirectory of the project to run commands in
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub workdir: Option<PathBuf>,
+    /// Run commands in a throwaway container of this docker image
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image: Option<String>,
+    /// Run commands in the project's devcontainer via the `devcontainer` CLI
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub devcontainer: bool,
+    /// Run on the host even when the project ships a Nix or devcontainer environment
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub native: bool,
+}
+
+impl ExecProfile {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Declared shape of a custom field
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CustomFieldSpec {
+    #[serde(rename = "type")]
+    pub kind: CustomFieldType,
+    /// Every project must set the field
+    #[serde(default)]
+    pub required: bool,
+    /// Allowed values; any value of the right type when empty
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub values: Vec<serde_json::Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CustomFieldType {
+    String,
+    Integer,
+    Number,
+    Boolean,
+}
+
+impl CustomFieldType {
+    fn accepts(&self, value: &serde_json::Value) -> bool {
+        match self {
+            CustomFieldType::String => value.is_string(),
+            CustomFieldType::Integer => value.is_i64() || value.is_u64(),
+            CustomFieldType::Number => value.is_number(),
+            CustomFieldType::Boolean => value.is_boolean(),
+        }
+    }
+    
+    fn as_str(&self) -> &'static str {
+        match self {
+            CustomFieldType::String => "string",
+            CustomFieldType::Integer => "integer",
+            CustomFieldType::Number => "number",
+            CustomFieldType::Boolean => "boolean",
+        }
+    }
+}
+
+/// Per-project git settings
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GitMeta {
+    /// Set to false to never query git for this project (e.g. huge monorepos)
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+impl Default for GitMeta {
+    fn default() -> Self {
+        Self { enabled: true }
+    }
+}
+
+/// A value that does not parse as its key's type
+fn invalid_value(key: &str, value: &str, error: impl std::fmt::Display) -> SKMError {
+    SKMError::ConfigError { message: format!("Invalid value for {}: {} ({})", key, value, error) }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for ProjectMetaStore {
+    fn default() -> Self {
+        Self {
+            schema_version: META_SCHEMA_VERSION,
+            version: "1.0.0".to_string(),
+            projects: BTreeMap::new(),
+            custom_schema: BTreeMap::new(),
+        }
+    }
+}
+
+impl ProjectMetaStore {
+    /// Load project metadata from .skm/meta.json
+    pub fn load(root: &Path) -> Result<Self> {
+        let meta_path = root.join(".skm/meta.json");
+        
+        if !meta_path.exists() {
+            return Ok(Self::default());
+        }
+        
+        Ok(Self::load_migrated(root)?.0)
+    }
+    
+    /// Load project metadata, upgrading older schema versions in memory
+    ///
+    /// Returns the migration that was applied so callers can persist it.
+    pub fn load_migrated(root: &Path) -> Result<(Self, Migration)> {
+        let meta_path = root.join(".skm/meta.json");
+        
+        if !meta_path.exists() {
+            let current = Migration { from: META_SCHEMA_VERSION, to: META_SCHEMA_VERSION };
+            return Ok((Self::default(), current));
+        }
+        
+        let content = fs::read_to_string(&meta_path)?;
+        let mut doc: serde_json::Value = serde_json::from_str(&content)?;
+        let migration = schema::migrate_meta(&mut doc)?;
+        let store: ProjectMetaStore = serde_json::from_value(doc)?;
+        Ok((store, migration))
+    }
+    
+    /// Save project metadata to .skm/meta.json
+    pub fn save(&self, root: &Path) -> Result<()> {
+        let skm_dir = root.join(".skm");
+        fs::create_dir_all(&skm_dir)?;
+        
+        let meta_path = skm_dir.join("meta.json");
+        let content = serde_json::to_string_pretty(self)?;
+        fs::write(&meta_path, content)?;
+        
+        Ok(())
+    }
+    
+    /// Get metadata for a specific project
+    pub fn get_project(&self, project_id: &str) -> Option<&ProjectMeta> {
+        self.projects.get(project_id)
+    }
+    
+    /// Problems with a project's custom fields according to `custom_schema`
+    ///
+    /// Fields the schema does not declare are accepted as they are.
+    pub fn custom_field_errors(&self, project_id: &str) -> Vec<String> {
+        let custom = self.get_project(project_id).map(|m| &m.custom);
+        let mut names: Vec<&String> = self.custom_schema.keys().collect();
+        names.sort();
+        
+        let mut errors = Vec::new();
+        for name in names {
+            let spec = &self.custom_schema[name];
+            match custom.and_then(|c| c.get(name)) {
+                None if spec.required => errors.push(format!("custom field `{}` is required", name)),
+                None => {}
+                Some(value) if !spec.kind.accepts(value) => {
+                    errors.push(format!("custom field `{}` must be of type {}, got {}", name, spec.kind.as_str(), value));
+                }
+                Some(value) if !spec.values.is_empty() && !spec.values.contains(value) => {
+                    let allowed: Vec<String> = spec.values.iter().map(|v| v.to_string()).collect();
+                    errors.push(format!("custom field `{}` is {}, expected one of {}", name, value, allowed.join(", ")));
+                }
+                Some(_) => {}
+            }
+        }
+        errors
+    }
+    
+    /// Get mutable metadata for a specific project
+    pub fn get_project_mut(&mut self, project_id: &str) -> &mut ProjectMeta {
+        self.projects.entry(project_id.to_string())
+            .or_default()
+    }
+    
+    /// Set a value for a project
+    ///
+    /// `custom.<field>` values are read as JSON when possible (`3`, `true`),
+    /// and as plain strings otherwise.
+    pub fn set_value(&mut self, project_id: &str, key: &str, value: String) -> Result<()> {
+        let meta = self.get_project_mut(project_id);
+        
+        match key {
+            "impact" => {
+                meta.impact = Some(value.parse::<u8>().map_err(|e| invalid_value(key, &value, e))?);
+            }
+            "approved_by_human" => {
+                meta.approved_by_human = value.parse::<bool>().map_err(|e| invalid_value(key, &value, e))?;
+            }
+            "agent_command" => {
+                meta.agent_command = Some(value);
+            }
+            "automation_level" => {
+                meta.automation_level = Some(serde_json::from_value(serde_json::Value::String(value))?);
+            }
+            "auto_approve" => {
+                meta.auto_approve = value.split(',')
+                    .map(|command| command.trim().to_string())
+                    .filter(|command| !command.is_empty())
+                    .collect();
+            }
+            "git.enabled" => {
+                meta.git.enabled = value.parse::<bool>().map_err(|e| invalid_value(key, &value, e))?;
+            }
+            "done" => {
+                meta.done = match value.as_str() {
+                    "" => None,
+                    _ => Some(value.parse::<bool>().map_err(|e| invalid_value(key, &value, e))?),
+                };
+            }
+            "pinned" => {
+                meta.pinned = value.parse::<bool>().map_err(|e| invalid_value(key, &value, e))?;
+            }
+            "priority_override" | "priority_boost" => {
+                let points = match value.parse::<f64>() {
+                    _ if value.is_empty() => None,
+                    Ok(points) if points.is_finite() => Some(points),
+                    Ok(_) => return Err(invalid_value(key, &value, "not a finite number")),
+                    Err(e) => return Err(invalid_value(key, &value, e)),
+                };
+                if key == "priority_boost" {
+                    meta.priority_boost = points;
+                } else if points.is_some_and(|points| points < 0.0) {
+                    return Err(invalid_value(key, &value, "priorities are not negative"));
+                } else {
+                    meta.priority_override = points;
+                }
+            }
+            "active_window" => {
+                meta.active_window = match value.as_str() {
+                    "" => None,
+                    _ => Some(value.parse()?),
+                };
+            }
+            "exec.image" => {
+                meta.exec.image = Some(value).filter(|v| !v.is_empty());
+            }
+            "exec.devcontainer" => {
+                meta.exec.devcontainer = value.parse::<bool>().map_err(|e| invalid_value(key, &value, e))?;
+            }
+            "exec.native" => {
+                meta.exec.native = value.parse::<bool>().map_err(|e| invalid_value(key, &value, e))?;
+            }
+            "exec.workdir" => {
+                meta.exec.workdir = Some(PathBuf::from(value)).filter(|v| !v.as_os_str().is_empty());
+            }
+            _ if key.starts_with("stage_automation.") => {
+                let stage: Stage = key.strip_prefix("stage_automation.").unwrap().parse()?;
+                if value.is_empty() {
+                    meta.stage_automation.remove(&stage);
+                } else {
+                    meta.stage_automation.insert(stage, serde_json::from_value(serde_json::Value::String(value))?);
+                }
+            }
+            _ if key.starts_with("exec.env.") => {
+                let name = key.strip_prefix("exec.env.").unwrap();
+                meta.exec.env.insert(name.to_string(), value);
+            }
+            _ if key.starts_with("custom.") => {
+                let field = key.strip_prefix("custom.").unwrap();
+                let value = serde_json::from_str(&value).unwrap_or(serde_json::Value::String(value));
+                meta.custom.insert(field.to_string(), value);
+            }
+            _ if key.starts_with("command.") => {
+                let cmd_name = key.strip_prefix("command.").unwrap();
+                meta.custom_commands.insert(cmd_name.to_string(), value);
+            }
+            _ => {
+                return Err(SKMError::ConfigError { message: format!("Unknown key: {}", key) });
+            }
+        }
+        
+        Ok(())
+    }
+}
+
+/// A history log line [`StatusCache::load_log`] could not read
+#[derive(Debug, Clone, PartialEq)]
+pub struct SkippedEntry {
+    /// 1-based line number in `.skm/history.jsonl`
+    pub line: usize,
+    pub error: String,
+}
+
+/// Cache for portfolio status
+#[derive(Serialize, Deserialize, Debug)]
+pub struct StatusCache {
+    #[serde(default)]
+    pub schema_version: u32,
+    pub last_updated: chrono::DateTime<chrono::Utc>,
+    pub data: serde_json::Value,
+    /// Scan fingerprint of every discovered project as of `data`, keyed by path
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub fingerprints: BTreeMap<String, String>,
+}
+
+impl StatusCache {
+    /// Wrap portfolio data in a cache entry stamped with the current time and schema
+    pub fn new(data: serde_json::Value) -> Self {
+        Self {
+            schema_version: STATUS_SCHEMA_VERSION,
+            last_updated: chrono::Utc::now(),
+            data,
+            fingerprints: BTreeMap::new(),
+        }
+    }
+    
+    /// Record the fingerprints the cached projects were scanned with
+    pub fn with_fingerprints(mut self, fingerprints: BTreeMap<String, String>) -> Self {
+        self.fingerprints = fingerprints;
+        self
+    }
+    
+    /// Whether the projects on disk still have the fingerprints the cache was built from
+    ///
+    /// `current` holds every project discovered now, so added and removed
+    /// projects invalidate the cache too. A cache without fingerprints, as
+    /// written before they were recorded, is never current.
+    pub fn is_current(&self, current: &BTreeMap<String, String>) -> bool {
+        !self.fingerprints.is_empty() && self.fingerprints == *current
+    }
+    
+    /// Load status cache from .skm/status.json regardless of age, upgrading older schema versions in memory
+    pub fn load_migrated(root: &Path) -> Result<Option<(Self, Migration)>> {
+        let cache_path = root.join(".skm/status.json");
+        
+        if !cache_path.exists() {
+            return Ok(None);
+        }
+        
+        let content = fs::read_to_string(&cache_path)?;
+        let mut doc: serde_json::Value = serde_json::from_str(&content)?;
+        let migration = schema::migrate_status(&mut doc)?;
+        let cache: StatusCache = serde_json::from_value(doc)?;
+        Ok(Some((cache, migration)))
+    }
+    
+    /// Find the nearest `.skm/status.json` in `start` or any of its ancestors
+    ///
+    /// Returns the directory holding `.skm` (the scan root) with its cache,
+    /// regardless of the cache's age.
+    pub fn find_upwards(start: &Path) -> Result<Option<(PathBuf, Self)>> {
+        let start = start.canonicalize()?;
+        for dir in start.ancestors() {
+            if dir.join(".skm/status.json").is_file() {
+                return Ok(Self::load_migrated(dir)?.map(|(cache, _)| (dir.to_path_buf(), cache)));
+            }
+        }
+        Ok(None)
+    }
+    
+    /// Append this entry to the portfolio history log in .skm/history.jsonl
+    pub fn append_to_log(&self, root: &Path) -> Result<()> {
+        let skm_dir = root.join(".skm");
+        fs::create_dir_all(&skm_dir)?;
+        
+        // Fingerprints only matter for the current cache
+        let mut entry = serde_json::to_value(self)?;
+        if let Some(entry) = entry.as_object_mut() {
+            entry.remove("fingerprints");
+        }
+        let mut file = fs::OpenOptions::new().create(true).append(true).open(skm_dir.join("history.jsonl"))?;
+        writeln!(file, "{}", entry)?;
+        Ok(())
+    }
+    
+    /// Drop history log entries older than the retention window as of `now`
+    ///
+    /// Only the first line is read unless it has expired, so the log is
+    /// rewritten occasionally rather than on every scan.
+    pub fn prune_log(root: &Path, now: chrono::DateTime<chrono::Utc>) -> Result<()> {
+        let log_path = root.join(".skm/history.jsonl");
+        let cutoff = now - chrono::Duration::days(LOG_RETENTION_DAYS);
+        let expired = |line: &str| {
+            serde_json::from_str::<LogStamp>(line)
+                .map(|stamp| stamp.last_updated < cutoff)
+                .unwrap_or(true)
+        };
+        
+        let Ok(file) = fs::File::open(&log_path) else {
+            return Ok(());
+        };
+        let mut first = String::new();
+        BufReader::new(file).read_line(&mut first)?;
+        if first.is_empty() || !expired(first.trim_end()) {
+            return Ok(());
+        }
+        let content = fs::read_to_string(&log_path)?;
+        let kept: String = content.lines().filter(|line| !expired(line)).map(|line| format!("{}\n", line)).collect();
+        fs::write(&log_path, kept)?;
+        Ok(())
+    }
+    
+    /// The latest logged portfolio state taken at or before `at`
+    ///
+    /// Lines that cannot be parsed (e.g. cut short by an interrupted write)
+    /// are skipped.
+    pub fn load_as_of(root: &Path, at: chrono::DateTime<chrono::Utc>) -> Result<Option<Self>> {
+        let log_path = root.join(".skm/history.jsonl");
+        let Ok(content) = fs::read_to_string(&log_path) else {
+            return Ok(None);
+        };
+        
+        let nearest = content.lines()
+            .filter_map(|line| serde_json::from_str::<LogStamp>(line).ok().map(|stamp| (stamp, line)))
+            .filter(|(stamp, _)| stamp.last_updated <= at)
+            .max_by_key(|(stamp, _)| stamp.last_updated);
+        let Some((_, line)) = nearest else {
+            return Ok(None);
+        };
+        
+        let mut doc: serde_json::Value = serde_json::from_str(line)?;
+        schema::migrate_status(&mut doc)?;
+        Ok(Some(serde_json::from_value(doc)?))
+    }
+    
+    /// Every logged portfolio state, oldest first, upgraded to the current schema
+    ///
+    /// Entries that cannot be read are left out and returned separately.
+    pub fn load_log(root: &Path) -> Result<(Vec<Self>, Vec<SkippedEntry>)> {
+        let log_path = root.join(".skm/history.jsonl");
+        let Ok(content) = fs::read_to_string(&log_path) else {
+            return Ok((Vec::new(), Vec::new()));
+        };
+        
+        let mut entries = Vec::new();
+        let mut skipped = Vec::new();
+        for (index, line) in content.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            match Self::parse_log_line(line) {
+                Ok(entry) => entries.push(entry),
+                Err(e) => skipped.push(SkippedEntry { line: index + 1, error: e.to_string() }),
+            }
+        }
+        entries.sort_by_key(|entry| entry.last_updated);
+        Ok((entries, skipped))
+    }
+    
+    fn parse_log_line(line: &str) -> Result<Self> {
+        let mut doc: serde_json::Value = serde_json::from_str(line)?;
+        schema::migrate_status(&mut doc)?;
+        Ok(serde_json::from_value(doc)?)
+    }
+    
+    /// Save status cache to .skm/status.json
+    pub fn save(&self, root: &Path) -> Result<()> {
+        let skm_dir = root.join(".skm");
+        fs::create_dir_all(&skm_dir)?;
+        
+        let cache_path = skm_dir.join("status.json");
+        let content = serde_json::to_string_pretty(&self)?;
+        fs::write(&cache_path, content)?;
+        
+        Ok(())
+    }
+}
+
+/// Timestamp of a history log entry, read without parsing the portfolio
+#[derive(Deserialize)]
+struct LogStamp {
+    last_updated: chrono::DateTime<chrono::Utc>,
+}
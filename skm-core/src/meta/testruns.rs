@@ -0,0 +1,54 @@
+use std::path::Path;
+use std::fs;
+use std::collections::BTreeMap;
+use serde::{Deserialize, Serialize};
+use crate::Result;
+use crate::TestRun;
+use super::schema::{self, TESTS_SCHEMA_VERSION};
+
+/// Last test and check run per project, stored in .skm/tests.json
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TestRunStore {
+    pub schema_version: u32,
+    pub projects: BTreeMap<String, TestRun>,
+    /// Build and typecheck runs from `skm check`
+    #[serde(default)]
+    pub checks: BTreeMap<String, TestRun>,
+}
+
+impl Default for TestRunStore {
+    fn default() -> Self {
+        Self {
+            schema_version: TESTS_SCHEMA_VERSION,
+            projects: BTreeMap::new(),
+            checks: BTreeMap::new(),
+        }
+    }
+}
+
+impl TestRunStore {
+    /// Load test runs from .skm/tests.json
+    pub fn load(root: &Path) -> Result<Self> {
+        let path = root.join(".skm/tests.json");
+        
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        
+        let content = fs::read_to_string(&path)?;
+        let mut doc: serde_json::Value = serde_json::from_str(&content)?;
+        schema::ensure_supported(&mut doc, "tests.json", TESTS_SCHEMA_VERSION)?;
+        Ok(serde_json::from_value(doc)?)
+    }
+    
+    /// Save test runs to .skm/tests.json
+    pub fn save(&self, root: &Path) -> Result<()> {
+        let skm_dir = root.join(".skm");
+        fs::create_dir_all(&skm_dir)?;
+        
+        let content = serde_json::to_string_pretty(self)?;
+        fs::write(skm_dir.join("tests.json"), content)?;
+        
+        Ok(())
+    }
+}
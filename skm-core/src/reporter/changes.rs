@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+use std::fmt;
+use crate::{HumanRequirement, PortfolioStatus, Project, Stage};
+use crate::analyzer::priority::PriorityTier;
+use super::style::{self, decorate};
+
+/// Something that changed for a project between two scans of a portfolio
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProjectChange {
+    Added { project: String, stage: Stage },
+    Removed { project: String },
+    StageMoved { project: String, from: Stage, to: Stage },
+    /// Tasks were completed, reopened, added or removed
+    TasksChanged { project: String, completed: u32, total: u32, was_completed: u32, was_total: u32 },
+    /// More tasks are marked `[BLOCKED]` than before
+    NewlyBlocked { project: String, blocked: u32, added: u32 },
+    /// Priority crossed the attention threshold, in either direction
+    Attention { project: String, priority: f64, needs_attention: bool },
+    /// Priority rose into the high tier
+    Critical { project: String, priority: f64 },
+    /// The project has waited in Review longer than `[sla] review_days`
+    SlaBreached { project: String, waited_days: u32, limit_days: u32 },
+    /// A human requirement raised by the previous scan is no longer raised
+    RequirementAddressed { project: String, requirement: HumanRequirement },
+}
+
+impl ProjectChange {
+    /// Display name of the project the change is about
+    pub fn project(&self) -> &str {
+        match self {
+            ProjectChange::Added { project, .. }
+            | ProjectChange::Removed { project }
+            | ProjectChange::StageMoved { project, .. }
+            | ProjectChange::TasksChanged { project, .. }
+            | ProjectChange::NewlyBlocked { project, .. }
+            | ProjectChange::Attention { project, .. }
+            | ProjectChange::Critical { project, .. }
+            | ProjectChange::SlaBreached { project, .. }
+            | ProjectChange::RequirementAddressed { project, .. } => project,
+        }
+    }
+}
+
+impl fmt::Display for ProjectChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            ProjectChange::Added { project, stage } => decorate("+", &format!("{} appeared ({})", project, stage)),
+            ProjectChange::Removed { project } => decorate("-", &format!("{} disappeared", project)),
+            ProjectChange::StageMoved { project, from, to } if style::accessible() => {
+                format!("{} moved from {} to {}", project, from, to)
+            }
+            ProjectChange::StageMoved { project, from, to } => format!("→ {}: {} → {}", project, from, to),
+            ProjectChange::TasksChanged { project, completed, total, was_completed, was_total } => {
+                let mut parts = Vec::new();
+                match completed.cmp(was_completed) {
+                    std::cmp::Ordering::Greater => parts.push(format!("{} done", completed - was_completed)),
+                    std::cmp::Ordering::Less => parts.push(format!("{} reopened", was_completed - completed)),
+                    std::cmp::Ordering::Equal => {}
+                }
+                match total.cmp(was_total) {
+                    std::cmp::Ordering::Greater => parts.push(format!("{} added", total - was_total)),
+                    std::cmp::Ordering::Less => parts.push(format!("{} removed", was_total - total)),
+                    std::cmp::Ordering::Equal => {}
+                }
+                decorate("☑", &format!("{}: {}/{} tasks ({})", project, completed, total, parts.join(", ")))
+            }
+            ProjectChange::NewlyBlocked { project, blocked, added } => {
+                decorate("⛔", &format!("{}: {} new blocked task(s), {} blocked", project, added, blocked))
+            }
+            ProjectChange::Attention { project, priority, needs_attention: true } => {
+                decorate("🚨", &format!("{}: priority {:.1} now needs attention", project, priority))
+            }
+            ProjectChange::Attention { project, priority, needs_attention: false } => {
+                decorate("✅", &format!("{}: priority {:.1} no longer needs attention", project, priority))
+            }
+            ProjectChange::SlaBreached { project, waited_days, limit_days } => {
+                decorate("⏰", &format!("{}: {} business day(s) in Review, over the {}-day SLA", project, waited_days, limit_days))
+            }
+            ProjectChange::RequirementAddressed { project, requirement } => {
+                decorate("✔", &format!("{}: {:?} addressed", project, requirement))
+            }
+            ProjectChange::Critical { project, priority } => {
+                decorate("🔥", &format!("{}: priority {:.1} reached the high tier", project, priority))
+            }
+        };
+        f.write_str(&text)
+    }
+}
+
+/// Changes from `old` to `new`, in the order of the new portfolio's projects
+///
+/// Projects are matched by display name, so the same id under two roots
+/// stays distinct. Removed projects come last.
+pub fn portfolio_changes(old: &PortfolioStatus, new: &PortfolioStatus) -> Vec<ProjectChange> {
+    let previous: HashMap<String, &Project> = old.projects.iter()
+        .map(|p| (p.display_name(), p))
+        .collect();
+    let mut changes = Vec::new();
+
+    for project in &new.projects {
+        let name = project.display_name();
+        let Some(before) = previous.get(&name) else {
+            changes.push(ProjectChange::Added { project: name, stage: project.stage.clone() });
+            continue;
+        };
+        if before.stage != project.stage {
+            changes.push(ProjectChange::StageMoved {
+                project: name.clone(),
+                from: before.stage.clone(),
+                to: project.stage.clone(),
+            });
+        }
+        if (project.tasks.completed, project.tasks.total) != (before.tasks.completed, before.tasks.total) {
+            changes.push(ProjectChange::TasksChanged {
+                project: name.clone(),
+                completed: project.tasks.completed,
+                total: project.tasks.total,
+                was_completed: before.tasks.completed,
+                was_total: before.tasks.total,
+            });
+        }
+        if project.tasks.blocked > before.tasks.blocked {
+            changes.push(ProjectChange::NewlyBlocked {
+                project: name.clone(),
+                blocked: project.tasks.blocked,
+                added: project.tasks.blocked - before.tasks.blocked,
+            });
+        }
+        if PriorityTier::from_priority(project.priority) == PriorityTier::High
+            && PriorityTier::from_priority(before.priority) != PriorityTier::High
+        {
+            changes.push(ProjectChange::Critical { project: name.clone(), priority: project.priority });
+        }
+        let breached = |p: &Project| p.review_sla.as_ref().is_some_and(|sla| sla.breached);
+        if let Some(sla) = project.review_sla.as_ref().filter(|_| breached(project) && !breached(before)) {
+            changes.push(ProjectChange::SlaBreached {
+                project: name.clone(),
+                waited_days: sla.waited_days,
+                limit_days: sla.limit_days,
+            });
+        }
+        for requirement in before.requires_human.iter().filter(|r| !project.requires_human.contains(r)) {
+            changes.push(ProjectChange::RequirementAddressed { project: name.clone(), requirement: requirement.clone() });
+        }
+        if before.needs_attention != project.needs_attention {
+            changes.push(ProjectChange::Attention {
+                project: name,
+                priority: project.priority,
+                needs_attention: project.needs_attention,
+            });
+        }
+    }
+
+    let current: Vec<String> = new.projects.iter().map(|p| p.display_name()).collect();
+    for project in &old.projects {
+        let name = project.display_name();
+        if !current.contains(&name) {
+            changes.push(ProjectChange::Removed { project: name });
+        }
+    }
+    changes
+}
@@ -0,0 +1,4 @@
+pub mod changes;
+pub mod style;
+
+pub use changes::{ProjectChange, portfolio_changes};
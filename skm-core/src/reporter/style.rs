@@ -0,0 +1,87 @@
+use std::io::{self, Write};
+use std::sync::OnceLock;
+
+static ACCESSIBLE: OnceLock<bool> = OnceLock::new();
+
+/// Switch human-facing output to accessible mode; only the first call has an effect
+pub fn set_accessible(on: bool) {
+    let _ = ACCESSIBLE.set(on);
+}
+
+/// Whether output avoids emoji, symbol-only signals and tables
+pub fn accessible() -> bool {
+    ACCESSIBLE.get().copied().unwrap_or(false)
+}
+
+/// A symbol that carries meaning on its own: the emoji, or its label in accessible mode
+pub fn icon(emoji: &'static str, label: &'static str) -> &'static str {
+    if accessible() { label } else { emoji }
+}
+
+/// Text led by an emoji that only repeats what the text says; accessible mode drops it
+pub fn decorate(emoji: &str, text: &str) -> String {
+    if accessible() {
+        text.to_string()
+    } else {
+        format!("{} {}", emoji, text)
+    }
+}
+
+/// Terminal colors for lines whose color repeats what they say
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    Red,
+    Yellow,
+    Green,
+}
+
+impl Color {
+    fn code(&self) -> &'static str {
+        match self {
+            Color::Red => "31",
+            Color::Yellow => "33",
+            Color::Green => "32",
+        }
+    }
+}
+
+/// Whether stdout gets colors: a terminal, outside accessible mode, without `NO_COLOR`
+pub fn colored() -> bool {
+    use std::io::IsTerminal;
+    !accessible() && std::env::var_os("NO_COLOR").is_none() && io::stdout().is_terminal()
+}
+
+/// Text in a color, or unchanged when stdout gets no colors
+pub fn paint(text: &str, color: Color) -> String {
+    if colored() {
+        paint_always(text, color)
+    } else {
+        text.to_string()
+    }
+}
+
+/// Text in a color whatever stdout is, for output that decided on colors itself
+pub fn paint_always(text: &str, color: Color) -> String {
+    format!("\x1b[{}m{}\x1b[0m", color.code(), text)
+}
+
+/// Rows as a markdown table, or in accessible mode as one bullet per row
+/// with each value labelled by its column, which screen readers read in order
+pub fn write_table<W: Write>(out: &mut W, headers: &[&str], rows: &[Vec<String>]) -> io::Result<()> {
+    if accessible() {
+        for row in rows {
+            let cells: Vec<String> = headers.iter().zip(row)
+                .map(|(header, value)| format!("{}: {}", header, value))
+                .collect();
+            writeln!(out, "- {}", cells.join("; "))?;
+        }
+        return Ok(());
+    }
+    writeln!(out, "| {} |", headers.join(" | "))?;
+    let rule: Vec<String> = headers.iter().map(|h| "-".repeat(h.chars().count() + 2)).collect();
+    writeln!(out, "|{}|", rule.join("|"))?;
+    for row in rows {
+        writeln!(out, "| {} |", row.join(" | "))?;
+    }
+    Ok(())
+}
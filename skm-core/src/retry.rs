@@ -0,0 +1,165 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use serde::{Deserialize, Serialize};
+use crate::{SKMError, is_debug};
+
+/// Backoff policy for operations that can fail transiently (git, Qdrant, forge APIs)
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct RetryPolicy {
+    /// Total attempts including the first one; 1 disables retrying
+    pub max_attempts: u32,
+    pub base_delay_ms: u64,
+    pub max_delay_ms: u64,
+    /// Fraction (0-1) of each delay that is randomized
+    pub jitter: f64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay_ms: 200,
+            max_delay_ms: 5_000,
+            jitter: 0.5,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Policy that runs the operation exactly once
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    /// Delay before the given retry (1-based), with exponential growth and jitter
+    pub fn delay_for(&self, retry: u32) -> Duration {
+        let exp = self.base_delay_ms.saturating_mul(1u64 << retry.saturating_sub(1).min(16));
+        let capped = exp.min(self.max_delay_ms) as f64;
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        let factor = 1.0 - jitter + jitter * pseudo_random();
+        Duration::from_millis((capped * factor) as u64)
+    }
+}
+
+/// Whether an error is worth retrying
+///
+/// Transient errors are timeouts, refused/reset connections and lock
+/// contention; everything else (missing repos, bad credentials, parse
+/// failures) is permanent and reported immediately.
+pub trait Transience {
+    fn is_transient(&self) -> bool;
+}
+
+impl Transience for std::io::Error {
+    fn is_transient(&self) -> bool {
+        use std::io::ErrorKind;
+        matches!(self.kind(),
+            ErrorKind::TimedOut |
+            ErrorKind::Interrupted |
+            ErrorKind::WouldBlock |
+            ErrorKind::ConnectionRefused |
+            ErrorKind::ConnectionReset |
+            ErrorKind::ConnectionAborted |
+            ErrorKind::BrokenPipe |
+            ErrorKind::UnexpectedEof
+        )
+    }
+}
+
+#[cfg(feature = "git")]
+impl Transience for git2::Error {
+    fn is_transient(&self) -> bool {
+        matches!(self.code(), git2::ErrorCode::Locked) ||
+            matches!(self.class(), git2::ErrorClass::Net | git2::ErrorClass::Ssl | git2::ErrorClass::Http)
+    }
+}
+
+impl Transience for SKMError {
+    fn is_transient(&self) -> bool {
+        match self {
+            SKMError::FsError { source } => source.is_transient(),
+            #[cfg(feature = "git")]
+            SKMError::GitError { source } => source.is_transient(),
+            SKMError::QdrantError { message } => is_transient_message(message),
+            _ => false,
+        }
+    }
+}
+
+/// Classify free-form error text from external tools (curl, HTTP status lines)
+pub fn is_transient_message(message: &str) -> bool {
+    let message = message.to_lowercase();
+    let markers = [
+        "timed out", "timeout", "connection refused", "connection reset",
+        "temporarily unavailable", "try again", "could not resolve host",
+        " 429", " 502", " 503", " 504",
+    ];
+    markers.iter().any(|m| message.contains(m))
+}
+
+/// Run a blocking operation, retrying transient failures according to the policy
+pub fn retry<T, E, F>(policy: &RetryPolicy, what: &str, mut op: F) -> Result<T, E>
+where
+    E: Transience + std::fmt::Display,
+    F: FnMut() -> Result<T, E>,
+{
+    let mut attempt = 1;
+    loop {
+        match op() {
+            Ok(value) => return Ok(value),
+            Err(e) if attempt < policy.max_attempts && e.is_transient() => {
+                let delay = policy.delay_for(attempt);
+                if is_debug() {
+                    eprintln!("[DEBUG] {} failed (attempt {}/{}), retrying in {}ms: {}",
+                        what, attempt, policy.max_attempts, delay.as_millis(), e);
+                }
+                std::thread::sleep(delay);
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Async variant of [`retry`] that sleeps on the tokio timer
+#[cfg(feature = "async")]
+pub async fn retry_async<T, E, F, Fut>(policy: &RetryPolicy, what: &str, mut op: F) -> Result<T, E>
+where
+    E: Transience + std::fmt::Display,
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Result<T, E>>,
+{
+    let mut attempt = 1;
+    loop {
+        match op().await {
+            Ok(value) => return Ok(value),
+            Err(e) if attempt < policy.max_attempts && e.is_transient() => {
+                let delay = policy.delay_for(attempt);
+                if is_debug() {
+                    eprintln!("[DEBUG] {} failed (attempt {}/{}), retrying in {}ms: {}",
+                        what, attempt, policy.max_attempts, delay.as_millis(), e);
+                }
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Cheap jitter source in [0, 1); not suitable for anything security related
+fn pseudo_random() -> f64 {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or(0);
+    // xorshift to spread the low-entropy nanosecond counter
+    let mut x = nanos as u64 ^ 0x9E37_79B9_7F4A_7C15;
+    x ^= x << 13;
+    x ^= x >> 7;
+    x ^= x << 17;
+    (x % 10_000) as f64 / 10_000.0
+}
@@ -1,11 +1,14 @@
+use std::collections::BTreeSet;
 use std::path::{Path, PathBuf};
 use walkdir::{WalkDir, DirEntry};
-use crate::ProjectType;
+use crate::{Capability, ProjectType};
+use super::ignore::DiscoveryFilter;
 
 pub struct ProjectScanner {
     root: PathBuf,
     max_depth: usize,
     glob_pattern: String,
+    respect_gitignore: bool,
 }
 
 impl ProjectScanner {
@@ -14,9 +17,16 @@ impl ProjectScanner {
             root,
             max_depth: max_depth as usize,
             glob_pattern: "*/{.specify,specs}".to_string(),
+            respect_gitignore: false,
         }
     }
     
+    /// Also skip directories ignored by the git repository they are in
+    pub fn with_gitignore(mut self, respect: bool) -> Self {
+        self.respect_gitignore = respect;
+        self
+    }
+    
     pub fn with_max_depth(mut self, depth: usize) -> Self {
         self.max_depth = depth;
         self
@@ -28,13 +38,21 @@ impl ProjectScanner {
     }
     
     /// Find projects with .specify or specs directories
+    ///
+    /// Build and dependency directories, those listed in `.skmignore` files
+    /// and, with [`ProjectScanner::with_gitignore`], gitignored ones are not
+    /// descended into.
     pub fn find_projects(&self) -> Vec<PathBuf> {
         let mut projects = Vec::new();
         let mut seen_projects = std::collections::HashSet::new();
+        let filter = DiscoveryFilter::new(&self.root, self.respect_gitignore);
         
+        // Sorted, so projects are found in the same order on every run
         for entry in WalkDir::new(&self.root)
             .max_depth(self.max_depth)
+            .sort_by_file_name()
             .into_iter()
+            .filter_entry(|e| !e.file_type().is_dir() || filter.keep(e.path()))
             .filter_map(|e| e.ok())
         {
             if self.is_specify_dir(&entry)
@@ -76,6 +94,20 @@ impl ProjectScanner {
     }
 }
 
+/// Find the project containing `start` by walking up to a directory with `.specify` or `specs`
+///
+/// Directories that are themselves `.specify`/`specs` (or inside `.specify`)
+/// are skipped, so running from `specs/001-feature` finds the project root.
+/// `start` is canonicalized first and compared by path component.
+pub fn find_enclosing_project(start: &Path) -> Option<PathBuf> {
+    let start = start.canonicalize().unwrap_or_else(|_| start.to_path_buf());
+    let specify = start.ancestors().find(|dir| dir.file_name() == Some(".specify".as_ref()));
+    start.ancestors()
+        .filter(|dir| specify.is_none_or(|specify| !dir.starts_with(specify)) && dir.file_name() != Some("specs".as_ref()))
+        .find(|dir| dir.join(".specify").is_dir() || dir.join("specs").is_dir())
+        .map(Path::to_path_buf)
+}
+
 /// Detect project type based on language-specific files
 pub fn detect_project_type(path: &Path) -> ProjectType {
     // Check for Rust project
@@ -106,6 +138,24 @@ pub fn detect_project_type(path: &Path) -> ProjectType {
     ProjectType::Unknown
 }
 
+/// Detect development environments (devcontainer, Nix, Dockerfile) in a project root
+pub fn detect_capabilities(path: &Path) -> BTreeSet<Capability> {
+    let mut capabilities = BTreeSet::new();
+    if path.join(".devcontainer").is_dir() || path.join(".devcontainer.json").is_file() {
+        capabilities.insert(Capability::Devcontainer);
+    }
+    if path.join("flake.nix").is_file() {
+        capabilities.insert(Capability::NixFlake);
+    }
+    if path.join("shell.nix").is_file() {
+        capabilities.insert(Capability::NixShell);
+    }
+    if path.join("Dockerfile").is_file() {
+        capabilities.insert(Capability::Dockerfile);
+    }
+    capabilities
+}
+
 /// Check if a directory should be ignored (e.g., node_modules, target)
 pub fn should_ignore(path: &Path) -> bool {
     let ignore_dirs = ["node_modules", "target", ".git", "dist", "build", "__pycache__"];
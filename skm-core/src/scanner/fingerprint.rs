@@ -0,0 +1,105 @@
+use std::fs;
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+use walkdir::WalkDir;
+use crate::hash;
+use crate::analyzer::testrun;
+use crate::scanner::pipeline::ScanContext;
+
+/// Modification time in nanoseconds and size of a file, `None` if missing
+fn stamp(path: &Path) -> Option<(u128, u64)> {
+    let metadata = fs::metadata(path).ok()?;
+    let mtime = metadata.modified().ok()?.duration_since(UNIX_EPOCH).ok()?.as_nanos();
+    Some((mtime, metadata.len()))
+}
+
+/// Cheap fingerprint of everything a scan of a project reads
+///
+/// Covers the mtimes of all files under `.specify` and `specs`, the content
+/// of every `tasks.md`, the files in the project root (manifests, lockfiles,
+/// licenses), test result files, the git HEAD, branches, tags, remote-tracking
+/// heads, index and CI notes, and the parts of the scan context the
+/// project's analysis depends on. Source files are not stat'ed, so
+/// uncommitted code edits and new TODO comments outside staged changes are
+/// only picked up by a full scan.
+pub fn fingerprint(project: &Path, ctx: &ScanContext) -> String {
+    let mut artifacts = Vec::new();
+    for dir in [".specify", "specs"] {
+        for entry in WalkDir::new(project.join(dir)).sort_by_file_name().into_iter().filter_map(|e| e.ok()) {
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let relative = entry.path().strip_prefix(project).unwrap_or(entry.path()).to_string_lossy().into_owned();
+            let tasks_hash = (entry.file_name() == "tasks.md")
+                .then(|| hash::file_blob_id(entry.path()).ok())
+                .flatten();
+            artifacts.push((relative, stamp(entry.path()), tasks_hash));
+        }
+    }
+
+    let mut root_files: Vec<_> = fs::read_dir(project).into_iter().flatten()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_ok_and(|t| t.is_file()))
+        .map(|e| (e.file_name().to_string_lossy().into_owned(), stamp(&e.path())))
+        .collect();
+    root_files.sort();
+
+    let test_results: Vec<_> = testrun::result_files(project).iter().map(|path| stamp(path)).collect();
+
+    #[cfg(feature = "git")]
+    let (head, refs, index, ci_notes) = match git2::Repository::open(project) {
+        Ok(repo) => (
+            repo.head().ok().and_then(|h| h.target()).map(|oid| oid.to_string()),
+            git_refs(&repo),
+            stamp(&repo.path().join("index")),
+            repo.refname_to_id(crate::scanner::git::CI_NOTES_REF).ok().map(|oid| oid.to_string()),
+        ),
+        Err(_) => (None, Vec::new(), None, None),
+    };
+    // Without git data there is no HEAD to fingerprint
+    #[cfg(not(feature = "git"))]
+    let (head, index, ci_notes): (Option<String>, Option<(u128, u64)>, Option<String>) = (None, None, None);
+    #[cfg(not(feature = "git"))]
+    let refs: Vec<(String, String)> = Vec::new();
+
+    let id = ctx.project_id(project);
+    let context = serde_json::json!({
+        "config": ctx.config,
+        // Per-project overrides are read at analysis time, not held in the context
+        "project_config": stamp(&project.join(".skm/config.toml")),
+        "meta": ctx.meta_store.get_project(&id),
+        // A paused project wakes up when its window opens, without any change on disk
+        "season": ctx.meta_store.get_project(&id)
+            .and_then(|m| m.active_window.as_ref())
+            .map(|window| window.contains(chrono::Utc::now().date_naive())),
+        "test": ctx.test_runs.projects.get(&id).map(|r| r.at),
+        "check": ctx.test_runs.checks.get(&id).map(|r| r.at),
+        "audit": ctx.audits.projects.get(&id).map(|a| a.at),
+        "freshness": ctx.audits.freshness.get(&id).map(|f| f.at),
+        "constitution": ctx.constitution.as_ref().and_then(|c| stamp(&c.file.path)),
+    });
+
+    let inputs = serde_json::json!({
+        "artifacts": artifacts,
+        "root_files": root_files,
+        "head": head,
+        "refs": refs,
+        "index": index,
+        "ci_notes": ci_notes,
+        "test_results": test_results,
+        "context": context,
+    });
+    hash::blob_id(inputs.to_string().as_bytes())
+}
+
+/// Every reference with its target, so a fetch (ahead/behind) or a new
+/// branch or tag changes the fingerprint
+#[cfg(feature = "git")]
+fn git_refs(repo: &git2::Repository) -> Vec<(String, String)> {
+    let mut refs: Vec<(String, String)> = repo.references().into_iter().flatten()
+        .filter_map(|r| r.ok())
+        .filter_map(|r| Some((r.name()?.to_string(), r.target()?.to_string())))
+        .collect();
+    refs.sort();
+    refs
+}
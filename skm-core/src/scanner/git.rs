@@ -0,0 +1,209 @@
+use std::path::Path;
+#[cfg(feature = "git")]
+use git2::{Repository, StatusOptions};
+#[cfg(feature = "git")]
+use chrono::{DateTime, Utc, TimeZone};
+use crate::{Result, GitStatus};
+#[cfg(feature = "git")]
+use crate::{TestEvidence, TestSource};
+#[cfg(feature = "git")]
+use crate::analyzer::testrun::parse_ci_note;
+#[cfg(feature = "git")]
+use crate::retry::retry;
+use crate::retry::RetryPolicy;
+
+/// Notes ref CI pipelines record their results for a commit under
+pub const CI_NOTES_REF: &str = "refs/notes/ci";
+
+/// Get Git repository status for a project
+pub fn get_git_status(project_path: &Path) -> Result<GitStatus> {
+    get_git_status_with_retry(project_path, &RetryPolicy::default())
+}
+
+/// Get Git repository status, retrying transient failures such as index lock contention
+#[cfg(feature = "git")]
+pub fn get_git_status_with_retry(project_path: &Path, policy: &RetryPolicy) -> Result<GitStatus> {
+    let repo = match Repository::open(project_path) {
+        Ok(repo) => repo,
+        Err(_) => {
+            // Not a git repository
+            return Ok(GitStatus::not_a_repo());
+        }
+    };
+    
+    let is_repo = true;
+    let branch = get_current_branch(&repo)?;
+    let clean = retry(policy, "git status", || is_working_tree_clean(&repo))?;
+    let last_commit = get_last_commit_time(&repo)?;
+    let upstream = get_upstream(&repo).is_some();
+    let (ahead, behind) = retry(policy, "git ahead/behind", || get_ahead_behind(&repo))?;
+    let merged = is_merged_into_default(&repo, branch.as_deref())?;
+    let ci_note = get_ci_note(&repo);
+    
+    Ok(GitStatus {
+        is_repo,
+        branch,
+        clean,
+        last_commit,
+        ahead,
+        behind,
+        upstream,
+        merged,
+        skipped: false,
+        pending: false,
+        github: None,
+        ci_note,
+    })
+}
+
+#[cfg(feature = "git")]
+fn get_current_branch(repo: &Repository) -> Result<Option<String>> {
+    let head = match repo.head() {
+        Ok(head) => head,
+        Err(_) => return Ok(None), // Detached HEAD or no commits
+    };
+    
+    if let Some(name) = head.shorthand() {
+        Ok(Some(name.to_string()))
+    } else {
+        Ok(None)
+    }
+}
+
+#[cfg(feature = "git")]
+fn is_working_tree_clean(repo: &Repository) -> Result<bool> {
+    let mut status_opts = StatusOptions::new();
+    status_opts.include_untracked(true);
+    
+    let statuses = repo.statuses(Some(&mut status_opts))?;
+    Ok(statuses.is_empty())
+}
+
+#[cfg(feature = "git")]
+fn get_last_commit_time(repo: &Repository) -> Result<Option<DateTime<Utc>>> {
+    let head = match repo.head() {
+        Ok(head) => head,
+        Err(_) => return Ok(None),
+    };
+    
+    let oid = match head.target() {
+        Some(oid) => oid,
+        None => return Ok(None),
+    };
+    
+    let commit = repo.find_commit(oid)?;
+    let time = commit.time();
+    let timestamp = time.seconds();
+    
+    Ok(Some(Utc.timestamp_opt(timestamp, 0).unwrap()))
+}
+
+/// Commit the current branch's upstream points at, if it has one
+#[cfg(feature = "git")]
+fn get_upstream(repo: &Repository) -> Option<git2::Oid> {
+    let head = repo.head().ok()?;
+    let branch = repo.find_branch(head.shorthand()?, git2::BranchType::Local).ok()?;
+    branch.upstream().ok()?.get().target()
+}
+
+#[cfg(feature = "git")]
+fn get_ahead_behind(repo: &Repository) -> Result<(u32, u32)> {
+    let Some(local_oid) = repo.head().ok().and_then(|head| head.target()) else {
+        return Ok((0, 0));
+    };
+    let Some(upstream_oid) = get_upstream(repo) else {
+        return Ok((0, 0)); // No upstream configured
+    };
+    
+    let (ahead, behind) = repo.graph_ahead_behind(local_oid, upstream_oid)?;
+    Ok((ahead as u32, behind as u32))
+}
+
+/// Whether HEAD is already part of the default branch while another branch
+/// is checked out
+///
+/// The default branch is the one `origin/HEAD` points at, or else a local
+/// `main` or `master`.
+#[cfg(feature = "git")]
+fn is_merged_into_default(repo: &Repository, current: Option<&str>) -> Result<bool> {
+    let Some(head_oid) = repo.head().ok().and_then(|head| head.target()) else {
+        return Ok(false);
+    };
+    let remote_default = repo.find_reference("refs/remotes/origin/HEAD").ok()
+        .and_then(|r| r.symbolic_target().map(|target| target.to_string()));
+    let candidates = remote_default.into_iter()
+        .chain(["refs/heads/main", "refs/heads/master"].map(String::from));
+    for name in candidates {
+        let Ok(reference) = repo.find_reference(&name) else { continue };
+        let short = name.strip_prefix("refs/remotes/origin/").or_else(|| name.strip_prefix("refs/heads/"));
+        if current.is_some() && current == short {
+            return Ok(false);
+        }
+        let Some(default_oid) = reference.resolve().ok().and_then(|r| r.target()) else { continue };
+        return Ok(default_oid == head_oid || repo.graph_descendant_of(default_oid, head_oid)?);
+    }
+    Ok(false)
+}
+
+/// CI result a pipeline recorded for HEAD with `git notes --ref=ci add`,
+/// dated by the commit that added the note
+#[cfg(feature = "git")]
+fn get_ci_note(repo: &Repository) -> Option<TestEvidence> {
+    let head = repo.head().ok()?.target()?;
+    let note = repo.find_note(Some(CI_NOTES_REF), head).ok()?;
+    let passed = parse_ci_note(note.message()?)?;
+    let notes = repo.find_reference(CI_NOTES_REF).ok()?.peel_to_commit().ok()?;
+    let at = Utc.timestamp_opt(notes.time().seconds(), 0).single()?;
+    Some(TestEvidence { source: TestSource::CiNote, at, passed, path: None })
+}
+
+/// Check if there are any error markers in recent commits
+#[cfg(feature = "git")]
+pub fn has_recent_errors(path: &Path) -> Result<bool> {
+    let repo = match Repository::open(path) {
+        Ok(repo) => repo,
+        Err(_) => return Ok(false), // Not a git repo
+    };
+    
+    Ok(has_recent_errors_in_repo(&repo))
+}
+
+#[cfg(feature = "git")]
+fn has_recent_errors_in_repo(repo: &Repository) -> bool {
+    let error_markers = ["FIXME", "TODO", "XXX", "HACK", "BUG"];
+    
+    // Check the last 5 commits for error markers
+    if let Ok(mut revwalk) = repo.revwalk() {
+        let _ = revwalk.push_head();
+        let _ = revwalk.set_sorting(git2::Sort::TIME);
+        
+        for (i, oid) in revwalk.enumerate() {
+            if i >= 5 {
+                break;
+            }
+
+            if let Ok(oid) = oid
+                && let Ok(commit) = repo.find_commit(oid)
+                && let Some(message) = commit.message() {
+                for marker in &error_markers {
+                    if message.contains(marker) {
+                        return true;
+                    }
+                }
+            }
+        }
+    }
+    
+    false
+}
+
+/// Without the `git` feature every project reads as a scan with `--skip-git`
+#[cfg(not(feature = "git"))]
+pub fn get_git_status_with_retry(_project_path: &Path, _policy: &RetryPolicy) -> Result<GitStatus> {
+    Ok(GitStatus::skipped())
+}
+
+#[cfg(not(feature = "git"))]
+pub fn has_recent_errors(_path: &Path) -> Result<bool> {
+    Ok(false)
+}
@@ -0,0 +1,162 @@
+use std::path::Path;
+use chrono::{Duration, Utc};
+use serde_json::{json, Value};
+use crate::GitHubStatus;
+use crate::meta::config::GitHubConfig;
+use crate::http::http_json_with;
+use crate::retry::{self, RetryPolicy};
+
+/// Open issues and pull requests, the check state of the latest commit of
+/// the first 100 pull requests and of the local HEAD, and the pull requests
+/// asking the viewer for a review, in one request
+const QUERY: &str = "query($owner: String!, $name: String!, $reviews: String!, $head: String!) {
+  repository(owner: $owner, name: $name) {
+    issues(states: OPEN) { totalCount }
+    head: object(expression: $head) { ... on Commit { statusCheckRollup { state } } }
+    pullRequests(states: OPEN, first: 100) {
+      totalCount
+      nodes { commits(last: 1) { nodes { commit { statusCheckRollup { state } } } } }
+    }
+  }
+  search(query: $reviews, type: ISSUE) { issueCount }
+}";
+
+/// `owner/name` of the repository the `origin` remote points to on `host`
+#[cfg(feature = "git")]
+pub fn repo_slug(project_path: &Path, host: &str) -> Option<String> {
+    let repo = git2::Repository::open(project_path).ok()?;
+    let remote = repo.find_remote("origin").ok()?;
+    parse_remote_url(remote.url()?, host)
+}
+
+/// Without the `git` feature the remote cannot be read
+#[cfg(not(feature = "git"))]
+pub fn repo_slug(_project_path: &Path, _host: &str) -> Option<String> {
+    None
+}
+
+/// The commit HEAD points at
+#[cfg(feature = "git")]
+pub fn head_commit(project_path: &Path) -> Option<String> {
+    let repo = git2::Repository::open(project_path).ok()?;
+    repo.head().ok()?.target().map(|oid| oid.to_string())
+}
+
+#[cfg(not(feature = "git"))]
+pub fn head_commit(_project_path: &Path) -> Option<String> {
+    None
+}
+
+/// `owner/name` from an https, ssh or scp-style (`git@host:owner/name.git`) URL on `host`
+pub fn parse_remote_url(url: &str, host: &str) -> Option<String> {
+    let rest = match url.split_once("://") {
+        Some((_, rest)) => rest.split_once('/').filter(|(authority, _)| {
+            // Drop credentials and port: `git@github.com:22`
+            let authority = authority.rsplit('@').next().unwrap_or(authority);
+            authority.split(':').next() == Some(host)
+        })?.1,
+        None => {
+            let (authority, path) = url.split_once(':')?;
+            if authority.rsplit('@').next() != Some(host) {
+                return None;
+            }
+            path
+        }
+    };
+    let mut parts = rest.trim_matches('/').trim_end_matches(".git").splitn(2, '/');
+    let (owner, name) = (parts.next()?, parts.next()?);
+    (!owner.is_empty() && !name.is_empty() && !name.contains('/')).then(|| format!("{}/{}", owner, name))
+}
+
+/// GitHub counts for a project whose `origin` is on the configured host
+///
+/// A cached result for the same repository and HEAD is reused until it is
+/// `max_age_minutes` old. A missing token or a failed request gives a
+/// status with `error` set. Returns `None` for other projects, and for
+/// all of them without the `integrations` feature.
+pub fn github_status(
+    project_path: &Path,
+    config: &GitHubConfig,
+    cached: Option<&GitHubStatus>,
+    policy: &RetryPolicy,
+) -> Option<GitHubStatus> {
+    if !cfg!(feature = "integrations") {
+        return None;
+    }
+    let repo = repo_slug(project_path, &config.host)?;
+    let head = head_commit(project_path);
+    let fresh = |c: &&GitHubStatus| {
+        c.repo == repo && c.head == head && c.error.is_none()
+            && Utc::now() - c.at < Duration::minutes(config.max_age_minutes as i64)
+    };
+    if let Some(cached) = cached.filter(fresh) {
+        return Some(cached.clone());
+    }
+
+    let mut status = GitHubStatus {
+        at: Utc::now(),
+        repo,
+        open_prs: 0,
+        open_issues: 0,
+        failing_checks: 0,
+        review_requests: 0,
+        head,
+        head_checks: None,
+        error: None,
+    };
+    let token = std::env::var(&config.token_env).ok().filter(|token| !token.is_empty());
+    let result = match token {
+        Some(token) => fetch(&status.repo, status.head.as_deref(), &token, &config.api_url, policy),
+        None => Err(format!("set {} to query GitHub", config.token_env)),
+    };
+    match result {
+        Ok(doc) => apply(&mut status, &doc),
+        Err(error) => status.error = Some(error),
+    }
+    Some(status)
+}
+
+fn fetch(repo: &str, head: Option<&str>, token: &str, api_url: &str, policy: &RetryPolicy) -> Result<Value, String> {
+    let (owner, name) = repo.split_once('/').unwrap_or((repo, ""));
+    let body = json!({
+        "query": QUERY,
+        "variables": {
+            "owner": owner,
+            "name": name,
+            "reviews": format!("repo:{} is:pr is:open review-requested:@me", repo),
+            // An empty expression resolves to no object, like an unpushed commit
+            "head": head.unwrap_or_default(),
+        },
+    });
+    let url = format!("{}/graphql", api_url.trim_end_matches('/'));
+    let headers = [format!("Authorization: Bearer {}", token)];
+    let doc = retry::retry(policy, &format!("querying GitHub for {}", repo), || {
+        http_json_with("POST", &url, &headers, Some(&body))
+    }).map_err(|e| e.to_string())?;
+    // GraphQL reports errors such as an unknown repository with status 200
+    if let Some(message) = doc.pointer("/errors/0/message").and_then(Value::as_str) {
+        return Err(message.to_string());
+    }
+    Ok(doc)
+}
+
+fn apply(status: &mut GitHubStatus, doc: &Value) {
+    let count = |pointer: &str| doc.pointer(pointer).and_then(Value::as_u64).unwrap_or(0) as u32;
+    status.open_issues = count("/data/repository/issues/totalCount");
+    status.open_prs = count("/data/repository/pullRequests/totalCount");
+    status.review_requests = count("/data/search/issueCount");
+    status.failing_checks = doc.pointer("/data/repository/pullRequests/nodes")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter(|pr| matches!(
+            pr.pointer("/commits/nodes/0/commit/statusCheckRollup/state").and_then(Value::as_str),
+            Some("FAILURE" | "ERROR")
+        ))
+        .count() as u32;
+    status.head_checks = match doc.pointer("/data/repository/head/statusCheckRollup/state").and_then(Value::as_str) {
+        Some("SUCCESS") => Some(true),
+        Some("FAILURE" | "ERROR") => Some(false),
+        _ => None,
+    };
+}
@@ -0,0 +1,175 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+/// Name of the file listing directories project discovery skips
+pub const SKMIGNORE: &str = ".skmignore";
+
+/// Patterns from one `.skmignore` file
+///
+/// One pattern per line; blank lines and `#` comments are skipped. A
+/// pattern without a `/` matches a directory name at any depth below the
+/// file (`vendor`, `*.cache`). A pattern with a `/` matches a path relative
+/// to the file's directory (`archive/2023`, `/legacy`); `*` and `?` stay
+/// within a path segment and `**` spans segments. A trailing `/` is allowed
+/// and ignored, since only directories are matched.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SkmIgnore {
+    names: Vec<String>,
+    paths: Vec<String>,
+}
+
+impl SkmIgnore {
+    pub fn parse(content: &str) -> Self {
+        let mut names = Vec::new();
+        let mut paths = Vec::new();
+        for line in content.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let pattern = line.trim_end_matches('/');
+            match pattern.strip_prefix('/') {
+                Some(anchored) => paths.push(anchored.to_string()),
+                None if pattern.contains('/') => paths.push(pattern.to_string()),
+                None => names.push(pattern.to_string()),
+            }
+        }
+        SkmIgnore { names, paths }
+    }
+
+    /// `<dir>/.skmignore`, if present and readable
+    pub fn load(dir: &Path) -> Option<Self> {
+        std::fs::read_to_string(dir.join(SKMIGNORE)).ok().map(|content| Self::parse(&content))
+    }
+
+    /// Whether a directory at `relative` (to the file's directory) is ignored
+    pub fn matches(&self, relative: &Path) -> bool {
+        let name = relative.file_name().map(|n| n.to_string_lossy()).unwrap_or_default();
+        let path = relative.to_string_lossy().replace('\\', "/");
+        self.names.iter().any(|pattern| wildcard(pattern, &name))
+            || self.paths.iter().any(|pattern| wildcard(pattern, &path))
+    }
+}
+
+/// Match `*` (within a segment), `**` (across segments) and `?`
+fn wildcard(pattern: &str, text: &str) -> bool {
+    fn go(p: &[u8], t: &[u8]) -> bool {
+        match p {
+            [] => t.is_empty(),
+            [b'*', b'*', rest @ ..] => {
+                let rest = rest.strip_prefix(b"/").unwrap_or(rest);
+                (0..=t.len()).any(|skip| go(rest, &t[skip..]))
+            }
+            [b'*', rest @ ..] => {
+                (0..=t.len()).take_while(|&skip| skip == 0 || t[skip - 1] != b'/').any(|skip| go(rest, &t[skip..]))
+            }
+            [b'?', rest @ ..] => t.first().is_some_and(|&c| c != b'/') && go(rest, &t[1..]),
+            [c, rest @ ..] => t.first() == Some(c) && go(rest, &t[1..]),
+        }
+    }
+    go(pattern.as_bytes(), text.as_bytes())
+}
+
+/// Decides which directories project discovery descends into
+///
+/// Skips build and dependency directories by name, anything matched by a
+/// `.skmignore` in the root or a directory above the entry and, when
+/// enabled, directories a containing git repository ignores. Without the
+/// `git` feature gitignores are not consulted.
+pub struct DiscoveryFilter {
+    root: PathBuf,
+    /// The root resolved like repository work directories are, for matching against them
+    #[cfg(feature = "git")]
+    canonical_root: PathBuf,
+    #[cfg(feature = "git")]
+    gitignore: bool,
+    skmignores: RefCell<HashMap<PathBuf, Option<Rc<SkmIgnore>>>>,
+    /// Repositories met so far, by work directory
+    #[cfg(feature = "git")]
+    repos: RefCell<Vec<(PathBuf, git2::Repository)>>,
+}
+
+impl DiscoveryFilter {
+    #[cfg_attr(not(feature = "git"), allow(unused_variables))]
+    pub fn new(root: &Path, gitignore: bool) -> Self {
+        let filter = DiscoveryFilter {
+            root: root.to_path_buf(),
+            #[cfg(feature = "git")]
+            canonical_root: root.canonicalize().unwrap_or_else(|_| root.to_path_buf()),
+            #[cfg(feature = "git")]
+            gitignore,
+            skmignores: RefCell::new(HashMap::new()),
+            #[cfg(feature = "git")]
+            repos: RefCell::new(Vec::new()),
+        };
+        // The root may itself sit inside a repository
+        #[cfg(feature = "git")]
+        if gitignore
+            && let Ok(repo) = git2::Repository::discover(root)
+            && let Some(workdir) = repo.workdir().and_then(|dir| dir.canonicalize().ok())
+        {
+            filter.repos.borrow_mut().push((workdir, repo));
+        }
+        filter
+    }
+
+    /// Whether to descend into `dir`; the root is always kept
+    pub fn keep(&self, dir: &Path) -> bool {
+        if dir == self.root {
+            return true;
+        }
+        if super::finder::should_ignore(dir) || self.skmignored(dir) || self.gitignored(dir) {
+            return false;
+        }
+        #[cfg(feature = "git")]
+        if self.gitignore && dir.join(".git").exists()
+            && let Ok(repo) = git2::Repository::open(dir)
+        {
+            self.repos.borrow_mut().push((self.canonical(dir), repo));
+        }
+        true
+    }
+
+    fn skmignored(&self, dir: &Path) -> bool {
+        dir.ancestors()
+            .skip(1)
+            .take_while(|ancestor| ancestor.starts_with(&self.root))
+            .any(|ancestor| {
+                let ignore = self.skmignores.borrow_mut()
+                    .entry(ancestor.to_path_buf())
+                    .or_insert_with(|| SkmIgnore::load(ancestor).map(Rc::new))
+                    .clone();
+                ignore.is_some_and(|ignore| dir.strip_prefix(ancestor).is_ok_and(|relative| ignore.matches(relative)))
+            })
+    }
+
+    #[cfg(feature = "git")]
+    fn gitignored(&self, dir: &Path) -> bool {
+        if !self.gitignore {
+            return false;
+        }
+        let dir = self.canonical(dir);
+        let repos = self.repos.borrow();
+        // The innermost repository decides, as git does for nested repositories
+        repos.iter()
+            .filter(|(workdir, _)| dir.starts_with(workdir))
+            .max_by_key(|(workdir, _)| workdir.components().count())
+            .is_some_and(|(workdir, repo)| {
+                dir.strip_prefix(workdir).is_ok_and(|relative| repo.is_path_ignored(relative).unwrap_or(false))
+            })
+    }
+
+    #[cfg(not(feature = "git"))]
+    fn gitignored(&self, _dir: &Path) -> bool {
+        false
+    }
+
+    #[cfg(feature = "git")]
+    fn canonical(&self, dir: &Path) -> PathBuf {
+        match dir.strip_prefix(&self.root) {
+            Ok(relative) => self.canonical_root.join(relative),
+            Err(_) => dir.to_path_buf(),
+        }
+    }
+}
@@ -0,0 +1,258 @@
+use std::path::{Path, PathBuf};
+use std::fs;
+use crate::{LicenseDeclaration, LicenseStatus};
+
+/// File names (case-insensitive, before any extension) that hold a license text
+const LICENSE_FILES: &[&str] = &["license", "licence", "copying", "unlicense"];
+
+/// Detect license files and manifest declarations of a project and its workspace members
+pub fn detect_license(project: &Path) -> LicenseStatus {
+    let mut status = LicenseStatus::default();
+
+    if let Ok(entries) = fs::read_dir(project) {
+        let mut files: Vec<(String, PathBuf)> = entries
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_ok_and(|t| t.is_file()))
+            .map(|e| (e.file_name().to_string_lossy().into_owned(), e.path()))
+            .filter(|(name, _)| {
+                let stem = name.to_lowercase();
+                let stem = stem.split(['.', '-', '_']).next().unwrap_or_default();
+                LICENSE_FILES.contains(&stem)
+            })
+            .collect();
+        files.sort();
+        for (name, path) in files {
+            let license = license_from_file_name(&name)
+                .or_else(|| fs::read_to_string(&path).ok().and_then(|text| identify_license_text(&text)));
+            status.files.push(name.clone());
+            if let Some(license) = license {
+                status.declared.push(LicenseDeclaration { source: name, license });
+            }
+        }
+    }
+
+    let mut manifests = vec![PathBuf::new()];
+    manifests.extend(workspace_members(project));
+    for member in manifests {
+        let dir = project.join(&member);
+        for (manifest, license) in manifest_licenses(&dir) {
+            status.declared.push(LicenseDeclaration {
+                source: member.join(manifest).to_string_lossy().into_owned(),
+                license,
+            });
+        }
+    }
+
+    status.conflicts = conflicts(&status.declared);
+    status
+}
+
+/// License declared in the manifests found directly in a directory
+fn manifest_licenses(dir: &Path) -> Vec<(&'static str, String)> {
+    let mut found = Vec::new();
+
+    if let Some(manifest) = read_toml(&dir.join("Cargo.toml"))
+        && let Some(license) = manifest.get("package").and_then(|p| p.get("license")).and_then(|l| l.as_str()) {
+        found.push(("Cargo.toml", license.to_string()));
+    }
+    if let Ok(content) = fs::read_to_string(dir.join("package.json"))
+        && let Ok(manifest) = serde_json::from_str::<serde_json::Value>(&content) {
+        let license = match manifest.get("license") {
+            Some(serde_json::Value::String(s)) => Some(s.clone()),
+            Some(other) => other.get("type").and_then(|t| t.as_str()).map(str::to_string),
+            None => None,
+        };
+        if let Some(license) = license {
+            found.push(("package.json", license));
+        }
+    }
+    if let Some(manifest) = read_toml(&dir.join("pyproject.toml")) {
+        let declared = manifest.get("project").and_then(|p| p.get("license"))
+            .or_else(|| manifest.get("tool").and_then(|t| t.get("poetry")).and_then(|p| p.get("license")));
+        let license = match declared {
+            Some(toml::Value::String(s)) => Some(s.clone()),
+            // PEP 621 table form: `license = { text = "MIT" }`
+            Some(toml::Value::Table(t)) => t.get("text").and_then(|v| v.as_str()).map(str::to_string),
+            _ => None,
+        };
+        if let Some(license) = license {
+            found.push(("pyproject.toml", license));
+        }
+    }
+
+    found
+}
+
+/// Member directories of a Cargo or npm workspace, relative to the project
+///
+/// Patterns may end in `/*` (every directory below); other globs are ignored.
+fn workspace_members(project: &Path) -> Vec<PathBuf> {
+    let mut patterns: Vec<String> = Vec::new();
+    if let Some(manifest) = read_toml(&project.join("Cargo.toml"))
+        && let Some(members) = manifest.get("workspace").and_then(|w| w.get("members")).and_then(|m| m.as_array()) {
+        patterns.extend(members.iter().filter_map(|m| m.as_str()).map(str::to_string));
+    }
+    if let Ok(content) = fs::read_to_string(project.join("package.json"))
+        && let Ok(manifest) = serde_json::from_str::<serde_json::Value>(&content) {
+        let workspaces = manifest.get("workspaces")
+            .and_then(|w| w.as_array().or_else(|| w.get("packages").and_then(|p| p.as_array())));
+        if let Some(workspaces) = workspaces {
+            patterns.extend(workspaces.iter().filter_map(|m| m.as_str()).map(str::to_string));
+        }
+    }
+
+    let mut members = Vec::new();
+    for pattern in patterns {
+        match pattern.strip_suffix("/*") {
+            Some(parent) => {
+                let Ok(entries) = fs::read_dir(project.join(parent)) else { continue };
+                let mut dirs: Vec<PathBuf> = entries
+                    .filter_map(|e| e.ok())
+                    .filter(|e| e.path().is_dir())
+                    .map(|e| Path::new(parent).join(e.file_name()))
+                    .collect();
+                dirs.sort();
+                members.extend(dirs);
+            }
+            None if !pattern.contains('*') && pattern != "." => members.push(PathBuf::from(pattern)),
+            None => {}
+        }
+    }
+    members.dedup();
+    members
+}
+
+fn read_toml(path: &Path) -> Option<toml::Value> {
+    toml::from_str(&fs::read_to_string(path).ok()?).ok()
+}
+
+/// `LICENSE-MIT`, `LICENSE-APACHE` and similar dual-license file names
+fn license_from_file_name(name: &str) -> Option<String> {
+    let suffix = name.to_uppercase();
+    let suffix = suffix.split_once(['-', '_'])?.1;
+    let suffix = suffix.split('.').next().unwrap_or(suffix);
+    let license = match suffix {
+        "MIT" => "MIT",
+        "APACHE" | "APACHE2" | "APACHE-2.0" => "Apache-2.0",
+        "BSD" => "BSD-3-Clause",
+        "GPL" => "GPL-3.0",
+        "ISC" => "ISC",
+        _ => return None,
+    };
+    Some(license.to_string())
+}
+
+/// SPDX id of a well-known license text
+pub fn identify_license_text(text: &str) -> Option<String> {
+    let text = text.to_lowercase();
+    let has = |s: &str| text.contains(s);
+    let license = if let Some(license) = gnu_license(&text) {
+        license
+    } else if has("mozilla public license") {
+        "MPL-2.0"
+    } else if has("apache license") {
+        "Apache-2.0"
+    } else if has("permission is hereby granted, free of charge") {
+        "MIT"
+    } else if has("permission to use, copy, modify, and/or distribute this software") {
+        "ISC"
+    } else if has("redistribution and use in source and binary forms") {
+        if has("neither the name") { "BSD-3-Clause" } else { "BSD-2-Clause" }
+    } else if has("this is free and unencumbered software") {
+        "Unlicense"
+    } else {
+        return None;
+    };
+    Some(license.to_string())
+}
+
+/// GNU license named by the first GNU title in a lowercased text
+///
+/// The GPL-3.0 text mentions the Affero license in its section 13 and the
+/// LGPL and AGPL texts refer to the GPL, so only the earliest title counts.
+/// Its version is the one that follows it (`Version 3, 29 June 2007` or
+/// `either version 3 of the License` in a source notice).
+fn gnu_license(text: &str) -> Option<&'static str> {
+    const TITLES: &[(&str, &str)] = &[
+        ("gnu affero general public license", "AGPL"),
+        ("gnu lesser general public license", "LGPL"),
+        ("gnu library general public license", "LGPL"),
+        ("gnu general public license", "GPL"),
+    ];
+    let (at, title, family) = TITLES.iter()
+        .filter_map(|(title, family)| text.find(title).map(|at| (at, title, family)))
+        .min_by_key(|(at, _, _)| *at)?;
+    let rest = &text[at + title.len()..];
+    let version = rest.find("version ").map(|i| rest[i + "version ".len()..].trim_start()).unwrap_or_default();
+    let license = match (*family, version.starts_with('3')) {
+        ("AGPL", _) => "AGPL-3.0",
+        ("LGPL", true) => "LGPL-3.0",
+        ("LGPL", false) => "LGPL-2.1",
+        (_, true) => "GPL-3.0",
+        (_, false) => "GPL-2.0",
+    };
+    Some(license)
+}
+
+/// Broad license families used to judge compatibility
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Family {
+    Permissive,
+    /// Apache-2.0: permissive, but incompatible with GPL-2.0-only
+    Apache,
+    WeakCopyleft,
+    Gpl2Only,
+    Gpl3,
+    Proprietary,
+    Unknown,
+}
+
+fn family(id: &str) -> Family {
+    let id = id.trim().to_uppercase();
+    match id.as_str() {
+        "MIT" | "ISC" | "0BSD" | "ZLIB" | "UNLICENSE" | "CC0-1.0" | "BSL-1.0" | "BSD-2-CLAUSE" | "BSD-3-CLAUSE" => Family::Permissive,
+        "APACHE-2.0" => Family::Apache,
+        "UNLICENSED" | "PROPRIETARY" => Family::Proprietary,
+        "GPL-2.0" | "GPL-2.0-ONLY" => Family::Gpl2Only,
+        _ if id.starts_with("MPL") || id.starts_with("LGPL") || id.starts_with("EPL") => Family::WeakCopyleft,
+        _ if id.starts_with("GPL") || id.starts_with("AGPL") => Family::Gpl3,
+        _ => Family::Unknown,
+    }
+}
+
+fn families_compatible(a: Family, b: Family) -> bool {
+    use Family::*;
+    !matches!((a, b),
+        (Proprietary, Gpl2Only | Gpl3) | (Gpl2Only | Gpl3, Proprietary)
+        | (Gpl2Only, Apache | Gpl3) | (Apache | Gpl3, Gpl2Only))
+}
+
+/// Alternatives of an SPDX expression, each a set of license ids that all apply
+fn alternatives(expression: &str) -> Vec<Vec<String>> {
+    let cleaned = expression.replace(['(', ')'], " ").replace('/', " OR ");
+    cleaned.split(" OR ")
+        .map(|alt| alt.split(" AND ")
+            .map(|id| id.split(" WITH ").next().unwrap_or(id).trim().to_string())
+            .filter(|id| !id.is_empty())
+            .collect())
+        .collect()
+}
+
+fn expressions_compatible(a: &str, b: &str) -> bool {
+    alternatives(a).iter().any(|alt_a| alternatives(b).iter().any(|alt_b| {
+        alt_a.iter().all(|x| alt_b.iter().all(|y| families_compatible(family(x), family(y))))
+    }))
+}
+
+/// Pairs of declarations whose licenses cannot be combined
+fn conflicts(declared: &[LicenseDeclaration]) -> Vec<String> {
+    let mut found = Vec::new();
+    for (i, a) in declared.iter().enumerate() {
+        for b in &declared[i + 1..] {
+            if !expressions_compatible(&a.license, &b.license) {
+                found.push(format!("{} ({}) vs {} ({})", a.license, a.source, b.license, b.source));
+            }
+        }
+    }
+    found
+}
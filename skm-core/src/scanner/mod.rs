@@ -0,0 +1,14 @@
+pub mod finder;
+pub mod fingerprint;
+pub mod ignore;
+pub mod parser;
+pub mod git;
+pub mod github;
+pub mod license;
+pub mod pipeline;
+pub mod remote;
+pub mod todos;
+
+pub use finder::*;
+pub use parser::*;
+pub use git::*;
\ No newline at end of file
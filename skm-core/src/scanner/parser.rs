@@ -0,0 +1,526 @@
+use std::path::{Path, PathBuf};
+use std::fs;
+use std::sync::LazyLock;
+use regex::Regex;
+use chrono::{DateTime, Utc};
+use crate::{
+    Result, FileInfo, ArtifactStatus, ArtifactMetrics, ArtifactKind, TaskSummary, FeatureStatus,
+    OpenQuestion, TaskItem, TaskFormat, is_debug,
+};
+
+/// Parse Spec-Kit artifacts from a directory
+///
+/// This function supports two Spec-Kit structures:
+/// 1. Direct artifacts: Files (spec.md, plan.md, tasks.md) directly in the directory
+/// 2. Feature directories: Numbered subdirectories (001-feature-name, 002-feature-name)
+///
+/// When multiple feature directories exist, it aggregates the latest artifacts
+/// and returns the most recent tasks file.
+///
+/// # Arguments
+/// * `specify_path` - Path to .specify or specs directory
+///
+/// # Returns
+/// * `ArtifactStatus` containing references to found artifacts
+pub fn parse_artifacts(specify_path: &Path) -> Result<ArtifactStatus> {
+    let status = ArtifactStatus {
+        constitution: None,
+        spec: None,
+        plan: None,
+        tasks: None,
+    };
+    
+    // First check if artifacts are directly in the directory
+    let direct_artifacts = check_direct_artifacts(specify_path)?;
+    if has_any_artifact(&direct_artifacts) {
+        return Ok(direct_artifacts);
+    }
+    
+    // If no direct artifacts, check for numbered feature directories (001-feature, 002-feature, etc.)
+    // This is the Spec-Kit structure for feature branches
+    let feature_dirs = list_feature_dirs(specify_path);
+    if !feature_dirs.is_empty() {
+        // Aggregate artifacts from all numbered directories
+        // This gives a complete picture of the project's features
+        let mut aggregated = ArtifactStatus {
+            constitution: None,
+            spec: None,
+            plan: None,
+            tasks: None,
+        };
+        
+        // Look for constitution in parent .specify/memory or specs root
+        let memory_constitution = specify_path.parent()
+            .map(|p| p.join(".specify/memory/constitution.md"))
+            .filter(|p| p.exists());
+        if let Some(const_path) = memory_constitution {
+            aggregated.constitution = Some(parse_file_info(&const_path)?);
+        }
+        
+        // Get the latest spec, plan, and aggregate all tasks
+        let mut all_tasks = Vec::new();
+        
+        for feature_dir in feature_dirs.iter().rev() {
+            let feature_status = check_direct_artifacts(feature_dir)?;
+            
+            // Use the latest spec if we don't have one
+            if aggregated.spec.is_none() && feature_status.spec.is_some() {
+                aggregated.spec = feature_status.spec;
+            }
+            
+            // Use the latest plan if we don't have one
+            if aggregated.plan.is_none() && feature_status.plan.is_some() {
+                aggregated.plan = feature_status.plan;
+            }
+            
+            // Collect all tasks for aggregation
+            if let Some(task_file) = feature_status.tasks {
+                all_tasks.push(task_file);
+            }
+        }
+        
+        // If we found tasks, use the most recent one (could aggregate in future)
+        if !all_tasks.is_empty() {
+            aggregated.tasks = Some(all_tasks[0].clone());
+        }
+        
+        if has_any_artifact(&aggregated) {
+            return Ok(aggregated);
+        }
+    }
+    
+    // Return empty status if nothing found
+    Ok(status)
+}
+
+/// List numbered feature directories (001-feature, 002-feature, ...) sorted by name
+pub fn list_feature_dirs(specify_path: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(specify_path) else {
+        return Vec::new();
+    };
+    
+    let mut feature_dirs: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().is_dir())
+        .filter(|e| {
+            // Match directories that start with 3 digits (001, 002, etc.)
+            e.file_name()
+                .to_str()
+                .map(|name| name.chars().take(3).all(|c| c.is_ascii_digit()))
+                .unwrap_or(false)
+        })
+        .map(|e| e.path())
+        .collect();
+    
+    if is_debug() && !feature_dirs.is_empty() {
+        eprintln!("[DEBUG] Found {} numbered feature directories in {}",
+            feature_dirs.len(), specify_path.display());
+        for dir in &feature_dirs {
+            eprintln!("[DEBUG]   - {}", dir.file_name().unwrap_or_default().to_string_lossy());
+        }
+    }
+    
+    // Sort by directory name so the latest feature comes last
+    feature_dirs.sort();
+    feature_dirs
+}
+
+/// Parse every numbered feature directory into its own artifact and task summary
+pub fn parse_features(specify_path: &Path) -> Result<Vec<FeatureStatus>> {
+    list_feature_dirs(specify_path)
+        .into_iter()
+        .map(|dir| {
+            let artifacts = check_direct_artifacts(&dir)?;
+            let tasks = match artifacts.tasks {
+                Some(ref task_file) => parse_tasks_file(&task_file.path)?,
+                None => TaskSummary::default(),
+            };
+            let id = dir.file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            let (number, name) = split_feature_id(&id);
+            
+            Ok(FeatureStatus {
+                id,
+                number,
+                name,
+                path: dir,
+                artifacts,
+                tasks,
+                // Set from the project's context during analysis
+                stage: crate::Stage::default(),
+            })
+        })
+        .collect()
+}
+
+/// Split "001-user-auth" into (Some(1), "user-auth")
+fn split_feature_id(id: &str) -> (Option<u32>, String) {
+    let digits: String = id.chars().take_while(|c| c.is_ascii_digit()).collect();
+    let name = id[digits.len()..].trim_start_matches(['-', '_']).to_string();
+    (digits.parse().ok(), name)
+}
+
+/// Check for artifacts directly in a directory
+fn check_direct_artifacts(path: &Path) -> Result<ArtifactStatus> {
+    let mut status = ArtifactStatus {
+        constitution: None,
+        spec: None,
+        plan: None,
+        tasks: None,
+    };
+    
+    // Check for constitution.md (can be in .specify/memory/ or directly in specs/)
+    let constitution_path = path.join("constitution.md");
+    let memory_constitution = path.join("memory/constitution.md");
+    if constitution_path.exists() {
+        status.constitution = Some(parse_file_info(&constitution_path)?);
+    } else if memory_constitution.exists() {
+        status.constitution = Some(parse_file_info(&memory_constitution)?);
+    }
+    
+    // Check for spec.md
+    let spec_path = path.join("spec.md");
+    if spec_path.exists() {
+        status.spec = Some(parse_file_info(&spec_path)?);
+    }
+    
+    // Check for plan.md
+    let plan_path = path.join("plan.md");
+    if plan_path.exists() {
+        status.plan = Some(parse_file_info(&plan_path)?);
+    }
+    
+    // Check for tasks.md
+    let tasks_path = path.join("tasks.md");
+    if tasks_path.exists() {
+        status.tasks = Some(parse_file_info(&tasks_path)?);
+    }
+    
+    Ok(status)
+}
+
+/// Check if an ArtifactStatus has any artifacts
+fn has_any_artifact(status: &ArtifactStatus) -> bool {
+    status.constitution.is_some() || 
+    status.spec.is_some() || 
+    status.plan.is_some() || 
+    status.tasks.is_some()
+}
+
+pub fn parse_file_info(path: &Path) -> Result<FileInfo> {
+    let metadata = fs::metadata(path)?;
+    let modified = metadata.modified()?;
+    
+    // Basic validation - check if file is readable and not empty
+    let content = fs::read_to_string(path).ok();
+    let valid = content.as_ref()
+        .map(|content| !content.trim().is_empty())
+        .unwrap_or(false);
+    
+    Ok(FileInfo {
+        path: path.to_path_buf(),
+        size: metadata.len(),
+        modified: DateTime::<Utc>::from(modified),
+        valid,
+        metrics: content.as_deref().map(artifact_metrics),
+    })
+}
+
+/// Compute size and complexity metrics for a markdown artifact
+///
+/// Requirements are lines carrying an ID like `FR-001`/`NFR-002`/`REQ-3` or
+/// an RFC 2119 `MUST`/`SHALL`; TODO density is TODO/TBD/FIXME markers per
+/// 1000 words.
+pub fn artifact_metrics(content: &str) -> ArtifactMetrics {
+    static REQUIREMENT: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"\b(?:FR|NFR|REQ)-\d+|\bMUST\b|\bSHALL\b").unwrap());
+    static TODO: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\b(?:TODO|TBD|FIXME)\b").unwrap());
+    
+    let words = content.split_whitespace().count() as u32;
+    let requirements = content.lines()
+        .filter(|line| REQUIREMENT.is_match(line))
+        .count() as u32;
+    let todos = TODO.find_iter(content).count() as u32;
+    
+    ArtifactMetrics {
+        words,
+        sections: count_sections(content) as u32,
+        requirements,
+        todos,
+        todo_density: if words > 0 { todos as f64 * 1000.0 / words as f64 } else { 0.0 },
+    }
+}
+
+/// Parse tasks.md file to extract task summary
+///
+/// Supports multiple task formats:
+/// - Checkbox: `- [ ]`, `- [x]`, `- [X]`
+/// - Task IDs: `T001:`, `T002:` (standalone)
+/// - Emojis: ✅, ❌, 🔄, ⬜
+/// - Keywords: `TODO:`, `DONE:`
+///
+/// Also detects:
+/// - Parallel tasks: `[P]`, `(P)`, `||`
+/// - Blocked tasks: `[BLOCKED]`, 🚫, ⛔
+///
+/// # Arguments
+/// * `path` - Path to tasks.md file
+///
+/// # Returns
+/// * `TaskSummary` with counts and last activity timestamp
+pub fn parse_tasks_file(path: &Path) -> Result<TaskSummary> {
+    let content = fs::read_to_string(path)?;
+
+    if is_debug() {
+        eprintln!("[DEBUG] Parsing tasks from: {}", path.display());
+    }
+
+    let items = parse_task_items(&content);
+    let count = |f: fn(&TaskItem) -> bool| items.iter().filter(|t| f(t)).count() as u32;
+    let (total, completed, parallel_marked, blocked) = (
+        items.len() as u32,
+        count(|t| t.done),
+        count(|t| t.parallel),
+        count(|t| t.blocked),
+    );
+    
+    let metadata = fs::metadata(path)?;
+    let modified = metadata.modified()?;
+    
+    if is_debug() {
+        eprintln!("[DEBUG] Tasks parsed: total={}, completed={}, parallel={}, blocked={}",
+            total, completed, parallel_marked, blocked);
+    }
+    
+    Ok(TaskSummary {
+        total,
+        completed,
+        parallel_marked,
+        blocked,
+        last_activity: Some(DateTime::<Utc>::from(modified)),
+    })
+}
+
+/// Parse every task line of a tasks file, in file order
+pub fn parse_task_items(content: &str) -> Vec<TaskItem> {
+    content.lines()
+        .enumerate()
+        .filter_map(|(i, line)| parse_task_line(line, i + 1))
+        .collect()
+}
+
+static TASK_ID: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"T\d{3,4}:").unwrap());
+
+/// Classify a single line as a task
+///
+/// Supported formats:
+/// - [ ] task / - [x] task / - [X] task (also with task IDs like T001)
+/// - T001: task (done when marked ✅, DONE, [COMPLETE] or [x])
+/// - ✅ task / ☑ task, and ⬜ ❌ ☐ 🔄 for incomplete (in progress counts as incomplete)
+/// - TODO: task / DONE: task
+pub fn parse_task_line(line: &str, line_number: usize) -> Option<TaskItem> {
+    let trimmed = line.trim();
+    let parallel = line.contains("[P]") || line.contains("(P)") || line.contains("||");
+    let item = |format: TaskFormat, text: &str, done: bool, parallel: bool, blocked: bool| Some(TaskItem {
+        line: line_number,
+        text: text.trim().to_string(),
+        done,
+        parallel,
+        blocked,
+        format,
+    });
+
+    // Checkbox format (including those with task IDs like T001)
+    if let Some(text) = trimmed.strip_prefix("- [ ]").or_else(|| trimmed.strip_prefix("* [ ]")) {
+        let blocked = line.contains("[BLOCKED]") || line.contains("🚫") || line.contains("⛔");
+        return item(TaskFormat::Checkbox, text, false, parallel, blocked);
+    }
+    if let Some(text) = ["- [x]", "- [X]", "* [x]", "* [X]"].iter().find_map(|p| trimmed.strip_prefix(p)) {
+        return item(TaskFormat::Checkbox, text, true, parallel, false);
+    }
+
+    // TODO/DONE format (before the task ID format, which claims every line with a colon)
+    if let Some(text) = trimmed.strip_prefix("TODO:").or_else(|| trimmed.strip_prefix("- TODO:")) {
+        return item(TaskFormat::Keyword, text, false, false, false);
+    }
+    if let Some(text) = trimmed.strip_prefix("DONE:").or_else(|| trimmed.strip_prefix("- DONE:")) {
+        return item(TaskFormat::Keyword, text, true, false, false);
+    }
+
+    // Task ID format with colon (T001:, T002:, etc) - standalone format
+    if trimmed.contains(':') && !trimmed.starts_with("- [") && !trimmed.starts_with("* [") {
+        if !TASK_ID.is_match(trimmed) {
+            return None;
+        }
+        let done = line.contains("✅") || line.contains("DONE") || line.contains("[COMPLETE]") ||
+            line.contains("[x]") || line.contains("[X]");
+        let parallel = line.contains("[P]") || line.contains("||");
+        let blocked = line.contains("[BLOCKED]") || line.contains("🚫");
+        return item(TaskFormat::TaskId, trimmed, done, parallel, blocked);
+    }
+
+    // Emoji format
+    if let Some(text) = ["✅", "☑"].iter().find_map(|p| trimmed.strip_prefix(p)) {
+        return item(TaskFormat::Emoji, text, true, false, false);
+    }
+    if let Some(text) = ["⬜", "☐", "❌", "🔄"].iter().find_map(|p| trimmed.strip_prefix(p)) {
+        return item(TaskFormat::Emoji, text, false, false, false);
+    }
+
+    None
+}
+
+/// Mark the checkbox task on a 1-based line done or not done
+///
+/// Only the checkbox itself changes, so the rest of the file (and line
+/// endings) round-trip unchanged; a task already in the requested state is
+/// left as is, and one marked done gets a lowercase `[x]`. Returns `None`
+/// when the line is not a checkbox task.
+pub fn set_task_done(content: &str, line_number: usize, done: bool) -> Option<String> {
+    let mut offset = 0;
+    for (index, line) in content.split_inclusive('\n').enumerate() {
+        if index + 1 == line_number {
+            let item = parse_task_line(line, line_number)?;
+            if item.format != TaskFormat::Checkbox {
+                return None;
+            }
+            if item.done == done {
+                return Some(content.to_string());
+            }
+            let start = line.find('[')?;
+            let mark = if done { "x" } else { " " };
+            let position = offset + start + 1;
+            return Some(format!("{}{}{}", &content[..position], mark, &content[position + 1..]));
+        }
+        offset += line.len();
+    }
+    None
+}
+
+/// Extract unresolved questions from a spec or plan
+///
+/// Recognizes Spec-Kit `[NEEDS CLARIFICATION: ...]` markers (anywhere in a
+/// line), `OPEN:` lines, and question bullets written as `- ? ...` or `- [?] ...`.
+pub fn extract_open_questions(content: &str, source: ArtifactKind) -> Vec<OpenQuestion> {
+    static CLARIFICATION: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"\[NEEDS CLARIFICATION(?::\s*([^\]]*))?\]").unwrap());
+    
+    let mut questions = Vec::new();
+    
+    for (index, line) in content.lines().enumerate() {
+        let trimmed = line.trim();
+        let line_no = index as u32 + 1;
+        let mut push = |text: &str| {
+            let text = if text.trim().is_empty() { trimmed } else { text.trim() };
+            questions.push(OpenQuestion { source, line: line_no, text: text.to_string() });
+        };
+        
+        if CLARIFICATION.is_match(trimmed) {
+            for caps in CLARIFICATION.captures_iter(trimmed) {
+                push(caps.get(1).map(|m| m.as_str()).unwrap_or(""));
+            }
+        } else if let Some(rest) = trimmed.strip_prefix("OPEN:")
+            .or_else(|| trimmed.strip_prefix("- OPEN:"))
+            .or_else(|| trimmed.strip_prefix("* OPEN:")) {
+            push(rest);
+        } else if let Some(rest) = ["- ? ", "* ? ", "- [?] ", "* [?] "].iter()
+            .find_map(|prefix| trimmed.strip_prefix(prefix)) {
+            push(rest);
+        }
+    }
+    
+    questions
+}
+
+/// Principle names of a constitution
+///
+/// Principles are the `###` headings under "Core Principles" (or anywhere,
+/// when there is no such section), with Spec-Kit's roman numerals removed:
+/// `### I. Library-First` gives `Library-First`.
+pub fn extract_principles(content: &str) -> Vec<String> {
+    static NUMERAL: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^[IVXLC]+\.\s+").unwrap());
+    
+    let scoped = has_heading(content, "Core Principles");
+    let mut in_section = !scoped;
+    let mut principles = Vec::new();
+    for line in content.lines() {
+        if let Some(heading) = line.strip_prefix("## ") {
+            in_section = !scoped || heading.trim().eq_ignore_ascii_case("Core Principles");
+        } else if let Some(heading) = line.strip_prefix("### ")
+            && in_section {
+            let name = NUMERAL.replace(heading.trim(), "").trim().to_string();
+            if !name.is_empty() {
+                principles.push(name);
+            }
+        }
+    }
+    principles
+}
+
+/// List items under headings mentioning "Acceptance" (Spec-Kit's
+/// "Acceptance Scenarios", or "Acceptance Criteria")
+///
+/// A section runs to the next heading of the same or a higher level.
+/// Bullet and numbered markers are removed; bold markup is kept.
+pub fn extract_acceptance_criteria(content: &str) -> Vec<String> {
+    static ITEM: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^\s*(?:[-*+]|\d+\.)\s+(.+)$").unwrap());
+
+    let mut section_level: Option<usize> = None;
+    let mut criteria = Vec::new();
+    for line in content.lines() {
+        if line.starts_with('#') {
+            let level = line.chars().take_while(|&c| c == '#').count();
+            if section_level.is_some_and(|l| level <= l) {
+                section_level = None;
+            }
+            if section_level.is_none() && line.to_lowercase().contains("acceptance") {
+                section_level = Some(level);
+            }
+        } else if section_level.is_some()
+            && let Some(caps) = ITEM.captures(line) {
+            criteria.push(caps[1].trim().to_string());
+        }
+    }
+    criteria
+}
+
+/// Extract the title from a markdown file (first # heading)
+pub fn extract_title(content: &str) -> Option<String> {
+    content.lines()
+        .find(|line| line.starts_with("# "))
+        .map(|line| line.trim_start_matches("# ").trim().to_string())
+}
+
+/// Count sections in a markdown file (## headings)
+pub fn count_sections(content: &str) -> usize {
+    content.lines()
+        .filter(|line| line.starts_with("## "))
+        .count()
+}
+
+/// Check whether a markdown file has a heading (any level) with the given title
+pub fn has_heading(content: &str, title: &str) -> bool {
+    content.lines()
+        .filter(|line| line.starts_with('#'))
+        .any(|line| line.trim_start_matches('#').trim().eq_ignore_ascii_case(title))
+}
+
+/// Value of a `key: value` line in the front matter (between `---` lines at
+/// the top) of a markdown file, unquoted
+pub fn frontmatter_value(content: &str, key: &str) -> Option<String> {
+    let mut lines = content.lines();
+    if lines.next()?.trim() != "---" {
+        return None;
+    }
+    lines.take_while(|line| line.trim() != "---")
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case(key))
+        .map(|(_, value)| value.trim().trim_matches(|c| c == '"' || c == '\'').to_string())
+}
+
+/// Whether a tasks file declares its feature finished with `status: done`
+/// in its front matter
+pub fn is_marked_done(content: &str) -> bool {
+    frontmatter_value(content, "status").is_some_and(|status| status.eq_ignore_ascii_case("done"))
+}
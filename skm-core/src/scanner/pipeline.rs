@@ -0,0 +1,788 @@
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+use chrono::{DateTime, Utc};
+use crate::{
+    SKMError, Project, ProjectTiming, ScanError, ScanErrorKind, ScanPhase, StatusSummary, Stage, GitStatus,
+    ArtifactStatus, ArtifactKind, FeatureStatus, FileInfo, OpenQuestion, TestEvidence, TestSource, HumanRequirement,
+    PortfolioStatus, ScanStats,
+};
+use crate::{hash, is_debug};
+use crate::analyzer::sla::ReviewSla;
+use crate::analyzer::{activity, audit, completion, freshness, constitution, stage, testrun, priority::{self, PriorityBreakdown, PriorityFactors, PriorityInputs}};
+use crate::meta::{audits::AuditStore, config::{GlobalConfig, WorkflowConfig}, history::MetricsHistory, registry::Registry, snapshots::SnapshotStore, state::ProjectMetaStore, testruns::TestRunStore};
+use crate::scanner::{finder, git, github, license, parser, todos};
+use crate::session::launch;
+use crate::template::Variables;
+
+/// How git data is collected during a scan
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GitMode {
+    /// Query git inline while processing each project
+    #[default]
+    Sync,
+    /// Leave git data pending so the caller can fill it in later with [`refresh_git`]
+    Deferred,
+    /// Never query git
+    Skip,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ScanOptions {
+    pub git: GitMode,
+    /// Reuse cached results for projects whose fingerprint is unchanged
+    pub incremental: bool,
+}
+
+/// Everything a scan reads besides the project directories themselves
+///
+/// Owned so it can be shared across worker threads behind an `Arc`.
+#[derive(Debug, Clone, Default)]
+pub struct ScanContext {
+    pub config: GlobalConfig,
+    pub meta_store: ProjectMetaStore,
+    pub history: MetricsHistory,
+    pub snapshots: SnapshotStore,
+    pub test_runs: TestRunStore,
+    pub audits: AuditStore,
+    /// The root's `.skm/constitution.md`, inherited by projects without their own
+    pub constitution: Option<PortfolioConstitution>,
+    pub options: ScanOptions,
+    /// Ids of the discovered projects, unique within the root (see [`ScanContext::assign_ids`])
+    pub ids: HashMap<PathBuf, String>,
+}
+
+/// A constitution shared by every project of a root
+#[derive(Debug, Clone)]
+pub struct PortfolioConstitution {
+    pub file: FileInfo,
+    pub principles: Vec<String>,
+}
+
+impl PortfolioConstitution {
+    /// Load `<root>/.skm/constitution.md`, if present
+    pub fn load(root: &Path) -> crate::Result<Option<Self>> {
+        let path = root.join(".skm/constitution.md");
+        if !path.is_file() {
+            return Ok(None);
+        }
+        Ok(Some(Self {
+            file: parser::parse_file_info(&path)?,
+            principles: parser::extract_principles(&std::fs::read_to_string(&path)?),
+        }))
+    }
+}
+
+impl ScanContext {
+    /// Load config, project metadata and metrics history for a root
+    pub fn load(root: &Path, options: ScanOptions) -> crate::Result<Self> {
+        Self::with_config(GlobalConfig::load()?, root, options)
+    }
+    
+    /// Like [`ScanContext::load`] with an already resolved config, e.g. one from [`GlobalConfig::for_root`]
+    pub fn with_config(config: GlobalConfig, root: &Path, options: ScanOptions) -> crate::Result<Self> {
+        Ok(Self {
+            config,
+            meta_store: ProjectMetaStore::load(root)?,
+            history: MetricsHistory::load(root)?,
+            snapshots: SnapshotStore::load(root)?,
+            test_runs: TestRunStore::load(root)?,
+            audits: AuditStore::load(root)?,
+            constitution: PortfolioConstitution::load(root)?,
+            options,
+            ids: HashMap::new(),
+        })
+    }
+    
+    /// Give the projects at `paths`, everything a scan of the root found,
+    /// ids that are unique among them (see [`unique_project_ids`])
+    pub fn assign_ids(&mut self, paths: &[PathBuf]) {
+        self.ids = unique_project_ids(paths);
+    }
+    
+    /// Id of the project at `path`: the one assigned by
+    /// [`ScanContext::assign_ids`], or else [`project_id`]
+    pub fn project_id(&self, path: &Path) -> String {
+        self.ids.get(path).cloned().unwrap_or_else(|| project_id(path))
+    }
+}
+
+/// Discovered and registered projects under `root`, with their ids assigned in `ctx`
+///
+/// Registered projects below the root that discovery skipped are added
+/// after the discovered ones.
+pub fn discover(root: &Path, ctx: &mut ScanContext) -> crate::Result<Vec<PathBuf>> {
+    let mut paths = finder::ProjectScanner::new(root.to_path_buf(), ctx.config.scan_depth)
+        .with_gitignore(ctx.config.discovery.gitignore)
+        .find_projects();
+    if let Ok(canonical) = root.canonicalize() {
+        let registry = Registry::load()?;
+        let known: Vec<_> = paths.iter().filter_map(|p| p.canonicalize().ok()).collect();
+        let extra: Vec<_> = registry.projects_under(&canonical)
+            .filter(|path| !known.iter().any(|k| k == path))
+            .map(Path::to_path_buf)
+            .collect();
+        paths.extend(extra);
+    }
+    ctx.assign_ids(&paths);
+    Ok(paths)
+}
+
+/// Discover and process every project under `root` into a portfolio
+///
+/// A full scan held in memory: nothing under `.skm` is read or written (no
+/// status cache, history or fingerprints), so every project is processed.
+/// Like any scan, `[code_debt] sync_tasks` still rewrites the code debt
+/// section of tasks.md files.
+pub fn scan_portfolio(root: &Path, ctx: &mut ScanContext) -> crate::Result<PortfolioStatus> {
+    let start = Instant::now();
+    let paths = discover(root, ctx)?;
+    let discover_ms = start.elapsed().as_millis() as u64;
+
+    let mut projects = Vec::new();
+    let mut errors = Vec::new();
+    let mut timings = Vec::new();
+    for result in process_projects(&paths, ctx) {
+        match result {
+            Ok((project, timing)) => {
+                errors.extend(custom_field_errors(&project, ctx));
+                timings.push(timing);
+                projects.push(project);
+            }
+            Err(e) => errors.push(e),
+        }
+    }
+
+    let mut scan_stats = ScanStats {
+        directories_scanned: paths.len() as u32,
+        projects_found: projects.len() as u32,
+        scan_time_ms: start.elapsed().as_millis() as u64,
+        errors,
+        discover_ms,
+        slowest: Vec::new(),
+    };
+    scan_stats.set_slowest(timings);
+    Ok(PortfolioStatus {
+        generated_at: Utc::now(),
+        scan_stats,
+        summary: summarize(&projects, &ctx.config.workflow),
+        projects,
+    })
+}
+
+/// Process project directories on up to `config.scan_workers()` threads
+///
+/// Results are returned in the order of `paths`, so output and the cached
+/// portfolio do not depend on which worker finished first.
+pub fn process_projects(
+    paths: &[std::path::PathBuf],
+    ctx: &ScanContext,
+) -> Vec<std::result::Result<(Project, ProjectTiming), ScanError>> {
+    process_projects_with(paths, ctx, |_, _| {})
+}
+
+/// Like [`process_projects`], also handing each result with its index in
+/// `paths` to `on_result` as soon as it is ready, on the worker that produced it
+pub fn process_projects_with(
+    paths: &[std::path::PathBuf],
+    ctx: &ScanContext,
+    on_result: impl Fn(usize, &std::result::Result<(Project, ProjectTiming), ScanError>) + Sync,
+) -> Vec<std::result::Result<(Project, ProjectTiming), ScanError>> {
+    let workers = ctx.config.scan_workers().clamp(1, paths.len().max(1));
+    if workers == 1 {
+        return paths.iter()
+            .enumerate()
+            .map(|(index, path)| {
+                let result = process_project(path, ctx);
+                on_result(index, &result);
+                result
+            })
+            .collect();
+    }
+
+    // Workers take the next unprocessed path until none are left
+    let next = AtomicUsize::new(0);
+    let results = Mutex::new(Vec::with_capacity(paths.len()));
+    std::thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| {
+                loop {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(path) = paths.get(index) else { break };
+                    let result = process_project(path, ctx);
+                    on_result(index, &result);
+                    results.lock().unwrap().push((index, result));
+                }
+            });
+        }
+    });
+    let mut results = results.into_inner().unwrap();
+    results.sort_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+/// Process a single project directory into a fully analyzed `Project`
+pub fn process_project(
+    project_path: &Path,
+    ctx: &ScanContext,
+) -> std::result::Result<(Project, ProjectTiming), ScanError> {
+    let (meta_store, options) = (&ctx.meta_store, &ctx.options);
+    let fail = |phase: ScanPhase| move |e: SKMError| ScanError::from_skm(project_path, phase, &e);
+    let project_start = Instant::now();
+    let overrides = ctx.config.for_project(project_path).map_err(fail(ScanPhase::Analyze))?;
+    let config = overrides.as_ref().unwrap_or(&ctx.config);
+    let mut timing = ProjectTiming {
+        path: project_path.to_path_buf(),
+        ..Default::default()
+    };
+
+    let mut artifacts = load_artifacts(project_path).map_err(fail(ScanPhase::Artifacts))?;
+    // Read before the portfolio constitution is filled in: questions in it
+    // belong to the portfolio, not to every project inheriting it
+    let open_questions = load_open_questions(&artifacts);
+    let own_principles = match &artifacts.constitution {
+        Some(file) => parser::extract_principles(&std::fs::read_to_string(&file.path).unwrap_or_default()),
+        None => Vec::new(),
+    };
+    let constitution_inherited = artifacts.constitution.is_none() && ctx.constitution.is_some();
+    if constitution_inherited {
+        artifacts.constitution = ctx.constitution.as_ref().map(|c| c.file.clone());
+    }
+    let principles = constitution::merge_principles(
+        ctx.constitution.as_ref().map_or(&[][..], |c| &c.principles),
+        &own_principles,
+    );
+    let features = load_features(project_path).map_err(fail(ScanPhase::Artifacts))?;
+    timing.artifacts_ms = project_start.elapsed().as_millis() as u64;
+
+    // Harvest code debt first, so a synced tasks.md section is counted below
+    let code_debt = if config.code_debt.enabled {
+        let todos = todos::harvest(project_path);
+        if config.code_debt.sync_tasks
+            && let Some(task_file) = &artifacts.tasks {
+            todos::sync_tasks_section(&task_file.path, &todos)
+                .map_err(|e| fail(ScanPhase::Tasks)(e.into()))?;
+        }
+        Some(todos.len() as u32)
+    } else {
+        None
+    };
+
+    // Parse tasks if available
+    let phase_start = Instant::now();
+    let tasks = if let Some(ref task_file) = artifacts.tasks {
+        parser::parse_tasks_file(&task_file.path).map_err(fail(ScanPhase::Tasks))?
+    } else {
+        Default::default()
+    };
+    timing.tasks_ms = phase_start.elapsed().as_millis() as u64;
+
+    let project_id = ctx.project_id(project_path);
+    let git_enabled = meta_store.get_project(&project_id)
+        .map(|m| m.git.enabled)
+        .unwrap_or(true);
+
+    // Get git status
+    let phase_start = Instant::now();
+    let (git_status, has_errors) = match options.git {
+        _ if !git_enabled => (GitStatus::skipped(), false),
+        GitMode::Skip => (GitStatus::skipped(), false),
+        GitMode::Deferred => (GitStatus::pending(), false),
+        GitMode::Sync => (
+            query_git(project_path, &project_id, ctx).map_err(fail(ScanPhase::Git))?,
+            git::has_recent_errors(project_path).map_err(fail(ScanPhase::Git))?,
+        ),
+    };
+    timing.git_ms = phase_start.elapsed().as_millis() as u64;
+
+    let phase_start = Instant::now();
+    let project_type = finder::detect_project_type(project_path);
+    let capabilities = finder::detect_capabilities(project_path);
+    let license = license::detect_license(project_path);
+    let audit = if config.audit.enabled {
+        let profile = meta_store.get_project(&project_id).map(|m| m.exec.clone()).unwrap_or_default();
+        audit::audit_project(project_path, &profile, &capabilities, ctx.audits.projects.get(&project_id))
+    } else {
+        None
+    };
+    let freshness = if config.freshness.enabled {
+        freshness::check_freshness(project_path, ctx.audits.freshness.get(&project_id),
+            config.freshness.max_age_days, &config.retry)
+    } else {
+        None
+    };
+    let spec_words = artifacts.spec.as_ref()
+        .and_then(|f| f.metrics.as_ref())
+        .map(|m| m.words);
+    let spec_trend = ctx.history.spec_trend(&project_id, spec_words);
+
+    let mut project = Project {
+        id: project_id,
+        path: project_path.to_path_buf(),
+        stage: Stage::Bootstrap,
+        next: stage::get_next_action(&Stage::Bootstrap),
+        requires_human: Vec::new(),
+        priority: 0.0,
+        tasks,
+        updated: Utc::now(),
+        git: git_status,
+        project_type,
+        artifacts,
+        features,
+        spec_trend,
+        open_questions,
+        root: None,
+        needs_attention: false,
+        pinned: false,
+        remote: None,
+        last_test: None,
+        last_check: None,
+        test_evidence: None,
+        unstable: Vec::new(),
+        code_debt,
+        constitution_inherited,
+        principles,
+        audit,
+        freshness,
+        license,
+        stage_since: None,
+        inputs: None,
+        capabilities,
+        custom: BTreeMap::new(),
+        season: None,
+        priority_breakdown: None,
+        review_sla: None,
+    };
+    if let Some(meta) = meta_store.get_project(&project.id) {
+        project.custom = meta.custom.clone().into_iter().collect();
+    }
+    Variables::for_project(&project, config).resolve_project(&mut project);
+    project.last_test = ctx.test_runs.projects.get(&project.id).cloned();
+    project.last_check = ctx.test_runs.checks.get(&project.id).cloned();
+    analyze_project(&mut project, has_errors, config, ctx);
+
+    timing.analyze_ms = phase_start.elapsed().as_millis() as u64;
+    timing.total_ms = project_start.elapsed().as_millis() as u64;
+    timing.id = project.id.clone();
+
+    Ok((project, timing))
+}
+
+/// Custom fields of a project that do not match the declared schema, as scan errors
+///
+/// These are reported alongside the project rather than excluding it.
+pub fn custom_field_errors(project: &Project, ctx: &ScanContext) -> Vec<ScanError> {
+    ctx.meta_store.custom_field_errors(&project.id)
+        .into_iter()
+        .map(|message| ScanError::new(&project.path, ScanPhase::Analyze, ScanErrorKind::Config, message))
+        .collect()
+}
+
+/// Parse artifacts from the `specs` or `.specify` directory of a project
+pub fn load_artifacts(project_path: &Path) -> crate::Result<ArtifactStatus> {
+    let specify_path = project_path.join(".specify");
+    let specs_path = project_path.join("specs");
+
+    if is_debug() {
+        eprintln!("[DEBUG] Processing project: {}", project_path.display());
+        eprintln!("[DEBUG]   .specify exists: {}", specify_path.exists());
+        eprintln!("[DEBUG]   specs exists: {}", specs_path.exists());
+    }
+
+    // Prefer specs directory if it exists (it usually has the feature directories)
+    // Only use .specify if specs doesn't exist or has no content
+    if specs_path.exists() {
+        let specs_artifacts = parser::parse_artifacts(&specs_path)?;
+        // Check if specs has any artifacts
+        if specs_artifacts.constitution.is_some() || specs_artifacts.spec.is_some() ||
+           specs_artifacts.plan.is_some() || specs_artifacts.tasks.is_some() {
+            if is_debug() {
+                eprintln!("[DEBUG]   Using artifacts from specs");
+            }
+            Ok(specs_artifacts)
+        } else if specify_path.exists() {
+            // specs has no artifacts, try .specify
+            if is_debug() {
+                eprintln!("[DEBUG]   specs has no artifacts, trying .specify");
+            }
+            parser::parse_artifacts(&specify_path)
+        } else {
+            Ok(specs_artifacts) // Use empty artifacts from specs
+        }
+    } else if specify_path.exists() {
+        // No specs, try .specify
+        if is_debug() {
+            eprintln!("[DEBUG]   Using artifacts from .specify");
+        }
+        parser::parse_artifacts(&specify_path)
+    } else {
+        // Neither exists, return empty artifacts
+        if is_debug() {
+            eprintln!("[DEBUG]   No artifacts found");
+        }
+        parser::parse_artifacts(&specify_path)
+    }
+}
+
+/// Parse per-feature artifacts, preferring `specs` over `.specify` like [`load_artifacts`]
+pub fn load_features(project_path: &Path) -> crate::Result<Vec<FeatureStatus>> {
+    for dir in [project_path.join("specs"), project_path.join(".specify")] {
+        let features = parser::parse_features(&dir)?;
+        if !features.is_empty() {
+            return Ok(features);
+        }
+    }
+    Ok(Vec::new())
+}
+
+/// Collect open questions from the project's own spec and plan
+pub fn load_open_questions(artifacts: &ArtifactStatus) -> Vec<OpenQuestion> {
+    [(ArtifactKind::Spec, &artifacts.spec), (ArtifactKind::Plan, &artifacts.plan)]
+        .into_iter()
+        .filter_map(|(kind, file)| {
+            let content = std::fs::read_to_string(&file.as_ref()?.path).ok()?;
+            Some(parser::extract_open_questions(&content, kind))
+        })
+        .flatten()
+        .collect()
+}
+
+/// Derive stage, human requirements, priority and next action from collected data
+///
+/// `config` is the project's effective config (see [`GlobalConfig::for_project`]).
+pub fn analyze_project(project: &mut Project, has_errors: bool, config: &GlobalConfig, ctx: &ScanContext) {
+    let meta_store = &ctx.meta_store;
+    let implemented = stage::has_implementation_artifacts(&project.path, &project.project_type);
+    let shipped = project.git.is_shipped();
+    let evidence = test_evidence(project);
+    project.test_evidence = testrun::latest_outcome(evidence.clone(), project.tasks.last_activity);
+    let signals = stage::StageSignals {
+        implemented,
+        shipped,
+        ..stage::StageSignals::collect(&project.artifacts, &project.tasks, project.test_evidence.as_ref())
+    };
+    // A failing test run counts as an error signal, like error markers in commits
+    let has_errors = has_errors
+        || project.last_test.as_ref().is_some_and(|run| !run.success)
+        || project.test_evidence.as_ref().is_some_and(|evidence| !evidence.passed)
+        || project.last_check.as_ref().is_some_and(|run| !run.success);
+    // `done` in the project meta overrides what the signals say either way,
+    let project_meta = meta_store.get_project(&project.id);
+    let done_override = project_meta.and_then(|m| m.done);
+    // and applies to the features as well
+    let detect = |artifacts: &ArtifactStatus, signals: stage::StageSignals| match done_override {
+        Some(true) => Stage::Done,
+        Some(false) => stage::detect_stage(artifacts, &stage::StageSignals { marked_done: false, shipped: false, ..signals }, &config.workflow),
+        None => stage::detect_stage(artifacts, &signals, &config.workflow),
+    };
+    let current_stage = detect(&project.artifacts, signals);
+    for feature in &mut project.features {
+        // A feature shares the project's constitution and test runs
+        let mut artifacts = feature.artifacts.clone();
+        artifacts.constitution = artifacts.constitution.or_else(|| project.artifacts.constitution.clone());
+        let tests = testrun::latest_outcome(evidence.clone(), feature.tasks.last_activity);
+        let signals = stage::StageSignals {
+            implemented,
+            shipped,
+            ..stage::StageSignals::collect(&artifacts, &feature.tasks, tests.as_ref())
+        };
+        feature.stage = detect(&artifacts, signals);
+    }
+    rank_project(project, current_stage.clone(), has_errors, config, ctx);
+
+    // Custom stages stand between review and done; their rules see the project
+    // as ranked with its built-in stage
+    if done_override != Some(true) && matches!(current_stage, Stage::Review | Stage::Done)
+        && let Some(custom) = config.workflow.stages.iter()
+            .find(|custom| custom.rule().is_ok_and(|rule| rule.matches(project)))
+    {
+        rank_project(project, Stage::Custom(custom.name.clone()), has_errors, config, ctx);
+    }
+}
+
+/// Risk, human requirements, priority and next action of a project in `current_stage`
+fn rank_project(project: &mut Project, current_stage: Stage, has_errors: bool, config: &GlobalConfig, ctx: &ScanContext) {
+    let project_meta = ctx.meta_store.get_project(&project.id);
+    let scope_creep = project.spec_trend.as_ref().is_some_and(|t| t.scope_creep);
+
+    // Calculate risk and detect requirements
+    let vulnerabilities = project.audit.as_ref().map_or(0, |a| a.vulnerabilities);
+    let stale_dependencies = project.freshness.as_ref()
+        .map_or(0, |f| priority::freshness_risk(f.major_behind, f.direct));
+    let risk_level = (priority::calculate_risk(&current_stage, &project.git, &project.tasks, has_errors, scope_creep)
+        + priority::vulnerability_risk(vulnerabilities) + stale_dependencies).min(3);
+    let mut human_reqs = priority::detect_human_requirements(
+        &current_stage, &project.git, &project.tasks, project.open_questions.len(),
+    );
+    let custom = match &current_stage {
+        Stage::Custom(name) => config.workflow.custom_stage(name),
+        _ => None,
+    };
+    if custom.is_some_and(|custom| custom.human) && !human_reqs.contains(&HumanRequirement::Review) {
+        human_reqs.insert(0, HumanRequirement::Review);
+    }
+
+    let impact = project_meta.and_then(|m| m.impact).unwrap_or(2);
+    let confidence = if project_meta.map(|m| m.approved_by_human).unwrap_or(false) { 2 } else { 1 };
+
+    let last_updated = activity::last_activity(project, &ctx.snapshots)
+        .unwrap_or_else(Utc::now);
+    let open_tasks = project.tasks.total.saturating_sub(project.tasks.completed);
+    project.stage_since = Some(ctx.history.stage_since(&project.id, &current_stage).unwrap_or_else(Utc::now));
+    project.stage = current_stage;
+    project.requires_human = human_reqs;
+    project.updated = last_updated;
+    let inputs = PriorityInputs {
+        risk_level,
+        impact,
+        approved_by_human: confidence > 1,
+        last_activity: last_updated,
+        idle_days: 0,
+        open_tasks,
+    };
+    rank_at(project, inputs, config, ctx, Utc::now());
+    project.next = match custom {
+        Some(custom) => custom.next_action(project),
+        None => stage::get_next_action(&project.stage),
+    };
+    project.next.launch = launch::launch_targets(&project.next, &project.path, &project.capabilities, &config.agent_priority);
+    project.inputs = Some(analysis_inputs(project, config, ctx));
+    project.unstable = ctx.history.unstable_fields(project);
+}
+
+/// Bring the clock-dependent parts of a cached project's ranking up to date
+///
+/// Staleness, season, review SLA, priority and attention change with time
+/// alone, so a project reused from a cache whose fingerprint still matches
+/// is re-ranked from its recorded inputs without being analyzed again.
+/// `config` is the project's effective config.
+pub fn rerank(project: &mut Project, config: &GlobalConfig, ctx: &ScanContext) {
+    if let Some(inputs) = project.priority_breakdown.as_ref().map(|b| b.inputs.clone()) {
+        rank_at(project, inputs, config, ctx, Utc::now());
+    }
+}
+
+/// Priority, season, review SLA and attention of a project in its stage as of `now`
+fn rank_at(project: &mut Project, inputs: PriorityInputs, config: &GlobalConfig, ctx: &ScanContext, now: DateTime<Utc>) {
+    let project_meta = ctx.meta_store.get_project(&project.id);
+    let calculator = config.priority_strategy.calculator(&priority::PriorityWeights {
+        needs_human: config.weights.needs_human,
+        risk: config.weights.risk,
+        staleness: config.weights.staleness,
+        impact: config.weights.impact,
+        confidence: config.weights.confidence,
+    });
+
+    // Out of season, staleness stops growing where the window closed
+    let window = project_meta.and_then(|m| m.active_window.as_ref());
+    project.season = window.map(|window| window.season(now));
+    let clock = window.map_or(now, |window| window.staleness_clock(now));
+    let confidence = if inputs.approved_by_human { 2 } else { 1 };
+    let factors = PriorityFactors::new(&project.requires_human, inputs.risk_level, inputs.last_activity, inputs.impact, confidence, clock)
+        .with_open_tasks(inputs.open_tasks);
+    let score = calculator.score(&factors);
+    // Manual adjustments from meta.json: an override replaces the score, a boost shifts it
+    let priority_override = project_meta.and_then(|m| m.priority_override);
+    let boost = project_meta.and_then(|m| m.priority_boost);
+    project.priority = priority_override.unwrap_or_else(|| (score + boost.unwrap_or(0.0)).max(0.0));
+    // Finished projects sink to the bottom and leave the attention list
+    if project.stage == Stage::Done {
+        project.priority = 0.0;
+    }
+    project.pinned = project_meta.is_some_and(|m| m.pinned) && project.stage != Stage::Done;
+    project.priority_breakdown = Some(PriorityBreakdown {
+        strategy: config.priority_strategy.to_string(),
+        inputs: PriorityInputs { idle_days: (clock - inputs.last_activity).num_days(), ..inputs },
+        factors,
+        terms: calculator.terms(&factors),
+        score,
+        boost,
+        priority_override,
+    });
+    let stage_since = project.stage_since.unwrap_or(now);
+    project.review_sla = match (&project.stage, config.sla.review_days) {
+        (Stage::Review, Some(limit)) => Some(ReviewSla::new(stage_since, limit, now)),
+        _ => None,
+    };
+    // A breached review SLA escalates the project whatever its priority,
+    // unless the priority was overridden; pinning overrides everything
+    let breached = project.review_sla.as_ref().is_some_and(|sla| sla.breached) && priority_override.is_none();
+    let paused = project.season.as_ref().is_some_and(|season| !season.active);
+    project.needs_attention = project.pinned
+        || ((project.priority > config.attention_threshold || breached) && !paused);
+}
+
+/// Every test outcome known for a project: the last `skm test` run, result
+/// files left by its test tools, a CI note on HEAD and the GitHub checks of HEAD
+fn test_evidence(project: &Project) -> Vec<TestEvidence> {
+    let mut evidence = testrun::find_test_results(&project.path);
+    evidence.extend(project.last_test.as_ref().map(TestEvidence::from_run));
+    evidence.extend(project.git.ci_note.clone());
+    evidence.extend(project.git.github.as_ref().and_then(|github| {
+        github.head_checks.map(|passed| TestEvidence {
+            source: TestSource::GitHubChecks,
+            at: github.at,
+            passed,
+            path: None,
+        })
+    }));
+    evidence
+}
+
+/// Fingerprint of what `analyze_project` reads, so scans of an unchanged
+/// project can be told apart from scans of a changed one
+fn analysis_inputs(project: &Project, config: &GlobalConfig, ctx: &ScanContext) -> String {
+    let inputs = serde_json::json!({
+        "artifacts": project.artifacts,
+        "features": project.features,
+        "tasks": project.tasks,
+        "open_questions": project.open_questions.len(),
+        "spec_trend": project.spec_trend,
+        "git": {
+            "last_commit": project.git.last_commit,
+            "clean": project.git.clean,
+            "is_repo": project.git.is_repo,
+            "shipped": project.git.is_shipped(),
+            "skipped": project.git.skipped,
+            "pending": project.git.pending,
+            "github": project.git.github.as_ref().map(|g| (g.open_prs, g.failing_checks, g.review_requests)),
+            "ci_note": project.git.ci_note,
+        },
+        "vulnerabilities": project.audit.as_ref().map(|a| a.vulnerabilities),
+        "major_behind": project.freshness.as_ref().map(|f| (f.major_behind, f.direct)),
+        "runs": [project.last_test.as_ref().map(|r| r.at), project.last_check.as_ref().map(|r| r.at)],
+        "test_evidence": project.test_evidence,
+        "meta": ctx.meta_store.get_project(&project.id),
+        "season": project.season.as_ref().map(|season| season.active),
+        "weights": config.weights,
+        "priority_strategy": config.priority_strategy,
+        "workflow": config.workflow,
+        "sla": config.sla,
+    });
+    hash::blob_id(inputs.to_string().as_bytes())
+}
+
+/// Git status of a project, with its GitHub counts when those are enabled
+fn query_git(project_path: &Path, project_id: &str, ctx: &ScanContext) -> crate::Result<GitStatus> {
+    let mut status = git::get_git_status_with_retry(project_path, &ctx.config.retry)?;
+    if ctx.config.github.enabled && status.is_repo {
+        status.github = github::github_status(project_path, &ctx.config.github,
+            ctx.audits.github.get(project_id), &ctx.config.retry);
+    }
+    Ok(status)
+}
+
+/// Query git for a project whose git data was deferred, then re-run the analysis
+///
+/// The project is no longer pending afterwards, even when the query fails;
+/// the error is returned for the scan's error list.
+pub fn refresh_git(project: &mut Project, ctx: &ScanContext) -> std::result::Result<(), ScanError> {
+    project.git.pending = false;
+    let path = project.path.clone();
+    let path = path.as_path();
+    let fail = |phase: ScanPhase| move |e: SKMError| ScanError::from_skm(path, phase, &e);
+    let overrides = ctx.config.for_project(path).map_err(fail(ScanPhase::Analyze))?;
+    let git_status = query_git(path, &project.id, ctx).map_err(fail(ScanPhase::Git))?;
+    let has_errors = git::has_recent_errors(path).map_err(fail(ScanPhase::Git))?;
+
+    project.git = git_status;
+    analyze_project(project, has_errors, overrides.as_ref().unwrap_or(&ctx.config), ctx);
+    Ok(())
+}
+
+/// Summary statistics with a completion breakdown for each labeled scan root
+pub fn summarize_roots(projects: &[Project], roots: &[(String, &Path)], workflow: &WorkflowConfig) -> StatusSummary {
+    StatusSummary {
+        completion: completion::completion_by_root(projects, roots),
+        ..summarize(projects, workflow)
+    }
+}
+
+/// Build portfolio summary statistics for a set of analyzed projects
+///
+/// Attention counts use each project's `needs_attention`, so projects from
+/// roots with different thresholds can be summarized together. The
+/// workflow's custom stages are kept in order for [`StatusSummary::ladder`].
+pub fn summarize(projects: &[Project], workflow: &WorkflowConfig) -> StatusSummary {
+    let mut by_stage: BTreeMap<Stage, u32> = BTreeMap::new();
+    for project in projects {
+        *by_stage.entry(project.stage.clone()).or_insert(0) += 1;
+    }
+
+    let avg_priority = if projects.is_empty() {
+        0.0
+    } else {
+        projects.iter().map(|p| p.priority).sum::<f64>() / projects.len() as f64
+    };
+
+    StatusSummary {
+        needs_attention: projects.iter()
+            .filter(|p| p.needs_attention)
+            .count() as u32,
+        total_projects: projects.len() as u32,
+        by_stage,
+        custom_stages: workflow.stages.iter().map(|stage| stage.name.clone()).collect(),
+        total_tasks: projects.iter().map(|p| p.tasks.total).sum(),
+        completed_tasks: projects.iter().map(|p| p.tasks.completed).sum(),
+        avg_priority,
+        completion: completion::completion(projects),
+        open_questions: projects.iter().map(|p| p.open_questions.len() as u32).sum(),
+    }
+}
+
+/// Project identifier derived from its directory name
+///
+/// Names that are not valid UTF-8 keep every byte: invalid bytes, and `%`
+/// so the encoding stays unambiguous, are percent-encoded. A path without a
+/// final component, such as `.`, is named after the directory it resolves to.
+pub fn project_id(project_path: &Path) -> String {
+    let name = project_path.file_name().map(OsStr::to_os_string)
+        .or_else(|| project_path.canonicalize().ok()?.file_name().map(OsStr::to_os_string));
+    let Some(name) = name else {
+        return "unknown".to_string();
+    };
+    match name.to_str() {
+        Some(name) => name.to_string(),
+        None => {
+            let mut id = String::new();
+            for chunk in name.as_encoded_bytes().utf8_chunks() {
+                id.push_str(&chunk.valid().replace('%', "%25"));
+                chunk.invalid().iter().for_each(|byte| id.push_str(&format!("%{:02X}", byte)));
+            }
+            id
+        }
+    }
+}
+
+/// [`project_id`]s for the projects at `paths`, made unique among them
+///
+/// Where directories share a name, the first in path order keeps it and the
+/// others get a numeric suffix (`api-2`, `api-3`), skipping ids another
+/// project already has, so the same paths always get the same ids.
+pub fn unique_project_ids(paths: &[PathBuf]) -> HashMap<PathBuf, String> {
+    let mut sorted: Vec<&PathBuf> = paths.iter().collect();
+    sorted.sort();
+    sorted.dedup();
+    let bases: Vec<String> = sorted.iter().map(|path| project_id(path)).collect();
+    let mut taken: HashSet<String> = bases.iter().cloned().collect();
+    let mut seen = HashSet::new();
+    let mut ids = HashMap::new();
+    for (path, base) in sorted.into_iter().zip(bases) {
+        let id = if seen.insert(base.clone()) {
+            base
+        } else {
+            let mut suffix = 2;
+            while taken.contains(&format!("{}-{}", base, suffix)) {
+                suffix += 1;
+            }
+            let id = format!("{}-{}", base, suffix);
+            taken.insert(id.clone());
+            id
+        };
+        ids.insert(path.clone(), id);
+    }
+    ids
+}
@@ -0,0 +1,121 @@
+use std::path::{Component, Path, PathBuf};
+use std::fs;
+#[cfg(feature = "git")]
+use git2::{FetchOptions, Repository, ResetType, build::RepoBuilder};
+use crate::{Result, SKMError};
+#[cfg(feature = "git")]
+use crate::retry::retry;
+use crate::retry::RetryPolicy;
+
+/// Directory holding shallow clones of remote projects (`~/.cache/skm/remotes`)
+pub fn cache_dir() -> Result<PathBuf> {
+    let base = match std::env::var("XDG_CACHE_HOME") {
+        Ok(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => {
+            let home = std::env::var("HOME").map_err(|_| SKMError::ConfigError {
+                message: "HOME is not set; cannot locate the remote cache".to_string(),
+            })?;
+            PathBuf::from(home).join(".cache")
+        }
+    };
+    Ok(base.join("skm/remotes"))
+}
+
+/// Check that a remote name is a single plain path component
+///
+/// Names become directories under [`cache_dir`], so `..`, `/` and absolute
+/// paths would let a clone or a removal escape the cache.
+pub fn validate_name(name: &str) -> Result<()> {
+    let mut components = Path::new(name).components();
+    match (components.next(), components.next()) {
+        (Some(Component::Normal(_)), None) => Ok(()),
+        _ => Err(SKMError::ConfigError {
+            message: format!("Invalid remote name {:?}: use a single directory name", name),
+        }),
+    }
+}
+
+/// Local checkout path of a remote project
+pub fn checkout_path(name: &str) -> Result<PathBuf> {
+    validate_name(name)?;
+    Ok(cache_dir()?.join(name))
+}
+
+/// Whether `path` resolves to a directory strictly inside the remote cache
+fn inside_cache(path: &Path) -> bool {
+    let (Ok(cache), Ok(path)) = (cache_dir().and_then(|dir| Ok(dir.canonicalize()?)), path.canonicalize()) else {
+        return false;
+    };
+    path != cache && path.starts_with(&cache)
+}
+
+/// Project name derived from a URL: its last path segment without `.git`
+pub fn name_from_url(url: &str) -> Option<String> {
+    let name = url.trim_end_matches('/')
+        .rsplit(['/', ':'])
+        .next()?
+        .trim_end_matches(".git");
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+/// Clone a remote into the cache, or update an existing clone to the remote HEAD
+///
+/// Clones are shallow (depth 1); network failures are retried per `policy`.
+#[cfg(feature = "git")]
+pub fn sync(name: &str, url: &str, policy: &RetryPolicy) -> Result<PathBuf> {
+    let path = checkout_path(name)?;
+
+    if path.join(".git").exists() {
+        let repo = Repository::open(&path)?;
+        retry(policy, "git fetch", || fetch_head(&repo))?;
+        let target = repo.find_reference("FETCH_HEAD")?.peel_to_commit()?;
+        repo.reset(target.as_object(), ResetType::Hard, None)?;
+    } else {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        retry(policy, "git clone", || shallow_clone(url, &path))?;
+    }
+
+    Ok(path)
+}
+
+#[cfg(not(feature = "git"))]
+pub fn sync(_name: &str, _url: &str, _policy: &RetryPolicy) -> Result<PathBuf> {
+    Err(SKMError::FeatureDisabled { feature: "git" })
+}
+
+#[cfg(feature = "git")]
+fn shallow_clone(url: &str, path: &Path) -> std::result::Result<Repository, git2::Error> {
+    // A failed attempt can leave a partial checkout behind
+    if inside_cache(path) {
+        let _ = fs::remove_dir_all(path);
+    }
+
+    let mut fetch = FetchOptions::new();
+    fetch.depth(1);
+    RepoBuilder::new().fetch_options(fetch).clone(url, path)
+}
+
+#[cfg(feature = "git")]
+fn fetch_head(repo: &Repository) -> std::result::Result<(), git2::Error> {
+    let mut remote = repo.find_remote("origin")?;
+    let mut fetch = FetchOptions::new();
+    fetch.depth(1);
+    remote.fetch(&["HEAD"], Some(&mut fetch), None)
+}
+
+/// Delete the cached clone of a remote project
+pub fn remove_checkout(name: &str) -> Result<()> {
+    let path = checkout_path(name)?;
+    if !path.exists() {
+        return Ok(());
+    }
+    if !inside_cache(&path) {
+        return Err(SKMError::ConfigError {
+            message: format!("Refusing to delete {}: it is outside the remote cache", path.display()),
+        });
+    }
+    fs::remove_dir_all(path)?;
+    Ok(())
+}
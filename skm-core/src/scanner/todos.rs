@@ -0,0 +1,154 @@
+use std::path::{Path, PathBuf};
+use std::fs;
+use std::sync::LazyLock;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use walkdir::WalkDir;
+use crate::scanner::finder::should_ignore;
+
+/// Upper bound on source files read per project
+const FILE_LIMIT: usize = 5_000;
+
+/// Files larger than this are skipped (generated or vendored code)
+const MAX_FILE_BYTES: u64 = 1024 * 1024;
+
+/// Extensions of files whose comments are harvested
+pub(crate) const SOURCE_EXTENSIONS: &[&str] = &[
+    "rs", "go", "py", "js", "jsx", "ts", "tsx", "mjs", "cjs", "java", "kt", "swift", "c", "h",
+    "cc", "cpp", "hpp", "cs", "rb", "php", "scala", "sh", "bash", "zsh", "lua", "ex", "exs",
+    "sql", "vue", "svelte", "dart", "zig",
+];
+
+static MARKER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?://|#|/\*|--|\*|<!--)\s*(TODO|FIXME)\b(?:\([^)]*\))?:?\s*(.*)").unwrap()
+});
+
+/// A TODO or FIXME comment in a source file
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CodeTodo {
+    /// Path relative to the project root
+    pub path: PathBuf,
+    /// 1-based line number
+    pub line: usize,
+    /// `TODO` or `FIXME`
+    pub marker: String,
+    pub text: String,
+}
+
+impl CodeTodo {
+    /// `src/lib.rs:12 FIXME: text`
+    pub fn describe(&self) -> String {
+        if self.text.is_empty() {
+            format!("{}:{} {}", self.path.display(), self.line, self.marker)
+        } else {
+            format!("{}:{} {}: {}", self.path.display(), self.line, self.marker, self.text)
+        }
+    }
+}
+
+/// Collect TODO/FIXME comments from the source files of a project
+///
+/// Build output and dependency directories are skipped, as are Spec-Kit
+/// and skm directories and, inside a git repository, gitignored files
+/// (with the `git` feature). Results are in path order.
+pub fn harvest(project: &Path) -> Vec<CodeTodo> {
+    #[cfg(feature = "git")]
+    let repo = git2::Repository::open(project).ok();
+    #[cfg(feature = "git")]
+    let ignored = |path: &Path| {
+        let Some(repo) = &repo else { return false };
+        path.strip_prefix(project).is_ok_and(|relative| repo.is_path_ignored(relative).unwrap_or(false))
+    };
+    #[cfg(not(feature = "git"))]
+    let ignored = |_: &Path| false;
+
+    let mut todos = Vec::new();
+    let files = WalkDir::new(project)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|e| {
+            let name = e.file_name().to_string_lossy();
+            e.depth() == 0
+                || !(should_ignore(e.path()) || name.starts_with('.') || name == "specs" || name == "vendor"
+                    || ignored(e.path()))
+        })
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file() && is_source(e.path()))
+        .take(FILE_LIMIT);
+
+    for entry in files {
+        if entry.metadata().is_ok_and(|m| m.len() > MAX_FILE_BYTES) {
+            continue;
+        }
+        let Ok(content) = fs::read_to_string(entry.path()) else {
+            continue;
+        };
+        let relative = entry.path().strip_prefix(project).unwrap_or(entry.path());
+        for (index, line) in content.lines().enumerate() {
+            if let Some(caps) = MARKER.captures(line) {
+                let text = caps[2].trim().trim_end_matches("*/").trim_end_matches("-->").trim();
+                todos.push(CodeTodo {
+                    path: relative.to_path_buf(),
+                    line: index + 1,
+                    marker: caps[1].to_string(),
+                    text: text.to_string(),
+                });
+            }
+        }
+    }
+    todos
+}
+
+fn is_source(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| SOURCE_EXTENSIONS.contains(&ext))
+}
+
+/// Heading of the tasks.md section kept in sync with the harvested comments
+pub const CODE_DEBT_HEADING: &str = "## Code debt";
+
+const GENERATED_NOTE: &str = "<!-- Generated by skm from TODO/FIXME comments; changes here are overwritten -->";
+
+/// Replace the `## Code debt` section of a tasks file with one open task
+/// per comment, appending the section if missing
+///
+/// The section runs to the next `##` heading. CRLF line endings are kept.
+/// The file is only written when its content changes; returns whether it was.
+pub fn sync_tasks_section(tasks_path: &Path, todos: &[CodeTodo]) -> std::io::Result<bool> {
+    let original = fs::read_to_string(tasks_path)?;
+    let crlf = original.contains("\r\n");
+    let content = original.replace("\r\n", "\n");
+    let mut section = format!("{}\n\n{}\n", CODE_DEBT_HEADING, GENERATED_NOTE);
+    for todo in todos {
+        section.push_str(&format!("- [ ] {}\n", todo.describe()));
+    }
+
+    let lines: Vec<&str> = content.lines().collect();
+    let updated = match lines.iter().position(|line| line.trim().eq_ignore_ascii_case(CODE_DEBT_HEADING)) {
+        Some(start) => {
+            let end = lines[start + 1..].iter()
+                .position(|line| line.starts_with("## ") || line.starts_with("# "))
+                .map_or(lines.len(), |offset| start + 1 + offset);
+            let mut updated = lines[..start].join("\n");
+            if start > 0 {
+                updated.push('\n');
+            }
+            updated.push_str(&section);
+            if end < lines.len() {
+                updated.push('\n');
+                updated.push_str(&lines[end..].join("\n"));
+                updated.push('\n');
+            }
+            updated
+        }
+        None => format!("{}\n\n{}", content.trim_end(), section),
+    };
+
+    let updated = if crlf { updated.replace('\n', "\r\n") } else { updated };
+    if updated == original {
+        return Ok(false);
+    }
+    fs::write(tasks_path, updated)?;
+    Ok(true)
+}
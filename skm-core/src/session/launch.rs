@@ -0,0 +1,92 @@
+use std::collections::BTreeSet;
+use std::path::Path;
+use crate::{Agent, AgentLaunch, Capability, NextAction};
+use crate::exec::wrap_in_environment;
+
+/// Build launch strings for a next action, ordered by `agent_priority`
+///
+/// Only slash commands (`/speckit.plan`) can be handed to an agent; other
+/// next actions get no launch strings. Agents missing from the priority
+/// list come last in their default order. The `claude` invocation runs in
+/// the project's Nix environment when it has one.
+pub fn launch_targets(
+    next: &NextAction,
+    project_path: &Path,
+    capabilities: &BTreeSet<Capability>,
+    agent_priority: &[String],
+) -> Vec<AgentLaunch> {
+    if !next.command.starts_with('/') {
+        return Vec::new();
+    }
+
+    let path = project_path.canonicalize().unwrap_or_else(|_| project_path.to_path_buf());
+    let path = path.to_string_lossy();
+
+    let mut agents = Agent::ALL.to_vec();
+    agents.sort_by_key(|agent| {
+        agent_priority.iter()
+            .position(|name| name.eq_ignore_ascii_case(agent.as_str()))
+            .unwrap_or(usize::MAX)
+    });
+
+    agents.into_iter()
+        .map(|agent| AgentLaunch {
+            agent,
+            target: launch_target(agent, &next.command, &path, capabilities),
+        })
+        .collect()
+}
+
+fn launch_target(agent: Agent, command: &str, path: &str, capabilities: &BTreeSet<Capability>) -> String {
+    match agent {
+        // Runs the slash command as the initial prompt of an interactive session
+        Agent::ClaudeCode => {
+            let claude = format!("claude {}", shell_quote(command));
+            format!("cd {} && {}", shell_quote(path), wrap_in_environment(capabilities, &claude))
+        }
+        // Opens the Cursor chat pre-filled with the command
+        Agent::Cursor => format!("cursor://anysphere.cursor-deeplink/prompt?text={}", percent_encode(command)),
+        // Opens the project folder; VS Code has no URI for starting a chat prompt
+        Agent::VsCode => format!("vscode://file{}{}", if path.starts_with('/') { "" } else { "/" }, percent_encode_path(path)),
+    }
+}
+
+/// Single-quote a string for POSIX shells
+#[doc(hidden)]
+pub fn shell_quote(s: &str) -> String {
+    if !s.is_empty() && s.chars().all(|c| c.is_ascii_alphanumeric() || "/._-".contains(c)) {
+        s.to_string()
+    } else {
+        format!("'{}'", s.replace('\'', r"'\''"))
+    }
+}
+
+fn percent_encode(s: &str) -> String {
+    encode(s.as_bytes(), |c| c.is_ascii_alphanumeric() || b"-_.~".contains(&c))
+}
+
+/// Like [`percent_encode`] but keeps path separators
+fn percent_encode_path(s: &str) -> String {
+    encode(s.as_bytes(), |c| c.is_ascii_alphanumeric() || b"-_.~/".contains(&c))
+}
+
+/// `file://` URL of a path, with every byte of it that is not valid UTF-8
+/// or not safe in a URL percent-encoded
+#[doc(hidden)]
+pub fn file_url(path: &Path) -> String {
+    let path = path.as_os_str().as_encoded_bytes();
+    let encoded = encode(path, |c| c.is_ascii_alphanumeric() || b"-_.~/".contains(&c));
+    format!("file://{}{}", if path.starts_with(b"/") { "" } else { "/" }, encoded)
+}
+
+fn encode(bytes: &[u8], keep: impl Fn(u8) -> bool) -> String {
+    let mut out = String::with_capacity(bytes.len());
+    for &byte in bytes {
+        if keep(byte) {
+            out.push(byte as char);
+        } else {
+            out.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    out
+}
@@ -0,0 +1,90 @@
+use std::collections::BTreeMap;
+use crate::Project;
+use crate::meta::config::GlobalConfig;
+
+/// Values for the `{{name}}` variables in spec artifacts
+///
+/// Shared boilerplate can say `{{project.name}}` or `{{portfolio.deadline}}`
+/// and stay accurate: the files keep the variables, and scans, reports,
+/// release notes and search chunks show the values.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Variables {
+    values: BTreeMap<String, String>,
+}
+
+impl Variables {
+    /// Variables of one project under its effective config
+    ///
+    /// - `project.id` and `project.path`
+    /// - `project.name`: the `name` custom field, or the id
+    /// - `project.<field>`: any other custom field from `.skm/meta.json`
+    /// - `portfolio.<name>`: each entry of the config's `[variables]`
+    pub fn for_project(project: &Project, config: &GlobalConfig) -> Self {
+        let mut variables = Variables::default();
+        for (name, value) in &config.variables {
+            variables.insert(format!("portfolio.{}", name), value.clone());
+        }
+        for (field, value) in &project.custom {
+            let text = match value {
+                serde_json::Value::String(s) => s.clone(),
+                serde_json::Value::Array(items) => items.iter()
+                    .map(|item| item.as_str().map_or_else(|| item.to_string(), str::to_string))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                other => other.to_string(),
+            };
+            variables.insert(format!("project.{}", field), text);
+        }
+        if !project.custom.contains_key("name") {
+            variables.insert("project.name".to_string(), project.id.clone());
+        }
+        variables.insert("project.id".to_string(), project.id.clone());
+        variables.insert("project.path".to_string(), project.path.display().to_string());
+        variables
+    }
+
+    pub fn insert(&mut self, name: String, value: String) {
+        self.values.insert(name, value);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.values.get(name).map(String::as_str)
+    }
+
+    /// `text` with every known `{{name}}` (or `{{ name }}`) replaced by its value
+    ///
+    /// Unknown variables are left as they are, so a typo stays visible
+    /// instead of turning into an empty string.
+    pub fn resolve(&self, text: &str) -> String {
+        let mut out = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(open) = rest.find("{{") {
+            let Some(close) = rest[open + 2..].find("}}") else { break };
+            let inner = &rest[open + 2..open + 2 + close];
+            out.push_str(&rest[..open]);
+            match self.get(inner.trim()) {
+                Some(value) => out.push_str(value),
+                None => out.push_str(&rest[open..open + close + 4]),
+            }
+            rest = &rest[open + close + 4..];
+        }
+        out.push_str(rest);
+        out
+    }
+
+    /// Resolve each of `texts` in place
+    pub fn resolve_all<'a>(&self, texts: impl IntoIterator<Item = &'a mut String>) {
+        for text in texts {
+            if text.contains("{{") {
+                *text = self.resolve(text);
+            }
+        }
+    }
+
+    /// Resolve the text a scan took from the project's artifacts: its
+    /// principles and open questions
+    pub fn resolve_project(&self, project: &mut Project) {
+        self.resolve_all(project.principles.iter_mut().map(|p| &mut p.name));
+        self.resolve_all(project.open_questions.iter_mut().map(|q| &mut q.text));
+    }
+}
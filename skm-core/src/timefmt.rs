@@ -0,0 +1,161 @@
+use std::fmt;
+use std::str::FromStr;
+use std::sync::OnceLock;
+use chrono::{DateTime, FixedOffset, Local, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use crate::SKMError;
+
+/// Time zone timestamps are shown in; stored data and JSON always stay UTC
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(try_from = "String", into = "String")]
+pub enum DisplayTimeZone {
+    #[default]
+    Utc,
+    /// The system time zone, which honors the `TZ` environment variable
+    Local,
+    /// A fixed offset such as `+02:00`
+    Fixed(FixedOffset),
+}
+
+impl FromStr for DisplayTimeZone {
+    type Err = SKMError;
+
+    fn from_str(s: &str) -> crate::Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "utc" | "z" => Ok(DisplayTimeZone::Utc),
+            "local" => Ok(DisplayTimeZone::Local),
+            offset => offset.parse::<FixedOffset>()
+                .map(DisplayTimeZone::Fixed)
+                .map_err(|_| SKMError::ConfigError {
+                    message: format!("Unknown time zone: {} (expected utc, local or an offset like +02:00)", s),
+                }),
+        }
+    }
+}
+
+impl fmt::Display for DisplayTimeZone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DisplayTimeZone::Utc => f.write_str("utc"),
+            DisplayTimeZone::Local => f.write_str("local"),
+            DisplayTimeZone::Fixed(offset) => write!(f, "{}", offset),
+        }
+    }
+}
+
+impl TryFrom<String> for DisplayTimeZone {
+    type Error = SKMError;
+
+    fn try_from(s: String) -> crate::Result<Self> {
+        s.parse()
+    }
+}
+
+impl From<DisplayTimeZone> for String {
+    fn from(zone: DisplayTimeZone) -> Self {
+        zone.to_string()
+    }
+}
+
+impl DisplayTimeZone {
+    /// Render `at` in this zone with a strftime pattern, followed by the zone
+    /// (`UTC`, or the offset such as `+02:00`)
+    pub fn format(&self, at: DateTime<Utc>, pattern: &str) -> String {
+        match self {
+            DisplayTimeZone::Utc => format!("{} UTC", at.format(pattern)),
+            DisplayTimeZone::Local => at.with_timezone(&Local).format(&format!("{} %:z", pattern)).to_string(),
+            DisplayTimeZone::Fixed(offset) => at.with_timezone(offset).format(&format!("{} %:z", pattern)).to_string(),
+        }
+    }
+
+    /// `at` in this zone with a strftime pattern and no zone suffix
+    pub fn format_bare(&self, at: DateTime<Utc>, pattern: &str) -> String {
+        match self {
+            DisplayTimeZone::Utc => at.format(pattern).to_string(),
+            DisplayTimeZone::Local => at.with_timezone(&Local).format(pattern).to_string(),
+            DisplayTimeZone::Fixed(offset) => at.with_timezone(offset).format(pattern).to_string(),
+        }
+    }
+
+    /// Calendar date of `at` in this zone
+    pub fn date(&self, at: DateTime<Utc>) -> NaiveDate {
+        match self {
+            DisplayTimeZone::Utc => at.date_naive(),
+            DisplayTimeZone::Local => at.with_timezone(&Local).date_naive(),
+            DisplayTimeZone::Fixed(offset) => at.with_timezone(offset).date_naive(),
+        }
+    }
+}
+
+static DISPLAY_ZONE: OnceLock<DisplayTimeZone> = OnceLock::new();
+
+/// Set the zone all human-readable output uses; only the first call has an effect
+pub fn set_display_zone(zone: DisplayTimeZone) {
+    let _ = DISPLAY_ZONE.set(zone);
+}
+
+/// Zone for human-readable output, UTC unless set at startup
+pub fn display_zone() -> DisplayTimeZone {
+    DISPLAY_ZONE.get().copied().unwrap_or_default()
+}
+
+/// `2025-01-06 14:30 UTC`, or `2025-01-06 15:30 +01:00` in another zone
+pub fn minutes(at: DateTime<Utc>) -> String {
+    display_zone().format(at, "%Y-%m-%d %H:%M")
+}
+
+/// Like [`minutes`], with seconds
+pub fn seconds(at: DateTime<Utc>) -> String {
+    display_zone().format(at, "%Y-%m-%d %H:%M:%S")
+}
+
+fn plural(count: i64, unit: &str) -> String {
+    if count == 1 {
+        format!("1 {}", unit)
+    } else {
+        format!("{} {}s", count, unit)
+    }
+}
+
+/// Rough length of a duration in its largest sensible unit: `3 days`, `2 weeks`
+///
+/// Counts are rounded down, so 13 days is still `13 days` and 20 days is
+/// `2 weeks`. Days switch to weeks at 14 and weeks to months at 60 days;
+/// months are 30 days and years 365. Negative durations are measured by
+/// their length.
+pub fn humanize_duration(duration: chrono::Duration) -> String {
+    let seconds = duration.num_seconds().abs();
+    let (minutes, hours, days) = (seconds / 60, seconds / 3600, seconds / 86_400);
+    if seconds < 60 {
+        "less than a minute".to_string()
+    } else if minutes < 60 {
+        plural(minutes, "minute")
+    } else if hours < 24 {
+        plural(hours, "hour")
+    } else if days < 14 {
+        plural(days, "day")
+    } else if days < 60 {
+        plural(days / 7, "week")
+    } else if days < 365 {
+        plural(days / 30, "month")
+    } else {
+        plural(days / 365, "year")
+    }
+}
+
+/// `3 days ago`, `in 2 hours` or `just now`, for `at` seen from `now`
+pub fn relative_to(at: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let elapsed = now - at;
+    if elapsed.num_seconds().abs() < 60 {
+        "just now".to_string()
+    } else if elapsed.num_seconds() > 0 {
+        format!("{} ago", humanize_duration(elapsed))
+    } else {
+        format!("in {}", humanize_duration(elapsed))
+    }
+}
+
+/// [`relative_to`] the current time
+pub fn ago(at: DateTime<Utc>) -> String {
+    relative_to(at, Utc::now())
+}
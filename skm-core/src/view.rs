@@ -0,0 +1,167 @@
+use std::sync::Arc;
+use serde::{Serialize, Serializer, ser::SerializeStruct};
+use crate::{PortfolioStatus, Project, SKMError};
+
+/// A portfolio shared between layers (reporters, daemon handlers, TUI) without copying
+pub type SharedPortfolio = Arc<PortfolioStatus>;
+
+/// Borrowed, filterable view over a `PortfolioStatus`
+///
+/// Filtering and sorting only rearrange references, so a long-running
+/// service can answer many queries against one loaded portfolio without
+/// cloning any `Project`. Serializes with the same shape as
+/// `PortfolioStatus`, containing only the selected projects.
+#[derive(Debug, Clone)]
+pub struct PortfolioView<'a> {
+    pub status: &'a PortfolioStatus,
+    projects: Vec<&'a Project>,
+}
+
+impl<'a> PortfolioView<'a> {
+    /// View over all projects in their stored order
+    pub fn new(status: &'a PortfolioStatus) -> Self {
+        Self {
+            status,
+            projects: status.projects.iter().collect(),
+        }
+    }
+
+    /// Keep only projects matching the predicate
+    pub fn filter(mut self, mut predicate: impl FnMut(&Project) -> bool) -> Self {
+        self.projects.retain(|p| predicate(p));
+        self
+    }
+
+    /// Keep only projects matching every `--where` condition
+    pub fn filter_where(self, conditions: &[FieldFilter]) -> Self {
+        self.filter(|p| conditions.iter().all(|c| c.matches(p)))
+    }
+
+    /// Order projects by priority, highest first (see [`Project::priority_order`])
+    pub fn sorted_by_priority(mut self) -> Self {
+        self.projects.sort_by(|a, b| a.priority_order(b));
+        self
+    }
+
+    /// Keep at most `n` projects
+    pub fn take(mut self, n: usize) -> Self {
+        self.projects.truncate(n);
+        self
+    }
+
+    pub fn projects(&self) -> &[&'a Project] {
+        &self.projects
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &'a Project> + '_ {
+        self.projects.iter().copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.projects.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.projects.is_empty()
+    }
+
+    /// Look up a project by id within the view
+    pub fn get(&self, id: &str) -> Option<&'a Project> {
+        self.iter().find(|p| p.id == id)
+    }
+
+    /// Materialize the view into an owned portfolio (clones the selected projects)
+    pub fn to_owned_status(&self) -> PortfolioStatus {
+        PortfolioStatus {
+            generated_at: self.status.generated_at,
+            scan_stats: self.status.scan_stats.clone(),
+            projects: self.projects.iter().map(|p| (*p).clone()).collect(),
+            summary: self.status.summary.clone(),
+        }
+    }
+}
+
+impl<'a> From<&'a PortfolioStatus> for PortfolioView<'a> {
+    fn from(status: &'a PortfolioStatus) -> Self {
+        Self::new(status)
+    }
+}
+
+impl Serialize for PortfolioView<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("PortfolioStatus", 4)?;
+        state.serialize_field("generated_at", &self.status.generated_at)?;
+        state.serialize_field("scan_stats", &self.status.scan_stats)?;
+        state.serialize_field("projects", &self.projects)?;
+        state.serialize_field("summary", &self.status.summary)?;
+        state.end()
+    }
+}
+
+/// A `<field>=<value>` or `<field>:<value>` condition, as given to `--where` and `--filter`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldFilter {
+    pub field: FilterField,
+    pub value: String,
+}
+
+/// Project attribute a condition tests
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FilterField {
+    /// Project id, or `root/id`
+    Id,
+    Stage,
+    Root,
+    /// One of the project's `custom.tags`
+    Tag,
+    Custom(String),
+}
+
+impl FieldFilter {
+    /// Custom values other than strings are compared as JSON, so `custom.tier=1`
+    /// matches the number 1 and `custom.env=prod` matches the string "prod"
+    pub fn matches(&self, project: &Project) -> bool {
+        match &self.field {
+            FilterField::Id => project.id == self.value || project.display_name() == self.value,
+            FilterField::Stage => project.stage.as_str().eq_ignore_ascii_case(&self.value),
+            FilterField::Root => project.root.as_deref() == Some(self.value.as_str()),
+            // A list of tags, or one string of comma-separated tags
+            FilterField::Tag => match project.custom.get("tags") {
+                Some(serde_json::Value::Array(tags)) => tags.iter().any(|tag| tag.as_str() == Some(self.value.as_str())),
+                Some(serde_json::Value::String(tags)) => tags.split(',').any(|tag| tag.trim() == self.value),
+                _ => false,
+            },
+            FilterField::Custom(field) => match project.custom.get(field) {
+                Some(serde_json::Value::String(s)) => *s == self.value,
+                Some(other) => serde_json::from_str::<serde_json::Value>(&self.value).is_ok_and(|v| v == *other),
+                None => false,
+            },
+        }
+    }
+}
+
+impl std::str::FromStr for FieldFilter {
+    type Err = SKMError;
+
+    fn from_str(s: &str) -> crate::Result<Self> {
+        let invalid = || SKMError::ConfigError {
+            message: format!("Invalid condition: {} (expected id, stage, root, tag or custom.<field>, then =<value> or :<value>)", s),
+        };
+        let split = s.find(['=', ':']).ok_or_else(invalid)?;
+        let (field, value) = (&s[..split], &s[split + 1..]);
+        let field = match field.trim() {
+            "id" => FilterField::Id,
+            "stage" => FilterField::Stage,
+            "root" => FilterField::Root,
+            "tag" => FilterField::Tag,
+            other => match other.strip_prefix("custom.") {
+                Some(name) if !name.is_empty() => FilterField::Custom(name.to_string()),
+                _ => return Err(invalid()),
+            },
+        };
+        Ok(Self {
+            field,
+            value: value.trim().to_string(),
+        })
+    }
+}
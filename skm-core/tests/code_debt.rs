@@ -0,0 +1,79 @@
+//! `[code_debt] sync_tasks` keeps the `## Code debt` section of tasks.md in step with the code
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use skm_core::scanner::todos::{CodeTodo, sync_tasks_section};
+
+fn todo(path: &str, line: usize, text: &str) -> CodeTodo {
+    CodeTodo { path: PathBuf::from(path), line, marker: "TODO".to_string(), text: text.to_string() }
+}
+
+/// A tasks.md with the given content in a fresh directory
+fn tasks_file(name: &str, content: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("skm-code-debt-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("tasks.md");
+    fs::write(&path, content).unwrap();
+    path
+}
+
+fn cleanup(path: &Path) {
+    fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
+
+#[test]
+fn the_section_is_appended_when_missing() {
+    let path = tasks_file("missing", "# Tasks\n\n- [x] T001 Set up\n");
+    assert!(sync_tasks_section(&path, &[todo("src/lib.rs", 12, "handle errors")]).unwrap());
+    let content = fs::read_to_string(&path).unwrap();
+    assert!(content.starts_with("# Tasks\n\n- [x] T001 Set up\n\n## Code debt\n\n<!--"), "{}", content);
+    assert!(content.ends_with("\n- [ ] src/lib.rs:12 TODO: handle errors\n"), "{}", content);
+    cleanup(&path);
+}
+
+#[test]
+fn an_existing_section_is_replaced_up_to_the_next_heading() {
+    let path = tasks_file("existing", "\
+# Tasks
+
+## Code debt
+
+- [ ] src/old.rs:1 TODO: gone
+
+## Phase 2
+
+- [ ] T002 Ship
+");
+    assert!(sync_tasks_section(&path, &[todo("src/new.rs", 3, "")]).unwrap());
+    let content = fs::read_to_string(&path).unwrap();
+    assert!(!content.contains("src/old.rs"), "{}", content);
+    assert!(content.contains("- [ ] src/new.rs:3 TODO\n\n## Phase 2\n\n- [ ] T002 Ship\n"), "{}", content);
+    assert_eq!(content.matches("## Code debt").count(), 1);
+    cleanup(&path);
+}
+
+#[test]
+fn syncing_twice_writes_once() {
+    let todos = [todo("src/lib.rs", 12, "handle errors"), todo("src/main.rs", 4, "flags")];
+    for (name, content) in [("idempotent-end", "# Tasks\n\n- [ ] T001 Build\n"), ("idempotent-middle", "# Tasks\n\n## Code debt\n\n## Later\n- [ ] T009\n")] {
+        let path = tasks_file(name, content);
+        assert!(sync_tasks_section(&path, &todos).unwrap());
+        let synced = fs::read_to_string(&path).unwrap();
+        assert!(!sync_tasks_section(&path, &todos).unwrap(), "{}", synced);
+        assert_eq!(fs::read_to_string(&path).unwrap(), synced);
+        cleanup(&path);
+    }
+}
+
+#[test]
+fn crlf_files_keep_their_line_endings() {
+    let path = tasks_file("crlf", "# Tasks\r\n\r\n## Code debt\r\n\r\n- [ ] src/old.rs:1 TODO\r\n\r\n## Phase 2\r\n- [ ] T002 Ship\r\n");
+    let todos = [todo("src/lib.rs", 12, "handle errors")];
+    assert!(sync_tasks_section(&path, &todos).unwrap());
+    let content = fs::read_to_string(&path).unwrap();
+    assert!(!content.replace("\r\n", "").contains('\n'), "only CRLF endings: {:?}", content);
+    assert!(content.contains("- [ ] src/lib.rs:12 TODO: handle errors\r\n\r\n## Phase 2\r\n- [ ] T002 Ship\r\n"), "{:?}", content);
+    assert!(!sync_tasks_section(&path, &todos).unwrap(), "an unchanged CRLF file is not rewritten");
+    cleanup(&path);
+}
@@ -0,0 +1,71 @@
+//! Dependency freshness: lookups that failed are retried, the rest come from the cache
+
+use std::cell::RefCell;
+use std::fs;
+use std::path::PathBuf;
+use skm_core::analyzer::freshness::{Registry, check_freshness_with, major_behind};
+
+/// A project whose requirements.txt pins the given packages
+fn project(name: &str, pins: &[(&str, &str)]) -> PathBuf {
+    let root = std::env::temp_dir().join(format!("skm-freshness-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&root).unwrap();
+    let requirements: String = pins.iter().map(|(name, version)| format!("{}=={}\n", name, version)).collect();
+    fs::write(root.join("requirements.txt"), requirements).unwrap();
+    root
+}
+
+#[test]
+fn major_versions_and_pre_1_0_minors_are_behind() {
+    assert!(major_behind("1.4.0", "2.0.0"));
+    assert!(major_behind("0.3.1", "0.4.0"));
+    assert!(!major_behind("1.4.0", "1.9.0"));
+    assert!(!major_behind("2.0.0", "2.0.0"));
+}
+
+#[test]
+fn failed_lookups_are_named_and_only_they_are_retried() {
+    let root = project("partial", &[("requests", "1.0.0"), ("flask", "2.0.0"), ("private-pkg", "1.0.0")]);
+    let queried = RefCell::new(Vec::new());
+    let offline_private = |registry: Registry, name: &str| {
+        assert_eq!(registry, Registry::PyPi);
+        queried.borrow_mut().push(name.to_string());
+        match name {
+            "requests" => Some("2.31.0".to_string()),
+            "flask" => Some("2.3.0".to_string()),
+            _ => None,
+        }
+    };
+
+    let first = check_freshness_with(&root, None, 7, offline_private).unwrap();
+    assert_eq!((first.direct, first.unchecked, first.major_behind), (3, 1, 1));
+    assert_eq!(first.failed, ["private-pkg"]);
+
+    // The next scan asks the registry only about the lookup that failed
+    queried.borrow_mut().clear();
+    let second = check_freshness_with(&root, Some(&first), 7, |_, name| {
+        queried.borrow_mut().push(name.to_string());
+        Some("3.0.0".to_string())
+    }).unwrap();
+    assert_eq!(*queried.borrow(), ["private-pkg"]);
+    assert_eq!((second.unchecked, second.major_behind), (0, 2));
+    assert!(second.failed.is_empty());
+    assert_eq!(second.at, first.at, "the check keeps its age");
+
+    // A complete result is reused without any lookup
+    let third = check_freshness_with(&root, Some(&second), 7, |_, name| panic!("looked up {}", name)).unwrap();
+    assert_eq!(third, second);
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn partial_results_cached_without_names_are_checked_again() {
+    let root = project("legacy", &[("requests", "1.0.0"), ("flask", "2.0.0")]);
+    let mut cached = check_freshness_with(&root, None, 7, |_, _| Some("2.0.0".to_string())).unwrap();
+    // As written before failed lookups were recorded by name
+    cached.unchecked = 1;
+    let checked = check_freshness_with(&root, Some(&cached), 7, |_, _| Some("2.0.0".to_string())).unwrap();
+    assert_eq!((checked.unchecked, checked.major_behind), (0, 1));
+    assert!(checked.at > cached.at);
+    fs::remove_dir_all(&root).unwrap();
+}
@@ -0,0 +1,97 @@
+//! Source code beyond a scaffold is what moves a project with tasks into implement
+
+use std::fs;
+use std::path::PathBuf;
+use skm_core::analyzer::stage::has_implementation_artifacts;
+use skm_core::ProjectType;
+
+/// A temporary project holding the given files
+fn project(name: &str, files: &[(&str, String)]) -> PathBuf {
+    let root = std::env::temp_dir().join(format!("skm-implementation-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    for (path, content) in files {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+    root
+}
+
+/// `n` non-blank lines of code
+fn lines(n: usize, line: &str) -> String {
+    (0..n).map(|i| format!("{} // {}\n\n", line, i)).collect()
+}
+
+#[test]
+fn a_cargo_new_scaffold_is_not_an_implementation() {
+    let root = project("cargo-new", &[
+        ("Cargo.toml", "[package]\nname = \"app\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[dependencies]\n".to_string()),
+        ("src/main.rs", "fn main() {\n    println!(\"Hello, world!\");\n}\n".to_string()),
+    ]);
+    assert!(!has_implementation_artifacts(&root, &ProjectType::Rust));
+
+    fs::write(root.join("src/lib.rs"), lines(20, "pub fn f() {}")).unwrap();
+    assert!(has_implementation_artifacts(&root, &ProjectType::Rust));
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn a_node_repo_with_only_a_root_index_js_is_implemented() {
+    let root = project("node-index", &[
+        ("package.json", "{ \"name\": \"app\", \"main\": \"index.js\" }\n".to_string()),
+        ("index.js", lines(20, "const x = require('x');")),
+    ]);
+    assert!(has_implementation_artifacts(&root, &ProjectType::Node));
+    fs::remove_dir_all(&root).unwrap();
+
+    // Tool configs in the root are not code
+    let root = project("node-config", &[
+        ("package.json", "{ \"name\": \"app\" }\n".to_string()),
+        ("webpack.config.js", lines(20, "module.exports = {};")),
+        (".eslintrc.js", lines(20, "module.exports = {};")),
+    ]);
+    assert!(!has_implementation_artifacts(&root, &ProjectType::Node));
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn a_python_package_is_implemented() {
+    let root = project("python-package", &[
+        ("pyproject.toml", "[project]\nname = \"app\"\n".to_string()),
+        ("app/__init__.py", String::new()),
+        ("app/core.py", lines(20, "x = 1")),
+    ]);
+    assert!(has_implementation_artifacts(&root, &ProjectType::Python));
+    fs::remove_dir_all(&root).unwrap();
+
+    let root = project("python-setup", &[("setup.py", lines(20, "setup()"))]);
+    assert!(!has_implementation_artifacts(&root, &ProjectType::Python));
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn a_test_only_tree_is_not_an_implementation() {
+    let root = project("tests-only", &[
+        ("Cargo.toml", "[package]\nname = \"app\"\n".to_string()),
+        ("src/main.rs", "fn main() {}\n".to_string()),
+        ("tests/api.rs", lines(40, "#[test] fn t() {}")),
+        ("benches/speed.rs", lines(40, "fn b() {}")),
+    ]);
+    assert!(!has_implementation_artifacts(&root, &ProjectType::Rust));
+    fs::remove_dir_all(&root).unwrap();
+
+    let root = project("tests-only-node", &[
+        ("package.json", "{ \"name\": \"app\" }\n".to_string()),
+        ("__tests__/app.js", lines(40, "test('x', () => {});")),
+        ("src/app.test.ts", lines(40, "test('x', () => {});")),
+    ]);
+    assert!(!has_implementation_artifacts(&root, &ProjectType::Node));
+    fs::remove_dir_all(&root).unwrap();
+
+    let root = project("tests-only-python", &[
+        ("test_app.py", lines(40, "def test_x(): pass")),
+        ("app/test_core.py", lines(40, "def test_y(): pass")),
+    ]);
+    assert!(!has_implementation_artifacts(&root, &ProjectType::Python));
+    fs::remove_dir_all(&root).unwrap();
+}
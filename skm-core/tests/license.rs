@@ -0,0 +1,109 @@
+//! License texts are told apart by their own title, not by the licenses they mention
+
+use skm_core::scanner::license::identify_license_text;
+
+const GPL_3: &str = "\
+                    GNU GENERAL PUBLIC LICENSE
+                       Version 3, 29 June 2007
+
+ Copyright (C) 2007 Free Software Foundation, Inc. <https://fsf.org/>
+ Everyone is permitted to copy and distribute verbatim copies
+ of this license document, but changing it is not allowed.
+
+                            Preamble
+
+  The GNU General Public License is a free, copyleft license for
+software and other kinds of works.
+
+  13. Use with the GNU Affero General Public License.
+
+  Notwithstanding any other provision of this License, you have
+permission to link or combine any covered work with a work licensed
+under version 3 of the GNU Affero General Public License into a single
+combined work, and to convey the resulting work.
+";
+
+const AGPL_3: &str = "\
+                    GNU AFFERO GENERAL PUBLIC LICENSE
+                       Version 3, 19 November 2007
+
+ Copyright (C) 2007 Free Software Foundation, Inc. <https://fsf.org/>
+ Everyone is permitted to copy and distribute verbatim copies
+ of this license document, but changing it is not allowed.
+
+                            Preamble
+
+  The GNU Affero General Public License is a free, copyleft license for
+software and other kinds of works, specifically designed to ensure
+cooperation with the community in the case of network server software.
+
+  13. Remote Network Interaction; Use with the GNU General Public License.
+";
+
+const LGPL_3: &str = "\
+                   GNU LESSER GENERAL PUBLIC LICENSE
+                       Version 3, 29 June 2007
+
+ Copyright (C) 2007 Free Software Foundation, Inc. <https://fsf.org/>
+ Everyone is permitted to copy and distribute verbatim copies
+ of this license document, but changing it is not allowed.
+
+
+  This version of the GNU Lesser General Public License incorporates
+the terms and conditions of version 3 of the GNU General Public
+License, supplemented by the additional permissions listed below.
+";
+
+const LGPL_2_1: &str = "\
+                  GNU LESSER GENERAL PUBLIC LICENSE
+                       Version 2.1, February 1999
+
+ Copyright (C) 1991, 1999 Free Software Foundation, Inc.
+ 51 Franklin Street, Fifth Floor, Boston, MA  02110-1301  USA
+ Everyone is permitted to copy and distribute verbatim copies
+ of this license document, but changing it is not allowed.
+
+[This is the first released version of the Lesser GPL.  It also counts
+ as the successor of the GNU Library Public License, version 2, hence
+ the version number 2.1.]
+";
+
+const GPL_2: &str = "\
+                    GNU GENERAL PUBLIC LICENSE
+                       Version 2, June 1991
+
+ Copyright (C) 1989, 1991 Free Software Foundation, Inc.,
+ 51 Franklin Street, Fifth Floor, Boston, MA 02110-1301 USA
+ Everyone is permitted to copy and distribute verbatim copies
+ of this license document, but changing it is not allowed.
+";
+
+const GPL_3_NOTICE: &str = "\
+Copyright (C) 2024 Example Authors
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+";
+
+#[test]
+fn gnu_licenses_are_identified_by_their_title() {
+    for (text, expected) in [
+        (GPL_3, "GPL-3.0"),
+        (AGPL_3, "AGPL-3.0"),
+        (LGPL_3, "LGPL-3.0"),
+        (LGPL_2_1, "LGPL-2.1"),
+        (GPL_2, "GPL-2.0"),
+        (GPL_3_NOTICE, "GPL-3.0"),
+    ] {
+        assert_eq!(identify_license_text(text).as_deref(), Some(expected), "{}", text.lines().find(|l| !l.trim().is_empty()).unwrap());
+    }
+}
+
+#[test]
+fn other_licenses_are_still_identified() {
+    let mit = "MIT License\n\nPermission is hereby granted, free of charge, to any person obtaining a copy\n";
+    assert_eq!(identify_license_text(mit).as_deref(), Some("MIT"));
+    assert_eq!(identify_license_text("All rights reserved.\n"), None);
+}
@@ -0,0 +1,14 @@
+//! Remote project names are confined to the remote cache
+
+use skm_core::scanner::remote::{checkout_path, validate_name};
+
+#[test]
+fn remote_names_are_single_directory_names() {
+    for name in ["api", "my-service", "web.v2"] {
+        assert!(validate_name(name).is_ok(), "{}", name);
+    }
+    for name in ["", ".", "..", "../home", "a/b", "/etc", "api/.."] {
+        assert!(validate_name(name).is_err(), "{:?} should be rejected", name);
+        assert!(checkout_path(name).is_err());
+    }
+}
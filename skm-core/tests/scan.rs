@@ -0,0 +1,101 @@
+//! Scanning through `skm-core` alone, as embedding tools do
+
+use std::fs;
+use skm_core::Stage;
+use skm_core::meta::config::GlobalConfig;
+use skm_core::scanner::finder::{ProjectScanner, find_enclosing_project};
+use skm_core::scanner::pipeline::{self, GitMode, ScanContext, ScanOptions};
+
+#[test]
+fn a_root_scans_without_the_cli() {
+    let root = std::env::temp_dir().join(format!("skm-core-scan-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    let project = root.join("api");
+    fs::create_dir_all(project.join(".specify/memory")).unwrap();
+    fs::create_dir_all(project.join("specs/001-core")).unwrap();
+    fs::write(project.join(".specify/memory/constitution.md"), "# Constitution\n").unwrap();
+    fs::write(project.join("specs/001-core/spec.md"), "# Core\n\n## Requirements\n\n- Users can sign in\n").unwrap();
+
+    let options = ScanOptions { git: GitMode::Skip, ..ScanOptions::default() };
+    let mut ctx = ScanContext::with_config(GlobalConfig::default(), &root, options).unwrap();
+    let paths = ProjectScanner::new(root.clone(), ctx.config.scan_depth).find_projects();
+    ctx.assign_ids(&paths);
+    let projects: Vec<_> = pipeline::process_projects(&paths, &ctx).into_iter().map(|result| result.unwrap().0).collect();
+    assert_eq!(projects.len(), 1);
+    assert_eq!((projects[0].id.as_str(), &projects[0].stage), ("api", &Stage::Clarify));
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn a_portfolio_scan_leaves_no_skm_state() {
+    let root = std::env::temp_dir().join(format!("skm-core-portfolio-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    for id in ["api", "web"] {
+        fs::create_dir_all(root.join(id).join(".specify/memory")).unwrap();
+        fs::write(root.join(id).join(".specify/memory/constitution.md"), "# Constitution\n").unwrap();
+    }
+
+    let options = ScanOptions { git: GitMode::Skip, ..ScanOptions::default() };
+    let mut ctx = ScanContext::with_config(GlobalConfig::default(), &root, options).unwrap();
+    let portfolio = pipeline::scan_portfolio(&root, &mut ctx).unwrap();
+    assert_eq!((portfolio.summary.total_projects, portfolio.scan_stats.projects_found), (2, 2));
+    assert_eq!(ctx.project_id(&root.join("web")), "web");
+    assert!(!root.join(".skm").exists());
+    assert!(["api", "web"].iter().all(|id| !root.join(id).join(".skm").exists()));
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn a_failed_deferred_git_query_leaves_the_project_no_longer_pending() {
+    let root = std::env::temp_dir().join(format!("skm-core-deferred-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(root.join("api/.specify/memory")).unwrap();
+    fs::write(root.join("api/.specify/memory/constitution.md"), "# Constitution\n").unwrap();
+
+    let options = ScanOptions { git: GitMode::Deferred, ..ScanOptions::default() };
+    let mut ctx = ScanContext::with_config(GlobalConfig::default(), &root, options).unwrap();
+    let paths = ProjectScanner::new(root.clone(), ctx.config.scan_depth).find_projects();
+    ctx.assign_ids(&paths);
+    let (mut project, _) = pipeline::process_project(&paths[0], &ctx).unwrap();
+    assert!(project.git.pending);
+
+    fs::create_dir_all(root.join("api/.skm")).unwrap();
+    fs::write(root.join("api/.skm/config.toml"), "scan_depth = \n").unwrap();
+    assert!(pipeline::refresh_git(&mut project, &ctx).is_err());
+    assert!(!project.git.pending);
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn questions_in_the_portfolio_constitution_are_not_the_projects() {
+    let root = std::env::temp_dir().join(format!("skm-core-inherited-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(root.join(".skm")).unwrap();
+    fs::write(root.join(".skm/constitution.md"), "# Constitution\n\n- OPEN: Which license do we use?\n").unwrap();
+    fs::create_dir_all(root.join("api/specs/001-core")).unwrap();
+    fs::write(root.join("api/specs/001-core/spec.md"), "# Core\n\n- Users sign in [NEEDS CLARIFICATION: SSO?]\n").unwrap();
+
+    let options = ScanOptions { git: GitMode::Skip, ..ScanOptions::default() };
+    let mut ctx = ScanContext::with_config(GlobalConfig::default(), &root, options).unwrap();
+    let portfolio = pipeline::scan_portfolio(&root, &mut ctx).unwrap();
+    let project = &portfolio.projects[0];
+    assert!(project.constitution_inherited);
+    let questions: Vec<_> = project.open_questions.iter().map(|q| q.text.as_str()).collect();
+    assert_eq!(questions, ["SSO?"]);
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn the_enclosing_project_is_found_from_its_spec_directories() {
+    let root = std::env::temp_dir().join(format!("skm-core-enclosing-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    for dir in ["app/.specify/memory", "app/specs/001-core", "app/src/deep", "app.specify/notes"] {
+        fs::create_dir_all(root.join(dir)).unwrap();
+    }
+    let app = root.join("app").canonicalize().unwrap();
+    for dir in ["app", "app/.specify/memory", "app/specs/001-core", "app/src/deep"] {
+        assert_eq!(find_enclosing_project(&root.join(dir)).as_deref(), Some(app.as_path()), "from {}", dir);
+    }
+    assert_eq!(find_enclosing_project(&root.join("app.specify/notes")), None);
+    fs::remove_dir_all(&root).unwrap();
+}
@@ -0,0 +1,35 @@
+/*
+ * C interface to skm (libskm_ffi). Every function returning char * hands
+ * the caller a JSON document, {"ok": true, "result": ...} or
+ * {"ok": false, "error": "..."}, that must be released with
+ * skm_v1_string_free() and nothing else. Returned strings are never NULL.
+ *
+ * All symbols are versioned: check skm_v1_abi_version() == SKM_ABI_VERSION
+ * after loading the library.
+ */
+#ifndef SKM_H
+#define SKM_H
+
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define SKM_ABI_VERSION 1
+
+uint32_t skm_v1_abi_version(void);
+/* Scan the projects under root; result is the portfolio as in .skm/status.json. Nothing under .skm is read or written. */
+/* Scan the projects under root; result is the portfolio as in .skm/status.json. Writes nothing. */
+char *skm_v1_scan(const char *root);
+
+/* One project (id or root/id) from the status cache of the last `skm scan` of root. */
+char *skm_v1_project(const char *root, const char *id);
+
+void skm_v1_string_free(char *s);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* SKM_H */
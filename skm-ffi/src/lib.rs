@@ -0,0 +1,123 @@
+//! C ABI over skm's scanner and status cache, for consumers such as a
+//! Python service that would otherwise spawn `skm` for every query
+//!
+//! Every function returns a JSON document as a NUL-terminated string that
+//! the library allocated: `{"ok": true, "result": ...}` or
+//! `{"ok": false, "error": "..."}`. The caller owns it and must release it
+//! with [`skm_v1_string_free`], and with nothing else. Symbols carry the ABI
+//! version, so a later incompatible API can be added as `skm_v2_*` beside
+//! this one. See `include/skm.h`.
+
+use std::ffi::{CStr, CString, c_char};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use serde_json::{json, Value};
+use skm_core::PortfolioStatus;
+use skm_core::meta::state::StatusCache;
+use skm_core::scanner::pipeline::{self, ScanContext, ScanOptions};
+
+/// Version of the `skm_v1_*` functions and their JSON shapes
+pub const ABI_VERSION: u32 = 1;
+
+type Outcome = Result<Value, String>;
+
+/// The ABI version this library implements; check it is 1 before calling
+/// any other `skm_v1_*` function
+#[unsafe(no_mangle)]
+pub extern "C" fn skm_v1_abi_version() -> u32 {
+    ABI_VERSION
+}
+
+/// Scan the projects under `root` and return the portfolio, shaped like
+/// `.skm/status.json`
+///
+/// Like `skm scan --root <root>` with the global config, except that
+/// nothing under `.skm` is read or written: no status cache, history or
+/// fingerprints, so every project is processed. `[code_debt] sync_tasks`
+/// still rewrites the code debt section of tasks.md files, as in any scan.
+///
+/// # Safety
+///
+/// `root` must be null or point to a NUL-terminated string that stays valid
+/// for the duration of the call.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn skm_v1_scan(root: *const c_char) -> *mut c_char {
+    // SAFETY: the caller guarantees `root` per this function's contract
+    let root = unsafe { arg(root, "root") };
+    respond(|| scan(Path::new(&root?)))
+}
+
+/// The project `id` (an id or `root/id` display name) from the status cache
+/// of the last `skm scan` of `root`
+///
+/// # Safety
+///
+/// `root` and `id` must each be null or point to a NUL-terminated string
+/// that stays valid for the duration of the call.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn skm_v1_project(root: *const c_char, id: *const c_char) -> *mut c_char {
+    // SAFETY: the caller guarantees both pointers per this function's contract
+    let (root, id) = unsafe { (arg(root, "root"), arg(id, "id")) };
+    respond(|| project(Path::new(&root?), &id?))
+}
+
+/// Release a string returned by an `skm_v1_*` function; null is ignored
+///
+/// # Safety
+///
+/// `s` must be null or a pointer returned by this library that has not been
+/// freed yet. It must not be used afterwards.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn skm_v1_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        // SAFETY: `s` came from `CString::into_raw` in `respond`
+        drop(unsafe { CString::from_raw(s) });
+    }
+}
+
+/// Copy a borrowed C string argument into an owned string
+///
+/// # Safety
+///
+/// `ptr` must be null or a valid NUL-terminated string.
+unsafe fn arg(ptr: *const c_char, name: &str) -> Result<String, String> {
+    if ptr.is_null() {
+        return Err(format!("{} is null", name));
+    }
+    // SAFETY: non-null and NUL-terminated per the caller's contract
+    let s = unsafe { CStr::from_ptr(ptr) };
+    s.to_str().map(str::to_string).map_err(|_| format!("{} is not valid UTF-8", name))
+}
+
+/// Run a call, turning its outcome (or a panic, which must not unwind into
+/// the caller) into an owned JSON string
+fn respond(call: impl FnOnce() -> Outcome) -> *mut c_char {
+    let outcome = panic::catch_unwind(AssertUnwindSafe(call))
+        .unwrap_or_else(|_| Err("internal error: skm panicked".to_string()));
+    let response = match outcome {
+        Ok(result) => json!({ "ok": true, "result": result }),
+        Err(error) => json!({ "ok": false, "error": error }),
+    };
+    // serde_json escapes control characters, so the text has no interior NUL
+    CString::new(response.to_string()).unwrap_or_default().into_raw()
+}
+
+fn scan(root: &Path) -> Outcome {
+    if !root.is_dir() {
+        return Err(format!("{} is not a directory", root.display()));
+    }
+    let mut ctx = ScanContext::load(root, ScanOptions::default()).map_err(|e| format!("{:#}", e))?;
+    let portfolio = pipeline::scan_portfolio(root, &mut ctx).map_err(|e| format!("{:#}", e))?;
+    serde_json::to_value(&portfolio).map_err(|e| e.to_string())
+}
+
+fn project(root: &Path, id: &str) -> Outcome {
+    let (cache, _) = StatusCache::load_migrated(root)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("No status cache in {}; run `skm scan` first", root.display()))?;
+    let portfolio: PortfolioStatus = serde_json::from_value(cache.data).map_err(|e| e.to_string())?;
+    let project = portfolio.projects.iter()
+        .find(|p| p.id == id || p.display_name() == id)
+        .ok_or_else(|| format!("Project not found: {}", id))?;
+    serde_json::to_value(project).map_err(|e| e.to_string())
+}
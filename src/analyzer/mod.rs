@@ -1,6 +0,0 @@
-pub mod stage;
-pub mod priority;
-pub mod requirements;
-
-pub use stage::*;
-pub use priority::*;
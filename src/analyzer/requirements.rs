@@ -1 +0,0 @@
-// Placeholder for src/analyzer/requirements.rs
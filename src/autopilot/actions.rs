@@ -1 +1,180 @@
-// Placeholder for src/autopilot/actions.rs
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use crate::Result;
+use crate::{AutomationLevel, NextAction, Project};
+use crate::meta::state::ExecProfile;
+use crate::session::launch::shell_quote;
+
+/// Headless agent invocation slash commands are handed to
+pub const HEADLESS_AGENT: &str = "claude -p";
+
+/// Interactive agent for `skm next --exec --tmux` when a project has no `agent_command`
+pub const INTERACTIVE_AGENT: &str = "claude";
+
+/// Command line starting an interactive agent on a slash command
+///
+/// `{command}` in `agent_command` is replaced by the quoted command;
+/// without the placeholder the command is appended as the first prompt.
+pub fn agent_command_line(agent_command: Option<&str>, command: &str) -> String {
+    command_line(agent_command, INTERACTIVE_AGENT, command)
+}
+
+/// Command line running a slash command unattended: the project's
+/// `agent_command` when it has one, else [`HEADLESS_AGENT`]
+pub fn headless_command_line(agent_command: Option<&str>, command: &str) -> String {
+    command_line(agent_command, HEADLESS_AGENT, command)
+}
+
+fn command_line(agent_command: Option<&str>, default: &str, command: &str) -> String {
+    let agent = agent_command.filter(|agent| !agent.trim().is_empty()).unwrap_or(default);
+    if agent.contains("{command}") {
+        agent.replace("{command}", &shell_quote(command))
+    } else {
+        format!("{} {}", agent, shell_quote(command))
+    }
+}
+
+/// What autopilot does with a project's next action
+#[derive(Debug, Clone, PartialEq)]
+pub enum Decision {
+    Run,
+    /// Allowed, but not run because autopilot is in dry-run mode
+    DryRun,
+    Skip(String),
+}
+
+/// Gate a next action on the project's automation level and its stage cap
+///
+/// Only slash commands can be run; other next actions (reviews, manual
+/// test runs) need a person. `level` is the project's effective level,
+/// already capped for its stage. Commands the project auto-approves run
+/// above that level without a person confirming them, but never above
+/// `stage_cap`. Dry-run mode still applies to approved commands.
+pub fn decide(next: &NextAction, level: &AutomationLevel, stage_cap: Option<&AutomationLevel>, approved: bool, dry_run: bool) -> Decision {
+    if !next.command.starts_with('/') {
+        return Decision::Skip("not a runnable command".to_string());
+    }
+    if let Some(cap) = stage_cap && !next.risk_level.permitted_at(cap) {
+        return Decision::Skip(format!("risk {:?} is above the stage cap {:?}", next.risk_level, cap));
+    }
+    if !next.risk_level.permitted_at(level) && !approved {
+        return Decision::Skip(format!("risk {:?} is above automation level {:?}", next.risk_level, level));
+    }
+    if dry_run {
+        return Decision::DryRun;
+    }
+    Decision::Run
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Outcome {
+    Executed,
+    Skipped,
+    Failed,
+}
+
+/// One line of `.skm/autopilot.log`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AutopilotEntry {
+    /// Start of the autopilot run this entry belongs to
+    pub run_at: DateTime<Utc>,
+    pub at: DateTime<Utc>,
+    pub project: String,
+    pub command: String,
+    pub risk_level: AutomationLevel,
+    pub outcome: Outcome,
+    /// Why the action was skipped or failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// Captured output of an executed action
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log: Option<PathBuf>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<u64>,
+}
+
+impl AutopilotEntry {
+    pub fn new(run_at: DateTime<Utc>, project: &Project, outcome: Outcome, reason: Option<String>) -> Self {
+        AutopilotEntry {
+            run_at,
+            at: Utc::now(),
+            project: project.display_name(),
+            command: project.next.command.clone(),
+            risk_level: project.next.risk_level.clone(),
+            outcome,
+            reason,
+            log: None,
+            duration_ms: None,
+        }
+    }
+}
+
+/// Run a project's next action through its `agent_command` or the headless
+/// agent, with output captured to `.skm/logs` under `meta_root`
+pub fn execute(run_at: DateTime<Utc>, project: &Project, agent_command: Option<&str>, profile: &ExecProfile, meta_root: &Path) -> AutopilotEntry {
+    let started = Instant::now();
+    let command_line = headless_command_line(agent_command, &project.next.command);
+    let result = crate::exec::run(&project.path, profile, &project.capabilities, &command_line)
+        .and_then(|output| {
+            let logs = meta_root.join(".skm/logs");
+            fs::create_dir_all(&logs)?;
+            let log = logs.join(format!("{}-autopilot-{}.log", project.id, Utc::now().format("%Y%m%d-%H%M%S")));
+            fs::write(&log, format!("$ {}\n\n{}{}", command_line,
+                String::from_utf8_lossy(&output.stdout), String::from_utf8_lossy(&output.stderr)))?;
+            Ok((output.status, log))
+        });
+
+    let (outcome, reason, log) = match result {
+        Ok((status, log)) if status.success() => (Outcome::Executed, None, Some(log)),
+        Ok((status, log)) => (Outcome::Failed, Some(format!("exited with {}", status)), Some(log)),
+        Err(e) => (Outcome::Failed, Some(e.to_string()), None),
+    };
+    AutopilotEntry {
+        log,
+        duration_ms: Some(started.elapsed().as_millis() as u64),
+        ..AutopilotEntry::new(run_at, project, outcome, reason)
+    }
+}
+
+fn log_path(root: &Path) -> PathBuf {
+    root.join(".skm/autopilot.log")
+}
+
+/// Append entries to `.skm/autopilot.log`, one JSON object per line
+pub fn append_log(root: &Path, entries: &[AutopilotEntry]) -> Result<()> {
+    fs::create_dir_all(root.join(".skm"))?;
+    let mut file = fs::OpenOptions::new().create(true).append(true).open(log_path(root))?;
+    for entry in entries {
+        writeln!(file, "{}", serde_json::to_string(entry)?)?;
+    }
+    Ok(())
+}
+
+/// Every entry of `.skm/autopilot.log`, oldest first; unreadable lines are skipped
+pub fn read_log(root: &Path) -> Vec<AutopilotEntry> {
+    let Ok(content) = fs::read_to_string(log_path(root)) else {
+        return Vec::new();
+    };
+    content.lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+/// Entries of the latest autopilot run
+pub fn last_run(root: &Path) -> Vec<AutopilotEntry> {
+    let entries = read_log(root);
+    let Some(latest) = entries.iter().map(|e| e.run_at).max() else {
+        return Vec::new();
+    };
+    entries.into_iter().filter(|e| e.run_at == latest).collect()
+}
+
+/// Number of entries with an outcome
+pub fn count(entries: &[AutopilotEntry], outcome: Outcome) -> usize {
+    entries.iter().filter(|e| e.outcome == outcome).count()
+}
@@ -1 +1,12 @@
-// Placeholder for src/autopilot/approval.rs
+use crate::meta::state::ProjectMeta;
+
+/// Whether a project pre-approved a command in its `auto_approve` list
+///
+/// Entries match the command with or without its leading `/`
+/// (`/speckit.plan` or `speckit.plan`), ignoring case; `*` approves any.
+pub fn is_auto_approved(meta: Option<&ProjectMeta>, command: &str) -> bool {
+    let command = command.trim_start_matches('/');
+    meta.is_some_and(|m| m.auto_approve.iter().any(|entry| {
+        entry == "*" || entry.trim_start_matches('/').eq_ignore_ascii_case(command)
+    }))
+}
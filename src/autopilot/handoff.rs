@@ -0,0 +1,161 @@
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use chrono::{DateTime, SecondsFormat, Utc};
+use crate::Result;
+use crate::Project;
+use crate::reporter::markdown::format_requirements;
+use super::actions::{AutopilotEntry, Outcome};
+
+/// Starts each note in `.skm/handoff.md`, followed by its time and what stopped
+const MARKER: &str = "<!-- skm-handoff ";
+/// Earlier autopilot actions listed under "Done by autopilot"
+const DONE_LIMIT: usize = 5;
+
+/// Why autopilot left a project to a person
+#[derive(Debug, Clone, PartialEq)]
+pub enum Stop {
+    /// The next action is not a command autopilot can run (a review, a manual test run)
+    NotRunnable,
+    /// The action's risk is above the automation level
+    AboveLevel(String),
+    Failed(String),
+}
+
+impl Stop {
+    fn reason(&self) -> &str {
+        match self {
+            Stop::NotRunnable => "not a runnable command",
+            Stop::AboveLevel(reason) | Stop::Failed(reason) => reason,
+        }
+    }
+}
+
+/// A note in a project's `.skm/handoff.md`
+#[derive(Debug, Clone, PartialEq)]
+pub struct HandoffNote {
+    pub at: DateTime<Utc>,
+    pub command: String,
+    /// The note as written, including its heading
+    pub text: String,
+}
+
+fn handoff_path(project: &Path) -> PathBuf {
+    project.join(".skm/handoff.md")
+}
+
+/// Compose a note on where autopilot stopped and what a person should do
+///
+/// `history` is the autopilot log; the project's actions since its previous
+/// note are listed as done, with their logs.
+pub fn compose(project: &Project, stop: &Stop, history: &[AutopilotEntry], at: DateTime<Utc>) -> HandoffNote {
+    let name = project.display_name();
+    let command = &project.next.command;
+    let since = read(&project.path).last().map(|note| note.at);
+    let done: Vec<&AutopilotEntry> = history.iter()
+        .filter(|e| e.project == name && e.outcome != Outcome::Skipped && since.is_none_or(|since| e.at > since))
+        .collect();
+
+    let mut text = format!("## {} — {} stopped\n", at.format("%Y-%m-%d %H:%M UTC"), command);
+    text.push_str(&format!("{}{} {}: {} -->\n\n", MARKER, at.to_rfc3339_opts(SecondsFormat::Secs, true), command, stop.reason()));
+    text.push_str(&format!("**Why it stopped:** {}\n\n", stop.reason()));
+
+    text.push_str("**Done by autopilot:**\n");
+    if done.is_empty() {
+        text.push_str(if since.is_some() { "- nothing since the last note\n" } else { "- nothing yet\n" });
+    }
+    for entry in done.iter().skip(done.len().saturating_sub(DONE_LIMIT)) {
+        let outcome = match entry.outcome {
+            Outcome::Failed => "failed",
+            _ => "done",
+        };
+        text.push_str(&format!("- {} `{}` {}", entry.at.format("%Y-%m-%d %H:%M"), entry.command, outcome));
+        if let Some(log) = &entry.log {
+            text.push_str(&format!(" — log: {}", log.display()));
+        }
+        text.push('\n');
+    }
+
+    let mut blocked = Vec::new();
+    if project.tasks.blocked > 0 {
+        blocked.push(format!("{} blocked task(s)", project.tasks.blocked));
+    }
+    if !project.requires_human.is_empty() {
+        blocked.push(format!("requires human: {}", format_requirements(&project.requires_human)));
+    }
+    if !project.open_questions.is_empty() {
+        blocked.push(format!("{} open question(s)", project.open_questions.len()));
+    }
+    if let Some(check) = project.last_check.as_ref().filter(|check| !check.success) {
+        blocked.push(format!("build failing: `{}`", check.command));
+    }
+    if !blocked.is_empty() {
+        text.push_str(&format!("\n**Blocked:** {}\n", blocked.join("; ")));
+    }
+
+    let suggestion = match stop {
+        Stop::NotRunnable => format!("{} (`{}`).", project.next.description, command),
+        Stop::AboveLevel(reason) if reason.contains("stage cap") => format!(
+            "Run `{}` yourself: {}. Approvals do not lift the stage cap; to let autopilot run it, raise `stage_automation.{}`.",
+            command, project.next.description, project.stage),
+        Stop::AboveLevel(_) => format!(
+            "Run `{}` yourself: {}. To let autopilot run it, `skm meta set {} auto_approve {}`.",
+            command, project.next.description, name, command),
+        Stop::Failed(_) => format!("Check the log, then rerun with `skm next {} --exec`.", name),
+    };
+    text.push_str(&format!("\n**Suggested:** {}\n", suggestion));
+
+    HandoffNote { at, command: command.clone(), text }
+}
+
+/// Append a note to the project's `.skm/handoff.md`
+///
+/// Returns false without writing when the latest note already stopped at
+/// the same command for the same reason, so repeated runs don't pile up
+/// copies of one note.
+pub fn append(project: &Path, note: &HandoffNote) -> Result<bool> {
+    let key = |text: &str| text.lines()
+        .find_map(|line| line.strip_prefix(MARKER))
+        .and_then(|rest| rest.split_once(' ').map(|(_, key)| key.to_string()));
+    if read(project).last().is_some_and(|last| key(&last.text) == key(&note.text)) {
+        return Ok(false);
+    }
+
+    let path = handoff_path(project);
+    fs::create_dir_all(path.parent().unwrap_or(project))?;
+    let fresh = !path.exists();
+    let mut file = fs::OpenOptions::new().create(true).append(true).open(&path)?;
+    if fresh {
+        writeln!(file, "# Handoff notes\n")?;
+    }
+    writeln!(file, "{}", note.text)?;
+    Ok(true)
+}
+
+/// Notes in a project's `.skm/handoff.md`, oldest first
+pub fn read(project: &Path) -> Vec<HandoffNote> {
+    let Ok(content) = fs::read_to_string(handoff_path(project)) else {
+        return Vec::new();
+    };
+    let mut notes: Vec<HandoffNote> = Vec::new();
+    for line in content.lines() {
+        if line.starts_with("## ") {
+            notes.push(HandoffNote { at: DateTime::<Utc>::MIN_UTC, command: String::new(), text: String::new() });
+        }
+        let Some(note) = notes.last_mut() else { continue };
+        if let Some(rest) = line.strip_prefix(MARKER)
+            && let Some((at, key)) = rest.split_once(' ')
+        {
+            note.at = DateTime::parse_from_rfc3339(at).map_or(note.at, |at| at.with_timezone(&Utc));
+            note.command = key.split(": ").next().unwrap_or_default().to_string();
+        }
+        note.text.push_str(line);
+        note.text.push('\n');
+    }
+    // Headings without a marker are the user's own sections
+    notes.retain(|note| note.at != DateTime::<Utc>::MIN_UTC);
+    for note in &mut notes {
+        note.text = note.text.trim_end().to_string() + "\n";
+    }
+    notes
+}
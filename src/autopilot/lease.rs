@@ -0,0 +1,238 @@
+use std::path::{Path, PathBuf};
+use std::fs;
+use std::io::ErrorKind;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use crate::{Result, SKMError};
+
+/// Lease lifetime unless the holder renews it
+pub const DEFAULT_LEASE_TTL_SECS: i64 = 15 * 60;
+
+/// What a lease guards: a whole portfolio run or a single project
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LeaseScope {
+    Portfolio,
+    Project(String),
+}
+
+impl LeaseScope {
+    /// Name used by `skm locks`: `portfolio` or `project:<id>`
+    pub fn name(&self) -> String {
+        match self {
+            LeaseScope::Portfolio => "portfolio".to_string(),
+            LeaseScope::Project(id) => format!("project:{}", id),
+        }
+    }
+
+    fn file_name(&self) -> String {
+        match self {
+            LeaseScope::Portfolio => "portfolio.json".to_string(),
+            LeaseScope::Project(id) => format!("project-{}.json", id),
+        }
+    }
+}
+
+impl std::str::FromStr for LeaseScope {
+    type Err = SKMError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.split_once(':') {
+            None if s == "portfolio" => Ok(LeaseScope::Portfolio),
+            Some(("project", id)) if !id.is_empty() => Ok(LeaseScope::Project(id.to_string())),
+            _ => Err(SKMError::ConfigError { message: format!("Unknown lock: {} (expected portfolio or project:<id>)", s) }),
+        }
+    }
+}
+
+/// Contents of a lease file in `.skm/locks`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Lease {
+    /// What took the lease, e.g. `autopilot`
+    pub holder: String,
+    pub pid: u32,
+    pub host: String,
+    pub acquired_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl Lease {
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at <= now
+    }
+}
+
+/// A held lease; released when dropped
+///
+/// Release only removes the file while it still holds this lease, so a
+/// holder whose lease expired and was taken over cannot free the new one.
+#[derive(Debug)]
+pub struct LeaseGuard {
+    path: PathBuf,
+    lease: Lease,
+}
+
+impl LeaseGuard {
+    pub fn lease(&self) -> &Lease {
+        &self.lease
+    }
+
+    /// Extend the lease by `ttl` from now
+    pub fn renew(&mut self, ttl: Duration) -> Result<()> {
+        if read_lease(&self.path).as_ref() != Some(&self.lease) {
+            return Err(lock_error(format!("lease {} was taken over", self.path.display())));
+        }
+        self.lease.expires_at = Utc::now() + ttl;
+        // Replaced by a rename so no reader sees a partly written lease
+        let staged = staged_path(&self.path);
+        fs::write(&staged, serde_json::to_string_pretty(&self.lease)?)?;
+        fs::rename(&staged, &self.path)?;
+        Ok(())
+    }
+
+    pub fn release(self) {}
+}
+
+impl Drop for LeaseGuard {
+    fn drop(&mut self) {
+        if read_lease(&self.path).as_ref() == Some(&self.lease) {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+}
+
+/// Take a lease under `root`, replacing an expired one
+///
+/// Fails while another holder's lease is still valid.
+pub fn acquire(root: &Path, scope: &LeaseScope, holder: &str, ttl: Duration) -> Result<LeaseGuard> {
+    let dir = locks_dir(root);
+    fs::create_dir_all(&dir)?;
+    let path = dir.join(scope.file_name());
+
+    let now = Utc::now();
+    let lease = Lease {
+        holder: holder.to_string(),
+        pid: std::process::id(),
+        host: host_name(),
+        acquired_at: now,
+        expires_at: now + ttl,
+    };
+
+    // The lease is written aside and linked into place, so the lease file
+    // never exists without its contents and linking fails while one is held.
+    // A second attempt follows removing an expired or unreadable lease.
+    let staged = staged_path(&path);
+    fs::write(&staged, serde_json::to_string_pretty(&lease)?)?;
+    let result = link_lease(&staged, &path, scope, now);
+    let _ = fs::remove_file(&staged);
+    result?;
+    Ok(LeaseGuard { path, lease })
+}
+
+fn link_lease(staged: &Path, path: &Path, scope: &LeaseScope, now: DateTime<Utc>) -> Result<()> {
+    for _ in 0..2 {
+        match fs::hard_link(staged, path) {
+            Ok(()) => return Ok(()),
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => match read_lease(path) {
+                Some(held) if !held.is_expired(now) => {
+                    return Err(lock_error(format!(
+                        "{} is locked by {} (pid {} on {}) until {}; see `skm locks`",
+                        scope.name(), held.holder, held.pid, held.host,
+                        crate::timefmt::seconds(held.expires_at),
+                    )));
+                }
+                stale => remove_stale(path, stale.as_ref())?,
+            },
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    Err(lock_error(format!("{} is being locked concurrently; try again", scope.name())))
+}
+
+/// Remove the lease file at `path` if it still holds `stale`
+///
+/// The check and the removal happen under a takeover file created with
+/// `create_new`, so two processes replacing the same expired lease cannot
+/// remove each other's new one.
+fn remove_stale(path: &Path, stale: Option<&Lease>) -> Result<()> {
+    let takeover = path.with_extension("takeover");
+    // One left behind by a crash would block the lease for good
+    let abandoned = fs::metadata(&takeover).ok()
+        .and_then(|meta| meta.modified().ok())
+        .and_then(|modified| modified.elapsed().ok())
+        .is_some_and(|age| age > std::time::Duration::from_secs(60));
+    if abandoned {
+        let _ = fs::remove_file(&takeover);
+    }
+    match fs::OpenOptions::new().write(true).create_new(true).open(&takeover) {
+        Ok(_) => {}
+        Err(e) if e.kind() == ErrorKind::AlreadyExists => return Ok(()),
+        Err(e) => return Err(e.into()),
+    }
+    if read_lease(path).as_ref() == stale {
+        let _ = fs::remove_file(path);
+    }
+    fs::remove_file(&takeover)?;
+    Ok(())
+}
+
+fn lock_error(message: String) -> SKMError {
+    SKMError::LockError { message }
+}
+
+/// All lease files under `root`, by name
+///
+/// Unreadable lease files are listed as `None`.
+pub fn list(root: &Path) -> Result<Vec<(String, Option<Lease>)>> {
+    let Ok(entries) = fs::read_dir(locks_dir(root)) else {
+        return Ok(Vec::new());
+    };
+
+    let mut leases: Vec<(String, Option<Lease>)> = entries
+        .filter_map(|e| e.ok())
+        .filter_map(|entry| {
+            let file_name = entry.file_name().to_string_lossy().into_owned();
+            let stem = file_name.strip_suffix(".json")?;
+            let name = match stem.strip_prefix("project-") {
+                Some(id) => LeaseScope::Project(id.to_string()).name(),
+                None => stem.to_string(),
+            };
+            Some((name, read_lease(&entry.path())))
+        })
+        .collect();
+    leases.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(leases)
+}
+
+/// Remove a lease regardless of its holder; returns whether one existed
+pub fn force_release(root: &Path, scope: &LeaseScope) -> Result<bool> {
+    match fs::remove_file(locks_dir(root).join(scope.file_name())) {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(false),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn locks_dir(root: &Path) -> PathBuf {
+    root.join(".skm/locks")
+}
+
+/// A fresh file to write a lease to before moving it to `path`
+fn staged_path(path: &Path) -> PathBuf {
+    static STAGED: AtomicUsize = AtomicUsize::new(0);
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    path.with_file_name(format!(".{}.{}.{}", name, std::process::id(), STAGED.fetch_add(1, Ordering::Relaxed)))
+}
+
+fn read_lease(path: &Path) -> Option<Lease> {
+    serde_json::from_str(&fs::read_to_string(path).ok()?).ok()
+}
+
+fn host_name() -> String {
+    std::env::var("HOSTNAME").ok()
+        .or_else(|| fs::read_to_string("/etc/hostname").ok())
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| "unknown".to_string())
+}
@@ -1 +1,25 @@
-// Placeholder for src/autopilot/levels.rs
+use crate::{AutomationLevel, Stage};
+use crate::meta::config::GlobalConfig;
+use crate::meta::state::ProjectMeta;
+
+/// Level a project's actions are gated at in `stage`
+///
+/// The project's own `automation_level`, else the one in `config`, capped
+/// by the stage's entry in the project's `stage_automation`, else in
+/// `config`'s. Pass the project's effective config
+/// ([`GlobalConfig::for_project`]) so its `.skm/config.toml` applies.
+pub fn effective_level(config: &GlobalConfig, meta: Option<&ProjectMeta>, stage: &Stage) -> AutomationLevel {
+    let level = meta.and_then(|m| m.automation_level.clone())
+        .unwrap_or_else(|| config.automation_level.clone());
+    match stage_cap(config, meta, stage) {
+        Some(cap) => level.min(cap),
+        None => level,
+    }
+}
+
+/// Highest level allowed in `stage`, from the project's matrix or the global one
+pub fn stage_cap(config: &GlobalConfig, meta: Option<&ProjectMeta>, stage: &Stage) -> Option<AutomationLevel> {
+    meta.and_then(|m| m.stage_automation.get(stage))
+        .or_else(|| config.stage_automation.get(stage))
+        .cloned()
+}
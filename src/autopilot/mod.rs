@@ -1,4 +1,8 @@
 pub mod levels;
 pub mod actions;
 pub mod approval;
+pub mod plan;
+pub mod handoff;
 
+pub mod lease;
+pub mod sandbox;
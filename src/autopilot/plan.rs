@@ -0,0 +1,133 @@
+use std::time::Duration;
+use crate::Project;
+use super::actions::{AutopilotEntry, Outcome};
+
+/// Minutes assumed for a command before autopilot has timed a run of it
+const DEFAULT_MINUTES: &[(&str, u64)] = &[
+    ("/speckit.constitution", 3),
+    ("/speckit.specify", 5),
+    ("/speckit.clarify", 4),
+    ("/speckit.plan", 8),
+    ("/speckit.tasks", 4),
+    ("/speckit.analyze", 3),
+    ("/speckit.implement", 30),
+];
+const FALLBACK_MINUTES: u64 = 10;
+/// Tasks one `/speckit.implement` run is assumed to complete
+const TASKS_PER_RUN: u32 = 5;
+/// Most recent executed runs of a command its estimate is averaged from
+const HISTORY_RUNS: usize = 20;
+
+/// Where a duration estimate comes from
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Basis {
+    /// Average of this many timed runs in `.skm/autopilot.log`
+    History(usize),
+    Default,
+}
+
+/// Expected cost and benefit of running a project's next action
+#[derive(Debug, Clone, PartialEq)]
+pub struct Estimate {
+    pub duration: Duration,
+    pub basis: Basis,
+    /// Stages the action is expected to move the project forward; an
+    /// implement run covers a share of the open tasks
+    pub advancement: f64,
+}
+
+impl Estimate {
+    /// Expected progress per minute: advancement weighted by priority
+    pub fn score(&self, priority: f64) -> f64 {
+        let minutes = (self.duration.as_secs_f64() / 60.0).max(0.1);
+        self.advancement * priority.max(1.0) / 100.0 / minutes
+    }
+}
+
+/// Estimate a project's next action from earlier timed runs of the same
+/// command anywhere in the portfolio, or a per-command default
+pub fn estimate(project: &Project, history: &[AutopilotEntry]) -> Estimate {
+    let command = &project.next.command;
+    let timed: Vec<u64> = history.iter()
+        .rev()
+        .filter(|e| e.outcome == Outcome::Executed && &e.command == command)
+        .filter_map(|e| e.duration_ms)
+        .take(HISTORY_RUNS)
+        .collect();
+    let (duration, basis) = if timed.is_empty() {
+        let minutes = DEFAULT_MINUTES.iter()
+            .find(|(name, _)| name == command)
+            .map_or(FALLBACK_MINUTES, |(_, minutes)| *minutes);
+        (Duration::from_secs(minutes * 60), Basis::Default)
+    } else {
+        let mean = timed.iter().sum::<u64>() / timed.len() as u64;
+        (Duration::from_millis(mean), Basis::History(timed.len()))
+    };
+
+    let advancement = if command == "/speckit.implement" {
+        let open = project.tasks.total.saturating_sub(project.tasks.completed);
+        if open <= TASKS_PER_RUN { 1.0 } else { TASKS_PER_RUN as f64 / open as f64 }
+    } else {
+        1.0
+    };
+    Estimate { duration, basis, advancement }
+}
+
+/// A candidate action in the order autopilot will take it
+#[derive(Debug, Clone)]
+pub struct Planned<'a> {
+    pub project: &'a Project,
+    pub estimate: Estimate,
+    pub score: f64,
+    /// Fits in the remaining budget when its turn comes
+    pub within_budget: bool,
+}
+
+impl Planned<'_> {
+    /// Why the action sits where it does, e.g. `~4m (default), +1.0 stage, priority 44.9: 0.112/min`
+    pub fn rationale(&self) -> String {
+        let basis = match self.estimate.basis {
+            Basis::History(runs) => format!("avg of {} run(s)", runs),
+            Basis::Default => "default".to_string(),
+        };
+        format!("~{} ({}), +{:.2} stage, priority {:.1}: {:.3}/min",
+            format_minutes(self.estimate.duration), basis, self.estimate.advancement, self.project.priority, self.score)
+    }
+}
+
+/// Order candidates by expected progress per minute and mark which fit in
+/// the time budget
+///
+/// Greedy: the best-scoring actions are taken first, and an action that
+/// does not fit is passed over for cheaper ones behind it. Without a budget
+/// everything fits. Ties go to the higher-priority project.
+pub fn schedule<'a>(candidates: &[&'a Project], history: &[AutopilotEntry], budget: Option<Duration>) -> Vec<Planned<'a>> {
+    let mut planned: Vec<Planned<'a>> = candidates.iter()
+        .map(|project| {
+            let estimate = estimate(project, history);
+            let score = estimate.score(project.priority);
+            Planned { project, estimate, score, within_budget: true }
+        })
+        .collect();
+    planned.sort_by(|a, b| b.score.total_cmp(&a.score).then_with(|| a.project.priority_order(b.project)));
+
+    if let Some(budget) = budget {
+        let mut used = Duration::ZERO;
+        for entry in &mut planned {
+            entry.within_budget = used + entry.estimate.duration <= budget;
+            if entry.within_budget {
+                used += entry.estimate.duration;
+            }
+        }
+    }
+    planned
+}
+
+fn format_minutes(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    if secs < 60 {
+        format!("{}s", secs)
+    } else {
+        format!("{}m", (secs + 30) / 60)
+    }
+}
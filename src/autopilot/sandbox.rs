@@ -0,0 +1,206 @@
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use crate::{Result, SKMError};
+use walkdir::WalkDir;
+use crate::Project;
+use crate::meta::state::{ProjectMeta, ProjectMetaStore};
+use crate::scanner::finder;
+
+/// A project copied into a sandbox
+#[derive(Debug, Clone)]
+pub struct SandboxCopy {
+    pub original: PathBuf,
+    pub copy: PathBuf,
+    /// The copy was left by an earlier run and kept as it is
+    pub reused: bool,
+    /// Symlinks not copied because they lead outside the project
+    pub skipped: Vec<PathBuf>,
+}
+
+/// Directory name of a project's copy: its display name with `/` replaced,
+/// so projects with the same id under different roots stay apart
+pub fn copy_name(project: &Project) -> String {
+    project.display_name().replace('/', "-")
+}
+
+/// Copy projects into `dir` along with their metadata, keeping copies and
+/// metadata an earlier run left there
+///
+/// Copies are plain directories including their git data, cut off from the
+/// original: gitfiles (worktrees, submodules) are replaced by a copy of the
+/// directory they point at, and every remote is removed, so nothing done in
+/// the sandbox (commits, branches, stashes, pushes) reaches the original
+/// repository or its origin. Symlinks that lead outside the project are not
+/// copied. Build and dependency directories are skipped.
+pub fn prepare(dir: &Path, projects: &[(&Project, Option<ProjectMeta>)]) -> Result<Vec<SandboxCopy>> {
+    fs::create_dir_all(dir)?;
+    let sandbox = dir.canonicalize()?;
+    let mut store = ProjectMetaStore::load(&sandbox)?;
+    let mut copies = Vec::new();
+
+    for (project, meta) in projects {
+        let original = project.path.canonicalize()?;
+        if sandbox.starts_with(&original) || original.starts_with(&sandbox) {
+            return Err(SKMError::ConfigError {
+                message: format!("sandbox {} overlaps project {}", sandbox.display(), original.display()),
+            });
+        }
+        let name = copy_name(project);
+        let copy = sandbox.join(&name);
+        let reused = copy.exists();
+        let mut skipped = Vec::new();
+        if !reused {
+            skipped = copy_tree(&original, &copy)?;
+            for git_dir in git_dirs(&copy) {
+                detach(&git_dir)?;
+            }
+        }
+        if let Some(meta) = meta && store.get_project(&name).is_none() {
+            *store.get_project_mut(&name) = meta.clone();
+        }
+        copies.push(SandboxCopy { original, copy, reused, skipped });
+    }
+
+    store.save(&sandbox)?;
+    Ok(copies)
+}
+
+/// Copy a project tree, returning the symlinks left out because they lead
+/// outside it
+///
+/// Absolute symlinks into the original are pointed at the copy, and a `.git`
+/// file is replaced by the git directory it names.
+fn copy_tree(from: &Path, to: &Path) -> Result<Vec<PathBuf>> {
+    let mut skipped = Vec::new();
+    let walker = WalkDir::new(from).into_iter()
+        .filter_entry(|e| e.depth() == 0 || copies_git(e.path()) || !finder::should_ignore(e.path()));
+    for entry in walker {
+        let entry = entry.map_err(std::io::Error::from)?;
+        let relative = entry.path().strip_prefix(from).map_err(std::io::Error::other)?;
+        let target = to.join(relative);
+        let file_type = entry.file_type();
+        if file_type.is_symlink() {
+            let link = fs::read_link(entry.path())?;
+            let parent = entry.path().parent().unwrap_or(from);
+            if !normalize(&parent.join(&link)).starts_with(from) {
+                skipped.push(relative.to_path_buf());
+                continue;
+            }
+            let link = match link.strip_prefix(from) {
+                Ok(inside) if link.is_absolute() => to.join(inside),
+                _ => link,
+            };
+            symlink(&link, &target)?;
+        } else if file_type.is_dir() {
+            fs::create_dir_all(&target)?;
+        } else if entry.file_name() == ".git" {
+            let git_dir = gitfile_target(entry.path())?;
+            copy_git_dir(&git_dir, &target)?;
+        } else {
+            fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(skipped)
+}
+
+/// Git data is only copied when it can be cut off from its remotes
+fn copies_git(path: &Path) -> bool {
+    cfg!(feature = "git") && path.file_name().is_some_and(|name| name == ".git")
+}
+
+/// Directory a `.git` file (`gitdir: <path>`) points at
+fn gitfile_target(gitfile: &Path) -> Result<PathBuf> {
+    let content = fs::read_to_string(gitfile)?;
+    let dir = content.trim().strip_prefix("gitdir:").map(str::trim).ok_or_else(|| SKMError::ConfigError {
+        message: format!("{} is not a gitfile", gitfile.display()),
+    })?;
+    Ok(gitfile.parent().unwrap_or(Path::new("")).join(dir))
+}
+
+/// Copy a linked git directory into a standalone one
+///
+/// A worktree's git directory only holds its HEAD and index; the objects,
+/// refs and config come from the common directory it names.
+fn copy_git_dir(git_dir: &Path, to: &Path) -> Result<()> {
+    if let Ok(common) = fs::read_to_string(git_dir.join("commondir")) {
+        copy_all(&git_dir.join(common.trim()), to, &["worktrees"])?;
+    }
+    copy_all(git_dir, to, &["commondir", "gitdir"])
+}
+
+/// Copy a directory tree as it is, leaving out the top-level `except` entries
+fn copy_all(from: &Path, to: &Path, except: &[&str]) -> Result<()> {
+    let walker = WalkDir::new(from).into_iter()
+        .filter_entry(|e| e.depth() != 1 || !except.iter().any(|name| e.file_name() == *name));
+    for entry in walker {
+        let entry = entry.map_err(std::io::Error::from)?;
+        let target = to.join(entry.path().strip_prefix(from).map_err(std::io::Error::other)?);
+        if entry.file_type().is_dir() {
+            fs::create_dir_all(&target)?;
+        } else if entry.file_type().is_file() {
+            fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
+/// Git directories in a copy: every `.git` and the submodules kept in them
+fn git_dirs(copy: &Path) -> Vec<PathBuf> {
+    WalkDir::new(copy).into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_dir() && e.file_name() == ".git")
+        .flat_map(|e| {
+            let modules = WalkDir::new(e.path().join("modules")).into_iter()
+                .filter_map(|e| e.ok())
+                .filter(|e| e.file_type().is_dir() && e.path().join("HEAD").is_file())
+                .map(|e| e.into_path());
+            std::iter::once(e.into_path()).chain(modules).collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Remove a copied repository's remotes and any work tree it still names
+#[cfg(feature = "git")]
+fn detach(git_dir: &Path) -> Result<()> {
+    let repo = git2::Repository::open(git_dir)?;
+    for name in repo.remotes()?.iter().flatten() {
+        repo.remote_delete(name)?;
+    }
+    let mut config = repo.config()?.open_level(git2::ConfigLevel::Local)?;
+    if config.get_entry("core.worktree").is_ok() {
+        config.remove("core.worktree")?;
+    }
+    Ok(())
+}
+
+/// Without libgit2 no git data is copied, so there is nothing to detach
+#[cfg(not(feature = "git"))]
+fn detach(_git_dir: &Path) -> Result<()> {
+    Ok(())
+}
+
+/// Resolve `.` and `..` without touching the file system
+fn normalize(path: &Path) -> PathBuf {
+    let mut normal = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normal.pop();
+            }
+            other => normal.push(other),
+        }
+    }
+    normal
+}
+
+#[cfg(unix)]
+fn symlink(link: &Path, at: &Path) -> std::io::Result<()> {
+    std::os::unix::fs::symlink(link, at)
+}
+
+/// Symlinks are not copied where they need special privileges
+#[cfg(not(unix))]
+fn symlink(_link: &Path, _at: &Path) -> std::io::Result<()> {
+    Ok(())
+}
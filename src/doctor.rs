@@ -0,0 +1,314 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use serde::Serialize;
+use crate::SKMError;
+use crate::meta::config::GlobalConfig;
+use crate::meta::config_file;
+
+/// Outcome of one check, from fine to broken
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Ok,
+    /// Off or not configured, which is fine
+    Info,
+    /// Something skm can live without, such as search or sessions
+    Warning,
+    /// Commands will fail until it is fixed
+    Error,
+}
+
+/// One finding of `skm doctor`
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Check {
+    /// Group the check belongs to: config, services, tools, storage, integrations, portfolio or scan time
+    pub area: &'static str,
+    pub name: String,
+    pub status: CheckStatus,
+    pub detail: String,
+    /// What to do about a warning or an error
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fix: Option<String>,
+}
+
+impl Check {
+    fn new(area: &'static str, name: impl Into<String>, status: CheckStatus, detail: impl Into<String>) -> Self {
+        Check { area, name: name.into(), status, detail: detail.into(), fix: None }
+    }
+
+    /// Status symbol, or its label in accessible mode
+    pub fn icon(&self) -> &'static str {
+        use crate::reporter::style::icon;
+        match self.status {
+            CheckStatus::Ok => icon("✅", "[ok]"),
+            CheckStatus::Info => icon("ℹ️ ", "[info]"),
+            CheckStatus::Warning => icon("⚠️ ", "[warning]"),
+            CheckStatus::Error => icon("❌", "[error]"),
+        }
+    }
+
+    fn fix(mut self, fix: impl Into<String>) -> Self {
+        self.fix = Some(fix.into());
+        self
+    }
+}
+
+/// Validate a config file's content: TOML syntax, setting types, custom
+/// stages, unknown keys and values that can never work
+///
+/// Returns the config when it loads, so the other checks can use it.
+pub fn check_config(path: &Path, content: &str) -> (Vec<Check>, Option<GlobalConfig>) {
+    let name = path.display().to_string();
+    let regenerate = "Fix it, or start over with `skm config init --force`";
+    let config = match toml::from_str::<GlobalConfig>(content) {
+        Ok(config) => config,
+        Err(e) => {
+            // Errors about the document as a whole, like a missing setting, span all of it
+            let line = e.span().filter(|span| span.start > 0 || span.end < content.trim_end().len())
+                .map(|span| content[..span.start].matches('\n').count() + 1);
+            let detail = match line {
+                Some(line) => format!("line {}: {}", line, e.message().trim().replace('\n', "; ")),
+                None => e.message().trim().replace('\n', "; "),
+            };
+            return (vec![Check::new("config", name, CheckStatus::Error, detail).fix(regenerate)], None);
+        }
+    };
+
+    let mut checks = Vec::new();
+    match config.workflow.validate() {
+        Ok(()) => checks.push(Check::new("config", name.clone(), CheckStatus::Ok, "valid")),
+        Err(SKMError::ConfigError { message }) => checks.push(Check::new("config", name.clone(), CheckStatus::Error, message).fix(regenerate)),
+        Err(e) => checks.push(Check::new("config", name.clone(), CheckStatus::Error, e.to_string()).fix(regenerate)),
+    }
+    for key in config_file::unknown_keys(content).unwrap_or_default() {
+        checks.push(Check::new("config", key.clone(), CheckStatus::Warning, "unknown setting, ignored")
+            .fix("Check the spelling against `skm config show`, or remove it"));
+    }
+    if !config.qdrant_url.starts_with("http://") && !config.qdrant_url.starts_with("https://") {
+        checks.push(Check::new("config", "qdrant_url", CheckStatus::Error, format!("{} is not an http(s) URL", config.qdrant_url))
+            .fix("skm config set qdrant_url http://localhost:6333"));
+    }
+    for root in &config.roots {
+        let path = root.resolved_path();
+        if !path.is_dir() {
+            checks.push(Check::new("config", format!("roots.{}", root.name), CheckStatus::Warning,
+                format!("{} is not a directory; its projects are missing from scans", path.display()))
+                .fix(format!("Create {} or correct its path in the [[roots]] entry", path.display())));
+        }
+    }
+    (checks, Some(config))
+}
+
+/// Read and validate the user config; a missing file means the defaults
+pub fn check_config_file(path: &Path) -> (Vec<Check>, Option<GlobalConfig>) {
+    match std::fs::read_to_string(path) {
+        Ok(content) => check_config(path, &content),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            let check = Check::new("config", path.display().to_string(), CheckStatus::Info, "not found; using the defaults (`skm config init` writes one)");
+            (vec![check], Some(GlobalConfig::default()))
+        }
+        Err(e) => {
+            let check = Check::new("config", path.display().to_string(), CheckStatus::Error, format!("cannot be read: {}", e))
+                .fix(format!("Check the permissions of {}", path.display()));
+            (vec![check], None)
+        }
+    }
+}
+
+/// Validate a project's `.skm/config.toml` over the user config
+pub fn check_project_config(config: &GlobalConfig, project: &str, path: &Path) -> Option<Check> {
+    match config.for_project(path) {
+        Ok(Some(_)) => Some(Check::new("config", format!("{} .skm/config.toml", project), CheckStatus::Ok, "valid")),
+        Ok(None) => None,
+        Err(SKMError::ConfigError { message }) => Some(Check::new("config", format!("{} .skm/config.toml", project), CheckStatus::Error, message)
+            .fix(format!("Only {} may be set per project", crate::meta::config::PROJECT_SETTINGS.join(", ")))),
+        Err(e) => Some(Check::new("config", format!("{} .skm/config.toml", project), CheckStatus::Error, e.to_string())),
+    }
+}
+
+/// Whether Qdrant answers at `qdrant_url`, and whether the search
+/// collection exists; with Ollama embeddings, the Ollama server too
+#[cfg(feature = "rag")]
+pub fn check_services(config: &GlobalConfig) -> Vec<Check> {
+    use crate::http::http_json;
+    use crate::meta::config::EmbeddingProvider;
+
+    let url = config.qdrant_url.trim_end_matches('/');
+    let mut checks = Vec::new();
+    match http_json("GET", &format!("{}/collections", url), None) {
+        Ok(response) => {
+            let collection = &config.rag.collection;
+            let exists = response.pointer("/result/collections").and_then(|c| c.as_array())
+                .is_some_and(|all| all.iter().any(|c| c.get("name").and_then(|n| n.as_str()) == Some(collection)));
+            checks.push(match exists {
+                true => Check::new("services", "qdrant", CheckStatus::Ok, format!("reachable at {}; collection {} indexed", url, collection)),
+                false => Check::new("services", "qdrant", CheckStatus::Info, format!("reachable at {}; collection {} not created yet", url, collection))
+                    .fix("skm index builds it"),
+            });
+        }
+        Err(e) => checks.push(Check::new("services", "qdrant", CheckStatus::Warning, format!("not reachable at {}: {}", url, e))
+            .fix("Start it with `docker run -p 6333:6333 qdrant/qdrant`, or point `skm config set qdrant_url <url>` at a running one; only skm index and skm search need it")),
+    }
+    if config.rag.embedding == EmbeddingProvider::Ollama {
+        let url = config.rag.embedding_url.trim_end_matches('/');
+        checks.push(match http_json("GET", &format!("{}/api/tags", url), None) {
+            Ok(_) => Check::new("services", "ollama", CheckStatus::Ok, format!("reachable at {}", url)),
+            Err(e) => Check::new("services", "ollama", CheckStatus::Warning, format!("not reachable at {}: {}", url, e))
+                .fix(format!("Start it with `ollama serve` and `ollama pull {}`, or set rag.embedding = \"hash\"", config.rag.model)),
+        });
+    }
+    checks
+}
+
+/// Without the `rag` feature nothing talks to Qdrant
+#[cfg(not(feature = "rag"))]
+pub fn check_services(_config: &GlobalConfig) -> Vec<Check> {
+    vec![Check::new("services", "qdrant", CheckStatus::Info, "not checked; built without the rag feature")]
+}
+
+/// Whether a program runs, with the first line of its version output
+pub fn check_tool(program: &str, version_arg: &str, needed_for: &str, install: &str) -> Check {
+    match Command::new(program).arg(version_arg).output() {
+        Ok(output) if output.status.success() => {
+            // curl appends its libraries in parentheses
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            let version = stdout.lines().next().unwrap_or_default().split(" (").next().unwrap_or_default().trim().to_string();
+            Check::new("tools", program, CheckStatus::Ok, version)
+        }
+        Ok(output) => Check::new("tools", program, CheckStatus::Warning,
+            format!("`{} {}` failed: {}", program, version_arg, String::from_utf8_lossy(&output.stderr).trim()))
+            .fix(install),
+        Err(_) => Check::new("tools", program, CheckStatus::Warning, format!("not found on PATH; {}", needed_for))
+            .fix(install),
+    }
+}
+
+/// The programs skm runs: tmux for sessions, git for Spec-Kit and agents,
+/// curl for every HTTP request
+pub fn check_tools() -> Vec<Check> {
+    let mut checks = Vec::new();
+    if cfg!(feature = "session") {
+        checks.push(check_tool("tmux", "-V", "skm session cannot start project sessions",
+            "Install tmux (apt install tmux, brew install tmux)"));
+    }
+    checks.push(check_tool("git", "--version", "Spec-Kit scripts and agents cannot create branches or commit",
+        "Install git (apt install git, brew install git)"));
+    if cfg!(any(feature = "rag", feature = "integrations")) {
+        checks.push(check_tool("curl", "--version", "search, publishing, notifications and GitHub counts fail",
+            "Install curl (apt install curl, brew install curl)"));
+    }
+    checks
+}
+
+/// Whether skm can write its files in `dir`
+///
+/// A directory that does not exist yet passes when its nearest existing
+/// ancestor is writable, since skm creates it on first use. The check
+/// writes and removes a probe file.
+pub fn check_writable(name: &str, dir: &Path) -> Check {
+    let existing = dir.ancestors().find(|path| path.is_dir()).map(Path::to_path_buf).unwrap_or_else(|| PathBuf::from("."));
+    let probe = existing.join(format!(".skm-doctor-{}", std::process::id()));
+    let fix = format!("Make {} writable (chmod u+w {}) or choose another root with --root", existing.display(), existing.display());
+    match std::fs::write(&probe, b"") {
+        Ok(()) => {
+            let _ = std::fs::remove_file(&probe);
+            match existing == dir {
+                true => Check::new("storage", name, CheckStatus::Ok, format!("{} is writable", dir.display())),
+                false => Check::new("storage", name, CheckStatus::Ok, format!("{} will be created on first use", dir.display())),
+            }
+        }
+        Err(e) => Check::new("storage", name, CheckStatus::Error, format!("{} is not writable: {}", existing.display(), e)).fix(fix),
+    }
+}
+
+/// Which optional parts were built in, and whether the configured
+/// integrations have what they need
+///
+/// A token is only checked for presence in the environment, never sent anywhere.
+pub fn check_integrations(config: &GlobalConfig) -> Vec<Check> {
+    let built = |feature: &str, what: &str, enabled: bool| match enabled {
+        true => Check::new("integrations", feature, CheckStatus::Ok, format!("built in: {}", what)),
+        false => Check::new("integrations", feature, CheckStatus::Info, format!("not built in: {}", what))
+            .fix(format!("Rebuild with `cargo install skm --features {}`", feature)),
+    };
+    let mut checks = vec![
+        built("git", "git status, remote clones, release notes and report commits", cfg!(feature = "git")),
+        built("rag", "skm index and skm search", cfg!(feature = "rag")),
+        built("session", "skm session", cfg!(feature = "session")),
+        built("serve", "skm metrics serve", cfg!(feature = "serve")),
+        built("tui", "skm watch", cfg!(feature = "tui")),
+        built("integrations", "GitHub, Notion, Confluence and webhooks", cfg!(feature = "integrations")),
+    ];
+
+    let token = |name: &str, env: &str| match std::env::var(env) {
+        Ok(value) if !value.is_empty() => Check::new("integrations", name, CheckStatus::Ok, format!("enabled; token in ${}", env)),
+        _ => Check::new("integrations", name, CheckStatus::Warning, format!("enabled, but ${} is not set", env))
+            .fix(format!("export {}=<token>", env)),
+    };
+    let mut configured = Vec::new();
+    if config.github.enabled {
+        configured.push(token("github", &config.github.token_env));
+    }
+    if let Some(notion) = &config.publish.notion {
+        configured.push(token("notion", &notion.token_env));
+    }
+    if let Some(confluence) = &config.publish.confluence {
+        configured.push(token("confluence", &confluence.token_env));
+    }
+    if config.notifications.webhook_url.is_some() {
+        configured.push(Check::new("integrations", "webhook", CheckStatus::Ok,
+            format!("enabled; batched every {} minute(s)", config.notifications.batch_minutes)));
+    }
+    if !cfg!(feature = "integrations") {
+        for check in &mut configured {
+            check.status = CheckStatus::Warning;
+            check.detail = "configured, but not built in".to_string();
+            check.fix = Some("Rebuild with `cargo install skm --features integrations`".to_string());
+        }
+    }
+    if config.notifications.desktop {
+        let program = if cfg!(target_os = "macos") { "osascript" } else { "notify-send" };
+        let found = Command::new(program).arg("--version").output().is_ok();
+        configured.push(match found {
+            true => Check::new("integrations", "desktop notifications", CheckStatus::Ok, format!("enabled; shown with {}", program)),
+            false => Check::new("integrations", "desktop notifications", CheckStatus::Warning, format!("enabled, but {} is not on PATH", program))
+                .fix("Install libnotify (apt install libnotify-bin), or set notifications.desktop = false"),
+        });
+    }
+    checks.extend(configured);
+    checks
+}
+
+/// The slowest projects of the last scan, each with the phase that took longest
+pub fn check_scan_time(portfolio: Option<&crate::PortfolioStatus>) -> Vec<Check> {
+    let Some(portfolio) = portfolio else {
+        return Vec::new();
+    };
+    portfolio.scan_stats.slowest.iter()
+        .map(|timing| {
+            let (phase, phase_ms) = timing.dominant_phase();
+            Check::new("scan time", timing.id.clone(), CheckStatus::Info,
+                format!("{}ms, mostly {} ({}ms)", timing.total_ms, phase, phase_ms))
+        })
+        .collect()
+}
+
+/// Projects whose stage or priority changed between scans although nothing
+/// they are analyzed from did, which usually means their artifacts parse
+/// ambiguously
+pub fn check_portfolio(portfolio: Option<&crate::PortfolioStatus>) -> Vec<Check> {
+    let Some(portfolio) = portfolio else {
+        return vec![Check::new("portfolio", "status cache", CheckStatus::Info, "no scan yet; run `skm scan`")];
+    };
+    let unstable: Vec<Check> = portfolio.projects.iter()
+        .filter(|p| !p.unstable.is_empty())
+        .map(|p| Check::new("portfolio", p.display_name(), CheckStatus::Warning,
+            format!("unstable across unchanged scans: {}", crate::reporter::format_unstable(&p.unstable)))
+            .fix(format!("Look for ambiguous content in the artifacts under {}; the flag clears once the project changes", p.path.display())))
+        .collect();
+    match unstable.is_empty() {
+        true => vec![Check::new("portfolio", "analysis", CheckStatus::Ok,
+            format!("{} project(s), none unstable", portfolio.projects.len()))],
+        false => unstable,
+    }
+}
@@ -1,26 +1,42 @@
-use clap::{Parser, Subcommand};
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
 use anyhow::Result;
-use std::path::Path;
-use std::collections::HashMap;
-use chrono::Utc;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use chrono::{DateTime, NaiveDate, Utc};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use skm::{
-    scanner::{finder::ProjectScanner, parser, git},
-    analyzer::{stage, priority::{self, PriorityCalculator}},
-    meta::{config::GlobalConfig, state::{ProjectMetaStore, StatusCache}},
-    Project, PortfolioStatus, ScanStats, StatusSummary, Stage,
+    analyzer::{self, filter::Filter, requirements, stage_description, sprint::SprintRollup},
+    publish::{self, PublishState},
+    autopilot::{self, actions::{self, AutopilotEntry, Decision, Outcome}, handoff::{self, Stop}, lease::{self, LeaseScope}},
+    scanner::{finder::find_enclosing_project, fingerprint::fingerprint, parser::{parse_task_items, parse_task_line}, remote, pipeline::{self, GitMode, ScanContext, ScanOptions}},
+    meta::{bulk::ChangeSet, config::{GlobalConfig, RootConfig, SprintConfig, WorkflowConfig}, config_file, fingerprints::FingerprintStore, history::MetricsHistory, registry::Registry, snapshots::{SnapshotStore, artifact_file}, schema::Migration, state::{ProjectMetaStore, StatusCache}, testruns::TestRunStore},
+    reporter::{release_notes, style, completion_feed, DEFAULT_PROMPT_FORMAT, DigestMode, DigestState, find_project, format_custom_fields, format_github, format_principles, format_unstable, publish_site, render_prompt, save_digest, write_html_view, write_markdown_view, write_priority_explanation, write_delimited_view, write_table_view, Delimited, TableColumn, TableOptions, DEFAULT_COLUMNS, write_sprint_table, write_time_to_address, write_unified_diff},
+    doctor::{self, CheckStatus},
+    rewrite::{self, Replacement},
+    template::Variables,
+    scaffold,
+    session::{completions::{self, Completion, CompletionShell}, hook::hook_script},
+    timefmt::{self, DisplayTimeZone},
+    view::{FieldFilter, PortfolioView},
+    ArtifactKind, PortfolioStatus, ScanStats, ScanPhase, Stage,
 };
-
-/// Helper function to check if debug mode is enabled
-#[inline]
-fn is_debug() -> bool {
-    std::env::var("SKM_DEBUG").is_ok()
-}
+#[cfg(feature = "session")]
+use skm::session::tmux::{self, SessionLayout};
+#[cfg(feature = "tui")]
+use skm::{alerts::{self, Batcher, Notification, Severity}, reporter::portfolio_changes};
 
 #[derive(Parser)]
 #[command(name = "skm")]
 #[command(about = "SKM (Spec-Kit Manager) - Intelligent meta-agent for project portfolio management")]
 #[command(version = "1.0.0")]
 struct Cli {
+    /// Show timestamps in this zone (utc, local or an offset like +02:00) instead of the configured one
+    #[arg(long, global = true)]
+    tz: Option<DisplayTimeZone>,
+    /// Text labels instead of emoji and color, and lists instead of tables
+    #[arg(long, global = true)]
+    accessible: bool,
     #[command(subcommand)]
     command: Commands,
 }
@@ -29,108 +45,3224 @@ struct Cli {
 enum Commands {
     /// Scan for Spec-Kit projects in current directory
     Scan {
-        #[arg(long, default_value = ".")]
-        root: String,
+        /// Root to scan; defaults to the configured roots, or the current directory
+        #[arg(long)]
+        root: Option<String>,
         #[arg(long, default_value = "*/.specify")]
         glob: String,
+        /// Do not query git at all (fastest; git-based signals are unavailable)
+        #[arg(long, conflicts_with = "deferred_git")]
+        skip_git: bool,
+        /// Show results first, then collect git status in the background and update the cache
+        #[arg(long)]
+        deferred_git: bool,
+        /// Rescan only this configured root; other roots are reused from their caches
+        #[arg(long, conflicts_with = "root")]
+        only_root: Option<String>,
+        /// Only re-process projects whose artifacts, root files, git HEAD or settings changed
+        #[arg(long)]
+        incremental: bool,
+        /// text, or jsonl: one JSON object per project as it is processed, then a summary record
+        #[arg(long, value_enum, default_value = "text")]
+        format: ScanFormat,
+    },
+    /// Keep rescanning a root as files change and print what moved
+    Watch {
+        /// Root to watch; defaults to the current directory
+        #[arg(long)]
+        root: Option<String>,
+        /// Seconds between rescans; defaults to `watch_interval_secs` from the config
+        #[arg(long)]
+        interval: Option<u64>,
+        /// Rescan every interval instead of waiting for filesystem events
+        #[arg(long)]
+        poll: bool,
+        /// Also show changes as desktop notifications, like `notifications.desktop`
+        #[arg(long)]
+        notify: bool,
     },
     /// Show status of all projects
     Status {
-        #[arg(long, default_value = ".")]
-        root: String,
+        /// Defaults to the combined portfolio of the configured roots, or the current directory
+        #[arg(long)]
+        root: Option<String>,
         #[arg(long)]
         json: bool,
+        /// Filter expression, e.g. "stage:implement AND priority>40 AND git:dirty"
         #[arg(long)]
         only: Option<String>,
+        /// Show only scan errors from this phase (discover, artifacts, tasks, git, analyze)
+        #[arg(long)]
+        errors: Option<String>,
+        /// Include optional detail sections in the output (comma-separated)
+        #[arg(long, value_enum, value_delimiter = ',')]
+        detail: Vec<Detail>,
+        /// Only matching projects: id=, stage=, root=, tag= or custom.<field>=<value> (repeatable)
+        #[arg(long = "where")]
+        conditions: Vec<String>,
+        /// Break each listed project's priority into its weighted factors
+        #[arg(long)]
+        explain: bool,
+        /// List all projects as a table of these columns, e.g. project,stage,priority,branch
+        #[arg(long)]
+        columns: Option<String>,
+        /// Table row order: a column, optionally with :asc or :desc (default priority)
+        #[arg(long)]
+        sort_by: Option<String>,
+        /// Rescan even if no project changed since the cached scan
+        #[arg(long)]
+        refresh: bool,
     },
     /// Generate reports
     Report {
-        #[arg(long, default_value = "./.skm/STATUS.md")]
-        out: String,
+        /// Defaults to the combined portfolio of the configured roots, or the current directory
+        #[arg(long)]
+        root: Option<String>,
+        /// Defaults to ./.skm/STATUS.<format>, or stdout for a table
+        #[arg(long)]
+        out: Option<String>,
+        /// md, html, json, csv, tsv or table
         #[arg(long, default_value = "md")]
         format: String,
+        /// Table columns, e.g. project,stage,priority,branch
+        #[arg(long)]
+        columns: Option<String>,
+        /// Table row order: a column, optionally with :asc or :desc (default priority)
+        #[arg(long)]
+        sort_by: Option<String>,
+        /// Report the portfolio as logged at this time (YYYY-MM-DD for the end of that day, or RFC 3339)
+        #[arg(long)]
+        as_of: Option<String>,
+        /// Filter expression, e.g. "stage:implement AND priority>40 AND git:dirty"
+        #[arg(long)]
+        only: Option<String>,
+        /// Only matching projects: id=, stage=, root=, tag= or custom.<field>=<value> (repeatable)
+        #[arg(long = "where")]
+        conditions: Vec<String>,
+    },
+    /// Publish the portfolio: a static HTML site, or the status report on a Notion or Confluence page
+    Publish {
+        /// Defaults to the combined portfolio of the configured roots, or the current directory
+        #[arg(long)]
+        root: Option<String>,
+        /// site, notion or confluence; the pages are configured under [publish]
+        #[arg(long, default_value = "site")]
+        target: String,
+        /// Output directory of the site
+        #[arg(long, default_value = "./site")]
+        out: String,
+        /// Update the page even when the report is unchanged since the last publish
+        #[arg(long)]
+        force: bool,
+        /// Only matching projects: id=, stage=, root=, tag= or custom.<field>=<value> (repeatable)
+        #[arg(long = "where")]
+        conditions: Vec<String>,
+    },
+    /// Upgrade .skm/status.json and .skm/meta.json to the current schema version
+    MigrateState {
+        #[arg(long, default_value = ".")]
+        root: String,
+        /// Report what would change without writing files
+        #[arg(long)]
+        dry_run: bool,
     },
     /// Generate digest summaries
     Digest {
+        /// Defaults to the combined portfolio of the configured roots, or the current directory
+        #[arg(long)]
+        root: Option<String>,
         #[arg(long)]
         project: Option<String>,
+        /// daily or weekly
         mode: String,
         #[arg(long, default_value = "DIGEST.md")]
         out: String,
+        /// Only matching projects: id=, stage=, root=, tag= or custom.<field>=<value> (repeatable)
+        #[arg(long = "where")]
+        conditions: Vec<String>,
+    },
+    /// Compare sprints: stage changes, completed tasks and new projects per sprint
+    Sprints {
+        /// Defaults to the combined portfolio of the configured roots, or the current directory
+        #[arg(long)]
+        root: Option<String>,
+    },
+    /// Show the stages a project went through, when it entered each and how long it stayed
+    History {
+        /// Project id or root/id
+        project: String,
+        #[arg(long)]
+        root: Option<String>,
+        #[arg(long)]
+        json: bool,
+    },
+    /// Show how long human requirements (review, fix, input, ...) take to be addressed, per type
+    Analytics {
+        /// Defaults to the combined portfolio of the configured roots, or the current directory
+        #[arg(long)]
+        root: Option<String>,
+        /// Count requirements addressed in this many days
+        #[arg(long, default_value_t = 30)]
+        days: u32,
+        #[arg(long)]
+        json: bool,
+    },
+    /// Break a project's priority into its weighted factors and their raw inputs
+    Explain {
+        /// Project id or root/id
+        project: String,
+        #[arg(long)]
+        root: Option<String>,
+        #[arg(long)]
+        json: bool,
+    },
+    /// Pin a project: list it first and keep it needing attention until it is done
+    Pin {
+        /// Project id or root/id
+        project: String,
+        #[arg(long)]
+        root: Option<String>,
+    },
+    /// Unpin a project pinned with `skm pin`
+    Unpin {
+        /// Project id or root/id
+        project: String,
+        #[arg(long)]
+        root: Option<String>,
+    },
+    /// Show the project containing the current directory: details, next action and tasks
+    Here {
+        #[arg(long)]
+        json: bool,
+    },
+    /// Print a shell hook that offers to register Spec-Kit projects on cd (zsh, bash or fish)
+    Hook {
+        shell: String,
+    },
+    /// Print a completion script for subcommands, options, `--only` conditions and project ids
+    Completions {
+        shell: CompletionShell,
+    },
+    /// Completion candidates for the words after `skm`, used by the completion scripts
+    #[command(name = "__complete", hide = true)]
+    Complete {
+        #[arg(allow_hyphen_values = true)]
+        words: Vec<String>,
+    },
+    /// Register the project containing a directory so scans always include it
+    Register {
+        #[arg(default_value = ".")]
+        path: String,
+        /// Print the project path if it is untracked and not ignored; print nothing otherwise
+        #[arg(long, conflicts_with_all = ["ignore", "warm"])]
+        check: bool,
+        /// Scan the project in the background after registering
+        #[arg(long)]
+        warm: bool,
+        /// Never offer this project for registration again
+        #[arg(long, conflicts_with = "warm")]
+        ignore: bool,
+    },
+    /// Set up Spec-Kit in a directory (via the specify CLI when available) and register it
+    Init {
+        #[arg(default_value = ".")]
+        path: String,
+        /// auto, internal or specify; defaults to init.backend
+        #[arg(long)]
+        backend: Option<String>,
+        /// Do not add the project to the registry
+        #[arg(long)]
+        no_register: bool,
+    },
+    /// Bring an existing codebase into the portfolio with a reviewable skeleton spec and tasks
+    Adopt {
+        #[arg(default_value = ".")]
+        path: String,
+        /// Do not add the project to the registry
+        #[arg(long)]
+        no_register: bool,
+    },
+    /// Start a numbered feature with a spec on its own branch
+    NewFeature {
+        /// What the feature should do
+        description: String,
+        /// Name for the directory and branch instead of the description's first words
+        #[arg(long)]
+        short_name: Option<String>,
+        /// Project directory (default: the project enclosing the current directory)
+        #[arg(long)]
+        path: Option<String>,
+        /// auto, internal or specify; defaults to init.backend
+        #[arg(long)]
+        backend: Option<String>,
+    },
+    /// Track repositories by URL without a local checkout of your own
+    Remote {
+        #[command(subcommand)]
+        action: RemoteAction,
+    },
+    /// Show how a project's spec (or plan/tasks) changed across scans
+    SpecDiff {
+        /// Project id (or root/id)
+        project: String,
+        #[arg(long)]
+        root: Option<String>,
+        /// spec, plan or tasks
+        #[arg(long, default_value = "spec")]
+        artifact: String,
+        /// Compare against the version this many changes back
+        #[arg(long, default_value_t = 1)]
+        back: usize,
+    },
+    /// Print a terse status segment for the project containing a directory (for shell prompts)
+    Prompt {
+        #[arg(long, default_value = ".")]
+        cwd: String,
+        /// Placeholders: {id} {stage} {open} {done} {total} {priority} {tier} {tier_emoji}
+        #[arg(long, default_value = DEFAULT_PROMPT_FORMAT)]
+        format: String,
+    },
+    /// Print a minimal, versioned JSON project list for launchers such as Raycast or Alfred
+    CompletionData {
+        #[arg(long)]
+        root: Option<String>,
+        /// Indent the JSON
+        #[arg(long)]
+        pretty: bool,
+    },
+    /// Run a command in a project with its execution profile (env, PATH, workdir, container)
+    Exec {
+        /// Project id (or root/id)
+        project: String,
+        #[arg(long)]
+        root: Option<String>,
+        /// Command line, run with `sh -c`
+        #[arg(last = true, required = true)]
+        command: Vec<String>,
+    },
+    /// Run a project's tests, record the outcome and update its stage and risk
+    Test {
+        /// Project id (or root/id)
+        project: Option<String>,
+        /// Test every project instead of one
+        #[arg(long, conflicts_with = "project")]
+        all: bool,
+        /// With --all, only projects in this stage
+        #[arg(long, requires = "all")]
+        stage: Option<String>,
+        /// Projects tested at the same time
+        #[arg(long, default_value_t = 1)]
+        jobs: usize,
+        #[arg(long)]
+        root: Option<String>,
+    },
+    /// Run a cheap build or typecheck (cargo check, tsc, ruff/mypy, go vet) and record failures as risk
+    Check {
+        /// Project id (or root/id)
+        project: Option<String>,
+        /// Check every project instead of one
+        #[arg(long, conflicts_with = "project")]
+        all: bool,
+        /// With --all, only projects in this stage
+        #[arg(long, requires = "all")]
+        stage: Option<String>,
+        /// Projects checked at the same time
+        #[arg(long, default_value_t = 4)]
+        jobs: usize,
+        #[arg(long)]
+        root: Option<String>,
+    },
+    /// Show how each line of a tasks file is parsed (for debugging task formats)
+    ParseTasks {
+        file: String,
+        #[arg(long)]
+        json: bool,
+    },
+    /// Draft a CHANGELOG entry from commits, completed tasks and acceptance criteria since the last tag
+    ReleaseNotes {
+        /// Project id (or root/id)
+        project: String,
+        #[arg(long)]
+        root: Option<String>,
+        /// Version heading of the entry; defaults to "Unreleased"
+        #[arg(long)]
+        version: Option<String>,
+        /// Commit the entry to CHANGELOG.md on a new release-notes/<version> branch
+        #[arg(long)]
+        write: bool,
+    },
+    /// Replace a name across the spec artifacts of many projects, previewed as a diff
+    Edit {
+        /// Old/New: the text to replace and its replacement, split at the first /
+        #[arg(long)]
+        replace: String,
+        /// id=, stage=, root=, tag= or custom.<field>=<value> (repeatable; all must match)
+        #[arg(long = "filter")]
+        conditions: Vec<String>,
+        #[arg(long)]
+        root: Option<String>,
+        /// Write the changes and commit them in each project on a new branch
+        #[arg(long)]
+        write: bool,
+        /// Branch for the commits; defaults to skm/replace-<new text>
+        #[arg(long)]
+        branch: Option<String>,
+    },
+    /// Chunk and embed every project's constitution, spec, plan and tasks into Qdrant
+    Index {
+        #[arg(long)]
+        root: Option<String>,
+        /// Only this project (id or root/id)
+        #[arg(long)]
+        project: Option<String>,
+        /// Drop the collection first, e.g. after changing the embedding provider
+        #[arg(long)]
+        reset: bool,
+    },
+    /// Find the artifact sections most relevant to a question across the portfolio
+    Search {
+        query: String,
+        #[arg(long)]
+        root: Option<String>,
+        /// Number of results
+        #[arg(long, default_value_t = 5)]
+        limit: usize,
+        /// Only this project (id or root/id)
+        #[arg(long)]
+        project: Option<String>,
+        /// Only projects in this stage
+        #[arg(long)]
+        stage: Option<Stage>,
+        /// Only this artifact: constitution, spec, plan or tasks
+        #[arg(long)]
+        artifact: Option<ArtifactKind>,
+        #[arg(long)]
+        json: bool,
+    },
+    /// Check the config, services, tools, .skm directories, integrations and
+    /// the last scan, with a suggested fix for each problem
+    Doctor {
+        #[arg(long)]
+        root: Option<String>,
+        #[arg(long)]
+        json: bool,
+    },
+    /// Generate a synthetic portfolio and time repeated scans of it
+    Bench {
+        /// Number of projects to generate
+        #[arg(long, default_value = "200")]
+        projects: usize,
+        /// Scans to run over the same portfolio
+        #[arg(long, default_value = "3")]
+        runs: usize,
+        /// Seed for task counts and git states; the same seed gives the same portfolio
+        #[arg(long, default_value = "1")]
+        seed: u64,
+        /// Generate the projects without repositories and scan without git
+        #[arg(long)]
+        skip_git: bool,
+        /// Generate into this new directory and leave it there afterwards
+        #[arg(long)]
+        keep: Option<String>,
+    },
+    /// Run the next action of each project, by priority, when its risk is within the automation level
+    Autopilot {
+        #[arg(long)]
+        root: Option<String>,
+        /// Only this project (id or root/id)
+        #[arg(long)]
+        project: Option<String>,
+        /// Run allowed actions even when dry_run_default is set
+        #[arg(long)]
+        execute: bool,
+        /// Only report what would run
+        #[arg(long, conflicts_with = "execute")]
+        dry_run: bool,
+        /// Stop after this many executed actions
+        #[arg(long)]
+        limit: Option<usize>,
+        /// Only take actions whose estimated durations fit in this many minutes
+        #[arg(long)]
+        budget: Option<u64>,
+        /// Run on copies of the projects in this directory (a new temp directory when omitted)
+        #[arg(long, num_args = 0..=1)]
+        sandbox: Option<Option<PathBuf>>,
+    },
+    /// Show a project's next action and whether its automation level permits it
+    Next {
+        /// Project id or root/id; defaults to the highest-priority unfinished project
+        project: Option<String>,
+        #[arg(long)]
+        root: Option<String>,
+        /// Run the action through the headless agent if the level permits it
+        #[arg(long)]
+        exec: bool,
+        /// With --exec, start the project's agent_command on the action in its tmux session instead
+        #[arg(long, requires = "exec")]
+        tmux: bool,
+    },
+    /// List autopilot leases (portfolio and per-project locks) and release stale ones
+    Locks {
+        #[arg(long)]
+        root: Option<String>,
+        /// Remove this lease (portfolio or project:<id>) even if it is still held
+        #[arg(long)]
+        release: Option<String>,
+        /// Remove all expired or unreadable leases
+        #[arg(long, conflicts_with = "release")]
+        release_stale: bool,
+    },
+    /// Open, list and kill per-project tmux sessions
+    Session {
+        #[command(subcommand)]
+        action: SessionAction,
+    },
+    /// Create, show and edit ~/.config/skm/config.toml
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+    /// Export portfolio metrics for Prometheus
+    Metrics {
+        #[command(subcommand)]
+        action: MetricsAction,
+    },
+    /// Run JSON commands from stdin, one per line, and print one JSON result per line
+    Batch {
+        #[arg(long)]
+        root: Option<String>,
+    },
+    /// Edit project metadata (.skm/meta.json) for many projects at once
+    Meta {
+        #[command(subcommand)]
+        action: MetaAction,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigAction {
+    /// Write the default config with a comment on each setting
+    Init {
+        /// Replace an existing config file
+        #[arg(long)]
+        force: bool,
+    },
+    /// Print the effective config, including defaults for unset values
+    Show,
+    /// Set a value, e.g. `weights.staleness 20` or `automation_level L2`
+    Set {
+        key: String,
+        value: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum MetricsAction {
+    /// Serve the last scan's metrics at /metrics until interrupted
+    Serve {
+        #[arg(long)]
+        root: Option<String>,
+        #[arg(long, default_value_t = 9188)]
+        port: u16,
+        /// Address to listen on; 0.0.0.0 for every interface
+        #[arg(long, default_value = "127.0.0.1")]
+        bind: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum MetaAction {
+    /// Set one key on a project (`meta set <project> impact 3`) or on every
+    /// project matching the filters (`meta set --filter stage=bootstrap impact 3`)
+    Set {
+        /// [project] key value; key is impact, approved_by_human, pinned, priority_override, priority_boost, automation_level, auto_approve (comma-separated), agent_command, git.enabled, exec.*, command.<name> or custom.<field>
+        #[arg(num_args = 2..=3, value_names = ["PROJECT", "KEY", "VALUE"], required = true)]
+        args: Vec<String>,
+        /// id=, stage=, root=, tag= or custom.<field>=<value> (repeatable; all must match)
+        #[arg(long = "filter")]
+        conditions: Vec<String>,
+        #[arg(long)]
+        root: Option<String>,
+        /// List the projects that would change without writing meta.json
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Print one key of a project, or all keys that differ from the defaults
+    Get {
+        project: String,
+        key: Option<String>,
+        #[arg(long)]
+        root: Option<String>,
+        #[arg(long)]
+        json: bool,
+    },
+    /// Print the metadata of every project that has any
+    List {
+        #[arg(long)]
+        root: Option<String>,
+        #[arg(long)]
+        json: bool,
+    },
+    /// Apply the [[change]] entries of a TOML file
+    Apply {
+        file: String,
+        #[arg(long)]
+        root: Option<String>,
+        /// List the projects that would change without writing meta.json
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum RemoteAction {
+    /// Track a remote repository and make a shallow clone of it
+    Add {
+        url: String,
+        /// Project name; defaults to the last segment of the URL
+        #[arg(long)]
+        name: Option<String>,
+    },
+    /// List tracked remote repositories
+    List,
+    /// Stop tracking a remote repository and delete its clone
+    Remove {
+        name: String,
+    },
+    /// Update the clones of all (or one) tracked remote repositories
+    Sync {
+        name: Option<String>,
     },
 }
 
+#[derive(Subcommand)]
+enum SessionAction {
+    /// Create (or reuse) the project's tmux session and attach to it
+    Open {
+        /// Project id (or root/id)
+        project: String,
+        #[arg(long)]
+        root: Option<String>,
+        /// Start the editor, with a pane beside it running the project's agent_command
+        #[arg(long)]
+        split: bool,
+        /// Create the session without attaching
+        #[arg(long)]
+        detach: bool,
+    },
+    /// List running skm sessions
+    List,
+    /// Kill a project's session, or all skm sessions
+    Kill {
+        /// Project id (or root/id)
+        #[arg(required_unless_present = "all")]
+        project: Option<String>,
+        #[arg(long)]
+        root: Option<String>,
+        #[arg(long, conflicts_with = "project")]
+        all: bool,
+    },
+}
+
+/// How `skm scan` reports what it finds
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+enum ScanFormat {
+    Text,
+    Jsonl,
+}
+
+/// Optional detail sections that are omitted from default output to keep it small
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+enum Detail {
+    /// Per-feature artifacts and task summaries
+    Features,
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     env_logger::init();
     
     let cli = Cli::parse();
+    let config = GlobalConfig::load().ok();
+    timefmt::set_display_zone(cli.tz.or(config.as_ref().map(|c| c.timezone)).unwrap_or_default());
+    style::set_accessible(cli.accessible || config.is_some_and(|c| c.accessible));
     
     match cli.command {
-        Commands::Scan { root, glob: _ } => {
-            scan_projects(&root).await
+        Commands::Scan { root, glob: _, skip_git, deferred_git, only_root, incremental, format } => {
+            if deferred_git && format == ScanFormat::Jsonl {
+                anyhow::bail!("--deferred-git reports git status as text afterwards; use it without --format jsonl");
+            }
+            let git = if skip_git {
+                GitMode::Skip
+            } else if deferred_git {
+                GitMode::Deferred
+            } else {
+                GitMode::Sync
+            };
+            run_scan(root.as_deref(), only_root.as_deref(), &ScanOptions { git, incremental }, format).await
+        }
+        #[cfg(feature = "tui")]
+        Commands::Watch { root, interval, poll, notify } => watch(root.as_deref(), interval, poll, notify),
+        #[cfg(not(feature = "tui"))]
+        Commands::Watch { .. } => disabled("tui"),
+        Commands::Status { root, json, only, errors, detail, conditions, explain, columns, sort_by, refresh } => {
+            let error_phase = errors.as_deref().map(str::parse::<ScanPhase>).transpose()?;
+            let conditions = parse_conditions(&conditions)?;
+            let only = only.as_deref().map(str::parse::<Filter>).transpose()?;
+            let table = (columns.is_some() || sort_by.is_some())
+                .then(|| table_options(columns.as_deref(), sort_by.as_deref(), style::colored()))
+                .transpose()?;
+            let output = StatusOutput { json, detail: &detail, explain, refresh, table };
+            show_status(root.as_deref(), only.as_ref(), &conditions, error_phase, &output).await
         }
-        Commands::Status { root, json, only } => {
-            show_status(&root, json, only.as_deref()).await
+        Commands::Report { root, out, format, as_of, only, conditions, columns, sort_by } => {
+            let as_of = as_of.as_deref().map(parse_as_of).transpose()?;
+            let only = only.as_deref().map(str::parse::<Filter>).transpose()?;
+            let out = match (out, format.as_str()) {
+                (Some(out), _) => Some(PathBuf::from(out)),
+                (None, "table") => None,
+                (None, format) => Some(PathBuf::from(format!("./.skm/STATUS.{}", format))),
+            };
+            let table = table_options(columns.as_deref(), sort_by.as_deref(), out.is_none() && style::colored())?;
+            generate_report(root.as_deref(), out.as_deref(), &format, &table, as_of, only.as_ref(), &parse_conditions(&conditions)?)
+        }
+        Commands::Publish { root, target, out, force, conditions } => {
+            publish(root.as_deref(), &target, Path::new(&out), force, &parse_conditions(&conditions)?)
+        }
+        Commands::MigrateState { root, dry_run } => {
+            migrate_state(Path::new(&root), dry_run)
+        }
+        Commands::Digest { root, project, mode, out, conditions } => {
+            generate_digest(root.as_deref(), project.as_deref(), &parse_conditions(&conditions)?, mode.parse()?, Path::new(&out))
+        }
+        Commands::Sprints { root } => {
+            show_sprints(root.as_deref())
+        }
+        Commands::History { project, root, json } => {
+            show_history(root.as_deref(), &project, json)
+        }
+        Commands::Analytics { root, days, json } => {
+            show_analytics(root.as_deref(), days, json)
+        }
+        Commands::Explain { project, root, json } => {
+            explain_priority(root.as_deref(), &project, json)
+        }
+        Commands::Pin { project, root } => set_pinned(root.as_deref(), &project, true),
+        Commands::Unpin { project, root } => set_pinned(root.as_deref(), &project, false),
+        Commands::Here { json } => {
+            show_here(json)
+        }
+        Commands::Hook { shell } => {
+            print!("{}", hook_script(shell.parse()?));
+            Ok(())
+        }
+        Commands::Completions { shell } => {
+            print!("{}", completions::completion_script(shell));
+            Ok(())
+        }
+        Commands::Complete { words } => {
+            print_completions(&words);
+            Ok(())
+        }
+        Commands::Register { path, check, warm, ignore } => {
+            register_project(Path::new(&path), check, warm, ignore)
+        }
+        Commands::Remote { action } => {
+            manage_remotes(action)
+        }
+        Commands::Init { path, backend, no_register } => {
+            init_project(Path::new(&path), backend.as_deref(), no_register)
+        }
+        Commands::Adopt { path, no_register } => adopt_project(Path::new(&path), no_register),
+        Commands::NewFeature { description, short_name, path, backend } => {
+            new_feature(path.as_deref(), &description, short_name.as_deref(), backend.as_deref())
+        }
+        Commands::Exec { project, root, command } => {
+            exec_in_project(root.as_deref(), &project, &command)
+        }
+        Commands::Test { project, all, stage, jobs, root } => {
+            let stage = stage.as_deref().map(str::parse::<Stage>).transpose()?;
+            run_project_commands(RunKind::Test, root.as_deref(), project.as_deref(), all, stage, jobs)
+        }
+        Commands::Check { project, all, stage, jobs, root } => {
+            let stage = stage.as_deref().map(str::parse::<Stage>).transpose()?;
+            run_project_commands(RunKind::Check, root.as_deref(), project.as_deref(), all, stage, jobs)
+        }
+        Commands::ParseTasks { file, json } => parse_tasks(Path::new(&file), json),
+        Commands::ReleaseNotes { project, root, version, write } => {
+            release_notes(root.as_deref(), &project, version.as_deref(), write)
+        }
+        Commands::Edit { replace, conditions, root, write, branch } => {
+            edit_artifacts(root.as_deref(), &parse_conditions(&conditions)?, &replace.parse()?, write, branch.as_deref())
+        }
+        #[cfg(feature = "rag")]
+        Commands::Index { root, project, reset } => {
+            index_artifacts(root.as_deref(), project.as_deref(), reset)
+        }
+        #[cfg(feature = "rag")]
+        Commands::Search { query, root, limit, project, stage, artifact, json } => {
+            search_artifacts(root.as_deref(), &query, limit, project.as_deref(), stage, artifact, json)
+        }
+        #[cfg(not(feature = "rag"))]
+        Commands::Index { .. } | Commands::Search { .. } => disabled("rag"),
+        Commands::Doctor { root, json } => run_doctor(root.as_deref(), json),
+        #[cfg(feature = "testkit")]
+        Commands::Bench { projects, runs, seed, skip_git, keep } => bench(projects, runs, seed, skip_git, keep.as_deref()),
+        #[cfg(not(feature = "testkit"))]
+        Commands::Bench { .. } => disabled("testkit"),
+        Commands::Autopilot { root, project, execute, dry_run, limit, budget, sandbox } => {
+            let budget = budget.map(|minutes| Duration::from_secs(minutes * 60));
+            autopilot(root.as_deref(), project.as_deref(), execute, dry_run, limit, budget, sandbox)
+        }
+        Commands::Next { project, root, exec, tmux } => next_action(root.as_deref(), project.as_deref(), exec, tmux),
+        Commands::Locks { root, release, release_stale } => {
+            manage_locks(root.as_deref(), release.as_deref(), release_stale)
+        }
+        #[cfg(feature = "session")]
+        Commands::Session { action } => session(action),
+        #[cfg(not(feature = "session"))]
+        Commands::Session { .. } => disabled("session"),
+        Commands::Config { action } => edit_config(action),
+        Commands::Meta { action } => {
+            match action {
+                MetaAction::Set { mut args, mut conditions, root, dry_run } => {
+                    let value = args.pop().unwrap_or_default();
+                    let key = args.pop().unwrap_or_default();
+                    if let Some(project) = args.pop() {
+                        conditions.push(format!("id={}", project));
+                    }
+                    let change = (parse_conditions(&conditions)?, vec![(key, value)]);
+                    edit_meta(root.as_deref(), &[change], dry_run)
+                }
+                MetaAction::Get { project, key, root, json } => {
+                    show_meta(root.as_deref(), Some(&project), key.as_deref(), json)
+                }
+                MetaAction::List { root, json } => show_meta(root.as_deref(), None, None, json),
+                MetaAction::Apply { file, root, dry_run } => {
+                    let changes = ChangeSet::load(Path::new(&file))?.changes.iter()
+                        .map(|change| Ok((parse_conditions(&change.filter)?, change.assignments())))
+                        .collect::<Result<Vec<_>>>()?;
+                    edit_meta(root.as_deref(), &changes, dry_run)
+                }
+            }
+        }
+        Commands::SpecDiff { project, root, artifact, back } => {
+            spec_diff(root.as_deref(), &project, artifact.parse()?, back)
+        }
+        Commands::Prompt { cwd, format } => {
+            print_prompt(Path::new(&cwd), &format);
+            Ok(())
+        }
+        Commands::CompletionData { root, pretty } => completion_data(root.as_deref(), pretty),
+        Commands::Batch { root } => run_batch(root.as_deref()),
+        #[cfg(feature = "serve")]
+        Commands::Metrics { action: MetricsAction::Serve { root, port, bind } } => serve_metrics(root.as_deref(), &bind, port),
+        #[cfg(not(feature = "serve"))]
+        Commands::Metrics { action: MetricsAction::Serve { .. } } => disabled("serve"),
+    }
+}
+
+/// The commands a build without `feature` leaves out
+#[cfg(not(all(feature = "rag", feature = "session", feature = "serve", feature = "tui", feature = "testkit")))]
+fn disabled(feature: &'static str) -> Result<()> {
+    Err(skm::SKMError::FeatureDisabled { feature }.into())
+}
+
+/// How `skm status` prints the portfolio
+struct StatusOutput<'a> {
+    json: bool,
+    detail: &'a [Detail],
+    /// Priority breakdowns after the project list
+    explain: bool,
+    /// Rescan even if the cache is current
+    refresh: bool,
+    /// A table of all projects instead of the top ten
+    table: Option<TableOptions>,
+}
+
+async fn show_status(
+    root_arg: Option<&str>,
+    only: Option<&Filter>,
+    conditions: &[FieldFilter],
+    error_phase: Option<ScanPhase>,
+    output: &StatusOutput<'_>,
+) -> Result<()> {
+    let StatusOutput { json: json_output, detail, explain, refresh, ref table } = *output;
+    let config = GlobalConfig::load()?;
+    let root = &resolve_root(root_arg, &config)?;
+    
+    // Serve the cache while the projects it was built from are unchanged
+    let cached = match StatusCache::load_migrated(root) {
+        Ok(Some((cache, _))) if !refresh => current_contexts(root_arg, &config, &cache)?.map(|contexts| (cache, contexts)),
+        _ => None,
+    };
+    if let Some((cached_status, contexts)) = cached {
+        let mut portfolio: PortfolioStatus = serde_json::from_value(cached_status.data)?;
+        // Unchanged projects still age: staleness, seasons and review SLAs follow the clock
+        for project in &mut portfolio.projects {
+            let Some((_, ctx)) = contexts.iter()
+                .find(|(label, _)| label.is_none() || *label == project.root)
+                .or(contexts.first()) else { continue };
+            let overrides = ctx.config.for_project(&project.path)?;
+            pipeline::rerank(project, overrides.as_ref().unwrap_or(&ctx.config), ctx);
+        }
+        if !detail.contains(&Detail::Features) {
+            portfolio.projects.iter_mut().for_each(|p| p.features.clear());
+        }
+        
+        if let Some(phase) = error_phase {
+            portfolio.scan_stats.errors.retain(|e| e.phase == phase);
+            if json_output {
+                println!("{}", serde_json::to_string_pretty(&portfolio.scan_stats.errors)?);
+            } else {
+                display_scan_errors(&portfolio.scan_stats);
+            }
+            return Ok(());
+        }
+        
+        // Apply filter if specified
+        let mut view = PortfolioView::new(&portfolio);
+        if let Some(only) = only {
+            view = view.filter(|p| only.matches(p));
+        }
+        view = view.filter_where(conditions);
+        
+        if json_output {
+            println!("{}", serde_json::to_string_pretty(&view)?);
+        } else {
+            match table.as_ref() {
+                Some(table) => write_table_view(&view, table, &mut std::io::stdout().lock())?,
+                None => {
+                    display_portfolio_status(&view);
+                    display_autopilot_run(&actions::last_run(root));
+                }
+            }
+            if explain {
+                println!();
+                println!("=== Priority Breakdown ===");
+                for project in view.clone().sorted_by_priority().iter().take(10) {
+                    write_priority_explanation(project, &mut std::io::stdout())?;
+                }
+            }
+        }
+        
+        return Ok(());
+    }
+    
+    // Cache is out of date, missing or bypassed with --refresh, rescan
+    println!("{}", if refresh { "Rescanning..." } else { "Cache is out of date or missing, rescanning..." });
+    run_scan(root_arg, None, &ScanOptions::default(), ScanFormat::Text).await
+}
+
+/// Scan context of each root, with the root's name in the combined portfolio
+type RootContexts = Vec<(Option<String>, ScanContext)>;
+
+/// Scan contexts of the roots behind the status cache, when the cache still
+/// describes the projects on disk
+///
+/// Rediscovers the projects of the root, or of every configured root for
+/// the combined portfolio, and fingerprints them without processing them:
+/// file stamps under `.specify` and `specs`, `tasks.md` contents, root
+/// files, git refs and index, and the config and metadata they are
+/// analyzed with. Much cheaper than a scan, which is only needed when a
+/// fingerprint differs or a project appeared or disappeared.
+fn current_contexts(root_arg: Option<&str>, config: &GlobalConfig, cache: &StatusCache) -> Result<Option<RootContexts>> {
+    let roots: Vec<(Option<String>, GlobalConfig, PathBuf)> = match root_arg {
+        None if !config.roots.is_empty() => config.roots.iter()
+            .map(|root| (Some(root.name.clone()), config.for_root(root), root.resolved_path()))
+            .filter(|(_, _, path)| path.is_dir())
+            .collect(),
+        root => vec![(None, config.clone(), PathBuf::from(root.unwrap_or(".")))],
+    };
+    let mut current = BTreeMap::new();
+    let mut contexts = Vec::new();
+    for (label, config, root) in roots {
+        let mut ctx = ScanContext::with_config(config, &root, ScanOptions::default())?;
+        for path in pipeline::discover(&root, &mut ctx)? {
+            let print = fingerprint(&path, &ctx);
+            current.insert(path.to_string_lossy().into_owned(), print);
+        }
+        contexts.push((label, ctx));
+    }
+    Ok(cache.is_current(&current).then_some(contexts))
+}
+
+/// Root a command reads from: an explicit `--root`, the combined portfolio
+/// when roots are configured, or the current directory
+/// Print one completion candidate per line; failures print nothing, so the
+/// shell falls back to file names instead of showing an error mid-line
+fn print_completions(words: &[String]) {
+    let mut cmd = Cli::command();
+    cmd.build();
+    let candidates = match completions::complete(&cmd, words) {
+        Completion::Words(words) => words,
+        Completion::Projects { root, lead, prefix } => project_names(root.as_deref()).unwrap_or_default()
+            .into_iter()
+            .filter(|name| name.starts_with(&prefix))
+            .map(|name| format!("{}{}", lead, name))
+            .collect(),
+    };
+    for candidate in candidates {
+        println!("{}", candidate);
+    }
+}
+
+/// Ids and `root/id` display names of the cached projects, without scanning
+fn project_names(root_arg: Option<&str>) -> Result<BTreeSet<String>> {
+    let config = GlobalConfig::load()?;
+    let root = resolve_root(root_arg, &config)?;
+    let Some((cache, _)) = StatusCache::load_migrated(&root)? else {
+        return Ok(BTreeSet::new());
+    };
+    let portfolio: PortfolioStatus = serde_json::from_value(cache.data)?;
+    Ok(portfolio.projects.iter().flat_map(|p| [p.id.clone(), p.display_name()]).collect())
+}
+
+fn resolve_root(root_arg: Option<&str>, config: &GlobalConfig) -> Result<PathBuf> {
+    match root_arg {
+        Some(root) => Ok(PathBuf::from(root)),
+        None if !config.roots.is_empty() => Ok(GlobalConfig::portfolio_home()?),
+        None => Ok(PathBuf::from(".")),
+    }
+}
+
+fn generate_digest(
+    root_arg: Option<&str>,
+    project: Option<&str>,
+    conditions: &[FieldFilter],
+    mode: DigestMode,
+    out: &Path,
+) -> Result<()> {
+    let config = GlobalConfig::load()?;
+    let root = &resolve_root(root_arg, &config)?;
+    let (cache, _) = StatusCache::load_migrated(root)?
+        .ok_or_else(|| anyhow::anyhow!("No status cache in {}; run `skm scan` first", root.display()))?;
+    let portfolio: PortfolioStatus = serde_json::from_value(cache.data)?;
+    
+    let mut view = PortfolioView::new(&portfolio);
+    if let Some(id) = project {
+        view = view.filter(|p| p.id == id || p.display_name() == id);
+        if view.is_empty() {
+            anyhow::bail!("Project not found: {}", id);
+        }
+    }
+    view = view.filter_where(conditions);
+    
+    let sprints = match &config.sprint {
+        Some(sprint) => sprint_rollups(root, sprint, project)?,
+        None => Vec::new(),
+    };
+    let now = Utc::now();
+    let mut state = DigestState::load(root)?;
+    let since = state.since(mode, now);
+    let mut handoffs: Vec<(String, handoff::HandoffNote)> = view.iter()
+        .flat_map(|p| handoff::read(&p.path).into_iter()
+            .filter(|note| note.at > since)
+            .map(|note| (p.display_name(), note)))
+        .collect();
+    handoffs.sort_by_key(|(_, note)| note.at);
+    let velocity = MetricsHistory::load(root)?.velocity(view.iter().map(|p| p.id.as_str()), since);
+    
+    save_digest(&view, mode, &velocity, &sprints, &handoffs, out)?;
+    state.record(mode, now);
+    state.save(root)?;
+    println!("Digest written to {}", out.display());
+    if config.report_commit.enabled {
+        let name = out.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_else(|| "DIGEST.md".to_string());
+        commit_reports(root, &config, &[(&name, &std::fs::read_to_string(out)?)]);
+    }
+    Ok(())
+}
+
+/// A root's history log, with a warning for each entry that cannot be read
+fn load_history_log(root: &Path) -> Result<Vec<StatusCache>> {
+    let (entries, skipped) = StatusCache::load_log(root)?;
+    for entry in skipped {
+        eprintln!("Warning: skipping {} line {}: {}", root.join(".skm/history.jsonl").display(), entry.line, entry.error);
+    }
+    Ok(entries)
+}
+
+/// Per-sprint rollups from a root's history log, optionally for a single project
+fn sprint_rollups(root: &Path, sprint: &SprintConfig, project: Option<&str>) -> Result<Vec<SprintRollup>> {
+    let mut log = Vec::new();
+    for entry in load_history_log(root)? {
+        let mut portfolio: PortfolioStatus = serde_json::from_value(entry.data)?;
+        if let Some(id) = project {
+            portfolio.projects.retain(|p| p.id == id || p.display_name() == id);
+        }
+        log.push(portfolio);
+    }
+    log.sort_by_key(|portfolio| portfolio.generated_at);
+    Ok(analyzer::sprint::sprint_rollups(&log, sprint))
+}
+
+/// Print the sprint-over-sprint table for the configured cadence
+fn show_sprints(root_arg: Option<&str>) -> Result<()> {
+    let config = GlobalConfig::load()?;
+    let sprint = config.sprint.as_ref()
+        .ok_or_else(|| anyhow::anyhow!("No sprint cadence configured; add [sprint] start and length_days to the config"))?;
+    let root = resolve_root(root_arg, &config)?;
+    let sprints = sprint_rollups(&root, sprint, None)?;
+    if sprints.is_empty() {
+        println!("No scans logged since sprint 1 started ({})", sprint.start);
+        return Ok(());
+    }
+    
+    let mut out = std::io::stdout().lock();
+    write_sprint_table(&sprints, None, &mut out)?;
+    Ok(())
+}
+
+/// Print the mean time to address each type of human requirement over the
+/// last `days`, from the episodes scans record in .skm/metrics.json
+fn show_analytics(root_arg: Option<&str>, days: u32, json_output: bool) -> Result<()> {
+    let config = GlobalConfig::load()?;
+    let root = resolve_root(root_arg, &config)?;
+    let (cache, _) = StatusCache::load_migrated(&root)?
+        .ok_or_else(|| anyhow::anyhow!("No status cache in {}; run `skm scan` first", root.display()))?;
+    let portfolio: PortfolioStatus = serde_json::from_value(cache.data)?;
+    
+    // Each root keeps the history of its own projects
+    let mut histories: BTreeMap<PathBuf, MetricsHistory> = BTreeMap::new();
+    for project in &portfolio.projects {
+        let meta_root = meta_root_for(project, &config, &root);
+        if !histories.contains_key(&meta_root) {
+            histories.insert(meta_root.clone(), MetricsHistory::load(&meta_root)?);
+        }
+    }
+    let episodes: Vec<_> = portfolio.projects.iter()
+        .flat_map(|project| histories[&meta_root_for(project, &config, &root)].requirements(&project.id))
+        .collect();
+    let now = Utc::now();
+    let stats = requirements::time_to_address(episodes, now - chrono::Duration::days(days as i64), now);
+    
+    if json_output {
+        let doc = serde_json::json!({ "days": days, "requirements": stats });
+        println!("{}", serde_json::to_string_pretty(&doc)?);
+        return Ok(());
+    }
+    if stats.is_empty() {
+        println!("No human requirements recorded in the last {} days; `skm scan` records them", days);
+        return Ok(());
+    }
+    println!("Time to address human requirements, last {} days:", days);
+    println!();
+    write_time_to_address(&stats, &mut std::io::stdout().lock())?;
+    Ok(())
+}
+
+/// Print a project's stage timeline, recorded by scans in .skm/metrics.json
+fn show_history(root_arg: Option<&str>, id: &str, json_output: bool) -> Result<()> {
+    let config = GlobalConfig::load()?;
+    let root = resolve_root(root_arg, &config)?;
+    let (cache, _) = StatusCache::load_migrated(&root)?
+        .ok_or_else(|| anyhow::anyhow!("No status cache in {}; run `skm scan` first", root.display()))?;
+    let portfolio: PortfolioStatus = serde_json::from_value(cache.data)?;
+    let project = portfolio.projects.iter()
+        .find(|p| p.id == id || p.display_name() == id)
+        .ok_or_else(|| anyhow::anyhow!("Project not found: {}", id))?;
+    
+    let history = MetricsHistory::load(&root)?;
+    let timeline = history.timeline(&project.id);
+    let now = Utc::now();
+    // Each stay lasts until the next transition; the last one is still running
+    let stays: Vec<_> = timeline.iter().enumerate()
+        .map(|(i, entered)| (entered, timeline.get(i + 1).map(|next| next.at)))
+        .collect();
+    
+    if json_output {
+        let stages: Vec<_> = stays.iter()
+            .map(|(entered, until)| serde_json::json!({
+                "stage": entered.stage,
+                "at": entered.at,
+                "until": until,
+                "seconds": (until.unwrap_or(now) - entered.at).num_seconds(),
+            }))
+            .collect();
+        let doc = serde_json::json!({ "project": project.display_name(), "stages": stages });
+        println!("{}", serde_json::to_string_pretty(&doc)?);
+        return Ok(());
+    }
+    
+    if timeline.is_empty() {
+        println!("No stage history for {} yet; `skm scan` records it", project.display_name());
+        return Ok(());
+    }
+    let path: Vec<&str> = timeline.iter().map(|entered| entered.stage.as_str()).collect();
+    println!("{}: {}", project.display_name(), path.join(&format!(" {} ", style::icon("→", "then"))));
+    for (entered, until) in stays {
+        let duration = timefmt::humanize_duration(until.unwrap_or(now) - entered.at);
+        println!("  {}  {:<10} {}{}", timefmt::minutes(entered.at), entered.stage.as_str(), duration,
+            if until.is_none() { " so far" } else { "" });
+    }
+    Ok(())
+}
+
+/// Table columns and order from `--columns` and `--sort-by`, defaults for the ones not given
+fn table_options(columns: Option<&str>, sort_by: Option<&str>, color: bool) -> Result<TableOptions> {
+    Ok(TableOptions {
+        columns: columns.map(TableColumn::parse_list).transpose()?.unwrap_or_else(|| DEFAULT_COLUMNS.to_vec()),
+        sort: sort_by.map(str::parse).transpose()?.unwrap_or_default(),
+        color,
+    })
+}
+
+fn explain_priority(root_arg: Option<&str>, id: &str, json_output: bool) -> Result<()> {
+    let config = GlobalConfig::load()?;
+    let root = resolve_root(root_arg, &config)?;
+    let (cache, _) = StatusCache::load_migrated(&root)?
+        .ok_or_else(|| anyhow::anyhow!("No status cache in {}; run `skm scan` first", root.display()))?;
+    let portfolio: PortfolioStatus = serde_json::from_value(cache.data)?;
+    let project = portfolio.projects.iter()
+        .find(|p| p.id == id || p.display_name() == id)
+        .ok_or_else(|| anyhow::anyhow!("Project not found: {}", id))?;
+
+    if json_output {
+        let doc = serde_json::json!({
+            "project": project.display_name(),
+            "priority": project.priority,
+            "requires_human": project.requires_human,
+            "breakdown": project.priority_breakdown,
+        });
+        println!("{}", serde_json::to_string_pretty(&doc)?);
+        return Ok(());
+    }
+    write_priority_explanation(project, &mut std::io::stdout())?;
+    Ok(())
+}
+
+/// Write a report of the current portfolio, or of the one logged nearest before `as_of`
+fn generate_report(
+    root_arg: Option<&str>,
+    out: Option<&Path>,
+    format: &str,
+    table: &TableOptions,
+    as_of: Option<DateTime<Utc>>,
+    only: Option<&Filter>,
+    conditions: &[FieldFilter],
+) -> Result<()> {
+    let root = &resolve_root(root_arg, &GlobalConfig::load()?)?;
+    let cache = match as_of {
+        Some(at) => StatusCache::load_as_of(root, at)?
+            .ok_or_else(|| anyhow::anyhow!("No portfolio logged in {} at or before {}", root.display(), timefmt::minutes(at)))?,
+        None => StatusCache::load_migrated(root)?
+            .map(|(cache, _)| cache)
+            .ok_or_else(|| anyhow::anyhow!("No status cache in {}; run `skm scan` first", root.display()))?,
+    };
+    let portfolio: PortfolioStatus = serde_json::from_value(cache.data)?;
+    let mut view = PortfolioView::new(&portfolio).filter_where(conditions);
+    if let Some(only) = only {
+        view = view.filter(|p| only.matches(p));
+    }
+    
+    // Only tables are printed when no file is given
+    let Some(out) = out else {
+        write_table_view(&view, table, &mut std::io::stdout().lock())?;
+        return Ok(());
+    };
+    if let Some(parent) = out.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    match format {
+        "md" => {
+            let mut file = std::io::BufWriter::new(std::fs::File::create(out)?);
+            write_markdown_view(&view, &mut file)?;
+            std::io::Write::flush(&mut file)?;
+        }
+        "html" => {
+            let mut file = std::io::BufWriter::new(std::fs::File::create(out)?);
+            write_html_view(&view, &mut file)?;
+            std::io::Write::flush(&mut file)?;
+        }
+        "json" => std::fs::write(out, serde_json::to_string_pretty(&view)?)?,
+        "csv" | "tsv" => {
+            let delimited = if format == "csv" { Delimited::Csv } else { Delimited::Tsv };
+            let mut file = std::io::BufWriter::new(std::fs::File::create(out)?);
+            write_delimited_view(&view, delimited, &mut file)?;
+            std::io::Write::flush(&mut file)?;
+        }
+        "table" => {
+            let mut file = std::io::BufWriter::new(std::fs::File::create(out)?);
+            write_table_view(&view, table, &mut file)?;
+            std::io::Write::flush(&mut file)?;
+        }
+        other => anyhow::bail!("Unknown report format: {} (expected md, html, json, csv, tsv or table)", other),
+    }
+    
+    match as_of {
+        Some(_) => println!("Report of the portfolio as of {} written to {}",
+            timefmt::minutes(portfolio.generated_at), out.display()),
+        None => println!("Report written to {}", out.display()),
+    }
+    Ok(())
+}
+
+/// Render the static site, or push the markdown report to a wiki page
+///
+/// Wiki pages are only updated when the report differs from the one last
+/// pushed there, unless `force` is set.
+fn publish(root_arg: Option<&str>, target: &str, out: &Path, force: bool, conditions: &[FieldFilter]) -> Result<()> {
+    let config = GlobalConfig::load()?;
+    let root = &resolve_root(root_arg, &config)?;
+    let cache = StatusCache::load_migrated(root)?
+        .map(|(cache, _)| cache)
+        .ok_or_else(|| anyhow::anyhow!("No status cache in {}; run `skm scan` first", root.display()))?;
+    let portfolio: PortfolioStatus = serde_json::from_value(cache.data)?;
+    let view = PortfolioView::new(&portfolio).filter_where(conditions);
+
+    if target == "site" {
+        let log = load_history_log(root)?
+            .into_iter()
+            .map(|entry| serde_json::from_value(entry.data))
+            .collect::<serde_json::Result<Vec<PortfolioStatus>>>()?;
+        let summary = publish_site(&view, &log, out)?;
+        println!("Site written to {}: {} file(s) updated, {} unchanged, {} removed",
+            out.display(), summary.written, summary.unchanged, summary.removed);
+        return Ok(());
+    }
+
+    let publisher = publish::publisher(target, &config.publish, &config.retry)?;
+    let mut report = Vec::new();
+    write_markdown_view(&view, &mut report)?;
+    let report = String::from_utf8(report)?;
+    let hash = publish::content_hash(&report);
+    let mut state = PublishState::load(root)?;
+    if !force && state.unchanged(&publisher.key(), &hash) {
+        println!("{} is up to date", publisher.key());
+        return Ok(());
+    }
+    publisher.publish(&publish::parse_markdown(&report))?;
+    state.record(publisher.key(), hash);
+    state.save(root)?;
+    println!("Published the status report to {}", publisher.key());
+    Ok(())
+}
+
+/// Parse repeated `--where` conditions
+fn parse_conditions(conditions: &[String]) -> Result<Vec<FieldFilter>> {
+    Ok(conditions.iter().map(|c| c.parse()).collect::<skm::Result<_>>()?)
+}
+
+/// Parse `--as-of`: a date means the end of that day (UTC), otherwise an RFC 3339 timestamp
+fn parse_as_of(value: &str) -> Result<DateTime<Utc>> {
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        let end_of_day = date.and_hms_opt(23, 59, 59).expect("valid time of day");
+        return Ok(end_of_day.and_utc());
+    }
+    DateTime::parse_from_rfc3339(value)
+        .map(|at| at.with_timezone(&Utc))
+        .map_err(|_| anyhow::anyhow!("Invalid --as-of {}: expected YYYY-MM-DD or an RFC 3339 timestamp", value))
+}
+
+/// Analyze the project around the current directory without a portfolio scan
+///
+/// Project metadata comes from the nearest `.skm` above the project, so
+/// `impact` and git settings still apply; nothing is written.
+fn show_here(json_output: bool) -> Result<()> {
+    let cwd = std::env::current_dir()?.canonicalize()?;
+    let project_path = find_enclosing_project(&cwd)
+        .ok_or_else(|| anyhow::anyhow!("Not inside a Spec-Kit project (no .specify or specs found above {})", cwd.display()))?;
+    let root = project_path.ancestors()
+        .find(|dir| dir.join(".skm").is_dir())
+        .unwrap_or(&project_path);
+    
+    let ctx = ScanContext::load(root, ScanOptions::default())?;
+    let (project, _) = pipeline::process_project(&project_path, &ctx)?;
+    let mut tasks = match &project.artifacts.tasks {
+        Some(file) => parse_task_items(&std::fs::read_to_string(&file.path)?),
+        None => Vec::new(),
+    };
+    let overrides = ctx.config.for_project(&project.path)?;
+    Variables::for_project(&project, overrides.as_ref().unwrap_or(&ctx.config))
+        .resolve_all(tasks.iter_mut().map(|task| &mut task.text));
+    
+    if json_output {
+        let here = serde_json::json!({ "project": project, "tasks": tasks });
+        println!("{}", serde_json::to_string_pretty(&here)?);
+        return Ok(());
+    }
+    
+    println!("=== {} ===", project.id);
+    println!("Path: {}", project.path.display());
+    println!("Stage: {} - {}", project.stage, stage_description(&project.stage));
+    if let Some(since) = project.stage_since {
+        println!("In stage for: {}", timefmt::humanize_duration(Utc::now() - since));
+    }
+    println!("Priority: {:.1}{}", project.priority, if project.pinned { " (pinned)" } else { "" });
+    if let Some(season) = &project.season {
+        println!("Season: {} (active {})", if season.active { "in season" } else { "paused" }, season.window);
+    }
+    println!("Updated: {}", timefmt::ago(project.updated));
+    if project.git.is_repo {
+        println!("Git: {} ({})",
+            project.git.branch.as_deref().unwrap_or("unknown"),
+            if project.git.clean { "clean" } else { "uncommitted changes" });
+    }
+    if let Some(github) = &project.git.github {
+        println!("GitHub: {}", format_github(github));
+    }
+    if !project.requires_human.is_empty() {
+        println!("Requires human: {:?}", project.requires_human);
+    }
+    if !project.capabilities.is_empty() {
+        let names: Vec<&str> = project.capabilities.iter().map(|c| c.as_str()).collect();
+        println!("Environments: {}", names.join(", "));
+    }
+    if let Some(debt) = project.code_debt {
+        println!("Code debt: {} TODO/FIXME comments", debt);
+    }
+    if let Some(audit) = &project.audit {
+        match &audit.error {
+            Some(error) => println!("Audit: {}", error),
+            None if audit.advisories.is_empty() => println!("Audit: {} known vulnerabilities ({})", audit.vulnerabilities, audit.tool),
+            None => println!("Audit: {} known vulnerabilities ({}): {}", audit.vulnerabilities, audit.tool, audit.advisories.join(", ")),
+        }
+    }
+    if let Some(freshness) = &project.freshness {
+        println!("Dependencies: {}/{} a major version behind ({})",
+            freshness.major_behind, freshness.direct, freshness.lockfile);
+        for dependency in &freshness.outdated {
+            println!("  {} {} → {}", dependency.name, dependency.current, dependency.latest);
+        }
+    }
+    println!("License: {}", project.license.compliance());
+    for conflict in &project.license.conflicts {
+        println!("  incompatible: {}", conflict);
+    }
+    if project.constitution_inherited {
+        println!("Constitution: inherited from {}", root.join(".skm/constitution.md").display());
+    }
+    if !project.principles.is_empty() {
+        println!("Principles: {}", format_principles(&project.principles));
+    }
+    if !project.custom.is_empty() {
+        println!("Custom: {}", format_custom_fields(&project.custom));
+    }
+    println!();
+    
+    println!("Next: {}", project.next.description);
+    println!("  {}", project.next.command);
+    for launch in &project.next.launch {
+        println!("  {}: {}", launch.agent.label(), launch.target);
+    }
+    
+    if !project.open_questions.is_empty() {
+        println!();
+        println!("Open questions ({}):", project.open_questions.len());
+        for question in &project.open_questions {
+            println!("  {}:{} {}", question.source, question.line, question.text);
+        }
+    }
+    
+    println!();
+    println!("Tasks: {}/{} completed", project.tasks.completed, project.tasks.total);
+    for task in tasks.iter().filter(|t| !t.done) {
+        let marker = if task.blocked { style::icon("🚫", "[blocked]") } else { "[ ]" };
+        println!("  {} {}", marker, task.text);
+    }
+    Ok(())
+}
+
+fn register_project(path: &Path, check: bool, warm: bool, ignore: bool) -> Result<()> {
+    let project = path.canonicalize().ok().and_then(|p| find_enclosing_project(&p));
+    let mut registry = Registry::load()?;
+    
+    if check {
+        if let Some(project) = project
+            && !registry.is_registered(&project)
+            && !registry.is_ignored(&project)
+            && !in_scanned_portfolio(&project) {
+            println!("{}", project.display());
+        }
+        return Ok(());
+    }
+    
+    let project = project
+        .ok_or_else(|| anyhow::anyhow!("Not inside a Spec-Kit project: {}", path.display()))?;
+    
+    if ignore {
+        registry.ignore(&project);
+        registry.save()?;
+        println!("skm will not offer to track {} again", project.display());
+        return Ok(());
+    }
+    
+    if registry.register(&project) {
+        registry.save()?;
+        println!("Registered {}", project.display());
+    } else {
+        println!("Already registered: {}", project.display());
+    }
+    
+    if warm {
+        // Detached so the shell hook returns immediately
+        std::process::Command::new(std::env::current_exe()?)
+            .arg("scan")
+            .arg("--root")
+            .arg(&project)
+            .stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .spawn()?;
+    }
+    Ok(())
+}
+
+/// Whether a project already appears in the status cache of a scan root above it
+fn in_scanned_portfolio(project: &Path) -> bool {
+    let Some(parent) = project.parent() else { return false };
+    let Ok(Some((root, cache))) = StatusCache::find_upwards(parent) else { return false };
+    serde_json::from_value::<PortfolioStatus>(cache.data)
+        .is_ok_and(|portfolio| find_project(&portfolio, &root, project).is_some())
+}
+
+/// Scaffold Spec-Kit in a directory, then register it unless a scanned portfolio already covers it
+fn init_project(path: &Path, backend: Option<&str>, no_register: bool) -> Result<()> {
+    let config = GlobalConfig::load()?;
+    let backend = match backend {
+        Some(name) => name.parse()?,
+        None => config.init.backend,
+    };
+    let scaffolder = scaffold::init_project(path, &config.init, backend)?;
+    let project = path.canonicalize()?;
+    println!("Initialized Spec-Kit in {} using {}", project.display(), scaffolder.as_str());
+    
+    if !no_register && !in_scanned_portfolio(&project) {
+        let mut registry = Registry::load()?;
+        if registry.register(&project) {
+            registry.save()?;
+            println!("Registered {}", project.display());
+        }
+    }
+    println!("Next: skm new-feature \"<what to build>\" --path {}", path.display());
+    Ok(())
+}
+
+/// Seed Spec-Kit artifacts for an existing repository and register it
+fn adopt_project(path: &Path, no_register: bool) -> Result<()> {
+    let adoption = scaffold::adopt_project(path)?;
+    let project = path.canonicalize()?;
+    println!("Adopted {}", project.display());
+    println!("Spec: {} ({} README sections)", adoption.spec.display(), adoption.headings);
+    println!("Tasks: {} ({} TODO/FIXME comments)", adoption.tasks.display(), adoption.todos);
+    
+    if !no_register && !in_scanned_portfolio(&project) {
+        let mut registry = Registry::load()?;
+        if registry.register(&project) {
+            registry.save()?;
+            println!("Registered {}", project.display());
+        }
+    }
+    println!("Everything generated is marked {}; review it, then run /speckit.constitution", scaffold::NEEDS_REVIEW);
+    Ok(())
+}
+
+fn new_feature(path: Option<&str>, description: &str, short_name: Option<&str>, backend: Option<&str>) -> Result<()> {
+    let config = GlobalConfig::load()?;
+    let backend = match backend {
+        Some(name) => name.parse()?,
+        None => config.init.backend,
+    };
+    let start = match path {
+        Some(path) => PathBuf::from(path),
+        None => std::env::current_dir()?,
+    }.canonicalize()?;
+    let project = find_enclosing_project(&start)
+        .ok_or_else(|| anyhow::anyhow!("Not inside a Spec-Kit project: {}; run `skm init` first", start.display()))?;
+    
+    let feature = scaffold::new_feature(&project, description, short_name, backend)?;
+    println!("Created feature {} using {}", feature.id, feature.scaffolder.as_str());
+    println!("Spec: {}", feature.spec.display());
+    match &feature.branch {
+        Some(branch) => println!("Branch: {}", branch),
+        None => println!("Branch: none (not a git repository)"),
+    }
+    Ok(())
+}
+
+fn manage_remotes(action: RemoteAction) -> Result<()> {
+    let config = GlobalConfig::load()?;
+    let mut registry = Registry::load()?;
+    
+    match action {
+        RemoteAction::Add { url, name } => {
+            let name = name.or_else(|| remote::name_from_url(&url))
+                .ok_or_else(|| anyhow::anyhow!("Cannot derive a name from {}; pass --name", url))?;
+            remote::validate_name(&name)?;
+            if !registry.add_remote(&name, &url) {
+                anyhow::bail!("A remote named {} is already tracked", name);
+            }
+            let path = remote::sync(&name, &url, &config.retry)?;
+            registry.mark_synced(&name);
+            registry.save()?;
+            println!("Tracking {} ({}), cloned to {}", name, url, path.display());
+        }
+        RemoteAction::List => {
+            if registry.remotes.is_empty() {
+                println!("No remote projects tracked.");
+            }
+            for remote in &registry.remotes {
+                let synced = remote.synced_at
+                    .map(timefmt::minutes)
+                    .unwrap_or_else(|| "never".to_string());
+                println!("{} - {} (synced {})", remote.name, remote.url, synced);
+            }
+        }
+        RemoteAction::Remove { name } => {
+            if !registry.remove_remote(&name) {
+                anyhow::bail!("No remote named {}", name);
+            }
+            remote::remove_checkout(&name)?;
+            registry.save()?;
+            println!("Stopped tracking {}", name);
+        }
+        RemoteAction::Sync { name } => {
+            let targets: Vec<_> = registry.remotes.iter()
+                .filter(|r| name.as_deref().is_none_or(|n| n == r.name))
+                .map(|r| (r.name.clone(), r.url.clone()))
+                .collect();
+            if let Some(name) = &name && targets.is_empty() {
+                anyhow::bail!("No remote named {}", name);
+            }
+            
+            let mut failed = 0;
+            for (name, url) in targets {
+                match remote::sync(&name, &url, &config.retry) {
+                    Ok(_) => {
+                        registry.mark_synced(&name);
+                        println!("Synced {}", name);
+                    }
+                    Err(e) => {
+                        failed += 1;
+                        eprintln!("Failed to sync {}: {}", name, e);
+                    }
+                }
+            }
+            registry.save()?;
+            if failed > 0 {
+                anyhow::bail!("{} remote(s) failed to sync", failed);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Analyze the cached clones of tracked remote projects
+///
+/// Scans never touch the network; clones are updated by `skm remote sync`.
+fn scan_remotes(ctx: &ScanContext, format: ScanFormat) -> Result<RootScan> {
+    let registry = Registry::load()?;
+    let mut scan = RootScan::default();
+    
+    for tracked in &registry.remotes {
+        let path = remote::checkout_path(&tracked.name)?;
+        scan.directories += 1;
+        if !path.is_dir() {
+            let error = skm::ScanError {
+                path,
+                phase: ScanPhase::Discover,
+                kind: skm::ScanErrorKind::NotFound,
+                message: format!("remote {} has no clone; run `skm remote sync`", tracked.name),
+            };
+            if format == ScanFormat::Jsonl {
+                emit_record("error", &error);
+            }
+            scan.errors.push(error);
+            continue;
+        }
+        
+        match pipeline::process_project(&path, ctx) {
+            Ok((mut project, timing)) => {
+                project.remote = Some(tracked.url.clone());
+                match format {
+                    ScanFormat::Text => println!("Found: {} (remote) [{}] Priority: {:.1}", tracked.url, project.stage, project.priority),
+                    ScanFormat::Jsonl => emit_project(&project, None, false),
+                }
+                scan.timings.push(timing);
+                let field_errors = pipeline::custom_field_errors(&project, ctx);
+                if format == ScanFormat::Jsonl {
+                    field_errors.iter().for_each(|e| emit_record("error", e));
+                }
+                scan.errors.extend(field_errors);
+                scan.projects.push(project);
+            }
+            Err(e) => {
+                if format == ScanFormat::Jsonl {
+                    emit_record("error", &e);
+                }
+                scan.errors.push(e);
+            }
+        }
+    }
+    
+    Ok(scan)
+}
+
+/// Print what the tasks parser recognizes on every line of a file
+fn parse_tasks(file: &Path, json_output: bool) -> Result<()> {
+    let content = std::fs::read_to_string(file)?;
+    let lines: Vec<(usize, &str, Option<skm::TaskItem>)> = content.lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line, parse_task_line(line, i + 1)))
+        .collect();
+    let tasks: Vec<&skm::TaskItem> = lines.iter().filter_map(|(_, _, task)| task.as_ref()).collect();
+    let count = |f: fn(&skm::TaskItem) -> bool| tasks.iter().filter(|t| f(t)).count();
+    
+    if json_output {
+        let lines: Vec<_> = lines.iter()
+            .map(|(number, line, task)| serde_json::json!({ "line": number, "content": line, "task": task }))
+            .collect();
+        let parsed = serde_json::json!({
+            "file": file,
+            "lines": lines,
+            "summary": {
+                "total": tasks.len(),
+                "completed": count(|t| t.done),
+                "parallel_marked": count(|t| t.parallel),
+                "blocked": count(|t| t.blocked),
+            },
+        });
+        println!("{}", serde_json::to_string_pretty(&parsed)?);
+        return Ok(());
+    }
+    
+    for (number, line, task) in &lines {
+        match task {
+            Some(task) => println!("{:>4} {} {:<8} {}{}{}",
+                number,
+                if task.done { style::icon("✔", "[x]") } else { style::icon("☐", "[ ]") },
+                task.format.as_str(),
+                if task.parallel { "[P] " } else { "" },
+                if task.blocked { "[BLOCKED] " } else { "" },
+                task.text),
+            None if line.trim().is_empty() => println!("{:>4}", number),
+            None => println!("{:>4} · {:<8} {}", number, "-", line.trim()),
+        }
+    }
+    println!("\n{} tasks, {} completed, {} parallel, {} blocked",
+        tasks.len(), count(|t| t.done), count(|t| t.parallel), count(|t| t.blocked));
+    Ok(())
+}
+
+/// Time scans of a portfolio generated by the testkit
+///
+/// Each run processes every project from scratch, without caches; the
+/// median run and the slowest project of the last one are reported.
+#[cfg(feature = "testkit")]
+fn bench(projects: usize, runs: usize, seed: u64, skip_git: bool, keep: Option<&str>) -> Result<()> {
+    use skm::testkit::{self, GitState, SyntheticPortfolio};
+
+    let skip_git = skip_git || !cfg!(feature = "git");
+    let mut specs = testkit::varied(projects, seed);
+    if skip_git {
+        specs.iter_mut().for_each(|spec| spec.git = GitState::None);
+    }
+    let start = Instant::now();
+    let portfolio = match keep {
+        Some(dir) => SyntheticPortfolio::create(Path::new(dir), &specs)?,
+        None => SyntheticPortfolio::temp("bench", &specs)?,
+    };
+    println!("Generated {} projects in {} ({}ms)", specs.len(), portfolio.root().display(), start.elapsed().as_millis());
+
+    let options = ScanOptions { git: if skip_git { GitMode::Skip } else { GitMode::Sync }, incremental: false };
+    let mut times = Vec::new();
+    let mut last = None;
+    for run in 1..=runs.max(1) {
+        let scan = portfolio.scan(options.clone())?;
+        let stats = &scan.scan_stats;
+        println!("Run {}: {} projects in {}ms (discovery {}ms), {} errors",
+            run, stats.projects_found, stats.scan_time_ms, stats.discover_ms, stats.errors.len());
+        times.push(stats.scan_time_ms);
+        last = Some(scan);
+    }
+    times.sort_unstable();
+    let median = times[times.len() / 2];
+    println!("Median: {}ms, {:.2}ms per project", median, median as f64 / specs.len().max(1) as f64);
+    if let Some(slowest) = last.as_ref().and_then(|scan| scan.scan_stats.slowest.first()) {
+        let (phase, phase_ms) = slowest.dominant_phase();
+        println!("Slowest project: {} ({}ms, {} {}ms)", slowest.id, slowest.total_ms, phase, phase_ms);
+    }
+    if keep.is_some() {
+        portfolio.keep();
+    }
+    Ok(())
+}
+
+/// Diagnose the environment and the cached portfolio
+///
+/// Reads the config without requiring it to load, so a broken file is
+/// reported rather than fatal. Fails when any check finds an error.
+fn run_doctor(root_arg: Option<&str>, json: bool) -> Result<()> {
+    let (mut checks, config) = doctor::check_config_file(&GlobalConfig::config_path()?);
+    let config = config.unwrap_or_default();
+    let root = resolve_root(root_arg, &config)?;
+    let portfolio: Option<PortfolioStatus> = match StatusCache::load_migrated(&root) {
+        Ok(cache) => cache.map(|(cache, _)| serde_json::from_value(cache.data)).transpose()?,
+        Err(e) => {
+            eprintln!("Cannot read the status cache in {}: {}", root.display(), e);
+            None
+        }
+    };
+    for project in portfolio.iter().flat_map(|p| &p.projects) {
+        checks.extend(doctor::check_project_config(&config, &project.display_name(), &project.path));
+    }
+    checks.extend(doctor::check_services(&config));
+    checks.extend(doctor::check_tools());
+    checks.push(doctor::check_writable("config directory", &GlobalConfig::config_dir()?));
+    checks.push(doctor::check_writable("scan root", &root.join(".skm")));
+    for root_config in &config.roots {
+        checks.push(doctor::check_writable(&format!("root {}", root_config.name), &root_config.resolved_path().join(".skm")));
+    }
+    checks.extend(doctor::check_integrations(&config));
+    checks.extend(doctor::check_portfolio(portfolio.as_ref()));
+    checks.extend(doctor::check_scan_time(portfolio.as_ref()));
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&checks)?);
+    } else {
+        let mut area = "";
+        for check in &checks {
+            if check.area != area {
+                if !area.is_empty() {
+                    println!();
+                }
+                area = check.area;
+                println!("{}{}", area[..1].to_uppercase(), &area[1..]);
+            }
+            println!("  {} {}: {}", check.icon(), check.name, check.detail);
+            if let Some(fix) = check.fix.as_ref().filter(|_| check.status >= CheckStatus::Warning) {
+                println!("     {} {}", style::icon("→", "fix:"), fix);
+            }
+        }
+    }
+    let count = |status| checks.iter().filter(|c| c.status == status).count();
+    let (errors, warnings) = (count(CheckStatus::Error), count(CheckStatus::Warning));
+    if !json {
+        println!();
+        match (errors, warnings) {
+            (0, 0) => println!("{}", style::decorate("✅", "No problems found")),
+            (0, _) => println!("{}", style::decorate("⚠️ ", &format!("{} warning(s)", warnings))),
+            _ => println!("{}", style::decorate("❌", &format!("{} error(s), {} warning(s)", errors, warnings))),
+        }
+    }
+    if errors > 0 {
+        anyhow::bail!("{} check(s) failed", errors);
+    }
+    Ok(())
+}
+
+/// Run the next actions the projects' automation levels (or `auto_approve`
+/// lists) permit, best expected progress per minute first
+///
+/// Actions are estimated from earlier timed runs and planned into the time
+/// budget; the plan is printed with each action's rationale. Dry runs follow `dry_run_default` unless `--execute` or `--dry-run` is
+/// given. Every decision is appended to `.skm/autopilot.log`. With a
+/// sandbox, the projects are copied there first and autopilot runs on the
+/// copies, where actions run unless `--dry-run` is given.
+fn autopilot(
+    root_arg: Option<&str>,
+    id: Option<&str>,
+    execute: bool,
+    dry_run: bool,
+    limit: Option<usize>,
+    budget: Option<Duration>,
+    sandbox: Option<Option<PathBuf>>,
+) -> Result<()> {
+    let config = GlobalConfig::load()?;
+    let mut root = resolve_root(root_arg, &config)?;
+    let mut id = id.map(str::to_string);
+    if let Some(dir) = &sandbox {
+        let dir = dir.clone().unwrap_or_else(|| {
+            std::env::temp_dir().join(format!("skm-sandbox-{}", Utc::now().format("%Y%m%d-%H%M%S")))
+        });
+        id = prepare_sandbox(&config, &root, id.as_deref(), &dir)?;
+        root = dir;
+    }
+    let (cache, _) = StatusCache::load_migrated(&root)?
+        .ok_or_else(|| anyhow::anyhow!("No status cache in {}; run `skm scan` first", root.display()))?;
+    let portfolio: PortfolioStatus = serde_json::from_value(cache.data)?;
+    let dry_run = dry_run || (config.dry_run_default && !execute && sandbox.is_none());
+    
+    let id = id.as_deref();
+    let mut projects: Vec<&skm::Project> = portfolio.projects.iter()
+        .filter(|p| id.is_none_or(|id| p.id == id || p.display_name() == id))
+        .filter(|p| p.stage != Stage::Done)
+        .collect();
+    if let Some(id) = id && projects.is_empty() {
+        anyhow::bail!("Project not found or already done: {}", id);
+    }
+    projects.sort_by(|a, b| a.priority_order(b));
+    
+    let mut guard = lease::acquire(&root, &LeaseScope::Portfolio, "autopilot", chrono::Duration::seconds(lease::DEFAULT_LEASE_TTL_SECS))?;
+    let run_at = Utc::now();
+    let history = actions::read_log(&root);
+    // Each entry is logged as soon as it is decided or its action finishes,
+    // so an interrupted run still leaves a record of what it did
+    let mut entries = Vec::new();
+    
+    // Decide every project first, so the permitted actions can be planned together
+    let mut permitted = Vec::new();
+    let mut stops = Vec::new();
+    let mut profiles = HashMap::new();
+    for project in projects {
+        let meta_root = meta_root_for(project, &config, &root);
+        let meta_store = ProjectMetaStore::load(&meta_root)?;
+        let meta = meta_store.get_project(&project.id);
+        let overrides = config.for_project(&project.path)?;
+        let project_config = overrides.as_ref().unwrap_or(&config);
+        let level = autopilot::levels::effective_level(project_config, meta, &project.stage);
+        let cap = autopilot::levels::stage_cap(project_config, meta, &project.stage);
+        let approved = autopilot::approval::is_auto_approved(meta, &project.next.command);
+        match actions::decide(&project.next, &level, cap.as_ref(), approved, dry_run) {
+            Decision::Skip(reason) => {
+                println!("skip      {}: {} ({})", project.display_name(), project.next.command, reason);
+                record_entry(&root, &mut entries, AutopilotEntry::new(run_at, project, Outcome::Skipped, Some(reason.clone())))?;
+                let stop = match project.next.command.starts_with('/') {
+                    true => Stop::AboveLevel(reason),
+                    false => Stop::NotRunnable,
+                };
+                stops.push((project, stop));
+            }
+            Decision::Run | Decision::DryRun => {
+                permitted.push(project);
+                let agent_command = meta.and_then(|m| m.agent_command.clone());
+                profiles.insert(project.display_name(), (meta_root, agent_command, meta.map(|m| m.exec.clone()).unwrap_or_default()));
+            }
+        }
+    }
+    
+    let plan = autopilot::plan::schedule(&permitted, &history, budget);
+    if !plan.is_empty() {
+        if !entries.is_empty() {
+            println!();
+        }
+        println!("Plan, by expected stage progress per minute{}:", match budget {
+            Some(budget) => format!(" within {} minutes", budget.as_secs() / 60),
+            None => String::new(),
+        });
+    }
+    let mut executed = 0;
+    for (position, planned) in plan.iter().enumerate() {
+        let project = planned.project;
+        let (meta_root, agent_command, profile) = &profiles[&project.display_name()];
+        let limit_reached = limit.is_some_and(|limit| executed >= limit);
+        
+        let entry = match dry_run {
+            _ if !planned.within_budget => {
+                println!("skip      {}: {} (over budget)", project.display_name(), project.next.command);
+                println!("  {}", planned.rationale());
+                AutopilotEntry::new(run_at, project, Outcome::Skipped, Some("over budget".to_string()))
+            }
+            true => {
+                println!("would run {}: {}", project.display_name(), project.next.command);
+                println!("  #{} {}", position + 1, planned.rationale());
+                AutopilotEntry::new(run_at, project, Outcome::Skipped, Some("dry run".to_string()))
+            }
+            false if limit_reached => {
+                println!("skip      {}: {} (limit reached)", project.display_name(), project.next.command);
+                AutopilotEntry::new(run_at, project, Outcome::Skipped, Some("limit reached".to_string()))
+            }
+            false => {
+                let ttl = chrono::Duration::seconds(lease::DEFAULT_LEASE_TTL_SECS);
+                guard.renew(ttl)?;
+                let _project_lease = match lease::acquire(&root, &LeaseScope::Project(project.id.clone()), "autopilot", ttl) {
+                    Ok(held) => held,
+                    Err(e) => {
+                        println!("skip      {}: {} ({})", project.display_name(), project.next.command, e);
+                        record_entry(&root, &mut entries, AutopilotEntry::new(run_at, project, Outcome::Skipped, Some(e.to_string())))?;
+                        continue;
+                    }
+                };
+                println!("run       {}: {}", project.display_name(), project.next.command);
+                println!("  #{} {}", position + 1, planned.rationale());
+                let entry = actions::execute(run_at, project, agent_command.as_deref(), profile, meta_root);
+                executed += 1;
+                if entry.outcome == Outcome::Failed {
+                    stops.push((project, Stop::Failed(entry.reason.clone().unwrap_or_default())));
+                }
+                match entry.outcome {
+                    Outcome::Failed => eprintln!("  {} {}", style::icon("❌", "FAILED"), entry.reason.as_deref().unwrap_or_default()),
+                    _ => println!("  {} ({:.1}s)", style::icon("✅", "done"), entry.duration_ms.unwrap_or(0) as f64 / 1000.0),
+                }
+                if let Some(log) = &entry.log {
+                    println!("  log: {}", log.display());
+                }
+                entry
+            }
+        };
+        record_entry(&root, &mut entries, entry)?;
+    }
+    if !dry_run {
+        let history: Vec<AutopilotEntry> = history.into_iter().chain(entries.iter().cloned()).collect();
+        for (project, stop) in &stops {
+            let note = handoff::compose(project, stop, &history, Utc::now());
+            if handoff::append(&project.path, &note)? {
+                println!("handoff   {}: {}", project.display_name(), project.path.join(".skm/handoff.md").display());
+            }
+        }
+    }
+    guard.release();
+    
+    println!();
+    println!("{} executed, {} skipped, {} failed{}",
+        actions::count(&entries, Outcome::Executed),
+        actions::count(&entries, Outcome::Skipped),
+        actions::count(&entries, Outcome::Failed),
+        if dry_run { " (dry run; pass --execute to run)" } else { "" });
+    if actions::count(&entries, Outcome::Failed) > 0 {
+        anyhow::bail!("{} autopilot action(s) failed", actions::count(&entries, Outcome::Failed));
+    }
+    Ok(())
+}
+
+/// Append an autopilot entry to `.skm/autopilot.log` and keep it for the run's summary
+fn record_entry(root: &Path, entries: &mut Vec<AutopilotEntry>, entry: AutopilotEntry) -> Result<()> {
+    actions::append_log(root, std::slice::from_ref(&entry))?;
+    entries.push(entry);
+    Ok(())
+}
+
+/// Show the next action of a project (or the highest-priority unfinished
+/// one) with the level it is gated at, and run it with `exec`
+///
+/// Gating is the same as autopilot's, except dry_run_default does not
+/// apply: asking for `--exec` is explicit.
+fn next_action(root_arg: Option<&str>, id: Option<&str>, exec: bool, tmux: bool) -> Result<()> {
+    let config = GlobalConfig::load()?;
+    let root = resolve_root(root_arg, &config)?;
+    let (cache, _) = StatusCache::load_migrated(&root)?
+        .ok_or_else(|| anyhow::anyhow!("No status cache in {}; run `skm scan` first", root.display()))?;
+    let portfolio: PortfolioStatus = serde_json::from_value(cache.data)?;
+    
+    let project = match id {
+        Some(id) => portfolio.projects.iter()
+            .find(|p| p.id == id || p.display_name() == id)
+            .ok_or_else(|| anyhow::anyhow!("Project not found: {}", id))?,
+        None => portfolio.projects.iter()
+            .filter(|p| p.stage != Stage::Done)
+            .min_by(|a, b| a.priority_order(b))
+            .ok_or_else(|| anyhow::anyhow!("No unfinished projects in {}", root.display()))?,
+    };
+    
+    let meta_root = meta_root_for(project, &config, &root);
+    let meta_store = ProjectMetaStore::load(&meta_root)?;
+    let meta = meta_store.get_project(&project.id);
+    let overrides = config.for_project(&project.path)?;
+    let project_config = overrides.as_ref().unwrap_or(&config);
+    let level = autopilot::levels::effective_level(project_config, meta, &project.stage);
+    let cap = autopilot::levels::stage_cap(project_config, meta, &project.stage);
+    let approved = autopilot::approval::is_auto_approved(meta, &project.next.command);
+    
+    println!("{} ({}): {}", project.display_name(), project.stage, project.next.command);
+    println!("  {}", project.next.description);
+    println!("  risk {:?}, automation level {:?}{}{}", project.next.risk_level, level,
+        match &cap {
+            Some(cap) => format!(" (stage {} capped at {:?})", project.stage, cap),
+            None => String::new(),
+        },
+        if approved { ", auto-approved" } else { "" });
+    
+    let decision = actions::decide(&project.next, &level, cap.as_ref(), approved, !exec);
+    match decision {
+        Decision::Skip(reason) if exec => anyhow::bail!("Not running {}: {}", project.next.command, reason),
+        Decision::Skip(reason) => println!("  needs a person: {}", reason),
+        Decision::DryRun => println!("  permitted; run it with `skm next {} --exec`", project.display_name()),
+        Decision::Run if tmux => run_next_in_tmux(project, meta)?,
+        Decision::Run => {
+            let _lease = project_lease(&root, project, "next")?;
+            let profile = meta.map(|m| m.exec.clone()).unwrap_or_default();
+            let entry = actions::execute(Utc::now(), project, meta.and_then(|m| m.agent_command.as_deref()), &profile, &meta_root);
+            if let Some(log) = &entry.log {
+                println!("  log: {}", log.display());
+            }
+            if entry.outcome == Outcome::Failed {
+                anyhow::bail!("{} failed: {}", project.next.command, entry.reason.unwrap_or_default());
+            }
+            println!("  {} ({:.1}s)", style::icon("✅", "done"), entry.duration_ms.unwrap_or(0) as f64 / 1000.0);
+        }
+    }
+    Ok(())
+}
+
+/// Start the project's agent on its next action in a window of the
+/// project's tmux session, then switch to it
+#[cfg(feature = "session")]
+fn run_next_in_tmux(project: &skm::Project, meta: Option<&skm::meta::state::ProjectMeta>) -> Result<()> {
+    let name = tmux::session_name(&project.display_name());
+    let line = actions::agent_command_line(meta.and_then(|m| m.agent_command.as_deref()), &project.next.command);
+    tmux::run_in_window(&name, &project.path, "next", &skm::exec::wrap_in_environment(&project.capabilities, &line))?;
+    println!("  started in tmux session {}: {}", name, line);
+    Ok(tmux::attach(&name)?)
+}
+
+#[cfg(not(feature = "session"))]
+fn run_next_in_tmux(_project: &skm::Project, _meta: Option<&skm::meta::state::ProjectMeta>) -> Result<()> {
+    disabled("session")
+}
+
+/// Copy the unfinished projects of `root` (or just `id`) into a sandbox
+/// directory with their metadata and scan the copies there
+///
+/// Returns the id the selected project has in the sandbox.
+fn prepare_sandbox(config: &GlobalConfig, root: &Path, id: Option<&str>, dir: &Path) -> Result<Option<String>> {
+    let (cache, _) = StatusCache::load_migrated(root)?
+        .ok_or_else(|| anyhow::anyhow!("No status cache in {}; run `skm scan` first", root.display()))?;
+    let portfolio: PortfolioStatus = serde_json::from_value(cache.data)?;
+    let selected: Vec<&skm::Project> = portfolio.projects.iter()
+        .filter(|p| id.is_none_or(|id| p.id == id || p.display_name() == id))
+        .filter(|p| p.stage != Stage::Done && p.remote.is_none())
+        .collect();
+    if let Some(id) = id && selected.is_empty() {
+        anyhow::bail!("Project not found or already done: {}", id);
+    }
+    
+    std::fs::create_dir_all(dir)?;
+    if let (Ok(dir), Ok(root)) = (dir.canonicalize(), root.canonicalize()) && dir.starts_with(&root) {
+        // Only removes the directory when this call created it empty
+        let _ = std::fs::remove_dir(&dir);
+        anyhow::bail!("Sandbox {} is inside {}, whose scans would pick up the copies", dir.display(), root.display());
+    }
+    
+    let mut with_meta = Vec::new();
+    for project in &selected {
+        let store = ProjectMetaStore::load(&meta_root_for(project, config, root))?;
+        with_meta.push((*project, store.get_project(&project.id).cloned()));
+    }
+    let copies = autopilot::sandbox::prepare(dir, &with_meta)?;
+    println!("Sandbox: {}", dir.display());
+    for copy in &copies {
+        println!("  {} {}", if copy.reused { "kept  " } else { "copied" }, copy.original.display());
+        for link in &copy.skipped {
+            println!("         skipped symlink {} (points outside the project)", link.display());
+        }
+    }
+    
+    let start_time = Instant::now();
+    let mut ctx = ScanContext::load(dir, ScanOptions::default())?;
+    let scan = scan_root(dir, &mut ctx, None, None)?;
+    let (portfolio, fingerprints) = scan.into_portfolio(start_time, &[(root_label(dir), dir)], &ctx.config.workflow);
+    write_portfolio(dir, &portfolio, &fingerprints)?;
+    println!();
+    Ok(selected.first().filter(|_| id.is_some()).map(|p| autopilot::sandbox::copy_name(p)))
+}
+
+/// Show leases, or force-release one or all stale ones
+fn manage_locks(root_arg: Option<&str>, release: Option<&str>, release_stale: bool) -> Result<()> {
+    let root = resolve_root(root_arg, &GlobalConfig::load()?)?;
+    
+    if let Some(name) = release {
+        let scope: LeaseScope = name.parse()?;
+        if lease::force_release(&root, &scope)? {
+            println!("Released {}", scope.name());
+        } else {
+            println!("{} is not locked", scope.name());
+        }
+        return Ok(());
+    }
+    
+    let now = Utc::now();
+    let leases = lease::list(&root)?;
+    if leases.is_empty() {
+        println!("No locks held in {}", root.display());
+        return Ok(());
+    }
+    
+    for (name, held) in leases {
+        let stale = held.as_ref().is_none_or(|l| l.is_expired(now));
+        if release_stale && stale {
+            lease::force_release(&root, &name.parse()?)?;
+            println!("Released stale {}", name);
+            continue;
+        }
+        match held {
+            Some(l) => println!("{} - {} (pid {} on {}) since {}, {} {}",
+                name, l.holder, l.pid, l.host,
+                timefmt::minutes(l.acquired_at),
+                if stale { "expired" } else { "expires" },
+                timefmt::minutes(l.expires_at)),
+            None => println!("{} - unreadable lease file", name),
+        }
+    }
+    Ok(())
+}
+
+/// Root whose `.skm/meta.json` holds a project's metadata: its configured
+/// root when it has one, otherwise the root the portfolio was scanned from
+fn meta_root_for(project: &skm::Project, config: &GlobalConfig, root: &Path) -> PathBuf {
+    project.root.as_ref()
+        .and_then(|name| config.roots.iter().find(|r| &r.name == name))
+        .map(|r| r.resolved_path())
+        .unwrap_or_else(|| root.to_path_buf())
+}
+
+/// Scan context a single project is re-analyzed with: its root's settings and `.skm` data
+fn project_context(project: &skm::Project, config: &GlobalConfig, root: &Path) -> Result<ScanContext> {
+    let meta_root = meta_root_for(project, config, root);
+    let config = match project.root.as_ref().and_then(|name| config.roots.iter().find(|r| &r.name == name)) {
+        Some(root_config) => config.for_root(root_config),
+        None => config.clone(),
+    };
+    Ok(ScanContext::with_config(config, &meta_root, ScanOptions::default())?)
+}
+
+/// A command skm runs in projects and records the outcome of
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RunKind {
+    /// `skm test`: the project's test suite
+    Test,
+    /// `skm check`: a cheap build or typecheck
+    Check,
+}
+
+impl RunKind {
+    fn name(self) -> &'static str {
+        match self {
+            RunKind::Test => "test",
+            RunKind::Check => "check",
+        }
+    }
+    
+    fn default_command(self, project: &skm::Project) -> Option<&'static str> {
+        match self {
+            RunKind::Test => analyzer::testrun::default_test_command(&project.project_type),
+            RunKind::Check => analyzer::testrun::default_check_command(&project.project_type, &project.path),
+        }
+    }
+}
+
+/// A project command waiting to run
+struct PendingRun {
+    index: usize,
+    meta_root: PathBuf,
+    command: String,
+    profile: skm::meta::state::ExecProfile,
+}
+
+/// Run the test or check command of one project, or of all (optionally in
+/// one stage), at most `jobs` at a time
+///
+/// Output is captured to `.skm/logs`, the outcome is recorded in
+/// `.skm/tests.json`, and the projects are re-analyzed in the cache.
+fn run_project_commands(
+    kind: RunKind,
+    root_arg: Option<&str>,
+    id: Option<&str>,
+    all: bool,
+    stage: Option<Stage>,
+    jobs: usize,
+) -> Result<()> {
+    let config = GlobalConfig::load()?;
+    let root = resolve_root(root_arg, &config)?;
+    let (cache, _) = StatusCache::load_migrated(&root)?
+        .ok_or_else(|| anyhow::anyhow!("No status cache in {}; run `skm scan` first", root.display()))?;
+    let mut portfolio: PortfolioStatus = serde_json::from_value(cache.data)?;
+    
+    let selected: Vec<usize> = match (id, all) {
+        (Some(id), _) => {
+            let index = portfolio.projects.iter()
+                .position(|p| p.id == id || p.display_name() == id)
+                .ok_or_else(|| anyhow::anyhow!("Project not found: {}", id))?;
+            vec![index]
+        }
+        (None, true) => (0..portfolio.projects.len())
+            .filter(|&i| stage.as_ref().is_none_or(|s| portfolio.projects[i].stage == *s))
+            .collect(),
+        (None, false) => anyhow::bail!("Name a project or pass --all"),
+    };
+    
+    let mut pending = Vec::new();
+    for index in selected {
+        let project = &portfolio.projects[index];
+        let meta_root = meta_root_for(project, &config, &root);
+        let meta_store = ProjectMetaStore::load(&meta_root)?;
+        let meta = meta_store.get_project(&project.id);
+        let command = meta.and_then(|m| m.custom_commands.get(kind.name())).map(String::as_str)
+            .or_else(|| kind.default_command(project));
+        match command {
+            Some(command) => pending.push(PendingRun {
+                index,
+                command: command.to_string(),
+                profile: meta.map(|m| m.exec.clone()).unwrap_or_default(),
+                meta_root,
+            }),
+            None => println!("⏭️  {}: no {} command; set one with `skm meta set --filter id={} command.{} '<command>'`",
+                project.display_name(), kind.name(), project.id, kind.name()),
+        }
+    }
+    
+    // Workers take the next pending run until none are left
+    let next = std::sync::atomic::AtomicUsize::new(0);
+    let finished = std::sync::Mutex::new(Vec::new());
+    std::thread::scope(|scope| {
+        for _ in 0..jobs.clamp(1, pending.len().max(1)) {
+            scope.spawn(|| {
+                while let Some(run) = pending.get(next.fetch_add(1, std::sync::atomic::Ordering::Relaxed)) {
+                    let project = &portfolio.projects[run.index];
+                    let outcome = project_lease(&root, project, kind.name())
+                        .and_then(|_lease| run_project_command(kind, project, run));
+                    match &outcome {
+                        Ok(result) => report_run(project, result),
+                        Err(e) => eprintln!("{} {}: {}", style::icon("❌", "FAILED"), project.display_name(), e),
+                    }
+                    finished.lock().unwrap().push((run, outcome));
+                }
+            });
+        }
+    });
+    
+    let mut failures = 0;
+    for (run, outcome) in finished.into_inner().unwrap() {
+        let Ok(result) = outcome else {
+            failures += 1;
+            continue;
+        };
+        if !result.success {
+            failures += 1;
+        }
+        
+        let project = &portfolio.projects[run.index];
+        let mut store = TestRunStore::load(&run.meta_root)?;
+        let runs = match kind {
+            RunKind::Test => &mut store.projects,
+            RunKind::Check => &mut store.checks,
+        };
+        runs.insert(project.id.clone(), result);
+        store.save(&run.meta_root)?;
+        
+        // Re-analyze so the cached stage and risk reflect the new result
+        let ctx = project_context(project, &config, &root)?;
+        match pipeline::process_project(&project.path, &ctx) {
+            Ok((mut updated, _)) => {
+                updated.root = project.root.clone();
+                updated.remote = project.remote.clone();
+                portfolio.projects[run.index] = updated;
+            }
+            Err(e) => eprintln!("Warning: could not re-analyze {}: {}", project.display_name(), e),
+        }
+    }
+    
+    let labels = root_labels(&config, &root);
+    let labels: Vec<(String, &Path)> = labels.iter().map(|(name, path)| (name.clone(), path.as_path())).collect();
+    portfolio.summary = pipeline::summarize_roots(&portfolio.projects, &labels, &config.workflow);
+    // The new results are inputs of the re-analyzed projects, so the scan fingerprints no longer hold
+    save_portfolio(&root, &portfolio, &BTreeMap::new(), &config)?;
+    
+    if failures > 0 {
+        anyhow::bail!("{} project(s) failed their {}", failures, match kind {
+            RunKind::Test => "tests",
+            RunKind::Check => "checks",
+        });
+    }
+    Ok(())
+}
+
+/// Take the lease on a project under `root` for running its commands;
+/// fails with the holder's details while someone else holds it
+fn project_lease(root: &Path, project: &skm::Project, holder: &str) -> Result<lease::LeaseGuard> {
+    let ttl = chrono::Duration::seconds(lease::DEFAULT_LEASE_TTL_SECS);
+    Ok(lease::acquire(root, &LeaseScope::Project(project.id.clone()), holder, ttl)?)
+}
+
+/// Run one pending command with its output captured to `.skm/logs`
+fn run_project_command(kind: RunKind, project: &skm::Project, run: &PendingRun) -> Result<skm::TestRun> {
+    let started = Instant::now();
+    let at = Utc::now();
+    let output = skm::exec::run(&project.path, &run.profile, &project.capabilities, &run.command)?;
+    let duration_ms = started.elapsed().as_millis() as u64;
+    
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let logs = run.meta_root.join(".skm/logs");
+    std::fs::create_dir_all(&logs)?;
+    let log = logs.join(format!("{}-{}-{}.log", project.id, kind.name(), at.format("%Y%m%d-%H%M%S")));
+    std::fs::write(&log, format!("$ {}\n\n{}{}", run.command, stdout, stderr))?;
+    
+    let counts = match kind {
+        RunKind::Test => analyzer::testrun::parse_test_counts(&format!("{}\n{}", stdout, stderr)),
+        RunKind::Check => None,
+    };
+    Ok(skm::TestRun {
+        at,
+        command: run.command.clone(),
+        success: output.status.success(),
+        passed: counts.map(|c| c.passed),
+        failed: counts.map(|c| c.failed),
+        duration_ms,
+        log,
+    })
+}
+
+fn report_run(project: &skm::Project, run: &skm::TestRun) {
+    let outcome = match (run.passed, run.failed) {
+        (Some(passed), Some(failed)) => format!("{} passed, {} failed", passed, failed),
+        _ if run.success => "ok".to_string(),
+        _ => "failed".to_string(),
+    };
+    println!("{} {}: {} ({:.1}s), log: {}",
+        if run.success { style::icon("✅", "PASSED") } else { style::icon("❌", "FAILED") },
+        project.display_name(), outcome, run.duration_ms as f64 / 1000.0, run.log.display());
+}
+
+/// Roots a portfolio's summary is broken down by: the configured roots for
+/// the combined portfolio, otherwise the single scan root
+fn root_labels(config: &GlobalConfig, root: &Path) -> Vec<(String, PathBuf)> {
+    let combined = !config.roots.is_empty()
+        && GlobalConfig::portfolio_home().is_ok_and(|home| home == root);
+    if combined {
+        config.roots.iter().map(|r| (r.name.clone(), r.resolved_path())).collect()
+    } else {
+        vec![(root_label(root), root.to_path_buf())]
+    }
+}
+
+/// Print a release notes draft for a project, optionally committing it to a review branch
+fn release_notes(root_arg: Option<&str>, id: &str, version: Option<&str>, write: bool) -> Result<()> {
+    let config = GlobalConfig::load()?;
+    let root = resolve_root(root_arg, &config)?;
+    let (cache, _) = StatusCache::load_migrated(&root)?
+        .ok_or_else(|| anyhow::anyhow!("No status cache in {}; run `skm scan` first", root.display()))?;
+    let portfolio: PortfolioStatus = serde_json::from_value(cache.data)?;
+    let project = portfolio.projects.iter()
+        .find(|p| p.id == id || p.display_name() == id)
+        .ok_or_else(|| anyhow::anyhow!("Project not found: {}", id))?;
+    
+    let mut notes = release_notes::collect_release_notes(&project.path)?;
+    let overrides = config.for_project(&project.path)?;
+    Variables::for_project(project, overrides.as_ref().unwrap_or(&config)).resolve_all(
+        notes.tasks.iter_mut().map(|task| &mut task.text)
+            .chain(notes.acceptance.iter_mut().flat_map(|(_, criteria)| criteria.iter_mut())),
+    );
+    let today = timefmt::display_zone().date(Utc::now());
+    let entry = release_notes::render_release_notes(&notes, version, today);
+    print!("{}", entry);
+    
+    if write {
+        let label = version.map_or_else(|| today.format("%Y-%m-%d").to_string(), str::to_string);
+        let branch = format!("release-notes/{}", label);
+        let message = format!("docs: draft release notes for {}", version.unwrap_or("the next release"));
+        release_notes::write_changelog_branch(&project.path, &entry, &branch, &message)?;
+        eprintln!("\nCommitted CHANGELOG.md on branch {} for review", branch);
+    } else if let Some(tag) = &notes.since_tag {
+        eprintln!("\nChanges since {}", tag);
+    }
+    Ok(())
+}
+
+/// Preview a replacement across the spec artifacts of the matching projects,
+/// or with `write`, apply it and commit it in each project on a new branch
+///
+/// Remote projects are left out, since their clones are replaced on sync.
+/// A project that cannot be committed is reported and the others go ahead.
+fn edit_artifacts(
+    root_arg: Option<&str>,
+    conditions: &[FieldFilter],
+    replacement: &Replacement,
+    write: bool,
+    branch: Option<&str>,
+) -> Result<()> {
+    use std::io::Write;
+    
+    let config = GlobalConfig::load()?;
+    let root = resolve_root(root_arg, &config)?;
+    let (cache, _) = StatusCache::load_migrated(&root)?
+        .ok_or_else(|| anyhow::anyhow!("No status cache in {}; run `skm scan` first", root.display()))?;
+    let portfolio: PortfolioStatus = serde_json::from_value(cache.data)?;
+    let branch = branch.map_or_else(|| replacement.branch(), str::to_string);
+    let message = format!("docs: rename {} to {} in specs", replacement.from, replacement.to);
+    
+    let mut out = std::io::stdout().lock();
+    let (mut projects, mut files, mut replaced, mut failures) = (0, 0, 0, 0);
+    for project in portfolio.projects.iter().filter(|p| p.remote.is_none() && conditions.iter().all(|c| c.matches(p))) {
+        let edits = rewrite::plan_edits(&project.path, replacement)?;
+        let count: usize = edits.iter().map(|edit| edit.rewrite.replaced).sum();
+        let skipped: usize = edits.iter().map(|edit| edit.rewrite.skipped).sum();
+        if edits.is_empty() {
+            continue;
+        }
+        writeln!(out, "=== {} ({} replacement(s){}) ===", project.display_name(), count,
+            if skipped > 0 { format!(", {} in code or links left unchanged", skipped) } else { String::new() })?;
+        for edit in &edits {
+            let path = edit.path.display();
+            write_unified_diff(&edit.before, &edit.rewrite.text, &format!("a/{}", path), &format!("b/{}", path), 1, &mut out)?;
+        }
+        writeln!(out)?;
+        if count == 0 {
+            continue;
+        }
+        if write {
+            match rewrite::commit_edits(&project.path, &edits, &branch, &message) {
+                Ok(Some(branch)) => writeln!(out, "Committed on branch {}\n", branch)?,
+                Ok(None) => writeln!(out, "Edited in place (not a git repository)\n")?,
+                Err(e) => {
+                    eprintln!("{}: {}\n", project.display_name(), e);
+                    failures += 1;
+                    continue;
+                }
+            }
+        }
+        projects += 1;
+        files += edits.iter().filter(|edit| edit.rewrite.replaced > 0).count();
+        replaced += count;
+    }
+    
+    if projects + failures == 0 {
+        println!("No occurrences of {} to replace", replacement.from);
+    } else if write {
+        println!("Replaced {} occurrence(s) in {} file(s) of {} project(s)", replaced, files, projects);
+    } else {
+        println!("Would replace {} occurrence(s) in {} file(s) of {} project(s); run again with --write to commit them on branch {}",
+            replaced, files, projects, branch);
+    }
+    if failures > 0 {
+        anyhow::bail!("{} project(s) could not be edited", failures);
+    }
+    Ok(())
+}
+
+#[cfg(feature = "rag")]
+fn index_artifacts(root_arg: Option<&str>, id: Option<&str>, reset: bool) -> Result<()> {
+    let config = GlobalConfig::load()?;
+    let root = resolve_root(root_arg, &config)?;
+    let portfolio = cached_portfolio(&root)?
+        .ok_or_else(|| anyhow::anyhow!("No status cache in {}; run `skm scan` first", root.display()))?;
+    let projects: Vec<&skm::Project> = portfolio.projects.iter()
+        .filter(|p| id.is_none_or(|id| p.id == id || p.display_name() == id))
+        .collect();
+    if let Some(id) = id && projects.is_empty() {
+        anyhow::bail!("Project not found: {}", id);
+    }
+    
+    let embedder = skm::rag::Embedder::from_config(&config.rag, &config.retry);
+    println!("Indexing {} project(s) into {} at {} ({})", projects.len(), config.rag.collection, config.qdrant_url, embedder.name());
+    let stats = skm::rag::index_projects(&projects, &config, reset, |project, chunks| {
+        println!("  {} — {} chunk(s)", project.display_name(), chunks);
+    })?;
+    println!("Indexed {} chunk(s) from {} artifact file(s)", stats.chunks, stats.files);
+    Ok(())
+}
+
+#[cfg(feature = "rag")]
+fn search_artifacts(
+    root_arg: Option<&str>,
+    query: &str,
+    limit: usize,
+    project: Option<&str>,
+    stage: Option<Stage>,
+    artifact: Option<ArtifactKind>,
+    json: bool,
+) -> Result<()> {
+    let config = GlobalConfig::load()?;
+    // Project ids are stored by display name, so resolve a bare id through the cache
+    let project = match project {
+        Some(id) => {
+            let root = resolve_root(root_arg, &config)?;
+            let name = cached_portfolio(&root)?
+                .and_then(|portfolio| portfolio.projects.iter()
+                    .find(|p| p.id == id || p.display_name() == id)
+                    .map(|p| p.display_name()));
+            Some(name.unwrap_or_else(|| id.to_string()))
+        }
+        None => None,
+    };
+    let filter = skm::rag::SearchFilter {
+        project,
+        stage: stage.map(|s| s.to_string()),
+        artifact: artifact.map(|a| a.to_string()),
+    };
+    let hits = skm::rag::search(query, limit, &filter, &config)?;
+    
+    if json {
+        let hits: Vec<_> = hits.iter()
+            .map(|hit| serde_json::json!({ "score": hit.score, "chunk": hit.payload }))
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&hits)?);
+        return Ok(());
+    }
+    if hits.is_empty() {
+        println!("No matching artifacts; run `skm index` after scanning.");
+        return Ok(());
+    }
+    for (rank, hit) in hits.iter().enumerate() {
+        let chunk = &hit.payload;
+        let location = [chunk.feature.as_deref(), chunk.heading.as_deref()].into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(" › ");
+        println!("{}. {} ({}) — {} {}  [{:.2}]", rank + 1, chunk.project, chunk.stage, chunk.artifact, location, hit.score);
+        println!("   {}", Path::new(&chunk.project_path).join(&chunk.file).display());
+        let snippet: String = chunk.text.lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty() && !l.starts_with('#'))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let snippet: String = snippet.chars().take(160).collect();
+        if !snippet.is_empty() {
+            println!("   {}", snippet);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(feature = "session")]
+fn session(action: SessionAction) -> Result<()> {
+    let find = |root_arg: Option<&str>, id: &str| -> Result<(GlobalConfig, PathBuf, skm::Project)> {
+        let config = GlobalConfig::load()?;
+        let root = resolve_root(root_arg, &config)?;
+        let portfolio = cached_portfolio(&root)?
+            .ok_or_else(|| anyhow::anyhow!("No status cache in {}; run `skm scan` first", root.display()))?;
+        let project = portfolio.projects.into_iter()
+            .find(|p| p.id == id || p.display_name() == id)
+            .ok_or_else(|| anyhow::anyhow!("Project not found: {}", id))?;
+        Ok((config, root, project))
+    };
+    
+    match action {
+        SessionAction::Open { project, root, split, detach } => {
+            let (config, root, project) = find(root.as_deref(), &project)?;
+            let name = tmux::session_name(&project.display_name());
+            let layout = if split {
+                let meta_store = ProjectMetaStore::load(&meta_root_for(&project, &config, &root))?;
+                let agent = meta_store.get_project(&project.id).and_then(|m| m.agent_command.clone());
+                SessionLayout {
+                    editor: Some(skm::exec::wrap_in_environment(&project.capabilities, &format!("{} .", config.default_editor))),
+                    split: true,
+                    agent: agent.map(|command| skm::exec::wrap_in_environment(&project.capabilities, &command)),
+                }
+            } else {
+                SessionLayout::default()
+            };
+            if tmux::open_session(&name, &project.path, &layout)? {
+                println!("Created tmux session {} in {}", name, project.path.display());
+            } else if split {
+                println!("Session {} is already running; --split only applies to new sessions", name);
+            }
+            if detach {
+                println!("Attach with: tmux attach -t {}", name);
+            } else {
+                tmux::attach(&name)?;
+            }
+        }
+        SessionAction::List => {
+            let sessions = tmux::list_sessions()?;
+            if sessions.is_empty() {
+                println!("No skm sessions running.");
+            }
+            for session in sessions {
+                println!("{} - {} window(s){} - {}",
+                    session.name,
+                    session.windows,
+                    if session.attached > 0 { ", attached" } else { "" },
+                    session.path.display());
+            }
+        }
+        SessionAction::Kill { project, root, all } => {
+            let names: Vec<String> = if all {
+                tmux::list_sessions()?.into_iter().map(|s| s.name).collect()
+            } else {
+                let id = project.expect("clap requires a project without --all");
+                let (_, _, project) = find(root.as_deref(), &id)?;
+                vec![tmux::session_name(&project.display_name())]
+            };
+            for name in names {
+                if tmux::kill_session(&name)? {
+                    println!("Killed {}", name);
+                } else {
+                    println!("No session {} running", name);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Run a command in a project using its execution profile, streaming output
+fn exec_in_project(root_arg: Option<&str>, id: &str, command_line: &[String]) -> Result<()> {
+    let config = GlobalConfig::load()?;
+    let root = resolve_root(root_arg, &config)?;
+    let (cache, _) = StatusCache::load_migrated(&root)?
+        .ok_or_else(|| anyhow::anyhow!("No status cache in {}; run `skm scan` first", root.display()))?;
+    let portfolio: PortfolioStatus = serde_json::from_value(cache.data)?;
+    let project = portfolio.projects.iter()
+        .find(|p| p.id == id || p.display_name() == id)
+        .ok_or_else(|| anyhow::anyhow!("Project not found: {}", id))?;
+    
+    let meta_store = ProjectMetaStore::load(&meta_root_for(project, &config, &root))?;
+    let profile = meta_store.get_project(&project.id).map(|m| m.exec.clone()).unwrap_or_default();
+    // Exiting skips destructors, so the lease is released before that
+    let status = {
+        let _lease = project_lease(&root, project, "exec")?;
+        skm::exec::run_streaming(&project.path, &profile, &project.capabilities, &command_line.join(" "))?
+    };
+    if !status.success() {
+        std::process::exit(status.code().unwrap_or(1));
+    }
+    Ok(())
+}
+
+/// Conditions selecting projects and the `key = value` pairs to set on them
+type MetaEdit = (Vec<FieldFilter>, Vec<(String, String)>);
+
+/// `skm pin` / `skm unpin`: the `pinned` key of one project's meta.json
+fn set_pinned(root_arg: Option<&str>, project: &str, pinned: bool) -> Result<()> {
+    let change = (parse_conditions(&[format!("id={}", project)])?, vec![("pinned".to_string(), pinned.to_string())]);
+    edit_meta(root_arg, &[change], false)
+}
+
+/// Apply metadata edits to the projects of the cached portfolio they match
+///
+/// Each project's metadata lives in the `.skm/meta.json` of the root it
+/// was scanned under, so a combined portfolio may update several files.
+fn edit_meta(root_arg: Option<&str>, edits: &[MetaEdit], dry_run: bool) -> Result<()> {
+    let config = GlobalConfig::load()?;
+    let root = resolve_root(root_arg, &config)?;
+    let (cache, _) = StatusCache::load_migrated(&root)?
+        .ok_or_else(|| anyhow::anyhow!("No status cache in {}; run `skm scan` first", root.display()))?;
+    let portfolio: PortfolioStatus = serde_json::from_value(cache.data)?;
+    
+    let mut stores: BTreeMap<PathBuf, ProjectMetaStore> = BTreeMap::new();
+    let mut touched: BTreeMap<String, (PathBuf, String, Vec<String>)> = BTreeMap::new();
+    let mut matched = BTreeSet::new();
+    
+    for (conditions, assignments) in edits {
+        for project in portfolio.projects.iter().filter(|p| conditions.iter().all(|c| c.matches(p))) {
+            let meta_root = meta_root_for(project, &config, &root);
+            let store = match stores.entry(meta_root.clone()) {
+                std::collections::btree_map::Entry::Occupied(entry) => entry.into_mut(),
+                std::collections::btree_map::Entry::Vacant(entry) => {
+                    let store = ProjectMetaStore::load(entry.key())?;
+                    entry.insert(store)
+                }
+            };
+            matched.insert(project.display_name());
+            
+            for (key, value) in assignments {
+                let before = serde_json::to_value(store.get_project(&project.id))?;
+                store.set_value(&project.id, key, value.clone())
+                    .map_err(|e| anyhow::anyhow!("{}: cannot set {} = {}: {}", project.display_name(), key, value, e))?;
+                if serde_json::to_value(store.get_project(&project.id))? != before {
+                    touched.entry(project.display_name())
+                        .or_insert_with(|| (meta_root.clone(), project.id.clone(), Vec::new()))
+                        .2.push(format!("{} = {}", key, value));
+                }
+            }
+        }
+    }
+    
+    if matched.is_empty() {
+        println!("No projects match");
+        return Ok(());
+    }
+    
+    if !touched.is_empty() {
+        println!("{} {} project(s):", if dry_run { "Would update" } else { "Updated" }, touched.len());
+    }
+    for (name, (meta_root, id, changes)) in &touched {
+        println!("  {}: {}", name, changes.join(", "));
+        for error in stores[meta_root].custom_field_errors(id) {
+            eprintln!("    warning: {}", error);
+        }
+    }
+    let unchanged = matched.len() - touched.len();
+    if unchanged > 0 {
+        println!("{} matching project(s) already up to date", unchanged);
+    }
+    
+    if !dry_run {
+        for (meta_root, store) in &stores {
+            if touched.values().any(|(r, _, _)| r == meta_root) {
+                store.save(meta_root)?;
+            }
+        }
+    }
+    
+    Ok(())
+}
+
+fn edit_config(action: ConfigAction) -> Result<()> {
+    let path = GlobalConfig::config_path()?;
+    match action {
+        ConfigAction::Init { force } => {
+            if path.exists() && !force {
+                anyhow::bail!("{} already exists; pass --force to replace it", path.display());
+            }
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&path, config_file::commented_default()?)?;
+            println!("Wrote {}", path.display());
+        }
+        ConfigAction::Show => {
+            let config = GlobalConfig::load()?;
+            if path.exists() {
+                println!("# {}", path.display());
+            } else {
+                println!("# {} does not exist; showing defaults", path.display());
+            }
+            print!("{}", toml::to_string_pretty(&config)?);
+        }
+        ConfigAction::Set { key, value } => {
+            // Settings have no defaults field by field, so a new file starts from the full default
+            let content = if path.exists() { std::fs::read_to_string(&path)? } else { config_file::commented_default()? };
+            let updated = config_file::set_value(&content, &key, &value)?;
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&path, updated)?;
+            println!("{} = {}", key, value);
+        }
+    }
+    Ok(())
+}
+
+/// Print metadata for one project (optionally one key) or for every project
+/// with non-default metadata
+fn show_meta(root_arg: Option<&str>, project_name: Option<&str>, key: Option<&str>, json_output: bool) -> Result<()> {
+    let config = GlobalConfig::load()?;
+    let root = resolve_root(root_arg, &config)?;
+    let (cache, _) = StatusCache::load_migrated(&root)?
+        .ok_or_else(|| anyhow::anyhow!("No status cache in {}; run `skm scan` first", root.display()))?;
+    let portfolio: PortfolioStatus = serde_json::from_value(cache.data)?;
+    
+    let selected: Vec<&skm::Project> = match project_name {
+        Some(name) => {
+            let matches: Vec<&skm::Project> = portfolio.projects.iter()
+                .filter(|p| p.id == name || p.display_name() == name)
+                .collect();
+            if matches.is_empty() {
+                return Err(anyhow::anyhow!("Project not found: {}", name));
+            }
+            matches
+        }
+        None => portfolio.projects.iter().collect(),
+    };
+    
+    let mut stores: BTreeMap<PathBuf, ProjectMetaStore> = BTreeMap::new();
+    let mut listing = Vec::new();
+    for project in selected {
+        let meta_root = meta_root_for(project, &config, &root);
+        if !stores.contains_key(&meta_root) {
+            stores.insert(meta_root.clone(), ProjectMetaStore::load(&meta_root)?);
+        }
+        let meta = stores[&meta_root].get_project(&project.id).cloned().unwrap_or_default();
+        
+        if let Some(key) = key {
+            let value = meta.get_value(key)?;
+            if json_output {
+                println!("{}", serde_json::to_string(&value)?);
+            } else if let Some(value) = value {
+                println!("{}", value);
+            } else {
+                return Err(anyhow::anyhow!("{}: {} is not set", project.display_name(), key));
+            }
+            return Ok(());
+        }
+        
+        let entries = meta.entries();
+        // `meta get <project>` shows the project even when it has only defaults
+        if project_name.is_some() || !entries.is_empty() {
+            listing.push((project.display_name(), entries));
+        }
+    }
+    
+    if json_output {
+        let map: serde_json::Map<String, serde_json::Value> = listing.into_iter()
+            .map(|(name, entries)| {
+                let fields = entries.into_iter().map(|(k, v)| (k, serde_json::Value::String(v))).collect();
+                (name, serde_json::Value::Object(fields))
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&map)?);
+        return Ok(());
+    }
+    
+    if listing.is_empty() {
+        println!("No project metadata set");
+    }
+    for (name, entries) in listing {
+        println!("{}", name);
+        if entries.is_empty() {
+            println!("  (defaults)");
+        }
+        for (key, value) in entries {
+            println!("  {} = {}", key, value);
+        }
+    }
+    Ok(())
+}
+
+/// Diff the current artifact against a retained snapshot
+///
+/// Versions are the snapshots taken by earlier scans plus the file on disk
+/// when it changed since the last scan.
+fn spec_diff(root_arg: Option<&str>, id: &str, kind: ArtifactKind, back: usize) -> Result<()> {
+    let config = GlobalConfig::load()?;
+    let root = resolve_root(root_arg, &config)?;
+    let (cache, _) = StatusCache::load_migrated(&root)?
+        .ok_or_else(|| anyhow::anyhow!("No status cache in {}; run `skm scan` first", root.display()))?;
+    let portfolio: PortfolioStatus = serde_json::from_value(cache.data)?;
+    let project = portfolio.projects.iter()
+        .find(|p| p.id == id || p.display_name() == id)
+        .ok_or_else(|| anyhow::anyhow!("Project not found: {}", id))?;
+    
+    // Snapshots live with the root the project was scanned under
+    let snapshot_root = project.root.as_ref()
+        .and_then(|name| config.roots.iter().find(|r| &r.name == name))
+        .map(|r| r.resolved_path())
+        .unwrap_or(root);
+    let store = SnapshotStore::load(&snapshot_root)?;
+    
+    let mut versions = Vec::new();
+    for snapshot in store.history(&project.id, kind) {
+        let label = format!("{} {} ({})", kind, timefmt::minutes(snapshot.taken_at), &snapshot.oid[..8]);
+        versions.push((label, store.read(&snapshot_root, snapshot)?, Some(snapshot.oid.as_str())));
+    }
+    if let Some(file) = artifact_file(project, kind)
+        && let Ok(current) = std::fs::read(&file.path) {
+        let oid = skm::hash::blob_id(&current);
+        if versions.last().is_none_or(|(_, _, last)| *last != Some(oid.as_str())) {
+            versions.push((format!("{} (working copy)", file.path.display()), String::from_utf8_lossy(&current).into_owned(), None));
+        }
+    }
+    
+    if versions.len() < 2 {
+        println!("No earlier {} versions retained for {}.", kind, project.display_name());
+        return Ok(());
+    }
+    if back == 0 || back >= versions.len() {
+        anyhow::bail!("--back must be between 1 and {}", versions.len() - 1);
+    }
+    
+    let (old_label, old, _) = &versions[versions.len() - 1 - back];
+    let (new_label, new, _) = &versions[versions.len() - 1];
+    let mut out = std::io::stdout().lock();
+    write_unified_diff(old, new, old_label, new_label, 3, &mut out)?;
+    Ok(())
+}
+
+/// Print the prompt segment, or nothing when outside a tracked project
+///
+/// Reads only the status cache and never fails, so a prompt cannot break
+/// or slow down because of skm.
+fn print_prompt(cwd: &Path, format: &str) {
+    let Ok(cwd) = cwd.canonicalize() else { return };
+    let Ok(Some((root, cache))) = StatusCache::find_upwards(&cwd) else { return };
+    let Ok(portfolio) = serde_json::from_value::<PortfolioStatus>(cache.data) else { return };
+    
+    if let Some(project) = find_project(&portfolio, &root, &cwd) {
+        println!("{}", render_prompt(project, format));
+    }
+}
+
+/// Print the launcher feed from the cached portfolio, however old, without scanning
+fn completion_data(root_arg: Option<&str>, pretty: bool) -> Result<()> {
+    let root = resolve_root(root_arg, &GlobalConfig::load()?)?;
+    let (cache, _) = StatusCache::load_migrated(&root)?
+        .ok_or_else(|| anyhow::anyhow!("No status cache in {}; run `skm scan` first", root.display()))?;
+    let portfolio: PortfolioStatus = serde_json::from_value(cache.data)?;
+    let feed = completion_feed(&portfolio);
+    if pretty {
+        println!("{}", serde_json::to_string_pretty(&feed)?);
+    } else {
+        println!("{}", serde_json::to_string(&feed)?);
+    }
+    Ok(())
+}
+
+/// Answer Prometheus scrapes of `/metrics`, one connection at a time
+///
+/// The status cache is read on every scrape, so each `skm scan` (e.g. from
+/// cron or `skm watch`) shows up at the next scrape without a restart.
+/// Each of the `[[roots]]` is also served on its own from its cache at
+/// `/portfolios/{name}/metrics`.
+#[cfg(feature = "serve")]
+fn serve_metrics(root_arg: Option<&str>, bind: &str, port: u16) -> Result<()> {
+    use std::io::{BufRead, BufReader, Write};
+    let config = GlobalConfig::load()?;
+    let root = resolve_root(root_arg, &config)?;
+    let roots: BTreeMap<String, PathBuf> = config.roots.iter()
+        .map(|root| (root.name.clone(), root.resolved_path()))
+        .collect();
+    let listener = std::net::TcpListener::bind((bind, port))
+        .map_err(|e| anyhow::anyhow!("Cannot listen on {}:{}: {}", bind, port, e))?;
+    println!("Serving metrics for {} at http://{}:{}/metrics; Ctrl-C to stop", root.display(), bind, port);
+    
+    for stream in listener.incoming() {
+        let Ok(mut stream) = stream else { continue };
+        let _ = stream.set_read_timeout(Some(Duration::from_secs(5)));
+        let mut reader = BufReader::new(&stream);
+        let mut request_line = String::new();
+        if reader.read_line(&mut request_line).is_err() {
+            continue;
+        }
+        // Read the headers too; closing with unread input resets the connection
+        let mut header = String::new();
+        while reader.read_line(&mut header).is_ok_and(|n| n > 0) && header.trim_end() != "" {
+            header.clear();
+        }
+        let mut parts = request_line.split_whitespace();
+        let (method, path) = (parts.next().unwrap_or_default(), parts.next().unwrap_or_default());
+        let path = path.split('?').next().unwrap_or_default();
+        // `/portfolios/{name}/{route}`, with each root read from its own cache
+        let portfolio = path.strip_prefix("/portfolios/")
+            .and_then(|rest| rest.split_once('/'))
+            .and_then(|(name, route)| Some((roots.get(name)?, route)));
+        let (status, content_type, body) = match (method, path) {
+            ("GET", "/metrics") => metrics_response(&root),
+            ("GET", _) if let Some((root, route)) = portfolio => portfolio_response(root, route),
+            ("GET", "/portfolios") => {
+                let list: Vec<_> = roots.iter().map(|(name, path)| serde_json::json!({
+                    "name": name,
+                    "path": path,
+                    "metrics": format!("/portfolios/{}/metrics", name),
+                    "status": format!("/portfolios/{}/status", name),
+                })).collect();
+                ("200 OK", "application/json", serde_json::to_string_pretty(&list)?)
+            }
+            ("GET", "/") => {
+                let mut links = "<a href=\"/metrics\">Metrics</a>\n".to_string();
+                for name in roots.keys() {
+                    links.push_str(&format!("<br>{0}: <a href=\"/portfolios/{0}/metrics\">metrics</a>, <a href=\"/portfolios/{0}/status\">status</a>\n", name));
+                }
+                ("200 OK", "text/html", links)
+            }
+            ("GET", _) => ("404 Not Found", "text/plain", "Not found\n".to_string()),
+            _ => ("405 Method Not Allowed", "text/plain", "Method not allowed\n".to_string()),
+        };
+        let _ = write!(stream, "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            status, content_type, body.len(), body);
+    }
+    Ok(())
+}
+
+/// Status, content type and body of a route under `/portfolios/{name}/`
+#[cfg(feature = "serve")]
+fn portfolio_response(root: &Path, route: &str) -> (&'static str, &'static str, String) {
+    let not_found = ("404 Not Found", "text/plain", "Not found\n".to_string());
+    let project = match route {
+        "metrics" => return metrics_response(root),
+        "status" => None,
+        _ => match route.strip_prefix("projects/") {
+            Some(id) => Some(id),
+            None => return not_found,
+        },
+    };
+    match cached_portfolio(root) {
+        Ok(Some(portfolio)) => {
+            let json = match project {
+                None => serde_json::to_string_pretty(&portfolio),
+                Some(id) => match portfolio.projects.iter().find(|p| p.id == id || p.display_name() == id) {
+                    Some(project) => serde_json::to_string_pretty(project),
+                    None => return not_found,
+                },
+            };
+            match json {
+                Ok(json) => ("200 OK", "application/json", json),
+                Err(e) => ("500 Internal Server Error", "text/plain", format!("{}\n", e)),
+            }
+        }
+        Ok(None) => ("503 Service Unavailable", "text/plain", format!("No status cache in {}; run `skm scan` first\n", root.display())),
+        Err(e) => ("500 Internal Server Error", "text/plain", format!("{:#}\n", e)),
+    }
+}
+
+/// Status, content type and body of a scrape of a root's metrics
+#[cfg(feature = "serve")]
+fn metrics_response(root: &Path) -> (&'static str, &'static str, String) {
+    match cached_portfolio(root) {
+        Ok(Some(portfolio)) => {
+            let mut body = String::new();
+            skm::reporter::write_metrics(&portfolio, Utc::now(), &mut body);
+            ("200 OK", "text/plain; version=0.0.4; charset=utf-8", body)
+        }
+        Ok(None) => ("503 Service Unavailable", "text/plain", format!("No status cache in {}; run `skm scan` first\n", root.display())),
+        Err(e) => ("500 Internal Server Error", "text/plain", format!("{:#}\n", e)),
+    }
+}
+
+/// Config, status cache and metadata loaded once by `skm batch` and
+/// shared by every command it reads
+struct Batch {
+    config: GlobalConfig,
+    root: PathBuf,
+    portfolio: PortfolioStatus,
+    stores: BTreeMap<PathBuf, ProjectMetaStore>,
+}
+
+impl Batch {
+    fn load(root_arg: Option<&str>) -> Result<Self> {
+        let config = GlobalConfig::load()?;
+        let root = resolve_root(root_arg, &config)?;
+        let (cache, _) = StatusCache::load_migrated(&root)?
+            .ok_or_else(|| anyhow::anyhow!("No status cache in {}; run `skm scan` first", root.display()))?;
+        let portfolio = serde_json::from_value(cache.data)?;
+        Ok(Batch { config, root, portfolio, stores: BTreeMap::new() })
+    }
+
+    fn project(&self, request: &serde_json::Value) -> Result<&skm::Project> {
+        let name = request["project"].as_str().ok_or_else(|| anyhow::anyhow!("missing \"project\""))?;
+        let matches: Vec<&skm::Project> = self.portfolio.projects.iter()
+            .filter(|p| p.id == name || p.display_name() == name)
+            .collect();
+        match matches.as_slice() {
+            [] => anyhow::bail!("Project not found: {}", name),
+            [project] => Ok(project),
+            _ => anyhow::bail!("{} is ambiguous: {}", name,
+                matches.iter().map(|p| p.display_name()).collect::<Vec<_>>().join(", ")),
+        }
+    }
+
+    /// The metadata store for a project's root, loaded on first use
+    fn store(&mut self, project: &skm::Project) -> Result<(PathBuf, &mut ProjectMetaStore)> {
+        let meta_root = meta_root_for(project, &self.config, &self.root);
+        let store = match self.stores.entry(meta_root.clone()) {
+            std::collections::btree_map::Entry::Occupied(entry) => entry.into_mut(),
+            std::collections::btree_map::Entry::Vacant(entry) => {
+                let store = ProjectMetaStore::load(entry.key())?;
+                entry.insert(store)
+            }
+        };
+        Ok((meta_root, store))
+    }
+
+    fn run(&mut self, request: &serde_json::Value) -> Result<serde_json::Value> {
+        let command = request["cmd"].as_str().ok_or_else(|| anyhow::anyhow!("missing \"cmd\""))?;
+        match command {
+            "show" => Ok(serde_json::to_value(self.project(request)?)?),
+            "status" => {
+                let only = request["only"].as_str().map(str::parse::<Filter>).transpose()?;
+                let conditions = match &request["where"] {
+                    serde_json::Value::Null => Vec::new(),
+                    serde_json::Value::String(condition) => vec![condition.parse()?],
+                    value => parse_conditions(&serde_json::from_value::<Vec<String>>(value.clone())?)?,
+                };
+                let mut view = PortfolioView::new(&self.portfolio).filter_where(&conditions);
+                if let Some(only) = &only {
+                    view = view.filter(|p| only.matches(p));
+                }
+                Ok(serde_json::to_value(&view)?)
+            }
+            "meta.get" => {
+                let project = self.project(request)?.clone();
+                let (_, store) = self.store(&project)?;
+                let meta = store.get_project(&project.id).cloned().unwrap_or_default();
+                match request["key"].as_str() {
+                    Some(key) => Ok(serde_json::json!(meta.get_value(key)?)),
+                    None => Ok(serde_json::json!(meta.entries().into_iter().collect::<BTreeMap<_, _>>())),
+                }
+            }
+            "meta.set" => {
+                let project = self.project(request)?.clone();
+                let key = request["key"].as_str().ok_or_else(|| anyhow::anyhow!("missing \"key\""))?;
+                let value = match &request["value"] {
+                    serde_json::Value::String(value) => value.clone(),
+                    serde_json::Value::Null => anyhow::bail!("missing \"value\""),
+                    value => value.to_string(),
+                };
+                let dry_run = request["dry_run"].as_bool().unwrap_or(false);
+                let (meta_root, store) = self.store(&project)?;
+                let before = serde_json::to_value(store.get_project(&project.id))?;
+                store.set_value(&project.id, key, value.clone())
+                    .map_err(|e| anyhow::anyhow!("{}: cannot set {} = {}: {}", project.display_name(), key, value, e))?;
+                let changed = serde_json::to_value(store.get_project(&project.id))? != before;
+                let warnings = store.custom_field_errors(&project.id);
+                // Saved right away, so edits before a failing command are kept
+                if changed && !dry_run {
+                    store.save(&meta_root)?;
+                } else if changed {
+                    self.stores.remove(&meta_root);
+                }
+                Ok(serde_json::json!({ "changed": changed, "warnings": warnings }))
+            }
+            "reload" => {
+                let root = self.root.to_string_lossy().into_owned();
+                *self = Batch::load(Some(&root))?;
+                Ok(serde_json::json!({ "projects": self.portfolio.projects.len() }))
+            }
+            other => anyhow::bail!("Unknown command: {} (expected show, status, meta.get, meta.set or reload)", other),
         }
-        Commands::Report { out, format } => {
-            println!("Generating {} report to {}", format, out);
-            // TODO: Implement report functionality
-            Ok(())
+    }
+}
+
+/// Read one JSON command per line from stdin and answer each with one line
+/// of `{"ok": ..., "result" or "error": ...}`, echoing the command's `id`
+fn run_batch(root_arg: Option<&str>) -> Result<()> {
+    use std::io::{BufRead, Write};
+    let mut batch = Batch::load(root_arg)?;
+    let mut failed = 0;
+    for line in std::io::stdin().lock().lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
         }
-        Commands::Digest { project, mode, out } => {
-            println!("Generating {} digest for {:?} to {}", mode, project, out);
-            // TODO: Implement digest functionality
-            Ok(())
+        let request: Result<serde_json::Value> = serde_json::from_str(&line).map_err(|e| anyhow::anyhow!("invalid JSON: {}", e));
+        let id = request.as_ref().map(|r| r["id"].clone()).unwrap_or_default();
+        let mut response = match request.and_then(|request| batch.run(&request)) {
+            Ok(result) => serde_json::json!({ "ok": true, "result": result }),
+            Err(e) => {
+                failed += 1;
+                serde_json::json!({ "ok": false, "error": format!("{:#}", e) })
+            }
+        };
+        if !id.is_null() {
+            response["id"] = id;
         }
+        let mut out = std::io::stdout().lock();
+        writeln!(out, "{}", response)?;
+        out.flush()?;
+    }
+    if failed > 0 {
+        anyhow::bail!("{} command(s) failed", failed);
     }
+    Ok(())
 }
 
-async fn show_status(root_path: &str, json_output: bool, filter: Option<&str>) -> Result<()> {
-    let root = Path::new(root_path);
+fn migrate_state(root: &Path, dry_run: bool) -> Result<()> {
+    let (meta_store, migration) = ProjectMetaStore::load_migrated(root)?;
+    report_migration("meta.json", migration);
+    if migration.changed() && !dry_run {
+        meta_store.save(root)?;
+    }
     
-    // Try to load cached status first
-    if let Ok(Some(cached_status)) = StatusCache::load(root) {
-        // StatusCache already checks freshness in load(), so if we got Some, it's fresh
-        // Use cached data  
-        let portfolio: PortfolioStatus = serde_json::from_value(cached_status.data)?;
-        
-        // Apply filter if specified
-        let mut filtered_portfolio = portfolio.clone();
-        if let Some(filter_str) = filter {
-            match filter_str {
-                "needs-attention" => {
-                    let config = GlobalConfig::load()?;
-                    filtered_portfolio.projects.retain(|p| p.priority > config.attention_threshold);
-                }
-                "incomplete" => {
-                    filtered_portfolio.projects.retain(|p| p.tasks.completed < p.tasks.total);
-                }
-                stage if stage.starts_with("stage:") => {
-                    let stage_name = &stage[6..];
-                    filtered_portfolio.projects.retain(|p| format!("{:?}", p.stage).to_lowercase() == stage_name.to_lowercase());
-                }
-                _ => {}
+    match StatusCache::load_migrated(root)? {
+        Some((cache, migration)) => {
+            report_migration("status.json", migration);
+            if migration.changed() && !dry_run {
+                cache.save(root)?;
             }
         }
-        
-        if json_output {
-            println!("{}", serde_json::to_string_pretty(&filtered_portfolio)?);
-        } else {
-            display_portfolio_status(&filtered_portfolio);
-        }
-        
-        return Ok(());
+        None => println!("status.json: not present"),
+    }
+    
+    if dry_run {
+        println!("Dry run: no files written");
     }
     
-    // Cache is stale or doesn't exist, rescan
-    println!("Cache is stale or missing, rescanning...");
-    scan_projects(root_path).await
+    Ok(())
+}
+
+fn report_migration(name: &str, migration: Migration) {
+    if migration.changed() {
+        println!("{}: schema {} -> {}", name, migration.from, migration.to);
+    } else {
+        println!("{}: up to date (schema {})", name, migration.to);
+    }
+}
+
+/// Days in one stage after which status calls a project stuck
+const STUCK_AFTER_DAYS: i64 = 7;
+
+/// When the project entered its stage, if it has been there for a while and is not done
+fn stuck_since(project: &skm::Project) -> Option<DateTime<Utc>> {
+    project.stage_since
+        .filter(|since| Utc::now() - *since >= chrono::Duration::days(STUCK_AFTER_DAYS))
+        .filter(|_| project.stage != Stage::Done)
 }
 
-fn display_portfolio_status(portfolio: &PortfolioStatus) {
+fn display_portfolio_status(view: &PortfolioView<'_>) {
+    let portfolio = view.status;
     println!("=== Portfolio Status ===");
-    println!("Generated: {}", portfolio.generated_at.format("%Y-%m-%d %H:%M UTC"));
+    println!("Generated: {}", timefmt::minutes(portfolio.generated_at));
     println!();
     println!("Total Projects: {}", portfolio.summary.total_projects);
     println!("Need Attention: {}", portfolio.summary.needs_attention);
@@ -143,261 +3275,876 @@ fn display_portfolio_status(portfolio: &PortfolioStatus) {
             0.0
         }
     );
+    println!("Open Questions: {}", portfolio.summary.open_questions);
     println!("Average Priority: {:.1}", portfolio.summary.avg_priority);
+    println!("Completion: {:.0}% effort-weighted ({}/{} projects done)",
+        portfolio.summary.completion.effort_weighted_percent,
+        portfolio.summary.completion.projects_done,
+        portfolio.summary.completion.projects_total
+    );
+    if portfolio.summary.completion.by_root.len() > 1 {
+        for root in &portfolio.summary.completion.by_root {
+            println!("  {}: {} projects, {} need attention, {:.0}% effort-weighted",
+                root.root,
+                root.completion.projects_total,
+                root.needs_attention,
+                root.completion.effort_weighted_percent,
+            );
+        }
+    }
     println!();
     
     // Show projects by priority
-    let mut projects = portfolio.projects.clone();
-    projects.sort_by(|a, b| b.priority.partial_cmp(&a.priority).unwrap());
+    let projects = view.clone().sorted_by_priority();
     
     println!("Projects (by priority):");
     for project in projects.iter().take(10) {
         let status_icon = if project.tasks.completed == project.tasks.total && project.tasks.total > 0 {
-            "✅"
+            style::icon("✅", "done")
         } else if project.priority > 50.0 {
-            "🔴"
+            style::icon("🔴", "high")
         } else if project.priority > 30.0 {
-            "🟡"
+            style::icon("🟡", "medium")
         } else {
-            "🟢"
+            style::icon("🟢", "low")
         };
         
-        println!("  {} [{:>5.1}] {} - {:?} - {}/{} tasks", 
+        println!("  {} [{:>5.1}] {}{}{} - {:?} - {}/{} tasks - updated {}", 
             status_icon,
             project.priority,
-            project.path.file_name().and_then(|s| s.to_str()).unwrap_or("?"),
+            project.display_name(),
+            if project.remote.is_some() { " (remote)" } else { "" },
+            if project.pinned { " (pinned)" } else { "" },
             project.stage,
             project.tasks.completed,
-            project.tasks.total
+            project.tasks.total,
+            timefmt::ago(project.updated)
         );
+        if let Some(since) = stuck_since(project) {
+            println!("        stuck in {} for {}", project.stage, timefmt::humanize_duration(Utc::now() - since));
+        }
+        if let Some(sla) = project.review_sla.as_ref().filter(|sla| sla.breached) {
+            println!("        {}", style::paint(&style::decorate("⏰", &sla.summary()), style::Color::Red));
+        }
+        if !project.open_questions.is_empty() {
+            println!("        {} open questions", project.open_questions.len());
+        }
+        if let Some(debt) = project.code_debt.filter(|&n| n > 0) {
+            println!("        {} TODO/FIXME comments", debt);
+        }
+        if let Some(freshness) = project.freshness.as_ref().filter(|f| f.major_behind > 0) {
+            println!("        {}/{} dependencies a major version behind", freshness.major_behind, freshness.direct);
+        }
+        for feature in &project.features {
+            println!("        {} - {}, {}/{} tasks", feature.id, feature.stage, feature.tasks.completed, feature.tasks.total);
+        }
+    }
+    
+    if projects.len() > 10 {
+        println!("  ... and {} more projects", projects.len() - 10);
     }
     
-    if portfolio.projects.len() > 10 {
-        println!("  ... and {} more projects", portfolio.projects.len() - 10);
+    let error_counts = portfolio.scan_stats.error_counts();
+    if !error_counts.is_empty() {
+        let breakdown = error_counts.iter()
+            .map(|(phase, count)| format!("{} {}", count, phase))
+            .collect::<Vec<_>>()
+            .join(", ");
+        println!();
+        println!("Scan errors: {} ({})", portfolio.scan_stats.errors.len(), breakdown);
     }
 }
 
-async fn scan_projects(root_path: &str) -> Result<()> {
-    let root = Path::new(root_path);
-    let start_time = std::time::Instant::now();
+/// Outcome of the latest autopilot run, with the actions that failed
+fn display_autopilot_run(entries: &[AutopilotEntry]) {
+    let Some(first) = entries.first() else {
+        return;
+    };
+    println!();
+    println!("Autopilot: last run {}: {} executed, {} skipped, {} failed",
+        timefmt::ago(first.run_at),
+        actions::count(entries, Outcome::Executed),
+        actions::count(entries, Outcome::Skipped),
+        actions::count(entries, Outcome::Failed),
+    );
+    for entry in entries.iter().filter(|e| e.outcome == Outcome::Failed) {
+        println!("  {} {}: {} ({})", style::icon("❌", "FAILED"), entry.project, entry.command,
+            entry.reason.as_deref().unwrap_or("unknown error"));
+    }
+}
+
+fn display_scan_errors(stats: &ScanStats) {
+    if stats.errors.is_empty() {
+        println!("No matching scan errors.");
+        return;
+    }
     
-    // Load configuration
+    for error in &stats.errors {
+        println!("[{}/{}] {}: {}", error.phase, error.kind, error.path.display(), error.message);
+    }
+}
+
+/// Scan one root, or every configured root when no `--root` is given
+async fn run_scan(root_arg: Option<&str>, only_root: Option<&str>, options: &ScanOptions, format: ScanFormat) -> Result<()> {
     let config = GlobalConfig::load()?;
-    let meta_store = ProjectMetaStore::load(root)?;
-    
-    // Initialize scanner
-    let scanner = ProjectScanner::new(root.to_path_buf(), config.scan_depth);
-    let projects_found = scanner.find_projects();
-    
-    // Process each project
-    let mut projects = Vec::new();
-    let mut errors = Vec::new();
-    let mut stage_counts: HashMap<Stage, u32> = HashMap::new();
-    let mut total_tasks = 0u32;
-    let mut completed_tasks = 0u32;
-    
-    for project_path in &projects_found {
-        match process_project(project_path, &config, &meta_store).await {
-            Ok(project) => {
-                // Update statistics
-                *stage_counts.entry(project.stage.clone()).or_insert(0) += 1;
-                total_tasks += project.tasks.total;
-                completed_tasks += project.tasks.completed;
-                
-                // Display project info
-                println!("Found: {} [{:?}] Priority: {:.1}", 
-                    project.path.display(), 
-                    project.stage,
-                    project.priority
-                );
-                
-                projects.push(project);
-            }
-            Err(e) => {
-                errors.push(format!("Error processing {}: {}", project_path.display(), e));
+    if let Some(name) = only_root
+        && !config.roots.iter().any(|r| r.name == name)
+    {
+        anyhow::bail!("Unknown root: {} (configured: {})", name,
+            config.roots.iter().map(|r| r.name.as_str()).collect::<Vec<_>>().join(", "));
+    }
+    match root_arg {
+        None if !config.roots.is_empty() => {
+            if options.git == GitMode::Deferred {
+                anyhow::bail!("--deferred-git needs a single --root");
             }
+            scan_configured_roots(&config, only_root, options, format)
         }
+        None if only_root.is_some() => anyhow::bail!("--only-root needs [[roots]] in the config"),
+        root => scan_projects(root.unwrap_or("."), options, format).await,
     }
+}
+
+/// Rescan a root whenever files under it change, at most once per interval
+///
+/// Each rescan updates `.skm/status.json` like `skm scan` and prints the
+/// stage moves, task progress, newly blocked tasks and attention threshold
+/// crossings since the previous one, colored by severity. The changes also
+/// go to the configured notifiers, in batches of
+/// `notifications.batch_minutes`. Falls back to polling when filesystem
+/// notifications are unavailable. On Ctrl-C the pending batch is sent
+/// before exiting.
+///
+/// Without `--root`, each of the `[[roots]]` is watched on a thread of its
+/// own, with its own cache and interval; typing a root's name follows it alone.
+#[cfg(feature = "tui")]
+fn watch(root_arg: Option<&str>, interval: Option<u64>, poll: bool, notify: bool) -> Result<()> {
+    use std::sync::mpsc;
     
-    // Calculate summary statistics
-    let avg_priority = if projects.is_empty() { 
-        0.0 
-    } else { 
-        projects.iter().map(|p| p.priority).sum::<f64>() / projects.len() as f64
+    let mut config = GlobalConfig::load()?;
+    config.notifications.desktop |= notify;
+    if let Some(e) = alerts::webhook_unavailable(&config.notifications) {
+        eprintln!("Warning: notifications.webhook_url is ignored: {}", e);
+    }
+    let roots: Vec<WatchedRoot> = match root_arg {
+        None if !config.roots.is_empty() => config.roots.iter()
+            .filter(|root| {
+                let dir = root.resolved_path().is_dir();
+                if !dir {
+                    eprintln!("Skipping root {}: {} is not a directory", root.name, root.resolved_path().display());
+                }
+                dir
+            })
+            .map(|root| WatchedRoot { path: root.resolved_path(), config: config.for_root(root), root: Some(root.clone()) })
+            .collect(),
+        root => vec![WatchedRoot { path: PathBuf::from(root.unwrap_or(".")), config: config.clone(), root: None }],
     };
+    if roots.is_empty() {
+        anyhow::bail!("None of the configured roots is a directory");
+    }
+    
+    let channels: Vec<_> = roots.iter().map(|_| mpsc::channel::<Wake>()).collect();
+    let stops: Vec<_> = channels.iter().map(|(tx, _)| tx.clone()).collect();
+    tokio::runtime::Handle::current().spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            for stop in stops {
+                let _ = stop.send(Wake::Stop);
+            }
+        }
+    });
+    let focus = Focus::default();
+    if roots.len() > 1 {
+        let named: Vec<(String, PathBuf)> = roots.iter().filter_map(|root| Some((root.name()?.to_string(), root.path.clone()))).collect();
+        println!("Watching {} roots; type a root's name (or number) and Enter to follow it alone, `all` for every root", named.len());
+        follow_roots(named, focus.clone());
+    }
+    
+    std::thread::scope(|scope| {
+        for (root, (tx, rx)) in roots.iter().zip(channels) {
+            let interval = interval.map(Duration::from_secs)
+                .unwrap_or(root.config.watch_interval())
+                .max(Duration::from_secs(1));
+            let focus = &focus;
+            scope.spawn(move || {
+                if let Err(e) = watch_root(root, interval, poll, tx, rx, focus) {
+                    eprintln!("Stopped watching {}: {:#}", root.path.display(), e);
+                }
+            });
+        }
+    });
+    Ok(())
+}
+
+/// A root `skm watch` rescans, named when it is one of the `[[roots]]`
+#[cfg(feature = "tui")]
+struct WatchedRoot {
+    path: PathBuf,
+    /// The global config with the root's overrides, as of startup
+    config: GlobalConfig,
+    root: Option<RootConfig>,
+}
+
+#[cfg(feature = "tui")]
+impl WatchedRoot {
+    fn name(&self) -> Option<&str> {
+        self.root.as_ref().map(|root| root.name.as_str())
+    }
+    
+    /// Settings for the next rescan, so config edits apply without a restart
+    fn scan_config(&self) -> Result<GlobalConfig> {
+        let config = GlobalConfig::load()?;
+        Ok(match &self.root {
+            Some(root) => config.for_root(root),
+            None => config,
+        })
+    }
+}
+
+/// The root whose output `skm watch` shows, or `None` for every root
+#[cfg(feature = "tui")]
+type Focus = Arc<std::sync::Mutex<Option<String>>>;
+
+/// Switch the followed root as names or numbers are typed on stdin
+///
+/// Following a root prints its last cached summary. The thread is left
+/// running; it ends with the process.
+#[cfg(feature = "tui")]
+fn follow_roots(roots: Vec<(String, PathBuf)>, focus: Focus) {
+    use std::io::BufRead;
+    let names: Vec<String> = roots.iter().map(|(name, _)| name.clone()).collect();
+    for (number, name) in names.iter().enumerate() {
+        println!("  {}. {}", number + 1, name);
+    }
+    std::thread::spawn(move || {
+        for line in std::io::stdin().lock().lines() {
+            let Ok(line) = line else { break };
+            let choice = line.trim();
+            let name = choice.parse::<usize>().ok()
+                .and_then(|number| names.get(number.wrapping_sub(1)))
+                .or_else(|| names.iter().find(|name| name.as_str() == choice));
+            match name {
+                Some(name) => {
+                    *focus.lock().unwrap() = Some(name.clone());
+                    let path = roots.iter().find(|(root, _)| root == name).map(|(_, path)| path);
+                    match path.and_then(|path| cached_portfolio(path).ok().flatten()) {
+                        Some(portfolio) => println!("Following {}: {} projects, {} need attention", name,
+                            portfolio.summary.total_projects, portfolio.summary.needs_attention),
+                        None => println!("Following {}", name),
+                    }
+                }
+                None if choice == "all" || choice.is_empty() => {
+                    *focus.lock().unwrap() = None;
+                    println!("Following every root");
+                }
+                None => println!("Unknown root: {} (roots: {})", choice, names.join(", ")),
+            }
+        }
+    });
+}
+
+/// Watch one root until a `Wake::Stop` arrives on `rx`
+#[cfg(feature = "tui")]
+fn watch_root(
+    root: &WatchedRoot,
+    interval: Duration,
+    poll: bool,
+    tx: std::sync::mpsc::Sender<Wake>,
+    rx: std::sync::mpsc::Receiver<Wake>,
+    focus: &Focus,
+) -> Result<()> {
+    use notify::{RecursiveMode, Watcher};
+    use std::sync::mpsc::RecvTimeoutError;
+    
+    let config = &root.config;
+    let path = &root.path;
+    let notifiers = alerts::notifiers(&config.notifications, &config.retry);
+    let mut batch = Batcher::new(config.notifications.batch_interval());
+    // Output of a named root is prefixed with its name and follows the focus
+    let prefix = root.name().map(|name| format!("{}: ", name)).unwrap_or_default();
+    let shown = || focus.lock().unwrap().as_deref().is_none_or(|focus| root.name().is_none_or(|name| name == focus));
+    
+    let mut watcher = None;
+    if !poll {
+        let events = move |event| { let _ = tx.send(Wake::Changed(event)); };
+        match notify::recommended_watcher(events).and_then(|mut w| w.watch(path, RecursiveMode::Recursive).map(|_| w)) {
+            Ok(w) => watcher = Some(w),
+            Err(e) => eprintln!("{}Filesystem notifications unavailable ({}), polling instead", prefix, e),
+        }
+    }
+    println!("{}Watching {} ({}, every {}s at most); Ctrl-C to stop",
+        prefix,
+        path.display(),
+        if watcher.is_some() { "on changes" } else { "polling" },
+        interval.as_secs());
+    
+    let label = root.name().map(str::to_string).unwrap_or_else(|| root_label(path));
+    let mut previous = cached_portfolio(path)?;
+    'watch: loop {
+        let start_time = Instant::now();
+        let mut ctx = ScanContext::with_config(root.scan_config()?, path, ScanOptions::default())?;
+        let mut scan = scan_root(path, &mut ctx, root.name(), None)?;
+        // Remote projects are only refreshed by `skm scan`
+        if let Some(previous) = &previous {
+            scan.projects.extend(previous.projects.iter().filter(|p| p.remote.is_some()).cloned());
+        }
+        let (portfolio, fingerprints) = scan.into_portfolio(start_time, &[(label.clone(), path)], &ctx.config.workflow);
+        let changes = previous.as_ref().map(|previous| portfolio_changes(previous, &portfolio));
+        let cache = write_status(path, &portfolio, &fingerprints)?;
+        // The history gets a line when something changed, not on every tick
+        if changes.as_ref().is_none_or(|changes| !changes.is_empty()) {
+            cache.append_to_log(path)?;
+            StatusCache::prune_log(path, cache.last_updated)?;
+        }
+        commit_status_report(path, config)?;
+        record_history(path, &ctx, &portfolio.projects, portfolio.generated_at)?;
+        
+        let time = timefmt::display_zone().format_bare(portfolio.generated_at, "%H:%M:%S");
+        match changes {
+            None => println!("[{}] {}{} projects, {} need attention", time, prefix,
+                portfolio.summary.total_projects, portfolio.summary.needs_attention),
+            Some(changes) => {
+                if shown() {
+                    for change in &changes {
+                        println!("{}", style::paint(&format!("[{}] {}{}", time, prefix, change), Severity::of(change).color()));
+                    }
+                }
+                let notified = changes.into_iter().filter(|change| config.notifications.notifies(change)).collect();
+                if !notifiers.is_empty() && let Some(notification) = batch.push(notified) {
+                    send_notification(&notifiers, &notification);
+                }
+            }
+        }
+        previous = Some(portfolio);
+        
+        if watcher.is_none() {
+            if settle(&rx, interval) {
+                break;
+            }
+            if let Some(notification) = batch.take_due() {
+                send_notification(&notifiers, &notification);
+            }
+            continue;
+        }
+        // Block until something outside skm's own state changes, sending
+        // batched notifications as they fall due, then let the burst settle
+        // for an interval before rescanning
+        loop {
+            let wait = batch.time_to_flush().unwrap_or(Duration::from_secs(3600));
+            match rx.recv_timeout(wait) {
+                Ok(Wake::Stop) => break 'watch,
+                Ok(Wake::Changed(event)) if event.as_ref().is_ok_and(|event| event.paths.iter().any(|p| !is_scan_noise(p))) => break,
+                Ok(Wake::Changed(_)) => {}
+                Err(RecvTimeoutError::Timeout) => {
+                    if let Some(notification) = batch.take_due() {
+                        send_notification(&notifiers, &notification);
+                    }
+                }
+                Err(RecvTimeoutError::Disconnected) => anyhow::bail!("filesystem watcher stopped"),
+            }
+        }
+        if settle(&rx, interval) {
+            break;
+        }
+    }
     
-    let needs_attention = projects.iter()
-        .filter(|p| p.priority > config.attention_threshold)
-        .count() as u32;
+    // Changes still waiting for their batch are not dropped on Ctrl-C
+    if let Some(notification) = batch.take() {
+        send_notification(&notifiers, &notification);
+    }
+    println!("Stopped watching {}", path.display());
+    Ok(())
+}
+
+/// What wakes `skm watch` while it waits
+#[cfg(feature = "tui")]
+enum Wake {
+    Changed(notify::Result<notify::Event>),
+    Stop,
+}
+
+/// Wait out an interval, skipping filesystem events; returns whether watching was stopped
+#[cfg(feature = "tui")]
+fn settle(rx: &std::sync::mpsc::Receiver<Wake>, interval: Duration) -> bool {
+    let deadline = Instant::now() + interval;
+    loop {
+        match rx.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+            Ok(Wake::Stop) => return true,
+            Ok(Wake::Changed(_)) => {}
+            Err(_) => return false,
+        }
+    }
+}
+
+#[cfg(feature = "tui")]
+fn send_notification(notifiers: &[Box<dyn alerts::Notifier>], notification: &Notification) {
+    for (notifier, error) in alerts::deliver(notifiers, notification) {
+        eprintln!("Warning: could not notify {}: {}", notifier, error);
+    }
+}
+
+/// Changes that cannot affect the analysis: skm's own state, which each
+/// rescan writes, and build or dependency directories (`.git` still counts)
+#[cfg(feature = "tui")]
+fn is_scan_noise(path: &Path) -> bool {
+    path.components().any(|c| {
+        let dir = Path::new(c.as_os_str());
+        c.as_os_str() == ".skm" || (c.as_os_str() != ".git" && skm::scanner::finder::should_ignore(dir))
+    })
+}
+
+/// The portfolio of the last scan of a root, if it has a cache
+#[cfg(any(feature = "rag", feature = "session", feature = "serve", feature = "tui"))]
+fn cached_portfolio(root: &Path) -> Result<Option<PortfolioStatus>> {
+    match StatusCache::load_migrated(root)? {
+        Some((cache, _)) => Ok(Some(serde_json::from_value(cache.data)?)),
+        None => Ok(None),
+    }
+}
+
+/// Projects, errors and timings collected from one or more roots
+#[derive(Default)]
+struct RootScan {
+    directories: usize,
+    projects: Vec<skm::Project>,
+    errors: Vec<skm::ScanError>,
+    timings: Vec<skm::ProjectTiming>,
+    discover_ms: u64,
+    /// Fingerprint of every discovered project, keyed by path, including failed ones
+    fingerprints: BTreeMap<String, String>,
+}
+
+impl RootScan {
+    fn extend(&mut self, other: RootScan) {
+        self.directories += other.directories;
+        self.projects.extend(other.projects);
+        self.errors.extend(other.errors);
+        self.timings.extend(other.timings);
+        self.discover_ms += other.discover_ms;
+        self.fingerprints.extend(other.fingerprints);
+    }
     
-    // Create portfolio status
-    let portfolio = PortfolioStatus {
-        generated_at: Utc::now(),
-        scan_stats: ScanStats {
-            directories_scanned: projects_found.len() as u32,
-            projects_found: projects.len() as u32,
+    /// Assemble a portfolio, with a completion breakdown for each labeled
+    /// root, and the fingerprints to cache it with
+    fn into_portfolio(self, start_time: Instant, roots: &[(String, &Path)], workflow: &WorkflowConfig) -> (PortfolioStatus, BTreeMap<String, String>) {
+        let mut scan_stats = ScanStats {
+            directories_scanned: self.directories as u32,
+            projects_found: self.projects.len() as u32,
             scan_time_ms: start_time.elapsed().as_millis() as u64,
-            errors,
-        },
-        summary: StatusSummary {
-            needs_attention,
-            total_projects: projects.len() as u32,
-            by_stage: stage_counts,
-            total_tasks,
-            completed_tasks,
-            avg_priority,
-        },
-        projects,
+            errors: self.errors,
+            discover_ms: self.discover_ms,
+            slowest: Vec::new(),
+        };
+        scan_stats.set_slowest(self.timings);
+        
+        (PortfolioStatus {
+            generated_at: Utc::now(),
+            scan_stats,
+            summary: pipeline::summarize_roots(&self.projects, roots, workflow),
+            projects: self.projects,
+        }, self.fingerprints)
+    }
+}
+
+/// Discover and process the projects under a root, reporting each one in
+/// the `output` format, or silently with `None`
+///
+/// Every processed project's fingerprint is stored in `.skm/fingerprints.json`.
+/// In incremental mode, projects whose fingerprint is unchanged are taken
+/// from the root's status cache instead of being processed again.
+fn scan_root(root: &Path, ctx: &mut ScanContext, root_name: Option<&str>, output: Option<ScanFormat>) -> Result<RootScan> {
+    let discover_start = Instant::now();
+    let projects_found = pipeline::discover(root, ctx)?;
+    
+    let mut scan = RootScan {
+        directories: projects_found.len(),
+        discover_ms: discover_start.elapsed().as_millis() as u64,
+        ..Default::default()
     };
     
-    // Cache the status
-    let cache = StatusCache {
-        last_updated: Utc::now(),
-        data: serde_json::to_value(&portfolio)?,
+    let mut fingerprints = FingerprintStore::load(root)?;
+    let mut cached: HashMap<PathBuf, skm::Project> = match ctx.options.incremental {
+        true => cached_root_scan(root)?.map_or_else(HashMap::new, |cache| cache.projects.into_iter()
+            .filter(|p| p.remote.is_none())
+            .map(|p| (p.path.clone(), p))
+            .collect()),
+        false => HashMap::new(),
     };
-    cache.save(root)?;
+    let prints: Vec<String> = projects_found.iter().map(|path| fingerprint(path, ctx)).collect();
+    scan.fingerprints = projects_found.iter().zip(&prints)
+        .map(|(path, print)| (path.to_string_lossy().into_owned(), print.clone()))
+        .collect();
+    let mut reused: HashMap<usize, skm::Project> = HashMap::new();
+    for (index, path) in projects_found.iter().enumerate() {
+        if fingerprints.unchanged(path, &prints[index])
+            && let Some(project) = cached.remove(path)
+        {
+            reused.insert(index, project);
+        }
+    }
+    let stale: Vec<PathBuf> = projects_found.iter().enumerate()
+        .filter(|(index, _)| !reused.contains_key(index))
+        .map(|(_, path)| path.clone())
+        .collect();
+    let jsonl = output == Some(ScanFormat::Jsonl);
+    if jsonl {
+        let mut unchanged: Vec<_> = reused.iter().collect();
+        unchanged.sort_by_key(|(index, _)| **index);
+        unchanged.into_iter().for_each(|(_, project)| emit_project(project, root_name, true));
+    }
+    // Streamed as each project finishes, in whatever order the workers do
+    let mut results = pipeline::process_projects_with(&stale, ctx, |_, result| match result {
+        _ if !jsonl => {}
+        Ok((project, _)) => emit_project(project, root_name, false),
+        Err(e) => emit_record("error", e),
+    }).into_iter();
     
-    // Save markdown report
-    use skm::reporter::save_markdown_report;
-    let report_path = root.join(".skm/STATUS.md");
-    save_markdown_report(&portfolio, &report_path)?;
+    for (index, path) in projects_found.iter().enumerate() {
+        let (mut project, unchanged) = match reused.remove(&index) {
+            Some(mut project) => {
+                // Unchanged on disk, but staleness, seasons and review SLAs follow the clock
+                let overrides = ctx.config.for_project(path)?;
+                pipeline::rerank(&mut project, overrides.as_ref().unwrap_or(&ctx.config), ctx);
+                (project, true)
+            }
+            None => match results.next().expect("one result per stale project") {
+                Ok((project, timing)) => {
+                    fingerprints.insert(path, prints[index].clone());
+                    scan.timings.push(timing);
+                    (project, false)
+                }
+                Err(e) => {
+                    fingerprints.projects.remove(&path.to_string_lossy().into_owned());
+                    scan.errors.push(e);
+                    continue;
+                }
+            },
+        };
+        project.root = root_name.map(str::to_string);
+        
+        // Display project info
+        if output == Some(ScanFormat::Text) {
+            println!("Found: {} [{:?}] Priority: {:.1}{}", 
+                project.path.display(), 
+                project.stage,
+                project.priority,
+                if unchanged { " (unchanged)" } else { "" },
+            );
+            if !project.unstable.is_empty() {
+                println!("  {}", style::decorate("⚠️ ", &format!("Unstable across unchanged scans: {}", format_unstable(&project.unstable))));
+            }
+        }
+        
+        let field_errors = pipeline::custom_field_errors(&project, ctx);
+        if jsonl {
+            field_errors.iter().for_each(|e| emit_record("error", e));
+        }
+        scan.errors.extend(field_errors);
+        scan.projects.push(project);
+    }
+    fingerprints.save(root)?;
+    
+    Ok(scan)
+}
+
+async fn scan_projects(root_path: &str, options: &ScanOptions, format: ScanFormat) -> Result<()> {
+    let root = Path::new(root_path);
+    let start_time = Instant::now();
+    
+    // Load configuration, metadata and history
+    let mut ctx = ScanContext::load(root, options.clone())?;
+    let mut scan = scan_root(root, &mut ctx, None, Some(format))?;
+    let ctx = Arc::new(ctx);
+    scan.extend(scan_remotes(&ctx, format)?);
+    let (mut portfolio, fingerprints) = scan.into_portfolio(start_time, &[(root_label(root), root)], &ctx.config.workflow);
+    
+    // With deferred git the report is committed, and the cache trusted by
+    // `skm status`, once git status is filled in
+    if options.git == GitMode::Deferred {
+        let pending = portfolio.projects.iter().any(|p| p.git.pending);
+        let trusted = if pending { BTreeMap::new() } else { fingerprints.clone() };
+        write_portfolio(root, &portfolio, &trusted)?;
+    } else {
+        save_portfolio(root, &portfolio, &fingerprints, &ctx.config)?;
+    }
+    
+    record_history(root, &ctx, &portfolio.projects, portfolio.generated_at)?;
+    
+    display_scan_summary(&portfolio, format);
+    
+    if options.git == GitMode::Deferred {
+        fill_deferred_git(root, &mut portfolio, &fingerprints, Arc::clone(&ctx)).await?;
+    }
+    
+    Ok(())
+}
+
+/// Scan each configured root with its own weights and threshold
+///
+/// Every root gets its own cache, and the combined portfolio is cached in
+/// the portfolio home so `status` and `digest` work without `--root`.
+fn scan_configured_roots(config: &GlobalConfig, only_root: Option<&str>, options: &ScanOptions, format: ScanFormat) -> Result<()> {
+    let start_time = Instant::now();
+    let mut combined = RootScan::default();
+    let mut roots = Vec::new();
+    
+    for root_config in &config.roots {
+        let root = root_config.resolved_path();
+        if !root.is_dir() {
+            eprintln!("Skipping root {}: {} is not a directory", root_config.name, root.display());
+            continue;
+        }
+        
+        // Each root keeps its own cache, so untouched roots can be reused as-is
+        if only_root.is_some_and(|name| name != root_config.name)
+            && let Some(cached) = cached_root_scan(&root)?
+        {
+            combined.extend(cached);
+            roots.push((root_config.name.clone(), root));
+            continue;
+        }
+        
+        let root_start = Instant::now();
+        let mut ctx = ScanContext::with_config(config.for_root(root_config), &root, options.clone())?;
+        let scan = scan_root(&root, &mut ctx, Some(&root_config.name), Some(format))?;
+        
+        let (portfolio, fingerprints) = RootScan {
+            directories: scan.directories,
+            projects: scan.projects.clone(),
+            errors: scan.errors.clone(),
+            timings: scan.timings.clone(),
+            discover_ms: scan.discover_ms,
+            fingerprints: scan.fingerprints.clone(),
+        }.into_portfolio(root_start, &[(root_config.name.clone(), &root)], &ctx.config.workflow);
+        // Only the combined report below is committed
+        write_portfolio(&root, &portfolio, &fingerprints)?;
+        
+        record_history(&root, &ctx, &portfolio.projects, portfolio.generated_at)?;
+        
+        if format == ScanFormat::Text {
+            println!("Root {}: {} projects, {} need attention (threshold {:.1})",
+                root_config.name,
+                portfolio.summary.total_projects,
+                portfolio.summary.needs_attention,
+                ctx.config.attention_threshold,
+            );
+        }
+        
+        combined.extend(scan);
+        roots.push((root_config.name.clone(), root));
+    }
+    
+    let home = GlobalConfig::portfolio_home()?;
+    match only_root.and(cached_root_scan(&home)?) {
+        Some(cached) => {
+            // Remote projects are only refreshed by a full scan
+            combined.projects.extend(cached.projects.into_iter().filter(|p| p.remote.is_some()));
+        }
+        None => {
+            let remote_ctx = ScanContext::with_config(config.clone(), &home, options.clone())?;
+            let remotes = scan_remotes(&remote_ctx, format)?;
+            record_history(&home, &remote_ctx, &remotes.projects, Utc::now())?;
+            combined.extend(remotes);
+        }
+    }
+    
+    let labels: Vec<(String, &Path)> = roots.iter()
+        .map(|(name, path)| (name.clone(), path.as_path()))
+        .collect();
+    let (portfolio, fingerprints) = combined.into_portfolio(start_time, &labels, &config.workflow);
+    save_portfolio(&home, &portfolio, &fingerprints, config)?;
+    
+    display_scan_summary(&portfolio, format);
+    Ok(())
+}
+
+/// Projects and errors of a root as of its last scan, if it has a cache
+fn cached_root_scan(root: &Path) -> Result<Option<RootScan>> {
+    let Some((cache, _)) = StatusCache::load_migrated(root)? else {
+        return Ok(None);
+    };
+    let portfolio: PortfolioStatus = serde_json::from_value(cache.data)?;
+    Ok(Some(RootScan {
+        directories: portfolio.scan_stats.directories_scanned as usize,
+        projects: portfolio.projects,
+        errors: portfolio.scan_stats.errors,
+        fingerprints: cache.fingerprints,
+        ..Default::default()
+    }))
+}
+
+/// Append metrics samples, dependency checks and artifact snapshots for freshly scanned projects
+fn record_history(root: &Path, ctx: &ScanContext, projects: &[skm::Project], at: chrono::DateTime<Utc>) -> Result<()> {
+    let mut history = ctx.history.clone();
+    history.record(projects, at);
+    history.save(root)?;
+    
+    if ctx.config.audit.enabled || ctx.config.freshness.enabled || ctx.config.github.enabled {
+        let mut audits = ctx.audits.clone();
+        audits.record(projects);
+        audits.save(root)?;
+    }
     
-    // Display summary
+    let mut snapshots = ctx.snapshots.clone();
+    snapshots.record(root, projects, at, ctx.config.snapshots.keep)?;
+    Ok(snapshots.save(root)?)
+}
+
+fn display_scan_summary(portfolio: &PortfolioStatus, format: ScanFormat) {
+    if format == ScanFormat::Jsonl {
+        let record = serde_json::json!({
+            "generated_at": portfolio.generated_at,
+            "summary": portfolio.summary,
+            "directories_scanned": portfolio.scan_stats.directories_scanned,
+            "scan_time_ms": portfolio.scan_stats.scan_time_ms,
+            "errors": portfolio.scan_stats.errors.len(),
+        });
+        emit_record("summary", &record);
+        return;
+    }
     println!("\n=== Scan Complete ===");
     println!("Projects found: {}", portfolio.summary.total_projects);
     println!("Need attention: {}", portfolio.summary.needs_attention);
     println!("Tasks: {}/{} completed", portfolio.summary.completed_tasks, portfolio.summary.total_tasks);
     println!("Average priority: {:.1}", portfolio.summary.avg_priority);
     println!("Scan time: {}ms", portfolio.scan_stats.scan_time_ms);
+    if let Some(slowest) = portfolio.scan_stats.slowest.first() {
+        let (phase, phase_ms) = slowest.dominant_phase();
+        println!("Slowest project: {} ({}ms, {} {}ms)", slowest.id, slowest.total_ms, phase, phase_ms);
+    }
     
     if !portfolio.scan_stats.errors.is_empty() {
         println!("\nErrors encountered:");
         for error in &portfolio.scan_stats.errors {
-            println!("  - {}", error);
+            println!("  - [{}/{}] {}", error.phase, error.kind, error);
         }
     }
-    
-    Ok(())
 }
 
-async fn process_project(
-    project_path: &Path, 
-    config: &GlobalConfig,
-    meta_store: &ProjectMetaStore,
-) -> Result<Project> {
-    // Parse artifacts from .specify or specs directory
-    // Prefer .specify if it has proper artifacts, otherwise check specs
-    let specify_path = project_path.join(".specify");
-    let specs_path = project_path.join("specs");
-    
-    if is_debug() {
-        eprintln!("[DEBUG] Processing project: {}", project_path.display());
-        eprintln!("[DEBUG]   .specify exists: {}", specify_path.exists());
-        eprintln!("[DEBUG]   specs exists: {}", specs_path.exists());
+/// A project record of `skm scan --format jsonl`: the project as cached, with its root
+fn emit_project(project: &skm::Project, root: Option<&str>, unchanged: bool) {
+    let mut record = serde_json::to_value(project).unwrap_or_default();
+    if let Some(root) = root {
+        record["root"] = serde_json::json!(root);
+    }
+    record["unchanged"] = serde_json::json!(unchanged);
+    emit_record("project", &record);
+}
+
+/// Write one JSON Lines record with a `type` field, flushed so a consumer
+/// sees it at once; a closed pipe is ignored
+fn emit_record(kind: &str, value: &impl serde::Serialize) {
+    let mut record = serde_json::to_value(value).unwrap_or_default();
+    if let Some(object) = record.as_object_mut() {
+        object.insert("type".to_string(), serde_json::json!(kind));
+    }
+    use std::io::Write;
+    let mut out = std::io::stdout().lock();
+    let _ = writeln!(out, "{}", record).and_then(|_| out.flush());
+}
+
+/// Collect git status for projects scanned with deferred git, then re-save the cache
+async fn fill_deferred_git(
+    root: &Path,
+    portfolio: &mut PortfolioStatus,
+    fingerprints: &BTreeMap<String, String>,
+    ctx: Arc<ScanContext>,
+) -> Result<()> {
+    let pending = portfolio.projects.iter().filter(|p| p.git.pending).count();
+    if pending == 0 {
+        return Ok(());
     }
     
-    // Prefer specs directory if it exists (it usually has the feature directories)
-    // Only use .specify if specs doesn't exist or has no content
-    let artifacts = if specs_path.exists() {
-        let specs_artifacts = parser::parse_artifacts(&specs_path)?;
-        // Check if specs has any artifacts
-        if specs_artifacts.constitution.is_some() || specs_artifacts.spec.is_some() ||
-           specs_artifacts.plan.is_some() || specs_artifacts.tasks.is_some() {
-            if is_debug() {
-                eprintln!("[DEBUG]   Using artifacts from specs");
-            }
-            specs_artifacts
-        } else if specify_path.exists() {
-            // specs has no artifacts, try .specify
-            if is_debug() {
-                eprintln!("[DEBUG]   specs has no artifacts, trying .specify");
-            }
-            parser::parse_artifacts(&specify_path)?
-        } else {
-            specs_artifacts // Use empty artifacts from specs
-        }
-    } else if specify_path.exists() {
-        // No specs, try .specify
-        if is_debug() {
-            eprintln!("[DEBUG]   Using artifacts from .specify");
-        }
-        parser::parse_artifacts(&specify_path)?
-    } else {
-        // Neither exists, return empty artifacts
-        if is_debug() {
-            eprintln!("[DEBUG]   No artifacts found");
+    println!("\nCollecting git status for {} projects in background...", pending);
+    let attention_before = portfolio.summary.needs_attention;
+    
+    let handles: Vec<_> = std::mem::take(&mut portfolio.projects)
+        .into_iter()
+        .map(|mut project| {
+            let ctx = Arc::clone(&ctx);
+            tokio::task::spawn_blocking(move || {
+                let result = if project.git.pending {
+                    pipeline::refresh_git(&mut project, &ctx)
+                } else {
+                    Ok(())
+                };
+                (project, result)
+            })
+        })
+        .collect();
+    
+    for handle in handles {
+        let (project, result) = handle.await?;
+        if let Err(e) = result {
+            portfolio.scan_stats.errors.push(e);
         }
-        parser::parse_artifacts(&specify_path)?
-    };
+        portfolio.projects.push(project);
+    }
     
-    // Parse tasks if available
-    let tasks = if let Some(ref task_file) = artifacts.tasks {
-        parser::parse_tasks_file(&task_file.path)?
-    } else {
-        Default::default()
-    };
+    portfolio.summary = pipeline::summarize_roots(&portfolio.projects, &[(root_label(root), root)], &ctx.config.workflow);
+    portfolio.generated_at = Utc::now();
+    save_portfolio(root, portfolio, fingerprints, &ctx.config)?;
     
-    // Get git status
-    let git_status = git::get_git_status(project_path)?;
-    
-    // Detect project type first
-    use skm::scanner::finder;
-    let project_type = finder::detect_project_type(project_path);
-    
-    // Detect stage
-    let current_stage = stage::detect_stage(&artifacts, &project_type);
-    
-    // Calculate risk and detect requirements
-    let has_errors = git::has_recent_errors(project_path)?;
-    let risk_level = priority::calculate_risk(&current_stage, &git_status, &tasks, has_errors);
-    let human_reqs = priority::detect_human_requirements(&current_stage, &git_status, &tasks);
-    
-    // Get project metadata
-    let project_id = project_path.file_name()
-        .and_then(|s| s.to_str())
-        .unwrap_or("unknown")
-        .to_string();
-    
-    let project_meta = meta_store.get_project(&project_id);
-    let impact = project_meta.and_then(|m| m.impact).unwrap_or(2);
-    let confidence = if project_meta.map(|m| m.approved_by_human).unwrap_or(false) { 2 } else { 1 };
-    
-    // Calculate priority
-    let calculator = PriorityCalculator::new(priority::PriorityWeights {
-        needs_human: config.weights.needs_human,
-        risk: config.weights.risk,
-        staleness: config.weights.staleness,
-        impact: config.weights.impact,
-        confidence: config.weights.confidence,
-    });
+    println!("Git status updated: {} need attention (was {})",
+        portfolio.summary.needs_attention, attention_before);
     
-    let last_updated = artifacts.spec
-        .as_ref()
-        .map(|f| f.modified)
-        .unwrap_or_else(Utc::now);
-    
-    let priority_score = calculator.calculate(
-        &human_reqs,
-        risk_level,
-        last_updated,
-        impact,
-        confidence,
-    );
+    Ok(())
+}
+
+/// Label for an unnamed scan root: its directory name
+fn root_label(root: &Path) -> String {
+    root.canonicalize().ok()
+        .and_then(|p| p.file_name().map(|n| n.to_string_lossy().into_owned()))
+        .unwrap_or_else(|| root.display().to_string())
+}
+
+/// Write the status cache and markdown report, then commit the report if
+/// `[report_commit]` is enabled
+fn save_portfolio(root: &Path, portfolio: &PortfolioStatus, fingerprints: &BTreeMap<String, String>, config: &GlobalConfig) -> Result<()> {
+    write_portfolio(root, portfolio, fingerprints)?;
+    commit_status_report(root, config)
+}
+
+/// Commit `.skm/STATUS.md` if `[report_commit]` is enabled
+fn commit_status_report(root: &Path, config: &GlobalConfig) -> Result<()> {
+    if config.report_commit.enabled {
+        let report = std::fs::read_to_string(root.join(".skm/STATUS.md"))?;
+        commit_reports(root, config, &[("STATUS.md", &report)]);
+    }
+    Ok(())
+}
+
+/// Commit reports to the configured repository; a failure is reported but
+/// does not fail the command that wrote them
+fn commit_reports(root: &Path, config: &GlobalConfig, reports: &[(&str, &str)]) {
+    match publish::git::commit_reports(root, &config.report_commit, reports, Utc::now()) {
+        Ok(Some(oid)) => eprintln!("Committed reports ({})", &oid[..7]),
+        Ok(None) => {}
+        Err(e) => eprintln!("Warning: could not commit reports: {}", e),
+    }
+}
+
+/// Write the status cache and markdown report for a portfolio, and log it
+/// in the history
+///
+/// `fingerprints` are those the projects were scanned with; `skm status`
+/// serves the cache only while they still match.
+fn write_portfolio(root: &Path, portfolio: &PortfolioStatus, fingerprints: &BTreeMap<String, String>) -> Result<()> {
+    let cache = write_status(root, portfolio, fingerprints)?;
+    cache.append_to_log(root)?;
+    StatusCache::prune_log(root, cache.last_updated)?;
+    Ok(())
+}
+
+/// Write the status cache and markdown report without logging them
+fn write_status(root: &Path, portfolio: &PortfolioStatus, fingerprints: &BTreeMap<String, String>) -> Result<StatusCache> {
+    // Cache the status
+    let cache = StatusCache::new(serde_json::to_value(portfolio)?).with_fingerprints(fingerprints.clone());
+    cache.save(root)?;
     
-    // Get next action
-    let next_action = stage::get_next_action(&current_stage);
-    
-    Ok(Project {
-        id: project_id,
-        path: project_path.to_path_buf(),
-        stage: current_stage,
-        next: next_action,
-        requires_human: human_reqs,
-        priority: priority_score,
-        tasks,
-        updated: last_updated,
-        git: git_status,
-        project_type,
-        artifacts,
-    })
+    // Save markdown report
+    use skm::reporter::save_markdown_report;
+    let report_path = root.join(".skm/STATUS.md");
+    save_markdown_report(portfolio, &report_path)?;
+    
+    Ok(cache)
 }
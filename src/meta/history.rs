@@ -1 +0,0 @@
-// Placeholder for src/meta/history.rs
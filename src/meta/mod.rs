@@ -1,6 +0,0 @@
-pub mod config;
-pub mod state;
-pub mod history;
-
-pub use config::*;
-pub use state::*;
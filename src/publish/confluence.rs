@@ -0,0 +1,155 @@
+use serde_json::{json, Value};
+use crate::{Result, SKMError};
+use crate::meta::config::ConfluenceConfig;
+use crate::http::http_json_with;
+use crate::retry::{self, RetryPolicy};
+use super::{Block, Publisher, Span};
+
+/// Replaces a Confluence page's body with the report, as a new page version
+pub struct ConfluencePublisher {
+    base_url: String,
+    /// The page's REST endpoint
+    url: String,
+    page_id: String,
+    authorization: String,
+    policy: RetryPolicy,
+}
+
+impl ConfluencePublisher {
+    pub fn new(config: &ConfluenceConfig, token: String, policy: RetryPolicy) -> Self {
+        let authorization = match &config.email {
+            Some(email) => format!("Basic {}", base64(format!("{}:{}", email, token).as_bytes())),
+            None => format!("Bearer {}", token),
+        };
+        let base_url = config.base_url.trim_end_matches('/').to_string();
+        ConfluencePublisher {
+            url: format!("{}/rest/api/content/{}", base_url, config.page_id),
+            base_url,
+            page_id: config.page_id.clone(),
+            authorization,
+            policy,
+        }
+    }
+
+    fn request(&self, method: &str, url: &str, body: Option<&Value>) -> Result<Value> {
+        let headers = [format!("Authorization: {}", self.authorization), "Accept: application/json".to_string()];
+        retry::retry(&self.policy, &format!("{} {}", method, url), || http_json_with(method, url, &headers, body))
+            .map_err(|e| SKMError::PublishError { message: e.to_string() })
+    }
+}
+
+impl Publisher for ConfluencePublisher {
+    fn key(&self) -> String {
+        format!("confluence:{}/{}", self.base_url, self.page_id)
+    }
+
+    fn publish(&self, blocks: &[Block]) -> Result<()> {
+        let page = self.request("GET", &format!("{}?expand=version", self.url), None)?;
+        let version = page["version"]["number"].as_u64().ok_or_else(|| SKMError::PublishError {
+            message: format!("No version in the response for page {}", self.page_id),
+        })?;
+        let body = json!({
+            "id": self.page_id,
+            "type": "page",
+            "title": page["title"],
+            "version": { "number": version + 1, "message": "Updated by skm publish" },
+            "body": { "storage": { "value": to_storage(blocks), "representation": "storage" } },
+        });
+        self.request("PUT", &self.url, Some(&body))?;
+        Ok(())
+    }
+}
+
+/// The blocks in Confluence storage format (XHTML); consecutive list items
+/// share one list
+fn to_storage(blocks: &[Block]) -> String {
+    let mut out = String::new();
+    let mut in_list = false;
+    for block in blocks {
+        let bullet = matches!(block, Block::Bullet { .. });
+        if in_list && !bullet {
+            out.push_str("</ul>");
+        }
+        if bullet && !in_list {
+            out.push_str("<ul>");
+        }
+        in_list = bullet;
+        match block {
+            Block::Heading { level, text } => out.push_str(&format!("<h{0}>{1}</h{0}>", level, inline(text))),
+            Block::Paragraph(text) => out.push_str(&format!("<p>{}</p>", inline(text))),
+            Block::Bullet { text, children } => {
+                out.push_str(&format!("<li>{}", inline(text)));
+                if !children.is_empty() {
+                    out.push_str("<ul>");
+                    for child in children {
+                        out.push_str(&format!("<li>{}</li>", inline(child)));
+                    }
+                    out.push_str("</ul>");
+                }
+                out.push_str("</li>");
+            }
+            Block::Table { header, rows } => {
+                out.push_str("<table><tbody><tr>");
+                for cell in header {
+                    out.push_str(&format!("<th>{}</th>", inline(cell)));
+                }
+                out.push_str("</tr>");
+                for row in rows {
+                    out.push_str("<tr>");
+                    for cell in row {
+                        out.push_str(&format!("<td>{}</td>", inline(cell)));
+                    }
+                    out.push_str("</tr>");
+                }
+                out.push_str("</tbody></table>");
+            }
+            Block::Divider => out.push_str("<hr/>"),
+        }
+    }
+    if in_list {
+        out.push_str("</ul>");
+    }
+    out
+}
+
+fn inline(spans: &[Span]) -> String {
+    spans.iter()
+        .map(|span| {
+            let mut html = escape(&span.text);
+            if span.code {
+                html = format!("<code>{}</code>", html);
+            }
+            if span.italic {
+                html = format!("<em>{}</em>", html);
+            }
+            if span.bold {
+                html = format!("<strong>{}</strong>", html);
+            }
+            if let Some(url) = &span.link {
+                html = format!("<a href=\"{}\">{}</a>", escape(url), html);
+            }
+            html
+        })
+        .collect()
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// Standard base64 with padding, for basic auth
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk.iter().enumerate().fold(0u32, |n, (i, &b)| n | (b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
@@ -0,0 +1,127 @@
+#[cfg(feature = "git")]
+use std::fs;
+use std::path::Path;
+use chrono::{DateTime, Utc};
+#[cfg(feature = "git")]
+use git2::{Index, IndexEntry, IndexTime, Oid, Repository};
+use crate::{Result, SKMError};
+use crate::meta::config::ReportCommitConfig;
+#[cfg(feature = "git")]
+use crate::timefmt;
+
+/// Lines that change on every run without the report saying anything new
+const VOLATILE_PREFIXES: &[&str] = &["Generated: ", "- **Scan Time**: "];
+
+/// Copy reports into the configured repository and commit the ones that changed
+///
+/// `reports` are file names and contents. A report counts as changed when
+/// it differs from the committed copy in more than its timestamp lines;
+/// unchanged ones are left as committed. The commit is built from `HEAD`
+/// plus the reports, so anything else staged in the repository stays
+/// staged and out of it. Returns the id of the new commit, or `None` when
+/// nothing changed.
+#[cfg(feature = "git")]
+pub fn commit_reports(
+    root: &Path,
+    config: &ReportCommitConfig,
+    reports: &[(&str, &str)],
+    now: DateTime<Utc>,
+) -> Result<Option<String>> {
+    let repo = match config.resolved_repo() {
+        Some(path) => Repository::open(&path),
+        None => Repository::discover(root),
+    }.map_err(|e| publish_error(format!("no git repository for the reports: {}", e.message())))?;
+    let workdir = repo.workdir()
+        .ok_or_else(|| publish_error("the report repository has no working tree".to_string()))?
+        .to_path_buf();
+    let head = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+    let head_tree = head.as_ref().map(|commit| commit.tree()).transpose()?;
+
+    let mut tree_index = Index::new()?;
+    if let Some(tree) = &head_tree {
+        tree_index.read_tree(tree)?;
+    }
+    let mut changed = Vec::new();
+    for (name, content) in reports {
+        let relative = Path::new(&config.path).join(name);
+        let committed = head_tree.as_ref()
+            .and_then(|tree| tree.get_path(&relative).ok())
+            .and_then(|entry| repo.find_blob(entry.id()).ok())
+            .map(|blob| String::from_utf8_lossy(blob.content()).into_owned());
+        if committed.is_some_and(|committed| significant(&committed) == significant(content)) {
+            continue;
+        }
+
+        let path = workdir.join(&relative);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, content)?;
+        let path_str = relative.to_str()
+            .ok_or_else(|| publish_error(format!("{} is not valid UTF-8", relative.display())))?;
+        tree_index.add(&blob_entry(path_str, repo.blob(content.as_bytes())?, content.len()))?;
+        changed.push((*name, relative));
+    }
+    if changed.is_empty() {
+        return Ok(None);
+    }
+
+    let tree = repo.find_tree(tree_index.write_tree_to(&repo)?)?;
+    let names: Vec<&str> = changed.iter().map(|(name, _)| *name).collect();
+    let message = config.message
+        .replace("{files}", &names.join(", "))
+        .replace("{date}", &timefmt::display_zone().date(now).to_string());
+    let signature = repo.signature().or_else(|_| git2::Signature::now("skm", "skm@localhost"))?;
+    let parents: Vec<&git2::Commit> = head.iter().collect();
+    let oid = repo.commit(Some("HEAD"), &signature, &signature, &message, &tree, &parents)?;
+
+    // Stage the new contents too, so `git status` does not show the commit undone
+    let mut index = repo.index()?;
+    for (_, relative) in &changed {
+        index.add_path(relative)?;
+    }
+    index.write()?;
+    Ok(Some(oid.to_string()))
+}
+
+/// Without the `git` feature the reports cannot be committed
+#[cfg(not(feature = "git"))]
+pub fn commit_reports(
+    _root: &Path,
+    _config: &ReportCommitConfig,
+    _reports: &[(&str, &str)],
+    _now: DateTime<Utc>,
+) -> Result<Option<String>> {
+    Err(SKMError::FeatureDisabled { feature: "git" })
+}
+
+/// The report without its timestamp lines
+pub(crate) fn significant(report: &str) -> Vec<&str> {
+    report.lines()
+        .filter(|line| !VOLATILE_PREFIXES.iter().any(|prefix| line.starts_with(prefix)))
+        .collect()
+}
+
+/// Index entry for a regular file at `path` holding the blob `id`
+#[cfg(feature = "git")]
+fn blob_entry(path: &str, id: Oid, size: usize) -> IndexEntry {
+    IndexEntry {
+        ctime: IndexTime::new(0, 0),
+        mtime: IndexTime::new(0, 0),
+        dev: 0,
+        ino: 0,
+        mode: 0o100644,
+        uid: 0,
+        gid: 0,
+        file_size: size as u32,
+        id,
+        flags: 0,
+        flags_extended: 0,
+        path: path.as_bytes().to_vec(),
+    }
+}
+
+#[cfg(feature = "git")]
+fn publish_error(message: String) -> SKMError {
+    SKMError::PublishError { message }
+}
@@ -0,0 +1,257 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use crate::{Result, SKMError};
+use crate::meta::config::PublishConfig;
+use crate::meta::schema::{self, PUBLISHED_SCHEMA_VERSION};
+use crate::retry::RetryPolicy;
+
+#[cfg(feature = "integrations")]
+pub mod confluence;
+pub mod git;
+#[cfg(feature = "integrations")]
+pub mod notion;
+
+/// Run of text with its inline formatting
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Span {
+    pub text: String,
+    pub bold: bool,
+    pub italic: bool,
+    pub code: bool,
+    /// Target of an autolink (`<https://...>`)
+    pub link: Option<String>,
+}
+
+/// The parts of a markdown report that wiki pages have their own blocks for
+#[derive(Debug, Clone, PartialEq)]
+pub enum Block {
+    Heading { level: u8, text: Vec<Span> },
+    Paragraph(Vec<Span>),
+    /// A list item and the items indented under it
+    Bullet { text: Vec<Span>, children: Vec<Vec<Span>> },
+    Table { header: Vec<Vec<Span>>, rows: Vec<Vec<Vec<Span>>> },
+    Divider,
+}
+
+/// Split a markdown report into blocks
+///
+/// Covers what the reports use: `#` headings, `-` lists one level deep,
+/// pipe tables, `---` rules and paragraphs, with `**bold**`, `*italic*`,
+/// `` `code` `` and `<url>` inline.
+pub fn parse_markdown(markdown: &str) -> Vec<Block> {
+    let mut blocks = Vec::new();
+    let mut paragraph: Vec<&str> = Vec::new();
+    let mut table: Vec<&str> = Vec::new();
+    let flush = |blocks: &mut Vec<Block>, paragraph: &mut Vec<&str>, table: &mut Vec<&str>| {
+        if !paragraph.is_empty() {
+            blocks.push(Block::Paragraph(parse_inline(&paragraph.join(" "))));
+            paragraph.clear();
+        }
+        if !table.is_empty() {
+            blocks.push(parse_table(table));
+            table.clear();
+        }
+    };
+
+    for line in markdown.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('|') {
+            if !paragraph.is_empty() {
+                flush(&mut blocks, &mut paragraph, &mut Vec::new());
+            }
+            table.push(trimmed);
+            continue;
+        }
+        if trimmed.is_empty() {
+            flush(&mut blocks, &mut paragraph, &mut table);
+            continue;
+        }
+        if let Some(item) = trimmed.strip_prefix("- ") {
+            flush(&mut blocks, &mut paragraph, &mut table);
+            let text = parse_inline(item);
+            match blocks.last_mut() {
+                Some(Block::Bullet { children, .. }) if line.starts_with(' ') => children.push(text),
+                _ => blocks.push(Block::Bullet { text, children: Vec::new() }),
+            }
+            continue;
+        }
+        let hashes = trimmed.chars().take_while(|&c| c == '#').count();
+        if (1..=6).contains(&hashes) && trimmed[hashes..].starts_with(' ') {
+            flush(&mut blocks, &mut paragraph, &mut table);
+            blocks.push(Block::Heading { level: hashes as u8, text: parse_inline(trimmed[hashes..].trim()) });
+            continue;
+        }
+        if trimmed.len() >= 3 && trimmed.chars().all(|c| c == '-') {
+            flush(&mut blocks, &mut paragraph, &mut table);
+            blocks.push(Block::Divider);
+            continue;
+        }
+        if !table.is_empty() {
+            flush(&mut blocks, &mut Vec::new(), &mut table);
+        }
+        paragraph.push(trimmed);
+    }
+    flush(&mut blocks, &mut paragraph, &mut table);
+    blocks
+}
+
+/// The first row is the header; `|---|` rules are dropped
+fn parse_table(lines: &[&str]) -> Block {
+    let mut rows: Vec<Vec<Vec<Span>>> = lines.iter()
+        .filter(|line| !line.chars().all(|c| matches!(c, '|' | '-' | ':' | ' ')))
+        .map(|line| {
+            let inner = line.trim_start_matches('|').trim_end_matches('|');
+            inner.split('|').map(|cell| parse_inline(cell.trim())).collect()
+        })
+        .collect();
+    let header = if rows.is_empty() { Vec::new() } else { rows.remove(0) };
+    Block::Table { header, rows }
+}
+
+/// Split text into spans at `**`, `*`, `` ` `` and `<url>`; markers without
+/// a closing partner are kept as text
+pub fn parse_inline(text: &str) -> Vec<Span> {
+    let mut spans = Vec::new();
+    let mut plain = String::new();
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        let (marker, close) = match c {
+            '`' => ("`", "`"),
+            '*' if rest.starts_with("**") => ("**", "**"),
+            '*' => ("*", "*"),
+            '<' => ("<", ">"),
+            _ => ("", ""),
+        };
+        let closed = (!marker.is_empty())
+            .then(|| rest[marker.len()..].find(close))
+            .flatten()
+            .filter(|&end| end > 0);
+        let Some(end) = closed else {
+            plain.push(c);
+            rest = &rest[c.len_utf8()..];
+            continue;
+        };
+        let inner = &rest[marker.len()..marker.len() + end];
+        if marker == "<" && (!inner.contains("://") || inner.contains(' ')) {
+            plain.push(c);
+            rest = &rest[1..];
+            continue;
+        }
+
+        if !plain.is_empty() {
+            spans.push(Span { text: std::mem::take(&mut plain), ..Span::default() });
+        }
+        match marker {
+            "`" => spans.push(Span { text: inner.to_string(), code: true, ..Span::default() }),
+            "<" => spans.push(Span { text: inner.to_string(), link: Some(inner.to_string()), ..Span::default() }),
+            _ => spans.extend(parse_inline(inner).into_iter().map(|span| match marker {
+                "**" => Span { bold: true, ..span },
+                _ => Span { italic: true, ..span },
+            })),
+        }
+        rest = &rest[marker.len() + end + close.len()..];
+    }
+    if !plain.is_empty() {
+        spans.push(Span { text: plain, ..Span::default() });
+    }
+    spans
+}
+
+/// A page the report can be pushed to
+pub trait Publisher {
+    /// Identifies the page in `.skm/published.json`, e.g. `notion:<page id>`
+    fn key(&self) -> String;
+    /// Replace the page's content with the blocks, keeping its title
+    fn publish(&self, blocks: &[Block]) -> Result<()>;
+}
+
+/// The publisher for `skm publish --target notion|confluence`
+#[cfg_attr(not(feature = "integrations"), allow(unused_variables))]
+pub fn publisher(target: &str, config: &PublishConfig, policy: &RetryPolicy) -> Result<Box<dyn Publisher>> {
+    let missing = |section: &str| SKMError::ConfigError {
+        message: format!("skm publish --target {} needs a [publish.{}] section in the config", target, section),
+    };
+    match target {
+        #[cfg(not(feature = "integrations"))]
+        "notion" | "confluence" => Err(SKMError::FeatureDisabled { feature: "integrations" }),
+        #[cfg(feature = "integrations")]
+        "notion" => {
+            let config = config.notion.as_ref().ok_or_else(|| missing("notion"))?;
+            Ok(Box::new(notion::NotionPublisher::new(config, token(&config.token_env)?, policy.clone())))
+        }
+        #[cfg(feature = "integrations")]
+        "confluence" => {
+            let config = config.confluence.as_ref().ok_or_else(|| missing("confluence"))?;
+            Ok(Box::new(confluence::ConfluencePublisher::new(config, token(&config.token_env)?, policy.clone())))
+        }
+        other => Err(SKMError::ConfigError {
+            message: format!("Unknown publish target: {} (expected site, notion or confluence)", other),
+        }),
+    }
+}
+
+#[cfg(feature = "integrations")]
+fn token(env: &str) -> Result<String> {
+    std::env::var(env).ok().filter(|token| !token.is_empty()).ok_or_else(|| SKMError::ConfigError {
+        message: format!("Set {} to the API token", env),
+    })
+}
+
+/// Content hash of the report last pushed to each page, in `.skm/published.json`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PublishState {
+    pub schema_version: u32,
+    #[serde(default)]
+    pub pages: HashMap<String, String>,
+}
+
+impl Default for PublishState {
+    fn default() -> Self {
+        Self {
+            schema_version: PUBLISHED_SCHEMA_VERSION,
+            pages: HashMap::new(),
+        }
+    }
+}
+
+impl PublishState {
+    fn path(root: &Path) -> PathBuf {
+        root.join(".skm/published.json")
+    }
+
+    pub fn load(root: &Path) -> Result<Self> {
+        match fs::read_to_string(Self::path(root)) {
+            Ok(content) => {
+                let mut doc: serde_json::Value = serde_json::from_str(&content)?;
+                schema::migrate_published(&mut doc)?;
+                Ok(serde_json::from_value(doc)?)
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn save(&self, root: &Path) -> Result<()> {
+        fs::create_dir_all(root.join(".skm"))?;
+        fs::write(Self::path(root), serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Whether the page already holds content with this hash
+    pub fn unchanged(&self, key: &str, hash: &str) -> bool {
+        self.pages.get(key).is_some_and(|last| last == hash)
+    }
+
+    pub fn record(&mut self, key: String, hash: String) {
+        self.pages.insert(key, hash);
+    }
+}
+
+/// Hash identifying what a report says, leaving out its timestamp lines
+/// so a rescan that changed nothing does not count as a change
+pub fn content_hash(content: &str) -> String {
+    crate::hash::blob_id(git::significant(content).join("\n").as_bytes())
+}
@@ -0,0 +1,137 @@
+use serde_json::{json, Value};
+use crate::{Result, SKMError};
+use crate::meta::config::NotionConfig;
+use crate::http::http_json_with;
+use crate::retry::{self, RetryPolicy};
+use super::{Block, Publisher, Span};
+
+const API: &str = "https://api.notion.com/v1";
+const API_VERSION: &str = "2022-06-28";
+/// Most blocks one append request, or one table, may hold
+const MAX_CHILDREN: usize = 100;
+/// Most characters in one rich text object
+const MAX_TEXT: usize = 2000;
+
+/// Replaces a Notion page's blocks with the report
+pub struct NotionPublisher {
+    page_id: String,
+    token: String,
+    policy: RetryPolicy,
+}
+
+impl NotionPublisher {
+    pub fn new(config: &NotionConfig, token: String, policy: RetryPolicy) -> Self {
+        // Ids may be given with or without dashes, or as the end of the page URL
+        let page_id = config.page_id.rsplit(['-', '/']).next().unwrap_or_default();
+        let page_id = if page_id.len() == 32 { page_id.to_string() } else { config.page_id.clone() };
+        NotionPublisher { page_id, token, policy }
+    }
+
+    fn request(&self, method: &str, path: &str, body: Option<&Value>) -> Result<Value> {
+        let url = format!("{}{}", API, path);
+        let headers = [format!("Authorization: Bearer {}", self.token), format!("Notion-Version: {}", API_VERSION)];
+        retry::retry(&self.policy, &format!("{} {}", method, url), || http_json_with(method, &url, &headers, body))
+            .map_err(|e| SKMError::PublishError { message: e.to_string() })
+    }
+
+    /// Ids of the page's top-level blocks
+    fn children(&self) -> Result<Vec<String>> {
+        let mut ids = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let mut path = format!("/blocks/{}/children?page_size={}", self.page_id, MAX_CHILDREN);
+            if let Some(cursor) = &cursor {
+                path.push_str(&format!("&start_cursor={}", cursor));
+            }
+            let page = self.request("GET", &path, None)?;
+            ids.extend(page["results"].as_array().into_iter().flatten()
+                .filter_map(|block| block["id"].as_str().map(str::to_string)));
+            cursor = page["next_cursor"].as_str().map(str::to_string);
+            if !page["has_more"].as_bool().unwrap_or(false) || cursor.is_none() {
+                return Ok(ids);
+            }
+        }
+    }
+}
+
+impl Publisher for NotionPublisher {
+    fn key(&self) -> String {
+        format!("notion:{}", self.page_id)
+    }
+
+    fn publish(&self, blocks: &[Block]) -> Result<()> {
+        for id in self.children()? {
+            self.request("DELETE", &format!("/blocks/{}", id), None)?;
+        }
+        let blocks: Vec<Value> = blocks.iter().flat_map(to_notion).collect();
+        for chunk in blocks.chunks(MAX_CHILDREN) {
+            self.request("PATCH", &format!("/blocks/{}/children", self.page_id), Some(&json!({ "children": chunk })))?;
+        }
+        Ok(())
+    }
+}
+
+/// Notion blocks for a report block; long tables become several tables
+/// with the header repeated
+fn to_notion(block: &Block) -> Vec<Value> {
+    match block {
+        Block::Heading { level, text } => {
+            let kind = format!("heading_{}", (*level).clamp(1, 3));
+            vec![json!({ "type": kind, kind.clone(): { "rich_text": rich_text(text) } })]
+        }
+        Block::Paragraph(text) => vec![json!({ "type": "paragraph", "paragraph": { "rich_text": rich_text(text) } })],
+        Block::Bullet { text, children } => {
+            let mut item = json!({ "rich_text": rich_text(text) });
+            if !children.is_empty() {
+                item["children"] = children.iter()
+                    .take(MAX_CHILDREN)
+                    .map(|child| json!({ "type": "bulleted_list_item", "bulleted_list_item": { "rich_text": rich_text(child) } }))
+                    .collect();
+            }
+            vec![json!({ "type": "bulleted_list_item", "bulleted_list_item": item })]
+        }
+        Block::Table { header, rows } => {
+            let width = header.len().max(rows.iter().map(Vec::len).max().unwrap_or(0)).max(1);
+            let row = |cells: &[Vec<Span>]| {
+                let mut cells: Vec<Value> = cells.iter().map(|cell| rich_text(cell)).collect();
+                cells.resize(width, json!([]));
+                json!({ "type": "table_row", "table_row": { "cells": cells } })
+            };
+            let chunks: Vec<&[Vec<Vec<Span>>]> = if rows.is_empty() { vec![&[]] } else { rows.chunks(MAX_CHILDREN - 1).collect() };
+            chunks.into_iter()
+                .map(|chunk| {
+                    let children: Vec<Value> = std::iter::once(row(header)).chain(chunk.iter().map(|cells| row(cells))).collect();
+                    json!({ "type": "table", "table": {
+                        "table_width": width,
+                        "has_column_header": true,
+                        "has_row_header": false,
+                        "children": children,
+                    } })
+                })
+                .collect()
+        }
+        Block::Divider => vec![json!({ "type": "divider", "divider": {} })],
+    }
+}
+
+fn rich_text(spans: &[Span]) -> Value {
+    spans.iter()
+        .flat_map(|span| {
+            let chars: Vec<char> = span.text.chars().collect();
+            chars.chunks(MAX_TEXT)
+                .map(|chunk| {
+                    let mut text = json!({ "content": chunk.iter().collect::<String>() });
+                    // Notion only accepts web links
+                    if let Some(url) = span.link.as_ref().filter(|url| url.starts_with("http://") || url.starts_with("https://")) {
+                        text["link"] = json!({ "url": url });
+                    }
+                    json!({
+                        "type": "text",
+                        "text": text,
+                        "annotations": { "bold": span.bold, "italic": span.italic, "code": span.code },
+                    })
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
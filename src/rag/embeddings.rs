@@ -1 +1,117 @@
-// Placeholder for src/rag/embeddings.rs
+use serde_json::{json, Value};
+use crate::{Result, SKMError};
+use crate::meta::config::{EmbeddingProvider, RagConfig};
+use crate::retry::{self, RetryPolicy};
+use crate::http::http_json;
+
+/// Words too common in specs to say anything about what a chunk is about
+const STOP_WORDS: &[&str] = &[
+    "the", "and", "for", "are", "but", "not", "you", "all", "can", "has", "was", "with",
+    "this", "that", "from", "they", "will", "would", "there", "their", "what", "when",
+    "which", "into", "than", "then", "them", "these", "those", "should", "must", "have",
+    "been", "each", "its", "our", "any", "may", "also", "only", "such", "via",
+];
+
+/// Turns artifact chunks and queries into vectors
+#[derive(Debug, Clone)]
+pub enum Embedder {
+    /// Feature hashing of words and adjacent word pairs into a fixed-size vector
+    Hash { dimensions: usize },
+    /// An Ollama server's `/api/embed` endpoint
+    Ollama { url: String, model: String, policy: RetryPolicy },
+}
+
+impl Embedder {
+    pub fn from_config(config: &RagConfig, policy: &RetryPolicy) -> Self {
+        match config.embedding {
+            EmbeddingProvider::Hash => Embedder::Hash { dimensions: config.dimensions.max(16) },
+            EmbeddingProvider::Ollama => Embedder::Ollama {
+                url: config.embedding_url.trim_end_matches('/').to_string(),
+                model: config.model.clone(),
+                policy: policy.clone(),
+            },
+        }
+    }
+
+    /// Short description for progress output, e.g. `hash-512` or `ollama:nomic-embed-text`
+    pub fn name(&self) -> String {
+        match self {
+            Embedder::Hash { dimensions } => format!("hash-{}", dimensions),
+            Embedder::Ollama { model, .. } => format!("ollama:{}", model),
+        }
+    }
+
+    /// One vector per text, in order
+    pub fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        match self {
+            Embedder::Hash { dimensions } => Ok(texts.iter().map(|t| hash_embedding(t, *dimensions)).collect()),
+            Embedder::Ollama { url, model, policy } => {
+                let endpoint = format!("{}/api/embed", url);
+                let body = json!({ "model": model, "input": texts });
+                let response = retry::retry(policy, &format!("embedding with {}", model), || {
+                    http_json("POST", &endpoint, Some(&body))
+                }).map_err(|e| SKMError::ConfigError { message: format!("Ollama embedding failed: {}", e) })?;
+
+                let vectors: Vec<Vec<f32>> = response.get("embeddings")
+                    .and_then(Value::as_array)
+                    .map(|rows| rows.iter()
+                        .map(|row| row.as_array().into_iter().flatten().filter_map(Value::as_f64).map(|x| x as f32).collect())
+                        .collect())
+                    .unwrap_or_default();
+                if vectors.len() != texts.len() || vectors.iter().any(Vec::is_empty) {
+                    return Err(SKMError::ConfigError {
+                        message: format!("Ollama returned {} embeddings for {} inputs from {}", vectors.len(), texts.len(), endpoint),
+                    });
+                }
+                Ok(vectors)
+            }
+        }
+    }
+}
+
+/// Lowercase alphanumeric words of at least two characters, without stop words
+///
+/// Plural `s` is dropped so "refund" and "refunds" share a feature.
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.chars().count() >= 2)
+        .map(str::to_lowercase)
+        .filter(|w| !STOP_WORDS.contains(&w.as_str()))
+        .map(|w| match w.strip_suffix('s') {
+            Some(stem) if stem.len() > 2 && !stem.ends_with('s') => stem.to_string(),
+            _ => w,
+        })
+        .collect()
+}
+
+/// 64-bit FNV-1a, stable across runs and platforms unlike `DefaultHasher`
+fn fnv1a(text: &str) -> u64 {
+    text.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3))
+}
+
+/// Unit vector of hashed word and word-pair counts
+///
+/// Each feature lands in one bucket with a sign taken from its hash, so
+/// collisions tend to cancel out instead of piling up. Texts sharing
+/// vocabulary get a high cosine similarity; there is no notion of synonyms.
+pub fn hash_embedding(text: &str, dimensions: usize) -> Vec<f32> {
+    let mut vector = vec![0f32; dimensions];
+    let words = words(text);
+    let mut add = |feature: &str, weight: f32| {
+        let hash = fnv1a(feature);
+        let sign = if hash >> 63 == 0 { 1.0 } else { -1.0 };
+        vector[(hash % dimensions as u64) as usize] += sign * weight;
+    };
+    for word in &words {
+        add(word, 1.0);
+    }
+    for pair in words.windows(2) {
+        add(&format!("{} {}", pair[0], pair[1]), 0.5);
+    }
+
+    let norm = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        vector.iter_mut().for_each(|x| *x /= norm);
+    }
+    vector
+}
@@ -2,3 +2,6 @@ pub mod embeddings;
 pub mod storage;
 pub mod query;
 
+pub use embeddings::{hash_embedding, Embedder};
+pub use query::{chunk_markdown, index_projects, search, IndexStats};
+pub use storage::{ChunkPayload, QdrantStore, ScoredChunk, SearchFilter};
@@ -1 +1,186 @@
-// Placeholder for src/rag/query.rs
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::{ArtifactKind, Project, Result};
+use crate::meta::config::GlobalConfig;
+use crate::scanner::parser;
+use crate::template::Variables;
+use super::embeddings::Embedder;
+use super::storage::{point_id, ChunkPayload, Point, QdrantStore, ScoredChunk, SearchFilter};
+
+/// Chunks sent to the embedder at once
+const EMBED_BATCH: usize = 32;
+
+/// A piece of an artifact under its nearest heading
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chunk {
+    pub heading: Option<String>,
+    pub text: String,
+}
+
+/// Split markdown into one chunk per section, breaking long sections at blank lines
+///
+/// Headings inside fenced code blocks do not start a section. The heading
+/// line stays in the chunk text so it counts toward the embedding.
+pub fn chunk_markdown(content: &str, max_chars: usize) -> Vec<Chunk> {
+    let mut sections: Vec<(Option<String>, Vec<&str>)> = vec![(None, Vec::new())];
+    let mut in_fence = false;
+    for line in content.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+        }
+        if !in_fence && trimmed.starts_with('#') {
+            let heading = trimmed.trim_start_matches('#').trim();
+            sections.push((Some(heading.to_string()).filter(|h| !h.is_empty()), Vec::new()));
+        }
+        if let Some((_, lines)) = sections.last_mut() {
+            lines.push(line);
+        }
+    }
+
+    let mut chunks = Vec::new();
+    for (heading, lines) in sections {
+        let mut current = String::new();
+        for paragraph in lines.join("\n").split("\n\n") {
+            if !current.is_empty() && current.len() + paragraph.len() > max_chars {
+                chunks.push(Chunk { heading: heading.clone(), text: current.trim().to_string() });
+                current.clear();
+            }
+            current.push_str(paragraph);
+            current.push_str("\n\n");
+        }
+        // A section that is only its heading says nothing on its own
+        let text = current.trim();
+        if text.lines().any(|l| !l.trim().is_empty() && !l.trim_start().starts_with('#')) {
+            chunks.push(Chunk { heading, text: text.to_string() });
+        }
+    }
+    chunks
+}
+
+/// An artifact file of a project, with the feature directory it belongs to
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArtifactFile {
+    pub kind: ArtifactKind,
+    pub feature: Option<String>,
+    pub path: PathBuf,
+}
+
+/// Constitution plus every spec, plan and tasks file, top-level and per feature
+pub fn artifact_files(project: &Path) -> Vec<ArtifactFile> {
+    let mut files = vec![ArtifactFile {
+        kind: ArtifactKind::Constitution,
+        feature: None,
+        path: project.join(".specify/memory/constitution.md"),
+    }];
+    let kinds = [(ArtifactKind::Spec, "spec.md"), (ArtifactKind::Plan, "plan.md"), (ArtifactKind::Tasks, "tasks.md")];
+    for dir in [project.join(".specify"), project.join("specs")] {
+        for (kind, name) in kinds {
+            files.push(ArtifactFile { kind, feature: None, path: dir.join(name) });
+        }
+        for feature_dir in parser::list_feature_dirs(&dir) {
+            let feature = feature_dir.file_name().map(|n| n.to_string_lossy().into_owned());
+            for (kind, name) in kinds {
+                files.push(ArtifactFile { kind, feature: feature.clone(), path: feature_dir.join(name) });
+            }
+        }
+    }
+    files.retain(|f| f.path.is_file());
+    files
+}
+
+/// Counts from indexing a portfolio
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IndexStats {
+    pub projects: usize,
+    pub files: usize,
+    pub chunks: usize,
+}
+
+fn store(config: &GlobalConfig) -> QdrantStore {
+    QdrantStore::new(&config.qdrant_url, &config.rag.collection, &config.retry)
+}
+
+/// Chunks of all artifacts of a project, with their payloads and their
+/// `{{...}}` variables resolved
+fn project_chunks(project: &Project, variables: &Variables, max_chars: usize) -> (usize, Vec<ChunkPayload>) {
+    let project_path = project.path.to_string_lossy().into_owned();
+    let files = artifact_files(&project.path);
+    let mut payloads = Vec::new();
+    for file in &files {
+        let Ok(content) = fs::read_to_string(&file.path) else { continue };
+        let relative = file.path.strip_prefix(&project.path).unwrap_or(&file.path).to_string_lossy().into_owned();
+        for (index, chunk) in chunk_markdown(&variables.resolve(&content), max_chars).into_iter().enumerate() {
+            payloads.push(ChunkPayload {
+                project: project.display_name(),
+                project_path: project_path.clone(),
+                root: project.root.clone(),
+                stage: project.stage.to_string(),
+                artifact: file.kind.to_string(),
+                feature: file.feature.clone(),
+                file: relative.clone(),
+                heading: chunk.heading,
+                text: chunk.text,
+                chunk: index,
+            });
+        }
+    }
+    (files.len(), payloads)
+}
+
+/// Replace the indexed chunks of each project with its current artifacts
+///
+/// With `reset`, the collection is dropped first, which is needed after
+/// switching to an embedder with a different vector size. Remote projects
+/// are indexed like any other, from their read-only clones.
+pub fn index_projects(
+    projects: &[&Project],
+    config: &GlobalConfig,
+    reset: bool,
+    mut progress: impl FnMut(&Project, usize),
+) -> Result<IndexStats> {
+    let embedder = Embedder::from_config(&config.rag, &config.retry);
+    let store = store(config);
+    if reset {
+        store.delete_collection()?;
+    }
+
+    let mut stats = IndexStats::default();
+    let mut collection_ready = false;
+    for project in projects {
+        let overrides = config.for_project(&project.path).ok().flatten();
+        let variables = Variables::for_project(project, overrides.as_ref().unwrap_or(config));
+        let (files, payloads) = project_chunks(project, &variables, config.rag.chunk_chars.max(200));
+        let mut points = Vec::with_capacity(payloads.len());
+        for batch in payloads.chunks(EMBED_BATCH) {
+            let texts: Vec<String> = batch.iter().map(|p| p.text.clone()).collect();
+            let vectors = embedder.embed(&texts)?;
+            for (payload, vector) in batch.iter().zip(vectors) {
+                points.push(Point { id: point_id(&payload.project_path, &payload.file, payload.chunk), vector, payload: payload.clone() });
+            }
+        }
+        if !collection_ready {
+            let size = match points.first() {
+                Some(point) => point.vector.len(),
+                None => embedder.embed(&["probe".to_string()])?[0].len(),
+            };
+            store.ensure_collection(size)?;
+            collection_ready = true;
+        }
+
+        store.delete_project(&project.path.to_string_lossy())?;
+        store.upsert(&points)?;
+        progress(project, points.len());
+        stats.projects += 1;
+        stats.files += files;
+        stats.chunks += points.len();
+    }
+    Ok(stats)
+}
+
+/// The `limit` artifact chunks most similar to `query` across the indexed portfolio
+pub fn search(query: &str, limit: usize, filter: &SearchFilter, config: &GlobalConfig) -> Result<Vec<ScoredChunk>> {
+    let embedder = Embedder::from_config(&config.rag, &config.retry);
+    let vector = embedder.embed(&[query.to_string()])?.remove(0);
+    store(config).search(&vector, limit, filter)
+}
@@ -1 +1,162 @@
-// Placeholder for src/rag/storage.rs
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use crate::{hash, Result, SKMError};
+use crate::http::{http_json, HttpError};
+use crate::retry::{self, RetryPolicy};
+
+/// What is stored with each artifact chunk, returned with search hits
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ChunkPayload {
+    /// Project display name (`root/id` under named roots)
+    pub project: String,
+    pub project_path: String,
+    #[serde(default)]
+    pub root: Option<String>,
+    pub stage: String,
+    /// `constitution`, `spec`, `plan` or `tasks`
+    pub artifact: String,
+    /// Feature directory, e.g. `001-login`
+    #[serde(default)]
+    pub feature: Option<String>,
+    pub file: String,
+    /// Nearest heading above the chunk
+    #[serde(default)]
+    pub heading: Option<String>,
+    pub text: String,
+    /// Position of the chunk within its file
+    pub chunk: usize,
+}
+
+/// A chunk ready to be stored
+#[derive(Debug, Clone)]
+pub struct Point {
+    pub id: String,
+    pub vector: Vec<f32>,
+    pub payload: ChunkPayload,
+}
+
+/// A stored chunk matching a query, most similar first
+#[derive(Debug, Clone)]
+pub struct ScoredChunk {
+    pub score: f32,
+    pub payload: ChunkPayload,
+}
+
+/// Narrows a search to matching payload fields
+#[derive(Debug, Clone, Default)]
+pub struct SearchFilter {
+    pub project: Option<String>,
+    pub stage: Option<String>,
+    pub artifact: Option<String>,
+}
+
+impl SearchFilter {
+    fn to_qdrant(&self) -> Option<Value> {
+        let must: Vec<Value> = [("project", &self.project), ("stage", &self.stage), ("artifact", &self.artifact)]
+            .into_iter()
+            .filter_map(|(key, value)| value.as_ref().map(|v| json!({ "key": key, "match": { "value": v } })))
+            .collect();
+        (!must.is_empty()).then(|| json!({ "must": must }))
+    }
+}
+
+/// A Qdrant collection of artifact chunks, accessed over its REST API
+pub struct QdrantStore {
+    url: String,
+    collection: String,
+    policy: RetryPolicy,
+}
+
+impl QdrantStore {
+    pub fn new(url: &str, collection: &str, policy: &RetryPolicy) -> Self {
+        Self {
+            url: url.trim_end_matches('/').to_string(),
+            collection: collection.to_string(),
+            policy: policy.clone(),
+        }
+    }
+
+    fn request(&self, method: &str, path: &str, body: Option<&Value>) -> std::result::Result<Value, HttpError> {
+        let url = format!("{}/collections/{}{}", self.url, self.collection, path);
+        retry::retry(&self.policy, &format!("{} {}", method, url), || http_json(method, &url, body))
+    }
+
+    fn call(&self, method: &str, path: &str, body: Option<&Value>) -> Result<Value> {
+        self.request(method, path, body).map_err(|e| SKMError::QdrantError { message: e.to_string() })
+    }
+
+    /// Vector size of the collection, `None` when it does not exist
+    pub fn vector_size(&self) -> Result<Option<usize>> {
+        match self.request("GET", "", None) {
+            Ok(doc) => Ok(doc.pointer("/result/config/params/vectors/size").and_then(Value::as_u64).map(|s| s as usize)),
+            Err(HttpError { status: Some(404), .. }) => Ok(None),
+            Err(e) => Err(SKMError::QdrantError { message: e.to_string() }),
+        }
+    }
+
+    /// Create the collection for vectors of `size`, or check an existing one matches
+    pub fn ensure_collection(&self, size: usize) -> Result<()> {
+        match self.vector_size()? {
+            Some(existing) if existing == size => Ok(()),
+            Some(existing) => Err(SKMError::QdrantError {
+                message: format!("collection {} stores {}-dimensional vectors but the embedder produces {}; rerun with --reset",
+                    self.collection, existing, size),
+            }),
+            None => {
+                self.call("PUT", "", Some(&json!({ "vectors": { "size": size, "distance": "Cosine" } })))?;
+                for field in ["project", "project_path", "stage", "artifact"] {
+                    self.call("PUT", "/index?wait=true", Some(&json!({ "field_name": field, "field_schema": "keyword" })))?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Drop the collection and everything in it
+    pub fn delete_collection(&self) -> Result<()> {
+        match self.request("DELETE", "", None) {
+            Ok(_) | Err(HttpError { status: Some(404), .. }) => Ok(()),
+            Err(e) => Err(SKMError::QdrantError { message: e.to_string() }),
+        }
+    }
+
+    /// Remove all chunks of one project
+    pub fn delete_project(&self, project_path: &str) -> Result<()> {
+        let filter = json!({ "must": [{ "key": "project_path", "match": { "value": project_path } }] });
+        self.call("POST", "/points/delete?wait=true", Some(&json!({ "filter": filter })))?;
+        Ok(())
+    }
+
+    pub fn upsert(&self, points: &[Point]) -> Result<()> {
+        if points.is_empty() {
+            return Ok(());
+        }
+        let points: Vec<Value> = points.iter()
+            .map(|p| json!({ "id": p.id, "vector": p.vector, "payload": p.payload }))
+            .collect();
+        self.call("PUT", "/points?wait=true", Some(&json!({ "points": points })))?;
+        Ok(())
+    }
+
+    /// The `limit` chunks closest to `vector` that pass the filter
+    pub fn search(&self, vector: &[f32], limit: usize, filter: &SearchFilter) -> Result<Vec<ScoredChunk>> {
+        let mut body = json!({ "vector": vector, "limit": limit, "with_payload": true });
+        if let Some(filter) = filter.to_qdrant() {
+            body["filter"] = filter;
+        }
+        let doc = self.call("POST", "/points/search", Some(&body))?;
+        Ok(doc.get("result").and_then(Value::as_array).into_iter().flatten()
+            .filter_map(|hit| Some(ScoredChunk {
+                score: hit.get("score")?.as_f64()? as f32,
+                payload: serde_json::from_value(hit.get("payload")?.clone()).ok()?,
+            }))
+            .collect())
+    }
+}
+
+/// Stable point id for a chunk, as the UUID Qdrant requires
+pub fn point_id(project_path: &str, file: &str, chunk: usize) -> String {
+    let key = format!("{}\0{}\0{}", project_path, file, chunk);
+    let hex = hash::blob_id(key.as_bytes());
+    format!("{}-{}-{}-{}-{}", &hex[..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..32])
+}
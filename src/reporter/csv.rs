@@ -0,0 +1,72 @@
+use std::io::{self, Write};
+use crate::Project;
+use crate::view::PortfolioView;
+use super::markdown::format_requirements;
+
+/// Column headers of CSV and TSV reports
+pub const CSV_COLUMNS: [&str; 11] = [
+    "id", "path", "stage", "priority", "tasks_completed", "tasks_total", "blocked",
+    "branch", "requires_human", "needs_attention", "next",
+];
+
+/// Field separator of a spreadsheet export
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Delimited {
+    /// Comma-separated, fields quoted as in RFC 4180
+    Csv,
+    /// Tab-separated; tabs and line breaks inside fields become spaces
+    Tsv,
+}
+
+impl Delimited {
+    fn separator(&self) -> char {
+        match self {
+            Delimited::Csv => ',',
+            Delimited::Tsv => '\t',
+        }
+    }
+
+    /// One field, safe to import: quoted or flattened so it stays one cell,
+    /// and with a leading `'` on text a spreadsheet would run as a formula
+    fn field(&self, value: &str) -> String {
+        let value = match value.starts_with(['=', '+', '-', '@']) && value.parse::<f64>().is_err() {
+            true => format!("'{}", value),
+            false => value.to_string(),
+        };
+        match self {
+            Delimited::Csv if value.contains([',', '"', '\n', '\r']) => format!("\"{}\"", value.replace('"', "\"\"")),
+            Delimited::Csv => value,
+            Delimited::Tsv => value.replace(['\t', '\n', '\r'], " "),
+        }
+    }
+}
+
+/// One row per project, highest priority first, for spreadsheet import
+pub fn write_delimited_view<W: Write>(view: &PortfolioView<'_>, format: Delimited, out: &mut W) -> io::Result<()> {
+    write_row(out, format, CSV_COLUMNS.iter().map(|column| column.to_string()))?;
+    for project in view.clone().sorted_by_priority().iter() {
+        write_row(out, format, project_row(project).into_iter().map(|value| format.field(&value)))?;
+    }
+    Ok(())
+}
+
+fn project_row(project: &Project) -> [String; CSV_COLUMNS.len()] {
+    [
+        project.display_name(),
+        project.path.display().to_string(),
+        project.stage.as_str().to_string(),
+        format!("{:.1}", project.priority),
+        project.tasks.completed.to_string(),
+        project.tasks.total.to_string(),
+        project.tasks.blocked.to_string(),
+        project.git.branch.clone().unwrap_or_default(),
+        format_requirements(&project.requires_human),
+        project.needs_attention.to_string(),
+        project.next.command.clone(),
+    ]
+}
+
+fn write_row<W: Write>(out: &mut W, format: Delimited, fields: impl Iterator<Item = String>) -> io::Result<()> {
+    let line = fields.collect::<Vec<_>>().join(&format.separator().to_string());
+    writeln!(out, "{}", line)
+}
@@ -0,0 +1,118 @@
+use std::io::{self, Write};
+
+/// Inputs larger than this many line pairs are shown as a full replacement
+const MAX_DIFF_CELLS: usize = 16_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Equal,
+    Delete,
+    Insert,
+}
+
+/// Write a unified line diff of `old` and `new` with `context` lines around changes
+///
+/// Writes nothing when the inputs are identical.
+pub fn write_unified_diff<W: Write>(
+    old: &str,
+    new: &str,
+    old_label: &str,
+    new_label: &str,
+    context: usize,
+    out: &mut W,
+) -> io::Result<()> {
+    let a: Vec<&str> = old.lines().collect();
+    let b: Vec<&str> = new.lines().collect();
+    let ops = line_ops(&a, &b);
+    if ops.iter().all(|op| *op == Op::Equal) {
+        return Ok(());
+    }
+
+    writeln!(out, "--- {}", old_label)?;
+    writeln!(out, "+++ {}", new_label)?;
+
+    // Index into `ops` plus the line numbers in `a` and `b` at that point
+    let mut positions = Vec::with_capacity(ops.len() + 1);
+    let (mut i, mut j) = (0, 0);
+    for op in &ops {
+        positions.push((i, j));
+        match op {
+            Op::Equal => { i += 1; j += 1; }
+            Op::Delete => i += 1,
+            Op::Insert => j += 1,
+        }
+    }
+    positions.push((i, j));
+
+    let mut k = 0;
+    while k < ops.len() {
+        if ops[k] == Op::Equal {
+            k += 1;
+            continue;
+        }
+
+        // Extend the hunk while changes are closer than 2 * context apart
+        let start = k.saturating_sub(context);
+        let mut end = k;
+        let mut equal_run = 0;
+        while end < ops.len() && equal_run <= 2 * context {
+            equal_run = if ops[end] == Op::Equal { equal_run + 1 } else { 0 };
+            end += 1;
+        }
+        let end = end - equal_run.saturating_sub(context);
+
+        let (a_start, b_start) = positions[start];
+        let (a_end, b_end) = positions[end];
+        writeln!(out, "@@ -{},{} +{},{} @@", a_start + 1, a_end - a_start, b_start + 1, b_end - b_start)?;
+        for (op, (ai, bi)) in ops[start..end].iter().zip(&positions[start..end]) {
+            match op {
+                Op::Equal => writeln!(out, " {}", a[*ai])?,
+                Op::Delete => writeln!(out, "-{}", a[*ai])?,
+                Op::Insert => writeln!(out, "+{}", b[*bi])?,
+            }
+        }
+        k = end;
+    }
+    Ok(())
+}
+
+/// Edit script turning `a` into `b`, from a longest common subsequence
+fn line_ops(a: &[&str], b: &[&str]) -> Vec<Op> {
+    if a.len().saturating_mul(b.len()) > MAX_DIFF_CELLS {
+        return std::iter::repeat_n(Op::Delete, a.len())
+            .chain(std::iter::repeat_n(Op::Insert, b.len()))
+            .collect();
+    }
+
+    // lcs[i][j] = LCS length of a[i..] and b[j..]
+    let width = b.len() + 1;
+    let mut lcs = vec![0u32; (a.len() + 1) * width];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i * width + j] = if a[i] == b[j] {
+                lcs[(i + 1) * width + j + 1] + 1
+            } else {
+                lcs[(i + 1) * width + j].max(lcs[i * width + j + 1])
+            };
+        }
+    }
+
+    let mut ops = Vec::with_capacity(a.len() + b.len());
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        if a[i] == b[j] {
+            ops.push(Op::Equal);
+            i += 1;
+            j += 1;
+        } else if lcs[(i + 1) * width + j] >= lcs[i * width + j + 1] {
+            ops.push(Op::Delete);
+            i += 1;
+        } else {
+            ops.push(Op::Insert);
+            j += 1;
+        }
+    }
+    ops.extend(std::iter::repeat_n(Op::Delete, a.len() - i));
+    ops.extend(std::iter::repeat_n(Op::Insert, b.len() - j));
+    ops
+}
@@ -1 +1,367 @@
-// Placeholder for src/reporter/digest.rs
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::fs;
+use std::io::{self, BufWriter, Write};
+use std::str::FromStr;
+use crate::{Result, SKMError};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use crate::{PortfolioStatus, CompletionSummary};
+use crate::timefmt;
+use super::style;
+use crate::view::PortfolioView;
+use crate::analyzer::requirements::TimeToAddress;
+use crate::analyzer::sprint::SprintRollup;
+use crate::autopilot::handoff::HandoffNote;
+use crate::meta::history::StageVelocity;
+
+/// Digest cadence; controls the heading and how many projects are listed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DigestMode {
+    Daily,
+    Weekly,
+}
+
+impl DigestMode {
+    fn title(&self) -> &'static str {
+        match self {
+            DigestMode::Daily => "Daily Digest",
+            DigestMode::Weekly => "Weekly Digest",
+        }
+    }
+
+    fn attention_limit(&self) -> usize {
+        match self {
+            DigestMode::Daily => 5,
+            DigestMode::Weekly => 15,
+        }
+    }
+
+    /// Period a first digest reaches back over for handoff notes
+    pub fn period(&self) -> chrono::Duration {
+        match self {
+            DigestMode::Daily => chrono::Duration::days(1),
+            DigestMode::Weekly => chrono::Duration::days(7),
+        }
+    }
+
+    fn key(&self) -> &'static str {
+        match self {
+            DigestMode::Daily => "daily",
+            DigestMode::Weekly => "weekly",
+        }
+    }
+
+    fn sprint_limit(&self) -> usize {
+        match self {
+            DigestMode::Daily => 2,
+            DigestMode::Weekly => 4,
+        }
+    }
+}
+
+impl FromStr for DigestMode {
+    type Err = SKMError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "daily" => Ok(DigestMode::Daily),
+            "weekly" => Ok(DigestMode::Weekly),
+            other => Err(SKMError::ConfigError { message: format!("Unknown digest mode: {} (expected daily or weekly)", other) }),
+        }
+    }
+}
+
+/// When digests of each mode were last generated for a root, kept in
+/// `.skm/digests.json` so each digest picks up the handoff notes written since
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct DigestState {
+    #[serde(default)]
+    pub last: BTreeMap<String, DateTime<Utc>>,
+}
+
+impl DigestState {
+    fn path(root: &Path) -> PathBuf {
+        root.join(".skm/digests.json")
+    }
+
+    pub fn load(root: &Path) -> Result<Self> {
+        match fs::read_to_string(Self::path(root)) {
+            Ok(content) => Ok(serde_json::from_str(&content)?),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn save(&self, root: &Path) -> Result<()> {
+        fs::create_dir_all(root.join(".skm"))?;
+        fs::write(Self::path(root), serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Start of what the next digest of `mode` covers: the previous one, or one period back
+    pub fn since(&self, mode: DigestMode, now: DateTime<Utc>) -> DateTime<Utc> {
+        self.last.get(mode.key()).copied().unwrap_or(now - mode.period())
+    }
+
+    pub fn record(&mut self, mode: DigestMode, at: DateTime<Utc>) {
+        self.last.insert(mode.key().to_string(), at);
+    }
+}
+
+/// Generate a digest for the projects selected by a view
+pub fn generate_digest(
+    view: &PortfolioView<'_>,
+    mode: DigestMode,
+    velocity: &StageVelocity,
+    sprints: &[SprintRollup],
+    handoffs: &[(String, HandoffNote)],
+) -> String {
+    let mut buf = Vec::new();
+    write_digest(view, mode, velocity, sprints, handoffs, &mut buf).expect("writing to a Vec cannot fail");
+    String::from_utf8(buf).expect("digest is valid UTF-8")
+}
+
+/// Stream a digest into a writer
+///
+/// The digest leads with a single completion figure so it answers
+/// "how far along are we" before anything else. Velocity covers stage
+/// changes since the previous digest, and so do season changes of projects
+/// with an active window. Sprint rollups, when given, are
+/// listed most recent last. Handoff notes are given with the
+/// display name of their project.
+pub fn write_digest<W: Write>(
+    view: &PortfolioView<'_>,
+    mode: DigestMode,
+    velocity: &StageVelocity,
+    sprints: &[SprintRollup],
+    handoffs: &[(String, HandoffNote)],
+    out: &mut W,
+) -> io::Result<()> {
+    let status: &PortfolioStatus = view.status;
+    let completion = crate::analyzer::completion::completion(view.iter());
+
+    writeln!(out, "# SKM {}\n", mode.title())?;
+    writeln!(out, "Generated: {}\n", timefmt::minutes(status.generated_at))?;
+
+    writeln!(out, "## Overall Progress: {:.0}%\n", completion.effort_weighted_percent)?;
+    write_completion_lines(&completion, out)?;
+    writeln!(out)?;
+
+    if status.summary.completion.by_root.len() > 1 {
+        writeln!(out, "### By Root\n")?;
+        let rows: Vec<Vec<String>> = status.summary.completion.by_root.iter()
+            .map(|root| vec![
+                root.root.clone(),
+                format!("{:.0}%", root.completion.effort_weighted_percent),
+                format!("{}/{}", root.completion.projects_done, root.completion.projects_total),
+                format!("{:.0}%", root.completion.task_percent),
+                root.needs_attention.to_string(),
+            ])
+            .collect();
+        style::write_table(out, &["Root", "Progress", "Done", "Tasks", "Attention"], &rows)?;
+        writeln!(out)?;
+    }
+
+    let attention = view.clone()
+        .filter(|p| p.needs_attention)
+        .sorted_by_priority();
+    writeln!(out, "## Needs Attention ({})\n", attention.len())?;
+    if attention.is_empty() {
+        writeln!(out, "Nothing above the attention threshold.")?;
+    }
+    for project in attention.iter().take(mode.attention_limit()) {
+        writeln!(out, "- **{}** [{:.1}] {} — next: `{}`",
+            project.display_name(), project.priority, project.stage, project.next.command)?;
+    }
+    if attention.len() > mode.attention_limit() {
+        writeln!(out, "- ... and {} more", attention.len() - mode.attention_limit())?;
+    }
+    writeln!(out)?;
+
+    // Windows open and close at the start of a day (UTC)
+    let season_changes: Vec<_> = view.iter()
+        .filter_map(|p| p.season.as_ref().map(|season| (p, season)))
+        .filter(|(_, season)| season.since
+            .and_then(|day| day.and_hms_opt(0, 0, 0))
+            .is_some_and(|start| start.and_utc() > velocity.since))
+        .collect();
+    if !season_changes.is_empty() {
+        writeln!(out, "## Season Changes ({})\n", season_changes.len())?;
+        for (project, season) in season_changes {
+            writeln!(out, "- **{}** {} on {} (active {})",
+                project.display_name(),
+                if season.active { "is back in season" } else { "paused" },
+                season.since.map(|day| day.to_string()).unwrap_or_default(),
+                season.window)?;
+        }
+        writeln!(out)?;
+    }
+
+    if mode == DigestMode::Weekly {
+        write_review_sla(view, out)?;
+    }
+
+    if !handoffs.is_empty() {
+        writeln!(out, "## Handoff Notes ({})\n", handoffs.len())?;
+        for (project, note) in handoffs {
+            write_handoff(project, note, out)?;
+        }
+    }
+
+    let questions: usize = view.iter().map(|p| p.open_questions.len()).sum();
+    if questions > 0 {
+        let asking = view.iter().filter(|p| !p.open_questions.is_empty()).count();
+        writeln!(out, "## Open Questions\n")?;
+        writeln!(out, "{} unanswered across {} projects.\n", questions, asking)?;
+    }
+
+    let creeping: Vec<_> = view.iter()
+        .filter(|p| p.spec_trend.as_ref().is_some_and(|t| t.scope_creep))
+        .collect();
+    if !creeping.is_empty() {
+        writeln!(out, "## Scope Creep ({})\n", creeping.len())?;
+        for project in creeping {
+            if let Some(trend) = &project.spec_trend {
+                writeln!(out, "- **{}**: spec grew {} {} {} words ({:+.0}%) since planning",
+                    project.display_name(), trend.baseline_words, style::icon("→", "to"), trend.current_words, (trend.growth - 1.0) * 100.0)?;
+            }
+        }
+        writeln!(out)?;
+    }
+
+    if velocity.transitions > 0 {
+        writeln!(out, "## Velocity\n")?;
+        writeln!(out, "{} stage changes across {} projects since {}.\n",
+            velocity.transitions, velocity.projects, timefmt::minutes(velocity.since))?;
+        let rows: Vec<Vec<String>> = velocity.mean_days.iter()
+            .map(|(stage, days)| vec![stage.to_string(), format!("{:.1}", days)])
+            .collect();
+        style::write_table(out, &["Stage Left", "Mean Days"], &rows)?;
+        writeln!(out)?;
+    }
+
+    if !sprints.is_empty() {
+        writeln!(out, "## Sprints\n")?;
+        let shown = sprints.len().saturating_sub(mode.sprint_limit());
+        write_sprint_table(&sprints[shown..], shown.checked_sub(1).map(|i| &sprints[i]), out)?;
+        writeln!(out)?;
+    }
+
+    writeln!(out, "---")?;
+    writeln!(out, "*Generated by SKM (Spec-Kit Manager)*")?;
+    Ok(())
+}
+
+/// How long projects in Review have waited against `[sla] review_days`, with
+/// those past it listed longest wait first
+fn write_review_sla<W: Write>(view: &PortfolioView<'_>, out: &mut W) -> io::Result<()> {
+    let waiting: Vec<_> = view.iter().filter_map(|p| p.review_sla.as_ref().map(|sla| (p, sla))).collect();
+    let Some((_, first)) = waiting.first() else {
+        return Ok(());
+    };
+    let mut breached: Vec<_> = waiting.iter().filter(|(_, sla)| sla.breached).collect();
+    breached.sort_by(|(a, x), (b, y)| y.waited_days.cmp(&x.waited_days).then_with(|| a.display_name().cmp(&b.display_name())));
+    let mean = waiting.iter().map(|(_, sla)| sla.waited_days as f64).sum::<f64>() / waiting.len() as f64;
+    let longest = waiting.iter().map(|(_, sla)| sla.waited_days).max().unwrap_or(0);
+
+    writeln!(out, "## Review SLA\n")?;
+    writeln!(out, "{} in Review, {} over the {}-day SLA; mean wait {:.1} business days, longest {}.\n",
+        waiting.len(), breached.len(), first.limit_days, mean, longest)?;
+    for (project, sla) in &breached {
+        writeln!(out, "- **{}**: {} business days, due {}", project.display_name(), sla.waited_days, timefmt::minutes(sla.due))?;
+    }
+    if !breached.is_empty() {
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+/// A handoff note under a heading naming its project, without the note's marker
+fn write_handoff<W: Write>(project: &str, note: &HandoffNote, out: &mut W) -> io::Result<()> {
+    let mut lines = note.text.lines();
+    let heading = lines.next().unwrap_or_default().trim_start_matches("## ");
+    writeln!(out, "### {} — {}", project, heading)?;
+    for line in lines.filter(|line| !line.starts_with("<!--")) {
+        writeln!(out, "{}", line)?;
+    }
+    writeln!(out)
+}
+
+/// Bullet lines describing a completion summary
+pub fn write_completion_lines<W: Write>(completion: &CompletionSummary, out: &mut W) -> io::Result<()> {
+    writeln!(out, "- **Projects Done**: {}/{} ({:.0}%)",
+        completion.projects_done, completion.projects_total, completion.percent_done)?;
+    writeln!(out, "- **Tasks Completed**: {:.0}%", completion.task_percent)?;
+    writeln!(out, "- **Effort-Weighted Progress**: {:.0}%", completion.effort_weighted_percent)
+}
+
+/// Sprint-over-sprint table; `previous` is the sprint the first row is compared with
+pub fn write_sprint_table<W: Write>(
+    sprints: &[SprintRollup],
+    previous: Option<&SprintRollup>,
+    out: &mut W,
+) -> io::Result<()> {
+    let mut rows = Vec::new();
+    let mut previous = previous;
+    for sprint in sprints {
+        let comparison = match previous {
+            Some(prev) => format!("{:+} changes, {:+} tasks, {:+} new",
+                sprint.stage_transitions as i64 - prev.stage_transitions as i64,
+                sprint.tasks_completed as i64 - prev.tasks_completed as i64,
+                sprint.new_projects as i64 - prev.new_projects as i64),
+            None => if style::accessible() { "none" } else { "—" }.to_string(),
+        };
+        rows.push(vec![
+            sprint.number.to_string(),
+            format!("{} {} {}",
+                sprint.start.format("%Y-%m-%d"),
+                if style::accessible() { "to" } else { "–" },
+                (sprint.end - chrono::Duration::days(1)).format("%Y-%m-%d")),
+            sprint.stage_transitions.to_string(),
+            sprint.tasks_completed.to_string(),
+            sprint.new_projects.to_string(),
+            comparison,
+        ]);
+        previous = Some(sprint);
+    }
+    style::write_table(out, &["Sprint", "Dates", "Stage Changes", "Tasks Done", "New Projects", "vs Previous"], &rows)
+}
+
+/// Time to address each type of human requirement, as a table
+pub fn write_time_to_address<W: Write>(stats: &[TimeToAddress], out: &mut W) -> io::Result<()> {
+    let hours = |hours: Option<f64>| match hours {
+        Some(hours) => timefmt::humanize_duration(chrono::Duration::seconds((hours * 3600.0) as i64)),
+        None => if style::accessible() { "none" } else { "—" }.to_string(),
+    };
+    let rows: Vec<Vec<String>> = stats.iter()
+        .map(|stat| vec![
+            format!("{:?}", stat.requirement),
+            stat.resolved.to_string(),
+            hours(stat.mean_hours),
+            stat.open.to_string(),
+            hours(stat.oldest_open_hours),
+        ])
+        .collect();
+    style::write_table(out, &["Requirement", "Addressed", "Mean Time to Address", "Open", "Oldest Open"], &rows)
+}
+
+/// Save a digest to a file
+pub fn save_digest(
+    view: &PortfolioView<'_>,
+    mode: DigestMode,
+    velocity: &StageVelocity,
+    sprints: &[SprintRollup],
+    handoffs: &[(String, HandoffNote)],
+    path: &Path,
+) -> Result<()> {
+    if let Some(parent) = path.parent()
+        && !parent.as_os_str().is_empty() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut out = BufWriter::new(fs::File::create(path)?);
+    write_digest(view, mode, velocity, sprints, handoffs, &mut out)?;
+    out.flush()?;
+    Ok(())
+}
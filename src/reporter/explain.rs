@@ -0,0 +1,61 @@
+use std::io::{self, Write};
+use crate::{Project, Stage};
+use crate::analyzer::priority::{PriorityBreakdown, PriorityFactors};
+use super::markdown::format_requirements;
+
+/// A project's priority broken into its factors, with the raw input behind
+/// each and, for additive strategies, the weighted points each contributed
+pub fn write_priority_explanation<W: Write>(project: &Project, out: &mut W) -> io::Result<()> {
+    let Some(breakdown) = &project.priority_breakdown else {
+        return writeln!(out, "{}: priority {:.1} (no breakdown recorded; run `skm scan`)", project.display_name(), project.priority);
+    };
+    writeln!(out, "{}: priority {:.1} ({})", project.display_name(), project.priority, breakdown.strategy)?;
+    writeln!(out, "  {:<12} {:<28} {:>6} {:>7} {:>7}", "factor", "input", "value", "weight", "points")?;
+    for factor in PriorityFactors::NAMES {
+        let value = breakdown.factors.get(factor).unwrap_or(0.0);
+        let input = describe_input(project, breakdown, factor);
+        match breakdown.terms.iter().find(|term| term.factor == factor) {
+            Some(term) => writeln!(out, "  {:<12} {:<28} {:>6.2} {:>7.1} {:>+7.1}", factor, input, value, term.weight, term.points())?,
+            None => writeln!(out, "  {:<12} {:<28} {:>6.2}", factor, input, value)?,
+        }
+    }
+    if project.stage == Stage::Done {
+        writeln!(out, "  Done projects score 0 whatever their factors ({:.1} otherwise)", breakdown.score)?;
+    } else if breakdown.terms.is_empty() {
+        writeln!(out, "  {} is not a sum of factors; see `priority_strategy` for its formula", breakdown.strategy)?;
+    } else {
+        writeln!(out, "  {:<12} {:<28} {:>6} {:>7} {:>7.1}", "total", "", "", "", breakdown.score)?;
+    }
+    if project.stage != Stage::Done {
+        if let Some(priority) = breakdown.priority_override {
+            writeln!(out, "  priority_override in meta.json sets the priority to {:.1} instead of {:.1}", priority, breakdown.score)?;
+            if let Some(boost) = breakdown.boost {
+                writeln!(out, "  priority_boost {:+.1} in meta.json is ignored while priority_override is set", boost)?;
+            }
+        } else if let Some(boost) = breakdown.boost {
+            writeln!(out, "  priority_boost in meta.json adds {:+.1}, for {:.1}", boost, project.priority)?;
+        }
+    }
+    if project.pinned {
+        writeln!(out, "  Pinned: listed first and needing attention whatever its priority")?;
+    }
+    Ok(())
+}
+
+fn describe_input(project: &Project, breakdown: &PriorityBreakdown, factor: &str) -> String {
+    let inputs = &breakdown.inputs;
+    match factor {
+        "needs_human" if project.requires_human.is_empty() => "nothing needs a person".to_string(),
+        "needs_human" => format_requirements(&project.requires_human),
+        "risk" => format!("risk level {} of 3", inputs.risk_level),
+        "staleness" => {
+            let paused = project.season.as_ref().is_some_and(|season| !season.active);
+            format!("{} days idle{}", inputs.idle_days, if paused { ", out of season" } else { "" })
+        }
+        "impact" => format!("impact {} of 3", inputs.impact),
+        "confidence" if inputs.approved_by_human => "approach approved".to_string(),
+        "confidence" => "approach not approved".to_string(),
+        "effort" => format!("{} open tasks", inputs.open_tasks),
+        _ => String::new(),
+    }
+}
@@ -0,0 +1,341 @@
+use std::collections::HashMap;
+use std::io::{self, Write};
+use crate::{Project, Stage};
+use crate::analyzer::heatmap::{RiskHeatMap, RiskSignal};
+use crate::analyzer::priority::PriorityTier;
+use crate::session::launch;
+use crate::timefmt;
+use crate::view::PortfolioView;
+use super::markdown::{format_github, format_requirements};
+
+/// Width of the bar area in the charts, in pixels
+const CHART_WIDTH: u32 = 460;
+/// Width reserved for the labels left of the bars
+const LABEL_WIDTH: u32 = 100;
+const BAR_HEIGHT: u32 = 22;
+/// Height of the plot area in line charts, in pixels
+const LINE_HEIGHT: u32 = 140;
+
+const STYLE: &str = r#"
+body { font-family: -apple-system, "Segoe UI", Roboto, sans-serif; margin: 2rem auto; max-width: 960px; color: #1f2328; padding: 0 1rem; }
+h1 { margin-bottom: 0.2rem; }
+.generated { color: #59636e; margin-top: 0; }
+.summary { display: flex; flex-wrap: wrap; gap: 0.75rem; margin: 1.5rem 0; }
+.stat { border: 1px solid #d1d9e0; border-radius: 6px; padding: 0.6rem 1rem; min-width: 8rem; }
+.stat .value { font-size: 1.5rem; font-weight: 600; }
+.stat .label { color: #59636e; font-size: 0.85rem; }
+.charts { display: grid; grid-template-columns: repeat(auto-fit, minmax(420px, 1fr)); gap: 1.5rem; }
+svg { max-width: 100%; height: auto; }
+svg text { font-size: 12px; fill: #1f2328; }
+figure { margin: 0; }
+.cards { display: grid; grid-template-columns: repeat(auto-fill, minmax(280px, 1fr)); gap: 1rem; }
+.card { border: 1px solid #d1d9e0; border-left-width: 6px; border-radius: 6px; padding: 0.8rem 1rem; }
+.card.high { border-left-color: #cf222e; }
+.card.medium { border-left-color: #bf8700; }
+.card.low { border-left-color: #1a7f37; }
+.card h3 { margin: 0 0 0.3rem; font-size: 1.05rem; }
+.card .meta { color: #59636e; font-size: 0.85rem; margin-bottom: 0.5rem; }
+.card a { color: #0969da; }
+.tier { font-weight: 600; text-transform: uppercase; font-size: 0.75rem; }
+.bar { background: #eff2f5; border-radius: 4px; height: 10px; overflow: hidden; margin: 0.3rem 0; }
+.bar > div { background: #2da44e; height: 100%; }
+.card ul { padding-left: 1.1rem; margin: 0.4rem 0 0; font-size: 0.9rem; }
+code { background: #eff2f5; padding: 0 0.25rem; border-radius: 3px; }
+table { border-collapse: collapse; }
+th, td { border-bottom: 1px solid #d1d9e0; padding: 0.3rem 0.8rem 0.3rem 0; text-align: left; }
+.heatmap td, .heatmap th { text-align: center; }
+.heatmap td:first-child, .heatmap th:first-child { text-align: left; }
+.heatmap td.hot { background: #ffebe9; color: #cf222e; }
+.heatmap tfoot td { font-weight: 600; }
+.systemic, .breach { color: #cf222e; }
+footer { margin-top: 2rem; color: #59636e; font-size: 0.85rem; }
+"#;
+
+/// Stream a self-contained HTML report covering the projects selected by a view
+///
+/// Charts are inline SVG with a text description, so the file needs no
+/// network access or scripts and reads the same in a screen reader.
+pub fn write_html_view<W: Write>(view: &PortfolioView<'_>, out: &mut W) -> io::Result<()> {
+    write_dashboard(view, None, out)
+}
+
+/// What a published site adds to the dashboard
+pub(crate) struct SiteIndex<'a> {
+    /// Page of each project relative to the index, by display name
+    pub pages: &'a HashMap<String, String>,
+    /// Portfolio task completion in percent, one point per day
+    pub completion: &'a [(String, f64)],
+    /// Projects needing attention, one point per day
+    pub attention: &'a [(String, f64)],
+}
+
+/// The dashboard page; for a site, cards link to the project pages and show
+/// absolute times, so republishing unchanged data gives the same page
+pub(crate) fn write_dashboard<W: Write>(view: &PortfolioView<'_>, site: Option<&SiteIndex<'_>>, out: &mut W) -> io::Result<()> {
+    let status = view.status;
+    let sorted = view.clone().sorted_by_priority();
+
+    write_head(out, "SKM Portfolio Status Report")?;
+    writeln!(out, "<h1>SKM Portfolio Status Report</h1>")?;
+    writeln!(out, "<p class=\"generated\">Generated {}</p>", escape(&timefmt::seconds(status.generated_at)))?;
+
+    // Totals of the selected projects, which differ from the summary under --where
+    let completed: u32 = sorted.iter().map(|p| p.tasks.completed).sum();
+    let total: u32 = sorted.iter().map(|p| p.tasks.total).sum();
+    let avg_priority = if sorted.is_empty() {
+        0.0
+    } else {
+        sorted.iter().map(|p| p.priority).sum::<f64>() / sorted.len() as f64
+    };
+    writeln!(out, "<section class=\"summary\">")?;
+    write_stat(out, &sorted.len().to_string(), "Projects")?;
+    write_stat(out, &sorted.iter().filter(|p| p.needs_attention).count().to_string(), "Need attention")?;
+    write_stat(out, &sorted.iter().filter(|p| p.stage == Stage::Done).count().to_string(), "Done")?;
+    write_stat(out, &format!("{:.0}%", percent(completed, total)), &format!("Tasks ({}/{})", completed, total))?;
+    write_stat(out, &sorted.iter().map(|p| p.open_questions.len()).sum::<usize>().to_string(), "Open questions")?;
+    write_stat(out, &format!("{:.1}", avg_priority), "Average priority")?;
+    writeln!(out, "</section>")?;
+
+    writeln!(out, "<section class=\"charts\">")?;
+    let stages: Vec<(String, usize, &str)> = status.summary.ladder().iter()
+        .map(|stage| (stage.to_string(), sorted.iter().filter(|p| p.stage == *stage).count(), "#0969da"))
+        .collect();
+    write_bar_chart(out, "stages", "Stage distribution", &stages)?;
+    write_bar_chart(out, "priorities", "Priority histogram", &priority_buckets(&sorted))?;
+    if let Some(site) = site {
+        write_line_chart(out, "completion", "Tasks completed (%)", site.completion, 100.0)?;
+        write_line_chart(out, "attention", "Projects needing attention", site.attention, 0.0)?;
+    }
+    writeln!(out, "</section>")?;
+
+    write_heat_map(&RiskHeatMap::new(sorted.iter()), out)?;
+
+    writeln!(out, "<h2>Projects</h2>")?;
+    writeln!(out, "<section class=\"cards\">")?;
+    for project in sorted.iter() {
+        write_card(project, site.map(|site| site.pages), out)?;
+    }
+    writeln!(out, "</section>")?;
+
+    writeln!(out, "<footer>Generated by SKM (Spec-Kit Manager)</footer>")?;
+    writeln!(out, "</body>\n</html>")
+}
+
+/// Projects × risk signals as a grid; a column's total is shaded by the share
+/// of the portfolio it covers
+fn write_heat_map<W: Write>(map: &RiskHeatMap<'_>, out: &mut W) -> io::Result<()> {
+    if map.rows.is_empty() {
+        return Ok(());
+    }
+    writeln!(out, "<h2>Risk Heat Map</h2>")?;
+    for (signal, total) in map.systemic() {
+        writeln!(out, "<p class=\"systemic\">Systemic: {} of {} projects have {}</p>",
+            total, map.projects, escape(signal.description()))?;
+    }
+    writeln!(out, "<table class=\"heatmap\">\n<thead><tr><th scope=\"col\">Project</th>")?;
+    for signal in RiskSignal::ALL {
+        writeln!(out, "<th scope=\"col\">{}</th>", escape(signal.label()))?;
+    }
+    writeln!(out, "</tr></thead>\n<tbody>")?;
+    for (project, cells) in &map.rows {
+        write!(out, "<tr><th scope=\"row\">{}</th>", escape(&project.display_name()))?;
+        for (signal, hit) in RiskSignal::ALL.iter().zip(cells) {
+            match hit {
+                true => write!(out, "<td class=\"hot\" title=\"{}\">●<span hidden>yes</span></td>", escape(signal.label()))?,
+                false => write!(out, "<td></td>")?,
+            }
+        }
+        writeln!(out, "</tr>")?;
+    }
+    write!(out, "</tbody>\n<tfoot><tr><td>All {}</td>", map.projects)?;
+    for (signal, total) in RiskSignal::ALL.iter().zip(map.totals) {
+        let share = map.share(*signal);
+        write!(out, "<td style=\"background: rgba(207, 34, 46, {:.2})\">{} ({:.0}%)</td>", share * 0.6, total, share * 100.0)?;
+    }
+    writeln!(out, "</tr></tfoot>\n</table>")
+}
+
+/// Doctype, metadata and the shared stylesheet, up to the opening `<body>`
+pub(crate) fn write_head<W: Write>(out: &mut W, title: &str) -> io::Result<()> {
+    writeln!(out, "<!DOCTYPE html>")?;
+    writeln!(out, "<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">")?;
+    writeln!(out, "<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">")?;
+    writeln!(out, "<title>{}</title>", escape(title))?;
+    writeln!(out, "<style>{}</style>\n</head>\n<body>", STYLE)
+}
+
+pub(crate) fn write_stat<W: Write>(out: &mut W, value: &str, label: &str) -> io::Result<()> {
+    writeln!(out, "<div class=\"stat\"><div class=\"value\">{}</div><div class=\"label\">{}</div></div>",
+        escape(value), escape(label))
+}
+
+/// Projects per priority decile, colored by the tier the decile falls in
+fn priority_buckets(view: &PortfolioView<'_>) -> Vec<(String, usize, &'static str)> {
+    (0..10)
+        .map(|bucket| {
+            let low = bucket as f64 * 10.0;
+            let count = view.iter()
+                .filter(|p| (p.priority.clamp(0.0, 99.99) / 10.0).floor() as usize == bucket)
+                .count();
+            let color = match PriorityTier::from_priority(low + 5.0) {
+                PriorityTier::High => "#cf222e",
+                PriorityTier::Medium => "#bf8700",
+                PriorityTier::Low => "#1a7f37",
+            };
+            (format!("{:.0}-{:.0}", low, low + 10.0), count, color)
+        })
+        .collect()
+}
+
+/// Horizontal bar chart of labelled counts, with the counts repeated in
+/// the chart's title and description for screen readers
+pub(crate) fn write_bar_chart<W: Write>(out: &mut W, id: &str, title: &str, bars: &[(String, usize, &str)]) -> io::Result<()> {
+    let max = bars.iter().map(|(_, count, _)| *count).max().unwrap_or(0).max(1);
+    let height = BAR_HEIGHT * bars.len() as u32 + 4;
+    let description = bars.iter()
+        .map(|(label, count, _)| format!("{}: {}", label, count))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    writeln!(out, "<figure>\n<figcaption><h2>{}</h2></figcaption>", escape(title))?;
+    writeln!(out, "<svg role=\"img\" aria-labelledby=\"{id}-title {id}-desc\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\">",
+        LABEL_WIDTH + CHART_WIDTH + 40, height, LABEL_WIDTH + CHART_WIDTH + 40, height)?;
+    writeln!(out, "<title id=\"{}-title\">{}</title>", id, escape(title))?;
+    writeln!(out, "<desc id=\"{}-desc\">{}</desc>", id, escape(&description))?;
+    for (row, (label, count, color)) in bars.iter().enumerate() {
+        let y = row as u32 * BAR_HEIGHT + 2;
+        let width = (*count as u32 * CHART_WIDTH) / max as u32;
+        writeln!(out, "<text x=\"{}\" y=\"{}\" text-anchor=\"end\">{}</text>", LABEL_WIDTH - 8, y + 15, escape(label))?;
+        writeln!(out, "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"3\" fill=\"{}\"/>",
+            LABEL_WIDTH, y, width, BAR_HEIGHT - 6, color)?;
+        writeln!(out, "<text x=\"{}\" y=\"{}\">{}</text>", LABEL_WIDTH + width + 6, y + 15, count)?;
+    }
+    writeln!(out, "</svg>\n</figure>")
+}
+
+/// Line chart of values over time, one point per label, scaled to `max`
+/// or, when that is 0, to the largest value
+///
+/// The description names the first and latest values for screen readers.
+pub(crate) fn write_line_chart<W: Write>(out: &mut W, id: &str, title: &str, points: &[(String, f64)], max: f64) -> io::Result<()> {
+    let max = if max > 0.0 { max } else { points.iter().map(|(_, value)| *value).fold(1.0, f64::max) };
+    let description = match (points.first(), points.last()) {
+        (Some((first_label, first)), Some((last_label, last))) if points.len() > 1 =>
+            format!("{:.0} on {}, {:.0} on {}", first, first_label, last, last_label),
+        (Some((label, value)), _) => format!("{:.0} on {}", value, label),
+        _ => "No history yet".to_string(),
+    };
+    let width = LABEL_WIDTH + CHART_WIDTH + 40;
+    let height = LINE_HEIGHT + 30;
+    let x = |index: usize| LABEL_WIDTH as f64
+        + if points.len() > 1 { index as f64 * CHART_WIDTH as f64 / (points.len() - 1) as f64 } else { CHART_WIDTH as f64 / 2.0 };
+    let y = |value: f64| 8.0 + LINE_HEIGHT as f64 * (1.0 - (value / max).clamp(0.0, 1.0));
+
+    writeln!(out, "<figure>\n<figcaption><h2>{}</h2></figcaption>", escape(title))?;
+    writeln!(out, "<svg role=\"img\" aria-labelledby=\"{id}-title {id}-desc\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\">",
+        width, height, width, height)?;
+    writeln!(out, "<title id=\"{}-title\">{}</title>", id, escape(title))?;
+    writeln!(out, "<desc id=\"{}-desc\">{}</desc>", id, escape(&description))?;
+    writeln!(out, "<text x=\"{}\" y=\"{}\" text-anchor=\"end\">{:.0}</text>", LABEL_WIDTH - 8, y(max) + 4.0, max)?;
+    writeln!(out, "<text x=\"{}\" y=\"{}\" text-anchor=\"end\">0</text>", LABEL_WIDTH - 8, y(0.0) + 4.0)?;
+    writeln!(out, "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"#d1d9e0\"/>",
+        LABEL_WIDTH, y(0.0), LABEL_WIDTH + CHART_WIDTH, y(0.0))?;
+    let line = points.iter().enumerate()
+        .map(|(index, (_, value))| format!("{:.1},{:.1}", x(index), y(*value)))
+        .collect::<Vec<_>>()
+        .join(" ");
+    writeln!(out, "<polyline points=\"{}\" fill=\"none\" stroke=\"#0969da\" stroke-width=\"2\"/>", line)?;
+    for (index, (_, value)) in points.iter().enumerate() {
+        writeln!(out, "<circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"3\" fill=\"#0969da\"/>", x(index), y(*value))?;
+    }
+    if let (Some((first, _)), Some((last, _))) = (points.first(), points.last()) {
+        writeln!(out, "<text x=\"{}\" y=\"{}\">{}</text>", LABEL_WIDTH, height - 4, escape(first))?;
+        if points.len() > 1 {
+            writeln!(out, "<text x=\"{}\" y=\"{}\" text-anchor=\"end\">{}</text>", LABEL_WIDTH + CHART_WIDTH, height - 4, escape(last))?;
+        }
+    }
+    writeln!(out, "</svg>\n</figure>")
+}
+
+/// A project card; with site pages, the title links to the project's page
+/// instead of its directory
+fn write_card<W: Write>(project: &Project, pages: Option<&HashMap<String, String>>, out: &mut W) -> io::Result<()> {
+    let tier = PriorityTier::from_priority(project.priority);
+    let done = percent(project.tasks.completed, project.tasks.total);
+    let (href, updated) = match pages {
+        Some(pages) => (pages.get(&project.display_name()).cloned().unwrap_or_default(), timefmt::minutes(project.updated)),
+        None => {
+            let path = project.path.canonicalize().unwrap_or_else(|_| project.path.clone());
+            (launch::file_url(&path), timefmt::ago(project.updated))
+        }
+    };
+
+    writeln!(out, "<article class=\"card {}\" id=\"project-{}\">", tier.as_str(), escape(&project.id))?;
+    writeln!(out, "<h3><a href=\"{}\">{}</a></h3>", escape(&href), escape(&project.display_name()))?;
+    writeln!(out, "<div class=\"meta\"><span class=\"tier\">{} priority</span> {:.1} &middot; {:?} &middot; updated {}</div>",
+        tier.as_str(), project.priority, project.stage, escape(&updated))?;
+    writeln!(out, "<div class=\"bar\" role=\"progressbar\" aria-label=\"Tasks completed\" aria-valuemin=\"0\" aria-valuemax=\"100\" aria-valuenow=\"{:.0}\"><div style=\"width: {:.0}%\"></div></div>",
+        done, done)?;
+    writeln!(out, "<div>{}/{} tasks ({:.0}%)</div>", project.tasks.completed, project.tasks.total, done)?;
+
+    writeln!(out, "<ul>")?;
+    writeln!(out, "<li>Next: {} <code>{}</code></li>", escape(&project.next.description), escape(&project.next.command))?;
+    for launch in project.next.launch.iter().filter(|launch| launch.target.contains("://")) {
+        writeln!(out, "<li><a href=\"{}\">Open in {}</a></li>", escape(&launch.target), escape(launch.agent.label()))?;
+    }
+    if !project.requires_human.is_empty() {
+        writeln!(out, "<li>Requires human: {}</li>", escape(&format_requirements(&project.requires_human)))?;
+    }
+    if let Some(sla) = &project.review_sla {
+        writeln!(out, "<li{}>Review SLA: {}</li>", if sla.breached { " class=\"breach\"" } else { "" }, escape(&sla.summary()))?;
+    }
+    if !project.open_questions.is_empty() {
+        writeln!(out, "<li>{} open questions</li>", project.open_questions.len())?;
+    }
+    if project.git.is_repo {
+        writeln!(out, "<li>Git: {} ({})</li>",
+            escape(project.git.branch.as_deref().unwrap_or("unknown")),
+            if project.git.clean { "clean" } else { "uncommitted changes" })?;
+    }
+    if let Some(github) = &project.git.github {
+        writeln!(out, "<li>GitHub: {}</li>", escape(&format_github(github)))?;
+    }
+    if let Some(check) = project.last_check.as_ref().filter(|check| !check.success) {
+        writeln!(out, "<li>Build failing: <code>{}</code></li>", escape(&check.command))?;
+    }
+    writeln!(out, "<li>License: {}</li>", escape(&project.license.compliance()))?;
+    writeln!(out, "</ul>")?;
+    if !project.features.is_empty() {
+        writeln!(out, "<details><summary>{} features</summary><ul>", project.features.len())?;
+        for feature in &project.features {
+            writeln!(out, "<li>{}: {}, {}/{} tasks ({:.0}%)</li>", escape(&feature.id), feature.stage,
+                feature.tasks.completed, feature.tasks.total, percent(feature.tasks.completed, feature.tasks.total))?;
+        }
+        writeln!(out, "</ul></details>")?;
+    }
+    writeln!(out, "</article>")
+}
+
+pub(crate) fn percent(part: u32, total: u32) -> f64 {
+    if total > 0 {
+        part as f64 / total as f64 * 100.0
+    } else {
+        0.0
+    }
+}
+
+/// Escape text for HTML element content and double-quoted attributes
+pub(crate) fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
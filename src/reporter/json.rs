@@ -1 +1,66 @@
-// Placeholder for src/reporter/json.rs
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use crate::PortfolioStatus;
+use crate::analyzer::priority::PriorityTier;
+
+/// Version of the `skm completion-data` contract
+///
+/// Fields are only ever added within a version. Renaming, removing or
+/// changing the meaning of a field bumps it.
+pub const COMPLETION_FEED_VERSION: u32 = 1;
+
+/// Minimal project list for launchers (Raycast, Alfred), most urgent first
+#[derive(Serialize, Debug, Clone)]
+pub struct CompletionFeed {
+    pub schema_version: u32,
+    /// When the portfolio was scanned
+    pub generated_at: DateTime<Utc>,
+    pub projects: Vec<CompletionItem>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct CompletionItem {
+    pub id: String,
+    /// Display name, prefixed with its root (`work/api`) when roots are configured
+    pub name: String,
+    /// Kebab-case stage name, e.g. `implement`
+    pub stage: String,
+    /// `high`, `medium` or `low`
+    pub tier: &'static str,
+    /// Absolute path of the project directory
+    pub path: String,
+    pub next_action: CompletionAction,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct CompletionAction {
+    pub command: String,
+    pub description: String,
+}
+
+/// Build the launcher feed from a portfolio, highest priority first
+pub fn completion_feed(portfolio: &PortfolioStatus) -> CompletionFeed {
+    let mut projects: Vec<_> = portfolio.projects.iter().collect();
+    projects.sort_by(|a, b| a.priority_order(b));
+
+    CompletionFeed {
+        schema_version: COMPLETION_FEED_VERSION,
+        generated_at: portfolio.generated_at,
+        projects: projects.into_iter()
+            .map(|project| CompletionItem {
+                id: project.id.clone(),
+                name: project.display_name(),
+                stage: project.stage.as_str().to_string(),
+                tier: PriorityTier::from_priority(project.priority).as_str(),
+                path: project.path.canonicalize()
+                    .unwrap_or_else(|_| project.path.clone())
+                    .to_string_lossy()
+                    .into_owned(),
+                next_action: CompletionAction {
+                    command: project.next.command.clone(),
+                    description: project.next.description.clone(),
+                },
+            })
+            .collect(),
+    }
+}
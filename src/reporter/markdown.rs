@@ -1,21 +1,69 @@
+use std::collections::BTreeMap;
 use std::path::Path;
 use std::fs;
-use anyhow::Result;
-use crate::{PortfolioStatus, Stage, HumanRequirement};
+use std::io::{self, BufWriter, Write};
+use crate::Result;
+use crate::{FeatureStatus, GitHubStatus, PortfolioStatus, Project, HumanRequirement, Principle, UnstableField};
+use crate::analyzer::heatmap::{RiskHeatMap, RiskSignal};
+use crate::analyzer::priority::PriorityTier;
+use crate::timefmt;
+use super::style;
+use crate::view::PortfolioView;
 
 /// Generate a markdown report for the portfolio status
 pub fn generate_markdown_report(status: &PortfolioStatus) -> String {
-    let mut report = String::new();
-    
-    // Header
-    report.push_str("# SKM Portfolio Status Report\n\n");
-    report.push_str(&format!("Generated: {}\n\n", status.generated_at.format("%Y-%m-%d %H:%M:%S UTC")));
-    
-    // Summary
-    report.push_str("## Summary\n\n");
-    report.push_str(&format!("- **Total Projects**: {}\n", status.summary.total_projects));
-    report.push_str(&format!("- **Need Attention**: {} 🚨\n", status.summary.needs_attention));
-    report.push_str(&format!("- **Tasks Progress**: {}/{} completed ({:.0}%)\n", 
+    let mut buf = Vec::new();
+    write_markdown_report(status, &mut buf).expect("writing to a Vec cannot fail");
+    String::from_utf8(buf).expect("report is valid UTF-8")
+}
+
+/// Stream a markdown report for the portfolio status into a writer
+///
+/// Projects are sorted by reference, so the portfolio is never cloned and
+/// the report is never held in memory as a whole.
+pub fn write_markdown_report<W: Write>(status: &PortfolioStatus, out: &mut W) -> io::Result<()> {
+    write_markdown_view(&PortfolioView::new(status), out)
+}
+
+/// Stream a markdown report covering only the projects selected by a view
+pub fn write_markdown_view<W: Write>(view: &PortfolioView<'_>, out: &mut W) -> io::Result<()> {
+    let status = view.status;
+    let sorted = view.clone().sorted_by_priority();
+
+    write_header(status, out)?;
+    write_summary(status, out)?;
+    write_stage_distribution(status, out)?;
+    write_priority_table(sorted.iter().take(10), out)?;
+    write_broken_builds(sorted.iter(), out)?;
+    write_sla_breaches(sorted.iter(), out)?;
+    write_security_advisories(sorted.iter(), out)?;
+    write_risk_heat_map(&RiskHeatMap::new(sorted.iter()), out)?;
+
+    // All Projects Details
+    writeln!(out, "## Project Details\n")?;
+    for project in sorted.iter() {
+        write_project_details(project, out)?;
+    }
+
+    write_errors(status, out)?;
+
+    // Footer
+    writeln!(out, "---")?;
+    writeln!(out, "*Generated by SKM (Spec-Kit Manager)*")?;
+
+    Ok(())
+}
+
+fn write_header<W: Write>(status: &PortfolioStatus, out: &mut W) -> io::Result<()> {
+    writeln!(out, "# SKM Portfolio Status Report\n")?;
+    writeln!(out, "Generated: {}\n", timefmt::seconds(status.generated_at))
+}
+
+fn write_summary<W: Write>(status: &PortfolioStatus, out: &mut W) -> io::Result<()> {
+    writeln!(out, "## Summary\n")?;
+    writeln!(out, "- **Total Projects**: {}", status.summary.total_projects)?;
+    writeln!(out, "- **Need Attention**: {}{}", status.summary.needs_attention, if style::accessible() { "" } else { " 🚨" })?;
+    writeln!(out, "- **Tasks Progress**: {}/{} completed ({:.0}%)",
         status.summary.completed_tasks,
         status.summary.total_tasks,
         if status.summary.total_tasks > 0 {
@@ -23,151 +71,376 @@ pub fn generate_markdown_report(status: &PortfolioStatus) -> String {
         } else {
             0.0
         }
-    ));
-    report.push_str(&format!("- **Average Priority**: {:.1}\n", status.summary.avg_priority));
-    report.push_str(&format!("- **Scan Time**: {}ms\n\n", status.scan_stats.scan_time_ms));
-    
-    // Stage Distribution
-    report.push_str("## Stage Distribution\n\n");
-    report.push_str("| Stage | Count |\n");
-    report.push_str("|-------|-------|\n");
-    let stages = [
-        Stage::Bootstrap,
-        Stage::Specify,
-        Stage::Plan,
-        Stage::Tasks,
-        Stage::Implement,
-        Stage::Test,
-        Stage::Review,
-        Stage::Done,
-    ];
-    for stage in &stages {
-        let count = status.summary.by_stage.get(stage).unwrap_or(&0);
-        report.push_str(&format!("| {:?} | {} |\n", stage, count));
-    }
-    report.push('\n');
-
-    // Priority Projects (top 10)
-    report.push_str("## High Priority Projects\n\n");
-    let mut sorted_projects = status.projects.clone();
-    sorted_projects.sort_by(|a, b| b.priority.partial_cmp(&a.priority).unwrap());
-    
-    if sorted_projects.is_empty() {
-        report.push_str("No projects found.\n\n");
-    } else {
-        report.push_str("| Priority | Project | Stage | Next Action | Human Needed |\n");
-        report.push_str("|----------|---------|-------|-------------|---------------|\n");
-        
-        for project in sorted_projects.iter().take(10) {
-            let human_str = if project.requires_human.is_empty() {
-                "No".to_string()
-            } else {
-                format!("Yes ({})", format_requirements(&project.requires_human))
-            };
-            
-            let priority_emoji = if project.priority > 70.0 {
-                "🔴"
-            } else if project.priority > 40.0 {
-                "🟡"
-            } else {
-                "🟢"
-            };
-            
-            report.push_str(&format!(
-                "| {:.1} {} | {} | {:?} | {} | {} |\n",
-                project.priority,
-                priority_emoji,
-                project.path.file_name()
-                    .and_then(|s| s.to_str())
-                    .unwrap_or("unknown"),
-                project.stage,
-                truncate(&project.next.description, 40),
-                human_str
-            ));
+    )?;
+    writeln!(out, "- **Completion**: {:.0}% effort-weighted, {}/{} projects done",
+        status.summary.completion.effort_weighted_percent,
+        status.summary.completion.projects_done,
+        status.summary.completion.projects_total,
+    )?;
+    if status.summary.completion.by_root.len() > 1 {
+        for root in &status.summary.completion.by_root {
+            writeln!(out, "  - **{}**: {} projects, {} need attention, {:.0}% effort-weighted",
+                root.root,
+                root.completion.projects_total,
+                root.needs_attention,
+                root.completion.effort_weighted_percent,
+            )?;
         }
-        report.push('\n');
     }
-    
-    // All Projects Details
-    report.push_str("## Project Details\n\n");
-    for project in &sorted_projects {
-        report.push_str(&format!("### {}\n\n", 
-            project.path.display()
-        ));
-        
-        report.push_str(&format!("- **Stage**: {:?}\n", project.stage));
-        report.push_str(&format!("- **Priority**: {:.1}\n", project.priority));
-        report.push_str(&format!("- **Type**: {:?}\n", project.project_type));
-        report.push_str(&format!("- **Last Updated**: {}\n", 
-            project.updated.format("%Y-%m-%d %H:%M UTC")
-        ));
-        
-        if project.git.is_repo {
-            report.push_str(&format!("- **Git Branch**: {}\n", 
-                project.git.branch.as_ref().unwrap_or(&"unknown".to_string())
-            ));
-            report.push_str(&format!("- **Git Status**: {}\n", 
-                if project.git.clean { "✅ Clean" } else { "⚠️ Uncommitted changes" }
-            ));
-        }
-        
-        report.push_str(&format!("- **Tasks**: {}/{} completed", 
-            project.tasks.completed, 
-            project.tasks.total
-        ));
-        if project.tasks.parallel_marked > 0 {
-            report.push_str(&format!(" ({} parallel)", project.tasks.parallel_marked));
+    writeln!(out, "- **Open Questions**: {}", status.summary.open_questions)?;
+    writeln!(out, "- **Average Priority**: {:.1}", status.summary.avg_priority)?;
+    writeln!(out, "- **Scan Time**: {}ms\n", status.scan_stats.scan_time_ms)
+}
+
+fn write_stage_distribution<W: Write>(status: &PortfolioStatus, out: &mut W) -> io::Result<()> {
+    writeln!(out, "## Stage Distribution\n")?;
+    let rows: Vec<Vec<String>> = status.summary.ladder().iter()
+        .map(|stage| vec![stage.to_string(), status.summary.by_stage.get(stage).unwrap_or(&0).to_string()])
+        .collect();
+    style::write_table(out, &["Stage", "Count"], &rows)?;
+    writeln!(out)
+}
+
+/// Priority Projects table (callers pass the top N)
+fn write_priority_table<'a, W: Write>(
+    projects: impl Iterator<Item = &'a Project>,
+    out: &mut W,
+) -> io::Result<()> {
+    writeln!(out, "## High Priority Projects\n")?;
+
+    let mut projects = projects.peekable();
+    if projects.peek().is_none() {
+        return writeln!(out, "No projects found.\n");
+    }
+
+    let rows: Vec<Vec<String>> = projects.map(|project| {
+        let human_str = if project.requires_human.is_empty() {
+            "No".to_string()
+        } else {
+            format!("Yes ({})", format_requirements(&project.requires_human))
+        };
+        vec![
+            format!("{:.1} {}", project.priority, PriorityTier::from_priority(project.priority).symbol()),
+            if project.pinned { format!("{} {}", project.display_name(), style::icon("📌", "(pinned)")) } else { project.display_name() },
+            project.stage.to_string(),
+            truncate(&project.next.description, 40),
+            human_str,
+            project.license.compliance(),
+        ]
+    }).collect();
+    style::write_table(out, &["Priority", "Project", "Stage", "Next Action", "Human Needed", "Compliance"], &rows)?;
+    writeln!(out)
+}
+
+fn write_project_details<W: Write>(project: &Project, out: &mut W) -> io::Result<()> {
+    writeln!(out, "### {}\n", project.path.display())?;
+
+    if let Some(root) = &project.root {
+        writeln!(out, "- **Root**: {}", root)?;
+    }
+    if let Some(url) = &project.remote {
+        writeln!(out, "- **Remote**: {} (read-only clone)", url)?;
+    }
+    writeln!(out, "- **Stage**: {}", project.stage)?;
+    writeln!(out, "- **Priority**: {:.1}{}", project.priority, if project.pinned { " (pinned)" } else { "" })?;
+    if !project.unstable.is_empty() {
+        writeln!(out, "- **Unstable**: {}", style::decorate("⚠️", &format_unstable(&project.unstable)))?;
+    }
+    writeln!(out, "- **Type**: {:?}", project.project_type)?;
+    if !project.capabilities.is_empty() {
+        let names: Vec<&str> = project.capabilities.iter().map(|c| c.as_str()).collect();
+        writeln!(out, "- **Environments**: {}", names.join(", "))?;
+    }
+    writeln!(out, "- **Last Updated**: {}", timefmt::minutes(project.updated))?;
+    if let Some(debt) = project.code_debt {
+        writeln!(out, "- **Code Debt**: {} TODO/FIXME comments", debt)?;
+    }
+    if let Some(audit) = &project.audit {
+        match &audit.error {
+            Some(error) => writeln!(out, "- **Audit**: {}", style::decorate("⚠️", error))?,
+            None => writeln!(out, "- **Audit**: {} known vulnerabilities ({})", audit.vulnerabilities, audit.tool)?,
         }
-        if project.tasks.blocked > 0 {
-            report.push_str(&format!(" ({} blocked)", project.tasks.blocked));
+    }
+    if let Some(freshness) = &project.freshness {
+        writeln!(out, "- **Dependencies**: {}/{} a major version behind{}",
+            freshness.major_behind, freshness.direct,
+            if freshness.unchecked > 0 { format!(" ({} unchecked)", freshness.unchecked) } else { String::new() })?;
+    }
+    writeln!(out, "- **License**: {}", project.license.compliance())?;
+    for conflict in &project.license.conflicts {
+        writeln!(out, "  - incompatible: {}", conflict)?;
+    }
+    if project.constitution_inherited {
+        writeln!(out, "- **Constitution**: inherited from the portfolio")?;
+    }
+    if !project.principles.is_empty() {
+        writeln!(out, "- **Principles**: {}", format_principles(&project.principles))?;
+    }
+    if !project.custom.is_empty() {
+        writeln!(out, "- **Custom**: {}", format_custom_fields(&project.custom))?;
+    }
+
+    if project.git.is_repo {
+        writeln!(out, "- **Git Branch**: {}", project.git.branch.as_deref().unwrap_or("unknown"))?;
+        writeln!(out, "- **Git Status**: {}",
+            if project.git.clean { style::decorate("✅", "Clean") } else { style::decorate("⚠️", "Uncommitted changes") }
+        )?;
+    } else if project.git.skipped {
+        writeln!(out, "- **Git Status**: skipped")?;
+    } else if project.git.pending {
+        writeln!(out, "- **Git Status**: pending")?;
+    }
+    if let Some(github) = &project.git.github {
+        writeln!(out, "- **GitHub**: {}", format_github(github))?;
+    }
+
+    write!(out, "- **Tasks**: {}/{} completed", project.tasks.completed, project.tasks.total)?;
+    if project.tasks.parallel_marked > 0 {
+        write!(out, " ({} parallel)", project.tasks.parallel_marked)?;
+    }
+    if project.tasks.blocked > 0 {
+        write!(out, " ({} blocked)", project.tasks.blocked)?;
+    }
+    writeln!(out)?;
+    if !project.features.is_empty() {
+        writeln!(out, "- **Features**: {}", project.features.len())?;
+        for feature in &project.features {
+            writeln!(out, "  - {}: {}, {}/{} tasks{}", feature.id, feature.stage,
+                feature.tasks.completed, feature.tasks.total, format_feature_files(feature))?;
         }
-        report.push('\n');
-
-        report.push_str(&format!("- **Next Action**: {}\n", project.next.description));
-        report.push_str(&format!("  - Command: `{}`\n", project.next.command));
-        report.push_str(&format!("  - Automated: {}\n", 
-            if project.next.automated { "Yes" } else { "No" }
-        ));
-        
-        if !project.requires_human.is_empty() {
-            report.push_str(&format!("- **Requires Human**: {}\n", 
-                format_requirements(&project.requires_human)
-            ));
+    }
+
+    if let Some(metrics) = project.artifacts.spec.as_ref().and_then(|f| f.metrics.as_ref()) {
+        write!(out, "- **Spec**: {} words, {} sections, {} requirements, {} TODOs",
+            metrics.words, metrics.sections, metrics.requirements, metrics.todos)?;
+        if let Some(trend) = &project.spec_trend {
+            write!(out, " ({:+.0}% since planning{})",
+                (trend.growth - 1.0) * 100.0,
+                if trend.scope_creep { format!(", {}", style::decorate("⚠️", "scope creep")) } else { String::new() })?;
         }
+        writeln!(out)?;
+    }
 
-        report.push('\n');
+    if !project.open_questions.is_empty() {
+        writeln!(out, "- **Open Questions**: {}", project.open_questions.len())?;
+        for question in &project.open_questions {
+            writeln!(out, "  - {} (line {}): {}", question.source, question.line, question.text)?;
+        }
     }
 
-    // Errors
-    if !status.scan_stats.errors.is_empty() {
-        report.push_str("## Errors Encountered\n\n");
-        for error in &status.scan_stats.errors {
-            report.push_str(&format!("- {}\n", error));
+    writeln!(out, "- **Next Action**: {}", project.next.description)?;
+    writeln!(out, "  - Command: `{}`", project.next.command)?;
+    writeln!(out, "  - Automated: {}", if project.next.automated { "Yes" } else { "No" })?;
+    for launch in &project.next.launch {
+        if launch.target.contains("://") {
+            writeln!(out, "  - Open in {}: <{}>", launch.agent.label(), launch.target)?;
+        } else {
+            writeln!(out, "  - Run in {}: `{}`", launch.agent.label(), launch.target)?;
         }
-        report.push('\n');
     }
-    
-    // Footer
-    report.push_str("---\n");
-    report.push_str("*Generated by SKM (Spec-Kit Manager)*\n");
-    
-    report
+
+    if !project.requires_human.is_empty() {
+        writeln!(out, "- **Requires Human**: {}", format_requirements(&project.requires_human))?;
+    }
+    if let Some(sla) = &project.review_sla {
+        writeln!(out, "- **Review SLA**: {}", sla.summary())?;
+    }
+
+    writeln!(out)
+}
+
+/// Projects whose last `skm check` failed
+fn write_broken_builds<'a, W: Write>(
+    projects: impl Iterator<Item = &'a Project>,
+    out: &mut W,
+) -> io::Result<()> {
+    let broken: Vec<_> = projects
+        .filter_map(|p| p.last_check.as_ref().filter(|run| !run.success).map(|run| (p, run)))
+        .collect();
+    if broken.is_empty() {
+        return Ok(());
+    }
+
+    writeln!(out, "## Broken Builds ({})\n", broken.len())?;
+    let rows: Vec<Vec<String>> = broken.into_iter()
+        .map(|(project, run)| vec![
+            project.display_name(),
+            format!("`{}`", run.command),
+            timefmt::minutes(run.at),
+            run.log.display().to_string(),
+        ])
+        .collect();
+    style::write_table(out, &["Project", "Command", "Checked", "Log"], &rows)?;
+    writeln!(out)
+}
+
+/// Projects waiting in Review past `[sla] review_days`, longest wait first
+fn write_sla_breaches<'a, W: Write>(
+    projects: impl Iterator<Item = &'a Project>,
+    out: &mut W,
+) -> io::Result<()> {
+    let mut breached: Vec<_> = projects
+        .filter_map(|p| p.review_sla.as_ref().filter(|sla| sla.breached).map(|sla| (p, sla)))
+        .collect();
+    if breached.is_empty() {
+        return Ok(());
+    }
+    breached.sort_by(|(a, x), (b, y)| y.waited_days.cmp(&x.waited_days).then_with(|| a.priority_order(b)));
+
+    writeln!(out, "## Review SLA Breaches ({})\n", breached.len())?;
+    let rows: Vec<Vec<String>> = breached.into_iter()
+        .map(|(project, sla)| vec![
+            project.display_name(),
+            timefmt::minutes(sla.since),
+            sla.waited_days.to_string(),
+            sla.limit_days.to_string(),
+            timefmt::minutes(sla.due),
+        ])
+        .collect();
+    style::write_table(out, &["Project", "In Review Since", "Business Days", "SLA", "Due"], &rows)?;
+    writeln!(out)
+}
+
+/// Projects with known dependency vulnerabilities, most vulnerable first
+fn write_security_advisories<'a, W: Write>(
+    projects: impl Iterator<Item = &'a Project>,
+    out: &mut W,
+) -> io::Result<()> {
+    let mut affected: Vec<_> = projects
+        .filter_map(|p| p.audit.as_ref().filter(|a| a.vulnerabilities > 0).map(|a| (p, a)))
+        .collect();
+    if affected.is_empty() {
+        return Ok(());
+    }
+    affected.sort_by(|(a, x), (b, y)| y.vulnerabilities.cmp(&x.vulnerabilities).then_with(|| a.priority_order(b)));
+
+    writeln!(out, "## Security Advisories ({})
+", affected.len())?;
+    let rows: Vec<Vec<String>> = affected.into_iter()
+        .map(|(project, audit)| vec![
+            project.display_name(),
+            audit.tool.clone(),
+            audit.vulnerabilities.to_string(),
+            truncate(&audit.advisories.join(", "), 60),
+        ])
+        .collect();
+    style::write_table(out, &["Project", "Tool", "Vulnerabilities", "Advisories"], &rows)?;
+    writeln!(out)
+}
+
+/// Projects × risk signals, with a totals row and the signals shared by
+/// half the portfolio or more called out
+fn write_risk_heat_map<W: Write>(map: &RiskHeatMap<'_>, out: &mut W) -> io::Result<()> {
+    if map.rows.is_empty() {
+        return Ok(());
+    }
+
+    writeln!(out, "## Risk Heat Map\n")?;
+    for (signal, total) in map.systemic() {
+        writeln!(out, "- {} {} of {} projects have {}", style::icon("⚠️", "Systemic:"), total, map.projects, signal.description())?;
+    }
+    if !map.systemic().is_empty() {
+        writeln!(out)?;
+    }
+
+    let mut headers = vec!["Project"];
+    headers.extend(RiskSignal::ALL.iter().map(RiskSignal::label));
+    let mut rows: Vec<Vec<String>> = map.rows.iter()
+        .map(|(project, cells)| {
+            let mut row = vec![project.display_name()];
+            row.extend(cells.iter().map(|hit| if *hit { style::icon("🟥", "yes") } else { "" }.to_string()));
+            row
+        })
+        .collect();
+    let mut totals = vec![format!("**All {}**", map.projects)];
+    totals.extend(RiskSignal::ALL.iter().zip(map.totals)
+        .map(|(signal, total)| format!("{} ({:.0}%)", total, map.share(*signal) * 100.0)));
+    rows.push(totals);
+    style::write_table(out, &headers, &rows)?;
+    writeln!(out)
+}
+
+fn write_errors<W: Write>(status: &PortfolioStatus, out: &mut W) -> io::Result<()> {
+    if status.scan_stats.errors.is_empty() {
+        return Ok(());
+    }
+
+    writeln!(out, "## Errors Encountered\n")?;
+    for error in &status.scan_stats.errors {
+        writeln!(out, "- [{}/{}] {}", error.phase, error.kind, error)?;
+    }
+    writeln!(out)
 }
 
 /// Save the markdown report to a file
 pub fn save_markdown_report(status: &PortfolioStatus, path: &Path) -> Result<()> {
-    let report = generate_markdown_report(status);
-    
     // Ensure parent directory exists
     if let Some(parent) = path.parent() {
         fs::create_dir_all(parent)?;
     }
-    
-    fs::write(path, report)?;
+
+    let mut out = BufWriter::new(fs::File::create(path)?);
+    write_markdown_report(status, &mut out)?;
+    out.flush()?;
     Ok(())
 }
 
-fn format_requirements(reqs: &[HumanRequirement]) -> String {
+/// Principle names, inherited ones marked
+pub fn format_principles(principles: &[Principle]) -> String {
+    principles.iter()
+        .map(|p| if p.inherited { format!("{} (portfolio)", p.name) } else { p.name.clone() })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// `field: previous → current` for each field that differed between scans
+pub fn format_unstable(fields: &[UnstableField]) -> String {
+    fields.iter()
+        .map(|f| format!("{}: {} → {}", f.field, f.previous, f.current))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// ` (spec.md, plan.md)`: the feature's artifact files, or nothing without any
+fn format_feature_files(feature: &FeatureStatus) -> String {
+    let files: Vec<String> = [&feature.artifacts.spec, &feature.artifacts.plan, &feature.artifacts.tasks]
+        .into_iter()
+        .flatten()
+        .filter_map(|file| file.path.file_name().map(|name| name.to_string_lossy().into_owned()))
+        .collect();
+    if files.is_empty() { String::new() } else { format!(" ({})", files.join(", ")) }
+}
+
+/// `owner/name: 3 open PRs (1 failing checks, 2 review requests), 5 open issues`,
+/// or the repository and why it could not be queried
+pub fn format_github(github: &GitHubStatus) -> String {
+    if let Some(error) = &github.error {
+        return format!("{}: {}", github.repo, error);
+    }
+    let mut details = Vec::new();
+    if github.failing_checks > 0 {
+        details.push(format!("{} failing checks", github.failing_checks));
+    }
+    if github.review_requests > 0 {
+        details.push(format!("{} review requests", github.review_requests));
+    }
+    format!("{}: {} open PRs{}, {} open issues", github.repo, github.open_prs,
+        if details.is_empty() { String::new() } else { format!(" ({})", details.join(", ")) },
+        github.open_issues)
+}
+
+/// `field=value` pairs in field order, with strings unquoted
+pub fn format_custom_fields(custom: &BTreeMap<String, serde_json::Value>) -> String {
+    custom.iter()
+        .map(|(field, value)| match value {
+            serde_json::Value::String(s) => format!("{}={}", field, s),
+            other => format!("{}={}", field, other),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+pub(crate) fn format_requirements(reqs: &[HumanRequirement]) -> String {
     reqs.iter()
         .map(|r| format!("{:?}", r))
         .collect::<Vec<_>>()
@@ -180,4 +453,4 @@ fn truncate(s: &str, max_len: usize) -> String {
     } else {
         format!("{}...", &s[..max_len-3])
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,56 @@
+use std::fmt::Write;
+use chrono::{DateTime, Utc};
+use crate::{PortfolioStatus, Project};
+
+/// A project's value of a gauge, given the current time
+type Gauge = fn(&Project, DateTime<Utc>) -> f64;
+
+/// Per-project gauges: name, help text and value
+const PROJECT_GAUGES: &[(&str, &str, Gauge)] = &[
+    ("skm_project_priority", "Priority score (0-100)", |p, _| p.priority),
+    ("skm_project_tasks_total", "Tasks in tasks.md", |p, _| p.tasks.total as f64),
+    ("skm_project_tasks_completed", "Completed tasks", |p, _| p.tasks.completed as f64),
+    ("skm_project_tasks_blocked", "Blocked tasks", |p, _| p.tasks.blocked as f64),
+    ("skm_project_staleness_days", "Days since the project was last active", |p, now| {
+        (now - p.updated).num_seconds().max(0) as f64 / 86_400.0
+    }),
+    ("skm_project_needs_attention", "1 when priority is above the attention threshold", |p, _| {
+        if p.needs_attention { 1.0 } else { 0.0 }
+    }),
+];
+
+/// The portfolio in the Prometheus text exposition format (0.0.4)
+///
+/// Project series are labeled with `project` (the display name) and
+/// `stage`; staleness is measured from `now`.
+pub fn write_metrics(portfolio: &PortfolioStatus, now: DateTime<Utc>, out: &mut String) {
+    for (name, help, value) in PROJECT_GAUGES {
+        header(out, name, help);
+        for project in &portfolio.projects {
+            let _ = writeln!(out, "{}{{project=\"{}\",stage=\"{}\"}} {}",
+                name, escape(&project.display_name()), project.stage.as_str(), value(project, now));
+        }
+    }
+
+    header(out, "skm_projects", "Projects per stage");
+    for stage in &portfolio.summary.ladder() {
+        let count = portfolio.projects.iter().filter(|p| p.stage == *stage).count();
+        let _ = writeln!(out, "skm_projects{{stage=\"{}\"}} {}", stage.as_str(), count);
+    }
+    header(out, "skm_projects_needs_attention", "Projects above the attention threshold");
+    let _ = writeln!(out, "skm_projects_needs_attention {}", portfolio.summary.needs_attention);
+    header(out, "skm_scan_errors", "Errors in the last scan");
+    let _ = writeln!(out, "skm_scan_errors {}", portfolio.scan_stats.errors.len());
+    header(out, "skm_last_scan_timestamp_seconds", "When the portfolio was last scanned");
+    let _ = writeln!(out, "skm_last_scan_timestamp_seconds {}", portfolio.generated_at.timestamp());
+}
+
+fn header(out: &mut String, name: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} gauge", name);
+}
+
+/// Escape a label value: backslash, double quote and newline
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
@@ -1,6 +1,27 @@
+pub use skm_core::reporter::{changes, style};
+
 pub mod markdown;
 pub mod json;
+pub mod html;
 pub mod table;
 pub mod digest;
+pub mod prompt;
+pub mod diff;
+pub mod release_notes;
+pub mod site;
+pub mod metrics;
+pub mod explain;
+pub mod csv;
 
-pub use markdown::{generate_markdown_report, save_markdown_report};
\ No newline at end of file
+pub use markdown::{format_custom_fields, format_github, format_principles, format_unstable, generate_markdown_report, save_markdown_report, write_markdown_report, write_markdown_view};
+pub use digest::{DigestMode, DigestState, generate_digest, save_digest, write_digest, write_sprint_table, write_time_to_address};
+pub use prompt::{DEFAULT_PROMPT_FORMAT, find_project, render_prompt};
+pub use html::write_html_view;
+pub use site::{SiteSummary, publish_site};
+pub use metrics::write_metrics;
+pub use explain::write_priority_explanation;
+pub use table::{DEFAULT_COLUMNS, TableColumn, TableOptions, TableSort, write_table_view};
+pub use csv::{CSV_COLUMNS, Delimited, write_delimited_view};
+pub use diff::write_unified_diff;
+pub use json::{COMPLETION_FEED_VERSION, CompletionFeed, completion_feed};
+pub use changes::{ProjectChange, portfolio_changes};
@@ -0,0 +1,50 @@
+use std::path::{Path, PathBuf};
+use crate::{PortfolioStatus, Project};
+use crate::analyzer::priority::PriorityTier;
+
+/// Default segment, e.g. `implement 3 open 🟡`
+pub const DEFAULT_PROMPT_FORMAT: &str = "{stage} {open} open {tier_emoji}";
+
+/// Find the cached project whose directory contains `dir`
+///
+/// Project paths in the cache are relative to wherever `skm scan` ran, so
+/// they are tried as-is, relative to the scan root, and by directory name
+/// under the scan root. The deepest match wins for nested projects.
+pub fn find_project<'a>(status: &'a PortfolioStatus, root: &Path, dir: &Path) -> Option<&'a Project> {
+    status.projects.iter()
+        .filter_map(|project| {
+            resolve_project_path(root, &project.path)
+                .filter(|path| dir.starts_with(path))
+                .map(|path| (path.components().count(), project))
+        })
+        .max_by_key(|(depth, _)| *depth)
+        .map(|(_, project)| project)
+}
+
+fn resolve_project_path(root: &Path, path: &Path) -> Option<PathBuf> {
+    let candidates = [
+        Some(path.to_path_buf()),
+        Some(root.join(path)),
+        path.file_name().map(|name| root.join(name)),
+    ];
+    candidates.into_iter()
+        .flatten()
+        .find_map(|candidate| candidate.canonicalize().ok())
+}
+
+/// Render a prompt segment for a project
+///
+/// Placeholders: `{id}`, `{stage}`, `{open}`, `{done}`, `{total}`,
+/// `{priority}`, `{tier}` and `{tier_emoji}`.
+pub fn render_prompt(project: &Project, format: &str) -> String {
+    let tier = PriorityTier::from_priority(project.priority);
+    format
+        .replace("{id}", &project.id)
+        .replace("{stage}", project.stage.as_str())
+        .replace("{open}", &(project.tasks.total.saturating_sub(project.tasks.completed)).to_string())
+        .replace("{done}", &project.tasks.completed.to_string())
+        .replace("{total}", &project.tasks.total.to_string())
+        .replace("{priority}", &format!("{:.0}", project.priority))
+        .replace("{tier}", tier.as_str())
+        .replace("{tier_emoji}", tier.symbol())
+}
@@ -0,0 +1,280 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use crate::{Result, SKMError};
+use chrono::NaiveDate;
+use regex::Regex;
+use crate::scanner::parser;
+use super::style;
+
+/// A commit message header in Conventional Commits form, `type(scope)!: subject`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConventionalCommit {
+    pub kind: String,
+    pub scope: Option<String>,
+    pub breaking: bool,
+    pub subject: String,
+}
+
+static HEADER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(\w+)(?:\(([^)]*)\))?(!)?:\s*(.+)$").unwrap()
+});
+
+/// Parse a full commit message; `None` when its first line is not conventional
+pub fn parse_conventional(message: &str) -> Option<ConventionalCommit> {
+    let caps = HEADER.captures(message.lines().next()?.trim())?;
+    Some(ConventionalCommit {
+        kind: caps[1].to_lowercase(),
+        scope: caps.get(2).map(|m| m.as_str().to_string()).filter(|s| !s.is_empty()),
+        breaking: caps.get(3).is_some() || message.contains("BREAKING CHANGE"),
+        subject: caps[4].trim().to_string(),
+    })
+}
+
+/// Commit types that are housekeeping and left out of release notes
+#[cfg(feature = "git")]
+const HIDDEN_TYPES: &[&str] = &["chore", "ci", "build", "style", "test"];
+
+/// A task marked done since the last tag
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompletedTask {
+    /// Feature directory the tasks file belongs to, e.g. `001-login`
+    pub feature: Option<String>,
+    pub text: String,
+}
+
+/// Everything a release notes draft is built from
+#[derive(Debug, Clone, Default)]
+pub struct ReleaseNotes {
+    /// Most recent tag reachable from HEAD; `None` covers the whole history
+    pub since_tag: Option<String>,
+    pub commits: Vec<ConventionalCommit>,
+    /// Subjects of commits not in conventional form
+    pub other_commits: Vec<String>,
+    pub tasks: Vec<CompletedTask>,
+    /// Acceptance criteria of the features with completed tasks
+    pub acceptance: Vec<(String, Vec<String>)>,
+}
+
+/// Gather commits and completed tasks since the last tag, with the
+/// acceptance criteria of the features they belong to
+#[cfg(feature = "git")]
+pub fn collect_release_notes(project: &Path) -> Result<ReleaseNotes> {
+    let mut notes = ReleaseNotes::default();
+    let repo = git2::Repository::open(project).ok();
+    let tag = repo.as_ref().and_then(last_tag);
+    notes.since_tag = tag.as_ref().map(|(name, _)| name.clone());
+
+    if let Some(repo) = &repo
+        && let Ok(head) = repo.head().and_then(|h| h.peel_to_commit())
+    {
+        let mut walk = repo.revwalk()?;
+        walk.push(head.id())?;
+        if let Some((_, tagged)) = &tag {
+            walk.hide(*tagged)?;
+        }
+        for oid in walk {
+            let commit = repo.find_commit(oid?)?;
+            if commit.parent_count() > 1 {
+                continue;
+            }
+            let message = commit.message().unwrap_or_default();
+            match parse_conventional(message) {
+                Some(c) if HIDDEN_TYPES.contains(&c.kind.as_str()) && !c.breaking => {}
+                Some(c) => notes.commits.push(c),
+                None => notes.other_commits.push(message.lines().next().unwrap_or_default().trim().to_string()),
+            }
+        }
+    }
+
+    let tagged_tree = match (&repo, &tag) {
+        (Some(repo), Some((_, oid))) => Some(repo.find_commit(*oid)?.tree()?),
+        _ => None,
+    };
+    collect_tasks(project, &mut notes, |tasks_file| match (&repo, &tagged_tree) {
+        (Some(repo), Some(tree)) => repo.workdir()
+            .and_then(|workdir| tasks_file.canonicalize().ok()?.strip_prefix(workdir.canonicalize().ok()?).ok().map(Path::to_path_buf))
+            .and_then(|relative| tree.get_path(&relative).ok())
+            .and_then(|entry| repo.find_blob(entry.id()).ok())
+            .map(|blob| String::from_utf8_lossy(blob.content()).into_owned())
+            .unwrap_or_default(),
+        _ => String::new(),
+    });
+    Ok(notes)
+}
+
+/// Without the `git` feature there are no commits or tags, so every
+/// completed task counts as new
+#[cfg(not(feature = "git"))]
+pub fn collect_release_notes(project: &Path) -> Result<ReleaseNotes> {
+    let mut notes = ReleaseNotes::default();
+    collect_tasks(project, &mut notes, |_| String::new());
+    Ok(notes)
+}
+
+/// Add the tasks done now but not in `previous` (the tasks file as of the
+/// last tag), and the acceptance criteria of their features
+fn collect_tasks(project: &Path, notes: &mut ReleaseNotes, previous: impl Fn(&Path) -> String) {
+    let mut features = BTreeSet::new();
+    for tasks_file in tasks_files(project) {
+        let current = fs::read_to_string(&tasks_file).unwrap_or_default();
+        let previous = previous(&tasks_file);
+        let done_before: BTreeSet<String> = parser::parse_task_items(&previous).into_iter()
+            .filter(|t| t.done)
+            .map(|t| t.text)
+            .collect();
+
+        let feature = tasks_file.parent()
+            .and_then(|dir| dir.file_name())
+            .map(|name| name.to_string_lossy().into_owned())
+            .filter(|name| name.chars().take(3).all(|c| c.is_ascii_digit()));
+        for task in parser::parse_task_items(&current) {
+            if task.done && !done_before.contains(&task.text) {
+                if let Some(feature) = &feature {
+                    features.insert(feature.clone());
+                }
+                notes.tasks.push(CompletedTask { feature: feature.clone(), text: task.text });
+            }
+        }
+    }
+
+    for feature in features {
+        let spec = ["specs", ".specify"].iter()
+            .map(|dir| project.join(dir).join(&feature).join("spec.md"))
+            .find(|path| path.is_file());
+        let criteria = spec.and_then(|path| fs::read_to_string(path).ok())
+            .map(|content| parser::extract_acceptance_criteria(&content))
+            .unwrap_or_default();
+        if !criteria.is_empty() {
+            notes.acceptance.push((feature, criteria));
+        }
+    }
+}
+
+/// Tag with the newest commit among those reachable from HEAD
+#[cfg(feature = "git")]
+fn last_tag(repo: &git2::Repository) -> Option<(String, git2::Oid)> {
+    let head = repo.head().ok()?.peel_to_commit().ok()?;
+    let names = repo.tag_names(None).ok()?;
+    names.iter()
+        .flatten()
+        .filter_map(|name| {
+            let commit = repo.revparse_single(&format!("refs/tags/{}", name)).ok()?.peel_to_commit().ok()?;
+            let reachable = commit.id() == head.id()
+                || repo.graph_descendant_of(head.id(), commit.id()).unwrap_or(false);
+            reachable.then(|| (name.to_string(), commit.id(), commit.time().seconds()))
+        })
+        .max_by_key(|(_, _, time)| *time)
+        .map(|(name, oid, _)| (name, oid))
+}
+
+/// The project's tasks files: feature directories first, then top-level ones
+fn tasks_files(project: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    for dir in [project.join("specs"), project.join(".specify")] {
+        for feature in parser::list_feature_dirs(&dir) {
+            files.push(feature.join("tasks.md"));
+        }
+        files.push(dir.join("tasks.md"));
+    }
+    files.retain(|f| f.is_file());
+    files
+}
+
+/// Render a CHANGELOG entry for `version` (or "Unreleased")
+pub fn render_release_notes(notes: &ReleaseNotes, version: Option<&str>, date: NaiveDate) -> String {
+    let mut out = format!("## [{}] - {}\n", version.unwrap_or("Unreleased"), date.format("%Y-%m-%d"));
+    let commit_line = |c: &ConventionalCommit| match &c.scope {
+        Some(scope) => format!("- **{}**: {}\n", scope, c.subject),
+        None => format!("- {}\n", c.subject),
+    };
+    let mut section = |title: &str, lines: Vec<String>| {
+        if !lines.is_empty() {
+            out.push_str(&format!("\n### {}\n\n{}", title, lines.concat()));
+        }
+    };
+
+    section(&style::decorate("⚠", "Breaking Changes"), notes.commits.iter().filter(|c| c.breaking).map(commit_line).collect());
+    section("Features", notes.commits.iter().filter(|c| c.kind == "feat" && !c.breaking).map(commit_line).collect());
+    section("Bug Fixes", notes.commits.iter().filter(|c| c.kind == "fix" && !c.breaking).map(commit_line).collect());
+    section("Performance", notes.commits.iter().filter(|c| c.kind == "perf" && !c.breaking).map(commit_line).collect());
+    section("Other Changes", notes.commits.iter()
+        .filter(|c| !c.breaking && !["feat", "fix", "perf"].contains(&c.kind.as_str()))
+        .map(commit_line)
+        .chain(notes.other_commits.iter().map(|subject| format!("- {}\n", subject)))
+        .collect());
+    section("Completed Tasks", notes.tasks.iter().map(|t| match &t.feature {
+        Some(feature) => format!("- {} ({})\n", t.text, feature),
+        None => format!("- {}\n", t.text),
+    }).collect());
+
+    if !notes.acceptance.is_empty() {
+        out.push_str("\n### Acceptance Criteria\n");
+        for (feature, criteria) in &notes.acceptance {
+            out.push_str(&format!("\n#### {}\n\n", feature));
+            for criterion in criteria {
+                out.push_str(&format!("- {}\n", criterion));
+            }
+        }
+    }
+    if out.lines().count() == 1 {
+        out.push_str("\nNo changes since the last release.\n");
+    }
+    out
+}
+
+/// Insert an entry into CHANGELOG.md above the newest release, creating the file if needed
+pub fn prepend_changelog_entry(changelog: &str, entry: &str) -> String {
+    if changelog.trim().is_empty() {
+        return format!("# Changelog\n\n{}", entry);
+    }
+    let lines: Vec<&str> = changelog.lines().collect();
+    match lines.iter().position(|line| line.starts_with("## ")) {
+        Some(index) => format!("{}\n{}\n{}\n", lines[..index].join("\n"), entry, lines[index..].join("\n")),
+        None => format!("{}\n\n{}", changelog.trim_end(), entry),
+    }
+}
+
+/// Write the entry to CHANGELOG.md on a new branch and commit it for review
+///
+/// Refuses to run with uncommitted changes, so the commit holds only the
+/// changelog. Returns the branch name.
+#[cfg(feature = "git")]
+pub fn write_changelog_branch(project: &Path, entry: &str, branch: &str, message: &str) -> Result<String> {
+    let repo = git2::Repository::open(project)
+        .map_err(|_| integration_error(format!("{} is not a git repository", project.display())))?;
+    let mut options = git2::StatusOptions::new();
+    options.include_untracked(false);
+    if !repo.statuses(Some(&mut options))?.is_empty() {
+        return Err(integration_error(format!("{} has uncommitted changes; commit or stash them first", project.display())));
+    }
+    if repo.find_branch(branch, git2::BranchType::Local).is_ok() {
+        return Err(integration_error(format!("Branch {} already exists", branch)));
+    }
+    crate::scaffold::checkout_feature_branch(project, branch)?;
+
+    let workdir = repo.workdir().ok_or_else(|| integration_error(format!("{} has no working tree", project.display())))?;
+    let path = workdir.join("CHANGELOG.md");
+    let updated = prepend_changelog_entry(&fs::read_to_string(&path).unwrap_or_default(), entry);
+    fs::write(&path, updated)?;
+
+    let mut index = repo.index()?;
+    index.add_path(Path::new("CHANGELOG.md"))?;
+    index.write()?;
+    let tree = repo.find_tree(index.write_tree()?)?;
+    let signature = repo.signature().or_else(|_| git2::Signature::now("skm", "skm@localhost"))?;
+    let parent = repo.head()?.peel_to_commit()?;
+    repo.commit(Some("HEAD"), &signature, &signature, message, &tree, &[&parent])?;
+    Ok(branch.to_string())
+}
+
+#[cfg(not(feature = "git"))]
+pub fn write_changelog_branch(_project: &Path, _entry: &str, _branch: &str, _message: &str) -> Result<String> {
+    Err(SKMError::FeatureDisabled { feature: "git" })
+}
+
+#[cfg(feature = "git")]
+fn integration_error(message: String) -> SKMError {
+    SKMError::IntegrationError { message }
+}
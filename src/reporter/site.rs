@@ -0,0 +1,243 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+use chrono::NaiveDate;
+use crate::Result;
+use crate::{PortfolioStatus, Project, Stage};
+use crate::analyzer::priority::PriorityTier;
+use crate::timefmt;
+use crate::view::PortfolioView;
+use super::html::{SiteIndex, escape, percent, write_dashboard, write_head, write_line_chart};
+use super::markdown::{format_github, format_requirements};
+
+/// Directory of the per-project pages inside the site
+const PROJECTS_DIR: &str = "projects";
+
+/// Longest page name kept from an id, well below file name limits
+const MAX_PAGE_NAME: usize = 80;
+
+/// What publishing did to the output directory
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SiteSummary {
+    pub written: usize,
+    pub unchanged: usize,
+    /// Pages of projects no longer in the portfolio
+    pub removed: usize,
+}
+
+/// Render a static site for the projects in a view: `index.html` with the
+/// dashboard and history charts, and a page per project under `projects/`
+///
+/// `log` is the root's history log; charts show one point per day, the last
+/// scan of that day, ending with the current status. Pages only change when
+/// the data does, files whose content is unchanged are not rewritten, and
+/// pages of projects that left the portfolio are removed, so the site can be
+/// regenerated on a schedule and committed as is.
+pub fn publish_site(view: &PortfolioView<'_>, log: &[PortfolioStatus], out: &Path) -> Result<SiteSummary> {
+    let names: HashSet<String> = view.iter().map(|p| p.display_name()).collect();
+    let days = daily(log, view.status);
+    let pages = page_names(view);
+
+    let completion: Vec<(String, f64)> = days.iter()
+        .map(|(day, status)| {
+            let projects = || status.projects.iter().filter(|p| names.contains(&p.display_name()));
+            let completed = projects().map(|p| p.tasks.completed).sum();
+            let total = projects().map(|p| p.tasks.total).sum();
+            (day.to_string(), percent(completed, total))
+        })
+        .collect();
+    let attention: Vec<(String, f64)> = days.iter()
+        .map(|(day, status)| {
+            let count = status.projects.iter().filter(|p| names.contains(&p.display_name()) && p.needs_attention).count();
+            (day.to_string(), count as f64)
+        })
+        .collect();
+
+    let mut summary = SiteSummary::default();
+    fs::create_dir_all(out.join(PROJECTS_DIR))?;
+    let mut index = Vec::new();
+    write_dashboard(view, Some(&SiteIndex { pages: &pages, completion: &completion, attention: &attention }), &mut index)?;
+    write_if_changed(&out.join("index.html"), &index, &mut summary)?;
+    // Serve the files as they are on GitHub Pages
+    write_if_changed(&out.join(".nojekyll"), b"", &mut summary)?;
+
+    let mut history_log: Vec<&PortfolioStatus> = log.iter().collect();
+    history_log.push(view.status);
+    for project in view.iter() {
+        let mut page = Vec::new();
+        let name = project.display_name();
+        write_project_page(project, &days, &stage_changes(&name, &history_log), view.status, &mut page)?;
+        write_if_changed(&out.join(&pages[&name]), &page, &mut summary)?;
+    }
+
+    let current: HashSet<&str> = pages.values().filter_map(|page| page.strip_prefix("projects/")).collect();
+    for entry in fs::read_dir(out.join(PROJECTS_DIR))?.filter_map(|e| e.ok()) {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if name.ends_with(".html") && !current.contains(name.as_str()) {
+            fs::remove_file(entry.path())?;
+            summary.removed += 1;
+        }
+    }
+    Ok(summary)
+}
+
+/// The last logged status of each day, ending with `current`
+fn daily<'a>(log: &'a [PortfolioStatus], current: &'a PortfolioStatus) -> Vec<(NaiveDate, &'a PortfolioStatus)> {
+    let mut days = BTreeMap::new();
+    let mut entries: Vec<&PortfolioStatus> = log.iter().filter(|s| s.generated_at < current.generated_at).collect();
+    entries.sort_by_key(|status| status.generated_at);
+    entries.push(current);
+    for status in entries {
+        days.insert(timefmt::display_zone().date(status.generated_at), status);
+    }
+    days.into_iter().collect()
+}
+
+/// Page of each project relative to the site root, e.g. `projects/web-app.html`,
+/// keyed by its display name
+///
+/// Display names (`root/id` in a combined portfolio, so projects with the
+/// same id under different roots get their own pages) are reduced to
+/// letters, digits, `-` and `_` and cut to [`MAX_PAGE_NAME`] characters;
+/// names that reduce to the same page get a numeric suffix, assigned in
+/// name order.
+fn page_names(view: &PortfolioView<'_>) -> HashMap<String, String> {
+    let mut names: Vec<String> = view.iter().map(|p| p.display_name()).collect();
+    names.sort_unstable();
+    let mut taken = HashSet::new();
+    let mut pages = HashMap::new();
+    for display_name in names {
+        let base: String = display_name.chars()
+            .take(MAX_PAGE_NAME)
+            .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c.to_ascii_lowercase() } else { '-' })
+            .collect();
+        let base = if base.trim_matches('-').is_empty() { "project".to_string() } else { base.trim_matches('-').to_string() };
+        let mut name = base.clone();
+        let mut suffix = 2;
+        while !taken.insert(name.clone()) {
+            name = format!("{}-{}", base, suffix);
+            suffix += 1;
+        }
+        pages.insert(display_name, format!("{}/{}.html", PROJECTS_DIR, name));
+    }
+    pages
+}
+
+/// When the project entered each stage it was logged in, oldest first
+fn stage_changes(display_name: &str, log: &[&PortfolioStatus]) -> Vec<(String, Stage)> {
+    let mut changes: Vec<(String, Stage)> = Vec::new();
+    let mut previous: Option<&Stage> = None;
+    for status in log {
+        let Some(project) = status.projects.iter().find(|p| p.display_name() == display_name) else {
+            continue;
+        };
+        if previous != Some(&project.stage) {
+            changes.push((timefmt::minutes(status.generated_at), project.stage.clone()));
+        }
+        previous = Some(&project.stage);
+    }
+    changes
+}
+
+fn write_project_page<W: Write>(
+    project: &Project,
+    days: &[(NaiveDate, &PortfolioStatus)],
+    stages: &[(String, Stage)],
+    status: &PortfolioStatus,
+    out: &mut W,
+) -> io::Result<()> {
+    let name = project.display_name();
+    let tier = PriorityTier::from_priority(project.priority);
+    let done = percent(project.tasks.completed, project.tasks.total);
+
+    write_head(out, &format!("{} - SKM", name))?;
+    writeln!(out, "<p><a href=\"../index.html\">&larr; Portfolio</a></p>")?;
+    writeln!(out, "<h1>{}</h1>", escape(&name))?;
+    writeln!(out, "<p class=\"generated\"><span class=\"tier\">{} priority</span> {:.1} &middot; {:?} &middot; updated {}</p>",
+        tier.as_str(), project.priority, project.stage, escape(&timefmt::minutes(project.updated)))?;
+    writeln!(out, "<div class=\"bar\" role=\"progressbar\" aria-label=\"Tasks completed\" aria-valuemin=\"0\" aria-valuemax=\"100\" aria-valuenow=\"{:.0}\"><div style=\"width: {:.0}%\"></div></div>",
+        done, done)?;
+    writeln!(out, "<p>{}/{} tasks ({:.0}%){}</p>", project.tasks.completed, project.tasks.total, done,
+        if project.tasks.blocked > 0 { format!(", {} blocked", project.tasks.blocked) } else { String::new() })?;
+
+    writeln!(out, "<ul>")?;
+    writeln!(out, "<li>Next: {} <code>{}</code></li>", escape(&project.next.description), escape(&project.next.command))?;
+    if !project.requires_human.is_empty() {
+        writeln!(out, "<li>Requires human: {}</li>", escape(&format_requirements(&project.requires_human)))?;
+    }
+    if let Some(root) = &project.root {
+        writeln!(out, "<li>Root: {}</li>", escape(root))?;
+    }
+    if let Some(url) = &project.remote {
+        writeln!(out, "<li>Remote: {}</li>", escape(url))?;
+    }
+    writeln!(out, "<li>Type: {:?}</li>", project.project_type)?;
+    if project.git.is_repo {
+        writeln!(out, "<li>Git: {} ({})</li>",
+            escape(project.git.branch.as_deref().unwrap_or("unknown")),
+            if project.git.clean { "clean" } else { "uncommitted changes" })?;
+    }
+    if let Some(github) = &project.git.github {
+        writeln!(out, "<li>GitHub: {}</li>", escape(&format_github(github)))?;
+    }
+    if let Some(check) = project.last_check.as_ref().filter(|check| !check.success) {
+        writeln!(out, "<li>Build failing: <code>{}</code></li>", escape(&check.command))?;
+    }
+    if let Some(freshness) = project.freshness.as_ref().filter(|f| f.major_behind > 0) {
+        writeln!(out, "<li>{}/{} dependencies a major version behind</li>", freshness.major_behind, freshness.direct)?;
+    }
+    writeln!(out, "<li>License: {}</li>", escape(&project.license.compliance()))?;
+    writeln!(out, "</ul>")?;
+
+    if !project.features.is_empty() {
+        writeln!(out, "<h2>Features</h2>")?;
+        writeln!(out, "<table>\n<tr><th>Feature</th><th>Stage</th><th>Tasks</th></tr>")?;
+        for feature in &project.features {
+            writeln!(out, "<tr><td>{}</td><td>{}</td><td>{}/{} ({:.0}%)</td></tr>", escape(&feature.id), feature.stage,
+                feature.tasks.completed, feature.tasks.total, percent(feature.tasks.completed, feature.tasks.total))?;
+        }
+        writeln!(out, "</table>")?;
+    }
+
+    if !project.open_questions.is_empty() {
+        writeln!(out, "<h2>Open questions</h2>\n<ul>")?;
+        for question in &project.open_questions {
+            writeln!(out, "<li>{} (line {}): {}</li>", question.source, question.line, escape(&question.text))?;
+        }
+        writeln!(out, "</ul>")?;
+    }
+
+    let logged: Vec<(String, &Project)> = days.iter()
+        .filter_map(|(day, status)| status.projects.iter().find(|p| p.id == project.id).map(|p| (day.to_string(), p)))
+        .collect();
+    let completion: Vec<(String, f64)> = logged.iter()
+        .map(|(day, p)| (day.clone(), percent(p.tasks.completed, p.tasks.total)))
+        .collect();
+    let priority: Vec<(String, f64)> = logged.iter().map(|(day, p)| (day.clone(), p.priority)).collect();
+    writeln!(out, "<section class=\"charts\">")?;
+    write_line_chart(out, "completion", "Tasks completed (%)", &completion, 100.0)?;
+    write_line_chart(out, "priority", "Priority", &priority, 100.0)?;
+    writeln!(out, "</section>")?;
+
+    if !stages.is_empty() {
+        writeln!(out, "<h2>Stage history</h2>\n<ul>")?;
+        for (at, stage) in stages {
+            writeln!(out, "<li>{}: {}</li>", escape(at), stage)?;
+        }
+        writeln!(out, "</ul>")?;
+    }
+
+    writeln!(out, "<footer>Generated {} by SKM (Spec-Kit Manager)</footer>", escape(&timefmt::seconds(status.generated_at)))?;
+    writeln!(out, "</body>\n</html>")
+}
+
+fn write_if_changed(path: &Path, content: &[u8], summary: &mut SiteSummary) -> io::Result<()> {
+    if fs::read(path).is_ok_and(|existing| existing == content) {
+        summary.unchanged += 1;
+        return Ok(());
+    }
+    fs::write(path, content)?;
+    summary.written += 1;
+    Ok(())
+}
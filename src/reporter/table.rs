@@ -1 +1,294 @@
-// Placeholder for src/reporter/table.rs
+use std::cmp::Ordering;
+use std::io::{self, Write};
+use crate::{Project, SKMError};
+use crate::analyzer::priority::PriorityTier;
+use crate::timefmt;
+use crate::view::PortfolioView;
+use super::markdown::format_requirements;
+use super::style::{self, Color};
+
+/// A column of the terminal table, selected with `--columns`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableColumn {
+    Project,
+    Root,
+    Path,
+    Stage,
+    Priority,
+    Tasks,
+    Progress,
+    Blocked,
+    Branch,
+    Git,
+    Human,
+    Attention,
+    Updated,
+    Next,
+}
+
+/// Columns shown without `--columns`
+pub const DEFAULT_COLUMNS: [TableColumn; 6] = [
+    TableColumn::Priority,
+    TableColumn::Project,
+    TableColumn::Stage,
+    TableColumn::Tasks,
+    TableColumn::Updated,
+    TableColumn::Next,
+];
+
+/// Cells longer than this are cut with an ellipsis
+const MAX_WIDTH: usize = 48;
+
+impl TableColumn {
+    pub const ALL: [TableColumn; 14] = [
+        TableColumn::Project,
+        TableColumn::Root,
+        TableColumn::Path,
+        TableColumn::Stage,
+        TableColumn::Priority,
+        TableColumn::Tasks,
+        TableColumn::Progress,
+        TableColumn::Blocked,
+        TableColumn::Branch,
+        TableColumn::Git,
+        TableColumn::Human,
+        TableColumn::Attention,
+        TableColumn::Updated,
+        TableColumn::Next,
+    ];
+
+    /// Name used by `--columns` and `--sort-by`
+    pub fn name(&self) -> &'static str {
+        match self {
+            TableColumn::Project => "project",
+            TableColumn::Root => "root",
+            TableColumn::Path => "path",
+            TableColumn::Stage => "stage",
+            TableColumn::Priority => "priority",
+            TableColumn::Tasks => "tasks",
+            TableColumn::Progress => "progress",
+            TableColumn::Blocked => "blocked",
+            TableColumn::Branch => "branch",
+            TableColumn::Git => "git",
+            TableColumn::Human => "human",
+            TableColumn::Attention => "attention",
+            TableColumn::Updated => "updated",
+            TableColumn::Next => "next",
+        }
+    }
+
+    fn header(&self) -> &'static str {
+        match self {
+            TableColumn::Project => "Project",
+            TableColumn::Root => "Root",
+            TableColumn::Path => "Path",
+            TableColumn::Stage => "Stage",
+            TableColumn::Priority => "Priority",
+            TableColumn::Tasks => "Tasks",
+            TableColumn::Progress => "Progress",
+            TableColumn::Blocked => "Blocked",
+            TableColumn::Branch => "Branch",
+            TableColumn::Git => "Git",
+            TableColumn::Human => "Human",
+            TableColumn::Attention => "Attention",
+            TableColumn::Updated => "Updated",
+            TableColumn::Next => "Next",
+        }
+    }
+
+    /// Numbers are right-aligned
+    fn numeric(&self) -> bool {
+        matches!(self, TableColumn::Priority | TableColumn::Tasks | TableColumn::Progress | TableColumn::Blocked)
+    }
+
+    /// Whether `--sort-by` on this column puts the largest value first
+    fn descending_by_default(&self) -> bool {
+        matches!(self, TableColumn::Priority | TableColumn::Tasks | TableColumn::Progress | TableColumn::Blocked
+            | TableColumn::Attention | TableColumn::Updated)
+    }
+
+    pub fn value(&self, project: &Project) -> String {
+        match self {
+            TableColumn::Project => project.display_name(),
+            TableColumn::Root => project.root.clone().unwrap_or_default(),
+            TableColumn::Path => project.path.display().to_string(),
+            TableColumn::Stage => project.stage.as_str().to_string(),
+            TableColumn::Priority => format!("{:.1}", project.priority),
+            TableColumn::Tasks => format!("{}/{}", project.tasks.completed, project.tasks.total),
+            TableColumn::Progress => format!("{:.0}%", progress(project)),
+            TableColumn::Blocked => project.tasks.blocked.to_string(),
+            TableColumn::Branch => project.git.branch.clone().unwrap_or_default(),
+            TableColumn::Git if !project.git.is_repo => "none".to_string(),
+            TableColumn::Git if project.git.clean => "clean".to_string(),
+            TableColumn::Git => "dirty".to_string(),
+            TableColumn::Human => format_requirements(&project.requires_human),
+            TableColumn::Attention => if project.needs_attention { "yes" } else { "" }.to_string(),
+            TableColumn::Updated => timefmt::ago(project.updated),
+            TableColumn::Next => project.next.command.clone(),
+        }
+    }
+
+    /// Order of two projects by this column, smallest first
+    fn compare(&self, a: &Project, b: &Project) -> Ordering {
+        match self {
+            TableColumn::Stage => a.stage.cmp(&b.stage),
+            TableColumn::Priority => a.priority.total_cmp(&b.priority),
+            TableColumn::Tasks => (a.tasks.total - a.tasks.completed.min(a.tasks.total))
+                .cmp(&(b.tasks.total - b.tasks.completed.min(b.tasks.total))),
+            TableColumn::Progress => progress(a).total_cmp(&progress(b)),
+            TableColumn::Blocked => a.tasks.blocked.cmp(&b.tasks.blocked),
+            TableColumn::Attention => a.needs_attention.cmp(&b.needs_attention),
+            TableColumn::Updated => a.updated.cmp(&b.updated),
+            TableColumn::Human => a.requires_human.len().cmp(&b.requires_human.len()),
+            _ => self.value(a).cmp(&self.value(b)),
+        }
+    }
+
+    /// Priority cells are colored by tier
+    fn color(&self, project: &Project) -> Option<Color> {
+        match self {
+            TableColumn::Priority => Some(match PriorityTier::from_priority(project.priority) {
+                PriorityTier::High => Color::Red,
+                PriorityTier::Medium => Color::Yellow,
+                PriorityTier::Low => Color::Green,
+            }),
+            _ => None,
+        }
+    }
+
+    /// A comma-separated list such as `project,stage,priority`
+    pub fn parse_list(s: &str) -> crate::Result<Vec<TableColumn>> {
+        let columns: Vec<TableColumn> = s.split(',').map(str::trim).filter(|name| !name.is_empty())
+            .map(str::parse)
+            .collect::<crate::Result<_>>()?;
+        if columns.is_empty() {
+            return Err(SKMError::ConfigError { message: "No columns given".to_string() });
+        }
+        Ok(columns)
+    }
+}
+
+fn progress(project: &Project) -> f64 {
+    match project.tasks.total {
+        0 => 0.0,
+        total => project.tasks.completed as f64 / total as f64 * 100.0,
+    }
+}
+
+impl std::str::FromStr for TableColumn {
+    type Err = SKMError;
+
+    fn from_str(s: &str) -> crate::Result<Self> {
+        let name = s.trim().to_ascii_lowercase();
+        let name = match name.as_str() {
+            "id" | "name" => "project",
+            "human_needed" | "requires_human" => "human",
+            other => other,
+        };
+        TableColumn::ALL.into_iter().find(|column| column.name() == name).ok_or_else(|| SKMError::ConfigError {
+            message: format!("Unknown column: {} (expected one of {})", s,
+                TableColumn::ALL.map(|column| column.name()).join(", ")),
+        })
+    }
+}
+
+/// Row order: a column, in its natural direction unless `:asc` or `:desc` is given
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TableSort {
+    pub column: TableColumn,
+    pub descending: bool,
+}
+
+impl Default for TableSort {
+    fn default() -> Self {
+        TableSort { column: TableColumn::Priority, descending: true }
+    }
+}
+
+impl std::str::FromStr for TableSort {
+    type Err = SKMError;
+
+    /// `priority`, `stage:desc` or `updated:asc`
+    fn from_str(s: &str) -> crate::Result<Self> {
+        let (name, direction) = s.split_once(':').unwrap_or((s, ""));
+        let column: TableColumn = name.parse()?;
+        let descending = match direction.trim().to_ascii_lowercase().as_str() {
+            "" => column.descending_by_default(),
+            "asc" => false,
+            "desc" => true,
+            other => return Err(SKMError::ConfigError {
+                message: format!("Unknown sort direction: {} (expected asc or desc)", other),
+            }),
+        };
+        Ok(TableSort { column, descending })
+    }
+}
+
+/// What a table shows and how
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableOptions {
+    pub columns: Vec<TableColumn>,
+    pub sort: TableSort,
+    /// ANSI colors, for a terminal
+    pub color: bool,
+}
+
+impl Default for TableOptions {
+    fn default() -> Self {
+        TableOptions { columns: DEFAULT_COLUMNS.to_vec(), sort: TableSort::default(), color: false }
+    }
+}
+
+/// One aligned row per project, under a header and a rule
+///
+/// Ties in the sort column keep priority order. In accessible mode rows
+/// become lists with each value labelled by its column.
+pub fn write_table_view<W: Write>(view: &PortfolioView<'_>, options: &TableOptions, out: &mut W) -> io::Result<()> {
+    let mut projects: Vec<&Project> = view.clone().sorted_by_priority().iter().collect();
+    projects.sort_by(|a, b| {
+        let order = options.sort.column.compare(a, b);
+        if options.sort.descending { order.reverse() } else { order }
+    });
+    let rows: Vec<Vec<String>> = projects.iter()
+        .map(|project| options.columns.iter().map(|column| truncate(&column.value(project))).collect())
+        .collect();
+    let headers: Vec<&str> = options.columns.iter().map(TableColumn::header).collect();
+    if style::accessible() {
+        return style::write_table(out, &headers, &rows);
+    }
+
+    let widths: Vec<usize> = headers.iter().enumerate()
+        .map(|(i, header)| rows.iter().map(|row| row[i].chars().count()).chain([header.chars().count()]).max().unwrap_or(0))
+        .collect();
+    let line = |cells: Vec<String>| cells.join("  ").trim_end().to_string();
+    let header: Vec<String> = options.columns.iter().zip(&headers).zip(&widths)
+        .map(|((column, header), width)| pad(header, *width, column.numeric()))
+        .collect();
+    writeln!(out, "{}", line(header))?;
+    writeln!(out, "{}", line(widths.iter().map(|width| "─".repeat(*width)).collect()))?;
+    for (project, row) in projects.iter().zip(&rows) {
+        let cells: Vec<String> = options.columns.iter().zip(row).zip(&widths)
+            .map(|((column, value), width)| {
+                let cell = pad(value, *width, column.numeric());
+                match column.color(project).filter(|_| options.color) {
+                    Some(color) => style::paint_always(&cell, color),
+                    None => cell,
+                }
+            })
+            .collect();
+        writeln!(out, "{}", line(cells))?;
+    }
+    Ok(())
+}
+
+fn pad(value: &str, width: usize, right: bool) -> String {
+    if right { format!("{:>width$}", value) } else { format!("{:<width$}", value) }
+}
+
+fn truncate(value: &str) -> String {
+    let value = value.replace(['\n', '\t'], " ");
+    match value.chars().count() > MAX_WIDTH {
+        true => format!("{}…", value.chars().take(MAX_WIDTH - 1).collect::<String>()),
+        false => value,
+    }
+}
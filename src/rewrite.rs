@@ -0,0 +1,287 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+use crate::{Result, SKMError};
+
+/// A literal text replacement, written `Old/New` (split at the first `/`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Replacement {
+    pub from: String,
+    pub to: String,
+}
+
+impl std::str::FromStr for Replacement {
+    type Err = SKMError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.split_once('/') {
+            Some((from, to)) if !from.is_empty() => Ok(Replacement { from: from.to_string(), to: to.to_string() }),
+            _ => Err(SKMError::ConfigError {
+                message: format!("Invalid replacement: {} (expected Old/New)", s),
+            }),
+        }
+    }
+}
+
+impl Replacement {
+    /// Branch the edits are committed on, e.g. `skm/replace-newproductname`
+    pub fn branch(&self) -> String {
+        format!("skm/replace-{}", crate::scaffold::slug(&self.to))
+    }
+}
+
+/// Text after a replacement, with the occurrences replaced and those left alone
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rewrite {
+    pub text: String,
+    pub replaced: usize,
+    /// Occurrences in code, link targets, URLs or comments
+    pub skipped: usize,
+}
+
+/// Replace whole-word occurrences in the prose of a Markdown document
+///
+/// Fenced code blocks, inline code, link and image targets, reference
+/// definitions, URLs and HTML comments are left as they are, since a rename
+/// there would break code samples and links. An occurrence that is part of a
+/// longer word (`OldNameSpace` for `OldName`) is not a match.
+pub fn replace_markdown(text: &str, replacement: &Replacement) -> Rewrite {
+    let mut rewrite = Rewrite { text: String::with_capacity(text.len()), replaced: 0, skipped: 0 };
+    let mut fence: Option<String> = None;
+    for line in text.split_inclusive('\n') {
+        let marker = fence_marker(line);
+        let code = marker.is_some() || fence.is_some();
+        match (&fence, marker) {
+            (None, Some(marker)) => fence = Some(marker),
+            (Some(open), Some(marker)) if marker.starts_with(open.as_str()) => fence = None,
+            _ => {}
+        }
+        let protected = if code { Vec::new() } else { protected_ranges(line) };
+        let mut last = 0;
+        for (start, _) in line.match_indices(&replacement.from) {
+            let end = start + replacement.from.len();
+            if start < last || !whole_word(line, start, end) {
+                continue;
+            }
+            if code || protected.iter().any(|range| start < range.end && range.start < end) {
+                rewrite.skipped += 1;
+                continue;
+            }
+            rewrite.text.push_str(&line[last..start]);
+            rewrite.text.push_str(&replacement.to);
+            rewrite.replaced += 1;
+            last = end;
+        }
+        rewrite.text.push_str(&line[last..]);
+    }
+    rewrite
+}
+
+/// The ```` ``` ```` or `~~~` run opening or closing a fenced code block
+fn fence_marker(line: &str) -> Option<String> {
+    let trimmed = line.trim_start_matches(' ');
+    if line.len() - trimmed.len() > 3 {
+        return None;
+    }
+    ['`', '~'].into_iter().find_map(|c| {
+        let run = trimmed.len() - trimmed.trim_start_matches(c).len();
+        (run >= 3).then(|| c.to_string().repeat(run))
+    })
+}
+
+fn is_word(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Whether `line[start..end]` is not glued to a word on either side
+fn whole_word(line: &str, start: usize, end: usize) -> bool {
+    let matched = &line[start..end];
+    let glued_before = matched.chars().next().is_some_and(is_word)
+        && line[..start].chars().next_back().is_some_and(is_word);
+    let glued_after = matched.chars().next_back().is_some_and(is_word)
+        && line[end..].chars().next().is_some_and(is_word);
+    !glued_before && !glued_after
+}
+
+/// Byte ranges of a line that are code, link targets, URLs or comments
+fn protected_ranges(line: &str) -> Vec<std::ops::Range<usize>> {
+    let mut ranges = Vec::new();
+    // `[label]: https://...` reference definitions
+    let trimmed = line.trim_start();
+    if trimmed.starts_with('[')
+        && let Some(colon) = trimmed.find("]:")
+    {
+        let start = line.len() - trimmed.len() + colon + 2;
+        ranges.push(start..line.len());
+    }
+    let mut i = 0;
+    while i < line.len() {
+        let rest = &line[i..];
+        let end = if rest.starts_with('`') {
+            let run = rest.len() - rest.trim_start_matches('`').len();
+            let ticks = "`".repeat(run);
+            // A run without a matching closing run is literal text
+            match closing_ticks(&rest[run..], &ticks) {
+                Some(close) => Some(i + run + close + run),
+                None => { i += run; continue; }
+            }
+        } else if let Some(target) = rest.strip_prefix("](") {
+            Some(i + 2 + matching_paren(target))
+        } else if rest.starts_with("<!--") {
+            Some(rest.find("-->").map_or(line.len(), |close| i + close + 3))
+        } else if rest.starts_with('<')
+            && let Some(close) = rest.find('>')
+            && (rest[..close].contains("://") || rest[..close].contains('@'))
+        {
+            Some(i + close + 1)
+        } else if rest.starts_with("http://") || rest.starts_with("https://") {
+            Some(rest.find(char::is_whitespace).map_or(line.len(), |space| i + space))
+        } else {
+            None
+        };
+        match end {
+            Some(end) => {
+                ranges.push(i..end);
+                i = end;
+            }
+            None => i += rest.chars().next().map_or(1, char::len_utf8),
+        }
+    }
+    ranges
+}
+
+/// Offset of the next run of exactly `ticks` in `rest`
+fn closing_ticks(rest: &str, ticks: &str) -> Option<usize> {
+    let mut from = 0;
+    while let Some(found) = rest[from..].find(ticks) {
+        let at = from + found;
+        let run = rest[at..].len() - rest[at..].trim_start_matches('`').len();
+        if run == ticks.len() {
+            return Some(at);
+        }
+        from = at + run;
+    }
+    None
+}
+
+/// Offset just past the `)` closing a link target, or the end of `rest`
+fn matching_paren(rest: &str) -> usize {
+    let mut depth = 1;
+    for (i, c) in rest.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return i + 1;
+                }
+            }
+            _ => {}
+        }
+    }
+    rest.len()
+}
+
+/// Markdown files of a project's spec artifacts: everything under `specs/`
+/// and `.specify/memory/` (the constitution), sorted
+///
+/// Spec-Kit's own templates and scripts under `.specify` are left out.
+pub fn artifact_files(project: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = [project.join("specs"), project.join(".specify/memory")].iter()
+        .flat_map(|dir| WalkDir::new(dir).sort_by_file_name().into_iter().filter_map(|e| e.ok()))
+        .filter(|e| e.file_type().is_file() && e.path().extension().is_some_and(|ext| ext == "md"))
+        .map(|e| e.into_path())
+        .collect();
+    files.sort();
+    files
+}
+
+/// A replacement in one artifact of a project
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileEdit {
+    /// Relative to the project
+    pub path: PathBuf,
+    pub before: String,
+    pub rewrite: Rewrite,
+}
+
+/// Every artifact of a project with an occurrence to replace or one left alone
+pub fn plan_edits(project: &Path, replacement: &Replacement) -> Result<Vec<FileEdit>> {
+    let mut edits = Vec::new();
+    for file in artifact_files(project) {
+        let before = fs::read_to_string(&file)?;
+        let rewrite = replace_markdown(&before, replacement);
+        if rewrite.replaced + rewrite.skipped > 0 {
+            let path = file.strip_prefix(project).unwrap_or(&file).to_path_buf();
+            edits.push(FileEdit { path, before, rewrite });
+        }
+    }
+    Ok(edits)
+}
+
+/// Commit the edits on a new `branch` for review
+///
+/// The commit is HEAD's tree with only the edited files replaced, written
+/// straight to the branch: the checkout, the index and anything staged in
+/// it stay as they were, on the original branch. Refuses to run when a file
+/// to edit has uncommitted changes, or when HEAD has no commit yet. Projects
+/// outside git are edited in place. Returns the branch, or `None` when
+/// nothing was committed.
+#[cfg(feature = "git")]
+pub fn commit_edits(project: &Path, edits: &[FileEdit], branch: &str, message: &str) -> Result<Option<String>> {
+    let edits: Vec<&FileEdit> = edits.iter().filter(|edit| edit.rewrite.replaced > 0).collect();
+    let Ok(repo) = git2::Repository::open(project) else {
+        write_edits(project, &edits)?;
+        return Ok(None);
+    };
+    let head = repo.head().and_then(|head| head.peel_to_commit())
+        .map_err(|_| integration_error("HEAD has no commit to branch from".to_string()))?;
+    for edit in &edits {
+        let status = repo.status_file(&edit.path).unwrap_or(git2::Status::WT_NEW);
+        if !status.is_empty() {
+            return Err(integration_error(format!("{} has uncommitted changes; commit or stash them first", edit.path.display())));
+        }
+    }
+    if repo.find_branch(branch, git2::BranchType::Local).is_ok() {
+        return Err(integration_error(format!("Branch {} already exists", branch)));
+    }
+    if edits.is_empty() {
+        return Ok(None);
+    }
+
+    let mut index = git2::Index::new()?;
+    index.read_tree(&head.tree()?)?;
+    for edit in &edits {
+        let path = edit.path.to_string_lossy().replace('\\', "/");
+        let mut entry = index.get_path(Path::new(&path), 0)
+            .ok_or_else(|| integration_error(format!("{} is not tracked", edit.path.display())))?;
+        entry.id = repo.blob(edit.rewrite.text.as_bytes())?;
+        entry.file_size = edit.rewrite.text.len() as u32;
+        index.add(&entry)?;
+    }
+    let tree = repo.find_tree(index.write_tree_to(&repo)?)?;
+    let signature = repo.signature().or_else(|_| git2::Signature::now("skm", "skm@localhost"))?;
+    repo.branch(branch, &head, false)?;
+    repo.commit(Some(&format!("refs/heads/{}", branch)), &signature, &signature, message, &tree, &[&head])?;
+    Ok(Some(branch.to_string()))
+}
+
+/// Without the `git` feature every project is edited in place
+#[cfg(not(feature = "git"))]
+pub fn commit_edits(project: &Path, edits: &[FileEdit], _branch: &str, _message: &str) -> Result<Option<String>> {
+    let edits: Vec<&FileEdit> = edits.iter().filter(|edit| edit.rewrite.replaced > 0).collect();
+    write_edits(project, &edits)?;
+    Ok(None)
+}
+
+fn write_edits(project: &Path, edits: &[&FileEdit]) -> Result<()> {
+    for edit in edits {
+        fs::write(project.join(&edit.path), &edit.rewrite.text)?;
+    }
+    Ok(())
+}
+
+#[cfg(feature = "git")]
+fn integration_error(message: String) -> SKMError {
+    SKMError::IntegrationError { message }
+}
@@ -0,0 +1,394 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::fs;
+use chrono::Utc;
+use serde::Deserialize;
+use crate::{Result, SKMError};
+use crate::meta::config::{InitBackend, InitConfig};
+use crate::scanner::{finder, parser::list_feature_dirs, pipeline::project_id, todos};
+
+/// Spec-Kit's feature script, present in projects set up by `specify init`
+const CREATE_FEATURE_SCRIPT: &str = ".specify/scripts/bash/create-new-feature.sh";
+
+const CONSTITUTION_TEMPLATE: &str = "# {name} Constitution
+
+## Core Principles
+
+### I. [NEEDS CLARIFICATION: first principle]
+
+### II. [NEEDS CLARIFICATION: second principle]
+
+## Governance
+
+This constitution supersedes other practices. Amendments are documented here.
+
+**Version**: 1.0.0 | **Ratified**: {date}
+";
+
+const SPEC_TEMPLATE: &str = "# Feature Specification: {title}
+
+**Feature Branch**: `{branch}`
+**Created**: {date}
+**Status**: Draft
+**Input**: User description: \"{description}\"
+
+## User Scenarios & Testing
+
+### User Story 1
+
+[NEEDS CLARIFICATION: primary user journey]
+
+## Requirements
+
+### Functional Requirements
+
+- **FR-001**: System MUST [NEEDS CLARIFICATION: first requirement]
+
+## Success Criteria
+";
+
+/// Scaffolding that actually ran, after resolving `auto`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scaffolder {
+    Internal,
+    Specify,
+}
+
+impl Scaffolder {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Scaffolder::Internal => "skm templates",
+            Scaffolder::Specify => "specify",
+        }
+    }
+}
+
+/// Whether the official `specify` CLI is on PATH
+pub fn specify_available() -> bool {
+    std::env::var_os("PATH").is_some_and(|path| {
+        std::env::split_paths(&path).any(|dir| dir.join("specify").is_file())
+    })
+}
+
+fn resolve(backend: InitBackend) -> Result<Scaffolder> {
+    match backend {
+        InitBackend::Internal => Ok(Scaffolder::Internal),
+        InitBackend::Specify if specify_available() => Ok(Scaffolder::Specify),
+        InitBackend::Specify => Err(integration_error("init.backend is specify, but the specify CLI is not on PATH".to_string())),
+        InitBackend::Auto if specify_available() => Ok(Scaffolder::Specify),
+        InitBackend::Auto => Ok(Scaffolder::Internal),
+    }
+}
+
+/// Set up Spec-Kit in a directory, creating it if needed
+///
+/// The `specify` backend runs `specify init --here` with the configured
+/// arguments; the internal one writes a constitution template and an empty
+/// `specs/`. Either way the directory ends up a git repository.
+pub fn init_project(path: &Path, config: &InitConfig, backend: InitBackend) -> Result<Scaffolder> {
+    fs::create_dir_all(path)?;
+    if path.join(".specify").exists() {
+        return Err(invalid_target(format!("{} already has a .specify directory", path.display())));
+    }
+
+    let scaffolder = resolve(backend)?;
+    match scaffolder {
+        Scaffolder::Specify => {
+            let status = Command::new("specify")
+                .args(["init", "--here"])
+                .args(&config.specify_args)
+                .current_dir(path)
+                .status()
+                .map_err(|e| integration_error(format!("running specify init: {}", e)))?;
+            if !status.success() {
+                return Err(integration_error(format!("specify init failed with {}", status)));
+            }
+        }
+        Scaffolder::Internal => {
+            let name = project_id(&path.canonicalize()?);
+            let constitution = path.join(".specify/memory/constitution.md");
+            fs::create_dir_all(constitution.parent().unwrap())?;
+            fs::write(&constitution, fill(CONSTITUTION_TEMPLATE, &[("name", &name)]))?;
+            fs::create_dir_all(path.join("specs"))?;
+        }
+    }
+
+    #[cfg(feature = "git")]
+    if git2::Repository::open(path).is_err() {
+        git2::Repository::init(path)?;
+    }
+    Ok(scaffolder)
+}
+
+/// A feature directory created by `skm new-feature`
+#[derive(Debug, Clone)]
+pub struct NewFeature {
+    pub number: u32,
+    /// Directory and branch name, e.g. `003-user-export`
+    pub id: String,
+    pub spec: PathBuf,
+    /// Branch checked out for the feature, when the project is a git repository
+    pub branch: Option<String>,
+    pub scaffolder: Scaffolder,
+}
+
+/// JSON printed by Spec-Kit's `create-new-feature.sh --json`
+#[derive(Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+struct ScriptOutput {
+    branch_name: String,
+    spec_file: PathBuf,
+}
+
+/// Start a numbered feature: `specs/NNN-name/spec.md` on a branch of the same name
+///
+/// With the `specify` backend the project's own `create-new-feature.sh`
+/// creates the files; skm still makes sure the feature branch exists and
+/// is checked out.
+pub fn new_feature(project: &Path, description: &str, short_name: Option<&str>, backend: InitBackend) -> Result<NewFeature> {
+    let script = project.join(CREATE_FEATURE_SCRIPT);
+    let scaffolder = match backend {
+        InitBackend::Internal => Scaffolder::Internal,
+        _ if script.is_file() => Scaffolder::Specify,
+        InitBackend::Specify => return Err(integration_error(format!(
+            "{} has no {}; was it set up with specify init?", project.display(), CREATE_FEATURE_SCRIPT,
+        ))),
+        InitBackend::Auto => Scaffolder::Internal,
+    };
+
+    let (id, spec) = match scaffolder {
+        Scaffolder::Specify => {
+            let mut cmd = Command::new("bash");
+            cmd.arg(&script).arg("--json");
+            if let Some(name) = short_name {
+                cmd.args(["--short-name", name]);
+            }
+            let output = cmd.arg(description).current_dir(project).output()
+                .map_err(|e| integration_error(format!("running create-new-feature.sh: {}", e)))?;
+            if !output.status.success() {
+                return Err(integration_error(format!(
+                    "create-new-feature.sh failed: {}", String::from_utf8_lossy(&output.stderr).trim(),
+                )));
+            }
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            let json = stdout.lines().rev().find(|line| line.trim_start().starts_with('{'))
+                .ok_or_else(|| integration_error("create-new-feature.sh printed no JSON".to_string()))?;
+            let parsed: ScriptOutput = serde_json::from_str(json)?;
+            (parsed.branch_name, parsed.spec_file)
+        }
+        Scaffolder::Internal => {
+            let number = next_feature_number(&project.join("specs"));
+            let id = format!("{:03}-{}", number, slug(short_name.unwrap_or(description)));
+            let dir = project.join("specs").join(&id);
+            if dir.exists() {
+                return Err(invalid_target(format!("{} already exists", dir.display())));
+            }
+            fs::create_dir_all(&dir)?;
+            let spec = dir.join("spec.md");
+            let title = description.lines().next().unwrap_or(description).trim();
+            fs::write(&spec, fill(SPEC_TEMPLATE, &[
+                ("title", title),
+                ("branch", &id),
+                ("description", description.trim()),
+            ]))?;
+            (id, spec)
+        }
+    };
+
+    let number = id.chars().take_while(|c| c.is_ascii_digit()).collect::<String>().parse().unwrap_or(0);
+    let branch = checkout_feature_branch(project, &id)?;
+    Ok(NewFeature { number, id, spec, branch, scaffolder })
+}
+
+/// Number after the highest numbered feature directory
+fn next_feature_number(specs: &Path) -> u32 {
+    list_feature_dirs(specs).iter()
+        .filter_map(|dir| dir.file_name()?.to_str()?.get(..3)?.parse::<u32>().ok())
+        .max()
+        .map_or(1, |n| n + 1)
+}
+
+/// First words of a description as a branch-safe name: "Add user export!" -> "add-user-export"
+pub(crate) fn slug(text: &str) -> String {
+    let words: Vec<String> = text
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .take(4)
+        .map(str::to_ascii_lowercase)
+        .collect();
+    if words.is_empty() { "feature".to_string() } else { words.join("-") }
+}
+
+/// Check out `branch`, creating it from HEAD if needed; `None` outside git
+///
+/// A repository without commits gets its unborn HEAD pointed at the branch.
+#[cfg(feature = "git")]
+pub(crate) fn checkout_feature_branch(project: &Path, branch: &str) -> Result<Option<String>> {
+    let Ok(repo) = git2::Repository::open(project) else {
+        return Ok(None);
+    };
+    let reference = format!("refs/heads/{}", branch);
+
+    match repo.head() {
+        Ok(head) if head.name() == Some(reference.as_str()) => {}
+        Ok(head) => {
+            if repo.find_branch(branch, git2::BranchType::Local).is_err() {
+                repo.branch(branch, &head.peel_to_commit()?, false)?;
+            }
+            let target = repo.revparse_single(&reference)?;
+            repo.checkout_tree(&target, Some(git2::build::CheckoutBuilder::new().safe()))?;
+            repo.set_head(&reference)?;
+        }
+        Err(_) => repo.set_head(&reference)?,
+    }
+    Ok(Some(branch.to_string()))
+}
+
+/// Without the `git` feature every project is treated as outside git
+#[cfg(not(feature = "git"))]
+pub(crate) fn checkout_feature_branch(_project: &Path, _branch: &str) -> Result<Option<String>> {
+    Ok(None)
+}
+
+/// Marker on everything `skm adopt` generates, until a human has checked it
+pub const NEEDS_REVIEW: &str = "[NEEDS REVIEW]";
+
+/// Seeded tasks beyond this many are summarized instead of listed
+const MAX_ADOPTED_TASKS: usize = 200;
+
+/// Files written by `skm adopt`
+#[derive(Debug, Clone)]
+pub struct Adoption {
+    pub spec: PathBuf,
+    pub tasks: PathBuf,
+    pub headings: usize,
+    pub todos: usize,
+}
+
+/// Bring an existing codebase without Spec-Kit artifacts into the portfolio
+///
+/// Writes `specs/001-baseline/spec.md` outlining what the README describes
+/// and `tasks.md` seeded from TODO/FIXME comments in the source, everything
+/// marked `[NEEDS REVIEW]`.
+pub fn adopt_project(path: &Path) -> Result<Adoption> {
+    if !path.is_dir() {
+        return Err(invalid_target(format!("{} is not a directory", path.display())));
+    }
+    if path.join(".specify").exists() || path.join("specs").exists() {
+        return Err(invalid_target(format!("{} already has Spec-Kit artifacts", path.display())));
+    }
+
+    let name = project_id(&path.canonicalize()?);
+    let project_type = finder::detect_project_type(path);
+    let readme = fs::read_dir(path)?
+        .filter_map(|e| e.ok())
+        .find(|e| e.file_name().to_string_lossy().to_lowercase().starts_with("readme"))
+        .and_then(|e| fs::read_to_string(e.path()).ok())
+        .unwrap_or_default();
+    let headings = readme_headings(&readme);
+
+    let mut spec = format!(
+        "# Feature Specification: {name} baseline {NEEDS_REVIEW}\n\n\
+        **Status**: Draft {NEEDS_REVIEW}\n\
+        **Created**: {date} by `skm adopt` from the existing {kind:?} codebase\n\n\
+        ## Overview {NEEDS_REVIEW}\n\n{overview}\n\n\
+        ## Existing Functionality {NEEDS_REVIEW}\n\n",
+        date = Utc::now().format("%Y-%m-%d"),
+        kind = project_type,
+        overview = readme_overview(&readme).unwrap_or_else(|| "[NEEDS CLARIFICATION: what the project does]".to_string()),
+    );
+    if headings.is_empty() {
+        spec.push_str("- [NEEDS CLARIFICATION: existing features]\n");
+    }
+    for heading in &headings {
+        spec.push_str(&format!("- {} {}\n", heading, NEEDS_REVIEW));
+    }
+    spec.push_str(&format!(
+        "\n## Requirements {NEEDS_REVIEW}\n\n\
+        - **FR-001**: System MUST keep its current behavior [NEEDS CLARIFICATION: which behavior is essential]\n",
+    ));
+
+    let found = todos::harvest(path);
+    let mut tasks = format!(
+        "# Tasks: {name} baseline {NEEDS_REVIEW}\n\n\
+        Seeded by `skm adopt` from TODO/FIXME comments; review, reword and prune before implementing.\n\n\
+        ## Code debt\n\n",
+    );
+    for (index, todo) in found.iter().take(MAX_ADOPTED_TASKS).enumerate() {
+        tasks.push_str(&format!("- [ ] T{:03} {} {}\n", index + 1, NEEDS_REVIEW, todo.describe()));
+    }
+    if found.len() > MAX_ADOPTED_TASKS {
+        tasks.push_str(&format!("\n{} more TODO/FIXME comments were not listed.\n", found.len() - MAX_ADOPTED_TASKS));
+    }
+    if found.is_empty() {
+        tasks.push_str("- [ ] T001 [NEEDS REVIEW] Capture the first piece of work\n");
+    }
+
+    let dir = path.join("specs/001-baseline");
+    fs::create_dir_all(&dir)?;
+    let (spec_path, tasks_path) = (dir.join("spec.md"), dir.join("tasks.md"));
+    fs::write(&spec_path, spec)?;
+    fs::write(&tasks_path, tasks)?;
+    Ok(Adoption {
+        spec: spec_path,
+        tasks: tasks_path,
+        headings: headings.len(),
+        todos: found.len(),
+    })
+}
+
+/// `##` and `###` headings of a README, skipping boilerplate sections
+fn readme_headings(readme: &str) -> Vec<String> {
+    const BOILERPLATE: &[&str] = &[
+        "installation", "install", "license", "contributing", "table of contents", "contents",
+        "acknowledgments", "acknowledgements", "authors", "support", "badges", "changelog",
+    ];
+    let mut in_code = false;
+    readme.lines()
+        .filter(|line| {
+            if line.trim_start().starts_with("```") {
+                in_code = !in_code;
+            }
+            !in_code
+        })
+        .filter_map(|line| line.strip_prefix("### ").or_else(|| line.strip_prefix("## ")))
+        .map(|heading| heading.trim().trim_matches('#').trim().to_string())
+        .filter(|heading| {
+            let plain: String = heading.chars().filter(|c| c.is_alphanumeric() || c.is_whitespace()).collect();
+            !heading.is_empty() && !BOILERPLATE.contains(&plain.trim().to_lowercase().as_str())
+        })
+        .collect()
+}
+
+/// First prose paragraph of a README
+fn readme_overview(readme: &str) -> Option<String> {
+    readme.split("\n\n")
+        .map(str::trim)
+        .find(|paragraph| {
+            !paragraph.is_empty()
+                && !paragraph.starts_with('#')
+                && !paragraph.starts_with("```")
+                && !paragraph.starts_with('[')
+                && !paragraph.starts_with('<')
+                && !paragraph.starts_with('!')
+        })
+        .map(|paragraph| paragraph.lines().map(str::trim).collect::<Vec<_>>().join(" "))
+}
+
+fn fill(template: &str, values: &[(&str, &str)]) -> String {
+    let mut text = template.replace("{date}", &Utc::now().format("%Y-%m-%d").to_string());
+    for (key, value) in values {
+        text = text.replace(&format!("{{{}}}", key), value);
+    }
+    text
+}
+
+/// The `specify` CLI or a Spec-Kit script is missing or failed
+fn integration_error(message: String) -> SKMError {
+    SKMError::IntegrationError { message }
+}
+
+/// A directory that cannot be set up as asked
+fn invalid_target(message: String) -> SKMError {
+    SKMError::ConfigError { message }
+}
@@ -1,7 +0,0 @@
-pub mod finder;
-pub mod parser;
-pub mod git;
-
-pub use finder::*;
-pub use parser::*;
-pub use git::*;
\ No newline at end of file
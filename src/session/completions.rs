@@ -0,0 +1,158 @@
+use clap::{Arg, Command, ValueEnum};
+use crate::analyzer::filter;
+use crate::meta::config::GlobalConfig;
+
+/// Shells `skm completions` can generate a script for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum CompletionShell {
+    Bash,
+    Zsh,
+    Fish,
+    #[value(name = "powershell")]
+    PowerShell,
+}
+
+/// Shell code that registers completion for `skm`
+///
+/// The scripts hold no command list of their own. On every completion they
+/// run the hidden `skm __complete -- <words>` with the words up to the
+/// cursor and offer what it prints, or file names when it prints nothing,
+/// so they stay current as commands are added and projects are scanned.
+pub fn completion_script(shell: CompletionShell) -> &'static str {
+    match shell {
+        CompletionShell::Bash => include_str!("completions/skm.bash"),
+        CompletionShell::Zsh => include_str!("completions/skm.zsh"),
+        CompletionShell::Fish => include_str!("completions/skm.fish"),
+        CompletionShell::PowerShell => include_str!("completions/skm.ps1"),
+    }
+}
+
+/// What to offer for the word under the cursor
+#[derive(Debug, Clone, PartialEq)]
+pub enum Completion {
+    /// These candidates, already matched against the word
+    Words(Vec<String>),
+    /// Project ids from the status cache of `root`, or of the default root,
+    /// that start with `prefix`; each is offered with `lead` in front
+    Projects { root: Option<String>, lead: String, prefix: String },
+}
+
+/// Candidates for the last of `words`, the arguments after `skm` up to the
+/// cursor (the last one may be empty)
+///
+/// `cmd` must be built (`Command::build`) so that global options and
+/// `--help` appear on every subcommand. An empty list means nothing in
+/// particular is expected, such as a path.
+pub fn complete(cmd: &Command, words: &[String]) -> Completion {
+    let (current, before) = match words.split_last() {
+        Some((current, before)) => (current.as_str(), before),
+        None => ("", words),
+    };
+
+    let mut cmd = cmd;
+    let mut pending: Option<&Arg> = None;
+    let mut positionals = 0;
+    let mut after_dashes = false;
+    let mut root = None;
+    for word in before {
+        if let Some(arg) = pending.take() {
+            if arg.get_id() == "root" {
+                root = Some(word.clone());
+            }
+            continue;
+        }
+        if !after_dashes && word == "--" {
+            after_dashes = true;
+        } else if !after_dashes && word.starts_with('-') && word.len() > 1 {
+            match word.split_once('=') {
+                Some((name, value)) => {
+                    if find_option(cmd, name).is_some_and(|arg| arg.get_id() == "root") {
+                        root = Some(value.to_string());
+                    }
+                }
+                None => pending = find_option(cmd, word).filter(|arg| takes_value(arg)),
+            }
+        } else if let Some(sub) = (positionals == 0 && !after_dashes).then(|| find_subcommand(cmd, word)).flatten() {
+            cmd = sub;
+        } else {
+            positionals += 1;
+        }
+    }
+
+    if let Some(arg) = pending {
+        return values(arg, "", current, root);
+    }
+    if !after_dashes && current.starts_with('-') {
+        if let Some((name, value)) = current.split_once('=') {
+            return match find_option(cmd, name) {
+                Some(arg) => values(arg, &format!("{}=", name), value, root),
+                None => Completion::Words(Vec::new()),
+            };
+        }
+        let options = cmd.get_arguments()
+            .filter(|arg| !arg.is_hide_set())
+            .filter_map(Arg::get_long)
+            .map(|long| format!("--{}", long));
+        return Completion::Words(matching(options, current));
+    }
+
+    if positionals == 0 && cmd.has_subcommands() {
+        let names = cmd.get_subcommands()
+            .filter(|sub| !sub.is_hide_set())
+            .map(|sub| sub.get_name().to_string());
+        return Completion::Words(matching(names, current));
+    }
+    let positional = cmd.get_positionals().nth(positionals)
+        .or_else(|| cmd.get_positionals().last().filter(|arg| arg.is_trailing_var_arg_set() || takes_many(arg)));
+    match positional {
+        Some(arg) => values(arg, "", current, root),
+        None => Completion::Words(Vec::new()),
+    }
+}
+
+/// Values of `arg` starting with `prefix`, each offered with `lead` in front
+fn values(arg: &Arg, lead: &str, prefix: &str, root: Option<String>) -> Completion {
+    let candidates: Vec<String> = match arg.get_id().as_str() {
+        "project" => return Completion::Projects { root, lead: lead.to_string(), prefix: prefix.to_string() },
+        "only" => {
+            let custom_stages: Vec<String> = GlobalConfig::load().map(|config| config.workflow.stages)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|stage| stage.name)
+                .collect();
+            filter::completion_words(&custom_stages)
+        }
+        _ => arg.get_possible_values().iter()
+            .filter(|value| !value.is_hide_set())
+            .map(|value| value.get_name().to_string())
+            .collect(),
+    };
+    Completion::Words(matching(candidates, prefix).into_iter().map(|c| format!("{}{}", lead, c)).collect())
+}
+
+fn matching(candidates: impl IntoIterator<Item = String>, prefix: &str) -> Vec<String> {
+    candidates.into_iter().filter(|c| c.starts_with(prefix)).collect()
+}
+
+fn find_option<'a>(cmd: &'a Command, word: &str) -> Option<&'a Arg> {
+    if let Some(long) = word.strip_prefix("--") {
+        return cmd.get_arguments().find(|arg| {
+            arg.get_long() == Some(long) || arg.get_all_aliases().is_some_and(|aliases| aliases.contains(&long))
+        });
+    }
+    // The last of a run of short flags (`-xv`) is the one that may take a value
+    let short = word.chars().last()?;
+    cmd.get_arguments().find(|arg| arg.get_short() == Some(short))
+}
+
+fn find_subcommand<'a>(cmd: &'a Command, word: &str) -> Option<&'a Command> {
+    cmd.get_subcommands().find(|sub| sub.get_name() == word || sub.get_all_aliases().any(|alias| alias == word))
+}
+
+fn takes_value(arg: &Arg) -> bool {
+    arg.get_action().takes_values() && arg.get_num_args().is_some_and(|range| range.min_values() > 0)
+}
+
+fn takes_many(arg: &Arg) -> bool {
+    arg.get_num_args().is_some_and(|range| range.max_values() > 1)
+}
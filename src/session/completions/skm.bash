@@ -0,0 +1,16 @@
+# skm completion for bash; in ~/.bashrc:
+#   eval "$(skm completions bash)"
+_skm() {
+    # Rebuild the words from the line, since bash splits them at ':' and '='
+    local line="${COMP_LINE:0:COMP_POINT}" words
+    read -ra words <<< "$line"
+    [[ "$line" == *[[:space:]] ]] && words+=("")
+    local cur="${words[${#words[@]}-1]}" IFS=$'\n'
+    COMPREPLY=($(skm __complete -- "${words[@]:1}" 2>/dev/null))
+    # ...and replaces only the part after the last of them
+    if [[ "$cur" == *[:=]* ]]; then
+        local lead="${cur%"${cur##*[:=]}"}"
+        COMPREPLY=("${COMPREPLY[@]#"$lead"}")
+    fi
+}
+complete -o default -F _skm skm
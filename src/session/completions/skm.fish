@@ -0,0 +1,13 @@
+# skm completion for fish; in ~/.config/fish/config.fish:
+#   skm completions fish | source
+function __skm_complete
+    set -l current (commandline -ct)
+    set -l words (commandline -opc) "$current"
+    set -l candidates (skm __complete -- $words[2..-1] 2>/dev/null)
+    if test (count $candidates) -gt 0
+        printf '%s\n' $candidates
+    else
+        __fish_complete_path "$current"
+    end
+end
+complete -c skm -f -a '(__skm_complete)'
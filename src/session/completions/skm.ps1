@@ -0,0 +1,12 @@
+# skm completion for PowerShell; in $PROFILE:
+#   skm completions powershell | Out-String | Invoke-Expression
+Register-ArgumentCompleter -Native -CommandName skm -ScriptBlock {
+    param($wordToComplete, $commandAst, $cursorPosition)
+    $words = @($commandAst.CommandElements |
+        Where-Object { $_.Extent.StartOffset -lt $cursorPosition } |
+        ForEach-Object { $_.ToString() })
+    if ($wordToComplete -eq '') { $words += '' }
+    skm __complete -- $words[1..($words.Count - 1)] 2>$null | ForEach-Object {
+        [System.Management.Automation.CompletionResult]::new($_, $_, 'ParameterValue', $_)
+    }
+}
@@ -0,0 +1,36 @@
+use std::str::FromStr;
+use crate::{Result, SKMError};
+
+/// Shells `skm hook` can generate a directory-change hook for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shell {
+    Zsh,
+    Bash,
+    Fish,
+}
+
+impl FromStr for Shell {
+    type Err = SKMError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "zsh" => Ok(Shell::Zsh),
+            "bash" => Ok(Shell::Bash),
+            "fish" => Ok(Shell::Fish),
+            other => Err(SKMError::ConfigError { message: format!("Unsupported shell: {} (expected zsh, bash or fish)", other) }),
+        }
+    }
+}
+
+/// Shell code that offers to register untracked projects on `cd`
+///
+/// The hook calls `skm register --check`, which prints a project path only
+/// when the directory is inside an untracked, non-ignored project, so it
+/// stays silent everywhere else.
+pub fn hook_script(shell: Shell) -> &'static str {
+    match shell {
+        Shell::Zsh => include_str!("hooks/skm.zsh"),
+        Shell::Bash => include_str!("hooks/skm.bash"),
+        Shell::Fish => include_str!("hooks/skm.fish"),
+    }
+}
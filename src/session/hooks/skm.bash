@@ -0,0 +1,18 @@
+# skm: offer to track Spec-Kit projects on cd
+_skm_prompt_command() {
+  [[ "$PWD" == "${_SKM_LAST_PWD:-}" ]] && return
+  _SKM_LAST_PWD="$PWD"
+  local project reply
+  project="$(command skm register --check 2>/dev/null)" || return
+  [[ -n "$project" ]] || return
+  read -r -n 1 -p "skm: track $project? [y/N] " reply
+  echo
+  if [[ "$reply" == [yY] ]]; then
+    command skm register "$project" --warm
+  else
+    command skm register "$project" --ignore
+  fi
+}
+if [[ ";${PROMPT_COMMAND:-};" != *";_skm_prompt_command;"* ]]; then
+  PROMPT_COMMAND="_skm_prompt_command${PROMPT_COMMAND:+;$PROMPT_COMMAND}"
+fi
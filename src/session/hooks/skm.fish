@@ -0,0 +1,12 @@
+# skm: offer to track Spec-Kit projects on cd
+function __skm_on_pwd --on-variable PWD
+    status --is-command-substitution; and return
+    set -l project (command skm register --check 2>/dev/null); or return
+    test -n "$project"; or return
+    read -l -n 1 -P "skm: track $project? [y/N] " reply
+    if string match -qi y -- $reply
+        command skm register $project --warm
+    else
+        command skm register $project --ignore
+    end
+end
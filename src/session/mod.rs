@@ -1,3 +1,7 @@
+#[cfg(feature = "session")]
 pub mod tmux;
 pub mod handoff;
+pub use skm_core::session::launch;
+pub mod hook;
+pub mod completions;
 
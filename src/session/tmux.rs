@@ -1 +1,155 @@
-// Placeholder for src/session/tmux.rs
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use crate::{Result, SKMError};
+
+/// Prefix of the tmux sessions skm manages, so `skm session list` leaves others alone
+pub const SESSION_PREFIX: &str = "skm-";
+
+/// Panes of a new session; the default is a single shell
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SessionLayout {
+    /// Runs in the first pane, e.g. `nvim .`
+    pub editor: Option<String>,
+    /// Split off a second pane to the right
+    pub split: bool,
+    /// Runs in the second pane, e.g. `claude`
+    pub agent: Option<String>,
+}
+
+/// A running skm session
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionInfo {
+    pub name: String,
+    pub windows: u32,
+    /// Number of clients attached
+    pub attached: u32,
+    pub path: PathBuf,
+}
+
+fn tmux(args: &[&str]) -> Result<Output> {
+    let command = format!("tmux {}", args.join(" "));
+    Command::new("tmux")
+        .args(args)
+        .output()
+        .map_err(|e| SKMError::TmuxError { command: format!("{} ({})", command, e) })
+}
+
+/// Run tmux and fail with its error message when it exits non-zero
+fn tmux_ok(args: &[&str]) -> Result<Output> {
+    let output = tmux(args)?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(SKMError::TmuxError { command: format!("tmux {}: {}", args.join(" "), stderr.trim()) });
+    }
+    Ok(output)
+}
+
+/// Session name for a project display name: `skm-` plus the name with
+/// characters tmux reserves (`.`, `:`) and separators replaced by `-`
+pub fn session_name(project: &str) -> String {
+    let name: String = project.chars()
+        .map(|c| if c.is_alphanumeric() || c == '_' || c == '-' { c } else { '-' })
+        .collect();
+    format!("{}{}", SESSION_PREFIX, name)
+}
+
+/// Target that matches the session name exactly rather than by prefix
+fn exact(name: &str) -> String {
+    format!("={}", name)
+}
+
+pub fn session_exists(name: &str) -> bool {
+    tmux(&["has-session", "-t", &exact(name)]).is_ok_and(|o| o.status.success())
+}
+
+/// Create a detached session in `path` unless one with this name is running
+///
+/// Pane commands are typed into the panes' shells, so quitting the editor
+/// or agent leaves a shell in the project directory. Returns whether the
+/// session was created.
+pub fn open_session(name: &str, path: &Path, layout: &SessionLayout) -> Result<bool> {
+    if session_exists(name) {
+        return Ok(false);
+    }
+    let dir = path.to_string_lossy();
+    tmux_ok(&["new-session", "-d", "-s", name, "-c", &dir])?;
+    let first_pane = format!("{}:", exact(name));
+    if let Some(editor) = &layout.editor {
+        tmux_ok(&["send-keys", "-t", &first_pane, editor, "Enter"])?;
+    }
+    if layout.split {
+        let output = tmux_ok(&["split-window", "-h", "-P", "-F", "#{pane_id}", "-t", &first_pane, "-c", &dir])?;
+        let pane = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        if let Some(agent) = &layout.agent {
+            tmux_ok(&["send-keys", "-t", &pane, agent, "Enter"])?;
+        }
+        // Start with the cursor in the editor
+        tmux_ok(&["select-pane", "-L", "-t", &pane])?;
+    }
+    Ok(true)
+}
+
+/// Run `command` in a new window of the session, creating the session in
+/// `path` if it is not running
+///
+/// Like pane commands, it is typed into the window's shell.
+pub fn run_in_window(name: &str, path: &Path, window: &str, command: &str) -> Result<()> {
+    let dir = path.to_string_lossy();
+    let output = if session_exists(name) {
+        tmux_ok(&["new-window", "-P", "-F", "#{pane_id}", "-t", &format!("{}:", exact(name)), "-n", window, "-c", &dir])?
+    } else {
+        tmux_ok(&["new-session", "-d", "-P", "-F", "#{pane_id}", "-s", name, "-n", window, "-c", &dir])?
+    };
+    let pane = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    tmux_ok(&["send-keys", "-t", &pane, command, "Enter"])?;
+    Ok(())
+}
+
+/// Bring the terminal to the session: switch to it from inside tmux, attach otherwise
+pub fn attach(name: &str) -> Result<()> {
+    let target = exact(name);
+    let args = if std::env::var_os("TMUX").is_some() {
+        ["switch-client", "-t", target.as_str()]
+    } else {
+        ["attach-session", "-t", target.as_str()]
+    };
+    let status = Command::new("tmux")
+        .args(args)
+        .status()
+        .map_err(|e| SKMError::TmuxError { command: format!("tmux {} ({})", args.join(" "), e) })?;
+    if !status.success() {
+        return Err(SKMError::TmuxError { command: format!("tmux {}", args.join(" ")) });
+    }
+    Ok(())
+}
+
+/// Running sessions created by skm; empty when no tmux server is running
+pub fn list_sessions() -> Result<Vec<SessionInfo>> {
+    let output = tmux(&["list-sessions", "-F", "#{session_name}\t#{session_windows}\t#{session_attached}\t#{session_path}"])?;
+    if !output.status.success() {
+        // `no server running` / `no sessions` are not errors for a listing
+        return Ok(Vec::new());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).lines()
+        .filter_map(|line| {
+            let mut fields = line.split('\t');
+            let name = fields.next()?;
+            Some(SessionInfo {
+                name: name.to_string(),
+                windows: fields.next()?.parse().unwrap_or(0),
+                attached: fields.next()?.parse().unwrap_or(0),
+                path: PathBuf::from(fields.next().unwrap_or_default()),
+            })
+        })
+        .filter(|s| s.name.starts_with(SESSION_PREFIX))
+        .collect())
+}
+
+/// Kill a session; returns false when it was not running
+pub fn kill_session(name: &str) -> Result<bool> {
+    if !session_exists(name) {
+        return Ok(false);
+    }
+    tmux_ok(&["kill-session", "-t", &exact(name)])?;
+    Ok(true)
+}
@@ -0,0 +1,418 @@
+//! Synthetic Spec-Kit portfolios for integration tests and `skm bench`
+//!
+//! A [`ProjectSpec`] describes a project by the stage it should be detected
+//! in, the format of its tasks, its language and its git state;
+//! [`SyntheticPortfolio`] writes such projects under a root, scans them in
+//! process with the default config, and removes the root again on drop.
+//! [`snapshot`] reduces a scan to the fields that do not change between
+//! runs, for comparison with [`assert_snapshot`].
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use chrono::Utc;
+use serde_json::{json, Value};
+use crate::{PortfolioStatus, ProjectType, Result, SKMError, ScanStats, Stage, TestRun};
+use crate::meta::config::GlobalConfig;
+use crate::meta::testruns::TestRunStore;
+use crate::scanner::finder::ProjectScanner;
+use crate::scanner::pipeline::{self, ScanContext, ScanOptions};
+
+/// Set to `1` to write the snapshots compared by [`assert_snapshot`]
+pub const UPDATE_SNAPSHOTS_ENV: &str = "SKM_UPDATE_SNAPSHOTS";
+
+/// Format the tasks file is written in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskStyle {
+    /// `- [x] Task`
+    Checkbox,
+    /// `- [x] T001: Task`
+    TaskIds,
+    /// `✅ Task` / `❌ Task`
+    Emoji,
+    /// `DONE: Task` / `TODO: Task`
+    Keywords,
+}
+
+impl TaskStyle {
+    pub const ALL: [TaskStyle; 4] = [TaskStyle::Checkbox, TaskStyle::TaskIds, TaskStyle::Emoji, TaskStyle::Keywords];
+
+    fn line(self, number: usize, done: bool) -> String {
+        let text = format!("Implement part {}", number);
+        match self {
+            TaskStyle::Checkbox => format!("- [{}] {}", if done { "x" } else { " " }, text),
+            TaskStyle::TaskIds => format!("- [{}] T{:03}: {}", if done { "x" } else { " " }, number, text),
+            TaskStyle::Emoji => format!("{} {}", if done { "✅" } else { "❌" }, text),
+            TaskStyle::Keywords => format!("{}: {}", if done { "DONE" } else { "TODO" }, text),
+        }
+    }
+}
+
+/// Repository state of a generated project
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GitState {
+    /// Not a repository
+    None,
+    /// Everything committed
+    Clean,
+    /// Committed, then the tasks file edited
+    Dirty,
+    /// Committed on `master` and checked out on a feature branch at the
+    /// same commit, so the branch counts as merged
+    Merged,
+}
+
+/// A project to generate
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProjectSpec {
+    /// Directory under the root, whose last component is the project id
+    pub name: String,
+    /// Stage the scan should detect; `Done` is reached through
+    /// `status: done` in the tasks file's front matter
+    pub stage: Stage,
+    /// Number of tasks, at least 2 so `Implement` can have some done
+    pub tasks: usize,
+    pub style: TaskStyle,
+    /// Manifest and, from `Test` on, source code of this language
+    pub language: ProjectType,
+    pub git: GitState,
+}
+
+impl ProjectSpec {
+    /// Four checkbox tasks, Rust, no git
+    pub fn new(name: &str, stage: Stage) -> Self {
+        ProjectSpec {
+            name: name.to_string(),
+            stage,
+            tasks: 4,
+            style: TaskStyle::Checkbox,
+            language: ProjectType::Rust,
+            git: GitState::None,
+        }
+    }
+
+    pub fn with_tasks(mut self, tasks: usize) -> Self {
+        self.tasks = tasks;
+        self
+    }
+
+    pub fn with_style(mut self, style: TaskStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub fn with_language(mut self, language: ProjectType) -> Self {
+        self.language = language;
+        self
+    }
+
+    pub fn with_git(mut self, git: GitState) -> Self {
+        self.git = git;
+        self
+    }
+}
+
+/// `count` projects cycling through the stages, task styles and languages,
+/// with sizes and git states drawn from `seed`
+///
+/// The same arguments always give the same projects.
+pub fn varied(count: usize, seed: u64) -> Vec<ProjectSpec> {
+    const STAGES: [Stage; 9] = [
+        Stage::Bootstrap, Stage::Specify, Stage::Clarify, Stage::Plan, Stage::Tasks,
+        Stage::Analyze, Stage::Implement, Stage::Test, Stage::Review,
+    ];
+    const LANGUAGES: [ProjectType; 4] = [ProjectType::Rust, ProjectType::Node, ProjectType::Python, ProjectType::Go];
+    let git = [GitState::None, GitState::Clean, GitState::Dirty];
+    let mut state = seed;
+    let mut next = move || {
+        // 64-bit LCG (Knuth's MMIX constants); only the high bits are used
+        state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        (state >> 33) as usize
+    };
+    (0..count)
+        .map(|i| ProjectSpec {
+            name: format!("project-{:04}", i),
+            stage: STAGES[i % STAGES.len()].clone(),
+            tasks: 2 + next() % 30,
+            style: TaskStyle::ALL[(i / STAGES.len()) % TaskStyle::ALL.len()],
+            language: LANGUAGES[i % LANGUAGES.len()].clone(),
+            git: git[next() % git.len()],
+        })
+        .collect()
+}
+
+/// Generated projects under a root that is deleted on drop
+pub struct SyntheticPortfolio {
+    root: PathBuf,
+    keep: bool,
+}
+
+impl SyntheticPortfolio {
+    /// Generate the projects under `root`, which must not exist yet
+    pub fn create(root: &Path, projects: &[ProjectSpec]) -> Result<Self> {
+        if root.exists() {
+            return Err(SKMError::ConfigError { message: format!("{} already exists", root.display()) });
+        }
+        fs::create_dir_all(root)?;
+        let portfolio = SyntheticPortfolio { root: root.to_path_buf(), keep: false };
+        let mut test_runs = TestRunStore::default();
+        for spec in projects {
+            let dir = root.join(&spec.name);
+            if let Some(run) = write_project(&dir, spec)? {
+                test_runs.projects.insert(pipeline::project_id(&dir), run);
+            }
+        }
+        if !test_runs.projects.is_empty() {
+            test_runs.save(root)?;
+        }
+        Ok(portfolio)
+    }
+
+    /// Generate the projects in a fresh directory under the system temp
+    /// directory, named after `name` and the process
+    pub fn temp(name: &str, projects: &[ProjectSpec]) -> Result<Self> {
+        let root = std::env::temp_dir().join(format!("skm-testkit-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        Self::create(&root, projects)
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Leave the root on disk after drop and return it
+    pub fn keep(mut self) -> PathBuf {
+        self.keep = true;
+        self.root.clone()
+    }
+
+    /// Discover and process the projects with the default config, without
+    /// writing caches, history or fingerprints
+    pub fn scan(&self, options: ScanOptions) -> Result<PortfolioStatus> {
+        let start = Instant::now();
+        let mut ctx = ScanContext::with_config(GlobalConfig::default(), &self.root, options)?;
+        let paths = ProjectScanner::new(self.root.clone(), ctx.config.scan_depth)
+            .with_gitignore(ctx.config.discovery.gitignore)
+            .find_projects();
+        ctx.assign_ids(&paths);
+        let discover_ms = start.elapsed().as_millis() as u64;
+
+        let mut projects = Vec::new();
+        let mut errors = Vec::new();
+        let mut timings = Vec::new();
+        for result in pipeline::process_projects(&paths, &ctx) {
+            match result {
+                Ok((project, timing)) => {
+                    timings.push(timing);
+                    projects.push(project);
+                }
+                Err(e) => errors.push(e),
+            }
+        }
+        let mut scan_stats = ScanStats {
+            directories_scanned: paths.len() as u32,
+            projects_found: projects.len() as u32,
+            scan_time_ms: start.elapsed().as_millis() as u64,
+            errors,
+            discover_ms,
+            slowest: Vec::new(),
+        };
+        scan_stats.set_slowest(timings);
+        Ok(PortfolioStatus {
+            generated_at: Utc::now(),
+            scan_stats,
+            summary: pipeline::summarize(&projects, &ctx.config.workflow),
+            projects,
+        })
+    }
+}
+
+impl Drop for SyntheticPortfolio {
+    fn drop(&mut self) {
+        if !self.keep {
+            let _ = fs::remove_dir_all(&self.root);
+        }
+    }
+}
+
+fn write(path: &Path, content: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, content)?;
+    Ok(())
+}
+
+/// Write one project; returns the test run to record for it, if any
+fn write_project(dir: &Path, spec: &ProjectSpec) -> Result<Option<TestRun>> {
+    let reached = |stage: Stage| spec.stage >= stage;
+    let feature = dir.join("specs/001-core");
+    fs::create_dir_all(dir.join(".specify/memory"))?;
+    write_manifest(dir, spec)?;
+
+    if reached(Stage::Specify) {
+        write(&dir.join(".specify/memory/constitution.md"),
+            &format!("# {} Constitution\n\n## Core Principles\n\n### I. Tests first\n", spec.name))?;
+    }
+    if reached(Stage::Clarify) {
+        let mut content = format!("# Feature: {}\n\n## Requirements\n\n- FR-001: the core works\n", spec.name);
+        if reached(Stage::Plan) {
+            content.push_str("\n## Clarifications\n\n- Q: Scope? → A: The core only\n");
+        }
+        write(&feature.join("spec.md"), &content)?;
+    }
+    if reached(Stage::Tasks) {
+        write(&feature.join("plan.md"), "# Implementation Plan\n\n## Technical Context\n\nOne module.\n")?;
+    }
+    if reached(Stage::Analyze) {
+        let total = spec.tasks.max(2);
+        let done = match spec.stage {
+            Stage::Analyze => 0,
+            Stage::Implement => total / 2,
+            _ => total,
+        };
+        let mut content = String::new();
+        if spec.stage == Stage::Done {
+            content.push_str("---\nstatus: done\n---\n\n");
+        }
+        content.push_str("# Tasks\n\n");
+        for number in 1..=total {
+            content.push_str(&spec.style.line(number, number <= done));
+            content.push('\n');
+        }
+        if reached(Stage::Implement) {
+            content.push_str("\n## Analysis\n\nNo inconsistencies found.\n");
+        }
+        write(&feature.join("tasks.md"), &content)?;
+    }
+    if reached(Stage::Test) {
+        write_source(dir, &spec.language)?;
+    }
+
+    commit(dir, spec.git)?;
+    Ok((spec.stage == Stage::Review).then(|| TestRun {
+        at: Utc::now(),
+        command: "cargo test".to_string(),
+        success: true,
+        passed: Some(spec.tasks as u32),
+        failed: Some(0),
+        duration_ms: 1000,
+        log: dir.join(".skm/logs/test.log"),
+    }))
+}
+
+fn write_manifest(dir: &Path, spec: &ProjectSpec) -> Result<()> {
+    match spec.language {
+        ProjectType::Rust => write(&dir.join("Cargo.toml"),
+            &format!("[package]\nname = \"{}\"\nversion = \"0.1.0\"\nlicense = \"MIT\"\n", spec.name)),
+        ProjectType::Node => write(&dir.join("package.json"),
+            &format!("{{\n  \"name\": \"{}\",\n  \"license\": \"MIT\"\n}}\n", spec.name)),
+        ProjectType::Python => write(&dir.join("pyproject.toml"),
+            &format!("[project]\nname = \"{}\"\nlicense = \"MIT\"\n", spec.name)),
+        ProjectType::Go => write(&dir.join("go.mod"), &format!("module example.com/{}\n\ngo 1.22\n", spec.name)),
+        ProjectType::Generic => fs::create_dir_all(dir.join("src")).map_err(SKMError::from),
+        ProjectType::Unknown => Ok(()),
+    }
+}
+
+/// Enough code in the language's layout to count as an implementation
+fn write_source(dir: &Path, language: &ProjectType) -> Result<()> {
+    let functions = |template: &str| (1..=24).map(|n| template.replace("{n}", &n.to_string())).collect::<Vec<_>>().join("\n");
+    match language {
+        ProjectType::Rust => write(&dir.join("src/lib.rs"), &functions("pub fn part_{n}() -> u32 { {n} }")),
+        ProjectType::Node => write(&dir.join("src/index.js"), &functions("export const part{n} = () => {n};")),
+        ProjectType::Python => write(&dir.join("core/__init__.py"), &functions("def part_{n}(): return {n}")),
+        ProjectType::Go => write(&dir.join("core.go"), &format!("package core\n\n{}", functions("func Part{n}() int { return {n} }"))),
+        ProjectType::Generic => write(&dir.join("src/core.c"), &functions("int part_{n}(void) { return {n}; }")),
+        ProjectType::Unknown => write(&dir.join("core.sh"), &functions("part_{n}() { echo {n}; }")),
+    }
+}
+
+#[cfg(feature = "git")]
+fn commit(dir: &Path, state: GitState) -> Result<()> {
+    if state == GitState::None {
+        return Ok(());
+    }
+    let repo = git2::Repository::init(dir)?;
+    let mut index = repo.index()?;
+    index.add_all(["*"], git2::IndexAddOption::DEFAULT, None)?;
+    index.write()?;
+    let tree = repo.find_tree(index.write_tree()?)?;
+    let signature = git2::Signature::now("skm testkit", "testkit@localhost")?;
+    repo.commit(Some("HEAD"), &signature, &signature, "Initial commit", &tree, &[])?;
+    if state == GitState::Merged {
+        let head = repo.head()?.peel_to_commit()?;
+        repo.branch("001-core", &head, false)?;
+        repo.set_head("refs/heads/001-core")?;
+    }
+    if state == GitState::Dirty {
+        let spec = dir.join("specs/001-core/spec.md");
+        let file = if spec.exists() { spec } else { dir.join(".specify/memory/notes.md") };
+        let mut content = fs::read_to_string(&file).unwrap_or_default();
+        content.push_str("\nUncommitted edit.\n");
+        fs::write(file, content)?;
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "git"))]
+fn commit(_dir: &Path, state: GitState) -> Result<()> {
+    match state {
+        GitState::None => Ok(()),
+        GitState::Clean | GitState::Dirty | GitState::Merged => Err(SKMError::FeatureDisabled { feature: "git" }),
+    }
+}
+
+/// The parts of a scan that are the same on every run: per project its
+/// stage, tasks, git state, flags, rounded priority and next action, and
+/// the summary counts, without timestamps, paths or timings
+pub fn snapshot(portfolio: &PortfolioStatus) -> Value {
+    let mut projects: Vec<Value> = portfolio.projects.iter()
+        .map(|p| json!({
+            "id": p.id,
+            "type": format!("{:?}", p.project_type),
+            "stage": p.stage,
+            "tasks": { "total": p.tasks.total, "completed": p.tasks.completed, "blocked": p.tasks.blocked },
+            "git": { "repo": p.git.is_repo, "clean": p.git.clean },
+            "risk": p.next.risk_level,
+            "needs_attention": p.needs_attention,
+            "requires_human": p.requires_human,
+            "priority": (p.priority * 10.0).round() / 10.0,
+            "next": p.next.command,
+        }))
+        .collect();
+    projects.sort_by(|a, b| a["id"].as_str().cmp(&b["id"].as_str()));
+    let summary = &portfolio.summary;
+    json!({
+        "projects": projects,
+        "summary": {
+            "total_projects": summary.total_projects,
+            "needs_attention": summary.needs_attention,
+            "by_stage": summary.by_stage,
+        },
+        "errors": portfolio.scan_stats.errors.iter().map(|e| e.message.clone()).collect::<Vec<_>>(),
+    })
+}
+
+/// Compare `actual` with `<dir>/<name>.json`, panicking with both on a
+/// mismatch
+///
+/// With [`UPDATE_SNAPSHOTS_ENV`] set to `1`, the snapshot is written
+/// instead; review the diff before committing it. A missing snapshot fails
+/// like a mismatch, so a test cannot pass by creating its own expectation.
+pub fn assert_snapshot(dir: &Path, name: &str, actual: &Value) {
+    let path = dir.join(format!("{}.json", name));
+    let rendered = format!("{}\n", serde_json::to_string_pretty(actual).expect("snapshot serializes"));
+    if std::env::var(UPDATE_SNAPSHOTS_ENV).is_ok_and(|value| value == "1") {
+        write(&path, &rendered).unwrap_or_else(|e| panic!("cannot write {}: {}", path.display(), e));
+        return;
+    }
+    if !path.exists() {
+        panic!("{} does not exist (set {}=1 to create it)\n--- actual\n{}", path.display(), UPDATE_SNAPSHOTS_ENV, rendered);
+    }
+    let expected = fs::read_to_string(&path).unwrap_or_else(|e| panic!("cannot read {}: {}", path.display(), e));
+    if expected != rendered {
+        panic!("{} does not match the scan (set {}=1 to update it)\n--- expected\n{}\n--- actual\n{}",
+            path.display(), UPDATE_SNAPSHOTS_ENV, expected, rendered);
+    }
+}
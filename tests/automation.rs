@@ -0,0 +1,35 @@
+//! Autopilot gating: automation level, stage cap and `auto_approve`
+
+use skm::autopilot::actions::{decide, headless_command_line, Decision};
+use skm::{AutomationLevel, NextAction};
+
+fn action(risk_level: AutomationLevel) -> NextAction {
+    NextAction {
+        command: "/speckit.implement".to_string(),
+        description: String::new(),
+        automated: true,
+        risk_level,
+        launch: Vec::new(),
+    }
+}
+
+#[test]
+fn approval_lifts_the_project_level_but_not_the_stage_cap() {
+    let risky = action(AutomationLevel::L2);
+    assert!(matches!(decide(&risky, &AutomationLevel::L1, None, false, false), Decision::Skip(_)));
+    assert_eq!(decide(&risky, &AutomationLevel::L1, None, true, false), Decision::Run);
+    assert_eq!(decide(&risky, &AutomationLevel::L1, Some(&AutomationLevel::L3), true, true), Decision::DryRun);
+
+    let Decision::Skip(reason) = decide(&risky, &AutomationLevel::L1, Some(&AutomationLevel::L1), true, false) else {
+        panic!("an approved command ran above the stage cap");
+    };
+    assert!(reason.contains("stage cap"), "{}", reason);
+}
+
+#[test]
+fn headless_runs_use_the_project_agent_command() {
+    assert_eq!(headless_command_line(None, "/speckit.plan"), "claude -p /speckit.plan");
+    assert_eq!(headless_command_line(Some("  "), "/speckit.plan"), "claude -p /speckit.plan");
+    assert_eq!(headless_command_line(Some("codex exec"), "/speckit.plan"), "codex exec /speckit.plan");
+    assert_eq!(headless_command_line(Some("aider --message {command} --yes"), "/speckit.plan"), "aider --message /speckit.plan --yes");
+}
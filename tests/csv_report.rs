@@ -0,0 +1,56 @@
+//! CSV and TSV reports for spreadsheet import
+
+#![cfg(feature = "testkit")]
+
+use std::path::PathBuf;
+use skm::reporter::{CSV_COLUMNS, Delimited, write_delimited_view};
+use skm::scanner::pipeline::{GitMode, ScanOptions};
+use skm::testkit::{ProjectSpec, SyntheticPortfolio};
+use skm::view::PortfolioView;
+use skm::{HumanRequirement, PortfolioStatus, Stage};
+
+fn portfolio() -> PortfolioStatus {
+    let specs = [ProjectSpec::new("api", Stage::Implement).with_tasks(4), ProjectSpec::new("web", Stage::Plan)];
+    let portfolio = SyntheticPortfolio::temp("csv-report", &specs).unwrap();
+    let mut scan = portfolio.scan(ScanOptions { git: GitMode::Skip, ..ScanOptions::default() }).unwrap();
+    let api = scan.projects.iter_mut().find(|p| p.id == "api").unwrap();
+    api.path = PathBuf::from("/work/clients, \"big\"/api");
+    api.priority = 90.0;
+    api.git.branch = Some("feature/x".to_string());
+    api.requires_human = vec![HumanRequirement::Review, HumanRequirement::Input];
+    api.next.command = "=HYPERLINK(\"http://example.com\")\nthen\trun".to_string();
+    scan
+}
+
+fn render(status: &PortfolioStatus, format: Delimited) -> String {
+    let mut out = Vec::new();
+    write_delimited_view(&PortfolioView::new(status), format, &mut out).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn csv_quotes_separators_quotes_and_line_breaks() {
+    let status = portfolio();
+    let csv = render(&status, Delimited::Csv);
+    let mut lines = csv.split('\n');
+    assert_eq!(lines.next().unwrap(), CSV_COLUMNS.join(","));
+    let api = lines.next().unwrap();
+    assert!(api.starts_with("api,\"/work/clients, \"\"big\"\"/api\",implement,90.0,2,4,0,feature/x,\"Review, Input\",false,"), "{}", api);
+    // The next action spans two lines inside one quoted field, defused as a formula
+    assert_eq!(lines.next().unwrap(), "then\trun\"");
+    assert!(api.ends_with(",\"'=HYPERLINK(\"\"http://example.com\"\")"), "{}", api);
+    assert!(lines.next().unwrap().starts_with("web,"));
+}
+
+#[test]
+fn tsv_keeps_every_project_on_one_line() {
+    let status = portfolio();
+    let tsv = render(&status, Delimited::Tsv);
+    let lines: Vec<&str> = tsv.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert_eq!(lines[0].split('\t').count(), CSV_COLUMNS.len());
+    let api: Vec<&str> = lines[1].split('\t').collect();
+    assert_eq!(api.len(), CSV_COLUMNS.len());
+    assert_eq!(api[1], "/work/clients, \"big\"/api");
+    assert_eq!(api[10], "'=HYPERLINK(\"http://example.com\") then run");
+}
@@ -0,0 +1,110 @@
+//! Stages defined under `[[workflow.stages]]`, between review and done
+
+use std::collections::BTreeMap;
+use skm::meta::config::{CustomStage, WorkflowConfig};
+use skm::{AutomationLevel, Stage};
+
+fn custom(name: &str, when: &str) -> CustomStage {
+    CustomStage {
+        name: name.to_string(),
+        when: when.to_string(),
+        command: "Run the {stage} checklist for {id}".to_string(),
+        description: String::new(),
+        automated: false,
+        risk_level: AutomationLevel::L1,
+        human: true,
+    }
+}
+
+#[test]
+fn unknown_stages_round_trip_as_strings() {
+    let stage: Stage = serde_json::from_str("\"security-review\"").unwrap();
+    assert_eq!(stage, Stage::Custom("security-review".to_string()));
+    assert_eq!(serde_json::to_string(&stage).unwrap(), "\"security-review\"");
+    assert_eq!(format!("{:?}", stage), "SecurityReview");
+
+    // Built-in names keep their old spellings
+    assert_eq!(serde_json::from_str::<Stage>("\"Implement\"").unwrap(), Stage::Implement);
+    assert_eq!(serde_json::to_string(&Stage::Implement).unwrap(), "\"implement\"");
+
+    let counts: BTreeMap<Stage, u32> = serde_json::from_str(r#"{"review": 1, "rollout": 2, "done": 3}"#).unwrap();
+    assert_eq!(serde_json::to_string(&counts).unwrap(), r#"{"review":1,"rollout":2,"done":3}"#);
+}
+
+#[test]
+fn custom_stages_sort_between_review_and_done() {
+    let rollout = Stage::Custom("rollout".to_string());
+    assert!(Stage::Review < rollout && rollout < Stage::Done);
+    assert_eq!("SecurityReview".parse::<Stage>().unwrap(), Stage::Custom("security-review".to_string()));
+    assert_eq!("TEST".parse::<Stage>().unwrap(), Stage::Test);
+    assert!("security review!".parse::<Stage>().is_err());
+}
+
+#[test]
+fn workflow_config_rejects_clashing_names_and_bad_rules() {
+    let config = |stages: Vec<CustomStage>| WorkflowConfig { stages, ..WorkflowConfig::default() };
+    assert!(config(vec![custom("security-review", "custom.security!=approved"), custom("rollout", "custom.rollout:pending")])
+        .validate().is_ok());
+    assert!(config(vec![custom("review", "done")]).validate().is_err());
+    assert!(config(vec![custom("SecurityReview", "done")]).validate().is_err());
+    assert!(config(vec![custom("rollout", "done"), custom("rollout", "human")]).validate().is_err());
+    assert!(config(vec![custom("rollout", "priority>")]).validate().is_err());
+}
+
+#[cfg(feature = "testkit")]
+#[test]
+fn projects_wait_in_custom_stages_until_their_rules_stop_matching() {
+    use std::fs;
+    use skm::meta::state::ProjectMetaStore;
+    use skm::scanner::pipeline::{GitMode, ScanOptions};
+    use skm::testkit::{ProjectSpec, SyntheticPortfolio};
+    use skm::HumanRequirement;
+
+    let specs = [
+        ProjectSpec::new("unreviewed", Stage::Review),
+        ProjectSpec::new("rolling-out", Stage::Review),
+        ProjectSpec::new("finished", Stage::Review),
+        ProjectSpec::new("testing", Stage::Test),
+    ];
+    let portfolio = SyntheticPortfolio::temp("custom-stages", &specs).unwrap();
+    let workflow = r#"
+[[workflow.stages]]
+name = "security-review"
+when = "custom.security!=approved"
+command = "Review {id} for security issues"
+human = true
+
+[[workflow.stages]]
+name = "rollout"
+when = "custom.rollout:pending"
+command = "Roll out {id}"
+automated = true
+"#;
+    for spec in &specs {
+        fs::create_dir_all(portfolio.root().join(&spec.name).join(".skm")).unwrap();
+        fs::write(portfolio.root().join(&spec.name).join(".skm/config.toml"), workflow).unwrap();
+    }
+    let mut meta = ProjectMetaStore::default();
+    meta.set_value("rolling-out", "custom.security", "approved".to_string()).unwrap();
+    meta.set_value("rolling-out", "custom.rollout", "pending".to_string()).unwrap();
+    meta.set_value("finished", "custom.security", "approved".to_string()).unwrap();
+    meta.save(portfolio.root()).unwrap();
+    let scan = portfolio.scan(ScanOptions { git: GitMode::Skip, ..ScanOptions::default() }).unwrap();
+    assert!(scan.scan_stats.errors.is_empty(), "{:?}", scan.scan_stats.errors);
+
+    let project = |id: &str| scan.projects.iter().find(|p| p.id == id).unwrap();
+    let unreviewed = project("unreviewed");
+    assert_eq!(unreviewed.stage, Stage::Custom("security-review".to_string()));
+    assert_eq!(unreviewed.next.command, "Review unreviewed for security issues");
+    assert!(unreviewed.requires_human.contains(&HumanRequirement::Review));
+    let rolling_out = project("rolling-out");
+    assert_eq!(rolling_out.stage, Stage::Custom("rollout".to_string()));
+    assert!(rolling_out.next.automated);
+    assert_eq!(project("finished").stage, Stage::Review);
+    // Custom stages only follow the built-in ladder
+    assert_eq!(project("testing").stage, Stage::Test);
+
+    let json = serde_json::to_value(&scan).unwrap();
+    assert!(json["projects"].as_array().unwrap().iter().any(|p| p["stage"] == "security-review"));
+    assert_eq!(json["summary"]["by_stage"]["rollout"], 1);
+}
@@ -0,0 +1,169 @@
+//! `skm doctor`: config validation and environment checks
+
+use std::fs;
+use std::path::Path;
+use skm::doctor::{CheckStatus, check_config, check_integrations, check_project_config, check_writable};
+use skm::meta::config::GlobalConfig;
+use skm::meta::config_file::{commented_default, unknown_keys};
+
+fn status_of<'a>(checks: &'a [skm::doctor::Check], name: &str) -> &'a skm::doctor::Check {
+    checks.iter().find(|c| c.name == name).unwrap_or_else(|| panic!("no check {} in {:?}", name, checks))
+}
+
+#[test]
+fn the_default_config_passes() {
+    let content = commented_default().unwrap();
+    assert_eq!(unknown_keys(&content).unwrap(), Vec::<String>::new());
+    let (checks, config) = check_config(Path::new("config.toml"), &content);
+    assert!(config.is_some());
+    assert!(checks.iter().all(|c| c.status == CheckStatus::Ok), "{:?}", checks);
+}
+
+#[test]
+fn syntax_errors_name_their_line() {
+    let content = commented_default().unwrap().replace("scan_depth = 5", "scan_depth = ");
+    let line = content.lines().position(|l| l.starts_with("scan_depth")).unwrap() + 1;
+    let (checks, config) = check_config(Path::new("config.toml"), &content);
+    assert!(config.is_none());
+    assert_eq!(checks.len(), 1);
+    assert_eq!(checks[0].status, CheckStatus::Error);
+    assert!(checks[0].detail.starts_with(&format!("line {}:", line)), "{}", checks[0].detail);
+    assert!(checks[0].fix.as_deref().unwrap().contains("skm config init --force"));
+
+    let (checks, _) = check_config(Path::new("config.toml"), "scan_depth = 3\n");
+    assert!(checks[0].detail.starts_with("missing field"), "{}", checks[0].detail);
+}
+
+#[test]
+fn misspelled_settings_and_unusable_values_are_reported() {
+    let content = commented_default().unwrap()
+        .replace("scan_depth = 5", "scan_depht = 5\nscan_depth = 5")
+        .replace("qdrant_url = \"http://localhost:6333\"", "qdrant_url = \"localhost:6333\"")
+        .replace("[weights]\n", "[weights]\nrsik = 1.0\n")
+        .replace("roots = []\n", "")
+        + "\n[[roots]]\nname = \"work\"\npath = \"/nonexistent/skm-doctor\"\ncolour = \"red\"\n";
+    assert_eq!(unknown_keys(&content).unwrap(), ["roots[0].colour", "scan_depht", "weights.rsik"]);
+
+    let (checks, config) = check_config(Path::new("config.toml"), &content);
+    assert!(config.is_some());
+    assert_eq!(status_of(&checks, "config.toml").status, CheckStatus::Ok);
+    assert_eq!(status_of(&checks, "weights.rsik").status, CheckStatus::Warning);
+    assert_eq!(status_of(&checks, "qdrant_url").status, CheckStatus::Error);
+    let root = status_of(&checks, "roots.work");
+    assert_eq!(root.status, CheckStatus::Warning);
+    assert!(root.detail.contains("/nonexistent/skm-doctor is not a directory"), "{}", root.detail);
+}
+
+#[test]
+fn label_is_a_name_only_for_roots() {
+    let content = commented_default().unwrap().replace("roots = []\n", "")
+        + "\n[[roots]]\nlabel = \"work\"\npath = \"/tmp\"\n"
+        + "\n[[workflow.stages]]\nname = \"security-review\"\nlabel = \"Security\"\nwhen = \"stage=review\"\ncommand = \"audit\"\n";
+    assert_eq!(unknown_keys(&content).unwrap(), ["workflow.stages[0].label"]);
+}
+
+#[test]
+fn invalid_custom_stages_fail() {
+    let content = commented_default().unwrap()
+        + "\n[[workflow.stages]]\nname = \"review\"\nwhen = \"stage=review\"\ncommand = \"ship\"\n";
+    let (checks, _) = check_config(Path::new("config.toml"), &content);
+    let check = status_of(&checks, "config.toml");
+    assert_eq!(check.status, CheckStatus::Error);
+    assert!(check.detail.contains("already a built-in stage"), "{}", check.detail);
+}
+
+#[test]
+fn project_configs_only_take_project_settings() {
+    let dir = std::env::temp_dir().join(format!("skm-doctor-project-{}", std::process::id()));
+    fs::create_dir_all(dir.join(".skm")).unwrap();
+    let config = GlobalConfig::default();
+    assert!(check_project_config(&config, "api", &dir).is_none());
+
+    fs::write(dir.join(".skm/config.toml"), "[weights]\nrisk = 40.0\n").unwrap();
+    assert_eq!(check_project_config(&config, "api", &dir).unwrap().status, CheckStatus::Ok);
+    fs::write(dir.join(".skm/config.toml"), "qdrant_url = \"http://elsewhere\"\n").unwrap();
+    let check = check_project_config(&config, "api", &dir).unwrap();
+    assert_eq!((check.name.as_str(), check.status), ("api .skm/config.toml", CheckStatus::Error));
+    assert!(check.detail.contains("qdrant_url cannot be set per project"), "{}", check.detail);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn skm_directories_must_be_writable_or_creatable() {
+    let dir = std::env::temp_dir().join(format!("skm-doctor-storage-{}", std::process::id()));
+    fs::create_dir_all(dir.join(".skm")).unwrap();
+    let existing = check_writable("scan root", &dir.join(".skm"));
+    assert_eq!(existing.status, CheckStatus::Ok);
+    assert!(existing.detail.ends_with("is writable"), "{}", existing.detail);
+    let missing = check_writable("root work", &dir.join("work/.skm"));
+    assert_eq!(missing.status, CheckStatus::Ok);
+    assert!(missing.detail.ends_with("will be created on first use"), "{}", missing.detail);
+    assert_eq!(fs::read_dir(&dir).unwrap().count(), 1, "the probe file is removed");
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn enabled_integrations_need_their_tokens() {
+    let mut config = GlobalConfig::default();
+    assert!(check_integrations(&config).iter().all(|c| c.status <= CheckStatus::Info));
+
+    config.github.enabled = true;
+    config.github.token_env = "SKM_DOCTOR_TEST_UNSET_TOKEN".to_string();
+    let checks = check_integrations(&config);
+    let github = status_of(&checks, "github");
+    assert_eq!(github.status, CheckStatus::Warning);
+    let fix = if cfg!(feature = "integrations") {
+        "export SKM_DOCTOR_TEST_UNSET_TOKEN=<token>"
+    } else {
+        "Rebuild with `cargo install skm --features integrations`"
+    };
+    assert_eq!(github.fix.as_deref(), Some(fix));
+    assert_eq!(status_of(&checks, "git").status,
+        if cfg!(feature = "git") { CheckStatus::Ok } else { CheckStatus::Info });
+}
+
+#[cfg(feature = "testkit")]
+#[test]
+fn the_portfolio_check_lists_unstable_projects() {
+    use skm::doctor::check_portfolio;
+    use skm::scanner::pipeline::{GitMode, ScanOptions};
+    use skm::testkit::{ProjectSpec, SyntheticPortfolio};
+    use skm::{Stage, UnstableField};
+
+    assert_eq!(check_portfolio(None)[0].detail, "no scan yet; run `skm scan`");
+    let specs = [ProjectSpec::new("api", Stage::Plan), ProjectSpec::new("web", Stage::Tasks)];
+    let portfolio = SyntheticPortfolio::temp("doctor", &specs).unwrap();
+    let mut status = portfolio.scan(ScanOptions { git: GitMode::Skip, ..ScanOptions::default() }).unwrap();
+    let checks = check_portfolio(Some(&status));
+    assert_eq!((checks.len(), checks[0].status), (1, CheckStatus::Ok));
+    assert_eq!(checks[0].detail, "2 project(s), none unstable");
+
+    let web = status.projects.iter_mut().find(|p| p.id == "web").unwrap();
+    web.unstable = vec![UnstableField { field: "stage".to_string(), previous: "plan".to_string(), current: "tasks".to_string() }];
+    let checks = check_portfolio(Some(&status));
+    assert_eq!(checks.len(), 1);
+    assert_eq!((checks[0].name.as_str(), checks[0].status), ("web", CheckStatus::Warning));
+    assert_eq!(checks[0].detail, "unstable across unchanged scans: stage: plan → tasks");
+}
+
+#[test]
+fn the_slowest_projects_are_listed_with_their_dominant_phase() {
+    use skm::doctor::check_scan_time;
+    use skm::{PortfolioStatus, ProjectTiming};
+
+    assert!(check_scan_time(None).is_empty());
+    let mut status: PortfolioStatus = serde_json::from_value(serde_json::json!({
+        "generated_at": "2026-10-01T00:00:00Z",
+        "scan_stats": { "directories_scanned": 2, "projects_found": 2, "scan_time_ms": 900, "errors": [] },
+        "summary": { "needs_attention": 0, "total_projects": 2, "by_stage": {}, "total_tasks": 0, "completed_tasks": 0, "avg_priority": 0.0 },
+        "projects": [],
+    })).unwrap();
+    status.scan_stats.set_slowest(vec![
+        ProjectTiming { id: "web".to_string(), tasks_ms: 40, total_ms: 60, ..ProjectTiming::default() },
+        ProjectTiming { id: "api".to_string(), git_ms: 700, analyze_ms: 100, total_ms: 820, ..ProjectTiming::default() },
+    ]);
+    let checks = check_scan_time(Some(&status));
+    let listed: Vec<(&str, &str)> = checks.iter().map(|c| (c.name.as_str(), c.detail.as_str())).collect();
+    assert_eq!(listed, [("api", "820ms, mostly git (700ms)"), ("web", "60ms, mostly tasks (40ms)")]);
+    assert!(checks.iter().all(|c| c.status == CheckStatus::Info));
+}
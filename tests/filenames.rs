@@ -0,0 +1,88 @@
+//! Projects whose directory names are unusual: duplicated, not UTF-8,
+//! very long or full of characters that are special in URLs
+#![cfg(feature = "testkit")]
+
+use std::fs;
+use std::path::PathBuf;
+use skm::reporter::write_html_view;
+use skm::scanner::pipeline::{GitMode, ScanOptions, project_id, unique_project_ids};
+use skm::testkit::{ProjectSpec, SyntheticPortfolio};
+use skm::view::PortfolioView;
+use skm::Stage;
+
+fn offline() -> ScanOptions {
+    ScanOptions { git: GitMode::Skip, ..ScanOptions::default() }
+}
+
+#[test]
+fn projects_sharing_a_name_get_distinct_ids() {
+    let specs = [
+        ProjectSpec::new("work/api", Stage::Plan),
+        ProjectSpec::new("home/api", Stage::Tasks),
+        ProjectSpec::new("api-2", Stage::Specify),
+    ];
+    let portfolio = SyntheticPortfolio::temp("duplicates", &specs).unwrap();
+    let scan = portfolio.scan(offline()).unwrap();
+
+    let id = |dir: &str| {
+        let path = portfolio.root().join(dir);
+        scan.projects.iter().find(|p| p.path == path).unwrap().id.clone()
+    };
+    // Path order decides, and a suffix never takes an existing name
+    assert_eq!(id("home/api"), "api");
+    assert_eq!(id("work/api"), "api-3");
+    assert_eq!(id("api-2"), "api-2");
+}
+
+#[test]
+fn ids_do_not_depend_on_the_order_paths_are_found_in() {
+    let paths: Vec<PathBuf> = ["/b/web", "/a/web", "/c/web"].iter().map(PathBuf::from).collect();
+    let reversed: Vec<PathBuf> = paths.iter().rev().cloned().collect();
+    let ids = unique_project_ids(&paths);
+    assert_eq!(ids, unique_project_ids(&reversed));
+    assert_eq!(ids[&PathBuf::from("/a/web")], "web");
+    assert_eq!(ids[&PathBuf::from("/b/web")], "web-2");
+    assert_eq!(ids[&PathBuf::from("/c/web")], "web-3");
+}
+
+#[cfg(unix)]
+#[test]
+fn names_that_are_not_utf8_are_percent_encoded() {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    assert_eq!(project_id(&PathBuf::from(OsStr::from_bytes(b"caf\xe9"))), "caf%E9");
+    assert_eq!(project_id(&PathBuf::from(OsStr::from_bytes(b"50%\xff"))), "50%25%FF");
+    assert_eq!(project_id(&PathBuf::from("50%")), "50%");
+
+    let portfolio = SyntheticPortfolio::temp("non-utf8", &[
+        ProjectSpec::new("a", Stage::Plan),
+        ProjectSpec::new("b", Stage::Plan),
+    ]).unwrap();
+    fs::rename(portfolio.root().join("a"), portfolio.root().join(OsStr::from_bytes(b"proj\xe9"))).unwrap();
+    fs::rename(portfolio.root().join("b"), portfolio.root().join(OsStr::from_bytes(b"proj\xe8"))).unwrap();
+    let scan = portfolio.scan(offline()).unwrap();
+
+    let mut ids: Vec<&str> = scan.projects.iter().map(|p| p.id.as_str()).collect();
+    ids.sort_unstable();
+    assert_eq!(ids, ["proj%E8", "proj%E9"]);
+    assert!(scan.scan_stats.errors.is_empty());
+}
+
+#[test]
+fn long_and_url_unsafe_names_link_to_their_directory() {
+    let long = "n".repeat(200);
+    let specs = [
+        ProjectSpec::new(&long, Stage::Plan),
+        ProjectSpec::new("my project #1?", Stage::Plan),
+    ];
+    let portfolio = SyntheticPortfolio::temp("long-names", &specs).unwrap();
+    let scan = portfolio.scan(offline()).unwrap();
+    assert!(scan.projects.iter().any(|p| p.id == long));
+
+    let mut html = Vec::new();
+    write_html_view(&PortfolioView::new(&scan), &mut html).unwrap();
+    let html = String::from_utf8(html).unwrap();
+    assert!(html.contains("/my%20project%20%231%3F\""), "{}", html);
+    assert!(html.contains(&format!("/{}\"", long)));
+}
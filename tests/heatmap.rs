@@ -0,0 +1,59 @@
+//! The risk heat map of projects × risk signals in markdown and HTML reports
+
+#![cfg(feature = "testkit")]
+
+use skm::analyzer::heatmap::{RiskHeatMap, RiskSignal};
+use skm::reporter::{generate_markdown_report, write_html_view};
+use skm::scanner::pipeline::{GitMode, ScanOptions};
+use skm::testkit::{ProjectSpec, SyntheticPortfolio};
+use skm::view::PortfolioView;
+use skm::{PortfolioStatus, Stage};
+
+fn portfolio() -> PortfolioStatus {
+    let specs = [
+        ProjectSpec::new("alpha", Stage::Implement),
+        ProjectSpec::new("beta", Stage::Implement),
+        ProjectSpec::new("gamma", Stage::Implement),
+        ProjectSpec::new("delta", Stage::Implement),
+    ];
+    let portfolio = SyntheticPortfolio::temp("heatmap", &specs).unwrap();
+    let mut scan = portfolio.scan(ScanOptions { git: GitMode::Skip, ..ScanOptions::default() }).unwrap();
+    for project in &mut scan.projects {
+        match project.id.as_str() {
+            "alpha" => {
+                project.git.behind = 3;
+                project.tasks.blocked = 1;
+            }
+            "beta" => project.git.behind = 1,
+            _ => {}
+        }
+    }
+    scan
+}
+
+#[test]
+fn half_the_portfolio_behind_upstream_is_systemic() {
+    let scan = portfolio();
+    let map = RiskHeatMap::new(&scan.projects);
+    assert_eq!(map.projects, 4);
+    assert_eq!(map.rows.len(), 2);
+    assert_eq!(map.rows[0].0.id, "alpha");
+    assert_eq!(map.share(RiskSignal::Behind), 0.5);
+    assert_eq!(map.systemic(), vec![(RiskSignal::Behind, 2)]);
+}
+
+#[test]
+fn reports_render_the_heat_map() {
+    let scan = portfolio();
+    let markdown = generate_markdown_report(&scan);
+    assert!(markdown.contains("## Risk Heat Map"), "{}", markdown);
+    assert!(markdown.contains("2 of 4 projects have behind upstream"), "{}", markdown);
+    assert!(markdown.contains("2 (50%)"), "{}", markdown);
+
+    let mut html = Vec::new();
+    write_html_view(&PortfolioView::new(&scan), &mut html).unwrap();
+    let html = String::from_utf8(html).unwrap();
+    assert!(html.contains("<table class=\"heatmap\">"));
+    assert!(html.contains("Systemic: 2 of 4 projects have behind upstream"));
+    assert_eq!(html.matches("class=\"hot\"").count(), 3);
+}
@@ -0,0 +1,51 @@
+//! Leases under `.skm/locks`: one holder at a time, expired ones are taken over
+
+use std::fs;
+use std::sync::Barrier;
+use chrono::Duration;
+use skm::autopilot::lease::{self, LeaseScope};
+
+fn root(name: &str) -> std::path::PathBuf {
+    let root = std::env::temp_dir().join(format!("skm-leases-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&root).unwrap();
+    root
+}
+
+#[test]
+fn concurrent_attempts_take_the_lease_once() {
+    let root = root("concurrent");
+    let barrier = Barrier::new(8);
+    let taken = std::thread::scope(|scope| {
+        let attempts: Vec<_> = (0..8).map(|_| scope.spawn(|| {
+            barrier.wait();
+            lease::acquire(&root, &LeaseScope::Portfolio, "autopilot", Duration::minutes(5)).ok()
+        })).collect();
+        let guards: Vec<_> = attempts.into_iter().filter_map(|attempt| attempt.join().unwrap()).collect();
+        guards.len()
+    });
+    assert_eq!(taken, 1);
+    assert!(lease::list(&root).unwrap().is_empty(), "released on drop");
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn an_expired_lease_is_taken_over_once() {
+    let root = root("expired");
+    let scope = LeaseScope::Project("api".to_string());
+    std::mem::forget(lease::acquire(&root, &scope, "crashed", Duration::seconds(-1)).unwrap());
+
+    let barrier = Barrier::new(8);
+    let holders: Vec<String> = std::thread::scope(|s| {
+        let attempts: Vec<_> = (0..8).map(|_| s.spawn(|| {
+            barrier.wait();
+            lease::acquire(&root, &scope, "autopilot", Duration::minutes(5)).ok()
+        })).collect();
+        let guards: Vec<_> = attempts.into_iter().filter_map(|attempt| attempt.join().unwrap()).collect();
+        guards.iter().map(|guard| guard.lease().holder.clone()).collect()
+    });
+    assert_eq!(holders, ["autopilot"]);
+    let files: Vec<_> = fs::read_dir(root.join(".skm/locks")).unwrap().collect();
+    assert!(files.is_empty(), "no staged or takeover files are left: {:?}", files);
+    fs::remove_dir_all(&root).unwrap();
+}
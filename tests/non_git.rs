@@ -0,0 +1,147 @@
+//! End-to-end analysis of projects that are not git repositories
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use chrono::{DateTime, Utc};
+use skm::meta::snapshots::SnapshotStore;
+use skm::scanner::pipeline::{self, GitMode, ScanContext, ScanOptions};
+use skm::{HumanRequirement, Stage};
+
+/// A scratch project directory that is removed on drop
+struct Fixture {
+    root: PathBuf,
+}
+
+impl Fixture {
+    fn new(name: &str) -> Self {
+        let root = std::env::temp_dir().join(format!("skm-test-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+        Self { root }
+    }
+
+    fn write(&self, relative: &str, content: &str) -> PathBuf {
+        let path = self.root.join(relative);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    /// A project with constitution, spec, plan and half-finished tasks
+    fn spec_kit_project(name: &str) -> Self {
+        let fixture = Self::new(name);
+        fixture.write(".specify/memory/constitution.md", "# Constitution\n\n## Principles\n");
+        fixture.write("specs/001-login/spec.md", "# Login\n\n## Requirements\n- FR-001: users can log in\n");
+        fixture.write("specs/001-login/plan.md", "# Plan\n\n## Design\n");
+        fixture.write("specs/001-login/tasks.md", "# Tasks\n\n- [x] T001: model\n- [ ] T002: endpoint\n");
+        fixture
+    }
+}
+
+impl Drop for Fixture {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.root);
+    }
+}
+
+fn set_mtime(path: &Path, age: Duration) {
+    let file = fs::File::options().write(true).open(path).unwrap();
+    file.set_modified(SystemTime::now() - age).unwrap();
+}
+
+fn context(git: GitMode) -> ScanContext {
+    ScanContext {
+        options: ScanOptions { git, ..Default::default() },
+        ..Default::default()
+    }
+}
+
+const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+#[test]
+fn non_git_project_is_analyzed_without_git_signals() {
+    let fixture = Fixture::spec_kit_project("analyze");
+    let (project, _) = pipeline::process_project(&fixture.root, &context(GitMode::Sync)).unwrap();
+
+    assert!(!project.git.is_repo);
+    // Builds without git report every project as skipped
+    assert_eq!(project.git.skipped, !cfg!(feature = "git"));
+    assert_eq!(project.tasks.total, 2);
+    assert_eq!(project.tasks.completed, 1);
+    assert_eq!(project.stage, Stage::Implement);
+    assert!(!project.requires_human.contains(&HumanRequirement::Fix));
+    assert!(project.priority.is_finite());
+}
+
+#[test]
+fn missing_git_never_counts_as_uncommitted_changes() {
+    let fixture = Fixture::spec_kit_project("risk");
+    let (project, _) = pipeline::process_project(&fixture.root, &context(GitMode::Sync)).unwrap();
+
+    let mut dirty_outside_git = project.git.clone();
+    dirty_outside_git.clean = false;
+    let risk = skm::analyzer::calculate_risk(&project.stage, &dirty_outside_git, &project.tasks, false, false);
+    let human = skm::analyzer::detect_human_requirements(&project.stage, &dirty_outside_git, &project.tasks, 0);
+
+    assert_eq!(risk, 0);
+    assert!(!human.contains(&HumanRequirement::Fix));
+}
+
+#[test]
+fn staleness_falls_back_to_directory_mtimes() {
+    let fixture = Fixture::spec_kit_project("mtime");
+    for artifact in [
+        ".specify/memory/constitution.md",
+        "specs/001-login/spec.md",
+        "specs/001-login/plan.md",
+        "specs/001-login/tasks.md",
+    ] {
+        set_mtime(&fixture.root.join(artifact), 30 * DAY);
+    }
+    let source = fixture.write("src/main.rs", "fn main() {}\n");
+    set_mtime(&source, DAY);
+
+    let (project, _) = pipeline::process_project(&fixture.root, &context(GitMode::Sync)).unwrap();
+
+    let age = Utc::now().signed_duration_since(project.updated);
+    assert!(age.num_days() >= 1 && age.num_days() < 2, "updated {} days ago", age.num_days());
+}
+
+#[test]
+fn skipped_git_does_not_require_fix() {
+    let fixture = Fixture::spec_kit_project("skipped");
+    let (project, _) = pipeline::process_project(&fixture.root, &context(GitMode::Skip)).unwrap();
+
+    assert!(project.git.skipped);
+    assert!(!project.requires_human.contains(&HumanRequirement::Fix));
+}
+
+#[test]
+fn unchanged_artifact_hash_overrides_a_newer_mtime() {
+    let fixture = Fixture::spec_kit_project("hash");
+    let spec = fixture.root.join("specs/001-login/spec.md");
+    for artifact in [
+        ".specify/memory/constitution.md",
+        "specs/001-login/plan.md",
+        "specs/001-login/tasks.md",
+    ] {
+        set_mtime(&fixture.root.join(artifact), 30 * DAY);
+    }
+    set_mtime(&spec, 20 * DAY);
+
+    // Snapshot the spec as it was ten days ago
+    let ctx = context(GitMode::Sync);
+    let (project, _) = pipeline::process_project(&fixture.root, &ctx).unwrap();
+    let mut snapshots = SnapshotStore::default();
+    let ten_days_ago: DateTime<Utc> = (SystemTime::now() - 10 * DAY).into();
+    snapshots.record(&fixture.root, std::slice::from_ref(&project), ten_days_ago, 10).unwrap();
+
+    // Touch the spec without changing it, as a copy or sync would
+    set_mtime(&spec, Duration::ZERO);
+    let ctx = ScanContext { snapshots, ..ctx };
+    let (project, _) = pipeline::process_project(&fixture.root, &ctx).unwrap();
+
+    let age = Utc::now().signed_duration_since(project.updated);
+    assert!(age.num_days() >= 9, "updated {} days ago", age.num_days());
+}
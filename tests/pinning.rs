@@ -0,0 +1,65 @@
+//! `skm pin` and the `priority_override` / `priority_boost` metadata keys
+
+use skm::meta::state::ProjectMetaStore;
+
+#[test]
+fn manual_priority_keys_are_validated() {
+    let mut store = ProjectMetaStore::default();
+    store.set_value("api", "pinned", "true".to_string()).unwrap();
+    store.set_value("api", "priority_override", "80".to_string()).unwrap();
+    store.set_value("api", "priority_boost", "-12.5".to_string()).unwrap();
+    let meta = store.get_project("api").unwrap();
+    assert_eq!((meta.pinned, meta.priority_override, meta.priority_boost), (true, Some(80.0), Some(-12.5)));
+    assert_eq!(meta.get_value("priority_boost").unwrap().as_deref(), Some("-12.5"));
+
+    assert!(store.set_value("api", "priority_override", "-1".to_string()).is_err());
+    assert!(store.set_value("api", "priority_boost", "NaN".to_string()).is_err());
+    assert!(store.set_value("api", "pinned", "yes".to_string()).is_err());
+    store.set_value("api", "priority_override", String::new()).unwrap();
+    store.set_value("api", "pinned", "false".to_string()).unwrap();
+    let entries = store.get_project("api").unwrap().entries();
+    assert!(entries.iter().all(|(key, _)| key != "pinned" && key != "priority_override"), "{:?}", entries);
+}
+
+#[cfg(feature = "testkit")]
+#[test]
+fn pins_overrides_and_boosts_apply_on_the_next_scan() {
+    use skm::scanner::pipeline::{GitMode, ScanOptions};
+    use skm::testkit::{ProjectSpec, SyntheticPortfolio};
+    use skm::view::PortfolioView;
+    use skm::Stage;
+
+    let specs = [
+        ProjectSpec::new("api", Stage::Plan).with_tasks(4),
+        ProjectSpec::new("web", Stage::Plan).with_tasks(4),
+        ProjectSpec::new("old", Stage::Plan).with_tasks(4),
+        ProjectSpec::new("cli", Stage::Plan).with_tasks(4),
+    ];
+    let portfolio = SyntheticPortfolio::temp("pinning", &specs).unwrap();
+    let options = || ScanOptions { git: GitMode::Skip, ..ScanOptions::default() };
+    let before = portfolio.scan(options()).unwrap();
+    let score = |status: &skm::PortfolioStatus, id: &str| status.projects.iter().find(|p| p.id == id).unwrap().priority;
+
+    let mut store = ProjectMetaStore::load(portfolio.root()).unwrap();
+    store.set_value("web", "pinned", "true".to_string()).unwrap();
+    store.set_value("web", "priority_override", "0".to_string()).unwrap();
+    store.set_value("old", "priority_override", "0".to_string()).unwrap();
+    store.set_value("old", "priority_boost", "30".to_string()).unwrap();
+    store.set_value("cli", "priority_boost", "15".to_string()).unwrap();
+    store.save(portfolio.root()).unwrap();
+    let after = portfolio.scan(options()).unwrap();
+
+    let order: Vec<String> = PortfolioView::new(&after).sorted_by_priority().iter().map(|p| p.id.clone()).collect();
+    assert_eq!(order.first().map(String::as_str), Some("web"), "pinned projects come first: {:?}", order);
+    let project = |id: &str| after.projects.iter().find(|p| p.id == id).unwrap();
+    assert!(project("web").pinned && project("web").needs_attention);
+    assert_eq!((project("old").priority, project("old").needs_attention), (0.0, false));
+    let boosted = project("cli").priority_breakdown.as_ref().unwrap();
+    assert_eq!((boosted.boost, project("cli").priority), (Some(15.0), boosted.score + 15.0));
+    let parked = project("old").priority_breakdown.as_ref().unwrap();
+    assert_eq!((parked.priority_override, parked.score), (Some(0.0), score(&before, "old")));
+    let mut text = Vec::new();
+    skm::reporter::write_priority_explanation(project("old"), &mut text).unwrap();
+    let text = String::from_utf8(text).unwrap();
+    assert!(text.contains("priority_boost +30.0 in meta.json is ignored while priority_override is set"), "{}", text);
+}
@@ -0,0 +1,115 @@
+//! Prioritization schemes selected with `priority_strategy`
+
+use skm::analyzer::priority::{PriorityExpression, PriorityFactors, PriorityStrategy, PriorityWeights};
+
+fn factors() -> PriorityFactors {
+    PriorityFactors { needs_human: 1.0, risk: 2.0 / 3.0, staleness: 0.5, impact: 0.66, confidence: 0.5, effort: 0.0 }
+}
+
+#[test]
+fn weighted_is_the_default_and_keeps_the_original_formula() {
+    assert_eq!(PriorityStrategy::default(), PriorityStrategy::Weighted);
+    let calculator = PriorityStrategy::Weighted.calculator(&PriorityWeights::default());
+    let expected = 40.0 + 25.0 * 2.0 / 3.0 + 15.0 * 0.5 + 15.0 * 0.66 - 10.0 * 0.5;
+    assert!((calculator.score(&factors()) - expected).abs() < 1e-9);
+}
+
+#[test]
+fn wsjf_prefers_smaller_jobs_with_the_same_cost_of_delay() {
+    let wsjf = PriorityStrategy::Wsjf.calculator(&PriorityWeights::default());
+    let small = wsjf.score(&factors().with_open_tasks(2));
+    let large = wsjf.score(&factors().with_open_tasks(40));
+    assert!(small > large && large > 0.0, "{} {}", small, large);
+    assert!(wsjf.score(&PriorityFactors { impact: 1.0, staleness: 1.0, risk: 1.0, ..factors() }) <= 100.0);
+
+    let staleness_first = PriorityStrategy::StalenessFirst.calculator(&PriorityWeights::default());
+    let stale = PriorityFactors { staleness: 1.0, needs_human: 0.0, ..factors() };
+    assert!(staleness_first.score(&stale) > staleness_first.score(&factors()));
+}
+
+#[test]
+fn expressions_weigh_the_factors_freely() {
+    let expression: PriorityExpression = "50*staleness + 30 * (risk + needs_human) / 2 - 10*confidence".parse().unwrap();
+    let strategy = PriorityStrategy::Expression(expression);
+    let score = strategy.calculator(&PriorityWeights::default()).score(&factors());
+    assert!((score - (25.0 + 25.0 - 5.0)).abs() < 1e-9, "{}", score);
+    assert_eq!(strategy.to_string(), "50*staleness + 30 * (risk + needs_human) / 2 - 10*confidence");
+
+    let zero: PriorityExpression = "risk / effort".parse().unwrap();
+    assert_eq!(PriorityStrategy::Expression(zero).calculator(&PriorityWeights::default()).score(&factors()), 0.0);
+    for invalid in ["", "50*age", "risk +", "(risk", "risk $ 2", "2 risk"] {
+        assert!(invalid.parse::<PriorityStrategy>().is_err(), "{}", invalid);
+    }
+}
+
+#[test]
+fn strategies_are_written_as_strings() {
+    for name in ["weighted", "wsjf", "staleness-first", "-risk + 2*impact"] {
+        let strategy: PriorityStrategy = serde_json::from_str(&format!("\"{}\"", name)).unwrap();
+        assert_eq!(serde_json::to_string(&strategy).unwrap(), format!("\"{}\"", name));
+    }
+    assert_eq!("WSJF".parse::<PriorityStrategy>().unwrap(), PriorityStrategy::Wsjf);
+}
+
+#[test]
+fn additive_strategies_split_the_score_into_points() {
+    for strategy in [PriorityStrategy::Weighted, PriorityStrategy::StalenessFirst] {
+        let calculator = strategy.calculator(&PriorityWeights::default());
+        let points: f64 = calculator.terms(&factors()).iter().map(|term| term.points()).sum();
+        assert!((points - calculator.score(&factors())).abs() < 1e-9, "{}", strategy);
+    }
+    let weighted = PriorityStrategy::Weighted.calculator(&PriorityWeights::default()).terms(&factors());
+    assert_eq!(weighted.iter().find(|term| term.factor == "confidence").unwrap().points(), -5.0);
+    assert!(PriorityStrategy::Wsjf.calculator(&PriorityWeights::default()).terms(&factors()).is_empty());
+}
+
+#[cfg(feature = "testkit")]
+#[test]
+fn scans_record_why_a_project_got_its_priority() {
+    use skm::reporter::write_priority_explanation;
+    use skm::scanner::pipeline::{GitMode, ScanOptions};
+    use skm::testkit::{ProjectSpec, SyntheticPortfolio};
+    use skm::Stage;
+
+    let specs = [ProjectSpec::new("api", Stage::Implement).with_tasks(8), ProjectSpec::new("shipped", Stage::Done)];
+    let portfolio = SyntheticPortfolio::temp("priority-explain", &specs).unwrap();
+    let scan = portfolio.scan(ScanOptions { git: GitMode::Skip, ..ScanOptions::default() }).unwrap();
+
+    let project = |id: &str| scan.projects.iter().find(|p| p.id == id).unwrap();
+    let api = project("api");
+    let breakdown = api.priority_breakdown.as_ref().unwrap();
+    assert_eq!(breakdown.strategy, "weighted");
+    assert_eq!(breakdown.inputs.open_tasks, 4);
+    assert_eq!(breakdown.inputs.impact, 2);
+    assert!((breakdown.terms.iter().map(|term| term.points()).sum::<f64>() - api.priority).abs() < 1e-9);
+
+    let mut text = Vec::new();
+    write_priority_explanation(api, &mut text).unwrap();
+    let text = String::from_utf8(text).unwrap();
+    assert!(text.starts_with(&format!("api: priority {:.1} (weighted)", api.priority)), "{}", text);
+    assert!(text.contains("impact 2 of 3") && text.contains("4 open tasks") && text.contains("total"), "{}", text);
+
+    let mut text = Vec::new();
+    write_priority_explanation(project("shipped"), &mut text).unwrap();
+    assert!(String::from_utf8(text).unwrap().contains("Done projects score 0"));
+}
+
+#[cfg(feature = "testkit")]
+#[test]
+fn a_project_can_pick_its_own_strategy() {
+    use std::fs;
+    use skm::scanner::pipeline::{GitMode, ScanOptions};
+    use skm::testkit::{ProjectSpec, SyntheticPortfolio};
+    use skm::Stage;
+
+    let specs = [ProjectSpec::new("custom", Stage::Implement).with_tasks(10), ProjectSpec::new("default", Stage::Implement)];
+    let portfolio = SyntheticPortfolio::temp("priority-strategy", &specs).unwrap();
+    fs::create_dir_all(portfolio.root().join("custom/.skm")).unwrap();
+    fs::write(portfolio.root().join("custom/.skm/config.toml"), "priority_strategy = \"100*effort + 1\"\n").unwrap();
+    let scan = portfolio.scan(ScanOptions { git: GitMode::Skip, ..ScanOptions::default() }).unwrap();
+
+    let project = |id: &str| scan.projects.iter().find(|p| p.id == id).unwrap();
+    // Half of ten tasks are open: 5 / 20 of full effort
+    assert!((project("custom").priority - 26.0).abs() < 1e-9, "{}", project("custom").priority);
+    assert!(project("default").priority != 26.0);
+}
@@ -0,0 +1,29 @@
+//! `.skm/published.json`: which report each page last received
+
+use std::fs;
+use skm::publish::{PublishState, content_hash};
+
+#[test]
+fn timestamps_do_not_change_the_content_hash() {
+    let report = |time: &str| format!("# Portfolio Status\n\nGenerated: {}\n\n- **Scan Time**: {}\n- api: Plan\n", time, time);
+    assert_eq!(content_hash(&report("2025-01-06 10:00")), content_hash(&report("2025-01-07 09:30")));
+    assert_ne!(content_hash(&report("2025-01-06 10:00")), content_hash(&report("2025-01-06 10:00").replace("Plan", "Tasks")));
+}
+
+#[test]
+fn hashes_from_before_the_schema_version_are_dropped() {
+    let root = std::env::temp_dir().join(format!("skm-publish-state-{}", std::process::id()));
+    fs::create_dir_all(root.join(".skm")).unwrap();
+    fs::write(root.join(".skm/published.json"), r#"{"pages": {"notion:abc": "0123"}}"#).unwrap();
+    let state = PublishState::load(&root).unwrap();
+    assert!(state.pages.is_empty(), "{:?}", state.pages);
+
+    let mut state = PublishState::default();
+    state.record("notion:abc".to_string(), content_hash("report"));
+    state.save(&root).unwrap();
+    assert!(PublishState::load(&root).unwrap().unchanged("notion:abc", &content_hash("report")));
+
+    fs::write(root.join(".skm/published.json"), r#"{"schema_version": 99, "pages": {}}"#).unwrap();
+    assert!(PublishState::load(&root).is_err(), "files from a newer skm are refused");
+    fs::remove_dir_all(&root).unwrap();
+}
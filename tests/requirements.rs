@@ -0,0 +1,73 @@
+//! Human requirements are resolved when a scan no longer raises them
+
+use chrono::{DateTime, TimeZone, Utc};
+use skm::analyzer::requirements::{RequirementEpisode, reconcile, time_to_address};
+use skm::HumanRequirement::{Fix, Input, Review};
+
+fn at(day: u32, hour: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2025, 3, day, hour, 0, 0).unwrap()
+}
+
+#[test]
+fn scans_open_and_resolve_episodes() {
+    let mut episodes = Vec::new();
+    reconcile(&mut episodes, &[Review, Fix], at(3, 9));
+    reconcile(&mut episodes, &[Review, Fix], at(3, 12));
+    assert_eq!(episodes.len(), 2);
+
+    // The fix was committed; review is still waiting
+    reconcile(&mut episodes, &[Review], at(3, 15));
+    assert_eq!(episodes[1], RequirementEpisode { requirement: Fix, raised: at(3, 9), resolved: Some(at(3, 15)) });
+    assert_eq!(episodes[0].resolved, None);
+
+    // A new problem opens a new episode
+    reconcile(&mut episodes, &[Review, Fix], at(4, 9));
+    assert_eq!(episodes.len(), 3);
+    assert_eq!((episodes[2].raised, episodes[2].resolved), (at(4, 9), None));
+}
+
+#[test]
+fn mean_time_to_address_per_requirement_type() {
+    let episode = |requirement, raised, resolved| RequirementEpisode { requirement, raised, resolved };
+    let episodes = [
+        episode(Fix, at(1, 0), Some(at(1, 6))),
+        episode(Fix, at(2, 0), Some(at(2, 18))),
+        episode(Review, at(3, 0), Some(at(5, 0))),
+        episode(Review, at(8, 0), None),
+        // Addressed before the window
+        episode(Input, at(1, 0), Some(at(1, 1))),
+    ];
+    let stats = time_to_address(&episodes, at(1, 3), at(9, 0));
+    assert_eq!(stats.len(), 2);
+    assert_eq!((stats[0].requirement.clone(), stats[0].resolved, stats[0].mean_hours), (Review, 1, Some(48.0)));
+    assert_eq!((stats[0].open, stats[0].oldest_open_hours), (1, Some(24.0)));
+    assert_eq!((stats[1].requirement.clone(), stats[1].resolved, stats[1].mean_hours), (Fix, 2, Some(12.0)));
+    assert_eq!((stats[1].open, stats[1].oldest_open_hours), (0, None));
+}
+
+#[cfg(feature = "testkit")]
+#[test]
+fn history_records_episodes_and_watch_reports_addressed_requirements() {
+    use skm::meta::history::MetricsHistory;
+    use skm::reporter::{ProjectChange, portfolio_changes};
+    use skm::scanner::pipeline::{GitMode, ScanOptions};
+    use skm::testkit::{ProjectSpec, SyntheticPortfolio};
+    use skm::Stage;
+
+    let portfolio = SyntheticPortfolio::temp("requirements", &[ProjectSpec::new("api", Stage::Implement)]).unwrap();
+    let mut before = portfolio.scan(ScanOptions { git: GitMode::Skip, ..ScanOptions::default() }).unwrap();
+    before.projects[0].requires_human = vec![Review, Input];
+    let mut after = before.clone();
+    after.projects[0].requires_human = vec![Review];
+
+    let mut history = MetricsHistory::default();
+    history.record(&before.projects, at(3, 9));
+    history.record(&after.projects, at(4, 9));
+    let episodes = history.requirements("api");
+    assert_eq!(episodes.len(), 2);
+    assert_eq!(episodes[1], RequirementEpisode { requirement: Input, raised: at(3, 9), resolved: Some(at(4, 9)) });
+
+    let changes = portfolio_changes(&before, &after);
+    assert!(changes.contains(&ProjectChange::RequirementAddressed { project: "api".to_string(), requirement: Input }), "{:?}", changes);
+    assert!(changes.iter().any(|c| c.to_string().ends_with("api: Input addressed")));
+}
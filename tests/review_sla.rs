@@ -0,0 +1,73 @@
+//! Time projects wait in Review against `[sla] review_days`
+
+use chrono::{DateTime, TimeZone, Utc};
+use skm::analyzer::sla::{ReviewSla, add_business_days, business_days_between};
+
+fn at(day: u32, hour: u32) -> DateTime<Utc> {
+    // 2025-03-07 is a Friday
+    Utc.with_ymd_and_hms(2025, 3, day, hour, 0, 0).unwrap()
+}
+
+#[test]
+fn business_days_skip_weekends() {
+    assert_eq!(add_business_days(at(7, 17), 3), at(12, 17));
+    assert_eq!(add_business_days(at(8, 10), 1), at(11, 0));
+    assert_eq!(add_business_days(at(5, 9), 0), at(5, 9));
+
+    assert_eq!(business_days_between(at(7, 17), at(12, 16)), 2);
+    assert_eq!(business_days_between(at(7, 17), at(12, 17)), 3);
+    assert_eq!(business_days_between(at(7, 17), at(21, 17)), 10);
+    assert_eq!(business_days_between(at(12, 9), at(7, 9)), 0);
+}
+
+#[test]
+fn waiting_past_the_due_time_breaches() {
+    let sla = ReviewSla::new(at(7, 17), 3, at(12, 16));
+    assert!(!sla.breached);
+    assert_eq!(sla.summary(), "2 business day(s) in Review, within the 3-day SLA");
+    let sla = ReviewSla::new(at(7, 17), 3, at(13, 9));
+    assert!(sla.breached);
+    assert_eq!((sla.waited_days, sla.due), (3, at(12, 17)));
+}
+
+#[cfg(feature = "testkit")]
+#[test]
+fn breaches_escalate_and_show_up_in_reports_and_weekly_digests() {
+    use std::fs;
+    use skm::meta::history::StageVelocity;
+    use skm::reporter::{DigestMode, ProjectChange, generate_digest, generate_markdown_report, portfolio_changes};
+    use skm::scanner::pipeline::{GitMode, ScanOptions};
+    use skm::testkit::{ProjectSpec, SyntheticPortfolio};
+    use skm::view::PortfolioView;
+    use skm::Stage;
+
+    let specs = [ProjectSpec::new("api", Stage::Review), ProjectSpec::new("web", Stage::Implement)];
+    let portfolio = SyntheticPortfolio::temp("review-sla", &specs).unwrap();
+    for spec in &specs {
+        fs::create_dir_all(portfolio.root().join(&spec.name).join(".skm")).unwrap();
+        fs::write(portfolio.root().join(&spec.name).join(".skm/config.toml"), "[sla]\nreview_days = 3\n").unwrap();
+    }
+    let before = portfolio.scan(ScanOptions { git: GitMode::Skip, ..ScanOptions::default() }).unwrap();
+    let project = |id: &str| before.projects.iter().find(|p| p.id == id).unwrap();
+    let sla = project("api").review_sla.as_ref().unwrap();
+    assert_eq!((sla.limit_days, sla.waited_days, sla.breached), (3, 0, false));
+    assert!(project("web").review_sla.is_none());
+
+    let mut after = before.clone();
+    let api = after.projects.iter_mut().find(|p| p.id == "api").unwrap();
+    api.review_sla = Some(ReviewSla::new(at(7, 17), 3, at(14, 9)));
+    api.needs_attention = true;
+    let changes = portfolio_changes(&before, &after);
+    assert!(changes.iter().any(|c| matches!(c, ProjectChange::SlaBreached { waited_days: 4, limit_days: 3, .. })), "{:?}", changes);
+
+    let report = generate_markdown_report(&after);
+    assert!(report.contains("## Review SLA Breaches (1)"), "{}", report);
+    assert!(report.contains("4 business day(s) in Review, over the 3-day SLA"), "{}", report);
+    assert!(report.contains("- **Stage**: review\n") && !report.contains("Review |"), "stages are named as in JSON: {}", report);
+
+    let velocity = StageVelocity { since: at(7, 0), transitions: 0, projects: 0, mean_days: Default::default() };
+    let weekly = generate_digest(&PortfolioView::new(&after), DigestMode::Weekly, &velocity, &[], &[]);
+    assert!(weekly.contains("1 in Review, 1 over the 3-day SLA; mean wait 4.0 business days, longest 4."), "{}", weekly);
+    let daily = generate_digest(&PortfolioView::new(&after), DigestMode::Daily, &velocity, &[], &[]);
+    assert!(!daily.contains("## Review SLA"));
+}
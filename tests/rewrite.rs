@@ -0,0 +1,163 @@
+//! `skm edit --replace Old/New`: renames in the prose of spec artifacts
+
+use skm::rewrite::{Replacement, replace_markdown};
+
+fn nova() -> Replacement {
+    "Acme/Nova".parse().unwrap()
+}
+
+#[test]
+fn replacements_split_at_the_first_slash() {
+    let replacement: Replacement = "Acme/Nova/2".parse().unwrap();
+    assert_eq!((replacement.from.as_str(), replacement.to.as_str()), ("Acme", "Nova/2"));
+    assert_eq!(nova().branch(), "skm/replace-nova");
+    let err = "Acme".parse::<Replacement>().unwrap_err().to_string();
+    assert!(err.contains("expected Old/New"), "{}", err);
+    assert!("/Nova".parse::<Replacement>().is_err());
+}
+
+#[test]
+fn only_prose_is_rewritten() {
+    let text = "\
+# Acme Portal
+
+Acme users sign in; AcmeCloud stays as it is.
+Run `acme --name Acme` or see [the Acme docs](https://acme.dev/Acme/guide).
+<!-- Acme internal note -->
+
+```sh
+Acme deploy
+```
+
+[guide]: https://example.com/Acme
+";
+    let rewrite = replace_markdown(text, &nova());
+    assert_eq!(rewrite.text, "\
+# Nova Portal
+
+Nova users sign in; AcmeCloud stays as it is.
+Run `acme --name Acme` or see [the Nova docs](https://acme.dev/Acme/guide).
+<!-- Acme internal note -->
+
+```sh
+Acme deploy
+```
+
+[guide]: https://example.com/Acme
+");
+    assert_eq!((rewrite.replaced, rewrite.skipped), (3, 5));
+}
+
+#[test]
+fn bare_urls_and_unclosed_backticks() {
+    let rewrite = replace_markdown("See https://Acme.io for `Acme and Acme.\n", &nova());
+    assert_eq!(rewrite.text, "See https://Acme.io for `Nova and Nova.\n");
+    assert_eq!((rewrite.replaced, rewrite.skipped), (2, 1));
+}
+
+#[cfg(feature = "testkit")]
+#[test]
+fn tag_filters_match_lists_and_comma_separated_tags() {
+    use skm::scanner::pipeline::{GitMode, ScanOptions};
+    use skm::testkit::{ProjectSpec, SyntheticPortfolio};
+    use skm::view::FieldFilter;
+    use skm::Stage;
+
+    let specs = [ProjectSpec::new("api", Stage::Plan), ProjectSpec::new("web", Stage::Plan), ProjectSpec::new("cli", Stage::Plan)];
+    let portfolio = SyntheticPortfolio::temp("rewrite-tags", &specs).unwrap();
+    let mut status = portfolio.scan(ScanOptions { git: GitMode::Skip, ..ScanOptions::default() }).unwrap();
+    for project in &mut status.projects {
+        let tags = match project.id.as_str() {
+            "api" => serde_json::json!(["client-x", "backend"]),
+            "web" => serde_json::json!("frontend, client-x"),
+            _ => serde_json::json!("client-y"),
+        };
+        project.custom.insert("tags".to_string(), tags);
+    }
+    let filter: FieldFilter = "tag=client-x".parse().unwrap();
+    let mut matched: Vec<&str> = status.projects.iter().filter(|p| filter.matches(p)).map(|p| p.id.as_str()).collect();
+    matched.sort();
+    assert_eq!(matched, ["api", "web"]);
+}
+
+#[cfg(all(feature = "testkit", feature = "git"))]
+#[test]
+fn edits_are_committed_on_a_branch_per_project() {
+    use std::fs;
+    use skm::rewrite::{commit_edits, plan_edits};
+    use skm::testkit::{GitState, ProjectSpec, SyntheticPortfolio};
+    use skm::Stage;
+
+    let specs = [ProjectSpec::new("api", Stage::Plan).with_git(GitState::Clean)];
+    let portfolio = SyntheticPortfolio::temp("rewrite-commit", &specs).unwrap();
+    let project = portfolio.root().join("api");
+    let spec = project.join("specs/001-core/spec.md");
+    let repo = git2::Repository::open(&project).unwrap();
+    let mut content = fs::read_to_string(&spec).unwrap();
+    content.push_str("\nAcme customers see `Acme` in the header.\n");
+    fs::write(&spec, &content).unwrap();
+    let mut index = repo.index().unwrap();
+    index.add_path(std::path::Path::new("specs/001-core/spec.md")).unwrap();
+    index.write().unwrap();
+    let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+    let signature = git2::Signature::now("test", "test@localhost").unwrap();
+    let parent = repo.head().unwrap().peel_to_commit().unwrap();
+    let mentioned = repo.commit(Some("HEAD"), &signature, &signature, "Mention Acme", &tree, &[&parent]).unwrap();
+
+    let edits = plan_edits(&project, &nova()).unwrap();
+    assert_eq!(edits.len(), 1);
+    assert_eq!(edits[0].path, std::path::Path::new("specs/001-core/spec.md"));
+    assert_eq!((edits[0].rewrite.replaced, edits[0].rewrite.skipped), (1, 1));
+    assert_eq!(fs::read_to_string(&spec).unwrap(), content, "planning writes nothing");
+
+    let branch = commit_edits(&project, &edits, "skm/replace-nova", "Rename Acme to Nova").unwrap();
+    assert_eq!(branch.as_deref(), Some("skm/replace-nova"));
+    assert_eq!(fs::read_to_string(&spec).unwrap(), content, "the checkout is left alone");
+    assert_eq!(repo.head().unwrap().peel_to_commit().unwrap().id(), mentioned);
+    let commit = repo.revparse_single("skm/replace-nova").unwrap().peel_to_commit().unwrap();
+    assert_eq!((commit.message(), commit.parent_id(0).unwrap()), (Some("Rename Acme to Nova"), mentioned));
+    assert!(repo.statuses(None).unwrap().iter().all(|s| s.path() != Some("specs/001-core/spec.md")));
+
+    let err = commit_edits(&project, &edits, "skm/replace-nova", "Again").unwrap_err().to_string();
+    assert!(err.contains("already exists"), "{}", err);
+}
+
+#[cfg(feature = "git")]
+#[test]
+fn edits_are_committed_from_head_without_leaving_the_branch() {
+    use std::fs;
+    use git2::{Repository, Signature};
+    use skm::rewrite::{commit_edits, plan_edits};
+
+    let project = std::env::temp_dir().join(format!("skm-rewrite-commit-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&project);
+    fs::create_dir_all(project.join("specs/001-portal")).unwrap();
+    let repo = Repository::init(&project).unwrap();
+    assert!(commit_edits(&project, &[], "skm/unborn", "edit").is_err(), "an unborn HEAD is refused");
+
+    fs::write(project.join("specs/001-portal/spec.md"), "# Acme Portal\n").unwrap();
+    let signature = Signature::now("skm", "skm@localhost").unwrap();
+    let mut index = repo.index().unwrap();
+    index.add_path(std::path::Path::new("specs/001-portal/spec.md")).unwrap();
+    index.write().unwrap();
+    let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+    repo.commit(Some("HEAD"), &signature, &signature, "init", &tree, &[]).unwrap();
+    let original = repo.head().unwrap().name().unwrap().to_string();
+
+    fs::write(project.join("staged.txt"), "work in progress\n").unwrap();
+    index.add_path(std::path::Path::new("staged.txt")).unwrap();
+    index.write().unwrap();
+
+    let edits = plan_edits(&project, &nova()).unwrap();
+    assert_eq!(commit_edits(&project, &edits, "skm/replace-nova", "Rename Acme to Nova").unwrap().as_deref(), Some("skm/replace-nova"));
+
+    assert_eq!(repo.head().unwrap().name(), Some(original.as_str()), "the checkout stays on its branch");
+    assert_eq!(fs::read_to_string(project.join("specs/001-portal/spec.md")).unwrap(), "# Acme Portal\n");
+    assert!(repo.index().unwrap().get_path(std::path::Path::new("staged.txt"), 0).is_some(), "staged work stays staged");
+    let commit = repo.revparse_single("skm/replace-nova").unwrap().peel_to_commit().unwrap();
+    let tree = commit.tree().unwrap();
+    assert!(tree.get_path(std::path::Path::new("staged.txt")).is_err(), "only the edits are committed");
+    let blob = tree.get_path(std::path::Path::new("specs/001-portal/spec.md")).unwrap().to_object(&repo).unwrap().peel_to_blob().unwrap();
+    assert_eq!(blob.content(), b"# Nova Portal\n");
+    fs::remove_dir_all(&project).unwrap();
+}
@@ -0,0 +1,70 @@
+//! Several scan roots with per-root labels, settings and depth
+
+use std::fs;
+use skm::meta::config::GlobalConfig;
+use skm::meta::config_file::unknown_keys;
+use skm::scanner::finder::ProjectScanner;
+use skm::view::{FieldFilter, FilterField};
+
+const ROOTS: &str = "
+[[roots]]
+name = \"work\"
+path = \"~/work\"
+
+[[roots]]
+label = \"clients\"
+path = \"~/clients\"
+attention_threshold = 30.0
+scan_depth = 2
+watch_interval_secs = 60
+";
+
+fn content() -> String {
+    toml::to_string(&GlobalConfig::default()).unwrap().replace("roots = []\n", "") + ROOTS
+}
+
+fn config() -> GlobalConfig {
+    toml::from_str(&content()).unwrap()
+}
+
+#[test]
+fn roots_override_depth_and_threshold() {
+    let config = config();
+    assert_eq!(config.roots[1].name, "clients", "label is another name for name");
+    assert_eq!(unknown_keys(&content()).unwrap(), Vec::<String>::new());
+    let work = config.for_root(&config.roots[0]);
+    assert_eq!((work.scan_depth, work.attention_threshold), (config.scan_depth, config.attention_threshold));
+    let clients = config.for_root(&config.roots[1]);
+    assert_eq!((clients.scan_depth, clients.attention_threshold), (2, 30.0));
+    assert_eq!((work.watch_interval(), clients.watch_interval()), (config.watch_interval(), std::time::Duration::from_secs(60)));
+}
+
+#[test]
+fn discovery_stops_at_the_root_depth() {
+    let root = std::env::temp_dir().join(format!("skm-roots-depth-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    for project in ["acme", "group/globex"] {
+        fs::create_dir_all(root.join(project).join(".specify")).unwrap();
+    }
+    let config = config();
+    let depth = |root_config| config.for_root(root_config).scan_depth;
+    let found = |depth| {
+        let mut names: Vec<String> = ProjectScanner::new(root.clone(), depth).find_projects().iter()
+            .map(|p| p.strip_prefix(&root).unwrap().display().to_string())
+            .collect();
+        names.sort();
+        names
+    };
+    assert_eq!(found(depth(&config.roots[0])), ["acme", "group/globex"]);
+    assert_eq!(found(depth(&config.roots[1])), ["acme"]);
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn where_conditions_take_a_colon_or_an_equals_sign() {
+    let colon: FieldFilter = "root:clients".parse().unwrap();
+    assert_eq!(colon, "root=clients".parse().unwrap());
+    let url: FieldFilter = "custom.repo=https://example.com".parse().unwrap();
+    assert_eq!(url, FieldFilter { field: FilterField::Custom("repo".to_string()), value: "https://example.com".to_string() });
+    assert!("root".parse::<FieldFilter>().is_err());
+}
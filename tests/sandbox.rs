@@ -0,0 +1,56 @@
+//! `skm autopilot --sandbox` copies are cut off from the original repository
+
+#![cfg(all(feature = "testkit", feature = "git", unix))]
+
+use std::fs;
+use std::path::Path;
+use git2::{Repository, Signature};
+use skm::autopilot::sandbox;
+use skm::scanner::pipeline::{GitMode, ScanOptions};
+use skm::testkit::{ProjectSpec, SyntheticPortfolio};
+use skm::Stage;
+
+fn commit_all(repo: &Repository) {
+    let mut index = repo.index().unwrap();
+    index.add_all(["*"], git2::IndexAddOption::DEFAULT, None).unwrap();
+    let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+    let signature = Signature::now("skm", "skm@localhost").unwrap();
+    repo.commit(Some("HEAD"), &signature, &signature, "init", &tree, &[]).unwrap();
+}
+
+#[test]
+fn worktrees_are_copied_without_remotes_or_outside_symlinks() {
+    let portfolio = SyntheticPortfolio::temp("sandbox", &[ProjectSpec::new("api", Stage::Implement).with_tasks(2)]).unwrap();
+    let main = portfolio.root().join("api");
+    let repo = Repository::init(&main).unwrap();
+    commit_all(&repo);
+    repo.remote("origin", "https://example.com/api.git").unwrap();
+
+    let base = portfolio.root().parent().unwrap().join(format!("skm-sandbox-test-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&base);
+    fs::create_dir_all(&base).unwrap();
+    let worktree = base.join("api-wt");
+    repo.worktree("api-wt", &worktree, None).unwrap();
+    std::os::unix::fs::symlink("/etc", worktree.join("outside")).unwrap();
+    fs::create_dir(worktree.join("docs")).unwrap();
+    std::os::unix::fs::symlink("../README.md", worktree.join("docs/inside")).unwrap();
+    fs::write(worktree.join("README.md"), "# api\n").unwrap();
+
+    let mut project = portfolio.scan(ScanOptions { git: GitMode::Skip, ..ScanOptions::default() }).unwrap()
+        .projects.remove(0);
+    project.path = worktree.clone();
+    let dir = base.join("sandbox");
+    let copies = sandbox::prepare(&dir, &[(&project, None)]).unwrap();
+
+    let copy = &copies[0].copy;
+    assert!(copy.join(".git").is_dir(), "the gitfile is replaced by a git directory");
+    let copied = Repository::open(copy).unwrap();
+    assert_eq!(copied.workdir().map(Path::canonicalize).unwrap().unwrap(), copy.canonicalize().unwrap());
+    assert!(copied.remotes().unwrap().is_empty());
+    assert_eq!(copied.head().unwrap().shorthand(), Some("api-wt"));
+    assert_eq!(copies[0].skipped, [Path::new("outside")]);
+    assert!(fs::symlink_metadata(copy.join("outside")).is_err());
+    assert_eq!(fs::read_to_string(copy.join("docs/inside")).unwrap(), "# api\n");
+    assert_eq!(repo.remotes().unwrap().iter().flatten().collect::<Vec<_>>(), ["origin"], "the original keeps its remote");
+    fs::remove_dir_all(&base).unwrap();
+}
@@ -0,0 +1,92 @@
+//! Active windows of seasonal projects: parsing, pausing and digest notes
+
+use chrono::{NaiveDate, TimeZone, Utc};
+use skm::analyzer::season::ActiveWindow;
+
+fn day(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+#[test]
+fn windows_parse_quarters_months_and_wrapping_ranges() {
+    let q4: ActiveWindow = "Q4".parse().unwrap();
+    assert!(q4.contains(day(2026, 10, 1)) && q4.contains(day(2026, 12, 31)));
+    assert!(!q4.contains(day(2026, 9, 30)));
+
+    let winter: ActiveWindow = "nov-feb".parse().unwrap();
+    assert!(winter.contains(day(2026, 1, 15)) && winter.contains(day(2026, 11, 1)));
+    assert!(!winter.contains(day(2026, 3, 1)));
+
+    let split: ActiveWindow = "Q1, 7".parse().unwrap();
+    assert!(split.contains(day(2026, 2, 1)) && split.contains(day(2026, 7, 1)));
+    assert!(!split.contains(day(2026, 6, 1)));
+    assert_eq!(split.to_string(), "Q1, 7");
+
+    for invalid in ["", "Q5", "13", "smarch", "jan-"] {
+        assert!(invalid.parse::<ActiveWindow>().is_err(), "{}", invalid);
+    }
+}
+
+#[test]
+fn windows_know_when_they_last_opened_or_closed() {
+    let q4: ActiveWindow = "Q4".parse().unwrap();
+    assert_eq!(q4.last_change(day(2026, 11, 20)), Some(day(2026, 10, 1)));
+    assert_eq!(q4.last_change(day(2027, 3, 5)), Some(day(2027, 1, 1)));
+    assert_eq!("jan-dec".parse::<ActiveWindow>().unwrap().last_change(day(2026, 5, 1)), None);
+
+    // Out of season the clock stops where the window closed
+    let now = Utc.with_ymd_and_hms(2027, 3, 5, 12, 0, 0).unwrap();
+    assert_eq!(q4.staleness_clock(now), Utc.with_ymd_and_hms(2027, 1, 1, 0, 0, 0).unwrap());
+    let in_season = Utc.with_ymd_and_hms(2026, 11, 5, 12, 0, 0).unwrap();
+    assert_eq!(q4.staleness_clock(in_season), in_season);
+}
+
+#[cfg(all(feature = "testkit", feature = "git"))]
+#[test]
+fn paused_projects_leave_the_attention_list_and_wake_up_in_season() {
+    use std::collections::BTreeMap;
+    use chrono::{Datelike, Duration};
+    use skm::meta::history::StageVelocity;
+    use skm::meta::state::ProjectMetaStore;
+    use skm::reporter::{generate_digest, DigestMode};
+    use skm::scanner::pipeline::ScanOptions;
+    use skm::testkit::{GitState, ProjectSpec, SyntheticPortfolio};
+    use skm::view::PortfolioView;
+    use skm::Stage;
+
+    let month = Utc::now().month();
+    // Last month's window closed, and this month's opened, on the 1st
+    let elsewhere = format!("{}", (month + 10) % 12 + 1);
+    let here = format!("{}", month);
+    // Uncommitted changes lift a new project above the attention threshold
+    let specs = [
+        ProjectSpec::new("paused", Stage::Bootstrap).with_git(GitState::Dirty),
+        ProjectSpec::new("active", Stage::Bootstrap).with_git(GitState::Dirty),
+    ];
+    let portfolio = SyntheticPortfolio::temp("season", &specs).unwrap();
+    let mut meta = ProjectMetaStore::default();
+    meta.set_value("paused", "active_window", elsewhere).unwrap();
+    meta.set_value("active", "active_window", here).unwrap();
+    meta.save(portfolio.root()).unwrap();
+    let scan = portfolio.scan(ScanOptions::default()).unwrap();
+
+    let project = |id: &str| scan.projects.iter().find(|p| p.id == id).unwrap();
+    let paused = project("paused");
+    assert!(!paused.season.as_ref().unwrap().active);
+    assert!(!paused.needs_attention);
+    let active = project("active");
+    assert!(active.season.as_ref().unwrap().active);
+    assert!(active.needs_attention);
+    assert_eq!(scan.summary.needs_attention, 1);
+
+    let today = Utc::now().date_naive().with_day(1).unwrap();
+    let velocity = StageVelocity {
+        since: Utc::now() - Duration::days(40),
+        transitions: 0,
+        projects: 0,
+        mean_days: BTreeMap::new(),
+    };
+    let digest = generate_digest(&PortfolioView::new(&scan), DigestMode::Weekly, &velocity, &[], &[]);
+    assert!(digest.contains(&format!("- **active** is back in season on {} (active {})", today, month)), "{}", digest);
+    assert!(digest.contains(&format!("- **paused** paused on {}", today)), "{}", digest);
+}
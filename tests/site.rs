@@ -0,0 +1,33 @@
+//! `skm report --format site`: one page per project, linked from the index
+
+#![cfg(feature = "testkit")]
+
+use std::fs;
+use skm::reporter::publish_site;
+use skm::scanner::pipeline::{GitMode, ScanOptions};
+use skm::testkit::{ProjectSpec, SyntheticPortfolio};
+use skm::view::PortfolioView;
+use skm::Stage;
+
+#[test]
+fn projects_with_the_same_id_under_different_roots_get_their_own_pages() {
+    let portfolio = SyntheticPortfolio::temp("site-pages", &[ProjectSpec::new("api", Stage::Plan)]).unwrap();
+    let mut status = portfolio.scan(ScanOptions { git: GitMode::Skip, ..ScanOptions::default() }).unwrap();
+    let mut other = status.projects[0].clone();
+    status.projects[0].root = Some("work".to_string());
+    other.root = Some("clients".to_string());
+    status.projects.push(other);
+
+    let out = portfolio.root().join("site");
+    let summary = publish_site(&PortfolioView::new(&status), &[], &out).unwrap();
+    let mut pages: Vec<String> = fs::read_dir(out.join("projects")).unwrap()
+        .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    pages.sort();
+    assert_eq!(pages, ["clients-api.html", "work-api.html"]);
+    let index = fs::read_to_string(out.join("index.html")).unwrap();
+    for page in &pages {
+        assert!(index.contains(&format!("href=\"projects/{}\"", page)), "{} is linked", page);
+    }
+    assert_eq!(summary.removed, 0);
+}
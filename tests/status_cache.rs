@@ -0,0 +1,155 @@
+//! The status cache is served only while its projects' fingerprints still match
+
+use std::collections::BTreeMap;
+use std::fs;
+use skm::meta::state::StatusCache;
+
+fn prints(entries: &[(&str, &str)]) -> BTreeMap<String, String> {
+    entries.iter().map(|(path, print)| (path.to_string(), print.to_string())).collect()
+}
+
+#[test]
+fn cache_is_current_only_with_the_same_projects_and_fingerprints() {
+    let stored = prints(&[("./api", "a1"), ("./web", "b1")]);
+    let cache = StatusCache::new(serde_json::json!({})).with_fingerprints(stored.clone());
+    assert!(cache.is_current(&stored));
+    assert!(!cache.is_current(&prints(&[("./api", "a1"), ("./web", "b2")])));
+    assert!(!cache.is_current(&prints(&[("./api", "a1")])));
+    assert!(!cache.is_current(&prints(&[("./api", "a1"), ("./web", "b1"), ("./new", "c1")])));
+
+    // Caches written before fingerprints were recorded always rescan
+    assert!(!StatusCache::new(serde_json::json!({})).is_current(&BTreeMap::new()));
+}
+
+#[test]
+fn fingerprints_are_saved_with_the_cache_but_not_in_the_history_log() {
+    let root = std::env::temp_dir().join(format!("skm-status-cache-{}", std::process::id()));
+    let cache = StatusCache::new(serde_json::json!({ "projects": [] }))
+        .with_fingerprints(prints(&[("./api", "a1")]));
+    cache.save(&root).unwrap();
+    cache.append_to_log(&root).unwrap();
+
+    let (loaded, _) = StatusCache::load_migrated(&root).unwrap().unwrap();
+    assert_eq!(loaded.fingerprints, cache.fingerprints);
+    let log = fs::read_to_string(root.join(".skm/history.jsonl")).unwrap();
+    assert!(!log.contains("fingerprints"), "{}", log);
+    assert!(StatusCache::load_log(&root).unwrap().0[0].fingerprints.is_empty());
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[cfg(feature = "testkit")]
+#[test]
+fn editing_tasks_changes_the_fingerprint() {
+    use skm::meta::config::GlobalConfig;
+    use skm::scanner::fingerprint::fingerprint;
+    use skm::scanner::pipeline::{ScanContext, ScanOptions};
+    use skm::testkit::{ProjectSpec, SyntheticPortfolio};
+    use skm::Stage;
+
+    let portfolio = SyntheticPortfolio::temp("status-cache", &[ProjectSpec::new("api", Stage::Implement).with_tasks(4)]).unwrap();
+    let project = portfolio.root().join("api");
+    let ctx = ScanContext::with_config(GlobalConfig::default(), portfolio.root(), ScanOptions::default()).unwrap();
+    let before = fingerprint(&project, &ctx);
+    assert_eq!(fingerprint(&project, &ctx), before);
+
+    let feature = fs::read_dir(project.join("specs")).unwrap().next().unwrap().unwrap().path();
+    let tasks = fs::read_to_string(feature.join("tasks.md")).unwrap();
+    fs::write(feature.join("tasks.md"), tasks.replacen("- [ ]", "- [x]", 1)).unwrap();
+    assert_ne!(fingerprint(&project, &ctx), before);
+}
+
+#[cfg(feature = "testkit")]
+#[test]
+fn cached_projects_are_reranked_with_the_current_clock() {
+    use skm::meta::config::GlobalConfig;
+    use skm::scanner::pipeline::{self, GitMode, ScanContext, ScanOptions};
+    use skm::testkit::{ProjectSpec, SyntheticPortfolio};
+    use skm::Stage;
+
+    let portfolio = SyntheticPortfolio::temp("status-rerank", &[ProjectSpec::new("api", Stage::Implement).with_tasks(4)]).unwrap();
+    let mut project = portfolio.scan(ScanOptions { git: GitMode::Skip, ..ScanOptions::default() }).unwrap().projects.remove(0);
+    let fresh = project.priority;
+
+    // As if the cache had been written three months ago
+    let breakdown = project.priority_breakdown.as_mut().unwrap();
+    breakdown.inputs.last_activity -= chrono::Duration::days(90);
+    let ctx = ScanContext::with_config(GlobalConfig::default(), portfolio.root(), ScanOptions::default()).unwrap();
+    pipeline::rerank(&mut project, &GlobalConfig::default(), &ctx);
+
+    let breakdown = project.priority_breakdown.as_ref().unwrap();
+    assert!(breakdown.inputs.idle_days >= 90, "{:?}", breakdown.inputs);
+    assert!(project.priority > fresh, "staleness grows: {} -> {}", fresh, project.priority);
+}
+
+#[cfg(all(feature = "testkit", feature = "git"))]
+#[test]
+fn new_branches_and_fetched_heads_change_the_fingerprint() {
+    use skm::meta::config::GlobalConfig;
+    use skm::scanner::fingerprint::fingerprint;
+    use skm::scanner::pipeline::{ScanContext, ScanOptions};
+    use skm::testkit::{GitState, ProjectSpec, SyntheticPortfolio};
+    use skm::Stage;
+
+    let portfolio = SyntheticPortfolio::temp("status-refs", &[ProjectSpec::new("api", Stage::Plan).with_git(GitState::Clean)]).unwrap();
+    let project = portfolio.root().join("api");
+    let ctx = ScanContext::with_config(GlobalConfig::default(), portfolio.root(), ScanOptions::default()).unwrap();
+    let before = fingerprint(&project, &ctx);
+
+    let repo = git2::Repository::open(&project).unwrap();
+    let head = repo.head().unwrap().target().unwrap();
+    repo.reference("refs/remotes/origin/main", head, true, "fetch").unwrap();
+    assert_ne!(fingerprint(&project, &ctx), before);
+}
+
+#[cfg(feature = "testkit")]
+#[test]
+fn project_config_changes_the_fingerprint() {
+    use skm::meta::config::GlobalConfig;
+    use skm::scanner::fingerprint::fingerprint;
+    use skm::scanner::pipeline::{ScanContext, ScanOptions};
+    use skm::testkit::{ProjectSpec, SyntheticPortfolio};
+    use skm::Stage;
+
+    let portfolio = SyntheticPortfolio::temp("status-project-config", &[ProjectSpec::new("api", Stage::Plan)]).unwrap();
+    let project = portfolio.root().join("api");
+    let ctx = ScanContext::with_config(GlobalConfig::default(), portfolio.root(), ScanOptions::default()).unwrap();
+    let before = fingerprint(&project, &ctx);
+    fs::create_dir_all(project.join(".skm")).unwrap();
+    fs::write(project.join(".skm/config.toml"), "attention_threshold = 10.0\n").unwrap();
+    assert_ne!(fingerprint(&project, &ctx), before);
+}
+
+#[test]
+fn the_history_log_is_appended_and_pruned_separately() {
+    let root = std::env::temp_dir().join(format!("skm-status-log-prune-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    let now = chrono::Utc::now();
+    let mut old = StatusCache::new(serde_json::json!({ "projects": [] }));
+    old.last_updated = now - chrono::Duration::days(200);
+    old.append_to_log(&root).unwrap();
+    StatusCache::new(serde_json::json!({ "projects": [] })).append_to_log(&root).unwrap();
+    assert_eq!(StatusCache::load_log(&root).unwrap().0.len(), 2, "appending never drops entries");
+
+    StatusCache::prune_log(&root, now).unwrap();
+    let (kept, _) = StatusCache::load_log(&root).unwrap();
+    assert_eq!(kept.len(), 1);
+    assert!(kept[0].last_updated > now - chrono::Duration::days(1));
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn unreadable_history_entries_are_skipped() {
+    let root = std::env::temp_dir().join(format!("skm-status-log-bad-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    StatusCache::new(serde_json::json!({ "projects": [] })).append_to_log(&root).unwrap();
+    let log = root.join(".skm/history.jsonl");
+    let mut content = fs::read_to_string(&log).unwrap();
+    content.push_str("{\"last_updated\": \"not a time\"}\n{\"truncated\n\n");
+    fs::write(&log, content).unwrap();
+    StatusCache::new(serde_json::json!({ "projects": [] })).append_to_log(&root).unwrap();
+
+    let (entries, skipped) = StatusCache::load_log(&root).unwrap();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(skipped.iter().map(|entry| entry.line).collect::<Vec<_>>(), [2, 3]);
+    fs::remove_dir_all(&root).unwrap();
+}
@@ -0,0 +1,148 @@
+//! Scans of generated portfolios, compared against snapshots in
+//! `tests/fixtures/snapshots`
+#![cfg(feature = "testkit")]
+
+use std::path::{Path, PathBuf};
+use skm::meta::state::ProjectMetaStore;
+use skm::scanner::pipeline::{GitMode, ScanOptions};
+use skm::testkit::{self, GitState, ProjectSpec, SyntheticPortfolio, TaskStyle};
+use skm::{ProjectType, Stage};
+
+fn snapshots() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/snapshots")
+}
+
+fn offline() -> ScanOptions {
+    ScanOptions { git: GitMode::Skip, ..ScanOptions::default() }
+}
+
+#[test]
+fn every_stage_is_detected() {
+    let stages = [
+        Stage::Bootstrap, Stage::Specify, Stage::Clarify, Stage::Plan, Stage::Tasks,
+        Stage::Analyze, Stage::Implement, Stage::Test, Stage::Review, Stage::Done,
+    ];
+    let specs: Vec<ProjectSpec> = stages.iter()
+        .map(|stage| ProjectSpec::new(&format!("{:?}", stage).to_lowercase(), stage.clone()))
+        .collect();
+    let portfolio = SyntheticPortfolio::temp("stages", &specs).unwrap();
+    let scan = portfolio.scan(offline()).unwrap();
+
+    assert_eq!(scan.projects.len(), specs.len());
+    for spec in &specs {
+        let project = scan.projects.iter().find(|p| p.id == spec.name).unwrap();
+        assert_eq!(project.stage, spec.stage, "{}", spec.name);
+    }
+    testkit::assert_snapshot(&snapshots(), "stages", &testkit::snapshot(&scan));
+}
+
+#[test]
+fn task_styles_and_languages_are_counted_alike() {
+    let specs: Vec<ProjectSpec> = TaskStyle::ALL.iter()
+        .zip([ProjectType::Rust, ProjectType::Node, ProjectType::Python, ProjectType::Go])
+        .enumerate()
+        .map(|(i, (style, language))| {
+            ProjectSpec::new(&format!("styles-{}", i), Stage::Implement)
+                .with_tasks(6)
+                .with_style(*style)
+                .with_language(language)
+        })
+        .collect();
+    let portfolio = SyntheticPortfolio::temp("styles", &specs).unwrap();
+    let scan = portfolio.scan(offline()).unwrap();
+
+    for project in &scan.projects {
+        assert_eq!((project.tasks.completed, project.tasks.total), (3, 6), "{}", project.id);
+        assert_eq!(project.stage, Stage::Implement, "{}", project.id);
+    }
+    testkit::assert_snapshot(&snapshots(), "styles", &testkit::snapshot(&scan));
+}
+
+#[cfg(feature = "git")]
+#[test]
+fn git_states_are_reported() {
+    let specs = [
+        ProjectSpec::new("none", Stage::Plan),
+        ProjectSpec::new("clean", Stage::Plan).with_git(GitState::Clean),
+        ProjectSpec::new("dirty", Stage::Plan).with_git(GitState::Dirty),
+    ];
+    let portfolio = SyntheticPortfolio::temp("git", &specs).unwrap();
+    let scan = portfolio.scan(ScanOptions::default()).unwrap();
+
+    let git = |id: &str| {
+        let project = scan.projects.iter().find(|p| p.id == id).unwrap();
+        (project.git.is_repo, project.git.clean)
+    };
+    assert!(!git("none").0);
+    assert_eq!(git("clean"), (true, true));
+    assert_eq!(git("dirty"), (true, false));
+}
+
+#[test]
+fn done_can_be_overridden_in_project_meta() {
+    let specs = [
+        ProjectSpec::new("forced", Stage::Plan),
+        ProjectSpec::new("reopened", Stage::Done),
+    ];
+    let portfolio = SyntheticPortfolio::temp("done-override", &specs).unwrap();
+    let mut meta = ProjectMetaStore::default();
+    meta.set_value("forced", "done", "true".to_string()).unwrap();
+    meta.set_value("reopened", "done", "false".to_string()).unwrap();
+    meta.save(portfolio.root()).unwrap();
+    let scan = portfolio.scan(offline()).unwrap();
+
+    let project = |id: &str| scan.projects.iter().find(|p| p.id == id).unwrap();
+    assert_eq!(project("forced").stage, Stage::Done);
+    assert_eq!(project("forced").priority, 0.0);
+    assert!(!project("forced").needs_attention);
+    assert_eq!(project("reopened").stage, Stage::Test);
+    // The override holds for the features too
+    let features = |id: &str| project(id).features.iter().map(|f| f.stage.clone()).collect::<Vec<_>>();
+    assert_eq!(features("forced"), [Stage::Done]);
+    assert_eq!(features("reopened"), [Stage::Test]);
+}
+
+#[cfg(feature = "git")]
+#[test]
+fn finished_work_on_a_merged_branch_is_done() {
+    let specs = [
+        ProjectSpec::new("merged", Stage::Test).with_git(GitState::Merged),
+        ProjectSpec::new("unpushed", Stage::Test).with_git(GitState::Clean),
+        ProjectSpec::new("unfinished", Stage::Implement).with_git(GitState::Merged),
+    ];
+    let portfolio = SyntheticPortfolio::temp("done-git", &specs).unwrap();
+    let scan = portfolio.scan(ScanOptions::default()).unwrap();
+
+    let stage = |id: &str| scan.projects.iter().find(|p| p.id == id).unwrap().stage.clone();
+    assert_eq!(stage("merged"), Stage::Done);
+    assert_eq!(stage("unpushed"), Stage::Test);
+    assert_eq!(stage("unfinished"), Stage::Implement);
+}
+
+#[test]
+fn varied_portfolios_are_reproducible() {
+    assert_eq!(testkit::varied(40, 7), testkit::varied(40, 7));
+    assert_ne!(testkit::varied(40, 7), testkit::varied(40, 8));
+
+    let specs: Vec<ProjectSpec> = testkit::varied(27, 7).into_iter()
+        .map(|spec| spec.with_git(GitState::None))
+        .collect();
+    let portfolio = SyntheticPortfolio::temp("varied", &specs).unwrap();
+    let scan = portfolio.scan(offline()).unwrap();
+    for spec in &specs {
+        let project = scan.projects.iter().find(|p| p.id == spec.name).unwrap();
+        assert_eq!(project.stage, spec.stage, "{}", spec.name);
+    }
+    testkit::assert_snapshot(&snapshots(), "varied", &testkit::snapshot(&scan));
+}
+
+#[test]
+fn a_missing_snapshot_fails_instead_of_being_written() {
+    if std::env::var(testkit::UPDATE_SNAPSHOTS_ENV).is_ok_and(|value| value == "1") {
+        return;
+    }
+    let dir = std::env::temp_dir().join(format!("skm-snapshots-{}", std::process::id()));
+    let result = std::panic::catch_unwind(|| testkit::assert_snapshot(&dir, "missing", &serde_json::json!({ "projects": [] })));
+    assert!(result.is_err());
+    assert!(!dir.join("missing.json").exists());
+}
@@ -0,0 +1,81 @@
+//! Aligned terminal tables for `skm status --columns` and `report --format table`
+
+#![cfg(feature = "testkit")]
+
+use skm::reporter::{DEFAULT_COLUMNS, TableColumn, TableOptions, TableSort, write_table_view};
+use skm::scanner::pipeline::{GitMode, ScanOptions};
+use skm::testkit::{ProjectSpec, SyntheticPortfolio};
+use skm::view::PortfolioView;
+use skm::{PortfolioStatus, Stage};
+
+fn portfolio() -> PortfolioStatus {
+    let specs = [
+        ProjectSpec::new("api", Stage::Implement).with_tasks(4),
+        ProjectSpec::new("web", Stage::Plan),
+        ProjectSpec::new("billing-service", Stage::Review).with_tasks(10),
+    ];
+    let portfolio = SyntheticPortfolio::temp("table-report", &specs).unwrap();
+    let mut scan = portfolio.scan(ScanOptions { git: GitMode::Skip, ..ScanOptions::default() }).unwrap();
+    for (id, priority) in [("api", 80.0), ("web", 5.5), ("billing-service", 42.0)] {
+        scan.projects.iter_mut().find(|p| p.id == id).unwrap().priority = priority;
+    }
+    scan
+}
+
+fn render(status: &PortfolioStatus, options: &TableOptions) -> String {
+    let mut out = Vec::new();
+    write_table_view(&PortfolioView::new(status), options, &mut out).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn columns_and_sort_keys_parse_by_name() {
+    assert_eq!(TableColumn::parse_list("name, stage,priority").unwrap(),
+        vec![TableColumn::Project, TableColumn::Stage, TableColumn::Priority]);
+    let err = TableColumn::parse_list("project,owner").unwrap_err().to_string();
+    assert!(err.contains("Unknown column: owner"), "{}", err);
+    assert!(TableColumn::parse_list(" , ").is_err());
+
+    assert_eq!("stage".parse::<TableSort>().unwrap(), TableSort { column: TableColumn::Stage, descending: false });
+    assert_eq!("updated".parse::<TableSort>().unwrap(), TableSort { column: TableColumn::Updated, descending: true });
+    assert_eq!("priority:asc".parse::<TableSort>().unwrap(), TableSort { column: TableColumn::Priority, descending: false });
+    assert!("priority:up".parse::<TableSort>().is_err());
+    assert_eq!(TableOptions::default().columns, DEFAULT_COLUMNS.to_vec());
+}
+
+#[test]
+fn rows_are_aligned_under_a_header_and_rule() {
+    let status = portfolio();
+    let options = TableOptions {
+        columns: vec![TableColumn::Project, TableColumn::Priority, TableColumn::Tasks],
+        ..TableOptions::default()
+    };
+    let table = render(&status, &options);
+    let lines: Vec<&str> = table.lines().collect();
+    assert_eq!(lines, [
+        "Project          Priority  Tasks",
+        "───────────────  ────────  ─────",
+        "api                  80.0    2/4",
+        "billing-service      42.0  10/10",
+        "web                   5.5    0/0",
+    ], "{}", table);
+    assert!(!table.contains('\u{1b}'));
+}
+
+#[test]
+fn sort_by_reorders_rows_and_color_paints_priority() {
+    let status = portfolio();
+    let options = TableOptions {
+        columns: vec![TableColumn::Project, TableColumn::Priority],
+        sort: "project".parse().unwrap(),
+        color: false,
+    };
+    let names: Vec<String> = render(&status, &options).lines().skip(2)
+        .map(|line| line.split_whitespace().next().unwrap().to_string())
+        .collect();
+    assert_eq!(names, ["api", "billing-service", "web"]);
+
+    let colored = render(&status, &TableOptions { color: true, ..options });
+    assert!(colored.contains("\u{1b}[31m    80.0\u{1b}[0m"), "{:?}", colored);
+    assert!(colored.lines().next().unwrap().starts_with("Project"));
+}
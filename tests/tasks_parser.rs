@@ -0,0 +1,179 @@
+//! Corpus and property tests for the tasks parser
+//!
+//! Each `tests/fixtures/tasks/<name>.md` has a `<name>.json` with the tasks
+//! it must parse into. Regenerate an expectation after an intended change
+//! with `skm parse-tasks <file> --json` and review the diff.
+
+use std::fs;
+use std::path::Path;
+use skm::scanner::parser::{parse_task_items, set_task_done};
+use skm::{TaskFormat, TaskItem};
+
+#[test]
+fn corpus_matches_expectations() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/tasks");
+    let mut checked = 0;
+    for entry in fs::read_dir(&dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().is_none_or(|ext| ext != "md") {
+            continue;
+        }
+        let content = fs::read_to_string(&path).unwrap();
+        let expected: Vec<TaskItem> = serde_json::from_str(
+            &fs::read_to_string(path.with_extension("json")).unwrap(),
+        ).unwrap();
+        assert_eq!(parse_task_items(&content), expected, "{}", path.display());
+        checked += 1;
+    }
+    assert!(checked > 0, "no fixtures in {}", dir.display());
+}
+
+/// Small deterministic generator so failures reproduce from the seed
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+
+    fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+}
+
+/// A generated line and the task it must parse into, if any
+struct Line {
+    text: String,
+    task: Option<(TaskFormat, bool, bool, bool)>,
+}
+
+const WORDS: &[&str] = &["parser", "login", "cache", "retry", "docs", "schema", "rollout", "metrics"];
+
+fn gen_line(rng: &mut Rng, id: usize) -> Line {
+    let words: Vec<&str> = (0..1 + rng.below(4)).map(|_| *rng.pick(WORDS)).collect();
+    let words = words.join(" ");
+    let indent = *rng.pick(&["", "", "  ", "    "]);
+    let parallel = rng.below(3) == 0;
+    let p = if parallel { " [P]" } else { "" };
+
+    let (text, task) = match rng.below(9) {
+        0 => {
+            let bullet = rng.pick(&["-", "*"]);
+            (format!("{}{} [ ] T{:03}{} {}", indent, bullet, id, p, words),
+                Some((TaskFormat::Checkbox, false, parallel, false)))
+        }
+        1 => {
+            let (bullet, mark) = (rng.pick(&["-", "*"]), rng.pick(&["x", "X"]));
+            (format!("{}{} [{}] T{:03}{} {}", indent, bullet, mark, id, p, words),
+                Some((TaskFormat::Checkbox, true, parallel, false)))
+        }
+        2 => (format!("{}- [ ] T{:03} [BLOCKED] {}", indent, id, words),
+            Some((TaskFormat::Checkbox, false, false, true))),
+        3 => {
+            let done = rng.below(2) == 0;
+            let suffix = if done { *rng.pick(&[" ✅", " DONE", " [COMPLETE]"]) } else { "" };
+            (format!("T{:03}:{} {}{}", id, p, words, suffix), Some((TaskFormat::TaskId, done, parallel, false)))
+        }
+        4 => {
+            let done = rng.below(2) == 0;
+            let mark = if done { rng.pick(&["✅", "☑"]) } else { rng.pick(&["⬜", "☐", "❌", "🔄"]) };
+            (format!("{} {}", mark, words), Some((TaskFormat::Emoji, done, false, false)))
+        }
+        5 => {
+            let done = rng.below(2) == 0;
+            let prefix = rng.pick(&["", "- "]);
+            (format!("{}{}: {}", prefix, if done { "DONE" } else { "TODO" }, words),
+                Some((TaskFormat::Keyword, done, false, false)))
+        }
+        6 => (format!("{} {}", rng.pick(&["#", "##", "###"]), words), None),
+        7 => (format!("Note: {} {}", words, rng.pick(&["", "(see docs)", "and more"])), None),
+        _ => (String::new(), None),
+    };
+    Line { text, task }
+}
+
+fn gen_file(seed: u64) -> (String, Vec<Line>) {
+    let mut rng = Rng(seed);
+    let lines: Vec<Line> = (0..1 + rng.below(40)).map(|id| gen_line(&mut rng, id + 1)).collect();
+    let ending = if rng.below(4) == 0 { "\r\n" } else { "\n" };
+    let content = lines.iter().map(|l| l.text.as_str()).collect::<Vec<_>>().join(ending) + ending;
+    (content, lines)
+}
+
+const CASES: u64 = 500;
+
+#[test]
+fn generated_files_parse_as_generated() {
+    for seed in 1..=CASES {
+        let (content, lines) = gen_file(seed);
+        let parsed = parse_task_items(&content);
+
+        let expected: Vec<_> = lines.iter().enumerate()
+            .filter_map(|(i, l)| l.task.map(|task| (i + 1, task)))
+            .collect();
+        let actual: Vec<_> = parsed.iter()
+            .map(|t| (t.line, (t.format, t.done, t.parallel, t.blocked)))
+            .collect();
+        assert_eq!(actual, expected, "seed {}:\n{}", seed, content);
+    }
+}
+
+#[test]
+fn toggling_a_checkbox_changes_only_that_task() {
+    for seed in 1..=CASES {
+        let (content, _) = gen_file(seed);
+        let before = parse_task_items(&content);
+
+        for task in &before {
+            let toggled = set_task_done(&content, task.line, !task.done);
+            if task.format != TaskFormat::Checkbox {
+                assert_eq!(toggled, None, "seed {} line {}", seed, task.line);
+                continue;
+            }
+            let toggled = toggled.unwrap();
+            assert_eq!(toggled.len(), content.len(), "seed {} line {}", seed, task.line);
+
+            let after = parse_task_items(&toggled);
+            assert_eq!(after.len(), before.len(), "seed {} line {}", seed, task.line);
+            for (old, new) in before.iter().zip(&after) {
+                if old.line == task.line {
+                    assert_eq!(new.done, !old.done, "seed {} line {}", seed, task.line);
+                    assert_eq!(new.text, old.text, "seed {} line {}", seed, task.line);
+                } else {
+                    assert_eq!(new, old, "seed {} line {}", seed, task.line);
+                }
+            }
+
+            // Setting it back restores the file, except that `[X]` comes back as `[x]`
+            let restored = set_task_done(&toggled, task.line, task.done).unwrap();
+            assert_eq!(restored.replace("[X]", "[x]"), content.replace("[X]", "[x]"),
+                "seed {} line {}", seed, task.line);
+        }
+    }
+}
+
+#[test]
+fn setting_the_current_state_is_a_no_op() {
+    for seed in 1..=CASES {
+        let (content, _) = gen_file(seed);
+        for task in parse_task_items(&content).iter().filter(|t| t.format == TaskFormat::Checkbox) {
+            assert_eq!(set_task_done(&content, task.line, task.done).as_deref(), Some(content.as_str()),
+                "seed {} line {}", seed, task.line);
+        }
+    }
+}
+
+#[test]
+fn lines_outside_the_file_are_not_edited() {
+    let content = "- [ ] T001 only task\n";
+    assert_eq!(set_task_done(content, 0, true), None);
+    assert_eq!(set_task_done(content, 2, true), None);
+    assert_eq!(set_task_done(content, 1, true).as_deref(), Some("- [x] T001 only task\n"));
+}
@@ -0,0 +1,78 @@
+//! `{{project.name}}` and `{{portfolio.<name>}}` variables in spec artifacts
+
+use skm::template::Variables;
+
+fn variables() -> Variables {
+    let mut variables = Variables::default();
+    variables.insert("project.name".to_string(), "Nova".to_string());
+    variables.insert("portfolio.deadline".to_string(), "2025-06-30".to_string());
+    variables
+}
+
+#[test]
+fn known_variables_are_replaced() {
+    let text = "{{project.name}} ships by {{ portfolio.deadline }}.";
+    assert_eq!(variables().resolve(text), "Nova ships by 2025-06-30.");
+}
+
+#[test]
+fn unknown_and_unclosed_variables_stay_visible() {
+    let variables = variables();
+    assert_eq!(variables.resolve("Owner: {{project.owner}}, {{project.name}}"), "Owner: {{project.owner}}, Nova");
+    assert_eq!(variables.resolve("{{project.name} and {{"), "{{project.name} and {{");
+    assert_eq!(variables.resolve("no variables"), "no variables");
+}
+
+#[cfg(feature = "testkit")]
+#[test]
+fn project_variables_come_from_the_id_custom_fields_and_config() {
+    use skm::meta::config::GlobalConfig;
+    use skm::scanner::pipeline::{GitMode, ScanOptions};
+    use skm::testkit::{ProjectSpec, SyntheticPortfolio};
+    use skm::Stage;
+
+    let portfolio = SyntheticPortfolio::temp("template-vars", &[ProjectSpec::new("api", Stage::Plan)]).unwrap();
+    let status = portfolio.scan(ScanOptions { git: GitMode::Skip, ..ScanOptions::default() }).unwrap();
+    let mut project = status.projects[0].clone();
+    let mut config = GlobalConfig::default();
+    config.variables.insert("deadline".to_string(), "2025-06-30".to_string());
+
+    let variables = Variables::for_project(&project, &config);
+    assert_eq!(variables.get("project.name"), Some("api"));
+    assert_eq!(variables.get("project.path"), Some(project.path.display().to_string().as_str()));
+    assert_eq!(variables.get("portfolio.deadline"), Some("2025-06-30"));
+
+    project.custom.insert("name".to_string(), serde_json::json!("Nova API"));
+    project.custom.insert("tier".to_string(), serde_json::json!(1));
+    project.custom.insert("owners".to_string(), serde_json::json!(["ana", "li"]));
+    let variables = Variables::for_project(&project, &config);
+    assert_eq!(variables.resolve("{{project.name}} (tier {{project.tier}}, {{project.owners}})"), "Nova API (tier 1, ana, li)");
+}
+
+#[cfg(feature = "testkit")]
+#[test]
+fn scans_resolve_principles_and_open_questions() {
+    use std::fs;
+    use skm::scanner::pipeline::{GitMode, ScanOptions};
+    use skm::testkit::{ProjectSpec, SyntheticPortfolio};
+    use skm::Stage;
+
+    let specs = [ProjectSpec::new("api", Stage::Plan)];
+    let portfolio = SyntheticPortfolio::temp("template", &specs).unwrap();
+    let dir = portfolio.root().join("api");
+    fs::create_dir_all(dir.join(".skm")).unwrap();
+    fs::write(dir.join(".skm/config.toml"), "[variables]\ncompany = \"Acme\"\ndeadline = \"2025-06-30\"\n").unwrap();
+    fs::write(dir.join(".specify/memory/constitution.md"),
+        "# Constitution\n\n## Core Principles\n\n### I. {{portfolio.company}} style guide\n").unwrap();
+    let spec = dir.join("specs/001-core/spec.md");
+    let mut content = fs::read_to_string(&spec).unwrap();
+    content.push_str("\nOPEN: Can {{project.name}} ship by {{portfolio.deadline}} with {{portfolio.budget}}?\n");
+    fs::write(&spec, &content).unwrap();
+
+    let status = portfolio.scan(ScanOptions { git: GitMode::Skip, ..ScanOptions::default() }).unwrap();
+    let project = &status.projects[0];
+    assert_eq!(project.principles[0].name, "Acme style guide");
+    let question = project.open_questions.iter().find(|q| q.text.starts_with("Can ")).unwrap();
+    assert_eq!(question.text, "Can api ship by 2025-06-30 with {{portfolio.budget}}?");
+    assert!(fs::read_to_string(&spec).unwrap().contains("{{project.name}}"), "the artifact keeps its variables");
+}
@@ -0,0 +1,129 @@
+//! Test outcomes found in result files and CI, which tell `Test` from `Review`
+
+use chrono::{Duration, Utc};
+use skm::analyzer::testrun::{latest_outcome, parse_ci_note, parse_go_test_log, parse_junit};
+use skm::{TestEvidence, TestSource};
+
+#[test]
+fn junit_reports_fail_on_failures_or_errors() {
+    let passing = r#"<?xml version="1.0"?>
+<testsuites name="nextest-run" tests="3" failures="0" errors="0">
+  <testsuite name="skm" tests="3" failures="0" errors="0"></testsuite>
+</testsuites>"#;
+    assert_eq!(parse_junit(passing), Some(true));
+    let failing = r#"<testsuite name="pytest" errors="0" failures="1" tests="4"><testcase/></testsuite>"#;
+    assert_eq!(parse_junit(failing), Some(false));
+    let erroring = r#"<testsuites><testsuite tests="2" errors="2"/></testsuites>"#;
+    assert_eq!(parse_junit(erroring), Some(false));
+    assert_eq!(parse_junit("<html></html>"), None);
+}
+
+#[test]
+fn go_test_logs_are_read_plain_verbose_and_json() {
+    assert_eq!(parse_go_test_log("ok  \texample.com/app\t0.012s\nok  \texample.com/app/db\t0.3s\n"), Some(true));
+    assert_eq!(parse_go_test_log("--- FAIL: TestParse (0.00s)\nFAIL\nFAIL\texample.com/app\t0.01s\n"), Some(false));
+    let json = r#"{"Action":"run","Test":"TestA"}
+{"Action":"pass","Test":"TestA"}
+{"Action":"fail","Test":"TestB"}"#;
+    assert_eq!(parse_go_test_log(json), Some(false));
+    assert_eq!(parse_go_test_log("go: downloading example.com/dep v1.0.0\n"), None);
+}
+
+#[test]
+fn ci_notes_are_read_loosely() {
+    assert_eq!(parse_ci_note("CI: success\nhttps://ci.example.com/builds/42"), Some(true));
+    assert_eq!(parse_ci_note("tests passed"), Some(true));
+    assert_eq!(parse_ci_note("build FAILED"), Some(false));
+    assert_eq!(parse_ci_note("deployed to staging"), None);
+}
+
+#[test]
+fn ci_notes_with_zero_failures_passed() {
+    assert_eq!(parse_ci_note("42 passed, 0 failed"), Some(true));
+    assert_eq!(parse_ci_note("lint: no errors"), Some(true));
+    assert_eq!(parse_ci_note("tests: 120, failures: 0"), Some(true));
+    assert_eq!(parse_ci_note("3 passed; 2 failed"), Some(false));
+    assert_eq!(parse_ci_note("errors=1"), Some(false));
+    assert_eq!(parse_ci_note("CI: success https://ci.example.com/errors/42"), Some(true));
+    assert_eq!(parse_ci_note("failover test: passed"), Some(true));
+    assert_eq!(parse_ci_note("CI errored"), Some(false));
+}
+
+#[test]
+fn only_outcomes_since_the_tasks_changed_count() {
+    let now = Utc::now();
+    let evidence = |source, hours: i64, passed| TestEvidence { source, at: now - Duration::hours(hours), passed, path: None };
+    let all = vec![
+        evidence(TestSource::SkmTest, 30, false),
+        evidence(TestSource::Junit, 2, true),
+        evidence(TestSource::CiNote, 5, false),
+    ];
+    let latest = latest_outcome(all.clone(), Some(now - Duration::hours(24))).unwrap();
+    assert_eq!((latest.source, latest.passed), (TestSource::Junit, true));
+    assert_eq!(latest_outcome(all.clone(), Some(now - Duration::hours(1))), None);
+    assert_eq!(latest_outcome(all, None).unwrap().source, TestSource::Junit);
+}
+
+#[cfg(feature = "testkit")]
+#[test]
+fn result_files_move_finished_work_between_test_and_review() {
+    use std::fs;
+    use skm::scanner::pipeline::{GitMode, ScanOptions};
+    use skm::testkit::{ProjectSpec, SyntheticPortfolio};
+    use skm::Stage;
+
+    let specs = [
+        ProjectSpec::new("junit", Stage::Test),
+        ProjectSpec::new("nextest", Stage::Test),
+        ProjectSpec::new("pytest", Stage::Test),
+        ProjectSpec::new("go", Stage::Test),
+        ProjectSpec::new("untested", Stage::Test),
+    ];
+    let portfolio = SyntheticPortfolio::temp("test-results", &specs).unwrap();
+    let root = portfolio.root();
+    fs::write(root.join("junit/junit.xml"), r#"<testsuite tests="5" failures="0" errors="0"/>"#).unwrap();
+    fs::create_dir_all(root.join("nextest/target/nextest/ci")).unwrap();
+    fs::write(root.join("nextest/target/nextest/ci/junit.xml"), r#"<testsuites failures="1"/>"#).unwrap();
+    fs::create_dir_all(root.join("pytest/.pytest_cache/v/cache")).unwrap();
+    fs::write(root.join("pytest/.pytest_cache/v/cache/nodeids"), r#"["tests/test_app.py::test_ok"]"#).unwrap();
+    fs::write(root.join("pytest/.pytest_cache/v/cache/lastfailed"), "{}").unwrap();
+    fs::write(root.join("go/go-test.log"), "ok  \texample.com/go\t0.02s\n").unwrap();
+    let scan = portfolio.scan(ScanOptions { git: GitMode::Skip, ..ScanOptions::default() }).unwrap();
+
+    let project = |id: &str| scan.projects.iter().find(|p| p.id == id).unwrap();
+    assert_eq!(project("junit").stage, Stage::Review);
+    assert_eq!(project("junit").test_evidence.as_ref().unwrap().source, TestSource::Junit);
+    assert_eq!(project("nextest").stage, Stage::Test);
+    assert_eq!(project("pytest").stage, Stage::Review);
+    assert_eq!(project("pytest").test_evidence.as_ref().unwrap().source, TestSource::Pytest);
+    assert_eq!(project("go").stage, Stage::Review);
+    assert_eq!(project("untested").stage, Stage::Test);
+    assert!(project("untested").test_evidence.is_none());
+}
+
+#[cfg(all(feature = "testkit", feature = "git"))]
+#[test]
+fn a_ci_note_on_head_decides_the_stage() {
+    use skm::scanner::git::CI_NOTES_REF;
+    use skm::scanner::pipeline::ScanOptions;
+    use skm::testkit::{GitState, ProjectSpec, SyntheticPortfolio};
+    use skm::Stage;
+
+    let specs = [
+        ProjectSpec::new("green", Stage::Test).with_git(GitState::Clean),
+        ProjectSpec::new("red", Stage::Test).with_git(GitState::Clean),
+    ];
+    let portfolio = SyntheticPortfolio::temp("ci-notes", &specs).unwrap();
+    for (id, note) in [("green", "CI passed"), ("red", "CI failed: 2 tests")] {
+        let repo = git2::Repository::open(portfolio.root().join(id)).unwrap();
+        let head = repo.head().unwrap().target().unwrap();
+        let signature = git2::Signature::now("ci", "ci@example.com").unwrap();
+        repo.note(&signature, &signature, Some(CI_NOTES_REF), head, note, false).unwrap();
+    }
+    let scan = portfolio.scan(ScanOptions::default()).unwrap();
+
+    let project = |id: &str| scan.projects.iter().find(|p| p.id == id).unwrap();
+    assert_eq!(project("green").stage, Stage::Review);
+    assert_eq!(project("green").test_evidence.as_ref().unwrap().source, TestSource::CiNote);
+    assert_eq!(project("red").stage, Stage::Test);
+}
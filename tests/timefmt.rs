@@ -0,0 +1,58 @@
+//! Boundaries of the humanized durations and relative times in status output
+
+use chrono::{Duration, TimeZone, Utc};
+use skm::timefmt::{humanize_duration, relative_to, DisplayTimeZone};
+
+#[test]
+fn durations_switch_units_at_their_boundaries() {
+    let cases = [
+        (Duration::seconds(0), "less than a minute"),
+        (Duration::seconds(59), "less than a minute"),
+        (Duration::seconds(60), "1 minute"),
+        (Duration::seconds(119), "1 minute"),
+        (Duration::minutes(59), "59 minutes"),
+        (Duration::minutes(60), "1 hour"),
+        (Duration::hours(23), "23 hours"),
+        (Duration::hours(24), "1 day"),
+        (Duration::days(13), "13 days"),
+        (Duration::days(14), "2 weeks"),
+        (Duration::days(20), "2 weeks"),
+        (Duration::days(59), "8 weeks"),
+        (Duration::days(60), "2 months"),
+        (Duration::days(364), "12 months"),
+        (Duration::days(365), "1 year"),
+        (Duration::days(800), "2 years"),
+    ];
+    for (duration, expected) in cases {
+        assert_eq!(humanize_duration(duration), expected, "{:?}", duration);
+    }
+}
+
+#[test]
+fn negative_durations_are_measured_by_length() {
+    assert_eq!(humanize_duration(Duration::days(-3)), "3 days");
+}
+
+#[test]
+fn relative_times_read_as_past_future_or_now() {
+    let now = Utc.with_ymd_and_hms(2025, 3, 10, 12, 0, 0).unwrap();
+    assert_eq!(relative_to(now, now), "just now");
+    assert_eq!(relative_to(now - Duration::seconds(59), now), "just now");
+    assert_eq!(relative_to(now + Duration::seconds(30), now), "just now");
+    assert_eq!(relative_to(now - Duration::minutes(1), now), "1 minute ago");
+    assert_eq!(relative_to(now - Duration::days(3), now), "3 days ago");
+    assert_eq!(relative_to(now + Duration::hours(2), now), "in 2 hours");
+}
+
+#[test]
+fn display_zones_parse_and_format() {
+    let at = Utc.with_ymd_and_hms(2025, 1, 6, 23, 30, 0).unwrap();
+    assert_eq!(DisplayTimeZone::Utc.format(at, "%Y-%m-%d %H:%M"), "2025-01-06 23:30 UTC");
+
+    let plus_two: DisplayTimeZone = "+02:00".parse().unwrap();
+    assert_eq!(plus_two.format(at, "%Y-%m-%d %H:%M"), "2025-01-07 01:30 +02:00");
+    assert_eq!(plus_two.date(at).to_string(), "2025-01-07");
+
+    assert_eq!("Local".parse::<DisplayTimeZone>().unwrap(), DisplayTimeZone::Local);
+    assert!("Mars/Olympus".parse::<DisplayTimeZone>().is_err());
+}
@@ -0,0 +1,82 @@
+//! Changes between two scans, as `skm watch` prints and sends them
+
+#![cfg(feature = "testkit")]
+
+use skm::alerts::Severity;
+use skm::reporter::style::{self, Color};
+use skm::reporter::{ProjectChange, portfolio_changes};
+use skm::scanner::pipeline::{GitMode, ScanOptions};
+use skm::testkit::{ProjectSpec, SyntheticPortfolio};
+use skm::Stage;
+
+#[test]
+fn task_progress_between_scans_is_a_change() {
+    let specs = [ProjectSpec::new("api", Stage::Implement).with_tasks(6), ProjectSpec::new("web", Stage::Implement).with_tasks(4)];
+    let portfolio = SyntheticPortfolio::temp("watch-changes", &specs).unwrap();
+    let before = portfolio.scan(ScanOptions { git: GitMode::Skip, ..ScanOptions::default() }).unwrap();
+    let mut after = before.clone();
+    let api = after.projects.iter_mut().find(|p| p.id == "api").unwrap();
+    api.tasks.completed += 2;
+    api.tasks.total += 1;
+    let web = after.projects.iter_mut().find(|p| p.id == "web").unwrap();
+    web.tasks.completed -= 1;
+
+    let changes = portfolio_changes(&before, &after);
+    assert_eq!(changes.len(), 2, "{:?}", changes);
+    assert!(matches!(&changes[0], ProjectChange::TasksChanged { project, completed: 5, total: 7, was_completed: 3, was_total: 6 } if project == "api"));
+    assert!(changes[0].to_string().ends_with("api: 5/7 tasks (2 done, 1 added)"), "{}", changes[0]);
+    assert!(changes[1].to_string().ends_with("web: 1/4 tasks (1 reopened)"), "{}", changes[1]);
+    assert_eq!(Severity::of(&changes[0]), Severity::Info);
+
+    // Unchanged scans stay quiet
+    assert!(portfolio_changes(&after, &after).is_empty());
+}
+
+#[test]
+fn change_lines_are_colored_by_severity_only_on_a_terminal() {
+    assert_eq!(Severity::Critical.color(), Color::Red);
+    assert_eq!(Severity::Warning.color(), Color::Yellow);
+    assert_eq!(Severity::Info.color(), Color::Green);
+    // Test output is captured, not a terminal
+    assert_eq!(style::paint("[12:00:00] api: plan → tasks", Color::Green), "[12:00:00] api: plan → tasks");
+}
+
+#[test]
+fn min_severity_keeps_quieter_changes_out_of_notifications() {
+    use skm::meta::config::NotificationConfig;
+
+    let progress = ProjectChange::TasksChanged { project: "api".to_string(), completed: 2, total: 4, was_completed: 1, was_total: 4 };
+    let crossing = ProjectChange::Attention { project: "api".to_string(), priority: 72.0, needs_attention: true };
+    let blocked = ProjectChange::NewlyBlocked { project: "web".to_string(), blocked: 2, added: 1 };
+    let mut config = NotificationConfig::default();
+    assert!(!config.notifies(&progress), "task progress only shows in the watch output by default");
+    assert!(config.notifies(&crossing) && config.notifies(&blocked));
+
+    config = toml::from_str("min_severity = \"info\"").unwrap();
+    assert!([&progress, &crossing, &blocked].iter().all(|change| config.notifies(change)));
+}
+
+#[cfg(feature = "integrations")]
+#[test]
+fn webhook_payloads_suit_slack_and_discord() {
+    use skm::alerts::{Notification, webhook_payload};
+
+    let changes = vec![
+        ProjectChange::Attention { project: "api".to_string(), priority: 72.0, needs_attention: true },
+        ProjectChange::NewlyBlocked { project: "web".to_string(), blocked: 2, added: 1 },
+    ];
+    let payload = webhook_payload(&Notification::new("skm: 2 project change(s)".to_string(), changes));
+    assert_eq!(payload["text"], payload["content"]);
+    assert!(payload["text"].as_str().unwrap().starts_with("skm: 2 project change(s)\n\nwarning (2)"), "{}", payload);
+    assert_eq!(payload["severity"], "warning");
+    assert_eq!(payload["changes"][0]["project"], "api");
+
+    let many: Vec<ProjectChange> = (0..100)
+        .map(|i| ProjectChange::NewlyBlocked { project: format!("project-{:03}", i), blocked: 1, added: 1 })
+        .collect();
+    let payload = webhook_payload(&Notification::new("skm: 100 project change(s)".to_string(), many));
+    let content = payload["content"].as_str().unwrap();
+    assert_eq!(content.chars().count(), 2000);
+    assert!(content.ends_with('…'));
+    assert!(payload["text"].as_str().unwrap().chars().count() > 2000);
+}